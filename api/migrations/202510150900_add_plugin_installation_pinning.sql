@@ -0,0 +1,2 @@
+ALTER TABLE plugin_installations
+    ADD COLUMN IF NOT EXISTS pinned BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,7 @@
+-- Per-document overrides for CRDT snapshot/update retention. NULL columns fall back to the global config.
+CREATE TABLE IF NOT EXISTS document_retention (
+  document_id uuid PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+  snapshot_keep_versions BIGINT CHECK (snapshot_keep_versions IS NULL OR snapshot_keep_versions >= 1),
+  updates_keep_window BIGINT CHECK (updates_keep_window IS NULL OR updates_keep_window >= 0),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,11 @@
+-- Direct document access granted by an owner to another user (no share link involved).
+CREATE TABLE IF NOT EXISTS document_user_access (
+  document_id uuid NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+  user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  permission TEXT NOT NULL CHECK (permission IN ('view', 'edit')),
+  granted_by uuid REFERENCES users(id) ON DELETE SET NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  PRIMARY KEY (document_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_user_access_user ON document_user_access(user_id);
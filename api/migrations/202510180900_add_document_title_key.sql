@@ -0,0 +1,5 @@
+-- Normalized title (lowercased, diacritics folded, whitespace collapsed) used to resolve wikilinks.
+-- Computed by the application; existing rows are filled in lazily on first lookup.
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS title_key TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_documents_owner_title_key ON documents(owner_id, title_key);
@@ -0,0 +1,4 @@
+-- Per-user commit message template ({count}, {date}, {files}) and optional change summary body.
+ALTER TABLE git_configs
+    ADD COLUMN IF NOT EXISTS commit_message_template TEXT,
+    ADD COLUMN IF NOT EXISTS append_change_summary BOOLEAN NOT NULL DEFAULT false;
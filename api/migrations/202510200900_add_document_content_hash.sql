@@ -0,0 +1,3 @@
+-- SHA-256 of the markdown file as last written by the snapshot service. Lets git status skip
+-- reading documents whose file matches the latest commit; NULL means "unknown, read the file".
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS content_hash TEXT;
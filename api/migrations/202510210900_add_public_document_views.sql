@@ -0,0 +1,8 @@
+-- Daily view counts for published documents. Only aggregates are stored; the per-client
+-- debounce that keeps repeated views from inflating counts lives in memory.
+CREATE TABLE IF NOT EXISTS public_document_views (
+  document_id uuid NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+  day DATE NOT NULL,
+  views BIGINT NOT NULL DEFAULT 0,
+  PRIMARY KEY (document_id, day)
+);
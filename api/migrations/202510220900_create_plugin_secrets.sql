@@ -0,0 +1,10 @@
+-- Per-user plugin secrets such as API keys. Values are encrypted with the server
+-- encryption key and are only ever handed to the plugin at runtime.
+CREATE TABLE IF NOT EXISTS plugin_secrets (
+  user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  plugin TEXT NOT NULL,
+  key TEXT NOT NULL,
+  value TEXT NOT NULL,
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  PRIMARY KEY (user_id, plugin, key)
+);
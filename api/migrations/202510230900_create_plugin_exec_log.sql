@@ -0,0 +1,17 @@
+-- Recent plugin invocations, kept per user and plugin for troubleshooting.
+CREATE TABLE IF NOT EXISTS plugin_exec_log (
+  id BIGSERIAL PRIMARY KEY,
+  user_id uuid REFERENCES users(id) ON DELETE CASCADE,
+  plugin TEXT NOT NULL,
+  function TEXT NOT NULL,
+  input_bytes BIGINT NOT NULL,
+  output_bytes BIGINT,
+  duration_ms BIGINT NOT NULL,
+  ok BOOLEAN NOT NULL,
+  error TEXT,
+  payload JSONB,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS plugin_exec_log_user_plugin_idx
+  ON plugin_exec_log (user_id, plugin, created_at DESC);
@@ -0,0 +1,6 @@
+-- Block ids (`^id` markers) declared in each document, for resolving [[doc^id]] links.
+CREATE TABLE IF NOT EXISTS document_blocks (
+  document_id uuid NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+  block_id TEXT NOT NULL,
+  PRIMARY KEY (document_id, block_id)
+);
@@ -0,0 +1,11 @@
+-- Users mentioned with @[[user]] in a document, kept in sync on save.
+CREATE TABLE IF NOT EXISTS document_mentions (
+  document_id uuid NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+  user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  mentioned_by uuid REFERENCES users(id) ON DELETE SET NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  PRIMARY KEY (document_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS document_mentions_user_idx
+  ON document_mentions (user_id, created_at DESC);
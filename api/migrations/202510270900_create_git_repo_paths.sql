@@ -0,0 +1,7 @@
+-- Repository path of each document and folder as of the user's last git commit.
+CREATE TABLE IF NOT EXISTS git_repo_paths (
+  user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  document_id uuid NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+  path TEXT NOT NULL,
+  PRIMARY KEY (user_id, document_id)
+);
@@ -0,0 +1,2 @@
+-- Owners can freeze a document: while locked nobody, the owner included, can edit it.
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS locked BOOLEAN NOT NULL DEFAULT FALSE;
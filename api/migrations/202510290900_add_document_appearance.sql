@@ -0,0 +1,2 @@
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS icon TEXT;
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS color TEXT;
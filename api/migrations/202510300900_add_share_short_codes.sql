@@ -0,0 +1,2 @@
+ALTER TABLE shares ADD COLUMN IF NOT EXISTS short_code TEXT;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_shares_short_code ON shares(short_code) WHERE short_code IS NOT NULL;
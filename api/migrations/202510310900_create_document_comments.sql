@@ -0,0 +1,31 @@
+-- Comment permission for share links and direct grants.
+ALTER TABLE shares DROP CONSTRAINT IF EXISTS shares_permission_check;
+ALTER TABLE shares ADD CONSTRAINT shares_permission_check
+  CHECK (permission IN ('view', 'comment', 'edit'));
+ALTER TABLE document_user_access DROP CONSTRAINT IF EXISTS document_user_access_permission_check;
+ALTER TABLE document_user_access ADD CONSTRAINT document_user_access_permission_check
+  CHECK (permission IN ('view', 'comment', 'edit'));
+
+-- Comment threads on documents. A thread is a top-level comment, optionally anchored to a
+-- source range (1-based line:column, as in the renderer's sourcepos), plus its replies.
+CREATE TABLE IF NOT EXISTS document_comments (
+  id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+  document_id uuid NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+  parent_id uuid REFERENCES document_comments(id) ON DELETE CASCADE,
+  -- NULL for comments left through a share link.
+  author_id uuid REFERENCES users(id) ON DELETE SET NULL,
+  author_name TEXT,
+  anchor_start_line INT,
+  anchor_start_col INT,
+  anchor_end_line INT,
+  anchor_end_col INT,
+  body TEXT NOT NULL,
+  resolved BOOLEAN NOT NULL DEFAULT false,
+  resolved_by uuid REFERENCES users(id) ON DELETE SET NULL,
+  resolved_at TIMESTAMPTZ,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_comments_document
+  ON document_comments(document_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_document_comments_parent ON document_comments(parent_id);
@@ -0,0 +1,3 @@
+-- Document a user lands on after signing in.
+ALTER TABLE users
+  ADD COLUMN IF NOT EXISTS home_document_id uuid REFERENCES documents(id) ON DELETE SET NULL;
@@ -0,0 +1,6 @@
+-- Documents created by a git import that has not finished yet; removed again if it fails.
+CREATE TABLE IF NOT EXISTS git_pending_imports (
+  user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  document_id uuid NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+  PRIMARY KEY (user_id, document_id)
+);
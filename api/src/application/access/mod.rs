@@ -14,92 +14,177 @@ pub enum Actor {
 pub enum Capability {
     None,
     View,
+    /// View and discuss through comments, without changing the content.
+    Comment,
     Edit,
 }
 
+impl Capability {
+    /// Capability behind a stored share or grant permission ("view", "comment" or "edit").
+    pub fn from_permission(permission: &str) -> Self {
+        match permission {
+            "edit" => Capability::Edit,
+            "comment" => Capability::Comment,
+            _ => Capability::View,
+        }
+    }
+}
+
+/// Instance-wide access rules, built from configuration by the caller.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccessPolicy {
+    /// Whether anonymous actors (share links, public pages) may be granted edit. Off unless
+    /// the operator opts in.
+    pub allow_anonymous_edit: bool,
+}
+
 // Presentation layer is responsible for building Actor from HTTP inputs.
 // This module intentionally avoids depending on presentation types.
 
 pub async fn resolve_document<A, R>(
     access_repo: &A,
     shares_repo: &R,
+    policy: AccessPolicy,
+    actor: &Actor,
+    doc_id: Uuid,
+) -> Capability
+where
+    A: AccessRepository + ?Sized,
+    R: ShareAccessPort + ?Sized,
+{
+    let (cap, locked) = restricted_grant(access_repo, shares_repo, policy, actor, doc_id).await;
+    if cap != Capability::Edit {
+        return cap;
+    }
+    // User lookups already read the lock flag; only share links need a separate check.
+    let locked = match locked {
+        Some(locked) => locked,
+        None => access_repo
+            .is_document_locked(doc_id)
+            .await
+            .unwrap_or(false),
+    };
+    if locked { Capability::View } else { cap }
+}
+
+/// Capability granted by ownership, user grants, shares or publishing, ignoring the document
+/// lock. Realtime connections use it to tell who may edit once the document is unlocked.
+/// Anonymous actors never get more than view unless `policy` allows anonymous edit.
+pub async fn resolve_grant<A, R>(
+    access_repo: &A,
+    shares_repo: &R,
+    policy: AccessPolicy,
     actor: &Actor,
     doc_id: Uuid,
 ) -> Capability
+where
+    A: AccessRepository + ?Sized,
+    R: ShareAccessPort + ?Sized,
+{
+    restricted_grant(access_repo, shares_repo, policy, actor, doc_id)
+        .await
+        .0
+}
+
+/// [`resolve_grant`], along with the lock flag when the lookup already read it.
+async fn restricted_grant<A, R>(
+    access_repo: &A,
+    shares_repo: &R,
+    policy: AccessPolicy,
+    actor: &Actor,
+    doc_id: Uuid,
+) -> (Capability, Option<bool>)
+where
+    A: AccessRepository + ?Sized,
+    R: ShareAccessPort + ?Sized,
+{
+    let (cap, locked) = resolve_unrestricted_grant(access_repo, shares_repo, actor, doc_id).await;
+    let anonymous = matches!(actor, Actor::ShareToken(_) | Actor::Public);
+    if cap == Capability::Edit && anonymous && !policy.allow_anonymous_edit {
+        return (Capability::View, locked);
+    }
+    (cap, locked)
+}
+
+async fn resolve_unrestricted_grant<A, R>(
+    access_repo: &A,
+    shares_repo: &R,
+    actor: &Actor,
+    doc_id: Uuid,
+) -> (Capability, Option<bool>)
 where
     A: AccessRepository + ?Sized,
     R: ShareAccessPort + ?Sized,
 {
     match actor {
-        Actor::User(uid) => {
-            let owns = access_repo
-                .user_owns_document(doc_id, *uid)
-                .await
-                .unwrap_or(false);
-            if owns {
-                Capability::Edit
-            } else {
-                Capability::None
-            }
-        }
-        Actor::ShareToken(t) => {
-            // Resolve token target and then decide access when document matches token scope
-            if let Ok(Some((share_id, perm, expires_at, shared_id, shared_type))) =
-                shares_repo.resolve_share_by_token(t).await
-            {
-                // Check expiration
-                if let Some(exp) = expires_at {
-                    if exp < chrono::Utc::now() {
-                        return Capability::None;
-                    }
-                }
-                if shared_type != "folder" {
-                    if shared_id == doc_id {
-                        if perm == "edit" {
-                            Capability::Edit
-                        } else {
-                            Capability::View
-                        }
-                    } else {
-                        Capability::None
-                    }
+        Actor::User(uid) => match access_repo.user_document_access(doc_id, *uid).await {
+            Ok(Some(access)) => {
+                let cap = if access.owned {
+                    Capability::Edit
                 } else {
-                    // Need a materialized child share for this doc
-                    match shares_repo
-                        .get_materialized_permission(share_id, doc_id)
-                        .await
-                    {
-                        Ok(Some(p)) => {
-                            if p == "edit" {
-                                Capability::Edit
-                            } else {
-                                Capability::View
-                            }
-                        }
-                        _ => Capability::None,
-                    }
-                }
-            } else {
-                Capability::None
+                    access
+                        .permission
+                        .as_deref()
+                        .map_or(Capability::None, Capability::from_permission)
+                };
+                (cap, Some(access.locked))
             }
-        }
+            _ => (Capability::None, None),
+        },
+        Actor::ShareToken(t) => (resolve_share_grant(shares_repo, t, doc_id).await, None),
         Actor::Public => {
             let is_pub = access_repo
                 .is_document_public(doc_id)
                 .await
                 .unwrap_or(false);
             if is_pub {
-                Capability::View
+                (Capability::View, None)
+            } else {
+                (Capability::None, None)
+            }
+        }
+    }
+}
+
+async fn resolve_share_grant<R>(shares_repo: &R, t: &str, doc_id: Uuid) -> Capability
+where
+    R: ShareAccessPort + ?Sized,
+{
+    // Resolve token target and then decide access when document matches token scope
+    if let Ok(Some((share_id, perm, expires_at, shared_id, shared_type))) =
+        shares_repo.resolve_share_by_token(t).await
+    {
+        // Check expiration
+        if let Some(exp) = expires_at
+            && exp < chrono::Utc::now()
+        {
+            return Capability::None;
+        }
+        if shared_type != "folder" {
+            if shared_id == doc_id {
+                Capability::from_permission(&perm)
             } else {
                 Capability::None
             }
+        } else {
+            // Need a materialized child share for this doc
+            match shares_repo
+                .get_materialized_permission(share_id, doc_id)
+                .await
+            {
+                Ok(Some(p)) => Capability::from_permission(&p),
+                _ => Capability::None,
+            }
         }
+    } else {
+        Capability::None
     }
 }
 
 pub async fn require_view<A, R>(
     access_repo: &A,
     shares_repo: &R,
+    policy: AccessPolicy,
     actor: &Actor,
     doc_id: Uuid,
 ) -> anyhow::Result<Capability>
@@ -107,7 +192,7 @@ where
     A: AccessRepository + ?Sized,
     R: ShareAccessPort + ?Sized,
 {
-    let cap = resolve_document(access_repo, shares_repo, actor, doc_id).await;
+    let cap = resolve_document(access_repo, shares_repo, policy, actor, doc_id).await;
     if cap >= Capability::View {
         Ok(cap)
     } else {
@@ -118,6 +203,7 @@ where
 pub async fn require_edit<A, R>(
     access_repo: &A,
     shares_repo: &R,
+    policy: AccessPolicy,
     actor: &Actor,
     doc_id: Uuid,
 ) -> anyhow::Result<()>
@@ -125,10 +211,88 @@ where
     A: AccessRepository + ?Sized,
     R: ShareAccessPort + ?Sized,
 {
-    let cap = resolve_document(access_repo, shares_repo, actor, doc_id).await;
+    let cap = resolve_document(access_repo, shares_repo, policy, actor, doc_id).await;
     if cap >= Capability::Edit {
         Ok(())
     } else {
         anyhow::bail!("forbidden")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::application::ports::shares_repository::SharesRepository;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::shares::MemoryShares;
+
+    /// A published document someone else owns, with an edit share link to it.
+    async fn published_with_edit_link() -> (Arc<MemoryDocuments>, MemoryShares, Uuid, Actor) {
+        let docs = Arc::new(MemoryDocuments::default());
+        let shares = MemoryShares::new(docs.clone());
+        let owner = Uuid::new_v4();
+        let doc_id = docs.add(owner, "Notes", "document", None).id;
+        docs.update(doc_id, |d| d.public = true);
+        let (token, _, _) = shares
+            .create_share(owner, doc_id, "edit", None, None)
+            .await
+            .unwrap();
+        (docs, shares, doc_id, Actor::ShareToken(token))
+    }
+
+    #[tokio::test]
+    async fn anonymous_edit_is_denied_by_default() {
+        let (docs, shares, doc_id, actor) = published_with_edit_link().await;
+        let policy = AccessPolicy::default();
+
+        let cap = resolve_document(docs.as_ref(), &shares, policy, &actor, doc_id).await;
+        assert_eq!(cap, Capability::View);
+        assert!(
+            require_edit(docs.as_ref(), &shares, policy, &actor, doc_id)
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            resolve_document(docs.as_ref(), &shares, policy, &Actor::Public, doc_id).await,
+            Capability::View
+        );
+    }
+
+    #[tokio::test]
+    async fn anonymous_edit_is_allowed_when_enabled() {
+        let (docs, shares, doc_id, actor) = published_with_edit_link().await;
+        let policy = AccessPolicy {
+            allow_anonymous_edit: true,
+        };
+
+        let cap = resolve_document(docs.as_ref(), &shares, policy, &actor, doc_id).await;
+        assert_eq!(cap, Capability::Edit);
+        assert!(
+            require_edit(docs.as_ref(), &shares, policy, &actor, doc_id)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn locked_document_is_view_only_for_its_owner() {
+        let docs = Arc::new(MemoryDocuments::default());
+        let shares = MemoryShares::new(docs.clone());
+        let owner = Uuid::new_v4();
+        let doc_id = docs.add(owner, "Notes", "document", None).id;
+        docs.update(doc_id, |d| d.locked = true);
+        let policy = AccessPolicy::default();
+        let actor = Actor::User(owner);
+
+        assert_eq!(
+            resolve_document(docs.as_ref(), &shares, policy, &actor, doc_id).await,
+            Capability::View
+        );
+        assert_eq!(
+            resolve_grant(docs.as_ref(), &shares, policy, &actor, doc_id).await,
+            Capability::Edit
+        );
+    }
+}
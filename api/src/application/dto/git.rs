@@ -5,6 +5,8 @@ pub struct GitConfigDto {
     pub branch_name: String,
     pub auth_type: String,
     pub auto_sync: bool,
+    pub commit_message_template: Option<String>,
+    pub append_change_summary: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -30,6 +32,8 @@ pub struct UpsertGitConfigInput {
     pub auth_type: String,
     pub auth_data: serde_json::Value,
     pub auto_sync: Option<bool>,
+    pub commit_message_template: Option<String>,
+    pub append_change_summary: Option<bool>,
 }
 
 #[derive(Debug, Clone)]
@@ -47,6 +51,55 @@ pub struct GitCommitInfo {
     pub time: chrono::DateTime<chrono::Utc>,
 }
 
+/// Position in the commit history: the last commit of the previous page. Commits are listed
+/// newest first by `committed_at`, ties broken by commit id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHistoryCursor {
+    pub committed_at: chrono::DateTime<chrono::Utc>,
+    pub hash: String,
+}
+
+impl GitHistoryCursor {
+    pub fn of(commit: &GitCommitInfo) -> Self {
+        Self {
+            committed_at: commit.time,
+            hash: commit.hash.clone(),
+        }
+    }
+
+    /// Opaque `next_cursor` value: `<unix micros>.<hash>`.
+    pub fn encode(&self) -> String {
+        format!("{}.{}", self.committed_at.timestamp_micros(), self.hash)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let (micros, hash) = value.split_once('.')?;
+        if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        Some(Self {
+            committed_at: chrono::DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            hash: hash.to_ascii_lowercase(),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GitHistoryPage {
+    pub commits: Vec<GitCommitInfo>,
+    /// Pass back as `cursor` for the next (older) page; `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GitChangesPage {
+    pub files: Vec<GitChangeItem>,
+    /// Changed files across all pages.
+    pub total: usize,
+    /// Pass back as `cursor` for the next page; `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct GitWorkspaceStatus {
     pub repository_initialized: bool,
@@ -59,6 +112,8 @@ pub struct GitWorkspaceStatus {
 pub struct GitSyncRequestDto {
     pub message: Option<String>,
     pub force: Option<bool>,
+    /// Report what would be committed without committing or pushing.
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +122,7 @@ pub struct GitSyncResponseDto {
     pub message: String,
     pub commit_hash: Option<String>,
     pub files_changed: u32,
+    pub preview: Option<GitSyncPreview>,
 }
 
 #[derive(Debug, Clone)]
@@ -75,6 +131,85 @@ pub struct GitSyncOutcome {
     pub commit_hash: Option<String>,
     pub pushed: bool,
     pub message: String,
+    /// Set for dry runs only.
+    pub preview: Option<GitSyncPreview>,
+}
+
+/// Result of probing the configured remote without fetching or pushing.
+#[derive(Debug, Clone)]
+pub struct GitConnectionCheck {
+    pub branch: String,
+    pub reachable: bool,
+    pub authenticated: bool,
+    pub branch_exists: bool,
+    pub error: Option<GitConnectionError>,
+    pub message: Option<String>,
+}
+
+impl GitConnectionCheck {
+    pub fn ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitConnectionError {
+    NotConfigured,
+    AuthFailed,
+    HostUnreachable,
+    BranchMissing,
+    Other,
+}
+
+impl GitConnectionError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GitConnectionError::NotConfigured => "not_configured",
+            GitConnectionError::AuthFailed => "auth_failed",
+            GitConnectionError::HostUnreachable => "host_unreachable",
+            GitConnectionError::BranchMissing => "branch_missing",
+            GitConnectionError::Other => "other",
+        }
+    }
+}
+
+/// The commit a sync would create.
+#[derive(Debug, Clone)]
+pub struct GitSyncPreview {
+    pub files: Vec<GitChangeItem>,
+    pub diffs: Vec<DiffResult>,
+    pub stats: GitDiffStats,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GitDiffStats {
+    pub files_added: u32,
+    pub files_modified: u32,
+    pub files_deleted: u32,
+    pub lines_added: u32,
+    pub lines_deleted: u32,
+}
+
+impl GitDiffStats {
+    pub fn from_changes(files: &[GitChangeItem], diffs: &[DiffResult]) -> Self {
+        let mut stats = Self::default();
+        for file in files {
+            match file.status.as_str() {
+                "untracked" => stats.files_added += 1,
+                "modified" => stats.files_modified += 1,
+                "deleted" => stats.files_deleted += 1,
+                _ => {}
+            }
+        }
+        for line in diffs.iter().flat_map(|d| d.diff_lines.iter()) {
+            match line.line_type {
+                DiffLineType::Added => stats.lines_added += 1,
+                DiffLineType::Deleted => stats.lines_deleted += 1,
+                DiffLineType::Context => {}
+            }
+        }
+        stats
+    }
 }
 
 use serde::{Deserialize, Serialize};
@@ -102,3 +237,38 @@ pub struct DiffResult {
     pub old_content: Option<String>,
     pub new_content: Option<String>,
 }
+
+/// Uncommitted changes and the commit they are relative to.
+#[derive(Debug, Clone)]
+pub struct GitWorkingDiff {
+    /// Latest commit; `None` before the first sync, when every file is new.
+    pub base_commit_hash: Option<String>,
+    pub diffs: Vec<DiffResult>,
+}
+
+/// A line of a file with the commit that last changed it.
+#[derive(Debug, Clone)]
+pub struct GitBlameLine {
+    pub line_number: u32,
+    pub content: String,
+    pub commit: GitCommitInfo,
+}
+
+/// Head of a remote branch, fetched to import an existing repository.
+#[derive(Debug, Clone)]
+pub struct GitRemoteSnapshot {
+    pub commit: GitCommitInfo,
+    pub files: std::collections::BTreeMap<String, Vec<u8>>,
+    /// The commit and its history, stored as the pack of the imported baseline.
+    pub pack: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitImportSummary {
+    /// Baseline commit; `None` when the remote branch does not exist yet.
+    pub commit_hash: Option<String>,
+    pub folders: u32,
+    pub documents: u32,
+    /// Repository paths that are not markdown and were left out.
+    pub skipped: Vec<String>,
+}
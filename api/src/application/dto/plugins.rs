@@ -6,4 +6,15 @@ pub struct ExecResult {
     pub data: Option<serde_json::Value>,
     pub effects: Vec<serde_json::Value>,
     pub error: Option<serde_json::Value>,
+    /// Server-side effects that were applied on behalf of the plugin.
+    #[serde(default)]
+    pub applied: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PluginDataExport {
+    pub plugin: String,
+    pub doc_id: uuid::Uuid,
+    pub records: Vec<crate::application::ports::plugin_repository::PluginRecord>,
+    pub kv: Vec<crate::application::ports::plugin_repository::PluginKvEntry>,
 }
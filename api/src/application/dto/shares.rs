@@ -4,6 +4,7 @@ use uuid::Uuid;
 pub struct ActiveShareItemDto {
     pub id: Uuid,
     pub token: String,
+    pub short_code: Option<String>,
     pub permission: String,
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -0,0 +1,147 @@
+//! Resolves `@[[user]]` mentions to users and keeps a document's mentions in sync on save.
+//!
+//! A mention matches a user by email, or by name when exactly one user has that name (both
+//! ignoring case). Unknown or ambiguous mentions, and authors mentioning themselves, are
+//! ignored.
+
+use uuid::Uuid;
+
+use crate::application::ports::mention_repository::MentionRepository;
+use crate::application::ports::user_repository::{UserRepository, UserRow};
+
+use super::mention_targets;
+
+/// Users mentioned in `content`, each once, in order of first mention.
+pub async fn resolve_mentioned_users<U: UserRepository + ?Sized>(
+    users: &U,
+    content: &str,
+) -> anyhow::Result<Vec<Uuid>> {
+    let mut keys: Vec<String> = Vec::new();
+    for target in mention_targets(content) {
+        let key = target.trim().to_lowercase();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    if keys.is_empty() {
+        return Ok(Vec::new());
+    }
+    let candidates = users.find_by_names_or_emails(&keys).await?;
+    let mut resolved: Vec<Uuid> = Vec::new();
+    for key in &keys {
+        if let Some(id) = match_user(key, &candidates)
+            && !resolved.contains(&id)
+        {
+            resolved.push(id);
+        }
+    }
+    Ok(resolved)
+}
+
+fn match_user(key: &str, candidates: &[UserRow]) -> Option<Uuid> {
+    if let Some(user) = candidates.iter().find(|u| u.email.to_lowercase() == key) {
+        return Some(user.id);
+    }
+    let mut named = candidates.iter().filter(|u| u.name.to_lowercase() == key);
+    match (named.next(), named.next()) {
+        (Some(user), None) => Some(user.id),
+        _ => None,
+    }
+}
+
+/// Records the users mentioned in `doc_id` by `author_id` and returns those newly mentioned.
+pub async fn update_document_mentions<U, M>(
+    users: &U,
+    mentions: &M,
+    doc_id: Uuid,
+    author_id: Uuid,
+    content: &str,
+) -> anyhow::Result<Vec<Uuid>>
+where
+    U: UserRepository + ?Sized,
+    M: MentionRepository + ?Sized,
+{
+    let mut mentioned = resolve_mentioned_users(users, content).await?;
+    mentioned.retain(|id| *id != author_id);
+    mentions
+        .replace_mentions(doc_id, author_id, &mentioned)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::application::ports::mention_repository::MentionRow;
+    use crate::test_support::users::MemoryUsers;
+
+    #[derive(Default)]
+    struct Mentions(Mutex<Vec<(Uuid, Uuid)>>);
+
+    #[async_trait]
+    impl MentionRepository for Mentions {
+        async fn replace_mentions(
+            &self,
+            doc_id: Uuid,
+            _: Uuid,
+            user_ids: &[Uuid],
+        ) -> anyhow::Result<Vec<Uuid>> {
+            let mut rows = self.0.lock().unwrap();
+            rows.retain(|(doc, user)| *doc != doc_id || user_ids.contains(user));
+            let mut added = Vec::new();
+            for user in user_ids {
+                if !rows.contains(&(doc_id, *user)) {
+                    rows.push((doc_id, *user));
+                    added.push(*user);
+                }
+            }
+            Ok(added)
+        }
+        async fn list_for_user(&self, _: Uuid, _: i64) -> anyhow::Result<Vec<MentionRow>> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn valid_mentions_create_rows_for_the_user() {
+        let users = MemoryUsers::default();
+        let alice = users.add("Alice Liddell", "alice@example.com");
+        let bob = users.add("Bob", "bob@example.com");
+        let author = users.add("Carol", "carol@example.com");
+        let mentions = Mentions::default();
+        let doc = Uuid::new_v4();
+
+        let content = "Ping @[[alice liddell]] and @[[BOB@example.com|Bob]], cc @[[Carol]]";
+        let added = update_document_mentions(&users, &mentions, doc, author.id, content)
+            .await
+            .unwrap();
+        assert_eq!(added, vec![alice.id, bob.id]);
+
+        // Saving again only reports new mentions and drops removed ones.
+        let added = update_document_mentions(&users, &mentions, doc, author.id, "@[[Bob]]")
+            .await
+            .unwrap();
+        assert!(added.is_empty());
+        assert_eq!(*mentions.0.lock().unwrap(), vec![(doc, bob.id)]);
+    }
+
+    #[tokio::test]
+    async fn unknown_ambiguous_and_code_mentions_are_ignored() {
+        let users = MemoryUsers::default();
+        users.add("Sam", "sam.one@example.com");
+        users.add("Sam", "sam.two@example.com");
+        users.add("Dana", "dana@example.com");
+        let mentions = Mentions::default();
+
+        let content = "@[[Nobody]] @[[sam]] `@[[Dana]]`";
+        let added =
+            update_document_mentions(&users, &mentions, Uuid::new_v4(), Uuid::new_v4(), content)
+                .await
+                .unwrap();
+        assert!(added.is_empty());
+        assert!(mentions.0.lock().unwrap().is_empty());
+    }
+}
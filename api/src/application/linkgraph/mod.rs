@@ -1,6 +1,14 @@
-use crate::application::ports::linkgraph_repository::LinkGraphRepository;
+pub mod mentions;
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::application::ports::linkgraph_repository::{
+    LinkGraphRepository, NewDocumentLink, TitleCandidate,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -29,6 +37,8 @@ enum LinkTarget {
 #[derive(Debug, Clone)]
 struct DocumentLink {
     target: LinkTarget,
+    /// Block id of a `[[doc^id]]` reference.
+    block: Option<String>,
     link_type: LinkType,
     link_text: Option<String>,
     position_start: i32,
@@ -41,8 +51,113 @@ static EMBED_LINK_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"!\[\[([^\[\]|]+)(?:\|([^\[\]]+))?\]\]").unwrap());
 static MENTION_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"@\[\[([^\[\]|]+)(?:\|([^\[\]]+))?\]\]").unwrap());
+static BLOCK_MARKER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?:^|\s)\^([A-Za-z0-9-]+)\s*$").unwrap());
+
+/// Splits a trailing `^blockid` marker off a line of text, returning the text before it and
+/// the block id.
+pub fn split_block_marker(text: &str) -> Option<(&str, &str)> {
+    let cap = BLOCK_MARKER_REGEX.captures(text)?;
+    let id = cap.get(1)?;
+    Some((text[..cap.get(0)?.start()].trim_end(), id.as_str()))
+}
+
+/// Block ids declared by trailing `^id` markers in `content`, outside code.
+pub fn parse_block_ids(content: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for line in mask_code(content).lines() {
+        if let Some((_, id)) = split_block_marker(line)
+            && !ids.iter().any(|seen| seen == id)
+        {
+            ids.push(id.to_string());
+        }
+    }
+    ids
+}
+
+/// `content` with fenced code blocks and inline code spans blanked out, byte for byte, so
+/// offsets into the result are offsets into `content`. Newlines are kept.
+fn mask_code(content: &str) -> String {
+    let mut bytes = content.as_bytes().to_vec();
+    // Open fence: marker character and run length
+    let mut fence: Option<(u8, usize)> = None;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let range = offset..offset + line.len();
+        offset += line.len();
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+        let marker = trimmed.bytes().next().filter(|b| *b == b'`' || *b == b'~');
+        let run = marker.map_or(0, |m| trimmed.bytes().take_while(|b| *b == m).count());
+        match fence {
+            Some((ch, len)) => {
+                blank(&mut bytes[range]);
+                if marker == Some(ch) && run >= len && trimmed[run..].trim().is_empty() {
+                    fence = None;
+                }
+            }
+            None if indent <= 3 && run >= 3 => {
+                fence = marker.map(|m| (m, run));
+                blank(&mut bytes[range]);
+            }
+            None => blank_code_spans(&mut bytes[range]),
+        }
+    }
+    // Only ASCII bytes were written, so the result is still UTF-8.
+    String::from_utf8(bytes).unwrap_or_else(|_| content.to_string())
+}
+
+/// Blanks backtick code spans within one line; an unmatched backtick run is literal text.
+fn blank_code_spans(line: &mut [u8]) {
+    let run_at = |line: &[u8], at: usize| line[at..].iter().take_while(|b| **b == b'`').count();
+    let mut i = 0;
+    while i < line.len() {
+        if line[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let open = run_at(line, i);
+        let mut j = i + open;
+        let mut close = None;
+        while j < line.len() {
+            if line[j] == b'`' {
+                let run = run_at(line, j);
+                if run == open {
+                    close = Some(j + run);
+                    break;
+                }
+                j += run;
+            } else {
+                j += 1;
+            }
+        }
+        match close {
+            Some(end) => {
+                blank(&mut line[i..end]);
+                i = end;
+            }
+            None => i += open,
+        }
+    }
+}
+
+fn blank(bytes: &mut [u8]) {
+    for b in bytes.iter_mut().filter(|b| **b != b'\n') {
+        *b = b' ';
+    }
+}
 
+/// The block id of a `[[doc^id]]` target.
+fn parse_block_ref(txt: &str) -> Option<String> {
+    let (_, block) = txt.split_once('^')?;
+    let block = block.trim();
+    (!block.is_empty()).then(|| block.to_string())
+}
+
+/// Links in `content`, ignoring anything inside code spans and fenced code blocks.
 fn parse_links(content: &str) -> Vec<DocumentLink> {
+    let masked = mask_code(content);
+    let content = masked.as_str();
     let mut links: Vec<DocumentLink> = Vec::new();
     let mut seen: std::collections::HashSet<usize> = std::collections::HashSet::new();
 
@@ -57,6 +172,7 @@ fn parse_links(content: &str) -> Vec<DocumentLink> {
         let display_text = cap.get(2).map(|m| m.as_str().to_string());
         links.push(DocumentLink {
             target: parse_target(target_text),
+            block: parse_block_ref(target_text),
             link_type: LinkType::Embed,
             link_text: display_text,
             position_start: start as i32,
@@ -75,6 +191,7 @@ fn parse_links(content: &str) -> Vec<DocumentLink> {
         let display_text = cap.get(2).map(|m| m.as_str().to_string());
         links.push(DocumentLink {
             target: parse_target(target_text),
+            block: parse_block_ref(target_text),
             link_type: LinkType::Mention,
             link_text: display_text,
             position_start: start as i32,
@@ -85,13 +202,15 @@ fn parse_links(content: &str) -> Vec<DocumentLink> {
     for cap in WIKI_LINK_REGEX.captures_iter(content) {
         let mat = cap.get(0).unwrap();
         let start = mat.start();
-        if seen.contains(&start) {
+        // The `[[...]]` of an embed or mention starts one past its `!`/`@`.
+        if seen.contains(&start) || start.checked_sub(1).is_some_and(|at| seen.contains(&at)) {
             continue;
         }
         let target_text = cap.get(1).unwrap().as_str();
         let display_text = cap.get(2).map(|m| m.as_str().to_string());
         links.push(DocumentLink {
             target: parse_target(target_text),
+            block: parse_block_ref(target_text),
             link_type: LinkType::Reference,
             link_text: display_text,
             position_start: start as i32,
@@ -103,8 +222,9 @@ fn parse_links(content: &str) -> Vec<DocumentLink> {
     links
 }
 
+/// A `#heading` or `^block` suffix narrows the link within the target and is not part of it.
 fn parse_target(txt: &str) -> LinkTarget {
-    let t = txt.trim();
+    let t = txt.split(['#', '^']).next().unwrap_or("").trim();
     if let Ok(id) = Uuid::parse_str(t) {
         LinkTarget::Id(id)
     } else {
@@ -112,6 +232,118 @@ fn parse_target(txt: &str) -> LinkTarget {
     }
 }
 
+/// Targets of the `@[[...]]` mentions in `content`, outside code, in order of appearance.
+fn mention_targets(content: &str) -> Vec<String> {
+    parse_links(content)
+        .into_iter()
+        .filter(|link| link.link_type == LinkType::Mention)
+        .filter_map(|link| match link.target {
+            LinkTarget::Title(title) if !title.is_empty() => Some(title),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Normalized form used to match wikilink targets against document titles: diacritics folded,
+/// lowercased, and whitespace collapsed. The display title is stored unchanged.
+pub fn title_key(title: &str) -> String {
+    let folded: String = title
+        .nfkd()
+        .filter(|c| !is_combining_mark(*c))
+        .collect::<String>()
+        .to_lowercase();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Picks the target among documents sharing a title key: an exact title match wins,
+/// otherwise the most recently updated document.
+fn pick_title_match(title: &str, candidates: Vec<TitleCandidate>) -> Option<Uuid> {
+    if let Some(exact) = candidates.iter().find(|c| c.title.trim() == title) {
+        return Some(exact.id);
+    }
+    candidates
+        .into_iter()
+        .max_by_key(|c| c.updated_at)
+        .map(|c| c.id)
+}
+
+/// Resolves link targets by id or title among the owner's documents, with one query for all
+/// ids and one for all titles.
+async fn resolve_targets<'t, R: LinkGraphRepository + ?Sized>(
+    repo: &R,
+    owner_id: Uuid,
+    targets: impl IntoIterator<Item = &'t LinkTarget>,
+) -> anyhow::Result<HashMap<LinkTarget, Uuid>> {
+    let mut ids: Vec<Uuid> = Vec::new();
+    let mut titles: Vec<&str> = Vec::new();
+    for target in targets {
+        match target {
+            LinkTarget::Id(id) if !ids.contains(id) => ids.push(*id),
+            LinkTarget::Title(title) if !title.is_empty() && !titles.contains(&title.as_str()) => {
+                titles.push(title)
+            }
+            _ => {}
+        }
+    }
+
+    let mut resolved = HashMap::new();
+    if !ids.is_empty() {
+        for id in repo.existing_docs_for_owner(owner_id, &ids).await? {
+            resolved.insert(LinkTarget::Id(id), id);
+        }
+    }
+    if !titles.is_empty() {
+        let mut keys: Vec<String> = titles.iter().map(|t| title_key(t)).collect();
+        keys.sort();
+        keys.dedup();
+        let candidates = repo
+            .find_docs_by_owner_and_title_keys(owner_id, &keys)
+            .await?;
+        for title in titles {
+            let key = title_key(title);
+            let matching = candidates
+                .iter()
+                .filter(|c| title_key(&c.title) == key)
+                .cloned()
+                .collect();
+            if let Some(id) = pick_title_match(title, matching) {
+                resolved.insert(LinkTarget::Title(title.to_string()), id);
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// Documents the wikilinks in `content` point to, keyed by `title_key` of the link's
+/// document part, for the renderer to turn into document hrefs. Block references whose
+/// block exists in the target are also keyed as `{key}^{block}`.
+pub async fn resolve_wikilink_targets<R: LinkGraphRepository + ?Sized>(
+    repo: &R,
+    owner_id: Uuid,
+    content: &str,
+) -> anyhow::Result<BTreeMap<String, Uuid>> {
+    let links = parse_links(content);
+    let targets = resolve_targets(repo, owner_id, links.iter().map(|l| &l.target)).await?;
+    let mut resolved = BTreeMap::new();
+    for link in links {
+        let key = match &link.target {
+            LinkTarget::Id(id) => id.to_string(),
+            LinkTarget::Title(title) => title_key(title),
+        };
+        let Some(&id) = resolved.get(&key).or_else(|| targets.get(&link.target)) else {
+            continue;
+        };
+        resolved.entry(key.clone()).or_insert(id);
+        if let Some(block) = link.block {
+            let block_key = format!("{key}^{block}");
+            if !resolved.contains_key(&block_key) && repo.has_block(id, &block).await? {
+                resolved.insert(block_key, id);
+            }
+        }
+    }
+    Ok(resolved)
+}
+
 pub async fn update_document_links<R: LinkGraphRepository + ?Sized>(
     repo: &R,
     owner_id: Uuid,
@@ -121,34 +353,316 @@ pub async fn update_document_links<R: LinkGraphRepository + ?Sized>(
     let links = parse_links(content);
     // Clear previous links for the source
     repo.clear_links_for_source(source_id).await?;
+    repo.replace_block_ids(source_id, &parse_block_ids(content))
+        .await?;
+    if links.is_empty() {
+        return Ok(());
+    }
 
-    for link in links {
-        // Resolve target by id or title for the same owner
-        let target_doc_id: Option<Uuid> = match link.target {
-            LinkTarget::Id(id) => {
-                if repo.exists_doc_for_owner(id, owner_id).await? {
-                    Some(id)
-                } else {
-                    None
-                }
+    let targets = resolve_targets(repo, owner_id, links.iter().map(|l| &l.target)).await?;
+    let rows: Vec<NewDocumentLink> = links
+        .into_iter()
+        .filter_map(|link| {
+            Some(NewDocumentLink {
+                target_id: *targets.get(&link.target)?,
+                link_type: link.link_type.as_str().to_string(),
+                link_text: link.link_text,
+                position_start: link.position_start,
+                position_end: link.position_end,
+            })
+        })
+        .collect();
+    repo.upsert_links(source_id, &rows).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    struct Graph {
+        owner: Uuid,
+        docs: Vec<TitleCandidate>,
+        links: Mutex<Vec<Uuid>>,
+        blocks: Mutex<Vec<(Uuid, String)>>,
+        queries: AtomicUsize,
+    }
+
+    impl Graph {
+        fn new(owner: Uuid, titles: &[(&str, i64)]) -> Self {
+            let now = chrono::Utc::now();
+            let docs = titles
+                .iter()
+                .map(|(title, age)| TitleCandidate {
+                    id: Uuid::new_v4(),
+                    title: title.to_string(),
+                    updated_at: now - chrono::Duration::minutes(*age),
+                })
+                .collect();
+            Self {
+                owner,
+                docs,
+                links: Mutex::new(Vec::new()),
+                blocks: Mutex::new(Vec::new()),
+                queries: AtomicUsize::new(0),
+            }
+        }
+
+        async fn resolve(&self, content: &str) -> Vec<Uuid> {
+            update_document_links(self, self.owner, Uuid::new_v4(), content)
+                .await
+                .unwrap();
+            self.links.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl LinkGraphRepository for Graph {
+        async fn clear_links_for_source(&self, _source_id: Uuid) -> anyhow::Result<()> {
+            self.queries.fetch_add(1, Ordering::Relaxed);
+            self.links.lock().unwrap().clear();
+            Ok(())
+        }
+
+        async fn existing_docs_for_owner(
+            &self,
+            owner_id: Uuid,
+            doc_ids: &[Uuid],
+        ) -> anyhow::Result<Vec<Uuid>> {
+            self.queries.fetch_add(1, Ordering::Relaxed);
+            if owner_id != self.owner {
+                return Ok(Vec::new());
             }
-            LinkTarget::Title(title) => {
-                repo.find_doc_id_by_owner_and_title(owner_id, &title)
-                    .await?
+            Ok(self
+                .docs
+                .iter()
+                .map(|d| d.id)
+                .filter(|id| doc_ids.contains(id))
+                .collect())
+        }
+
+        async fn find_docs_by_owner_and_title_keys(
+            &self,
+            owner_id: Uuid,
+            keys: &[String],
+        ) -> anyhow::Result<Vec<TitleCandidate>> {
+            self.queries.fetch_add(1, Ordering::Relaxed);
+            if owner_id != self.owner {
+                return Ok(Vec::new());
             }
-        };
+            Ok(self
+                .docs
+                .iter()
+                .filter(|d| keys.contains(&title_key(&d.title)))
+                .cloned()
+                .collect())
+        }
 
-        if let Some(target_id) = target_doc_id {
-            repo.upsert_link(
-                source_id,
-                target_id,
-                link.link_type.as_str(),
-                link.link_text,
-                link.position_start,
-                link.position_end,
-            )
-            .await?;
+        async fn upsert_links(
+            &self,
+            _source_id: Uuid,
+            links: &[NewDocumentLink],
+        ) -> anyhow::Result<()> {
+            self.queries.fetch_add(1, Ordering::Relaxed);
+            self.links
+                .lock()
+                .unwrap()
+                .extend(links.iter().map(|l| l.target_id));
+            Ok(())
         }
+
+        async fn replace_block_ids(
+            &self,
+            doc_id: Uuid,
+            block_ids: &[String],
+        ) -> anyhow::Result<()> {
+            self.queries.fetch_add(1, Ordering::Relaxed);
+            let mut blocks = self.blocks.lock().unwrap();
+            blocks.retain(|(doc, _)| *doc != doc_id);
+            blocks.extend(block_ids.iter().map(|id| (doc_id, id.clone())));
+            Ok(())
+        }
+
+        async fn has_block(&self, doc_id: Uuid, block_id: &str) -> anyhow::Result<bool> {
+            Ok(self
+                .blocks
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|(doc, id)| *doc == doc_id && id == block_id))
+        }
+    }
+
+    #[test]
+    fn title_key_folds_case_diacritics_and_whitespace() {
+        assert_eq!(title_key("  My   Nóte "), "my note");
+        assert_eq!(title_key("Crème Brûlée"), "creme brulee");
+        assert_eq!(title_key("日本語"), "日本語");
+    }
+
+    #[tokio::test]
+    async fn case_only_difference_resolves() {
+        let graph = Graph::new(Uuid::new_v4(), &[("my note", 0), ("Other", 0)]);
+        let target = graph.docs[0].id;
+        assert_eq!(graph.resolve("see [[My Note]]").await, vec![target]);
+    }
+
+    #[tokio::test]
+    async fn diacritic_difference_resolves() {
+        let graph = Graph::new(Uuid::new_v4(), &[("My Nóte", 0)]);
+        let target = graph.docs[0].id;
+        assert_eq!(
+            graph.resolve("[[my note]] and [[MY  NOTE]]").await,
+            vec![target, target]
+        );
+    }
+
+    #[tokio::test]
+    async fn ambiguous_keys_prefer_exact_then_most_recent() {
+        // "Resume" is older than "Résumé" but matches the link text exactly.
+        let graph = Graph::new(
+            Uuid::new_v4(),
+            &[("Résumé", 5), ("Resume", 60), ("resume", 1)],
+        );
+        let exact = graph.docs[1].id;
+        assert_eq!(graph.resolve("[[Resume]]").await, vec![exact]);
+
+        let newest = graph.docs[2].id;
+        assert_eq!(graph.resolve("[[RESUME]]").await, vec![newest]);
+    }
+
+    async fn render_linked(graph: &Graph, content: &str) -> String {
+        use crate::application::services::markdown::{self, RenderOptions};
+        let wiki_links = resolve_wikilink_targets(graph, graph.owner, content)
+            .await
+            .unwrap();
+        let opts = RenderOptions {
+            wiki_links,
+            ..Default::default()
+        };
+        markdown::render(content.to_string(), opts, None)
+            .unwrap()
+            .html
+    }
+
+    #[tokio::test]
+    async fn heading_links_resolve_to_the_document_anchor() {
+        let graph = Graph::new(Uuid::new_v4(), &[("Doc", 0)]);
+        let doc = graph.docs[0].id;
+        // The heading narrows the link; the graph still records the document.
+        assert_eq!(graph.resolve("[[Doc#Section]]").await, vec![doc]);
+
+        let html = render_linked(&graph, "See [[Doc#Section]].").await;
+        assert!(html.contains(&format!("href=\"/document/{doc}#section\"")));
+        assert!(html.contains(">Doc#Section</a>"));
+
+        let html = render_linked(&graph, "See [[Doc#Next Steps!|the plan]].").await;
+        assert!(html.contains(&format!("href=\"/document/{doc}#next-steps\"")));
+        assert!(html.contains(">the plan</a>"));
+    }
+
+    #[tokio::test]
+    async fn unresolved_heading_links_keep_the_wiki_href() {
+        let graph = Graph::new(Uuid::new_v4(), &[]);
+        let html = render_linked(&graph, "See [[Missing#Section]].").await;
+        assert!(html.contains("href=\"#wiki:Missing#Section\""));
+    }
+
+    #[tokio::test]
+    async fn trailing_markers_assign_block_ids() {
+        let graph = Graph::new(Uuid::new_v4(), &[("Doc", 0)]);
+        let doc = graph.docs[0].id;
+        let content =
+            "Intro paragraph ^intro\n\n- item ^item-2\n\n```\ncode ^not-a-block\n```\n\nx^y\n";
+        update_document_links(&graph, graph.owner, doc, content)
+            .await
+            .unwrap();
+        let blocks: Vec<String> = graph
+            .blocks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, id)| id.clone())
+            .collect();
+        assert_eq!(blocks, vec!["intro", "item-2"]);
+
+        let html = render_linked(&graph, content).await;
+        assert!(html.contains("<span id=\"block-intro\" class=\"block-anchor\"></span>"));
+        assert!(html.contains("Intro paragraph<span"));
+        assert!(!html.contains("^intro"));
+    }
+
+    #[tokio::test]
+    async fn block_references_resolve_to_the_block_anchor() {
+        let graph = Graph::new(Uuid::new_v4(), &[("Doc", 0)]);
+        let doc = graph.docs[0].id;
+        update_document_links(&graph, graph.owner, doc, "Key finding ^finding")
+            .await
+            .unwrap();
+        // The block narrows the link; the graph still records the document.
+        assert_eq!(graph.resolve("[[Doc^finding]]").await, vec![doc]);
+
+        let html = render_linked(&graph, "See [[Doc^finding|the finding]].").await;
+        assert!(html.contains(&format!("href=\"/document/{doc}#block-finding\"")));
+        assert!(html.contains(">the finding</a>"));
+
+        // Unknown blocks fall back to the document itself.
+        let html = render_linked(&graph, "See [[Doc^gone]].").await;
+        assert!(html.contains(&format!("href=\"/document/{doc}\"")));
+    }
+
+    #[tokio::test]
+    async fn many_links_are_saved_in_a_fixed_number_of_queries() {
+        let titles: Vec<(String, i64)> = (0..30).map(|i| (format!("Note {i}"), 0)).collect();
+        let titles: Vec<(&str, i64)> = titles.iter().map(|(t, a)| (t.as_str(), *a)).collect();
+        let graph = Graph::new(Uuid::new_v4(), &titles);
+        let by_id = graph.docs[29].id;
+        let mut content: String = (0..30)
+            .map(|i| format!("[[Note {i}]] and again [[note {i}|alias]]\n"))
+            .collect();
+        content.push_str(&format!("![[{by_id}]] @[[{by_id}]] [[Missing]]\n"));
+
+        let links = graph.resolve(&content).await;
+        // clear + block ids + ids lookup + titles lookup + bulk upsert
+        assert_eq!(graph.queries.load(Ordering::Relaxed), 5);
+        assert_eq!(links.len(), 62);
+        assert_eq!(links[0], graph.docs[0].id);
+        assert_eq!(links[1], graph.docs[0].id);
+        assert_eq!(links[59], graph.docs[29].id);
+        assert_eq!(&links[60..], &[by_id, by_id]);
+    }
+
+    #[test]
+    fn embeds_and_mentions_are_not_also_references() {
+        let links = parse_links("![[Chart]] and @[[Ada]] and [[Doc]]\n");
+        let types: Vec<_> = links.iter().map(|l| l.link_type.clone()).collect();
+        assert!(matches!(
+            types.as_slice(),
+            [LinkType::Embed, LinkType::Mention, LinkType::Reference]
+        ));
+    }
+
+    #[tokio::test]
+    async fn links_inside_code_are_not_recorded() {
+        let graph = Graph::new(Uuid::new_v4(), &[("Prose", 0), ("Example", 0)]);
+        let prose = graph.docs[0].id;
+        let content = "Use `[[Example]]` syntax, see [[Prose]].\n\n```md\n[[Example]]\n```\n\n~~~~\n```\n[[Example]]\n~~~~\n\n``a ` [[Example]]`` then [[Prose]]\n";
+        assert_eq!(graph.resolve(content).await, vec![prose, prose]);
+
+        // Positions still point into the original content.
+        let links = parse_links(content);
+        let start = links[0].position_start as usize;
+        assert_eq!(&content[start..links[0].position_end as usize], "[[Prose]]");
+    }
+
+    #[test]
+    fn unmatched_backticks_do_not_hide_links() {
+        let links = parse_links("a ` stray backtick [[Doc]]\n");
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].target, LinkTarget::Title("Doc".into()));
     }
-    Ok(())
 }
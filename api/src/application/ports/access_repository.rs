@@ -1,8 +1,26 @@
 use async_trait::async_trait;
 use uuid::Uuid;
 
+/// What a signed-in user holds on a document, read in a single lookup.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserDocumentAccess {
+    pub owned: bool,
+    /// Permission ("view"/"comment"/"edit") granted directly to a non-owner user, if any.
+    pub permission: Option<String>,
+    pub locked: bool,
+}
+
 #[async_trait]
 pub trait AccessRepository: Send + Sync {
     async fn user_owns_document(&self, doc_id: Uuid, user_id: Uuid) -> anyhow::Result<bool>;
     async fn is_document_public(&self, doc_id: Uuid) -> anyhow::Result<bool>;
+    /// Ownership, direct grant and lock state of a document for one user; `None` when the
+    /// document does not exist.
+    async fn user_document_access(
+        &self,
+        doc_id: Uuid,
+        user_id: Uuid,
+    ) -> anyhow::Result<Option<UserDocumentAccess>>;
+    /// Locked documents cannot be edited by anyone until the owner unlocks them.
+    async fn is_document_locked(&self, doc_id: Uuid) -> anyhow::Result<bool>;
 }
@@ -1,6 +1,25 @@
 use async_trait::async_trait;
+use uuid::Uuid;
 
 #[async_trait]
 pub trait AwarenessPublisher: Send + Sync {
     async fn publish_awareness(&self, doc_id: &str, frame: Vec<u8>) -> anyhow::Result<()>;
 }
+
+/// Who owns a realtime connection, as established by the server when it connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresenceIdentity {
+    User(Uuid),
+    /// Share-link or public viewers, which carry no user id.
+    Anonymous,
+}
+
+/// An awareness client in a document, with its display name resolved by the server.
+#[derive(Debug, Clone)]
+pub struct PresenceEntry {
+    pub client_id: u64,
+    pub identity: PresenceIdentity,
+    pub display_name: Option<String>,
+    /// The awareness state as published by the client.
+    pub state: serde_json::Value,
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// Source range a comment points at, in the renderer's sourcepos form
+/// (`start_line:start_col-end_line:end_col`, 1-based and inclusive).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentAnchor {
+    pub start_line: i32,
+    pub start_col: i32,
+    pub end_line: i32,
+    pub end_col: i32,
+}
+
+impl CommentAnchor {
+    pub fn is_valid(&self) -> bool {
+        self.start_line >= 1
+            && self.start_col >= 1
+            && self.end_col >= 1
+            && (self.start_line, self.start_col) <= (self.end_line, self.end_col)
+    }
+}
+
+impl FromStr for CommentAnchor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn pos(p: &str) -> Option<(i32, i32)> {
+            let (line, col) = p.trim().split_once(':')?;
+            Some((line.parse().ok()?, col.parse().ok()?))
+        }
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| anyhow::anyhow!("invalid sourcepos"))?;
+        let ((start_line, start_col), (end_line, end_col)) = pos(start)
+            .zip(pos(end))
+            .ok_or_else(|| anyhow::anyhow!("invalid sourcepos"))?;
+        Ok(Self {
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+        })
+    }
+}
+
+impl fmt::Display for CommentAnchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}-{}:{}",
+            self.start_line, self.start_col, self.end_line, self.end_col
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DocumentComment {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    /// Top-level comment this one replies to; threads are one level deep.
+    pub parent_id: Option<Uuid>,
+    /// `None` for comments left through a share link.
+    pub author_id: Option<Uuid>,
+    pub author_name: Option<String>,
+    pub anchor: Option<CommentAnchor>,
+    pub body: String,
+    pub resolved: bool,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewComment {
+    pub document_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub author_id: Option<Uuid>,
+    pub author_name: Option<String>,
+    pub anchor: Option<CommentAnchor>,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait CommentRepository: Send + Sync {
+    async fn create(&self, comment: &NewComment) -> anyhow::Result<DocumentComment>;
+
+    /// Oldest first, replies included.
+    async fn list_for_document(&self, doc_id: Uuid) -> anyhow::Result<Vec<DocumentComment>>;
+
+    async fn get(&self, doc_id: Uuid, id: Uuid) -> anyhow::Result<Option<DocumentComment>>;
+
+    /// Returns `false` when the comment does not exist.
+    async fn set_resolved(
+        &self,
+        id: Uuid,
+        resolved: bool,
+        by: Option<Uuid>,
+    ) -> anyhow::Result<bool>;
+
+    /// Deletes the comment and its replies.
+    async fn delete(&self, id: Uuid) -> anyhow::Result<bool>;
+
+    /// Users to tell about a new comment: the document owner and, for replies, everyone
+    /// who already wrote in the thread.
+    async fn recipients(&self, doc_id: Uuid, thread_id: Option<Uuid>) -> anyhow::Result<Vec<Uuid>>;
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use uuid::Uuid;
 
@@ -8,11 +10,21 @@ use crate::domain::documents::document::{
 
 #[async_trait]
 pub trait DocumentRepository: Send + Sync {
+    /// Owned documents plus those other users granted `user_id` direct access to, newest first.
     async fn list_for_user(
         &self,
         user_id: Uuid,
-        query: Option<String>,
-        tag: Option<String>,
+        filter: &DocumentListFilter,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<DocumentPage>;
+
+    /// Most recently edited non-folder documents, newest first.
+    async fn list_recent_for_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        include_shared: bool,
     ) -> anyhow::Result<Vec<DomainDocument>>;
 
     async fn list_ids_for_user(&self, user_id: Uuid) -> anyhow::Result<Vec<Uuid>>;
@@ -43,6 +55,17 @@ pub trait DocumentRepository: Send + Sync {
         parent_id: Option<Option<Uuid>>,
     ) -> anyhow::Result<Option<DomainDocument>>;
 
+    async fn set_locked(&self, id: Uuid, locked: bool) -> anyhow::Result<()>;
+
+    /// Replaces the tree icon and color; `None` clears either. Returns `None` if the document
+    /// does not exist.
+    async fn set_appearance(
+        &self,
+        id: Uuid,
+        icon: Option<&str>,
+        color: Option<&str>,
+    ) -> anyhow::Result<Option<DomainDocument>>;
+
     // Returns Some(type) if deleted, None if not found/unauthorized
     async fn delete_owned(&self, id: Uuid, user_id: Uuid) -> anyhow::Result<Option<String>>;
 
@@ -72,3 +95,30 @@ pub struct DocMeta {
     pub path: Option<String>,
     pub title: String,
 }
+
+#[derive(Debug, Clone, Default)]
+pub struct DocumentListFilter {
+    pub query: Option<String>,
+    pub tag: Option<String>,
+    /// "document" or "folder"
+    pub doc_type: Option<String>,
+    pub updated_since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Also count links to and from each listed document. Off by default since it joins
+    /// `document_links`.
+    pub include_link_counts: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DocumentPage {
+    pub items: Vec<DomainDocument>,
+    /// Number of documents matching the filter across all pages.
+    pub total: i64,
+    /// Per-document link counts, present only when requested via `include_link_counts`.
+    pub link_counts: Option<HashMap<Uuid, LinkCounts>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkCounts {
+    pub backlinks: i64,
+    pub outgoing: i64,
+}
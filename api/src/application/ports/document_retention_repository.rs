@@ -0,0 +1,21 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// Per-document retention override; `None` fields fall back to the global config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DocumentRetention {
+    pub snapshot_keep_versions: Option<i64>,
+    pub updates_keep_window: Option<i64>,
+}
+
+#[async_trait]
+pub trait DocumentRetentionRepository: Send + Sync {
+    async fn get(&self, doc_id: Uuid) -> anyhow::Result<Option<DocumentRetention>>;
+
+    /// Batch lookup used by the snapshot loop; documents without an override are omitted.
+    async fn get_many(&self, doc_ids: &[Uuid]) -> anyhow::Result<HashMap<Uuid, DocumentRetention>>;
+
+    async fn upsert(&self, doc_id: Uuid, retention: DocumentRetention) -> anyhow::Result<()>;
+}
@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct DocumentUserAccess {
+    pub user_id: Uuid,
+    pub email: String,
+    pub name: String,
+    /// "view", "comment" or "edit"
+    pub permission: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[async_trait]
+pub trait DocumentUserAccessRepository: Send + Sync {
+    /// Inserts or replaces the grant for `user_id`.
+    async fn grant(
+        &self,
+        doc_id: Uuid,
+        user_id: Uuid,
+        permission: &str,
+        granted_by: Uuid,
+    ) -> anyhow::Result<()>;
+
+    async fn revoke(&self, doc_id: Uuid, user_id: Uuid) -> anyhow::Result<bool>;
+
+    async fn list_for_document(&self, doc_id: Uuid) -> anyhow::Result<Vec<DocumentUserAccess>>;
+}
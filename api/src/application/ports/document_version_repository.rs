@@ -0,0 +1,17 @@
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// A stored CRDT snapshot of a document.
+#[derive(Debug, Clone)]
+pub struct DocumentVersion {
+    pub version: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[async_trait]
+pub trait DocumentVersionRepository: Send + Sync {
+    /// Retained snapshots, newest first.
+    async fn list_versions(&self, doc_id: Uuid) -> anyhow::Result<Vec<DocumentVersion>>;
+
+    async fn get_snapshot(&self, doc_id: Uuid, version: i64) -> anyhow::Result<Option<Vec<u8>>>;
+}
@@ -8,6 +8,19 @@ pub struct UserGitCfg {
     pub auth_type: Option<String>,
     pub auth_data: Option<serde_json::Value>,
     pub auto_sync: bool,
+    /// Used when a sync has no explicit message; see `services::commit_message`.
+    pub commit_message_template: Option<String>,
+    pub append_change_summary: bool,
+}
+
+/// Uncommitted document activity of a user with auto-sync enabled.
+#[derive(Debug, Clone)]
+pub struct AutoSyncCandidate {
+    pub user_id: Uuid,
+    /// Earliest `updated_at` among documents changed since the last commit.
+    pub first_change_at: chrono::DateTime<chrono::Utc>,
+    /// Latest `updated_at` among them.
+    pub last_change_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[async_trait]
@@ -26,6 +39,7 @@ pub trait GitRepository: Send + Sync {
             chrono::DateTime<chrono::Utc>,
         )>,
     >;
+    #[allow(clippy::too_many_arguments)]
     async fn upsert_config(
         &self,
         user_id: Uuid,
@@ -34,6 +48,8 @@ pub trait GitRepository: Send + Sync {
         auth_type: &str,
         auth_data: &serde_json::Value,
         auto_sync: Option<bool>,
+        commit_message_template: Option<&str>,
+        append_change_summary: Option<bool>,
     ) -> anyhow::Result<(
         Uuid,
         String,
@@ -68,4 +84,19 @@ pub trait GitRepository: Send + Sync {
     async fn delete_sync_logs(&self, user_id: Uuid) -> anyhow::Result<()>;
 
     async fn delete_repository_state(&self, user_id: Uuid) -> anyhow::Result<()>;
+
+    /// Users with auto-sync on and an initialized repository whose documents changed after
+    /// their latest commit.
+    async fn auto_sync_candidates(&self) -> anyhow::Result<Vec<AutoSyncCandidate>>;
+
+    /// Documents created by an import of the remote that has not completed.
+    async fn pending_import_documents(&self, user_id: Uuid) -> anyhow::Result<Vec<Uuid>>;
+
+    async fn add_pending_import_document(
+        &self,
+        user_id: Uuid,
+        document_id: Uuid,
+    ) -> anyhow::Result<()>;
+
+    async fn clear_pending_import(&self, user_id: Uuid) -> anyhow::Result<()>;
 }
@@ -31,6 +31,16 @@ pub struct BlobKey {
     pub path: String,
 }
 
+/// Space taken by a user's repository: one pack per commit, plus the per-commit file
+/// snapshots used for diffs and syncs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GitStorageUsage {
+    pub packs: u64,
+    pub pack_bytes: u64,
+    pub snapshot_blobs: u64,
+    pub snapshot_bytes: u64,
+}
+
 pub type PackStream = Pin<Box<dyn Stream<Item = anyhow::Result<PackBlob>> + Send>>;
 
 #[async_trait]
@@ -58,6 +68,14 @@ pub trait GitStorage: Send + Sync {
         meta: Option<&CommitMeta>,
     ) -> anyhow::Result<()>;
     async fn delete_all(&self, user_id: Uuid) -> anyhow::Result<()>;
+    async fn usage(&self, user_id: Uuid) -> anyhow::Result<GitStorageUsage>;
+    /// Deletes the file snapshots stored for one commit, returning how many blobs and bytes
+    /// were removed. The commit's pack is kept.
+    async fn delete_commit_blobs(
+        &self,
+        user_id: Uuid,
+        commit_id: &[u8],
+    ) -> anyhow::Result<(u64, u64)>;
 }
 
 pub fn encode_commit_id(bytes: &[u8]) -> String {
@@ -65,14 +83,14 @@ pub fn encode_commit_id(bytes: &[u8]) -> String {
 }
 
 pub fn decode_commit_id(hex: &str) -> anyhow::Result<CommitId> {
-    if hex.len() % 2 != 0 {
+    if !hex.len().is_multiple_of(2) {
         anyhow::bail!("invalid commit id length");
     }
     let mut out = Vec::with_capacity(hex.len() / 2);
     let chars: Vec<char> = hex.chars().collect();
     for chunk in chars.chunks(2) {
         let hi = chunk
-            .get(0)
+            .first()
             .ok_or_else(|| anyhow::anyhow!("invalid commit id"))?;
         let lo = chunk
             .get(1)
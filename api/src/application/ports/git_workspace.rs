@@ -2,28 +2,56 @@ use async_trait::async_trait;
 use uuid::Uuid;
 
 use crate::application::dto::git::{
-    DiffResult, GitChangeItem, GitCommitInfo, GitSyncOutcome, GitSyncRequestDto, GitWorkspaceStatus,
+    DiffResult, GitBlameLine, GitChangeItem, GitCommitInfo, GitConnectionCheck, GitHistoryCursor,
+    GitRemoteSnapshot, GitSyncOutcome, GitSyncRequestDto, GitWorkingDiff, GitWorkspaceStatus,
 };
 use crate::application::ports::git_repository::UserGitCfg;
 
+/// Empty file committed in every folder, so folders without documents survive in the tree
+/// and are recreated on import.
+pub const FOLDER_MARKER: &str = ".gitkeep";
+
 #[async_trait]
 pub trait GitWorkspacePort: Send + Sync {
     async fn ensure_repository(&self, user_id: Uuid, default_branch: &str) -> anyhow::Result<()>;
     async fn remove_repository(&self, user_id: Uuid) -> anyhow::Result<()>;
     async fn status(&self, user_id: Uuid) -> anyhow::Result<GitWorkspaceStatus>;
     async fn list_changes(&self, user_id: Uuid) -> anyhow::Result<Vec<GitChangeItem>>;
-    async fn working_diff(&self, user_id: Uuid) -> anyhow::Result<Vec<DiffResult>>;
+    async fn working_diff(&self, user_id: Uuid) -> anyhow::Result<GitWorkingDiff>;
     async fn commit_diff(
         &self,
         user_id: Uuid,
         from: &str,
         to: &str,
     ) -> anyhow::Result<Vec<DiffResult>>;
-    async fn history(&self, user_id: Uuid) -> anyhow::Result<Vec<GitCommitInfo>>;
+    /// Up to `limit` commits older than `before` (all commits when `None`), newest first by
+    /// commit time, ties broken by descending commit id.
+    async fn history(
+        &self,
+        user_id: Uuid,
+        before: Option<&GitHistoryCursor>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<GitCommitInfo>>;
+    /// Line-level blame of `path` as of the latest commit; empty when it is not committed.
+    async fn blame(&self, user_id: Uuid, path: &str) -> anyhow::Result<Vec<GitBlameLine>>;
     async fn sync(
         &self,
         user_id: Uuid,
         req: &GitSyncRequestDto,
         cfg: Option<&UserGitCfg>,
     ) -> anyhow::Result<GitSyncOutcome>;
+    /// Lists the remote's refs with the configured credentials (like `git ls-remote`).
+    async fn test_connection(&self, cfg: &UserGitCfg) -> anyhow::Result<GitConnectionCheck>;
+    /// Fetches the head of the configured branch; `None` when the branch does not exist.
+    async fn fetch_remote_snapshot(
+        &self,
+        cfg: &UserGitCfg,
+    ) -> anyhow::Result<Option<GitRemoteSnapshot>>;
+    /// Records a fetched remote head as the user's first commit without pushing, so the next
+    /// sync builds on the remote history instead of diverging from it.
+    async fn record_baseline(
+        &self,
+        user_id: Uuid,
+        snapshot: &GitRemoteSnapshot,
+    ) -> anyhow::Result<()>;
 }
@@ -8,5 +8,11 @@ pub trait GitignorePort: Send + Sync {
         dir: &str,
         patterns: &[String],
     ) -> anyhow::Result<usize>;
+    /// Removes lines exactly matching `patterns`; returns how many were removed.
+    async fn remove_gitignore_patterns(
+        &self,
+        dir: &str,
+        patterns: &[String],
+    ) -> anyhow::Result<usize>;
     async fn read_gitignore_patterns(&self, dir: &str) -> anyhow::Result<Vec<String>>;
 }
@@ -1,22 +1,42 @@
 use async_trait::async_trait;
 use uuid::Uuid;
 
+/// A document whose normalized title matched a wikilink.
+#[derive(Debug, Clone)]
+pub struct TitleCandidate {
+    pub id: Uuid,
+    pub title: String,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A resolved link from a source document, as stored in the link graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewDocumentLink {
+    pub target_id: Uuid,
+    pub link_type: String,
+    pub link_text: Option<String>,
+    pub position_start: i32,
+    pub position_end: i32,
+}
+
 #[async_trait]
 pub trait LinkGraphRepository: Send + Sync {
     async fn clear_links_for_source(&self, source_id: Uuid) -> anyhow::Result<()>;
-    async fn exists_doc_for_owner(&self, doc_id: Uuid, owner_id: Uuid) -> anyhow::Result<bool>;
-    async fn find_doc_id_by_owner_and_title(
+    /// Which of `doc_ids` exist and belong to `owner_id`.
+    async fn existing_docs_for_owner(
         &self,
         owner_id: Uuid,
-        title: &str,
-    ) -> anyhow::Result<Option<Uuid>>;
-    async fn upsert_link(
+        doc_ids: &[Uuid],
+    ) -> anyhow::Result<Vec<Uuid>>;
+    /// Owner's documents whose stored `title_key` is one of `title_keys`.
+    async fn find_docs_by_owner_and_title_keys(
         &self,
-        source_id: Uuid,
-        target_id: Uuid,
-        link_type: &str,
-        link_text: Option<String>,
-        position_start: i32,
-        position_end: i32,
-    ) -> anyhow::Result<()>;
+        owner_id: Uuid,
+        title_keys: &[String],
+    ) -> anyhow::Result<Vec<TitleCandidate>>;
+    /// Upserts all `links` from `source_id` in one statement.
+    async fn upsert_links(&self, source_id: Uuid, links: &[NewDocumentLink]) -> anyhow::Result<()>;
+    /// Replaces the `^id` block markers recorded for `doc_id`.
+    async fn replace_block_ids(&self, doc_id: Uuid, block_ids: &[String]) -> anyhow::Result<()>;
+    async fn has_block(&self, doc_id: Uuid, block_id: &str) -> anyhow::Result<bool>;
 }
@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// A document in which a user was mentioned.
+#[derive(Debug, Clone)]
+pub struct MentionRow {
+    pub document_id: Uuid,
+    pub title: String,
+    pub mentioned_by: Option<Uuid>,
+    pub mentioned_by_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait MentionRepository: Send + Sync {
+    /// Makes `user_ids` the users mentioned in `doc_id`, keeping existing mentions as they
+    /// were. Returns the users that were not mentioned before.
+    async fn replace_mentions(
+        &self,
+        doc_id: Uuid,
+        mentioned_by: Uuid,
+        user_ids: &[Uuid],
+    ) -> anyhow::Result<Vec<Uuid>>;
+    /// Most recent mentions of `user_id` first.
+    async fn list_for_user(&self, user_id: Uuid, limit: i64) -> anyhow::Result<Vec<MentionRow>>;
+}
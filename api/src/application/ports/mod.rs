@@ -1,19 +1,29 @@
 pub mod access_repository;
 pub mod awareness_port;
+pub mod comment_repository;
 pub mod document_repository;
+pub mod document_retention_repository;
+pub mod document_user_access_repository;
+pub mod document_version_repository;
 pub mod files_repository;
 pub mod git_repository;
 pub mod git_storage;
 pub mod git_workspace;
 pub mod gitignore_port;
 pub mod linkgraph_repository;
+pub mod mention_repository;
+pub mod notification_repository;
+pub mod notifier;
+pub mod pdf_renderer;
 pub mod plugin_asset_store;
 pub mod plugin_event_publisher;
+pub mod plugin_exec_log_repository;
 pub mod plugin_installation_repository;
 pub mod plugin_installer;
 pub mod plugin_package_fetcher;
 pub mod plugin_repository;
 pub mod plugin_runtime;
+pub mod plugin_secret_repository;
 pub mod public_repository;
 pub mod realtime_hydration_port;
 pub mod realtime_persistence_port;
@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub user_id: Uuid,
+    pub kind: String,
+    pub document_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone)]
+pub struct NotificationRow {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub document_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub data: serde_json::Value,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait NotificationRepository: Send + Sync {
+    async fn insert(&self, notification: &NewNotification) -> anyhow::Result<NotificationRow>;
+    /// Newest first.
+    async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        unread_only: bool,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<NotificationRow>>;
+    async fn count_unread(&self, user_id: Uuid) -> anyhow::Result<i64>;
+    /// Returns `false` when `user_id` has no such notification; already read ones count as found.
+    async fn mark_read(&self, user_id: Uuid, id: Uuid) -> anyhow::Result<bool>;
+}
@@ -0,0 +1,10 @@
+use async_trait::async_trait;
+
+use crate::application::ports::notification_repository::NewNotification;
+
+/// Delivery channel for notifications, so producers (mentions, share grants, ...) do not need
+/// to know how notifications are stored or pushed to clients.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, notification: NewNotification) -> anyhow::Result<()>;
+}
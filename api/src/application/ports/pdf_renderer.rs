@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+
+/// Error inside the `anyhow::Error` of `render_pdf` when the document contains characters
+/// the renderer's fonts cannot represent.
+#[derive(thiserror::Error, Debug)]
+#[error("the PDF fonts cannot encode {0:?}")]
+pub struct UnsupportedPdfText(pub Vec<char>);
+
+#[async_trait]
+pub trait PdfRenderer: Send + Sync {
+    /// Converts a self-contained HTML document (images inlined as data URIs) to PDF bytes.
+    /// Fails with [`UnsupportedPdfText`] rather than dropping text it cannot encode.
+    async fn render_pdf(&self, html: &str) -> anyhow::Result<Vec<u8>>;
+}
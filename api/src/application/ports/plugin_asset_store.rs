@@ -13,6 +13,13 @@ pub trait PluginAssetStore: Send + Sync {
     fn global_plugin_manifest_path(&self, plugin_id: &str, version: &str) -> PathBuf;
     fn remove_user_plugin_dir(&self, user_id: &Uuid, plugin_id: &str) -> anyhow::Result<()>;
 
+    /// Newest version of the user's plugin present in storage.
+    async fn latest_user_version(
+        &self,
+        user_id: &Uuid,
+        plugin_id: &str,
+    ) -> anyhow::Result<Option<String>>;
+
     async fn list_latest_global_manifests(&self) -> anyhow::Result<Vec<(String, String, Value)>>;
 
     async fn load_user_manifest(
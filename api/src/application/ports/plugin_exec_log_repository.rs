@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// One plugin invocation. `payload` is only kept when payload logging is enabled, and is
+/// redacted before it reaches the repository.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginExecLogEntry {
+    pub user_id: Option<Uuid>,
+    pub plugin: String,
+    /// `exec:<action>` for actions, otherwise the exported function name.
+    pub function: String,
+    pub input_bytes: i64,
+    pub output_bytes: Option<i64>,
+    pub duration_ms: i64,
+    pub ok: bool,
+    pub error: Option<String>,
+    pub payload: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait PluginExecLogRepository: Send + Sync {
+    async fn append(&self, entry: &PluginExecLogEntry) -> anyhow::Result<()>;
+
+    /// Most recent entries first.
+    async fn recent(
+        &self,
+        user_id: Uuid,
+        plugin: &str,
+        limit: i64,
+    ) -> anyhow::Result<Vec<PluginExecLogEntry>>;
+}
@@ -9,6 +9,7 @@ pub struct PluginInstallation {
     pub scope: String,
     pub origin_url: Option<String>,
     pub status: String,
+    pub pinned: bool,
     pub installed_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
@@ -25,6 +26,16 @@ pub trait PluginInstallationRepository: Send + Sync {
         status: &str,
     ) -> anyhow::Result<()>;
 
+    /// Switches the active version of an existing installation. Returns false
+    /// when the plugin is not installed for the user.
+    async fn set_version(
+        &self,
+        user_id: Uuid,
+        plugin_id: &str,
+        version: &str,
+        pinned: bool,
+    ) -> anyhow::Result<bool>;
+
     async fn list_for_user(&self, user_id: Uuid) -> anyhow::Result<Vec<PluginInstallation>>;
 
     async fn list_all(&self) -> anyhow::Result<Vec<PluginInstallation>>;
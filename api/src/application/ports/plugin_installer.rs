@@ -22,4 +22,13 @@ pub trait PluginInstaller: Send + Sync {
         user_id: Uuid,
         archive: &[u8],
     ) -> Result<InstalledPlugin, PluginInstallError>;
+
+    /// Installs into the global scope, available to every user.
+    async fn install_global(&self, archive: &[u8]) -> Result<InstalledPlugin, PluginInstallError>;
+
+    /// Removes a global plugin; `false` when it was not installed.
+    async fn uninstall_global(&self, plugin_id: &str) -> anyhow::Result<bool>;
+
+    /// Hides or restores a global plugin without uninstalling it; `false` when not installed.
+    async fn set_global_enabled(&self, plugin_id: &str, enabled: bool) -> anyhow::Result<bool>;
 }
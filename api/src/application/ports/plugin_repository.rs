@@ -14,6 +14,43 @@ pub struct PluginRecord {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+#[derive(Debug, Clone)]
+pub struct PluginKvEntry {
+    pub key: String,
+    pub value: JsonValue,
+}
+
+/// Record restored by an import; `id` is kept only when ids are preserved.
+#[derive(Debug, Clone)]
+pub struct PluginRecordImport {
+    pub id: Option<Uuid>,
+    pub kind: String,
+    pub data: JsonValue,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Data mutation requested by a plugin through its exec `effects`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginDataEffect {
+    PutKv {
+        doc_id: Uuid,
+        key: String,
+        value: JsonValue,
+    },
+    CreateRecord {
+        doc_id: Uuid,
+        kind: String,
+        data: JsonValue,
+    },
+    UpdateRecord {
+        record_id: Uuid,
+        patch: JsonValue,
+    },
+    DeleteRecord {
+        record_id: Uuid,
+    },
+}
+
 #[async_trait]
 pub trait PluginRepository: Send + Sync {
     // KV
@@ -63,6 +100,35 @@ pub trait PluginRepository: Send + Sync {
         offset: i64,
     ) -> anyhow::Result<Vec<PluginRecord>>;
 
+    async fn list_all_records(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Uuid,
+    ) -> anyhow::Result<Vec<PluginRecord>>;
+
+    async fn list_kv(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Option<Uuid>,
+    ) -> anyhow::Result<Vec<PluginKvEntry>>;
+
+    // Restores records and KV into the scope in one transaction; returns the
+    // number of records written.
+    async fn import_scope(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Uuid,
+        records: &[PluginRecordImport],
+        kv: &[PluginKvEntry],
+    ) -> anyhow::Result<usize>;
+
+    // Applies all effects in a single transaction; nothing is persisted if any effect fails.
+    async fn apply_effects(&self, plugin: &str, effects: &[PluginDataEffect])
+    -> anyhow::Result<()>;
+
     async fn delete_scoped_kv(&self, scope: &str, scope_ids: &[Uuid]) -> anyhow::Result<()>;
 
     async fn delete_scoped_records(&self, scope: &str, scope_ids: &[Uuid]) -> anyhow::Result<()>;
@@ -1,16 +1,41 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 
 use crate::application::dto::plugins::ExecResult;
 use uuid::Uuid;
 
+/// Failure of the plugin call itself, as opposed to host-side errors.
+/// Runtimes return it wrapped in `anyhow::Error` so callers can downcast.
+#[derive(thiserror::Error, Debug)]
+pub enum PluginInvocationError {
+    #[error("plugin call exceeded its time limit")]
+    Timeout,
+    #[error("plugin trapped: {0}")]
+    Trap(String),
+}
+
+/// An entry of a manifest's `schedules`: run `action` whenever `cron` matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginScheduleSpec {
+    pub cron: String,
+    pub action: String,
+}
+
+/// Secret values by key, readable by a plugin during one invocation.
+pub type PluginSecrets = HashMap<String, String>;
+
 #[async_trait]
 pub trait PluginRuntime: Send + Sync {
+    /// `secrets` are exposed through the `secret_get` host function, and only to plugins
+    /// declaring the `secrets` permission; they never appear in the plugin's input.
     async fn execute(
         &self,
         user_id: Option<Uuid>,
         plugin: &str,
         action: &str,
         payload: &serde_json::Value,
+        secrets: &PluginSecrets,
     ) -> anyhow::Result<Option<ExecResult>>;
 
     async fn render_placeholder(
@@ -26,4 +51,11 @@ pub trait PluginRuntime: Send + Sync {
         user_id: Option<Uuid>,
         plugin: &str,
     ) -> anyhow::Result<Option<Vec<String>>>;
+
+    /// Schedules declared in the plugin's manifest; `None` when the plugin is not installed.
+    async fn schedules(
+        &self,
+        user_id: Option<Uuid>,
+        plugin: &str,
+    ) -> anyhow::Result<Option<Vec<PluginScheduleSpec>>>;
 }
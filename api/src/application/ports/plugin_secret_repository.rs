@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// Per-user plugin secrets. Implementations encrypt values at rest; values cross this
+/// boundary in plaintext only on their way into a plugin invocation.
+#[async_trait]
+pub trait PluginSecretRepository: Send + Sync {
+    async fn put_secret(
+        &self,
+        user_id: Uuid,
+        plugin: &str,
+        key: &str,
+        value: &str,
+    ) -> anyhow::Result<()>;
+
+    async fn delete_secret(&self, user_id: Uuid, plugin: &str, key: &str) -> anyhow::Result<bool>;
+
+    async fn list_secret_keys(&self, user_id: Uuid, plugin: &str) -> anyhow::Result<Vec<String>>;
+
+    async fn load_secrets(
+        &self,
+        user_id: Uuid,
+        plugin: &str,
+    ) -> anyhow::Result<HashMap<String, String>>;
+}
@@ -1,6 +1,14 @@
 use async_trait::async_trait;
 use uuid::Uuid;
 
+/// (id, title, updated_at, published_at)
+pub type PublicDocumentRow = (
+    Uuid,
+    String,
+    chrono::DateTime<chrono::Utc>,
+    chrono::DateTime<chrono::Utc>,
+);
+
 #[async_trait]
 pub trait PublicRepository: Send + Sync {
     async fn ensure_ownership_and_owner_name(
@@ -17,17 +25,12 @@ pub trait PublicRepository: Send + Sync {
         owner_id: Uuid,
         doc_id: Uuid,
     ) -> anyhow::Result<Option<(String, String)>>; // (slug, owner_name)
+    /// Most recently updated first.
     async fn list_user_public_documents(
         &self,
         owner_name: &str,
-    ) -> anyhow::Result<
-        Vec<(
-            Uuid,
-            String,
-            chrono::DateTime<chrono::Utc>,
-            chrono::DateTime<chrono::Utc>,
-        )>,
-    >;
+        limit: i64,
+    ) -> anyhow::Result<Vec<PublicDocumentRow>>;
     async fn get_public_meta_by_owner_and_id(
         &self,
         owner_name: &str,
@@ -48,4 +51,15 @@ pub trait PublicRepository: Send + Sync {
         owner_name: &str,
         doc_id: Uuid,
     ) -> anyhow::Result<bool>;
+    /// Adds each `(document, day, views)` to the stored daily counts.
+    async fn add_public_views(
+        &self,
+        counts: &[(Uuid, chrono::NaiveDate, i64)],
+    ) -> anyhow::Result<()>;
+    /// (total views, views on or after `since`)
+    async fn public_view_stats(
+        &self,
+        doc_id: Uuid,
+        since: chrono::NaiveDate,
+    ) -> anyhow::Result<(i64, i64)>;
 }
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -31,6 +32,9 @@ pub struct DocumentRecord {
 pub trait DocStateReader: Send + Sync {
     async fn latest_snapshot(&self, doc_id: &Uuid) -> anyhow::Result<Option<DocSnapshot>>;
 
+    /// When the latest snapshot of the document was taken.
+    async fn latest_snapshot_at(&self, doc_id: &Uuid) -> anyhow::Result<Option<DateTime<Utc>>>;
+
     async fn updates_since(&self, doc_id: &Uuid, from_seq: i64) -> anyhow::Result<Vec<DocUpdate>>;
 
     async fn document_record(&self, doc_id: &Uuid) -> anyhow::Result<Option<DocumentRecord>>;
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use futures_util::stream::BoxStream;
 use uuid::Uuid;
 
@@ -33,6 +34,33 @@ pub trait DocPersistencePort: Send + Sync {
     async fn prune_updates_before(&self, doc_id: &Uuid, seq_inclusive: i64) -> anyhow::Result<()>;
 
     async fn clear_updates(&self, doc_id: &Uuid) -> anyhow::Result<()>;
+
+    /// Records a content edit by bumping the document's `updated_at`.
+    async fn touch_document(&self, doc_id: &Uuid) -> anyhow::Result<()>;
+
+    /// Stores the SHA-256 (hex) of the markdown file just written for the document.
+    async fn record_content_hash(&self, doc_id: &Uuid, hash: &str) -> anyhow::Result<()>;
+
+    /// Forgets the stored hash so readers fall back to the file itself.
+    async fn clear_content_hash(&self, doc_id: &Uuid) -> anyhow::Result<()>;
+
+    /// Renames the document (display title and wikilink key) without counting as an edit.
+    async fn update_title(&self, doc_id: &Uuid, title: &str) -> anyhow::Result<()>;
+}
+
+/// A document with entries in the realtime update log.
+#[derive(Debug, Clone)]
+pub struct PendingUpdates {
+    pub document_id: Uuid,
+    pub last_update_at: DateTime<Utc>,
+}
+
+#[async_trait]
+pub trait PersistenceTaskProducerPort: Send + Sync {
+    /// Documents whose update log still holds entries, with the time of the newest one.
+    async fn pending_updates(&self) -> anyhow::Result<Vec<PendingUpdates>>;
+
+    async fn enqueue_task(&self, doc_id: &Uuid) -> anyhow::Result<()>;
 }
 
 #[async_trait]
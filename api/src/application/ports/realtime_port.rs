@@ -21,7 +21,24 @@ impl fmt::Display for RealtimeError {
 
 impl std::error::Error for RealtimeError {}
 
-use super::realtime_types::{DynRealtimeSink, DynRealtimeStream};
+use super::awareness_port::PresenceEntry;
+use super::realtime_types::{DynRealtimeSink, DynRealtimeStream, RealtimeSession};
+
+/// Version of a document's content used for optimistic concurrency (hex SHA-256).
+pub fn content_version(content: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// Outcome of a conditional content replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentWrite {
+    Applied,
+    /// The document changed since the expected version; carries the current content.
+    Conflict {
+        current: String,
+    },
+}
 
 #[async_trait]
 pub trait RealtimeEngine: Send + Sync {
@@ -30,13 +47,42 @@ pub trait RealtimeEngine: Send + Sync {
         doc_id: &str,
         sink: DynRealtimeSink,
         stream: DynRealtimeStream,
-        can_edit: bool,
+        session: RealtimeSession,
     ) -> anyhow::Result<()>;
 
+    /// Applies a lock change to open connections and tells their clients.
+    async fn set_locked(&self, doc_id: &str, locked: bool) -> anyhow::Result<()>;
+
+    /// Awareness clients connected to this node for the document.
+    async fn presence(&self, _doc_id: &str) -> anyhow::Result<Vec<PresenceEntry>> {
+        Ok(Vec::new())
+    }
+
     async fn get_content(&self, doc_id: &str) -> anyhow::Result<Option<String>>;
 
     async fn force_persist(&self, doc_id: &str) -> anyhow::Result<()>;
 
+    /// Replaces the document content through the regular update path, so connected clients
+    /// receive it and it is persisted like an edit.
+    async fn replace_content(&self, doc_id: &str, content: &str) -> anyhow::Result<()>;
+
+    /// Replaces the content only if its current `content_version` equals `expected_version`.
+    /// Engines that can should override this so the check and the write cannot interleave
+    /// with concurrent edits.
+    async fn replace_content_if(
+        &self,
+        doc_id: &str,
+        expected_version: &str,
+        content: &str,
+    ) -> anyhow::Result<ContentWrite> {
+        let current = self.get_content(doc_id).await?.unwrap_or_default();
+        if content_version(&current) != expected_version {
+            return Ok(ContentWrite::Conflict { current });
+        }
+        self.replace_content(doc_id, content).await?;
+        Ok(ContentWrite::Applied)
+    }
+
     async fn force_save_to_fs(&self, doc_id: &str) -> anyhow::Result<()> {
         self.force_persist(doc_id).await
     }
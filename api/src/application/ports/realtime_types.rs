@@ -4,9 +4,31 @@ use std::sync::Arc;
 use futures_util::{Sink, Stream};
 use tokio::sync::Mutex;
 
+use super::awareness_port::PresenceIdentity;
 use super::realtime_port::RealtimeError;
 
 pub type DynRealtimeSink =
     Arc<Mutex<Pin<Box<dyn Sink<Vec<u8>, Error = RealtimeError> + Send + Sync + 'static>>>>;
 pub type DynRealtimeStream =
     Pin<Box<dyn Stream<Item = Result<Vec<u8>, RealtimeError>> + Send + Sync + 'static>>;
+
+/// Realtime wire protocol version negotiated for a connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RealtimeProtocol {
+    /// Plain y-websocket framing.
+    #[default]
+    V1,
+    /// V1 plus a large initial state sent as numbered chunks the client reassembles.
+    V2,
+}
+
+/// Terms a realtime connection was accepted on.
+#[derive(Debug, Clone, Copy)]
+pub struct RealtimeSession {
+    /// Whether the connection's grant allows editing; edits also need the document unlocked.
+    pub can_edit: bool,
+    /// Lock state when the connection was accepted; engines follow later changes.
+    pub locked: bool,
+    pub identity: PresenceIdentity,
+    pub protocol: RealtimeProtocol,
+}
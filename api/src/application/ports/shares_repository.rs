@@ -5,6 +5,8 @@ use uuid::Uuid;
 pub struct ShareRow {
     pub id: Uuid,
     pub token: String,
+    /// Short alias accepted wherever `token` is.
+    pub short_code: Option<String>,
     pub permission: String,
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
     pub parent_share_id: Option<Uuid>,
@@ -14,6 +16,29 @@ pub struct ShareRow {
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// A folder share whose permission is materialized onto the folder's descendants.
+#[derive(Debug, Clone)]
+pub struct FolderShareGrant {
+    pub share_id: Uuid,
+    pub folder_id: Uuid,
+    pub permission: String,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_by: Uuid,
+}
+
+/// Error inside the `anyhow::Error` of `create_shares` when a document already has the
+/// maximum number of active share links.
+#[derive(thiserror::Error, Debug)]
+#[error("document already has {0} active share links")]
+pub struct ShareLimitReached(pub usize);
+
+/// One share of a batch created through `create_shares`.
+#[derive(Debug, Clone)]
+pub struct NewShare {
+    pub document_id: Uuid,
+    pub short_code: Option<String>,
+}
+
 #[async_trait]
 pub trait SharesRepository: Send + Sync {
     async fn create_share(
@@ -22,14 +47,30 @@ pub trait SharesRepository: Send + Sync {
         document_id: Uuid,
         permission: &str,
         expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        short_code: Option<&str>,
     ) -> anyhow::Result<(String, Uuid, String)>; // (token_saved, share_id, document_type)
 
+    /// Creates every share in one transaction, materializing folder shares onto their
+    /// descendants. Bails, creating none, with "forbidden" unless `owner_id` owns all the
+    /// documents, or with [`ShareLimitReached`] when a document already has
+    /// `max_per_document` active shares (0 means no limit). Results follow the order of
+    /// `shares`.
+    async fn create_shares(
+        &self,
+        owner_id: Uuid,
+        shares: &[NewShare],
+        permission: &str,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        max_per_document: usize,
+    ) -> anyhow::Result<Vec<(String, Uuid, String)>>; // (token_saved, share_id, document_type)
+
     async fn list_document_shares(
         &self,
         owner_id: Uuid,
         document_id: Uuid,
     ) -> anyhow::Result<Vec<ShareRow>>;
 
+    /// Lookups by `token` below also accept a share's short code.
     async fn delete_share(&self, owner_id: Uuid, token: &str) -> anyhow::Result<bool>;
 
     async fn validate_share_token(
@@ -74,5 +115,30 @@ pub trait SharesRepository: Send + Sync {
 
     async fn list_materialized_children(&self, parent_share_id: Uuid) -> anyhow::Result<Vec<Uuid>>;
 
-    async fn materialize_folder_share(&self, owner_id: Uuid, token: &str) -> anyhow::Result<i64>;
+    /// Folder share behind `token`; errors with not_found/forbidden/bad_request.
+    async fn get_folder_share_for_owner(
+        &self,
+        owner_id: Uuid,
+        token: &str,
+    ) -> anyhow::Result<FolderShareGrant>;
+
+    /// Unexpired folder shares on any ancestor of `doc_id`, excluding materialized children.
+    async fn list_ancestor_folder_shares(
+        &self,
+        doc_id: Uuid,
+    ) -> anyhow::Result<Vec<FolderShareGrant>>;
+
+    /// Creates child shares of `grant` for `doc_ids` that don't have one yet; returns how many.
+    async fn insert_materialized_shares(
+        &self,
+        grant: &FolderShareGrant,
+        doc_ids: &[Uuid],
+    ) -> anyhow::Result<i64>;
+
+    /// Deletes shares that expired before `now`, with their materialized children; returns how
+    /// many top-level shares went.
+    async fn delete_expired_shares(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> anyhow::Result<u64>;
 }
@@ -10,6 +10,14 @@ pub struct StoredAttachment {
     pub content_hash: String,
 }
 
+/// Size and validators of a stored object, used for conditional and range requests.
+#[derive(Debug, Clone)]
+pub struct StoredObjectMeta {
+    pub size: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 #[async_trait]
 pub trait StoragePort: Send + Sync {
     async fn move_folder_subtree(&self, folder_id: Uuid) -> anyhow::Result<usize>;
@@ -23,6 +31,9 @@ pub trait StoragePort: Send + Sync {
     async fn sync_doc_paths(&self, doc_id: Uuid) -> anyhow::Result<()>;
     async fn resolve_upload_path(&self, doc_id: Uuid, rest_path: &str) -> anyhow::Result<PathBuf>;
     async fn read_bytes(&self, abs_path: &Path) -> anyhow::Result<Vec<u8>>;
+    async fn stat(&self, abs_path: &Path) -> anyhow::Result<StoredObjectMeta>;
+    /// Reads the inclusive byte range `start..=end`; callers clamp `end` to the object size.
+    async fn read_range(&self, abs_path: &Path, start: u64, end: u64) -> anyhow::Result<Vec<u8>>;
     async fn write_bytes(&self, abs_path: &Path, data: &[u8]) -> anyhow::Result<()>;
     async fn store_doc_attachment(
         &self,
@@ -30,4 +41,7 @@ pub trait StoragePort: Send + Sync {
         original_filename: Option<&str>,
         bytes: &[u8],
     ) -> anyhow::Result<StoredAttachment>;
+    /// Writes, reads back and deletes a small probe object so a misconfigured backend is
+    /// reported at startup rather than on the first upload.
+    async fn self_test(&self) -> anyhow::Result<()>;
 }
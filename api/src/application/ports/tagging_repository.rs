@@ -3,8 +3,12 @@ use uuid::Uuid;
 
 #[async_trait]
 pub trait TaggingRepository: Send + Sync {
-    async fn clear_document_tags(&self, doc_id: Uuid) -> anyhow::Result<()>;
-    async fn upsert_tag_return_id(&self, name: &str) -> anyhow::Result<i64>;
+    /// Names of the tags currently associated with `doc_id`.
+    async fn document_tag_names(&self, doc_id: Uuid) -> anyhow::Result<Vec<String>>;
+    /// Drops the associations between `doc_id` and the named tags in one statement.
+    async fn remove_document_tags(&self, doc_id: Uuid, names: &[String]) -> anyhow::Result<()>;
+    /// Upserts tags by name in one statement and returns their ids.
+    async fn upsert_tags_return_ids(&self, names: &[String]) -> anyhow::Result<Vec<i64>>;
     async fn owner_doc_exists(&self, doc_id: Uuid, owner_id: Uuid) -> anyhow::Result<bool>;
-    async fn associate_document_tag(&self, doc_id: Uuid, tag_id: i64) -> anyhow::Result<()>;
+    async fn associate_document_tags(&self, doc_id: Uuid, tag_ids: &[i64]) -> anyhow::Result<()>;
 }
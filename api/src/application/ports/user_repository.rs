@@ -19,5 +19,10 @@ pub trait UserRepository: Send + Sync {
     ) -> anyhow::Result<UserRow>;
     async fn find_by_email(&self, email: &str) -> anyhow::Result<Option<UserRow>>;
     async fn find_by_id(&self, id: Uuid) -> anyhow::Result<Option<UserRow>>;
+    /// Users whose email or name equals one of `keys`, ignoring case.
+    async fn find_by_names_or_emails(&self, keys: &[String]) -> anyhow::Result<Vec<UserRow>>;
     async fn delete_user(&self, id: Uuid) -> anyhow::Result<bool>;
+    async fn home_document(&self, user_id: Uuid) -> anyhow::Result<Option<Uuid>>;
+    /// `None` clears the home document.
+    async fn set_home_document(&self, user_id: Uuid, doc_id: Option<Uuid>) -> anyhow::Result<()>;
 }
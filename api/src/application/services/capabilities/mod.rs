@@ -0,0 +1,84 @@
+//! Optional features this server has turned on, so clients can hide what is unavailable.
+//!
+//! Derived from configuration only: nothing here may expose endpoints, credentials or
+//! other operator details beyond whether a feature is on.
+
+use crate::bootstrap::config::{Config, StorageBackend};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKind {
+    Filesystem,
+    S3,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerCapabilities {
+    pub storage: StorageKind,
+    /// Realtime state is shared between nodes through Redis.
+    pub cluster_mode: bool,
+    pub git_sync: bool,
+    /// Changes are committed to linked repositories in the background.
+    pub git_auto_sync: bool,
+    pub plugins: bool,
+    /// PDF export goes through weasyprint rather than the built-in writer.
+    pub weasyprint_pdf: bool,
+    pub public_analytics: bool,
+    /// Edit share links grant edit to anonymous visitors.
+    pub anonymous_edit: bool,
+    /// `None` when documents may have any number of share links.
+    pub max_shares_per_document: Option<usize>,
+    pub upload_max_bytes: usize,
+}
+
+impl ServerCapabilities {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            storage: match cfg.storage_backend {
+                StorageBackend::Filesystem => StorageKind::Filesystem,
+                StorageBackend::S3 => StorageKind::S3,
+            },
+            cluster_mode: cfg.cluster_mode,
+            git_sync: true,
+            git_auto_sync: cfg.git_auto_sync_interval_secs > 0,
+            plugins: true,
+            weasyprint_pdf: cfg!(feature = "weasyprint"),
+            public_analytics: cfg.public_analytics_enabled,
+            anonymous_edit: cfg.allow_anonymous_edit,
+            max_shares_per_document: (cfg.max_shares_per_document > 0)
+                .then_some(cfg.max_shares_per_document),
+            upload_max_bytes: cfg.upload_max_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_follow_the_configuration() {
+        // from_env defaults to production, which insists on real secrets.
+        // SAFETY: no other test reads or writes the process environment.
+        unsafe { std::env::set_var("RUST_ENV", "development") };
+        let mut cfg = Config::from_env().unwrap();
+        cfg.storage_backend = StorageBackend::Filesystem;
+        cfg.cluster_mode = false;
+        cfg.git_auto_sync_interval_secs = 0;
+        cfg.max_shares_per_document = 0;
+        let caps = ServerCapabilities::from_config(&cfg);
+        assert_eq!(caps.storage, StorageKind::Filesystem);
+        assert!(!caps.cluster_mode);
+        assert!(!caps.git_auto_sync);
+        assert_eq!(caps.max_shares_per_document, None);
+
+        cfg.storage_backend = StorageBackend::S3;
+        cfg.cluster_mode = true;
+        cfg.git_auto_sync_interval_secs = 60;
+        cfg.max_shares_per_document = 10;
+        let caps = ServerCapabilities::from_config(&cfg);
+        assert_eq!(caps.storage, StorageKind::S3);
+        assert!(caps.cluster_mode);
+        assert!(caps.git_auto_sync);
+        assert_eq!(caps.max_shares_per_document, Some(10));
+    }
+}
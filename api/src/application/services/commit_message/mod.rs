@@ -0,0 +1,142 @@
+//! Commit messages for workspace syncs.
+//!
+//! A per-user template may use `{count}` (changed files), `{date}` (UTC, `YYYY-MM-DD`) and
+//! `{files}` (changed paths, comma separated). An explicit message always wins over the
+//! template; the change summary body is appended to either when enabled.
+
+use chrono::{DateTime, Utc};
+
+use crate::application::dto::git::GitChangeItem;
+use crate::application::ports::git_repository::UserGitCfg;
+
+pub const DEFAULT_COMMIT_MESSAGE: &str = "RefMD sync";
+/// Paths listed by `{files}` before the rest are summarized as "+N more".
+const MAX_LISTED_FILES: usize = 10;
+
+pub fn compose(
+    explicit: Option<&str>,
+    cfg: Option<&UserGitCfg>,
+    changes: &[GitChangeItem],
+    now: DateTime<Utc>,
+) -> String {
+    let template = cfg
+        .and_then(|c| c.commit_message_template.as_deref())
+        .filter(|t| !t.trim().is_empty());
+    let subject = match (explicit.filter(|m| !m.trim().is_empty()), template) {
+        (Some(message), _) => message.to_string(),
+        (None, Some(template)) => expand(template, changes, now),
+        (None, None) => DEFAULT_COMMIT_MESSAGE.to_string(),
+    };
+    if cfg.is_some_and(|c| c.append_change_summary) && !changes.is_empty() {
+        format!("{}\n\n{}", subject.trim_end(), summary(changes))
+    } else {
+        subject
+    }
+}
+
+fn expand(template: &str, changes: &[GitChangeItem], now: DateTime<Utc>) -> String {
+    template
+        .replace("{count}", &changes.len().to_string())
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{files}", &file_list(changes))
+}
+
+fn file_list(changes: &[GitChangeItem]) -> String {
+    let mut list = changes
+        .iter()
+        .take(MAX_LISTED_FILES)
+        .map(|c| c.path.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if changes.len() > MAX_LISTED_FILES {
+        list.push_str(&format!(" (+{} more)", changes.len() - MAX_LISTED_FILES));
+    }
+    list
+}
+
+/// One `A`/`M`/`D` line per changed path, like `git status --short`.
+fn summary(changes: &[GitChangeItem]) -> String {
+    changes
+        .iter()
+        .map(|c| {
+            let flag = match c.status.as_str() {
+                "untracked" => "A",
+                "deleted" => "D",
+                _ => "M",
+            };
+            format!("{} {}", flag, c.path)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn cfg(template: Option<&str>, append_change_summary: bool) -> UserGitCfg {
+        UserGitCfg {
+            repository_url: String::new(),
+            branch_name: "main".into(),
+            auth_type: None,
+            auth_data: None,
+            auto_sync: false,
+            commit_message_template: template.map(str::to_string),
+            append_change_summary,
+        }
+    }
+
+    fn changes(n: usize) -> Vec<GitChangeItem> {
+        (0..n)
+            .map(|i| GitChangeItem {
+                path: format!("notes/{}.md", i),
+                status: if i == 0 { "untracked" } else { "modified" }.into(),
+            })
+            .collect()
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 10, 19, 8, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn template_expands_placeholders() {
+        let cfg = cfg(Some("notes: {count} file(s) on {date} [{files}]"), false);
+        assert_eq!(
+            compose(None, Some(&cfg), &changes(2), now()),
+            "notes: 2 file(s) on 2025-10-19 [notes/0.md, notes/1.md]"
+        );
+        let many = compose(Some(" "), Some(&cfg), &changes(12), now());
+        assert!(many.starts_with("notes: 12 file(s) on 2025-10-19 [notes/0.md,"));
+        assert!(many.ends_with("notes/9.md (+2 more)]"));
+    }
+
+    #[test]
+    fn explicit_message_overrides_template() {
+        let cfg = cfg(Some("{count} changes"), false);
+        assert_eq!(
+            compose(Some("Fix typos"), Some(&cfg), &changes(3), now()),
+            "Fix typos"
+        );
+        assert_eq!(
+            compose(None, None, &changes(3), now()),
+            DEFAULT_COMMIT_MESSAGE
+        );
+    }
+
+    #[test]
+    fn appends_change_summary_when_enabled() {
+        let cfg = cfg(None, true);
+        let mut items = changes(2);
+        items.push(GitChangeItem {
+            path: "old.md".into(),
+            status: "deleted".into(),
+        });
+        assert_eq!(
+            compose(Some("Sync"), Some(&cfg), &items, now()),
+            "Sync\n\nA notes/0.md\nM notes/1.md\nD old.md"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use similar::{Algorithm, ChangeTag, TextDiff};
+
+use crate::application::dto::git::{DiffLine, DiffLineType, DiffResult};
+
+/// Line diff between two text revisions. When either side is missing (added or deleted file)
+/// no lines are produced and only the available content is returned.
+pub fn build_diff_result(
+    path: &str,
+    old_content: Option<&str>,
+    new_content: Option<&str>,
+) -> DiffResult {
+    match (old_content, new_content) {
+        (Some(old), Some(new)) => {
+            let diff = TextDiff::configure()
+                .algorithm(Algorithm::Myers)
+                .diff_lines(old, new);
+            let mut lines = Vec::new();
+            let mut old_line = 0u32;
+            let mut new_line = 0u32;
+            for op in diff.ops() {
+                for change in diff.iter_changes(op) {
+                    match change.tag() {
+                        ChangeTag::Delete => {
+                            old_line += 1;
+                            lines.push(DiffLine {
+                                line_type: DiffLineType::Deleted,
+                                old_line_number: Some(old_line),
+                                new_line_number: None,
+                                content: change.to_string().trim_end().to_string(),
+                            });
+                        }
+                        ChangeTag::Insert => {
+                            new_line += 1;
+                            lines.push(DiffLine {
+                                line_type: DiffLineType::Added,
+                                old_line_number: None,
+                                new_line_number: Some(new_line),
+                                content: change.to_string().trim_end().to_string(),
+                            });
+                        }
+                        ChangeTag::Equal => {
+                            old_line += 1;
+                            new_line += 1;
+                            lines.push(DiffLine {
+                                line_type: DiffLineType::Context,
+                                old_line_number: Some(old_line),
+                                new_line_number: Some(new_line),
+                                content: change.to_string().trim_end().to_string(),
+                            });
+                        }
+                    }
+                }
+            }
+            DiffResult {
+                file_path: path.to_string(),
+                diff_lines: lines,
+                old_content: Some(old.to_string()),
+                new_content: Some(new.to_string()),
+            }
+        }
+        _ => DiffResult {
+            file_path: path.to_string(),
+            diff_lines: Vec::new(),
+            old_content: old_content.map(|s| s.to_string()),
+            new_content: new_content.map(|s| s.to_string()),
+        },
+    }
+}
+
+/// Attributes every line of the last revision to the revision that introduced it. Revisions
+/// run oldest to newest; lines already present in the first one are attributed to it.
+pub fn blame_lines(revisions: &[&str]) -> Vec<usize> {
+    let mut owners: Vec<usize> = Vec::new();
+    let mut previous = "";
+    for (index, revision) in revisions.iter().enumerate() {
+        let diff = TextDiff::configure()
+            .algorithm(Algorithm::Myers)
+            .diff_lines(previous, revision);
+        let mut next = Vec::with_capacity(owners.len());
+        for change in diff.iter_all_changes() {
+            match change.tag() {
+                ChangeTag::Equal => next.push(change.old_index().map_or(index, |i| owners[i])),
+                ChangeTag::Insert => next.push(index),
+                ChangeTag::Delete => {}
+            }
+        }
+        owners = next;
+        previous = revision;
+    }
+    owners
+}
@@ -0,0 +1,85 @@
+/// Leading YAML (`---`) or TOML (`+++`) front matter block of a markdown document.
+pub struct FrontMatter<'a> {
+    separator: char,
+    lines: Vec<&'a str>,
+    body: &'a str,
+}
+
+impl<'a> FrontMatter<'a> {
+    /// Returns `None` unless the content opens with a delimiter line that is later closed.
+    pub fn parse(content: &'a str) -> Option<Self> {
+        let mut lines = content.split_inclusive('\n');
+        let first = lines.next()?;
+        let delim = match first.trim_end() {
+            d @ ("---" | "+++") => d,
+            _ => return None,
+        };
+        let mut offset = first.len();
+        let mut block = Vec::new();
+        for line in lines {
+            offset += line.len();
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.trim_end() == delim {
+                return Some(Self {
+                    separator: if delim == "+++" { '=' } else { ':' },
+                    lines: block,
+                    body: &content[offset..],
+                });
+            }
+            block.push(line);
+        }
+        None
+    }
+
+    /// Content following the closing delimiter.
+    pub fn body(&self) -> &'a str {
+        self.body
+    }
+
+    /// Top-level scalar value of `key`, unquoted.
+    pub fn scalar(&self, key: &str) -> Option<String> {
+        let (_, value) = self.find(key)?;
+        let value = unquote(value);
+        (!value.is_empty()).then(|| value.to_string())
+    }
+
+    /// List value of `key`: an inline array (`[a, "b"]`), a comma-separated string,
+    /// or a YAML block list.
+    pub fn list(&self, key: &str) -> Vec<String> {
+        let Some((index, value)) = self.find(key) else {
+            return Vec::new();
+        };
+        let value = value.trim();
+        if !value.is_empty() {
+            let value = value
+                .strip_prefix('[')
+                .and_then(|v| v.strip_suffix(']'))
+                .unwrap_or(unquote(value));
+            return value.split(',').map(|t| unquote(t).to_string()).collect();
+        }
+        self.lines[index + 1..]
+            .iter()
+            .map_while(|l| l.trim_start().strip_prefix('-'))
+            .map(|item| unquote(item).to_string())
+            .collect()
+    }
+
+    fn find(&self, key: &str) -> Option<(usize, &'a str)> {
+        self.lines.iter().enumerate().find_map(|(i, line)| {
+            if line.starts_with(char::is_whitespace) {
+                return None;
+            }
+            let (k, v) = line.split_once(self.separator)?;
+            k.trim().eq_ignore_ascii_case(key).then_some((i, v))
+        })
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
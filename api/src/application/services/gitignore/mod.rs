@@ -0,0 +1,130 @@
+//! Matching of repo-relative paths against the user's `.gitignore` patterns.
+//!
+//! Supports the subset of gitignore syntax the API writes and users commonly add: `*`, `**`
+//! and `?` wildcards, trailing `/` for directories, leading or inner `/` to anchor at the repo
+//! root, and `!` to re-include. The last matching pattern wins.
+
+struct Rule {
+    glob: Vec<char>,
+    anchored: bool,
+    dir_only: bool,
+    negate: bool,
+}
+
+impl Rule {
+    fn parse(raw: &str) -> Option<Self> {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negate, body) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let dir_only = body.ends_with('/');
+        let body = body.trim_end_matches('/');
+        let anchored = body.contains('/');
+        let body = body.trim_start_matches('/');
+        if body.is_empty() {
+            return None;
+        }
+        Some(Self {
+            glob: body.chars().collect(),
+            anchored,
+            dir_only,
+            negate,
+        })
+    }
+
+    fn matches(&self, segments: &[&str]) -> bool {
+        for i in 0..segments.len() {
+            // Paths are files, so only their ancestors can satisfy a directory pattern.
+            if self.dir_only && i + 1 == segments.len() {
+                break;
+            }
+            let candidate: Vec<char> = if self.anchored {
+                segments[..=i].join("/").chars().collect()
+            } else {
+                segments[i].chars().collect()
+            };
+            if glob_match(&self.glob, &candidate) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+pub struct GitignoreMatcher {
+    rules: Vec<Rule>,
+}
+
+impl GitignoreMatcher {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        Self {
+            rules: patterns
+                .iter()
+                .filter_map(|p| Rule::parse(p.as_ref()))
+                .collect(),
+        }
+    }
+
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let path = path.trim_start_matches('/');
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.is_empty() {
+            return false;
+        }
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(&segments))
+            .is_some_and(|rule| !rule.negate)
+    }
+}
+
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) if rest.first() == Some(&'*') => {
+            let rest = rest[1..].strip_prefix(&['/']).unwrap_or(&rest[1..]);
+            (0..=text.len()).any(|i| glob_match(rest, &text[i..]))
+        }
+        Some(('*', rest)) => {
+            let limit = text.iter().position(|c| *c == '/').unwrap_or(text.len());
+            (0..=limit).any(|i| glob_match(rest, &text[i..]))
+        }
+        Some(('?', rest)) => {
+            matches!(text.first(), Some(c) if *c != '/') && glob_match(rest, &text[1..])
+        }
+        Some((c, rest)) => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_files_and_directories() {
+        let m = GitignoreMatcher::new(&["notes/todo.md", "drafts/", "*.md.tmp"]);
+        assert!(m.is_ignored("notes/todo.md"));
+        assert!(!m.is_ignored("notes/todo.md.bak"));
+        assert!(m.is_ignored("drafts/a.md"));
+        assert!(m.is_ignored("work/drafts/deep/b.png"));
+        assert!(!m.is_ignored("drafts-old/a.md"));
+        assert!(!m.is_ignored("drafts"));
+        assert!(m.is_ignored("x/y/z.md.tmp"));
+    }
+
+    #[test]
+    fn anchoring_wildcards_and_negation() {
+        let m = GitignoreMatcher::new(&["/top.md", "assets/**/*.png", "*.md", "!keep.md"]);
+        assert!(m.is_ignored("top.md"));
+        assert!(m.is_ignored("assets/a/b/c.png"));
+        assert!(m.is_ignored("assets/c.png"));
+        assert!(!m.is_ignored("other/assets/c.png"));
+        assert!(m.is_ignored("dir/readme.md"));
+        assert!(!m.is_ignored("dir/keep.md"));
+    }
+}
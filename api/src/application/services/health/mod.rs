@@ -0,0 +1,128 @@
+//! Readiness probes for the subsystems the API depends on.
+
+use std::time::Duration;
+
+use crate::application::ports::plugin_asset_store::PluginAssetStore;
+
+/// A probe slower than this counts as a failure rather than stalling the health check.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyHealth {
+    pub name: &'static str,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+impl DependencyHealth {
+    fn from_result(name: &'static str, result: anyhow::Result<()>) -> Self {
+        match result {
+            Ok(()) => Self {
+                name,
+                ok: true,
+                error: None,
+            },
+            Err(err) => Self {
+                name,
+                ok: false,
+                error: Some(err.to_string()),
+            },
+        }
+    }
+}
+
+/// "ok" when every dependency is healthy, otherwise "degraded".
+pub fn overall_status(dependencies: &[DependencyHealth]) -> &'static str {
+    if dependencies.iter().all(|d| d.ok) {
+        "ok"
+    } else {
+        "degraded"
+    }
+}
+
+pub async fn probe_database(pool: &sqlx::PgPool) -> DependencyHealth {
+    let result = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        sqlx::query_scalar::<_, i32>("SELECT 1").fetch_one(pool),
+    )
+    .await;
+    let result = match result {
+        Ok(res) => res.map(|_| ()).map_err(anyhow::Error::from),
+        Err(_) => Err(anyhow::anyhow!("timed out")),
+    };
+    DependencyHealth::from_result("database", result)
+}
+
+/// Lists global plugin manifests, which reads the plugin directory (or syncs the S3 prefix),
+/// the same path the manifest endpoint depends on.
+pub async fn probe_plugin_store(store: &dyn PluginAssetStore) -> DependencyHealth {
+    let result =
+        match tokio::time::timeout(PROBE_TIMEOUT, store.list_latest_global_manifests()).await {
+            Ok(res) => res.map(|_| ()),
+            Err(_) => Err(anyhow::anyhow!("timed out")),
+        };
+    DependencyHealth::from_result("plugin_store", result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use async_trait::async_trait;
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    use super::*;
+
+    struct UnreadableStore;
+
+    #[async_trait]
+    impl PluginAssetStore for UnreadableStore {
+        fn global_root(&self) -> PathBuf {
+            PathBuf::from("/plugins/global")
+        }
+        fn user_root(&self, _: &Uuid) -> PathBuf {
+            unimplemented!()
+        }
+        fn latest_version_dir(&self, _: &Path) -> anyhow::Result<Option<PathBuf>> {
+            unimplemented!()
+        }
+        fn user_plugin_manifest_path(&self, _: &Uuid, _: &str, _: &str) -> PathBuf {
+            unimplemented!()
+        }
+        fn global_plugin_manifest_path(&self, _: &str, _: &str) -> PathBuf {
+            unimplemented!()
+        }
+        fn remove_user_plugin_dir(&self, _: &Uuid, _: &str) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn latest_user_version(&self, _: &Uuid, _: &str) -> anyhow::Result<Option<String>> {
+            unimplemented!()
+        }
+        async fn list_latest_global_manifests(
+            &self,
+        ) -> anyhow::Result<Vec<(String, String, Value)>> {
+            Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied).into())
+        }
+        async fn load_user_manifest(
+            &self,
+            _: &Uuid,
+            _: &str,
+            _: &str,
+        ) -> anyhow::Result<Option<Value>> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn an_unreadable_plugin_store_degrades_health() {
+        let plugins = probe_plugin_store(&UnreadableStore).await;
+        assert_eq!(plugins.name, "plugin_store");
+        assert!(!plugins.ok);
+        assert!(plugins.error.is_some());
+
+        let database = DependencyHealth::from_result("database", Ok(()));
+        assert_eq!(overall_status(std::slice::from_ref(&database)), "ok");
+        assert_eq!(overall_status(&[database, plugins]), "degraded");
+    }
+}
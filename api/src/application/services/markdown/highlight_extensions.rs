@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::OnceCell;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::{SyntaxDefinition, SyntaxSet};
+
+/// Syntaxes and themes an operator dropped into `HIGHLIGHT_ASSETS_DIR`, on top of the bundled
+/// highlighting assets.
+#[derive(Debug, Default)]
+pub struct HighlightExtensions {
+    /// Bundled syntaxes merged with the extra ones; `None` when no extra syntax loaded.
+    syntaxes: Option<SyntaxSet>,
+    /// Extra themes by file stem, e.g. `Solarized.tmTheme` as "Solarized".
+    themes: BTreeMap<String, Theme>,
+}
+
+static INSTALLED: OnceCell<HighlightExtensions> = OnceCell::new();
+
+/// Extensions installed at startup, if any.
+pub fn installed() -> Option<&'static HighlightExtensions> {
+    INSTALLED.get()
+}
+
+/// Loads `dir` and makes it available to code block highlighting. Only the first call has an
+/// effect.
+pub fn install(dir: &Path, bundled: &SyntaxSet) {
+    let extensions = HighlightExtensions::load(dir, bundled);
+    if INSTALLED.set(extensions).is_err() {
+        tracing::warn!(dir = %dir.display(), "highlight_extensions_already_installed");
+    }
+}
+
+impl HighlightExtensions {
+    /// Reads every `.sublime-syntax` and `.tmTheme` file directly inside `dir`. Files that fail
+    /// to parse are logged and skipped so a bad file cannot keep the server from starting.
+    pub fn load(dir: &Path, bundled: &SyntaxSet) -> Self {
+        let mut files: Vec<PathBuf> = match std::fs::read_dir(dir) {
+            Ok(entries) => entries.filter_map(|e| e.ok().map(|e| e.path())).collect(),
+            Err(e) => {
+                tracing::warn!(dir = %dir.display(), error = %e, "highlight_extensions_dir_unreadable");
+                return Self::default();
+            }
+        };
+        files.sort();
+
+        let mut definitions = Vec::new();
+        let mut themes = BTreeMap::new();
+        for path in files {
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("sublime-syntax") => match load_syntax(&path) {
+                    Ok(def) => definitions.push(def),
+                    Err(e) => {
+                        tracing::warn!(path = %path.display(), error = %e, "highlight_syntax_load_failed")
+                    }
+                },
+                Some("tmTheme") => {
+                    let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                        continue;
+                    };
+                    match ThemeSet::get_theme(&path) {
+                        Ok(theme) => {
+                            themes.insert(name.to_string(), theme);
+                        }
+                        Err(e) => {
+                            tracing::warn!(path = %path.display(), error = %e, "highlight_theme_load_failed")
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let syntax_count = definitions.len();
+        let syntaxes = (!definitions.is_empty()).then(|| {
+            let mut builder = bundled.clone().into_builder();
+            for def in definitions {
+                builder.add(def);
+            }
+            builder.build()
+        });
+        tracing::info!(
+            dir = %dir.display(),
+            syntaxes = syntax_count,
+            themes = themes.len(),
+            "highlight_extensions_loaded"
+        );
+        Self { syntaxes, themes }
+    }
+
+    pub fn syntax_set(&self) -> Option<&SyntaxSet> {
+        self.syntaxes.as_ref()
+    }
+
+    pub fn theme(&self, name: &str) -> Option<&Theme> {
+        self.themes.get(name)
+    }
+}
+
+fn load_syntax(path: &Path) -> anyhow::Result<SyntaxDefinition> {
+    let source = std::fs::read_to_string(path)?;
+    let fallback_name = path.file_stem().and_then(|s| s.to_str());
+    Ok(SyntaxDefinition::load_from_str(
+        &source,
+        true,
+        fallback_name,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::services::markdown::highlight_with;
+
+    const SYNTAX: &str = r#"%YAML 1.2
+---
+name: Frob
+file_extensions: [frob]
+scope: source.frob
+contexts:
+  main:
+    - match: '\bfrobnicate\b'
+      scope: keyword.control.frob
+"#;
+
+    #[test]
+    fn custom_syntax_from_directory_highlights_snippet() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Frob.sublime-syntax"), SYNTAX).unwrap();
+        std::fs::write(dir.path().join("Broken.sublime-syntax"), "contexts: [").unwrap();
+        std::fs::write(dir.path().join("Broken.tmTheme"), "not a plist").unwrap();
+
+        let code = "frobnicate widgets\n";
+        let plain = highlight_with(None, code, "frob", "InspiredGitHub");
+        assert!(!plain.contains("frobnicate</span>"));
+
+        let bundled = SyntaxSet::load_defaults_newlines();
+        let extensions = HighlightExtensions::load(dir.path(), &bundled);
+        let ss = extensions.syntax_set().expect("custom syntax merged");
+        assert!(ss.find_syntax_by_token("frob").is_some());
+        assert!(ss.find_syntax_by_token("rust").is_some());
+        assert!(extensions.theme("Broken").is_none());
+
+        let html = highlight_with(Some(&extensions), code, "frob", "InspiredGitHub");
+        assert!(html.contains("frobnicate</span>"), "{}", html);
+        assert_ne!(html, plain);
+    }
+}
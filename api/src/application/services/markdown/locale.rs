@@ -0,0 +1,51 @@
+//! Labels the renderer writes into generated pages, looked up by locale.
+//!
+//! Locales are matched on their language (`de-AT` uses `de`), ignoring case; anything not in
+//! the catalog falls back to English.
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    /// Heading of the table of contents in multi-document renders.
+    TableOfContents,
+}
+
+/// `(language, table of contents)`; the first entry is the fallback.
+const CATALOG: &[(&str, &str)] = &[
+    ("en", "Contents"),
+    ("de", "Inhalt"),
+    ("es", "Contenido"),
+    ("fr", "Sommaire"),
+    ("ja", "目次"),
+    ("zh", "目录"),
+];
+
+pub fn message(locale: Option<&str>, message: Message) -> &'static str {
+    let language = locale
+        .and_then(|l| l.trim().split(['-', '_']).next())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    let (_, contents) = CATALOG
+        .iter()
+        .find(|(lang, _)| *lang == language)
+        .unwrap_or(&CATALOG[0]);
+    match message {
+        Message::TableOfContents => contents,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_selects_the_label_and_falls_back_to_english() {
+        assert_eq!(message(None, Message::TableOfContents), "Contents");
+        assert_eq!(message(Some("de"), Message::TableOfContents), "Inhalt");
+        assert_eq!(message(Some("de-AT"), Message::TableOfContents), "Inhalt");
+        assert_eq!(message(Some("FR_ca"), Message::TableOfContents), "Sommaire");
+        assert_eq!(message(Some("xx"), Message::TableOfContents), "Contents");
+        assert_eq!(message(Some(""), Message::TableOfContents), "Contents");
+    }
+}
@@ -1,23 +1,119 @@
+pub mod highlight_extensions;
+pub mod locale;
+
 use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
-use std::sync::Mutex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::application::linkgraph::{split_block_marker, title_key};
 
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 #[serde(default)]
 pub struct RenderOptions {
     pub flavor: Option<String>,
     pub theme: Option<String>,
+    /// Optional extras such as `gfm`, `highlight` or `stats`. `gfm` enables tables,
+    /// strikethrough, task lists and autolinks together; `table`, `strikethrough`, `tasklist`
+    /// and `autolink` enable them one at a time.
     pub features: Option<Vec<String>>,
     pub sanitize: Option<bool>,
+    /// If false, raw HTML in the source is escaped and shown as text instead of being parsed
+    /// (and then sanitized). Defaults to true.
+    pub allow_raw_html: Option<bool>,
+    /// Turn bare URLs into links. Defaults to on with the gfm feature, off otherwise.
+    pub autolink: Option<bool>,
+    /// Curly quotes, en/em dashes and ellipses from their ASCII forms. Defaults to false.
+    pub smart_punctuation: Option<bool>,
+    /// Language of labels the renderer adds (e.g. `de`, `fr-CA`). Defaults to English.
+    pub locale: Option<String>,
     /// If provided, rewrite attachment-relative links/images to absolute under /uploads/{doc_id}
     pub doc_id: Option<uuid::Uuid>,
     /// If provided, prefix absolute URLs with this origin (e.g., https://api.example.com)
     pub base_origin: Option<String>,
+    /// Origin for attachment and image URLs (e.g. a CDN); falls back to `base_origin`.
+    pub asset_base: Option<String>,
     /// If true, rewrite attachment URLs (./attachments/, attachments/, /uploads/)
     pub absolute_attachments: Option<bool>,
     /// Optional share token to append as query (?token=...)
     pub token: Option<String>,
+    /// Documents that bracket wikilinks resolve to, keyed by `title_key` of the link target.
+    /// Filled server-side before rendering; never accepted from requests.
+    #[serde(skip_deserializing, skip_serializing_if = "BTreeMap::is_empty")]
+    pub wiki_links: BTreeMap<String, uuid::Uuid>,
+    /// Work limits for this render; `None` uses `RenderBudget::default()`. Set from instance
+    /// configuration only.
+    #[serde(skip)]
+    pub budget: Option<RenderBudget>,
+}
+
+/// Caps on the work one render may do, so a pathological document cannot hold a worker.
+/// Past a cap the render still succeeds but is flagged `truncated`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderBudget {
+    /// Parsed nodes kept; top-level blocks past the cap are dropped.
+    pub max_nodes: usize,
+    /// Code blocks that get syntax highlighting; later ones render as plain code.
+    pub max_highlighted_blocks: usize,
+    /// HTML emitted before sanitizing; top-level blocks that would overflow it are dropped.
+    pub max_html_bytes: usize,
+}
+
+impl Default for RenderBudget {
+    fn default() -> Self {
+        Self {
+            max_nodes: 200_000,
+            max_highlighted_blocks: 500,
+            max_html_bytes: 8 * 1024 * 1024,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Fills unset style options (flavor, theme, features, sanitize, allow_raw_html, autolink,
+    /// smart_punctuation, locale, asset_base) from instance defaults; values set on `self` always win.
+    pub fn with_defaults(mut self, defaults: &RenderOptions) -> Self {
+        if self.flavor.is_none() {
+            self.flavor = defaults.flavor.clone();
+        }
+        if self.theme.as_deref().is_none_or(str::is_empty) {
+            self.theme = defaults.theme.clone();
+        }
+        if self.features.is_none() {
+            self.features = defaults.features.clone();
+        }
+        if self.sanitize.is_none() {
+            self.sanitize = defaults.sanitize;
+        }
+        if self.allow_raw_html.is_none() {
+            self.allow_raw_html = defaults.allow_raw_html;
+        }
+        if self.autolink.is_none() {
+            self.autolink = defaults.autolink;
+        }
+        if self.smart_punctuation.is_none() {
+            self.smart_punctuation = defaults.smart_punctuation;
+        }
+        if self.locale.as_deref().is_none_or(str::is_empty) {
+            self.locale = defaults.locale.clone();
+        }
+        if self.asset_base.as_deref().is_none_or(str::is_empty) {
+            self.asset_base = defaults.asset_base.clone();
+        }
+        if self.budget.is_none() {
+            self.budget = defaults.budget;
+        }
+        self
+    }
+
+    /// Options for rendering the stored document `doc_id`: attachments resolve against that
+    /// document and become absolute unless the caller turned that off.
+    pub fn for_document(mut self, doc_id: uuid::Uuid) -> Self {
+        self.doc_id = Some(doc_id);
+        self.absolute_attachments.get_or_insert(true);
+        self
+    }
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -33,6 +129,15 @@ pub struct RenderResponse {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub placeholders: Vec<PlaceholderItem>,
     pub hash: String,
+    /// Task list items, when the `stats` feature is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks_total: Option<usize>,
+    /// Checked task list items, when the `stats` feature is requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks_completed: Option<usize>,
+    /// The render budget ran out: trailing blocks were dropped or code blocks were left
+    /// unhighlighted.
+    pub truncated: bool,
 }
 
 fn wants_feature(opts: &RenderOptions, name: &str) -> bool {
@@ -57,6 +162,79 @@ fn sha256_hex(s: &str) -> String {
     format!("{:x}", out)
 }
 
+/// Placeholder id derived from the block's kind and code, so a diagram keeps its id when other
+/// content moves around it. Repeats of identical blocks within one render get `-2`, `-3`, ...
+fn placeholder_id(seen: &mut HashMap<String, usize>, kind: &str, code: &str) -> String {
+    let base = format!("p{}", &sha256_hex(&format!("{kind}\n{code}"))[..12]);
+    let count = seen.entry(base.clone()).or_default();
+    *count += 1;
+    match *count {
+        1 => base,
+        n => format!("{base}-{n}"),
+    }
+}
+
+/// Characters dropped from heading slugs; matches comrak's `header_ids` anchorizer.
+static HEADING_SLUG_REJECTED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[^\p{L}\p{M}\p{N}\p{Pc} -]").unwrap());
+
+/// The id the renderer gives a heading: lowercased, punctuation dropped, spaces as `-`.
+/// (Repeated headings get `-1`, `-2`, ... suffixes in the rendered page.)
+pub fn heading_slug(heading: &str) -> String {
+    let lower = heading.trim().to_lowercase();
+    HEADING_SLUG_REJECTED
+        .replace_all(&lower, "")
+        .replace(' ', "-")
+}
+
+/// The id of the element the renderer emits for a `^blockid` marker.
+pub fn block_anchor(block_id: &str) -> String {
+    format!("block-{}", block_id)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WikiAnchor<'a> {
+    Heading(&'a str),
+    Block(&'a str),
+}
+
+/// Splits a wikilink target into the document part and an optional `#heading` or `^block`.
+fn split_wikilink_target(target: &str) -> (&str, Option<WikiAnchor<'_>>) {
+    let Some(pos) = target.find(['#', '^']) else {
+        return (target.trim(), None);
+    };
+    let (doc, rest) = (target[..pos].trim(), target[pos + 1..].trim());
+    if rest.is_empty() {
+        return (doc, None);
+    }
+    let anchor = if target[pos..].starts_with('#') {
+        WikiAnchor::Heading(rest)
+    } else {
+        WikiAnchor::Block(rest)
+    };
+    (doc, Some(anchor))
+}
+
+/// Href of a bracket wikilink: the resolved document, anchored at the heading for
+/// `[[Doc#Heading]]` or at the block for `[[Doc^id]]` when that block is known;
+/// unresolved targets keep the `#wiki:` form the client hydrates.
+fn wikilink_href(target: &str, links: &BTreeMap<String, uuid::Uuid>) -> String {
+    let (doc, anchor) = split_wikilink_target(target);
+    let key = title_key(doc);
+    let Some(id) = links.get(&key) else {
+        return format!("#wiki:{}", target);
+    };
+    match anchor {
+        Some(WikiAnchor::Heading(heading)) => {
+            format!("/document/{}#{}", id, heading_slug(heading))
+        }
+        Some(WikiAnchor::Block(block)) if links.contains_key(&format!("{}^{}", key, block)) => {
+            format!("/document/{}#{}", id, block_anchor(block))
+        }
+        _ => format!("/document/{}", id),
+    }
+}
+
 fn normalize_wikilink_label(raw: &str) -> (String, bool) {
     let mut label = raw.trim().to_string();
     if label.is_empty() {
@@ -64,12 +242,12 @@ fn normalize_wikilink_label(raw: &str) -> (String, bool) {
     }
     let mut inline = false;
     let mut lower = label.to_lowercase();
-    if let Some(pos) = lower.rfind("|inline") {
-        if lower[pos..].trim() == "|inline" {
-            label = label[..pos].trim_end().to_string();
-            inline = true;
-            lower = label.to_lowercase();
-        }
+    if let Some(pos) = lower.rfind("|inline")
+        && lower[pos..].trim() == "|inline"
+    {
+        label = label[..pos].trim_end().to_string();
+        inline = true;
+        lower = label.to_lowercase();
     }
     if label.starts_with('#') {
         label = label.trim_start_matches('#').to_string();
@@ -88,33 +266,48 @@ pub fn render(
 ) -> anyhow::Result<RenderResponse> {
     // Build comrak options (GFM-like)
     let mut c_opts = comrak::ComrakOptions::default();
-    c_opts.parse.smart = false;
-    if wants_feature(&opts, "gfm") {
-        c_opts.extension.table = true;
-        c_opts.extension.autolink = true;
-        c_opts.extension.strikethrough = true;
-        c_opts.extension.tasklist = true;
+    // Code spans and blocks are left alone by the parser either way
+    c_opts.parse.smart = opts.smart_punctuation.unwrap_or(false);
+    // `gfm` turns on the whole set; each extension can also be enabled on its own by name
+    let gfm = wants_feature(&opts, "gfm");
+    let gfm_extension = |name: &str| gfm || wants_feature(&opts, name);
+    c_opts.extension.table = gfm_extension("table");
+    c_opts.extension.strikethrough = gfm_extension("strikethrough");
+    c_opts.extension.tasklist = gfm_extension("tasklist");
+    if gfm {
         c_opts.extension.superscript = false;
         c_opts.extension.tagfilter = false;
         c_opts.render.github_pre_lang = true;
     }
+    c_opts.extension.autolink = opts.autolink.unwrap_or_else(|| gfm_extension("autolink"));
+    // Heading ids, so `[[Doc#Heading]]` links have something to land on
+    c_opts.extension.header_ids = Some(String::new());
     // Provide data-sourcepos for editor<->preview sync
     c_opts.render.sourcepos = true;
-    // Allow HtmlBlock/HtmlInline to pass through; will be sanitized by ammonia afterwards
+    // Allow HtmlBlock/HtmlInline to pass through; will be sanitized by ammonia afterwards.
+    // This also carries the renderer's own markup (highlighting, placeholders, wikilinks), so
+    // disallowed source HTML is turned into text below rather than switching this off.
     c_opts.render.unsafe_ = true;
 
     // Parse AST
     use comrak::nodes::AstNode;
     let arena = comrak::Arena::new();
     let root = comrak::parse_document(&arena, &text, &c_opts);
+    let budget = opts.budget.unwrap_or_default();
+    let mut truncated = drop_blocks_past_node_budget(root, budget.max_nodes);
+    if !opts.allow_raw_html.unwrap_or(true) {
+        escape_raw_html(&arena, root);
+    }
+    let task_counts = wants_feature(&opts, "stats").then(|| count_tasks(root));
 
     // Transform: capture code fences, highlight code blocks, and inline tag links
     let mut placeholders: Vec<PlaceholderItem> = Vec::new();
-    let mut counter: usize = 0;
+    let mut placeholder_ids: HashMap<String, usize> = HashMap::new();
 
     fn process_text_node<'a>(
         arena: &'a comrak::Arena<comrak::nodes::AstNode<'a>>,
         node: &'a AstNode<'a>,
+        wiki_links: &BTreeMap<String, uuid::Uuid>,
     ) {
         use comrak::nodes::{Ast, LineColumn, NodeValue};
         let value = node.data.borrow().value.clone();
@@ -161,7 +354,7 @@ pub fn render(
                             let link_node = arena.alloc(comrak::nodes::AstNode::new(
                                 std::cell::RefCell::new(Ast::new(
                                     NodeValue::Link(NodeLink {
-                                        url: format!("#wiki:{}", target),
+                                        url: wikilink_href(target, wiki_links),
                                         title: String::new(),
                                     }),
                                     LineColumn { line: 1, column: 1 },
@@ -204,10 +397,10 @@ pub fn render(
                     let prev_char = if start == 0 {
                         None
                     } else {
-                        s[..start].chars().rev().next()
+                        s[..start].chars().next_back()
                     };
-                    if let Some(prev) = prev_char {
-                        if prev.is_alphanumeric()
+                    if let Some(prev) = prev_char
+                        && (prev.is_alphanumeric()
                             || matches!(
                                 prev,
                                 '/' | ':'
@@ -221,18 +414,17 @@ pub fn render(
                                     | '?'
                                     | '&'
                                     | '%'
-                            )
-                        {
-                            let ast = Ast::new(
-                                NodeValue::Text("#".to_string()),
-                                LineColumn { line: 1, column: 1 },
-                            );
-                            let n = arena
-                                .alloc(comrak::nodes::AstNode::new(std::cell::RefCell::new(ast)));
-                            node.insert_before(n);
-                            i = start + 1;
-                            continue;
-                        }
+                            ))
+                    {
+                        let ast = Ast::new(
+                            NodeValue::Text("#".to_string()),
+                            LineColumn { line: 1, column: 1 },
+                        );
+                        let n =
+                            arena.alloc(comrak::nodes::AstNode::new(std::cell::RefCell::new(ast)));
+                        node.insert_before(n);
+                        i = start + 1;
+                        continue;
                     }
 
                     let rest = &s[start + 1..];
@@ -350,15 +542,49 @@ pub fn render(
         }
     }
 
+    /// Replaces a trailing `^blockid` marker in a paragraph with an anchor element.
+    fn anchor_block_marker<'a>(
+        arena: &'a comrak::Arena<comrak::nodes::AstNode<'a>>,
+        node: &'a AstNode<'a>,
+    ) {
+        use comrak::nodes::{Ast, LineColumn, NodeValue};
+        let Some(last) = node.last_child() else {
+            return;
+        };
+        let marker = match &last.data.borrow().value {
+            NodeValue::Text(t) => {
+                split_block_marker(t).map(|(rest, id)| (rest.to_string(), id.to_string()))
+            }
+            _ => None,
+        };
+        let Some((rest, block_id)) = marker else {
+            return;
+        };
+        last.data.borrow_mut().value = NodeValue::Text(rest);
+        let html = format!(
+            "<span id=\"{}\" class=\"block-anchor\"></span>",
+            block_anchor(&block_id)
+        );
+        let anchor = arena.alloc(comrak::nodes::AstNode::new(std::cell::RefCell::new(
+            Ast::new(
+                NodeValue::HtmlInline(html),
+                LineColumn { line: 1, column: 1 },
+            ),
+        )));
+        node.append(anchor);
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn walk<'a>(
         arena: &'a comrak::Arena<comrak::nodes::AstNode<'a>>,
         node: &'a AstNode<'a>,
         placeholders: &mut Vec<PlaceholderItem>,
-        counter: &mut usize,
+        placeholder_ids: &mut HashMap<String, usize>,
         enable_highlight: bool,
         theme_name: &str,
         opts: &RenderOptions,
         placeholder_kinds: Option<&HashSet<String>>,
+        highlights_left: &mut usize,
     ) {
         use comrak::nodes::NodeValue;
         fn is_attachment_url(url: &str) -> bool {
@@ -374,7 +600,12 @@ pub fn render(
                 return None;
             }
             let token = opts.token.as_deref();
-            let prefix = opts.base_origin.as_deref().unwrap_or("");
+            let prefix = opts
+                .asset_base
+                .as_deref()
+                .filter(|base| !base.is_empty())
+                .or(opts.base_origin.as_deref())
+                .unwrap_or("");
             let mut path = if url.starts_with("./attachments/") {
                 format!("/api/uploads/{}/{}", doc_id, &url.trim_start_matches("./"))
             } else if url.starts_with("attachments/") {
@@ -384,13 +615,13 @@ pub fn render(
             } else {
                 return None;
             };
-            if let Some(tok) = token {
-                if !tok.is_empty() {
-                    if path.contains('?') {
-                        path.push_str(&format!("&token={}", urlencoding::encode(tok)))
-                    } else {
-                        path.push_str(&format!("?token={}", urlencoding::encode(tok)))
-                    }
+            if let Some(tok) = token
+                && !tok.is_empty()
+            {
+                if path.contains('?') {
+                    path.push_str(&format!("&token={}", urlencoding::encode(tok)))
+                } else {
+                    path.push_str(&format!("?token={}", urlencoding::encode(tok)))
                 }
             }
             if prefix.is_empty() {
@@ -420,11 +651,12 @@ pub fn render(
                 arena,
                 child,
                 placeholders,
-                counter,
+                placeholder_ids,
                 enable_highlight,
                 theme_name,
                 opts,
                 placeholder_kinds,
+                highlights_left,
             );
 
             // Prepare replacement outside the borrow scope to avoid RefCell double-borrows
@@ -441,8 +673,7 @@ pub fn render(
                         .map(|set| set.contains(lang_norm.as_str()))
                         .unwrap_or(false);
                     if should_placeholder {
-                        *counter += 1;
-                        let id = format!("p{}", counter);
+                        let id = placeholder_id(placeholder_ids, &lang_norm, &cb.literal);
                         let code = cb.literal.clone();
                         placeholders.push(PlaceholderItem {
                             kind: lang_norm.clone(),
@@ -454,7 +685,8 @@ pub fn render(
                             id, lang_norm,
                         );
                         replace_with = Some(html);
-                    } else if enable_highlight {
+                    } else if enable_highlight && *highlights_left > 0 {
+                        *highlights_left -= 1;
                         let code = cb.literal.clone();
                         let html = highlight_codeblock(&code, lang, theme_name);
                         replace_with = Some(html);
@@ -468,7 +700,7 @@ pub fn render(
                             if !txt.is_empty() {
                                 txt
                             } else {
-                                url.split('/').last().unwrap_or(&url).to_string()
+                                url.split('/').next_back().unwrap_or(&url).to_string()
                             }
                         };
                         let new_url = rewrite_attachment_url(&url, opts).unwrap_or(url.clone());
@@ -557,9 +789,12 @@ pub fn render(
                 continue;
             }
 
+            if matches!(child.data.borrow().value, NodeValue::Paragraph) {
+                anchor_block_marker(arena, child);
+            }
             if matches!(child.data.borrow().value, NodeValue::Text(_)) {
                 // Hashtag / wiki / mention transform for inline text
-                process_text_node(arena, child);
+                process_text_node(arena, child, &opts.wiki_links);
             }
         }
     }
@@ -570,20 +805,40 @@ pub fn render(
         .as_deref()
         .filter(|s| !s.is_empty())
         .unwrap_or("Nord");
+    let mut highlights_left = budget.max_highlighted_blocks;
     walk(
         &arena,
         root,
         &mut placeholders,
-        &mut counter,
+        &mut placeholder_ids,
         enable_highlight,
         theme_name,
         &opts,
         placeholder_kinds,
+        &mut highlights_left,
     );
+    if enable_highlight && highlights_left == 0 {
+        let unhighlighted = root.descendants().any(|n| {
+            matches!(
+                n.data.borrow().value,
+                comrak::nodes::NodeValue::CodeBlock(_)
+            )
+        });
+        truncated |= unhighlighted;
+    }
 
-    // Render HTML
+    // Render HTML one top-level block at a time, stopping before the size cap.
     let mut html = Vec::new();
-    comrak::format_html(root, &c_opts, &mut html)?;
+    let mut block = Vec::new();
+    for child in root.children() {
+        block.clear();
+        comrak::format_html(child, &c_opts, &mut block)?;
+        if html.len() + block.len() > budget.max_html_bytes {
+            truncated = true;
+            break;
+        }
+        html.extend_from_slice(&block);
+    }
     let html = String::from_utf8(html)?;
 
     // Sanitize
@@ -627,25 +882,126 @@ pub fn render(
         html: safe_html,
         placeholders,
         hash,
+        tasks_total: task_counts.map(|(total, _)| total),
+        tasks_completed: task_counts.map(|(_, completed)| completed),
+        truncated,
     })
 }
 
+/// Detaches the top-level blocks after the one that takes the tree past `max_nodes`. Returns
+/// whether anything was dropped.
+fn drop_blocks_past_node_budget<'a>(
+    root: &'a comrak::nodes::AstNode<'a>,
+    max_nodes: usize,
+) -> bool {
+    let mut seen = 0usize;
+    let mut cut = None;
+    for child in root.children() {
+        seen += child.descendants().count();
+        if seen > max_nodes {
+            cut = Some(child);
+            break;
+        }
+    }
+    let Some(first_dropped) = cut else {
+        return false;
+    };
+    let mut next = Some(first_dropped);
+    while let Some(node) = next {
+        next = node.next_sibling();
+        node.detach();
+    }
+    true
+}
+
+/// (total, checked) task list items at any nesting depth. Code blocks hold literal text, so
+/// task-like lines inside them are never counted.
+fn count_tasks<'a>(root: &'a comrak::nodes::AstNode<'a>) -> (usize, usize) {
+    use comrak::nodes::NodeValue;
+    root.descendants().fold((0, 0), |(total, completed), node| {
+        match node.data.borrow().value {
+            NodeValue::TaskItem(symbol) => (total + 1, completed + usize::from(symbol.is_some())),
+            _ => (total, completed),
+        }
+    })
+}
+
+/// Turns raw HTML in the parsed source into literal text, so it renders escaped.
+fn escape_raw_html<'a>(
+    arena: &'a comrak::Arena<comrak::nodes::AstNode<'a>>,
+    root: &'a comrak::nodes::AstNode<'a>,
+) {
+    use comrak::nodes::{Ast, LineColumn, NodeValue};
+    let nodes: Vec<_> = root.descendants().collect();
+    for node in nodes {
+        let value = node.data.borrow().value.clone();
+        match value {
+            NodeValue::HtmlInline(literal) => {
+                node.data.borrow_mut().value = NodeValue::Text(literal);
+            }
+            NodeValue::HtmlBlock(block) => {
+                node.data.borrow_mut().value = NodeValue::Paragraph;
+                let text = arena.alloc(comrak::nodes::AstNode::new(std::cell::RefCell::new(
+                    Ast::new(
+                        NodeValue::Text(block.literal.trim_end().to_string()),
+                        LineColumn { line: 1, column: 1 },
+                    ),
+                )));
+                node.append(text);
+            }
+            _ => {}
+        }
+    }
+}
+
 static HIGHLIGHT_ASSETS: Lazy<Mutex<syntect_assets::assets::HighlightingAssets>> =
     Lazy::new(|| Mutex::new(syntect_assets::assets::HighlightingAssets::from_binary()));
 
+/// Locks the highlighting assets, recovering from poisoning. The assets are only read (the
+/// lock guards their lazy deserialization), so a panic elsewhere cannot leave them half-updated.
+fn highlight_assets() -> MutexGuard<'static, syntect_assets::assets::HighlightingAssets> {
+    HIGHLIGHT_ASSETS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Merges the syntaxes and themes in `dir` into code block highlighting. Called once at startup.
+pub fn install_highlight_extensions(dir: &std::path::Path) {
+    let assets = highlight_assets();
+    match assets.get_syntax_set() {
+        Ok(bundled) => highlight_extensions::install(dir, bundled),
+        Err(e) => tracing::warn!(error = %e, "highlight_assets_unavailable"),
+    }
+}
+
 fn highlight_codeblock(code: &str, lang: &str, theme_name: &str) -> String {
+    highlight_with(highlight_extensions::installed(), code, lang, theme_name)
+}
+
+pub(crate) fn highlight_with(
+    extensions: Option<&highlight_extensions::HighlightExtensions>,
+    code: &str,
+    lang: &str,
+    theme_name: &str,
+) -> String {
     use syntect::html::highlighted_html_for_string;
 
-    let assets = HIGHLIGHT_ASSETS
-        .lock()
-        .expect("highlight assets mutex poisoned");
-    let ss = assets.get_syntax_set().unwrap();
-    // Prefer requested theme; assets fall back internally when unavailable.
-    let theme = assets.get_theme(theme_name);
-    let syntax = ss
-        .find_syntax_by_token(lang)
-        .unwrap_or_else(|| ss.find_syntax_plain_text());
-    let out = highlighted_html_for_string(code, ss, syntax, theme).unwrap_or_else(|_| {
+    let assets = highlight_assets();
+    let ss = match extensions.and_then(|e| e.syntax_set()) {
+        Some(ss) => Some(ss),
+        None => assets.get_syntax_set().ok(),
+    };
+    let highlighted = ss.and_then(|ss| {
+        // Prefer requested theme; assets fall back internally when unavailable.
+        let theme = extensions
+            .and_then(|e| e.theme(theme_name))
+            .unwrap_or_else(|| assets.get_theme(theme_name));
+        let syntax = ss
+            .find_syntax_by_token(lang)
+            .unwrap_or_else(|| ss.find_syntax_plain_text());
+        highlighted_html_for_string(code, ss, syntax, theme).ok()
+    });
+    let out = highlighted.unwrap_or_else(|| {
         // Fallback to escaped pre/code
         let escaped = htmlescape::encode_minimal(code);
         format!(
@@ -655,3 +1011,324 @@ fn highlight_codeblock(code: &str, lang: &str, theme_name: &str) -> String {
     });
     format!("<div class=\"not-prose\">{}</div>", out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "```rust\nfn main() {}\n```\n";
+
+    fn instance_defaults() -> RenderOptions {
+        RenderOptions {
+            theme: Some("InspiredGitHub".into()),
+            features: Some(vec!["gfm".into(), "highlight".into()]),
+            ..Default::default()
+        }
+    }
+
+    fn html(opts: RenderOptions) -> String {
+        render(CODE.to_string(), opts, None).unwrap().html
+    }
+
+    #[test]
+    fn omitted_theme_uses_configured_default() {
+        let merged = RenderOptions::default().with_defaults(&instance_defaults());
+        assert_eq!(merged.theme.as_deref(), Some("InspiredGitHub"));
+        assert_eq!(html(merged), html(instance_defaults()));
+    }
+
+    #[test]
+    fn explicit_request_values_override_defaults() {
+        let request = RenderOptions {
+            theme: Some("Nord".into()),
+            sanitize: Some(true),
+            ..Default::default()
+        };
+        let defaults = RenderOptions {
+            sanitize: Some(false),
+            ..instance_defaults()
+        };
+        let merged = request.with_defaults(&defaults);
+        assert_eq!(merged.theme.as_deref(), Some("Nord"));
+        assert_eq!(merged.sanitize, Some(true));
+        assert_eq!(merged.features, defaults.features);
+        assert_ne!(html(merged), html(instance_defaults()));
+    }
+
+    #[test]
+    fn headings_get_ids_matching_their_slug() {
+        let out = render(
+            "## Next Steps!\n".to_string(),
+            RenderOptions::default(),
+            None,
+        )
+        .unwrap()
+        .html;
+        assert_eq!(heading_slug("Next Steps!"), "next-steps");
+        assert!(out.contains("id=\"next-steps\""));
+    }
+
+    #[test]
+    fn disallowed_raw_html_is_escaped_instead_of_sanitized() {
+        let text = "Hi <b onclick=\"x()\">there</b> ^greeting\n\n<div>block</div>\n";
+        let sanitized = render(text.to_string(), RenderOptions::default(), None)
+            .unwrap()
+            .html;
+        assert!(sanitized.contains("<b>there</b>"));
+        assert!(sanitized.contains("<div>block</div>"));
+
+        let opts = RenderOptions {
+            allow_raw_html: Some(false),
+            ..Default::default()
+        };
+        let escaped = render(text.to_string(), opts, None).unwrap().html;
+        assert!(escaped.contains("&lt;b onclick=\"x()\"&gt;there&lt;/b&gt;"));
+        assert!(escaped.contains("&lt;div&gt;block&lt;/div&gt;"));
+        assert!(!escaped.contains("<b>"));
+        // The renderer's own markup is unaffected.
+        assert!(escaped.contains("<span id=\"block-greeting\" class=\"block-anchor\"></span>"));
+    }
+
+    #[test]
+    fn autolink_toggles_independently_of_gfm() {
+        let link = "href=\"https://example.com\"";
+        let render_with = |features: &[&str], autolink: Option<bool>| {
+            let opts = RenderOptions {
+                features: Some(features.iter().map(|f| f.to_string()).collect()),
+                autolink,
+                ..Default::default()
+            };
+            render("see https://example.com".to_string(), opts, None)
+                .unwrap()
+                .html
+        };
+        assert!(render_with(&["gfm"], None).contains(link));
+        assert!(!render_with(&["gfm"], Some(false)).contains(link));
+        assert!(!render_with(&[], None).contains(link));
+        assert!(render_with(&[], Some(true)).contains(link));
+    }
+
+    fn stats(text: &str) -> RenderResponse {
+        let opts = RenderOptions {
+            features: Some(vec!["gfm".into(), "stats".into()]),
+            ..Default::default()
+        };
+        render(text.to_string(), opts, None).unwrap()
+    }
+
+    #[test]
+    fn task_stats_count_nested_items_but_not_code() {
+        let text = "- [x] ship\n- [ ] test\n  - [X] nested done\n  - [ ] nested open\n- plain\n\n```\n- [x] not a task\n```\n\n1. [ ] ordered\n";
+        let out = stats(text);
+        assert_eq!(out.tasks_total, Some(5));
+        assert_eq!(out.tasks_completed, Some(2));
+    }
+
+    #[test]
+    fn task_stats_are_zero_without_tasks_and_omitted_without_the_flag() {
+        let out = stats("# Notes\n\n- just a list\n");
+        assert_eq!((out.tasks_total, out.tasks_completed), (Some(0), Some(0)));
+
+        let out = render("- [x] done\n".to_string(), RenderOptions::default(), None).unwrap();
+        assert_eq!((out.tasks_total, out.tasks_completed), (None, None));
+        let json = serde_json::to_value(&out).unwrap();
+        assert!(json.get("tasks_total").is_none());
+    }
+
+    #[test]
+    fn smart_punctuation_is_opt_in_and_skips_code() {
+        let text = "\"Quoted\" -- it's fine... `\"raw\" -- code`";
+        let render_with = |smart_punctuation| {
+            let opts = RenderOptions {
+                smart_punctuation,
+                ..Default::default()
+            };
+            render(text.to_string(), opts, None).unwrap().html
+        };
+
+        let smart = render_with(Some(true));
+        assert!(smart.contains("“Quoted” – it’s fine…"));
+        assert!(smart.contains("\"raw\" -- code</code>"));
+
+        let plain = render_with(None);
+        assert!(plain.contains("\"Quoted\" -- it's fine..."));
+        assert_eq!(plain, render_with(Some(false)));
+    }
+
+    #[test]
+    fn placeholder_ids_depend_on_content_not_position() {
+        let kinds = HashSet::from(["mermaid".to_string()]);
+        let ids = |text: &str| -> Vec<String> {
+            render(text.to_string(), RenderOptions::default(), Some(&kinds))
+                .unwrap()
+                .placeholders
+                .into_iter()
+                .map(|p| p.id)
+                .collect()
+        };
+        let diagram = "```mermaid\ngraph TD; A-->B\n```\n";
+        let other = "```mermaid\ngraph LR; X-->Y\n```\n";
+
+        let alone = ids(diagram);
+        let after_edit = ids(&format!("# Intro\n\n{other}\nSome text.\n\n{diagram}"));
+        assert_eq!(after_edit[1], alone[0]);
+        assert_ne!(after_edit[0], alone[0]);
+
+        // Identical diagrams in one document still get distinct ids.
+        let twice = ids(&format!("{diagram}\n{diagram}"));
+        assert_eq!(twice[0], alone[0]);
+        assert_eq!(twice[1], format!("{}-2", alone[0]));
+    }
+
+    #[test]
+    fn gfm_extensions_can_be_enabled_individually() {
+        let text = "| a |\n|---|\n| 1 |\n\n~~old~~ see https://example.com\n";
+        let render_with = |features: &[&str]| {
+            let opts = RenderOptions {
+                features: Some(features.iter().map(|f| f.to_string()).collect()),
+                ..Default::default()
+            };
+            render(text.to_string(), opts, None).unwrap().html
+        };
+
+        let table_only = render_with(&["table"]);
+        assert!(table_only.contains("<table"));
+        assert!(!table_only.contains("<del"));
+        assert!(!table_only.contains("<a href=\"https://example.com\""));
+
+        let gfm = render_with(&["gfm"]);
+        assert!(gfm.contains("<table"));
+        assert!(gfm.contains("<del"));
+        assert!(gfm.contains("<a href=\"https://example.com\""));
+
+        let autolink_only = render_with(&["autolink"]);
+        assert!(!autolink_only.contains("<table"));
+        assert!(autolink_only.contains("<a href=\"https://example.com\""));
+    }
+
+    #[test]
+    fn document_options_resolve_attachments_against_the_document() {
+        let doc_id = uuid::Uuid::new_v4();
+        let text = "![chart](./attachments/chart.png) [notes](attachments/notes.pdf)";
+        let out = render(
+            text.to_string(),
+            RenderOptions::default().for_document(doc_id),
+            None,
+        )
+        .unwrap();
+        assert!(
+            out.html
+                .contains(&format!("/api/uploads/{doc_id}/attachments/chart.png"))
+        );
+        assert!(
+            out.html
+                .contains(&format!("/api/uploads/{doc_id}/attachments/notes.pdf"))
+        );
+
+        let relative = RenderOptions {
+            absolute_attachments: Some(false),
+            ..Default::default()
+        }
+        .for_document(doc_id);
+        assert_eq!(relative.absolute_attachments, Some(false));
+        assert_eq!(relative.doc_id, Some(doc_id));
+    }
+
+    #[test]
+    fn attachments_use_the_asset_base_when_set() {
+        let doc_id = uuid::Uuid::new_v4();
+        let text = "![chart](./attachments/chart.png) [site](https://example.com/page)";
+        let render_with = |asset_base: Option<&str>| {
+            let opts = RenderOptions {
+                base_origin: Some("https://api.example.com".into()),
+                asset_base: asset_base.map(Into::into),
+                token: Some("t1".into()),
+                ..Default::default()
+            };
+            render(text.to_string(), opts.for_document(doc_id), None)
+                .unwrap()
+                .html
+        };
+
+        let cdn = render_with(Some("https://cdn.example.com/"));
+        assert!(cdn.contains(&format!(
+            "https://cdn.example.com/api/uploads/{doc_id}/attachments/chart.png?token=t1"
+        )));
+        assert!(!cdn.contains("https://api.example.com/api/uploads"));
+        assert!(cdn.contains("https://example.com/page"));
+
+        let api = render_with(None);
+        assert!(api.contains(&format!(
+            "https://api.example.com/api/uploads/{doc_id}/attachments/chart.png?token=t1"
+        )));
+    }
+
+    #[test]
+    fn highlighting_survives_a_poisoned_assets_lock() {
+        let _ = std::thread::spawn(|| {
+            let _guard = highlight_assets();
+            panic!("panic while holding the highlight assets");
+        })
+        .join();
+        assert!(HIGHLIGHT_ASSETS.is_poisoned());
+
+        let out = html(instance_defaults());
+        assert!(out.contains("<span style="), "{}", out);
+        assert!(out.contains("main"));
+    }
+
+    fn budgeted(budget: RenderBudget) -> RenderOptions {
+        RenderOptions {
+            budget: Some(budget),
+            ..instance_defaults()
+        }
+    }
+
+    #[test]
+    fn code_blocks_past_the_highlight_cap_are_flagged() {
+        let doc: String = (0..5)
+            .map(|i| format!("```rust\nlet x{i} = {i};\n```\n\n"))
+            .collect();
+        let budget = RenderBudget {
+            max_highlighted_blocks: 3,
+            ..Default::default()
+        };
+
+        let out = render(doc.clone(), budgeted(budget), None).unwrap();
+        assert!(out.truncated);
+        assert_eq!(out.html.matches("<div class=\"not-prose\">").count(), 3);
+        assert!(out.html.contains("x4"));
+
+        let within = RenderBudget {
+            max_highlighted_blocks: 5,
+            ..Default::default()
+        };
+        let out = render(doc, budgeted(within), None).unwrap();
+        assert!(!out.truncated);
+    }
+
+    #[test]
+    fn blocks_past_node_and_size_caps_are_dropped() {
+        let doc: String = (0..50).map(|i| format!("paragraph {i}\n\n")).collect();
+
+        let by_nodes = RenderBudget {
+            max_nodes: 20,
+            ..Default::default()
+        };
+        let out = render(doc.clone(), budgeted(by_nodes), None).unwrap();
+        assert!(out.truncated);
+        assert!(out.html.contains("paragraph 0"));
+        assert!(!out.html.contains("paragraph 49"));
+
+        let by_size = RenderBudget {
+            max_html_bytes: 600,
+            ..Default::default()
+        };
+        let out = render(doc.clone(), budgeted(by_size), None).unwrap();
+        assert!(out.truncated);
+        assert!(out.html.len() <= 600);
+        assert!(out.html.ends_with("</p>\n"));
+
+        assert!(!render(doc, instance_defaults(), None).unwrap().truncated);
+    }
+}
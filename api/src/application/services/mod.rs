@@ -1,3 +1,16 @@
+pub mod capabilities;
+pub mod commit_message;
+pub mod diff;
+pub mod front_matter;
+pub mod gitignore;
+pub mod health;
 pub mod markdown;
+pub mod notifications;
+pub mod plugin_scheduler;
+pub mod public_listing;
+pub mod public_views;
+pub mod rate_limit;
 pub mod realtime;
 pub mod tagging;
+pub mod token_cache;
+pub mod uploads;
@@ -0,0 +1,284 @@
+//! Stores notifications for users and pushes them to connected clients.
+//!
+//! Producers go through the `Notifier` port. Live delivery reuses the per-user plugin event
+//! stream (`/api/me/plugins/updates`): each notification is published there as an `update`
+//! whose payload has `"type": "notification"`. Publishing is best effort; the stored
+//! notification is what `GET /api/me/notifications` returns.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::application::ports::notification_repository::{
+    NewNotification, NotificationRepository, NotificationRow,
+};
+use crate::application::ports::notifier::Notifier;
+use crate::application::ports::plugin_event_publisher::{PluginEventPublisher, PluginScopedEvent};
+
+pub const KIND_COMMENT: &str = "comment";
+pub const KIND_MENTION: &str = "mention";
+pub const KIND_SHARE: &str = "share";
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+pub struct NotificationPage {
+    pub items: Vec<NotificationRow>,
+    pub unread_count: i64,
+    pub next_offset: Option<i64>,
+}
+
+pub struct NotificationService {
+    repo: Arc<dyn NotificationRepository>,
+    publisher: Option<Arc<dyn PluginEventPublisher>>,
+}
+
+impl NotificationService {
+    pub fn new(repo: Arc<dyn NotificationRepository>) -> Self {
+        Self {
+            repo,
+            publisher: None,
+        }
+    }
+
+    /// Pushes new notifications to the recipient's event stream as they are stored.
+    pub fn with_live_updates(mut self, publisher: Arc<dyn PluginEventPublisher>) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    pub async fn list(
+        &self,
+        user_id: Uuid,
+        unread_only: bool,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> anyhow::Result<NotificationPage> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let offset = offset.unwrap_or(0).max(0);
+        let mut items = self
+            .repo
+            .list_for_user(user_id, unread_only, limit + 1, offset)
+            .await?;
+        let next_offset = (items.len() as i64 > limit).then_some(offset + limit);
+        items.truncate(limit as usize);
+        Ok(NotificationPage {
+            items,
+            unread_count: self.repo.count_unread(user_id).await?,
+            next_offset,
+        })
+    }
+
+    pub async fn unread_count(&self, user_id: Uuid) -> anyhow::Result<i64> {
+        self.repo.count_unread(user_id).await
+    }
+
+    /// Returns `false` when the notification does not exist or belongs to someone else.
+    pub async fn mark_read(&self, user_id: Uuid, id: Uuid) -> anyhow::Result<bool> {
+        self.repo.mark_read(user_id, id).await
+    }
+}
+
+#[async_trait]
+impl Notifier for NotificationService {
+    async fn notify(&self, notification: NewNotification) -> anyhow::Result<()> {
+        let row = self.repo.insert(&notification).await?;
+        if let Some(publisher) = &self.publisher {
+            let event = PluginScopedEvent {
+                user_id: Some(row.user_id),
+                payload: json!({ "type": "notification", "notification": to_json(&row) }),
+            };
+            if let Err(err) = publisher.publish(&event).await {
+                tracing::warn!(error = ?err, user_id = %row.user_id, "notification_publish_failed");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `recipient` was mentioned in `doc_id` by `actor`.
+pub fn mention(recipient: Uuid, doc_id: Uuid, actor: Uuid, title: &str) -> NewNotification {
+    NewNotification {
+        user_id: recipient,
+        kind: KIND_MENTION.to_string(),
+        document_id: Some(doc_id),
+        actor_id: Some(actor),
+        data: json!({ "title": title }),
+    }
+}
+
+/// `actor` gave `recipient` `permission` on `doc_id`.
+pub fn share(recipient: Uuid, doc_id: Uuid, actor: Uuid, permission: &str) -> NewNotification {
+    NewNotification {
+        user_id: recipient,
+        kind: KIND_SHARE.to_string(),
+        document_id: Some(doc_id),
+        actor_id: Some(actor),
+        data: json!({ "permission": permission }),
+    }
+}
+
+/// Someone commented on `doc_id`. `actor` is `None` for comments left through a share link.
+pub fn comment(
+    recipient: Uuid,
+    doc_id: Uuid,
+    actor: Option<Uuid>,
+    comment_id: Uuid,
+    excerpt: &str,
+) -> NewNotification {
+    NewNotification {
+        user_id: recipient,
+        kind: KIND_COMMENT.to_string(),
+        document_id: Some(doc_id),
+        actor_id: actor,
+        data: json!({ "comment_id": comment_id, "excerpt": excerpt }),
+    }
+}
+
+fn to_json(row: &NotificationRow) -> serde_json::Value {
+    json!({
+        "id": row.id,
+        "kind": row.kind,
+        "document_id": row.document_id,
+        "actor_id": row.actor_id,
+        "data": row.data,
+        "read_at": row.read_at,
+        "created_at": row.created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use chrono::Utc;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Store(Mutex<Vec<NotificationRow>>);
+
+    #[async_trait]
+    impl NotificationRepository for Store {
+        async fn insert(&self, n: &NewNotification) -> anyhow::Result<NotificationRow> {
+            let row = NotificationRow {
+                id: Uuid::new_v4(),
+                user_id: n.user_id,
+                kind: n.kind.clone(),
+                document_id: n.document_id,
+                actor_id: n.actor_id,
+                data: n.data.clone(),
+                read_at: None,
+                created_at: Utc::now(),
+            };
+            self.0.lock().unwrap().insert(0, row.clone());
+            Ok(row)
+        }
+        async fn list_for_user(
+            &self,
+            user_id: Uuid,
+            unread_only: bool,
+            limit: i64,
+            offset: i64,
+        ) -> anyhow::Result<Vec<NotificationRow>> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|n| n.user_id == user_id && (!unread_only || n.read_at.is_none()))
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+        async fn count_unread(&self, user_id: Uuid) -> anyhow::Result<i64> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|n| n.user_id == user_id && n.read_at.is_none())
+                .count() as i64)
+        }
+        async fn mark_read(&self, user_id: Uuid, id: Uuid) -> anyhow::Result<bool> {
+            let mut rows = self.0.lock().unwrap();
+            match rows.iter_mut().find(|n| n.id == id && n.user_id == user_id) {
+                Some(n) => {
+                    n.read_at.get_or_insert_with(Utc::now);
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct Published(Mutex<Vec<PluginScopedEvent>>);
+
+    #[async_trait]
+    impl PluginEventPublisher for Published {
+        async fn publish(&self, event: &PluginScopedEvent) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn a_mention_produces_an_unread_notification() {
+        let published = Arc::new(Published::default());
+        let service = NotificationService::new(Arc::new(Store::default()))
+            .with_live_updates(published.clone());
+        let (alice, bob, doc) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        service
+            .notify(mention(alice, doc, bob, "Roadmap"))
+            .await
+            .unwrap();
+
+        let page = service.list(alice, true, None, None).await.unwrap();
+        assert_eq!(page.unread_count, 1);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].kind, KIND_MENTION);
+        assert_eq!(page.items[0].document_id, Some(doc));
+        assert_eq!(page.items[0].actor_id, Some(bob));
+        assert_eq!(service.unread_count(bob).await.unwrap(), 0);
+
+        let events = published.0.lock().unwrap();
+        assert_eq!(events[0].user_id, Some(alice));
+        assert_eq!(events[0].payload["type"], "notification");
+        assert_eq!(
+            events[0].payload["notification"]["data"]["title"],
+            "Roadmap"
+        );
+    }
+
+    #[tokio::test]
+    async fn marking_read_updates_the_unread_count() {
+        let service = NotificationService::new(Arc::new(Store::default()));
+        let (alice, bob, doc) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        service
+            .notify(mention(alice, doc, bob, "Roadmap"))
+            .await
+            .unwrap();
+        service
+            .notify(share(alice, doc, bob, "edit"))
+            .await
+            .unwrap();
+        let first = service.list(alice, false, Some(1), None).await.unwrap();
+        assert_eq!(first.next_offset, Some(1));
+        assert_eq!(first.items[0].kind, KIND_SHARE);
+
+        assert!(service.mark_read(alice, first.items[0].id).await.unwrap());
+        assert_eq!(service.unread_count(alice).await.unwrap(), 1);
+        let unread = service.list(alice, true, None, None).await.unwrap();
+        assert_eq!(unread.items.len(), 1);
+        assert_eq!(unread.items[0].kind, KIND_MENTION);
+
+        // Other users cannot mark someone else's notifications.
+        assert!(!service.mark_read(bob, unread.items[0].id).await.unwrap());
+        assert_eq!(service.unread_count(alice).await.unwrap(), 1);
+    }
+}
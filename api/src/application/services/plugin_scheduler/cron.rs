@@ -0,0 +1,187 @@
+//! Five-field cron expressions (`minute hour day-of-month month day-of-week`), evaluated in UTC.
+//!
+//! Fields accept `*`, single values, ranges (`1-5`), steps (`*/15`, `0-30/10`) and comma lists.
+//! Day of week runs 0-6 from Sunday, with 7 also meaning Sunday. As in classic cron, when both
+//! day fields are restricted a time matches if either of them does.
+
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+
+/// How far ahead `next_after` looks before giving up on expressions like `0 0 31 2 *`.
+const SEARCH_DAYS: i64 = 366 * 5;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum CronError {
+    #[error("expected 5 fields, found {0}")]
+    FieldCount(usize),
+    #[error("invalid {field} field: {value}")]
+    Field { field: &'static str, value: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, CronError> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(CronError::FieldCount(fields.len()));
+        };
+        let mut weekdays = parse_field(weekday, 0, 7, "day-of-week")?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days: parse_field(day, 1, 31, "day-of-month")?,
+            months: parse_field(month, 1, 12, "month")?,
+            weekdays,
+            any_day: day == "*",
+            any_weekday: weekday == "*",
+        })
+    }
+
+    /// Whether the minute containing `at` is scheduled.
+    pub fn matches(&self, at: DateTime<Utc>) -> bool {
+        self.matches_day(at) && bit(self.hours, at.hour()) && bit(self.minutes, at.minute())
+    }
+
+    /// The first scheduled minute strictly after `after`.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut at = truncate_to_minute(after) + Duration::minutes(1);
+        let limit = at + Duration::days(SEARCH_DAYS);
+        while at < limit {
+            if !self.matches_day(at) {
+                let next_day = at.date_naive().succ_opt()?;
+                at = next_day.and_hms_opt(0, 0, 0)?.and_utc();
+            } else if !bit(self.hours, at.hour()) {
+                at += Duration::minutes(60 - i64::from(at.minute()));
+            } else if !bit(self.minutes, at.minute()) {
+                at += Duration::minutes(1);
+            } else {
+                return Some(at);
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, at: DateTime<Utc>) -> bool {
+        if !bit(self.months, at.month()) {
+            return false;
+        }
+        let day = bit(self.days, at.day());
+        let weekday = bit(self.weekdays, at.weekday().num_days_from_sunday());
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday,
+            (false, true) => day,
+            (false, false) => day || weekday,
+        }
+    }
+}
+
+pub fn truncate_to_minute(at: DateTime<Utc>) -> DateTime<Utc> {
+    at - Duration::seconds(i64::from(at.second()))
+        - Duration::nanoseconds(i64::from(at.nanosecond()))
+}
+
+fn bit(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+fn parse_field(spec: &str, min: u32, max: u32, field: &'static str) -> Result<u64, CronError> {
+    let invalid = || CronError::Field {
+        field,
+        value: spec.to_string(),
+    };
+    let mut set = 0u64;
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, Some(step.parse::<u32>().map_err(|_| invalid())?)),
+            None => (part, None),
+        };
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((lo, hi)) = range.split_once('-') {
+            (
+                lo.parse().map_err(|_| invalid())?,
+                hi.parse().map_err(|_| invalid())?,
+            )
+        } else {
+            let value = range.parse().map_err(|_| invalid())?;
+            // `5/15` means "from 5, every 15".
+            (value, if step.is_some() { max } else { value })
+        };
+        let step = step.unwrap_or(1);
+        if step == 0 || lo < min || hi > max || lo > hi {
+            return Err(invalid());
+        }
+        for value in (lo..=hi).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn matches_steps_ranges_and_lists() {
+        let every_quarter = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert!(every_quarter.matches(at(2025, 10, 20, 9, 45)));
+        assert!(!every_quarter.matches(at(2025, 10, 20, 9, 46)));
+
+        // 2025-10-20 is a Monday.
+        let weekday_mornings = CronSchedule::parse("0 8,9 * * 1-5").unwrap();
+        assert!(weekday_mornings.matches(at(2025, 10, 20, 9, 0)));
+        assert!(!weekday_mornings.matches(at(2025, 10, 19, 9, 0)));
+        assert!(!weekday_mornings.matches(at(2025, 10, 20, 10, 0)));
+
+        let sundays = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert!(sundays.matches(at(2025, 10, 19, 0, 0)));
+    }
+
+    #[test]
+    fn next_run_skips_ahead_to_the_matching_minute() {
+        let daily = CronSchedule::parse("30 6 * * *").unwrap();
+        assert_eq!(
+            daily.next_after(at(2025, 10, 20, 6, 30)),
+            Some(at(2025, 10, 21, 6, 30))
+        );
+        let leap_day = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            leap_day.next_after(at(2025, 10, 20, 0, 0)),
+            Some(at(2028, 2, 29, 0, 0))
+        );
+        let never = CronSchedule::parse("0 0 31 2 *").unwrap();
+        assert_eq!(never.next_after(at(2025, 10, 20, 0, 0)), None);
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        assert_eq!(
+            CronSchedule::parse("* * * *"),
+            Err(CronError::FieldCount(4))
+        );
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+        assert!(CronSchedule::parse("@hourly * * * *").is_err());
+    }
+}
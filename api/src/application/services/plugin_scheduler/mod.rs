@@ -0,0 +1,302 @@
+//! Runs the `schedules` plugins declare in their manifests.
+//!
+//! The scheduler ticks once a minute and runs, for every user with the plugin enabled, the
+//! schedules matching that minute. Minutes missed because a tick overran or the process was
+//! down are skipped, never caught up. Schedules are evaluated on the node running the loop, so
+//! a cluster should run it on one node only.
+
+pub mod cron;
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use futures_util::stream;
+use uuid::Uuid;
+
+use crate::application::ports::plugin_installation_repository::PluginInstallationRepository;
+use crate::application::ports::plugin_runtime::{PluginRuntime, PluginScheduleSpec};
+
+use self::cron::{CronSchedule, truncate_to_minute};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledRun {
+    pub user_id: Uuid,
+    pub plugin: String,
+    pub action: String,
+    pub cron: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpcomingRun {
+    pub cron: String,
+    pub action: String,
+    /// `None` when the expression is invalid or never matches.
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+pub struct PluginScheduler {
+    installations: Arc<dyn PluginInstallationRepository>,
+    runtime: Arc<dyn PluginRuntime>,
+    concurrency: usize,
+    last_minute: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl PluginScheduler {
+    pub fn new(
+        installations: Arc<dyn PluginInstallationRepository>,
+        runtime: Arc<dyn PluginRuntime>,
+        concurrency: usize,
+    ) -> Self {
+        Self {
+            installations,
+            runtime,
+            concurrency: concurrency.max(1),
+            last_minute: Mutex::new(None),
+        }
+    }
+
+    /// Schedules due in the minute containing `now`. Each minute is evaluated at most once.
+    pub async fn due_runs(&self, now: DateTime<Utc>) -> anyhow::Result<Vec<ScheduledRun>> {
+        let minute = truncate_to_minute(now);
+        {
+            let mut last = self.last_minute.lock().unwrap();
+            if last.is_some_and(|last| last >= minute) {
+                return Ok(Vec::new());
+            }
+            *last = Some(minute);
+        }
+
+        let mut runs = Vec::new();
+        let installs = self.installations.list_all().await?;
+        for inst in installs.into_iter().filter(|i| i.status == "enabled") {
+            let schedules = match self
+                .runtime
+                .schedules(Some(inst.user_id), &inst.plugin_id)
+                .await
+            {
+                Ok(Some(schedules)) => schedules,
+                Ok(None) => continue,
+                Err(err) => {
+                    tracing::warn!(
+                        error = ?err,
+                        user_id = %inst.user_id,
+                        plugin = inst.plugin_id.as_str(),
+                        "plugin_schedules_unavailable"
+                    );
+                    continue;
+                }
+            };
+            for spec in schedules {
+                match CronSchedule::parse(&spec.cron) {
+                    Ok(schedule) if schedule.matches(minute) => runs.push(ScheduledRun {
+                        user_id: inst.user_id,
+                        plugin: inst.plugin_id.clone(),
+                        action: spec.action,
+                        cron: spec.cron,
+                    }),
+                    Ok(_) => {}
+                    Err(err) => tracing::warn!(
+                        error = %err,
+                        plugin = inst.plugin_id.as_str(),
+                        cron = spec.cron.as_str(),
+                        "plugin_schedule_invalid"
+                    ),
+                }
+            }
+        }
+        Ok(runs)
+    }
+
+    /// Runs the schedules due at `now` through `invoke`, at most `concurrency` at a time, and
+    /// returns how many ran. A failed run is logged and does not affect the others.
+    pub async fn tick<F, Fut>(&self, now: DateTime<Utc>, invoke: F) -> anyhow::Result<usize>
+    where
+        F: Fn(ScheduledRun) -> Fut,
+        Fut: Future<Output = anyhow::Result<()>>,
+    {
+        let runs = self.due_runs(now).await?;
+        let count = runs.len();
+        stream::iter(runs)
+            .map(|run| {
+                let (user_id, plugin, action) =
+                    (run.user_id, run.plugin.clone(), run.action.clone());
+                let fut = invoke(run);
+                async move {
+                    if let Err(err) = fut.await {
+                        tracing::warn!(
+                            error = ?err,
+                            user_id = %user_id,
+                            plugin = plugin.as_str(),
+                            action = action.as_str(),
+                            "plugin_schedule_run_failed"
+                        );
+                    }
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .collect::<()>()
+            .await;
+        Ok(count)
+    }
+}
+
+/// When each schedule next runs after `now`.
+pub fn upcoming_runs(schedules: &[PluginScheduleSpec], now: DateTime<Utc>) -> Vec<UpcomingRun> {
+    schedules
+        .iter()
+        .map(|spec| UpcomingRun {
+            cron: spec.cron.clone(),
+            action: spec.action.clone(),
+            next_run_at: CronSchedule::parse(&spec.cron)
+                .ok()
+                .and_then(|schedule| schedule.next_after(now)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::application::dto::plugins::ExecResult;
+    use crate::application::ports::plugin_installation_repository::PluginInstallation;
+    use crate::application::ports::plugin_runtime::PluginSecrets;
+    use crate::test_support::plugins::MemoryPluginInstallations;
+
+    /// "reminders" runs `remind` at the top of every hour and `digest` daily at 08:30.
+    struct Runtime;
+
+    #[async_trait]
+    impl PluginRuntime for Runtime {
+        async fn execute(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+            _: &str,
+            _: &serde_json::Value,
+            _: &PluginSecrets,
+        ) -> anyhow::Result<Option<ExecResult>> {
+            unimplemented!()
+        }
+        async fn render_placeholder(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+            _: &str,
+            _: &serde_json::Value,
+        ) -> anyhow::Result<Option<serde_json::Value>> {
+            unimplemented!()
+        }
+        async fn permissions(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+        ) -> anyhow::Result<Option<Vec<String>>> {
+            unimplemented!()
+        }
+        async fn schedules(
+            &self,
+            _: Option<Uuid>,
+            plugin: &str,
+        ) -> anyhow::Result<Option<Vec<PluginScheduleSpec>>> {
+            Ok((plugin == "reminders").then(|| {
+                vec![
+                    PluginScheduleSpec {
+                        cron: "0 * * * *".into(),
+                        action: "remind".into(),
+                    },
+                    PluginScheduleSpec {
+                        cron: "30 8 * * *".into(),
+                        action: "digest".into(),
+                    },
+                ]
+            }))
+        }
+    }
+
+    fn install(user_id: Uuid, plugin_id: &str, status: &str) -> PluginInstallation {
+        PluginInstallation {
+            user_id,
+            plugin_id: plugin_id.into(),
+            version: "1.0.0".into(),
+            scope: "user".into(),
+            origin_url: None,
+            status: status.into(),
+            pinned: false,
+            installed_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn scheduler(installs: Vec<PluginInstallation>) -> PluginScheduler {
+        PluginScheduler::new(
+            Arc::new(MemoryPluginInstallations::new(installs)),
+            Arc::new(Runtime),
+            2,
+        )
+    }
+
+    #[tokio::test]
+    async fn due_schedule_triggers_an_exec() {
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let scheduler = scheduler(vec![
+            install(alice, "reminders", "enabled"),
+            install(bob, "reminders", "disabled"),
+        ]);
+        let ran = Mutex::new(Vec::new());
+
+        let now = Utc.with_ymd_and_hms(2025, 10, 20, 9, 0, 12).unwrap();
+        let count = scheduler
+            .tick(now, |run| {
+                ran.lock().unwrap().push(run);
+                async { Ok(()) }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(count, 1);
+        let ran = ran.into_inner().unwrap();
+        assert_eq!(ran[0].user_id, alice);
+        assert_eq!(ran[0].action, "remind");
+    }
+
+    #[tokio::test]
+    async fn schedule_that_is_not_due_does_not_run() {
+        let scheduler = scheduler(vec![install(Uuid::new_v4(), "reminders", "enabled")]);
+
+        let now = Utc.with_ymd_and_hms(2025, 10, 20, 9, 17, 0).unwrap();
+        assert!(scheduler.due_runs(now).await.unwrap().is_empty());
+
+        // A minute is only evaluated once, however often the loop ticks within it.
+        let top_of_hour = Utc.with_ymd_and_hms(2025, 10, 20, 10, 0, 0).unwrap();
+        assert_eq!(scheduler.due_runs(top_of_hour).await.unwrap().len(), 1);
+        let later_same_minute = top_of_hour + chrono::Duration::seconds(40);
+        assert!(
+            scheduler
+                .due_runs(later_same_minute)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn upcoming_runs_report_the_next_match() {
+        let now = Utc.with_ymd_and_hms(2025, 10, 20, 9, 17, 0).unwrap();
+        let schedules = Runtime.schedules(None, "reminders").await.unwrap().unwrap();
+
+        let upcoming = upcoming_runs(&schedules, now);
+        assert_eq!(
+            upcoming[0].next_run_at,
+            Some(Utc.with_ymd_and_hms(2025, 10, 20, 10, 0, 0).unwrap())
+        );
+        assert_eq!(
+            upcoming[1].next_run_at,
+            Some(Utc.with_ymd_and_hms(2025, 10, 21, 8, 30, 0).unwrap())
+        );
+    }
+}
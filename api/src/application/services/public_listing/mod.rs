@@ -0,0 +1,82 @@
+//! Published-document listings per owner, backing the public sitemap and JSON index.
+//!
+//! Listings are cached briefly; publishing or unpublishing clears the cache so a withdrawn
+//! document disappears from the next response.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::SecondsFormat;
+
+use crate::application::ports::public_repository::{PublicDocumentRow, PublicRepository};
+
+/// Sitemaps may list at most 50,000 URLs.
+pub const MAX_LISTED_DOCUMENTS: i64 = 50_000;
+
+type CachedListing = (Instant, Arc<Vec<PublicDocumentRow>>);
+
+pub struct PublicListingCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CachedListing>>,
+}
+
+impl PublicListingCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The owner's published documents, most recently updated first.
+    pub async fn published<R: PublicRepository + ?Sized>(
+        &self,
+        repo: &R,
+        owner_name: &str,
+    ) -> anyhow::Result<Arc<Vec<PublicDocumentRow>>> {
+        let cached = self
+            .entries
+            .lock()
+            .unwrap()
+            .get(owner_name)
+            .filter(|(at, _)| at.elapsed() < self.ttl)
+            .map(|(_, rows)| rows.clone());
+        if let Some(rows) = cached {
+            return Ok(rows);
+        }
+        let rows = Arc::new(
+            repo.list_user_public_documents(owner_name, MAX_LISTED_DOCUMENTS)
+                .await?,
+        );
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(owner_name.to_string(), (Instant::now(), rows.clone()));
+        Ok(rows)
+    }
+
+    /// Drops every cached listing after a publish state change.
+    pub fn invalidate(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Renders a sitemap linking each document's public page under `base_url`.
+pub fn render_sitemap(base_url: &str, owner_name: &str, rows: &[PublicDocumentRow]) -> String {
+    let base = base_url.trim_end_matches('/');
+    let owner = urlencoding::encode(owner_name);
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for (id, _, updated_at, _) in rows {
+        let loc = format!("{}/u/{}/{}", base, owner, id);
+        xml.push_str(&format!(
+            "  <url><loc>{}</loc><lastmod>{}</lastmod></url>\n",
+            htmlescape::encode_minimal(&loc),
+            updated_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ));
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
@@ -0,0 +1,133 @@
+//! View counting for published documents.
+//!
+//! Views are buffered in memory and written in batches by `flush`, keeping the public read
+//! path free of database writes. A client is identified only by a salted in-memory hash, used
+//! to ignore repeat views within the debounce window; nothing about the viewer is stored.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::application::ports::public_repository::PublicRepository;
+
+/// Days covered by the "recent views" figure, including today.
+pub const RECENT_DAYS: i64 = 7;
+
+pub struct PublicViewCounter {
+    debounce: Duration,
+    salt: RandomState,
+    seen: Mutex<HashMap<(Uuid, u64), Instant>>,
+    pending: Mutex<HashMap<(Uuid, NaiveDate), i64>>,
+}
+
+impl PublicViewCounter {
+    pub fn new(debounce: Duration) -> Self {
+        Self {
+            debounce,
+            salt: RandomState::new(),
+            seen: Mutex::new(HashMap::new()),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a view of `doc_id` unless `client` viewed it within the debounce window.
+    /// Returns whether the view was counted.
+    pub fn record(&self, doc_id: Uuid, client: &str, now: DateTime<Utc>) -> bool {
+        let key = (doc_id, self.salt.hash_one(client));
+        {
+            let mut seen = self.seen.lock().unwrap();
+            if seen
+                .get(&key)
+                .is_some_and(|at| at.elapsed() < self.debounce)
+            {
+                return false;
+            }
+            seen.insert(key, Instant::now());
+        }
+        *self
+            .pending
+            .lock()
+            .unwrap()
+            .entry((doc_id, now.date_naive()))
+            .or_default() += 1;
+        true
+    }
+
+    /// Views not yet flushed: (total, on or after `since`).
+    pub fn pending(&self, doc_id: Uuid, since: NaiveDate) -> (i64, i64) {
+        self.pending
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((id, _), _)| *id == doc_id)
+            .fold((0, 0), |(total, recent), ((_, day), views)| {
+                let recent_views = if *day >= since { *views } else { 0 };
+                (total + views, recent + recent_views)
+            })
+    }
+
+    /// Writes buffered views to the repository; on failure they are kept for the next flush.
+    pub async fn flush<R: PublicRepository + ?Sized>(&self, repo: &R) -> anyhow::Result<usize> {
+        self.seen
+            .lock()
+            .unwrap()
+            .retain(|_, at| at.elapsed() < self.debounce);
+        let batch: Vec<(Uuid, NaiveDate, i64)> = self
+            .pending
+            .lock()
+            .unwrap()
+            .drain()
+            .map(|((doc_id, day), views)| (doc_id, day, views))
+            .collect();
+        if batch.is_empty() {
+            return Ok(0);
+        }
+        if let Err(err) = repo.add_public_views(&batch).await {
+            let mut pending = self.pending.lock().unwrap();
+            for (doc_id, day, views) in batch {
+                *pending.entry((doc_id, day)).or_default() += views;
+            }
+            return Err(err);
+        }
+        Ok(batch.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_view_increments_the_counter() {
+        let counter = PublicViewCounter::new(Duration::from_secs(1800));
+        let doc = Uuid::new_v4();
+        let now = Utc::now();
+
+        assert!(counter.record(doc, "203.0.113.7 Firefox", now));
+        assert!(counter.record(doc, "198.51.100.2 Safari", now));
+        assert_eq!(counter.pending(doc, now.date_naive()), (2, 2));
+        assert_eq!(counter.pending(Uuid::new_v4(), now.date_naive()), (0, 0));
+    }
+
+    #[test]
+    fn rapid_repeat_views_from_one_client_are_debounced() {
+        let counter = PublicViewCounter::new(Duration::from_secs(1800));
+        let (doc, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let now = Utc::now();
+
+        assert!(counter.record(doc, "203.0.113.7 Firefox", now));
+        assert!(!counter.record(doc, "203.0.113.7 Firefox", now));
+        assert!(!counter.record(doc, "203.0.113.7 Firefox", now));
+        // The debounce is per document.
+        assert!(counter.record(other, "203.0.113.7 Firefox", now));
+        assert_eq!(counter.pending(doc, now.date_naive()), (1, 1));
+
+        let counter = PublicViewCounter::new(Duration::ZERO);
+        assert!(counter.record(doc, "203.0.113.7 Firefox", now));
+        assert!(counter.record(doc, "203.0.113.7 Firefox", now));
+    }
+}
@@ -0,0 +1,171 @@
+//! In-process token buckets for throttling expensive unauthenticated endpoints.
+//!
+//! Each key (a client address or user id) gets a bucket holding up to a minute's budget,
+//! refilled continuously. Limits are per node; in cluster mode each replica counts separately.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Buckets are pruned once the table grows past this many keys.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: Mutex<HashMap<String, (f64, Instant)>>,
+}
+
+impl RateLimiter {
+    /// Allows bursts of up to `per_minute` requests, refilling at `per_minute` per minute.
+    pub fn per_minute(per_minute: u32) -> Self {
+        let capacity = per_minute.max(1) as f64;
+        Self {
+            capacity,
+            refill_per_sec: capacity / 60.0,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from `key`'s bucket, or returns how long until one is available.
+    pub fn check(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= PRUNE_THRESHOLD {
+            // A bucket that would be full again carries no state worth keeping.
+            let full_after = Duration::from_secs_f64(self.capacity / self.refill_per_sec);
+            buckets.retain(|_, (_, at)| now.saturating_duration_since(*at) < full_after);
+        }
+        let (tokens, at) = buckets
+            .entry(key.to_string())
+            .or_insert((self.capacity, now));
+        let elapsed = now.saturating_duration_since(*at).as_secs_f64();
+        *tokens = (*tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        *at = now;
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - *tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+}
+
+/// Separate budgets for anonymous clients (keyed by address) and signed-in users (keyed by
+/// user id). `None` leaves that class of caller unthrottled.
+pub struct RequestRateLimits {
+    pub anonymous: Option<RateLimiter>,
+    pub authenticated: Option<RateLimiter>,
+    pub shares: ShareRateLimits,
+}
+
+fn limiter(per_minute: u32) -> Option<RateLimiter> {
+    (per_minute > 0).then(|| RateLimiter::per_minute(per_minute))
+}
+
+impl RequestRateLimits {
+    /// A budget of 0 disables limiting for that class.
+    pub fn new(anonymous_per_minute: u32, authenticated_per_minute: u32) -> Self {
+        Self {
+            anonymous: limiter(anonymous_per_minute),
+            authenticated: limiter(authenticated_per_minute),
+            shares: ShareRateLimits::default(),
+        }
+    }
+
+    pub fn with_share_limits(mut self, shares: ShareRateLimits) -> Self {
+        self.shares = shares;
+        self
+    }
+}
+
+/// Budgets for anonymous requests carrying a share token, counted both per token and per client
+/// address so that neither a leaked token nor one client cycling through tokens can hammer shared
+/// content. Signed-in callers are left to `RequestRateLimits`.
+#[derive(Default)]
+pub struct ShareRateLimits {
+    pub per_token: Option<RateLimiter>,
+    pub per_address: Option<RateLimiter>,
+}
+
+impl ShareRateLimits {
+    /// A budget of 0 disables that check.
+    pub fn new(per_token_per_minute: u32, per_address_per_minute: u32) -> Self {
+        Self {
+            per_token: limiter(per_token_per_minute),
+            per_address: limiter(per_address_per_minute),
+        }
+    }
+
+    /// Charges one request to `token` and to `address`; either budget running out rejects it.
+    pub fn check(&self, token: &str, address: &str, now: Instant) -> Result<(), Duration> {
+        if let Some(limiter) = &self.per_token {
+            limiter.check(token, now)?;
+        }
+        if let Some(limiter) = &self.per_address {
+            limiter.check(address, now)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_burst_beyond_the_budget_is_throttled() {
+        let limiter = RateLimiter::per_minute(10);
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            assert!(limiter.check("203.0.113.7", now).is_ok());
+        }
+        let retry_after = limiter.check("203.0.113.7", now).unwrap_err();
+        assert!(retry_after > Duration::ZERO && retry_after <= Duration::from_secs(6));
+        // Other clients have their own budget.
+        assert!(limiter.check("198.51.100.2", now).is_ok());
+    }
+
+    #[test]
+    fn requests_at_the_refill_rate_pass() {
+        let limiter = RateLimiter::per_minute(10);
+        let start = Instant::now();
+
+        // One request every six seconds matches the refill rate indefinitely.
+        for i in 0..100 {
+            let now = start + Duration::from_secs(6 * i);
+            assert!(limiter.check("203.0.113.7", now).is_ok());
+        }
+    }
+
+    #[test]
+    fn rapid_anonymous_share_access_is_throttled() {
+        let limits = ShareRateLimits::new(5, 8);
+        let now = Instant::now();
+
+        // One client scraping a leaked token runs out of the token's budget.
+        for _ in 0..5 {
+            assert!(limits.check("leaked", "203.0.113.7", now).is_ok());
+        }
+        assert!(limits.check("leaked", "198.51.100.2", now).is_err());
+        // Cycling through tokens still hits the per-address budget (5 + 3 = 8).
+        for token in ["a", "b", "c"] {
+            assert!(limits.check(token, "203.0.113.7", now).is_ok());
+        }
+        assert!(limits.check("d", "203.0.113.7", now).is_err());
+
+        // A visitor opening a shared document now and then is never refused.
+        let start = now + Duration::from_secs(600);
+        for i in 0..30 {
+            let at = start + Duration::from_secs(15 * i);
+            assert!(limits.check("fresh", "192.0.2.10", at).is_ok());
+        }
+        assert!(
+            ShareRateLimits::default()
+                .check("leaked", "203.0.113.7", now)
+                .is_ok()
+        );
+    }
+}
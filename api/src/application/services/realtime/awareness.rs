@@ -15,16 +15,19 @@ use yrs::sync::{Message, MessageReader};
 use yrs::updates::decoder::DecoderV1;
 use yrs::updates::encoder::Encode;
 
-use crate::application::ports::awareness_port::AwarenessPublisher;
+use crate::application::ports::awareness_port::{AwarenessPublisher, PresenceIdentity};
+use crate::application::services::realtime::presence::PresenceDirectory;
 
 #[derive(Clone)]
 pub struct AwarenessService {
     awareness: Arc<Awareness>,
     last_seen: Arc<Mutex<HashMap<ClientID, Instant>>>,
     ttl: Duration,
+    sweep_interval: Duration,
     publisher: Arc<dyn AwarenessPublisher>,
     doc_id: String,
     local_clients: Arc<Mutex<HashSet<ClientID>>>,
+    presence: Option<(PresenceDirectory, PresenceIdentity)>,
 }
 
 impl AwarenessService {
@@ -38,22 +41,62 @@ impl AwarenessService {
             awareness: Arc::new(Awareness::new(doc)),
             last_seen: Arc::new(Mutex::new(HashMap::new())),
             ttl,
+            sweep_interval: if ttl.is_zero() {
+                Duration::from_secs(10)
+            } else {
+                ttl / 2
+            },
             publisher,
             doc_id: doc_id.into(),
             local_clients: Arc::new(Mutex::new(HashSet::new())),
+            presence: None,
         }
     }
 
+    /// Attributes clients published by this connection to `identity` in `directory`.
+    pub fn with_presence(
+        mut self,
+        directory: PresenceDirectory,
+        identity: PresenceIdentity,
+    ) -> Self {
+        self.presence = Some((directory, identity));
+        self
+    }
+
+    /// How often clients past the TTL are looked for; a client can linger this long past it.
+    pub fn with_sweep_interval(mut self, interval: Duration) -> Self {
+        if !interval.is_zero() {
+            self.sweep_interval = interval;
+        }
+        self
+    }
+
     pub fn awareness(&self) -> Arc<Awareness> {
         self.awareness.clone()
     }
 
     pub async fn apply_remote_frame(&self, frame: &[u8]) -> anyhow::Result<()> {
-        self.process_frame(frame, FrameOrigin::Remote).await
+        self.process_frame(frame, FrameOrigin::Remote, Instant::now())
+            .await
+    }
+
+    /// Applies a frame replayed from the awareness backlog that was published `age` ago.
+    /// Returns false, without applying it, if it is already past the TTL: replaying it would
+    /// bring back clients that left without clearing their state.
+    pub async fn apply_backlog_frame(&self, frame: &[u8], age: Duration) -> anyhow::Result<bool> {
+        if !self.ttl.is_zero() && age > self.ttl {
+            return Ok(false);
+        }
+        let now = Instant::now();
+        let seen_at = now.checked_sub(age).unwrap_or(now);
+        self.process_frame(frame, FrameOrigin::Remote, seen_at)
+            .await?;
+        Ok(true)
     }
 
     pub async fn record_local_frame(&self, frame: &[u8]) -> anyhow::Result<()> {
-        self.process_frame(frame, FrameOrigin::Local).await
+        self.process_frame(frame, FrameOrigin::Local, Instant::now())
+            .await
     }
 
     pub async fn clear_local_clients(&self) -> anyhow::Result<()> {
@@ -74,6 +117,7 @@ impl AwarenessService {
         for client in &clients {
             self.awareness.remove_state(*client);
         }
+        self.forget_presence(&clients);
         if entries.is_empty() {
             return Ok(());
         }
@@ -87,18 +131,32 @@ impl AwarenessService {
         Ok(())
     }
 
-    async fn process_frame(&self, frame: &[u8], origin: FrameOrigin) -> anyhow::Result<()> {
+    async fn process_frame(
+        &self,
+        frame: &[u8],
+        origin: FrameOrigin,
+        seen_at: Instant,
+    ) -> anyhow::Result<()> {
         let mut decoder = DecoderV1::new(Cursor::new(frame));
-        let mut reader = MessageReader::new(&mut decoder);
+        let reader = MessageReader::new(&mut decoder);
         let mut combined = AwarenessUpdateSummary {
             added: Vec::new(),
             updated: Vec::new(),
             removed: Vec::new(),
         };
         let mut any = false;
-        while let Some(message) = reader.next() {
+        let mut published: Vec<(ClientID, Arc<str>)> = Vec::new();
+        for message in reader {
             let message = message?;
             if let Message::Awareness(update) = message {
+                if matches!(origin, FrameOrigin::Local) {
+                    published.extend(
+                        update
+                            .clients
+                            .iter()
+                            .map(|(client, entry)| (*client, entry.json.clone())),
+                    );
+                }
                 if let Some(summary) = self
                     .awareness
                     .apply_update_summary(update)
@@ -112,11 +170,22 @@ impl AwarenessService {
             }
         }
         if any {
-            self.apply_summary(combined, origin).await;
+            self.apply_summary(combined, origin, seen_at).await;
+        }
+        if let Some((directory, identity)) = &self.presence {
+            for (client, json) in published {
+                directory.record(&self.doc_id, client, *identity, &json);
+            }
         }
         Ok(())
     }
 
+    fn forget_presence(&self, clients: &[ClientID]) {
+        if let Some((directory, _)) = &self.presence {
+            directory.forget(&self.doc_id, clients);
+        }
+    }
+
     pub async fn encode_full_state_frame(&self) -> anyhow::Result<Option<Vec<u8>>> {
         let update = self
             .awareness
@@ -133,12 +202,7 @@ impl AwarenessService {
         let manager = self.clone();
         tokio::spawn(async move {
             loop {
-                let sleep_for = if manager.ttl.is_zero() {
-                    Duration::from_secs(10)
-                } else {
-                    manager.ttl / 2
-                };
-                sleep(sleep_for).await;
+                sleep(manager.sweep_interval).await;
                 if let Err(err) = manager.prune_stale().await {
                     tracing::debug!(
                         document_id = %manager.doc_id,
@@ -182,6 +246,7 @@ impl AwarenessService {
         for client in &expired {
             self.awareness.remove_state(*client);
         }
+        self.forget_presence(&expired);
         if entries.is_empty() {
             return Ok(());
         }
@@ -194,8 +259,12 @@ impl AwarenessService {
         Ok(())
     }
 
-    async fn apply_summary(&self, summary: AwarenessUpdateSummary, origin: FrameOrigin) {
-        let now = Instant::now();
+    async fn apply_summary(
+        &self,
+        summary: AwarenessUpdateSummary,
+        origin: FrameOrigin,
+        seen_at: Instant,
+    ) {
         let added: HashSet<ClientID> = summary.added.into_iter().collect();
         let updated: HashSet<ClientID> = summary.updated.into_iter().collect();
         let removed: HashSet<ClientID> = summary.removed.into_iter().collect();
@@ -203,7 +272,7 @@ impl AwarenessService {
         {
             let mut guard = self.last_seen.lock().await;
             for client in added.iter().chain(updated.iter()) {
-                guard.insert(*client, now);
+                guard.insert(*client, seen_at);
             }
             for client in &removed {
                 guard.remove(client);
@@ -252,3 +321,90 @@ enum FrameOrigin {
     Local,
     Remote,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex as StdMutex;
+
+    use async_trait::async_trait;
+    use yrs::sync::awareness::AwarenessUpdateEntry;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Published(StdMutex<Vec<Vec<u8>>>);
+
+    #[async_trait]
+    impl AwarenessPublisher for Published {
+        async fn publish_awareness(&self, _: &str, frame: Vec<u8>) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push(frame);
+            Ok(())
+        }
+    }
+
+    fn awareness_frame(client: ClientID, json: &str) -> Vec<u8> {
+        let entry = AwarenessUpdateEntry {
+            clock: 1,
+            json: Arc::<str>::from(json),
+        };
+        let update = AwarenessUpdate {
+            clients: HashMap::from([(client, entry)]),
+        };
+        Message::Awareness(update).encode_v1()
+    }
+
+    fn decode_update(frame: &[u8]) -> AwarenessUpdate {
+        let mut decoder = DecoderV1::new(Cursor::new(frame));
+        match MessageReader::new(&mut decoder).next() {
+            Some(Ok(Message::Awareness(update))) => update,
+            _ => panic!("not an awareness frame"),
+        }
+    }
+
+    #[tokio::test]
+    async fn silent_client_is_removed_and_removal_published_after_ttl() {
+        let published = Arc::new(Published::default());
+        let ttl = Duration::from_millis(30);
+        let service = AwarenessService::new(Doc::new(), ttl, published.clone(), "doc");
+        service
+            .record_local_frame(&awareness_frame(7, r#"{"user":{"name":"A"}}"#))
+            .await
+            .unwrap();
+
+        service.prune_stale().await.unwrap();
+        assert!(published.0.lock().unwrap().is_empty());
+
+        sleep(ttl * 2).await;
+        service.prune_stale().await.unwrap();
+        assert!(!service.last_seen.lock().await.contains_key(&7));
+        let frames = published.0.lock().unwrap();
+        assert_eq!(frames.len(), 1);
+        let removal = decode_update(&frames[0]);
+        assert_eq!(&*removal.clients[&7].json, "null");
+    }
+
+    #[tokio::test]
+    async fn backlog_frames_past_ttl_are_not_replayed() {
+        let service = AwarenessService::new(
+            Doc::new(),
+            Duration::from_secs(45),
+            Arc::new(Published::default()),
+            "doc",
+        );
+        let frame = awareness_frame(7, r#"{"user":{"name":"A"}}"#);
+        assert!(
+            !service
+                .apply_backlog_frame(&frame, Duration::from_secs(120))
+                .await
+                .unwrap()
+        );
+        assert!(!service.last_seen.lock().await.contains_key(&7));
+        assert!(
+            service
+                .apply_backlog_frame(&frame, Duration::from_secs(5))
+                .await
+                .unwrap()
+        );
+        assert!(service.last_seen.lock().await.contains_key(&7));
+    }
+}
@@ -8,7 +8,9 @@ use yrs::sync::{Message, MessageReader, SyncMessage};
 use yrs::updates::decoder::{Decode, DecoderV1};
 use yrs::{Doc, Transact, Update};
 
-use crate::application::ports::realtime_hydration_port::{DocStateReader, RealtimeBacklogReader};
+use crate::application::ports::realtime_hydration_port::{
+    DocStateReader, RealtimeBacklogReader, StreamFrame,
+};
 use crate::application::ports::storage_port::StoragePort;
 
 pub struct DocHydrationService {
@@ -99,8 +101,8 @@ impl DocHydrationService {
             .read_awareness_backlog(&doc_id_str, options.awareness_start_id)
             .await?;
         for entry in awareness_entries {
-            awareness_frames.push(entry.payload);
-            last_awareness_stream_id = Some(entry.id);
+            last_awareness_stream_id = Some(entry.id.clone());
+            awareness_frames.push(entry);
         }
 
         if options.read_storage_if_empty {
@@ -109,18 +111,17 @@ impl DocHydrationService {
             let is_empty = yrs::Text::len(&txt, &txn) == 0;
             drop(txn);
 
-            if is_empty {
-                if let Some(record) = self.state_reader.document_record(doc_id).await? {
-                    if let Some(path) = record.path {
-                        let absolute = self.storage.absolute_from_relative(&path);
-                        if let Ok(bytes) = self.storage.read_bytes(absolute.as_path()).await {
-                            if let Ok(content) = String::from_utf8(bytes) {
-                                let body = strip_frontmatter(&content);
-                                let mut txn = doc.transact_mut();
-                                yrs::Text::insert(&txt, &mut txn, 0, body);
-                            }
-                        }
-                    }
+            if is_empty
+                && let Some(record) = self.state_reader.document_record(doc_id).await?
+                && let Some(path) = record.path
+            {
+                let absolute = self.storage.absolute_from_relative(&path);
+                if let Ok(bytes) = self.storage.read_bytes(absolute.as_path()).await
+                    && let Ok(content) = String::from_utf8(bytes)
+                {
+                    let body = strip_frontmatter(&content);
+                    let mut txn = doc.transact_mut();
+                    yrs::Text::insert(&txt, &mut txn, 0, body);
                 }
             }
         }
@@ -140,7 +141,7 @@ pub struct HydratedDoc {
     pub last_seq: i64,
     pub last_update_stream_id: Option<String>,
     pub last_awareness_stream_id: Option<String>,
-    pub awareness_frames: Vec<Vec<u8>>,
+    pub awareness_frames: Vec<StreamFrame>,
 }
 
 fn apply_update_bytes(doc: &Doc, bytes: &[u8]) -> anyhow::Result<()> {
@@ -152,9 +153,9 @@ fn apply_update_bytes(doc: &Doc, bytes: &[u8]) -> anyhow::Result<()> {
 
 fn extract_updates(frame: &[u8]) -> anyhow::Result<Vec<Update>> {
     let mut decoder = DecoderV1::new(Cursor::new(frame));
-    let mut reader = MessageReader::new(&mut decoder);
+    let reader = MessageReader::new(&mut decoder);
     let mut updates = Vec::new();
-    while let Some(message) = reader.next() {
+    for message in reader {
         match message? {
             Message::Sync(SyncMessage::Update(bin))
             | Message::Sync(SyncMessage::SyncStep2(bin)) => {
@@ -168,10 +169,9 @@ fn extract_updates(frame: &[u8]) -> anyhow::Result<Vec<Update>> {
 }
 
 fn strip_frontmatter(content: &str) -> &str {
-    if content.starts_with("---\n") {
-        if let Some(idx) = content[4..].find("\n---\n") {
-            let start = 4 + idx + 5;
-            &content[start..]
+    if let Some(rest) = content.strip_prefix("---\n") {
+        if let Some(idx) = rest.find("\n---\n") {
+            &rest[idx + 5..]
         } else {
             content
         }
@@ -0,0 +1,24 @@
+use yrs::encoding::read::Cursor;
+use yrs::sync::{Message, MessageReader};
+use yrs::updates::decoder::DecoderV1;
+use yrs::updates::encoder::Encode;
+
+/// Message type announcing a document's lock state to clients. The payload is a one-byte
+/// buffer: 1 when locked, 0 when unlocked.
+pub const MSG_DOC_LOCK: u8 = 101;
+
+pub fn encode_lock_frame(locked: bool) -> Vec<u8> {
+    Message::Custom(MSG_DOC_LOCK, vec![locked as u8]).encode_v1()
+}
+
+/// Lock state carried by `frame`, if it is a lock frame. Only frames holding nothing but the
+/// lock message count, so a client cannot smuggle one in alongside its awareness update.
+pub fn decode_lock_frame(frame: &[u8]) -> Option<bool> {
+    let mut decoder = DecoderV1::new(Cursor::new(frame));
+    let mut reader = MessageReader::new(&mut decoder);
+    let locked = match reader.next()? {
+        Ok(Message::Custom(MSG_DOC_LOCK, payload)) => payload.first().is_some_and(|b| *b == 1),
+        _ => return None,
+    };
+    reader.next().is_none().then_some(locked)
+}
@@ -1,3 +1,6 @@
 pub mod awareness;
 pub mod doc_hydration;
+pub mod doc_lock;
+pub mod presence;
+pub mod reconcile;
 pub mod snapshot;
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+use yrs::block::ClientID;
+
+use crate::application::ports::awareness_port::{PresenceEntry, PresenceIdentity};
+use crate::application::ports::user_repository::UserRepository;
+
+struct ClientPresence {
+    identity: PresenceIdentity,
+    state: serde_json::Value,
+}
+
+type CachedName = (Option<String>, Instant);
+
+/// Tracks which connection identity published each awareness client, so presence can carry
+/// names from the user directory instead of the names clients report for themselves.
+#[derive(Clone)]
+pub struct PresenceDirectory {
+    users: Arc<dyn UserRepository>,
+    name_ttl: Duration,
+    docs: Arc<Mutex<HashMap<String, HashMap<ClientID, ClientPresence>>>>,
+    names: Arc<Mutex<HashMap<Uuid, CachedName>>>,
+}
+
+impl PresenceDirectory {
+    pub fn new(users: Arc<dyn UserRepository>, name_ttl: Duration) -> Self {
+        Self {
+            users,
+            name_ttl,
+            docs: Arc::new(Mutex::new(HashMap::new())),
+            names: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Records the awareness state a connection published for `client`; a `null` state means
+    /// the client left.
+    pub fn record(
+        &self,
+        doc_id: &str,
+        client: ClientID,
+        identity: PresenceIdentity,
+        state_json: &str,
+    ) {
+        let state: serde_json::Value =
+            serde_json::from_str(state_json).unwrap_or(serde_json::Value::Null);
+        if state.is_null() {
+            self.forget(doc_id, &[client]);
+            return;
+        }
+        self.docs
+            .lock()
+            .unwrap()
+            .entry(doc_id.to_string())
+            .or_default()
+            .insert(client, ClientPresence { identity, state });
+    }
+
+    pub fn forget(&self, doc_id: &str, clients: &[ClientID]) {
+        let mut docs = self.docs.lock().unwrap();
+        if let Some(entries) = docs.get_mut(doc_id) {
+            for client in clients {
+                entries.remove(client);
+            }
+            if entries.is_empty() {
+                docs.remove(doc_id);
+            }
+        }
+    }
+
+    /// Clients present in the document, ordered by client id.
+    pub async fn presence(&self, doc_id: &str) -> anyhow::Result<Vec<PresenceEntry>> {
+        let mut clients: Vec<(ClientID, PresenceIdentity, serde_json::Value)> = self
+            .docs
+            .lock()
+            .unwrap()
+            .get(doc_id)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .map(|(client, p)| (*client, p.identity, p.state.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        clients.sort_by_key(|(client, _, _)| *client);
+
+        let mut entries = Vec::with_capacity(clients.len());
+        for (client_id, identity, state) in clients {
+            let display_name = match identity {
+                PresenceIdentity::User(user_id) => self.display_name(user_id).await?,
+                PresenceIdentity::Anonymous => None,
+            };
+            entries.push(PresenceEntry {
+                client_id,
+                identity,
+                display_name,
+                state,
+            });
+        }
+        Ok(entries)
+    }
+
+    async fn display_name(&self, user_id: Uuid) -> anyhow::Result<Option<String>> {
+        let cached = self
+            .names
+            .lock()
+            .unwrap()
+            .get(&user_id)
+            .filter(|(_, at)| at.elapsed() < self.name_ttl)
+            .map(|(name, _)| name.clone());
+        if let Some(name) = cached {
+            return Ok(name);
+        }
+        let name = self.users.find_by_id(user_id).await?.map(|user| user.name);
+        self.names
+            .lock()
+            .unwrap()
+            .insert(user_id, (name.clone(), Instant::now()));
+        Ok(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use async_trait::async_trait;
+    use yrs::Doc;
+    use yrs::sync::Message;
+    use yrs::sync::awareness::{AwarenessUpdate, AwarenessUpdateEntry};
+    use yrs::updates::encoder::Encode;
+
+    use super::*;
+    use crate::application::ports::awareness_port::AwarenessPublisher;
+    use crate::application::services::realtime::awareness::AwarenessService;
+    use crate::test_support::users::MemoryUsers;
+
+    struct Silent;
+
+    #[async_trait]
+    impl AwarenessPublisher for Silent {
+        async fn publish_awareness(&self, _: &str, _: Vec<u8>) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn awareness_frame(client: ClientID, json: &str) -> Vec<u8> {
+        let entry = AwarenessUpdateEntry {
+            clock: 1,
+            json: Arc::<str>::from(json),
+        };
+        let update = AwarenessUpdate {
+            clients: HashMap::from([(client, entry)]),
+        };
+        Message::Awareness(update).encode_v1()
+    }
+
+    #[tokio::test]
+    async fn authenticated_clients_show_the_server_resolved_name() {
+        let users = Arc::new(MemoryUsers::default());
+        let alice = users.add("Alice", "alice@example.com");
+        let directory = PresenceDirectory::new(users.clone(), Duration::from_secs(60));
+        let doc_id = Uuid::new_v4().to_string();
+        let connection = |identity| {
+            AwarenessService::new(Doc::new(), Duration::ZERO, Arc::new(Silent), &doc_id)
+                .with_presence(directory.clone(), identity)
+        };
+        let claimed = r#"{"user":{"name":"Admin"}}"#;
+
+        connection(PresenceIdentity::User(alice.id))
+            .record_local_frame(&awareness_frame(1, claimed))
+            .await
+            .unwrap();
+        connection(PresenceIdentity::Anonymous)
+            .record_local_frame(&awareness_frame(2, claimed))
+            .await
+            .unwrap();
+
+        let presence = directory.presence(&doc_id).await.unwrap();
+        assert_eq!(presence.len(), 2);
+        assert_eq!(presence[0].identity, PresenceIdentity::User(alice.id));
+        assert_eq!(presence[0].display_name.as_deref(), Some("Alice"));
+        assert_eq!(presence[0].state["user"]["name"], "Admin");
+        assert_eq!(presence[1].identity, PresenceIdentity::Anonymous);
+        assert_eq!(presence[1].display_name, None);
+
+        // Names are cached between lookups.
+        directory.presence(&doc_id).await.unwrap();
+        assert_eq!(users.lookups.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::application::ports::realtime_hydration_port::DocStateReader;
+use crate::application::ports::realtime_persistence_port::PersistenceTaskProducerPort;
+
+/// Catches documents the event-driven persistence worker missed: any document whose update log
+/// has been idle for `idle_after` and has not been snapshotted since its last update gets a task.
+pub struct SnapshotReconciler {
+    tasks: Arc<dyn PersistenceTaskProducerPort>,
+    state_reader: Arc<dyn DocStateReader>,
+    idle_after: Duration,
+}
+
+impl SnapshotReconciler {
+    pub fn new(
+        tasks: Arc<dyn PersistenceTaskProducerPort>,
+        state_reader: Arc<dyn DocStateReader>,
+        idle_after: Duration,
+    ) -> Self {
+        Self {
+            tasks,
+            state_reader,
+            idle_after,
+        }
+    }
+
+    /// Enqueues persistence tasks for stale documents and returns their ids. Documents whose
+    /// updates were snapshotted (or trimmed from the log) are left alone, so repeated sweeps
+    /// only re-enqueue work the worker has not finished.
+    pub async fn sweep(&self, now: DateTime<Utc>) -> anyhow::Result<Vec<Uuid>> {
+        let mut enqueued = Vec::new();
+        for pending in self.tasks.pending_updates().await? {
+            if now - pending.last_update_at < self.idle_after {
+                continue;
+            }
+            let snapshot_at = self
+                .state_reader
+                .latest_snapshot_at(&pending.document_id)
+                .await?;
+            if snapshot_at.is_some_and(|at| at >= pending.last_update_at) {
+                continue;
+            }
+            self.tasks.enqueue_task(&pending.document_id).await?;
+            enqueued.push(pending.document_id);
+        }
+        Ok(enqueued)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::application::ports::realtime_hydration_port::{
+        DocSnapshot, DocUpdate, DocumentRecord,
+    };
+    use crate::application::ports::realtime_persistence_port::PendingUpdates;
+
+    #[derive(Default)]
+    struct Log {
+        pending: Vec<PendingUpdates>,
+        snapshots: HashMap<Uuid, DateTime<Utc>>,
+        enqueued: Mutex<Vec<Uuid>>,
+    }
+
+    #[async_trait]
+    impl PersistenceTaskProducerPort for Log {
+        async fn pending_updates(&self) -> anyhow::Result<Vec<PendingUpdates>> {
+            Ok(self.pending.clone())
+        }
+
+        async fn enqueue_task(&self, doc_id: &Uuid) -> anyhow::Result<()> {
+            self.enqueued.lock().unwrap().push(*doc_id);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl DocStateReader for Log {
+        async fn latest_snapshot(&self, _: &Uuid) -> anyhow::Result<Option<DocSnapshot>> {
+            unimplemented!()
+        }
+
+        async fn latest_snapshot_at(&self, doc_id: &Uuid) -> anyhow::Result<Option<DateTime<Utc>>> {
+            Ok(self.snapshots.get(doc_id).copied())
+        }
+
+        async fn updates_since(&self, _: &Uuid, _: i64) -> anyhow::Result<Vec<DocUpdate>> {
+            unimplemented!()
+        }
+
+        async fn document_record(&self, _: &Uuid) -> anyhow::Result<Option<DocumentRecord>> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn stale_unsnapshotted_documents_get_a_task() {
+        let now = Utc::now();
+        let (stale, fresh, snapshotted, never) = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+        );
+        let pending = |document_id, minutes_ago| PendingUpdates {
+            document_id,
+            last_update_at: now - Duration::minutes(minutes_ago),
+        };
+        let log = Arc::new(Log {
+            pending: vec![
+                pending(stale, 10),
+                pending(fresh, 0),
+                pending(snapshotted, 10),
+                pending(never, 10),
+            ],
+            snapshots: HashMap::from([
+                (stale, now - Duration::minutes(30)),
+                (snapshotted, now - Duration::minutes(5)),
+            ]),
+            ..Default::default()
+        });
+        let reconciler = SnapshotReconciler::new(log.clone(), log.clone(), Duration::minutes(1));
+
+        let enqueued = reconciler.sweep(now).await.unwrap();
+
+        assert_eq!(enqueued, vec![stale, never]);
+        assert_eq!(*log.enqueued.lock().unwrap(), vec![stale, never]);
+    }
+}
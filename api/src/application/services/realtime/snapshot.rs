@@ -1,14 +1,26 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use uuid::Uuid;
-use yrs::{Doc, GetString, ReadTxn, StateVector, Transact};
+use yrs::updates::decoder::Decode;
+use yrs::{Doc, GetString, ReadTxn, StateVector, Text, TextRef, Transact, TransactionMut, Update};
 
 use crate::application::linkgraph;
+use crate::application::linkgraph::mentions;
+use crate::application::ports::document_retention_repository::{
+    DocumentRetention, DocumentRetentionRepository,
+};
 use crate::application::ports::linkgraph_repository::LinkGraphRepository;
+use crate::application::ports::mention_repository::MentionRepository;
+use crate::application::ports::notifier::Notifier;
 use crate::application::ports::realtime_hydration_port::DocStateReader;
 use crate::application::ports::realtime_persistence_port::DocPersistencePort;
+use crate::application::ports::realtime_port::content_version;
 use crate::application::ports::storage_port::StoragePort;
 use crate::application::ports::tagging_repository::TaggingRepository;
+use crate::application::ports::user_repository::UserRepository;
+use crate::application::services::front_matter::FrontMatter;
+use crate::application::services::notifications;
 use crate::application::services::tagging;
 
 pub struct SnapshotService {
@@ -17,20 +29,42 @@ pub struct SnapshotService {
     storage: Arc<dyn StoragePort>,
     linkgraph_repo: Arc<dyn LinkGraphRepository>,
     tagging_repo: Arc<dyn TaggingRepository>,
+    retention_repo: Arc<dyn DocumentRetentionRepository>,
+    derive_titles: bool,
+    mention_repos: Option<(Arc<dyn UserRepository>, Arc<dyn MentionRepository>)>,
+    notifier: Option<Arc<dyn Notifier>>,
 }
 
+#[derive(Default)]
 pub struct SnapshotPersistOptions {
     pub clear_updates: bool,
     pub prune_snapshots: Option<i64>,
     pub prune_updates_before: Option<i64>,
 }
 
-impl Default for SnapshotPersistOptions {
-    fn default() -> Self {
-        Self {
+/// Snapshot/update retention applied when a document is snapshotted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub keep_versions: i64,
+    pub updates_keep_window: i64,
+}
+
+impl RetentionPolicy {
+    pub fn with_override(self, retention: Option<&DocumentRetention>) -> Self {
+        match retention {
+            Some(r) => Self {
+                keep_versions: r.snapshot_keep_versions.unwrap_or(self.keep_versions),
+                updates_keep_window: r.updates_keep_window.unwrap_or(self.updates_keep_window),
+            },
+            None => self,
+        }
+    }
+
+    pub fn persist_options(&self, current_seq: i64) -> SnapshotPersistOptions {
+        SnapshotPersistOptions {
             clear_updates: false,
-            prune_snapshots: None,
-            prune_updates_before: None,
+            prune_snapshots: Some(self.keep_versions),
+            prune_updates_before: Some((current_seq - self.updates_keep_window).max(0)),
         }
     }
 }
@@ -51,6 +85,7 @@ impl SnapshotService {
         storage: Arc<dyn StoragePort>,
         linkgraph_repo: Arc<dyn LinkGraphRepository>,
         tagging_repo: Arc<dyn TaggingRepository>,
+        retention_repo: Arc<dyn DocumentRetentionRepository>,
     ) -> Self {
         Self {
             state_reader,
@@ -58,9 +93,55 @@ impl SnapshotService {
             storage,
             linkgraph_repo,
             tagging_repo,
+            retention_repo,
+            derive_titles: false,
+            mention_repos: None,
+            notifier: None,
         }
     }
 
+    /// Lets saves replace a placeholder title with the content's front matter title or first H1.
+    pub fn with_title_from_content(mut self, enabled: bool) -> Self {
+        self.derive_titles = enabled;
+        self
+    }
+
+    /// Lets saves resolve `@[[user]]` mentions and record them for the mentioned users.
+    pub fn with_mentions(
+        mut self,
+        user_repo: Arc<dyn UserRepository>,
+        mention_repo: Arc<dyn MentionRepository>,
+    ) -> Self {
+        self.mention_repos = Some((user_repo, mention_repo));
+        self
+    }
+
+    /// Notifies users when a save mentions them for the first time.
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Resolves the effective retention for a batch of documents with a single lookup.
+    /// Lookup failures fall back to `defaults` so snapshotting never stalls on them.
+    pub async fn resolve_retention(
+        &self,
+        doc_ids: &[Uuid],
+        defaults: RetentionPolicy,
+    ) -> HashMap<Uuid, RetentionPolicy> {
+        let overrides = match self.retention_repo.get_many(doc_ids).await {
+            Ok(map) => map,
+            Err(e) => {
+                tracing::warn!(error = ?e, "retention_overrides_load_failed");
+                HashMap::new()
+            }
+        };
+        doc_ids
+            .iter()
+            .map(|id| (*id, defaults.with_override(overrides.get(id))))
+            .collect()
+    }
+
     pub async fn persist_snapshot(
         &self,
         doc_id: &Uuid,
@@ -71,29 +152,7 @@ impl SnapshotService {
             let txn = doc.transact();
             txn.encode_state_as_update_v1(&StateVector::default())
         };
-        let current_version = self
-            .persistence
-            .latest_snapshot_version(doc_id)
-            .await?
-            .unwrap_or(0);
-        let next_version = current_version + 1;
-        self.persistence
-            .persist_snapshot(doc_id, next_version, &snapshot_bin)
-            .await?;
-        if options.clear_updates {
-            self.persistence.clear_updates(doc_id).await?;
-        }
-        if let Some(keep) = options.prune_snapshots {
-            self.persistence.prune_snapshots(doc_id, keep).await?;
-        }
-        if let Some(cutoff) = options.prune_updates_before {
-            self.persistence
-                .prune_updates_before(doc_id, cutoff)
-                .await?;
-        }
-        Ok(SnapshotPersistResult {
-            version: next_version,
-        })
+        store_snapshot(self.persistence.as_ref(), doc_id, &snapshot_bin, options).await
     }
 
     pub async fn write_markdown(
@@ -109,6 +168,18 @@ impl SnapshotService {
             return Ok(MarkdownPersistResult { written: false });
         }
         let contents = extract_markdown(doc);
+        let mut record = record;
+        if self.derive_titles
+            && is_placeholder_title(&record.title)
+            && let Some(title) = derive_title(&contents)
+        {
+            match self.persistence.update_title(doc_id, &title).await {
+                Ok(()) => record.title = title,
+                Err(e) => {
+                    tracing::warn!(document_id = %doc_id, error = ?e, "document_title_derive_failed")
+                }
+            }
+        }
         let _ = self.storage.sync_doc_paths(*doc_id).await;
         let path = self.storage.build_doc_file_path(*doc_id).await?;
         let mut formatted = format!(
@@ -124,7 +195,23 @@ impl SnapshotService {
             Err(_) => true,
         };
         if should_write {
+            // The old hash must not outlive the file it describes: git sync would skip reading
+            // the new content if recording the new hash below failed.
+            self.persistence.clear_content_hash(doc_id).await?;
             self.storage.write_bytes(path.as_path(), &bytes).await?;
+            // Only real changes (content or the title in the front matter) count as edits;
+            // periodic saves of an unchanged document leave recency alone.
+            if let Err(e) = self.persistence.touch_document(doc_id).await {
+                tracing::warn!(document_id = %doc_id, error = ?e, "document_touch_failed");
+            }
+        }
+        // Recorded even when unchanged so documents saved before hashes existed pick one up.
+        if let Err(e) = self
+            .persistence
+            .record_content_hash(doc_id, &sha256_hex(&bytes))
+            .await
+        {
+            tracing::warn!(document_id = %doc_id, error = ?e, "document_content_hash_failed");
         }
         if let Some(owner_id) = record.owner_id {
             let _ = linkgraph::update_document_links(
@@ -141,6 +228,39 @@ impl SnapshotService {
                 &contents,
             )
             .await;
+            if let Some((user_repo, mention_repo)) = &self.mention_repos {
+                match mentions::update_document_mentions(
+                    user_repo.as_ref(),
+                    mention_repo.as_ref(),
+                    *doc_id,
+                    owner_id,
+                    &contents,
+                )
+                .await
+                {
+                    Ok(added) => {
+                        for user_id in added {
+                            tracing::info!(
+                                document_id = %doc_id,
+                                user_id = %user_id,
+                                mentioned_by = %owner_id,
+                                "document_mention_added"
+                            );
+                            let Some(notifier) = &self.notifier else {
+                                continue;
+                            };
+                            let notification =
+                                notifications::mention(user_id, *doc_id, owner_id, &record.title);
+                            if let Err(e) = notifier.notify(notification).await {
+                                tracing::warn!(document_id = %doc_id, user_id = %user_id, error = ?e, "mention_notify_failed");
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!(document_id = %doc_id, error = ?e, "document_mentions_update_failed")
+                    }
+                }
+            }
         }
         Ok(MarkdownPersistResult {
             written: should_write,
@@ -148,9 +268,356 @@ impl SnapshotService {
     }
 }
 
+async fn store_snapshot(
+    persistence: &dyn DocPersistencePort,
+    doc_id: &Uuid,
+    snapshot_bin: &[u8],
+    options: SnapshotPersistOptions,
+) -> anyhow::Result<SnapshotPersistResult> {
+    let current_version = persistence
+        .latest_snapshot_version(doc_id)
+        .await?
+        .unwrap_or(0);
+    let next_version = current_version + 1;
+    persistence
+        .persist_snapshot(doc_id, next_version, snapshot_bin)
+        .await?;
+    if options.clear_updates {
+        persistence.clear_updates(doc_id).await?;
+    }
+    if let Some(keep) = options.prune_snapshots {
+        persistence.prune_snapshots(doc_id, keep).await?;
+    }
+    if let Some(cutoff) = options.prune_updates_before {
+        persistence.prune_updates_before(doc_id, cutoff).await?;
+    }
+    Ok(SnapshotPersistResult {
+        version: next_version,
+    })
+}
+
+const PLACEHOLDER_TITLE: &str = "Untitled";
+const MAX_DERIVED_TITLE_CHARS: usize = 255;
+
+/// Titles assigned on creation ("Untitled", "Untitled (copy)") that the user has not replaced.
+fn is_placeholder_title(title: &str) -> bool {
+    let title = title.trim();
+    title.is_empty()
+        || title == PLACEHOLDER_TITLE
+        || title
+            .strip_prefix(PLACEHOLDER_TITLE)
+            .is_some_and(|rest| rest.starts_with(" (") && rest.ends_with(')'))
+}
+
+/// Front matter `title`, else the first ATX level-1 heading outside code fences.
+fn derive_title(contents: &str) -> Option<String> {
+    let front_matter = FrontMatter::parse(contents);
+    if let Some(title) = front_matter.as_ref().and_then(|fm| fm.scalar("title")) {
+        return Some(truncate_title(&title));
+    }
+    let body = front_matter.map(|fm| fm.body()).unwrap_or(contents);
+    let mut fence: Option<&str> = None;
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if let Some(open) = fence {
+            if trimmed.starts_with(open) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+        let Some(heading) = trimmed.strip_prefix('#') else {
+            continue;
+        };
+        if !(heading.is_empty() || heading.starts_with([' ', '\t'])) {
+            continue;
+        }
+        let text = heading.trim().trim_end_matches('#').trim();
+        if !text.is_empty() {
+            return Some(truncate_title(text));
+        }
+    }
+    None
+}
+
+fn truncate_title(title: &str) -> String {
+    title.trim().chars().take(MAX_DERIVED_TITLE_CHARS).collect()
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::Digest;
+    format!("{:x}", sha2::Sha256::digest(bytes))
+}
+
 fn extract_markdown(doc: &Doc) -> String {
     let txt = doc.get_or_insert_text("content");
     let txn = doc.transact();
-    let contents = txt.get_string(&txn);
-    contents
+
+    txt.get_string(&txn)
+}
+
+/// Markdown content captured in a stored snapshot.
+pub fn markdown_from_snapshot(snapshot: &[u8]) -> anyhow::Result<String> {
+    let doc = Doc::new();
+    let update = Update::decode_v1(snapshot)?;
+    doc.transact_mut().apply_update(update)?;
+    Ok(extract_markdown(&doc))
+}
+
+/// Replaces the document's content in a single transaction and returns the resulting update,
+/// so it can be persisted and broadcast like any client edit.
+pub fn replace_content(doc: &Doc, content: &str) -> Vec<u8> {
+    let txt = doc.get_or_insert_text("content");
+    let mut txn = doc.transact_mut();
+    replace_in_txn(&txt, &mut txn, content)
+}
+
+/// Like [`replace_content`], but only when the current content still has `expected_version`;
+/// the check and the replacement happen in one transaction. Returns the current content on
+/// a mismatch.
+pub fn replace_content_if(
+    doc: &Doc,
+    expected_version: &str,
+    content: &str,
+) -> Result<Vec<u8>, String> {
+    let txt = doc.get_or_insert_text("content");
+    let mut txn = doc.transact_mut();
+    let current = txt.get_string(&txn);
+    if content_version(&current) != expected_version {
+        return Err(current);
+    }
+    Ok(replace_in_txn(&txt, &mut txn, content))
+}
+
+fn replace_in_txn(txt: &TextRef, txn: &mut TransactionMut, content: &str) -> Vec<u8> {
+    let len = txt.len(&*txn);
+    if len > 0 {
+        txt.remove_range(txn, 0, len);
+    }
+    txt.insert(txn, 0, content);
+    txn.encode_update_v1()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::realtime::MemoryDocStore;
+    use std::path::PathBuf;
+
+    fn service(store: &Arc<MemoryDocStore>) -> SnapshotService {
+        SnapshotService::new(
+            store.clone(),
+            store.clone(),
+            store.storage.clone(),
+            store.clone(),
+            store.clone(),
+            store.clone(),
+        )
+    }
+
+    fn doc_with(text: &str) -> Doc {
+        let doc = Doc::new();
+        let content = doc.get_or_insert_text("content");
+        let mut txn = doc.transact_mut();
+        content.push(&mut txn, text);
+        drop(txn);
+        doc
+    }
+
+    #[tokio::test]
+    async fn only_real_edits_bump_recency() {
+        let store = Arc::new(MemoryDocStore::default());
+        let service = service(&store);
+        let (older, newer) = (Uuid::new_v4(), Uuid::new_v4());
+        store
+            .titles
+            .lock()
+            .unwrap()
+            .extend([(older, "Older".to_string()), (newer, "Newer".to_string())]);
+
+        service
+            .write_markdown(&older, &doc_with("a"))
+            .await
+            .unwrap();
+        service
+            .write_markdown(&newer, &doc_with("b"))
+            .await
+            .unwrap();
+        assert_eq!(*store.touched.lock().unwrap(), vec![older, newer]);
+
+        // Editing the older document moves it to the front of the recency order.
+        let written = service
+            .write_markdown(&older, &doc_with("a, edited"))
+            .await
+            .unwrap();
+        assert!(written.written);
+        assert_eq!(store.touched.lock().unwrap().last(), Some(&older));
+
+        // A periodic save without changes is not an edit.
+        let written = service
+            .write_markdown(&newer, &doc_with("b"))
+            .await
+            .unwrap();
+        assert!(!written.written);
+        assert_eq!(store.touched.lock().unwrap().len(), 3);
+
+        // Renaming rewrites the front matter, so it counts as an edit too.
+        store
+            .titles
+            .lock()
+            .unwrap()
+            .insert(newer, "Renamed".to_string());
+        service
+            .write_markdown(&newer, &doc_with("b"))
+            .await
+            .unwrap();
+        assert_eq!(store.touched.lock().unwrap().last(), Some(&newer));
+    }
+
+    #[tokio::test]
+    async fn failed_hash_update_does_not_leave_a_stale_hash() {
+        let store = Arc::new(MemoryDocStore::default());
+        let service = service(&store);
+        let doc_id = Uuid::new_v4();
+        store
+            .titles
+            .lock()
+            .unwrap()
+            .insert(doc_id, "Doc".to_string());
+
+        service
+            .write_markdown(&doc_id, &doc_with("a"))
+            .await
+            .unwrap();
+        assert!(store.hashes.lock().unwrap().contains_key(&doc_id));
+
+        *store.fail_hash.lock().unwrap() = true;
+        let written = service
+            .write_markdown(&doc_id, &doc_with("a, edited"))
+            .await
+            .unwrap();
+        assert!(written.written);
+        assert!(!store.hashes.lock().unwrap().contains_key(&doc_id));
+    }
+
+    #[tokio::test]
+    async fn placeholder_titles_follow_the_first_heading() {
+        let store = Arc::new(MemoryDocStore::default());
+        let service = service(&store).with_title_from_content(true);
+        let (untitled, copy, named) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        store.titles.lock().unwrap().extend([
+            (untitled, "Untitled".to_string()),
+            (copy, "Untitled (copy)".to_string()),
+            (named, "My Plan".to_string()),
+        ]);
+
+        let content = "```\n# not a heading\n```\n\n# Project Kickoff #\n\nNotes";
+        for doc_id in [untitled, named] {
+            service
+                .write_markdown(&doc_id, &doc_with(content))
+                .await
+                .unwrap();
+        }
+        service
+            .write_markdown(
+                &copy,
+                &doc_with("---\ntitle: \"From Front Matter\"\n---\n# Heading"),
+            )
+            .await
+            .unwrap();
+
+        let renamed = store.renamed.lock().unwrap().clone();
+        assert_eq!(
+            renamed.get(&untitled).map(String::as_str),
+            Some("Project Kickoff")
+        );
+        assert_eq!(
+            renamed.get(&copy).map(String::as_str),
+            Some("From Front Matter")
+        );
+        // A title the user chose is never replaced.
+        assert!(!renamed.contains_key(&named));
+
+        let files = store.storage.files.lock().unwrap();
+        let written =
+            String::from_utf8(files[&PathBuf::from(format!("{}.md", untitled))].clone()).unwrap();
+        assert!(written.contains("title: Project Kickoff\n"));
+    }
+
+    #[test]
+    fn placeholder_detection_and_heading_extraction() {
+        assert!(is_placeholder_title("Untitled"));
+        assert!(is_placeholder_title("Untitled (2)"));
+        assert!(!is_placeholder_title("Untitled notes"));
+        assert_eq!(
+            derive_title("intro\n#hashtag\n##  Sub\n#  Main  "),
+            Some("Main".into())
+        );
+        assert_eq!(derive_title("no headings"), None);
+    }
+
+    #[test]
+    fn conditional_replace_checks_the_current_version() {
+        let doc = doc_with("one");
+        let current = replace_content_if(&doc, &content_version("stale"), "two").unwrap_err();
+        assert_eq!(current, "one");
+        assert!(replace_content_if(&doc, &content_version("one"), "two").is_ok());
+        assert_eq!(extract_markdown(&doc), "two");
+    }
+
+    #[test]
+    fn override_replaces_only_configured_fields() {
+        let defaults = RetentionPolicy {
+            keep_versions: 5,
+            updates_keep_window: 500,
+        };
+        assert_eq!(defaults.with_override(None), defaults);
+        let custom = DocumentRetention {
+            snapshot_keep_versions: Some(2),
+            updates_keep_window: None,
+        };
+        assert_eq!(
+            defaults.with_override(Some(&custom)),
+            RetentionPolicy {
+                keep_versions: 2,
+                updates_keep_window: 500,
+            }
+        );
+        let options = defaults.persist_options(120);
+        assert_eq!(options.prune_snapshots, Some(5));
+        assert_eq!(options.prune_updates_before, Some(0));
+    }
+
+    #[tokio::test]
+    async fn custom_retention_keeps_configured_snapshot_count() {
+        let store = MemoryDocStore::default();
+        let defaults = RetentionPolicy {
+            keep_versions: 5,
+            updates_keep_window: 500,
+        };
+        let custom_doc = Uuid::new_v4();
+        let default_doc = Uuid::new_v4();
+        let overrides = HashMap::from([(
+            custom_doc,
+            DocumentRetention {
+                snapshot_keep_versions: Some(2),
+                updates_keep_window: None,
+            },
+        )]);
+
+        for round in 0..8 {
+            for doc_id in [custom_doc, default_doc] {
+                let policy = defaults.with_override(overrides.get(&doc_id));
+                store_snapshot(&store, &doc_id, b"snap", policy.persist_options(round))
+                    .await
+                    .unwrap();
+            }
+        }
+
+        assert_eq!(store.versions(&custom_doc), vec![7, 8]);
+        assert_eq!(store.versions(&default_doc), vec![4, 5, 6, 7, 8]);
+    }
 }
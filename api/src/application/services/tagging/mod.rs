@@ -1,12 +1,18 @@
+use std::collections::HashSet;
+
 use crate::application::ports::tagging_repository::TaggingRepository;
+use crate::application::services::front_matter::FrontMatter;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use uuid::Uuid;
 
-static TAG_RE: Lazy<Regex> = Lazy::new(|| {
-    // same ranges as frontend hashtag plugin
-    Regex::new(r"\B#([a-zA-Z0-9\u{3040}-\u{309F}\u{30A0}-\u{30FF}\u{4E00}-\u{9FAF}\u{3400}-\u{4DBF}\u{AC00}-\u{D7AF}_-]+)").unwrap()
-});
+// same ranges as frontend hashtag plugin
+const TAG_CHARS: &str = r"a-zA-Z0-9\u{3040}-\u{309F}\u{30A0}-\u{30FF}\u{4E00}-\u{9FAF}\u{3400}-\u{4DBF}\u{AC00}-\u{D7AF}_-";
+const MAX_TAG_CHARS: usize = 64;
+
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(&format!(r"\B#([{}]+)", TAG_CHARS)).unwrap());
+static TAG_NAME_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(&format!(r"^[{}]+$", TAG_CHARS)).unwrap());
 
 pub async fn update_document_tags<R: TaggingRepository + ?Sized>(
     repo: &R,
@@ -14,29 +20,273 @@ pub async fn update_document_tags<R: TaggingRepository + ?Sized>(
     owner_id: Uuid,
     content: &str,
 ) -> anyhow::Result<()> {
-    use std::collections::HashSet;
-    let mut set: HashSet<String> = HashSet::new();
+    let existing: HashSet<String> = repo.document_tag_names(doc_id).await?.into_iter().collect();
+    let wanted = extract_tags(content);
+    let mut added: Vec<String> = wanted.difference(&existing).cloned().collect();
+    let mut removed: Vec<String> = existing.difference(&wanted).cloned().collect();
+    // only the changed associations are written; an unchanged save writes nothing
+    if added.is_empty() && removed.is_empty() {
+        return Ok(());
+    }
+    // tags only change if the document belongs to owner
+    if !repo.owner_doc_exists(doc_id, owner_id).await? {
+        return Ok(());
+    }
+    if !removed.is_empty() {
+        removed.sort();
+        repo.remove_document_tags(doc_id, &removed).await?;
+    }
+    if !added.is_empty() {
+        added.sort();
+        // upsert tags (global unique by name) and associations, one statement each
+        let tag_ids = repo.upsert_tags_return_ids(&added).await?;
+        repo.associate_document_tags(doc_id, &tag_ids).await?;
+    }
+    Ok(())
+}
+
+/// Normalized tag names from inline `#hashtags` and a front matter `tags` list.
+pub fn extract_tags(content: &str) -> HashSet<String> {
+    let mut set = HashSet::new();
     for cap in TAG_RE.captures_iter(content) {
-        if let Some(m) = cap.get(1) {
-            let mut t = m.as_str().to_string();
-            if t.len() > 64 {
-                t.truncate(64);
-            }
-            if !t.is_empty() {
-                set.insert(t.to_lowercase());
-            }
-        }
-    }
-    // clear existing
-    repo.clear_document_tags(doc_id).await?;
-    // insert tags and associations
-    for name in set {
-        // upsert tag (global unique by name)
-        let tag_id = repo.upsert_tag_return_id(&name).await?;
-        // associate if document belongs to owner
-        if repo.owner_doc_exists(doc_id, owner_id).await? {
-            repo.associate_document_tag(doc_id, tag_id).await?;
+        if let Some(tag) = cap.get(1).and_then(|m| normalize_tag(m.as_str())) {
+            set.insert(tag);
         }
     }
-    Ok(())
+    let front_matter = FrontMatter::parse(content);
+    for raw in front_matter.iter().flat_map(|fm| fm.list("tags")) {
+        if let Some(tag) = normalize_tag(&raw) {
+            set.insert(tag);
+        }
+    }
+    set
+}
+
+/// Structural tags (`#_draft`, or any listed in `hidden`) are recorded like others but left out
+/// of tag listings by default.
+pub fn is_hidden_tag(name: &str, hidden: &[String]) -> bool {
+    name.starts_with('_') || hidden.iter().any(|h| h == name)
+}
+
+fn normalize_tag(raw: &str) -> Option<String> {
+    let name: String = raw
+        .trim()
+        .trim_start_matches('#')
+        .chars()
+        .take(MAX_TAG_CHARS)
+        .collect::<String>()
+        .to_lowercase();
+    if name.is_empty() || !TAG_NAME_RE.is_match(&name) {
+        return None;
+    }
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    /// Counts round-trips and writes; every document belongs to `owner`.
+    #[derive(Default)]
+    struct Tags {
+        owner: Uuid,
+        queries: AtomicUsize,
+        writes: AtomicUsize,
+        tags: Mutex<BTreeMap<String, i64>>,
+        associations: Mutex<Vec<(Uuid, i64)>>,
+    }
+
+    impl Tags {
+        fn write(&self) {
+            self.queries.fetch_add(1, Ordering::Relaxed);
+            self.writes.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[async_trait]
+    impl TaggingRepository for Tags {
+        async fn document_tag_names(&self, doc_id: Uuid) -> anyhow::Result<Vec<String>> {
+            self.queries.fetch_add(1, Ordering::Relaxed);
+            let tags = self.tags.lock().unwrap();
+            let associations = self.associations.lock().unwrap();
+            Ok(tags
+                .iter()
+                .filter(|(_, id)| associations.contains(&(doc_id, **id)))
+                .map(|(name, _)| name.clone())
+                .collect())
+        }
+        async fn remove_document_tags(&self, doc_id: Uuid, names: &[String]) -> anyhow::Result<()> {
+            self.write();
+            let tags = self.tags.lock().unwrap();
+            let ids: Vec<i64> = names.iter().filter_map(|n| tags.get(n).copied()).collect();
+            self.associations
+                .lock()
+                .unwrap()
+                .retain(|(doc, id)| *doc != doc_id || !ids.contains(id));
+            Ok(())
+        }
+        async fn upsert_tags_return_ids(&self, names: &[String]) -> anyhow::Result<Vec<i64>> {
+            self.write();
+            let mut tags = self.tags.lock().unwrap();
+            Ok(names
+                .iter()
+                .map(|name| {
+                    let next = tags.len() as i64 + 1;
+                    *tags.entry(name.clone()).or_insert(next)
+                })
+                .collect())
+        }
+        async fn owner_doc_exists(&self, _: Uuid, owner_id: Uuid) -> anyhow::Result<bool> {
+            self.queries.fetch_add(1, Ordering::Relaxed);
+            Ok(owner_id == self.owner)
+        }
+        async fn associate_document_tags(
+            &self,
+            doc_id: Uuid,
+            tag_ids: &[i64],
+        ) -> anyhow::Result<()> {
+            self.write();
+            self.associations
+                .lock()
+                .unwrap()
+                .extend(tag_ids.iter().map(|id| (doc_id, *id)));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn many_tags_are_saved_in_a_fixed_number_of_queries() {
+        let repo = Tags {
+            owner: Uuid::new_v4(),
+            ..Default::default()
+        };
+        let doc = Uuid::new_v4();
+        let content: String = (0..40).map(|i| format!("#tag{i} ")).collect();
+
+        update_document_tags(&repo, doc, repo.owner, &content)
+            .await
+            .unwrap();
+        assert_eq!(repo.queries.load(Ordering::Relaxed), 4);
+        assert_eq!(repo.tags.lock().unwrap().len(), 40);
+        assert_eq!(repo.associations.lock().unwrap().len(), 40);
+
+        // Saving again drops the removed associations and reuses the tags.
+        update_document_tags(&repo, doc, repo.owner, "#tag1 #tag2")
+            .await
+            .unwrap();
+        assert_eq!(repo.tags.lock().unwrap().len(), 40);
+        assert_eq!(repo.associations.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn non_owner_save_leaves_tags_unchanged() {
+        let repo = Tags {
+            owner: Uuid::new_v4(),
+            ..Default::default()
+        };
+        let doc = Uuid::new_v4();
+        update_document_tags(&repo, doc, repo.owner, "#rust #web")
+            .await
+            .unwrap();
+        let associated = repo.associations.lock().unwrap().clone();
+        let writes = repo.writes.load(Ordering::Relaxed);
+
+        for content in ["#rust #async", "no tags left"] {
+            update_document_tags(&repo, doc, Uuid::new_v4(), content)
+                .await
+                .unwrap();
+        }
+        assert_eq!(repo.writes.load(Ordering::Relaxed), writes);
+        assert_eq!(*repo.associations.lock().unwrap(), associated);
+    }
+
+    #[tokio::test]
+    async fn unchanged_content_performs_no_tag_writes() {
+        let repo = Tags {
+            owner: Uuid::new_v4(),
+            ..Default::default()
+        };
+        let doc = Uuid::new_v4();
+        update_document_tags(&repo, doc, repo.owner, "#rust #web")
+            .await
+            .unwrap();
+        let writes = repo.writes.load(Ordering::Relaxed);
+
+        update_document_tags(&repo, doc, repo.owner, "#web edited #rust")
+            .await
+            .unwrap();
+        assert_eq!(repo.writes.load(Ordering::Relaxed), writes);
+        assert_eq!(repo.associations.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn adding_one_tag_inserts_one_association() {
+        let repo = Tags {
+            owner: Uuid::new_v4(),
+            ..Default::default()
+        };
+        let doc = Uuid::new_v4();
+        update_document_tags(&repo, doc, repo.owner, "#rust #web")
+            .await
+            .unwrap();
+        let associated = repo.associations.lock().unwrap().clone();
+        let writes = repo.writes.load(Ordering::Relaxed);
+
+        update_document_tags(&repo, doc, repo.owner, "#rust #web #async")
+            .await
+            .unwrap();
+        // One upsert and one association insert; existing associations are left alone.
+        assert_eq!(repo.writes.load(Ordering::Relaxed), writes + 2);
+        let associations = repo.associations.lock().unwrap();
+        assert_eq!(associations.len(), 3);
+        assert_eq!(associations[..2], associated[..]);
+    }
+
+    fn sorted(set: HashSet<String>) -> Vec<String> {
+        let mut tags: Vec<String> = set.into_iter().collect();
+        tags.sort();
+        tags
+    }
+
+    #[test]
+    fn front_matter_only_tags() {
+        let yaml = "---\ntitle: Notes\ntags:\n  - Rust\n  - \"web-dev\"\n---\n\nBody text";
+        assert_eq!(sorted(extract_tags(yaml)), vec!["rust", "web-dev"]);
+
+        let inline = "---\ntags: [Alpha, 'beta']\n---\nBody";
+        assert_eq!(sorted(extract_tags(inline)), vec!["alpha", "beta"]);
+
+        let toml = "+++\ntitle = \"Notes\"\ntags = \"one, two\"\n+++\nBody";
+        assert_eq!(sorted(extract_tags(toml)), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn inline_only_tags() {
+        let content = "Some #Idea and #日本語 here, not a heading\n# Title";
+        assert_eq!(sorted(extract_tags(content)), vec!["idea", "日本語"]);
+        // A `tags:` line outside front matter is ordinary text.
+        assert!(extract_tags("tags: a, b").is_empty());
+    }
+
+    #[test]
+    fn overlapping_tags_are_counted_once() {
+        let content = "---\ntags: [rust, Draft]\n---\n#Rust notes, still a #draft";
+        assert_eq!(sorted(extract_tags(content)), vec!["draft", "rust"]);
+    }
+
+    #[test]
+    fn front_matter_tags_are_normalized() {
+        let long = "a".repeat(80);
+        let content = format!("---\ntags: [\"#Tagged\", has space, {}]\n---\n", long);
+        assert_eq!(
+            sorted(extract_tags(&content)),
+            vec!["a".repeat(MAX_TAG_CHARS), "tagged".to_string()]
+        );
+    }
 }
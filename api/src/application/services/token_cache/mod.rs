@@ -0,0 +1,164 @@
+//! Caches successful bearer-token validations so hot paths skip repeated signature checks.
+//!
+//! Entries are keyed by a SHA-256 of the token and never outlive the token's own expiry.
+//! Revocation is recorded per node: a revoked token is refused until it would have expired,
+//! but other nodes of a cluster keep accepting it, as they would with no cache at all.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use sha2::{Digest, Sha256};
+
+type TokenKey = [u8; 32];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenLookup {
+    /// Validated recently; the cached subject.
+    Hit(String),
+    /// Revoked on this node; must be rejected without validating.
+    Revoked,
+    Miss,
+}
+
+struct CachedToken {
+    subject: String,
+    /// Unix seconds after which the token no longer validates.
+    valid_until: i64,
+    cached_at: Instant,
+}
+
+pub struct TokenCache {
+    ttl: Duration,
+    capacity: usize,
+    entries: Mutex<HashMap<TokenKey, CachedToken>>,
+    revoked: Mutex<HashMap<TokenKey, i64>>,
+}
+
+impl TokenCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+            revoked: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn lookup(&self, token: &str, now_unix: i64) -> TokenLookup {
+        let key = token_key(token);
+        if let Some(until) = self.revoked.lock().unwrap().get(&key)
+            && *until >= now_unix
+        {
+            return TokenLookup::Revoked;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(&key) {
+            Some(entry) if self.is_fresh(entry, now_unix) => {
+                TokenLookup::Hit(entry.subject.clone())
+            }
+            Some(_) => {
+                entries.remove(&key);
+                TokenLookup::Miss
+            }
+            None => TokenLookup::Miss,
+        }
+    }
+
+    /// Remembers a validated token until `valid_until` (unix seconds) or the cache TTL.
+    pub fn insert(&self, token: &str, subject: &str, valid_until: i64, now_unix: i64) {
+        if self.ttl.is_zero() || self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity {
+            entries.retain(|_, entry| self.is_fresh(entry, now_unix));
+            self.prune_revoked(now_unix);
+            if entries.len() >= self.capacity {
+                entries.clear();
+            }
+        }
+        entries.insert(
+            token_key(token),
+            CachedToken {
+                subject: subject.to_string(),
+                valid_until,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drops any cached validation and refuses the token until `valid_until`.
+    pub fn revoke(&self, token: &str, valid_until: i64, now_unix: i64) {
+        let key = token_key(token);
+        self.entries.lock().unwrap().remove(&key);
+        self.prune_revoked(now_unix);
+        self.revoked.lock().unwrap().insert(key, valid_until);
+    }
+
+    /// Forgets revocations of tokens that have expired anyway.
+    fn prune_revoked(&self, now_unix: i64) {
+        self.revoked
+            .lock()
+            .unwrap()
+            .retain(|_, until| *until >= now_unix);
+    }
+
+    fn is_fresh(&self, entry: &CachedToken, now_unix: i64) -> bool {
+        entry.valid_until >= now_unix && entry.cached_at.elapsed() < self.ttl
+    }
+}
+
+fn token_key(token: &str) -> TokenKey {
+    Sha256::digest(token.as_bytes()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_validation_is_reused_until_revoked() {
+        let cache = TokenCache::new(Duration::from_secs(60), 16);
+        let now = 1_700_000_000;
+
+        assert_eq!(cache.lookup("token-a", now), TokenLookup::Miss);
+        cache.insert("token-a", "user-1", now + 3600, now);
+        assert_eq!(
+            cache.lookup("token-a", now),
+            TokenLookup::Hit("user-1".into())
+        );
+        // Never served past the token's own expiry.
+        assert_eq!(cache.lookup("token-a", now + 3601), TokenLookup::Miss);
+
+        cache.insert("token-b", "user-2", now + 3600, now);
+        cache.revoke("token-b", now + 3600, now);
+        assert_eq!(cache.lookup("token-b", now), TokenLookup::Revoked);
+        cache.insert("token-b", "user-2", now + 3600, now);
+        assert_eq!(cache.lookup("token-b", now), TokenLookup::Revoked);
+    }
+
+    #[test]
+    fn cache_stays_bounded() {
+        let cache = TokenCache::new(Duration::from_secs(60), 4);
+        let now = 1_700_000_000;
+        for i in 0..10 {
+            cache.insert(&format!("token-{i}"), "user", now + 3600, now);
+        }
+        assert!(cache.entries.lock().unwrap().len() <= 4);
+    }
+
+    #[test]
+    fn expired_revocations_are_forgotten() {
+        let cache = TokenCache::new(Duration::from_secs(60), 2);
+        let now = 1_700_000_000;
+        cache.revoke("token-a", now + 10, now);
+        cache.revoke("token-b", now + 3600, now);
+
+        cache.insert("token-c", "user", now + 3600, now + 20);
+        cache.insert("token-d", "user", now + 3600, now + 20);
+        cache.insert("token-e", "user", now + 3600, now + 20);
+        assert_eq!(cache.revoked.lock().unwrap().len(), 1);
+        assert_eq!(cache.lookup("token-b", now + 20), TokenLookup::Revoked);
+    }
+}
@@ -0,0 +1,315 @@
+//! Upload content-type policy: decides which attachments may be stored.
+//!
+//! The type used for decisions is derived from the file's magic bytes first, so a
+//! client cannot slip active content past the policy by lying about its type or name.
+
+pub mod svg;
+
+use std::path::Path;
+
+use crate::bootstrap::config::Config;
+use svg::SvgSanitizeError;
+
+const SVG: &str = "image/svg+xml";
+
+#[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+pub enum UploadPolicyError {
+    #[error("content type {content_type} is not permitted")]
+    DeniedType { content_type: String },
+    #[error("file extension .{extension} is not permitted")]
+    DeniedExtension { extension: String },
+    #[error("content type {content_type} is not in the upload allowlist")]
+    NotAllowed { content_type: String },
+    #[error("file content ({detected}) does not match declared type {declared}")]
+    TypeMismatch { declared: String, detected: String },
+    #[error("svg could not be sanitized: {0}")]
+    InvalidSvg(#[from] SvgSanitizeError),
+}
+
+impl UploadPolicyError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            UploadPolicyError::DeniedType { .. } => "denied_type",
+            UploadPolicyError::DeniedExtension { .. } => "denied_extension",
+            UploadPolicyError::NotAllowed { .. } => "not_allowed",
+            UploadPolicyError::TypeMismatch { .. } => "type_mismatch",
+            UploadPolicyError::InvalidSvg(_) => "invalid_svg",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UploadPolicy {
+    /// Type patterns (`image/png`, `image/*`); empty means any type not denied.
+    pub allowed_types: Vec<String>,
+    pub denied_types: Vec<String>,
+    /// Lowercase extensions without the leading dot.
+    pub denied_extensions: Vec<String>,
+    /// Accept SVG despite the denylists, storing a sanitized copy instead.
+    pub sanitize_svg: bool,
+}
+
+pub const DEFAULT_DENIED_TYPES: &[&str] = &[
+    "text/html",
+    "application/xhtml+xml",
+    "image/svg+xml",
+    "text/javascript",
+    "application/javascript",
+    "application/x-executable",
+    "application/x-elf",
+    "application/x-mach-binary",
+    "application/vnd.microsoft.portable-executable",
+    "application/x-msdownload",
+];
+
+pub const DEFAULT_DENIED_EXTENSIONS: &[&str] = &[
+    "html", "htm", "xhtml", "svg", "svgz", "js", "mjs", "exe", "dll", "msi", "bat", "cmd", "com",
+    "scr", "sh", "ps1",
+];
+
+impl UploadPolicy {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            allowed_types: cfg.upload_allowed_types.clone(),
+            denied_types: cfg.upload_denied_types.clone(),
+            denied_extensions: cfg.upload_denied_extensions.clone(),
+            sanitize_svg: cfg.upload_sanitize_svg,
+        }
+    }
+
+    /// Runs [`check`](Self::check) and returns the type and bytes to store; SVG is sanitized.
+    pub fn prepare(
+        &self,
+        filename: Option<&str>,
+        declared_type: Option<&str>,
+        bytes: Vec<u8>,
+    ) -> Result<(String, Vec<u8>), UploadPolicyError> {
+        let content_type = self.check(filename, declared_type, &bytes)?;
+        if content_type == SVG {
+            let cleaned = svg::sanitize_svg(&bytes)?;
+            return Ok((content_type, cleaned));
+        }
+        Ok((content_type, bytes))
+    }
+
+    /// Checks an upload and returns the content type that should be recorded for it.
+    pub fn check(
+        &self,
+        filename: Option<&str>,
+        declared_type: Option<&str>,
+        bytes: &[u8],
+    ) -> Result<String, UploadPolicyError> {
+        let extension = filename
+            .and_then(|f| Path::new(f).extension())
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        if let Some(ext) = extension.as_deref() {
+            let sanitized_svg = self.sanitize_svg && (ext == "svg");
+            if !sanitized_svg && self.denied_extensions.iter().any(|d| d == ext) {
+                return Err(UploadPolicyError::DeniedExtension {
+                    extension: ext.to_string(),
+                });
+            }
+        }
+
+        let declared = declared_type.map(essence).filter(|d| !d.is_empty());
+        let detected = sniff(bytes);
+        let by_extension = filename
+            .and_then(|f| mime_guess::from_path(f).first())
+            .map(|m| m.essence_str().to_string());
+
+        // Any signal pointing at denied content rejects the upload.
+        for candidate in [&detected, &declared, &by_extension].into_iter().flatten() {
+            if self.is_denied(candidate) && !(self.sanitize_svg && candidate == SVG) {
+                return Err(UploadPolicyError::DeniedType {
+                    content_type: candidate.clone(),
+                });
+            }
+        }
+
+        let effective = detected
+            .clone()
+            .or(declared.clone())
+            .or(by_extension)
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        if let (Some(declared), Some(detected)) = (&declared, &detected)
+            && !is_generic(declared)
+            && !same_family(declared, detected)
+        {
+            return Err(UploadPolicyError::TypeMismatch {
+                declared: declared.clone(),
+                detected: detected.clone(),
+            });
+        }
+
+        if !self.allowed_types.is_empty()
+            && !self
+                .allowed_types
+                .iter()
+                .any(|p| matches_pattern(p, &effective))
+        {
+            return Err(UploadPolicyError::NotAllowed {
+                content_type: effective,
+            });
+        }
+        Ok(effective)
+    }
+
+    fn is_denied(&self, content_type: &str) -> bool {
+        self.denied_types
+            .iter()
+            .any(|p| matches_pattern(p, content_type))
+    }
+}
+
+fn essence(ct: &str) -> String {
+    ct.split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase()
+}
+
+fn is_generic(ct: &str) -> bool {
+    ct == "application/octet-stream" || ct == "binary/octet-stream"
+}
+
+fn same_family(a: &str, b: &str) -> bool {
+    a == b || a.split('/').next() == b.split('/').next()
+}
+
+fn matches_pattern(pattern: &str, content_type: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    match pattern.strip_suffix("/*") {
+        Some(major) => content_type.split('/').next() == Some(major),
+        None => pattern == "*" || pattern == content_type,
+    }
+}
+
+/// Detects the type from magic bytes, including markup formats `infer` does not cover.
+pub fn sniff(bytes: &[u8]) -> Option<String> {
+    if let Some(kind) = infer::get(bytes) {
+        return Some(kind.mime_type().to_string());
+    }
+    let head = &bytes[..bytes.len().min(1024)];
+    let text = String::from_utf8_lossy(head).to_ascii_lowercase();
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    // Only markup-leading files are considered, so prose mentioning tags is not flagged.
+    if !text.starts_with('<') {
+        return None;
+    }
+    if text.contains("<svg") {
+        Some("image/svg+xml".to_string())
+    } else if [
+        "<!doctype html",
+        "<html",
+        "<head",
+        "<body",
+        "<script",
+        "<iframe",
+    ]
+    .iter()
+    .any(|tag| text.contains(tag))
+    {
+        Some("text/html".to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = &[
+        0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', 0, 0, 0, 13,
+    ];
+
+    fn default_policy() -> UploadPolicy {
+        UploadPolicy {
+            allowed_types: Vec::new(),
+            denied_types: DEFAULT_DENIED_TYPES.iter().map(|s| s.to_string()).collect(),
+            denied_extensions: DEFAULT_DENIED_EXTENSIONS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            sanitize_svg: false,
+        }
+    }
+
+    #[test]
+    fn allows_image() {
+        let policy = default_policy();
+        assert_eq!(
+            policy.check(Some("cat.png"), Some("image/png"), PNG),
+            Ok("image/png".to_string())
+        );
+    }
+
+    #[test]
+    fn denies_executable() {
+        let policy = default_policy();
+        let mut elf = vec![0u8; 64];
+        elf[..4].copy_from_slice(b"\x7fELF");
+        assert!(matches!(
+            policy.check(Some("tool"), None, &elf),
+            Err(UploadPolicyError::DeniedType { .. })
+        ));
+        assert!(matches!(
+            policy.check(Some("setup.exe"), None, b"MZ"),
+            Err(UploadPolicyError::DeniedExtension { .. })
+        ));
+    }
+
+    #[test]
+    fn catches_spoofed_type_by_sniffing() {
+        let policy = default_policy();
+        let html = b"<!DOCTYPE html><html><script>alert(1)</script></html>";
+        assert_eq!(
+            policy.check(Some("photo.png"), Some("image/png"), html),
+            Err(UploadPolicyError::DeniedType {
+                content_type: "text/html".to_string()
+            })
+        );
+        let pdf = b"%PDF-1.7\n";
+        assert!(matches!(
+            policy.check(Some("photo.png"), Some("image/png"), pdf),
+            Err(UploadPolicyError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn allowlist_restricts_to_matching_types() {
+        let policy = UploadPolicy {
+            allowed_types: vec!["image/*".to_string()],
+            ..default_policy()
+        };
+        assert!(policy.check(Some("cat.png"), None, PNG).is_ok());
+        assert!(matches!(
+            policy.check(Some("doc.pdf"), None, b"%PDF-1.7\n"),
+            Err(UploadPolicyError::NotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn svg_is_sanitized_when_enabled() {
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg"><script>alert(1)</script><circle r="4"/></svg>"#;
+        assert!(matches!(
+            default_policy().prepare(Some("icon.svg"), None, svg.to_vec()),
+            Err(UploadPolicyError::DeniedExtension { .. })
+        ));
+        let policy = UploadPolicy {
+            sanitize_svg: true,
+            ..default_policy()
+        };
+        let (content_type, cleaned) = policy
+            .prepare(Some("icon.svg"), Some("image/svg+xml"), svg.to_vec())
+            .unwrap();
+        assert_eq!(content_type, "image/svg+xml");
+        assert_eq!(
+            cleaned,
+            br#"<svg xmlns="http://www.w3.org/2000/svg"><circle r="4"/></svg>"#
+        );
+    }
+}
@@ -0,0 +1,438 @@
+//! Allowlist sanitizer for uploaded SVG images.
+//!
+//! The document is re-serialized from a minimal XML tokenizer: scripts, foreign
+//! content and unknown elements are dropped with their subtree, `on*` handlers and
+//! non-local references are removed, and DOCTYPEs/processing instructions never
+//! reach the output.
+
+#[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+pub enum SvgSanitizeError {
+    #[error("svg is not valid utf-8")]
+    Encoding,
+    #[error("svg markup is malformed: {0}")]
+    Malformed(&'static str),
+    #[error("document root is not <svg>")]
+    NotSvg,
+}
+
+const ALLOWED_ELEMENTS: &[&str] = &[
+    "svg",
+    "g",
+    "defs",
+    "symbol",
+    "use",
+    "title",
+    "desc",
+    "metadata",
+    "style",
+    "path",
+    "rect",
+    "circle",
+    "ellipse",
+    "line",
+    "polyline",
+    "polygon",
+    "text",
+    "tspan",
+    "textPath",
+    "image",
+    "linearGradient",
+    "radialGradient",
+    "stop",
+    "clipPath",
+    "mask",
+    "pattern",
+    "marker",
+    "filter",
+    "feBlend",
+    "feColorMatrix",
+    "feComponentTransfer",
+    "feComposite",
+    "feDropShadow",
+    "feFlood",
+    "feFuncA",
+    "feFuncB",
+    "feFuncG",
+    "feFuncR",
+    "feGaussianBlur",
+    "feMerge",
+    "feMergeNode",
+    "feMorphology",
+    "feOffset",
+];
+
+const REFERENCE_ATTRS: &[&str] = &["href", "xlink:href"];
+
+const SAFE_IMAGE_DATA: &[&str] = &[
+    "data:image/png",
+    "data:image/jpeg",
+    "data:image/gif",
+    "data:image/webp",
+];
+
+pub fn sanitize_svg(input: &[u8]) -> Result<Vec<u8>, SvgSanitizeError> {
+    let text = std::str::from_utf8(input).map_err(|_| SvgSanitizeError::Encoding)?;
+    let mut out = String::with_capacity(text.len());
+    // Names of kept open elements; `skip_depth` counts nesting inside a dropped subtree.
+    let mut stack: Vec<String> = Vec::new();
+    let mut skip_depth = 0usize;
+    let mut seen_root = false;
+    let mut rest = text.trim_start_matches('\u{feff}');
+
+    while !rest.is_empty() {
+        let Some(lt) = rest.find('<') else {
+            if skip_depth == 0 && !stack.is_empty() {
+                push_text(&mut out, rest);
+            }
+            break;
+        };
+        if lt > 0 {
+            if skip_depth == 0 && !stack.is_empty() {
+                push_text(&mut out, &rest[..lt]);
+            }
+            rest = &rest[lt..];
+            continue;
+        }
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after
+                .find("-->")
+                .ok_or(SvgSanitizeError::Malformed("unterminated comment"))?;
+            rest = &after[end + 3..];
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after
+                .find("]]>")
+                .ok_or(SvgSanitizeError::Malformed("unterminated cdata"))?;
+            if skip_depth == 0 && !stack.is_empty() {
+                push_escaped(&mut out, &after[..end]);
+            }
+            rest = &after[end + 3..];
+        } else if rest.starts_with("<!") {
+            rest = skip_declaration(rest)?;
+        } else if let Some(after) = rest.strip_prefix("<?") {
+            let end = after.find("?>").ok_or(SvgSanitizeError::Malformed(
+                "unterminated processing instruction",
+            ))?;
+            rest = &after[end + 2..];
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after
+                .find('>')
+                .ok_or(SvgSanitizeError::Malformed("unterminated end tag"))?;
+            let name = after[..end].trim();
+            rest = &after[end + 1..];
+            if skip_depth > 0 {
+                skip_depth -= 1;
+                continue;
+            }
+            match stack.pop() {
+                Some(open) if open == name => {
+                    out.push_str("</");
+                    out.push_str(name);
+                    out.push('>');
+                }
+                _ => return Err(SvgSanitizeError::Malformed("mismatched end tag")),
+            }
+        } else {
+            let (tag, after) = parse_start_tag(&rest[1..])?;
+            rest = after;
+            if skip_depth > 0 {
+                if !tag.self_closing {
+                    skip_depth += 1;
+                }
+                continue;
+            }
+            if !seen_root {
+                if tag.name != "svg" {
+                    return Err(SvgSanitizeError::NotSvg);
+                }
+                seen_root = true;
+            } else if stack.is_empty() {
+                return Err(SvgSanitizeError::Malformed("content after root element"));
+            }
+            if !ALLOWED_ELEMENTS.contains(&tag.name.as_str()) {
+                if !tag.self_closing {
+                    skip_depth = 1;
+                }
+                continue;
+            }
+            if tag.name == "style" && !tag.self_closing {
+                // Style text is checked as a whole; unsafe sheets drop the element.
+                let close = rest
+                    .find("</style>")
+                    .ok_or(SvgSanitizeError::Malformed("unterminated style"))?;
+                let css = &rest[..close];
+                rest = &rest[close + "</style>".len()..];
+                if css_is_safe(css) {
+                    out.push_str("<style>");
+                    push_escaped(
+                        &mut out,
+                        css.trim_start_matches("<![CDATA[").trim_end_matches("]]>"),
+                    );
+                    out.push_str("</style>");
+                }
+                continue;
+            }
+            write_start_tag(&mut out, &tag);
+            if !tag.self_closing {
+                stack.push(tag.name);
+            }
+        }
+    }
+
+    if !seen_root {
+        return Err(SvgSanitizeError::NotSvg);
+    }
+    if !stack.is_empty() || skip_depth > 0 {
+        return Err(SvgSanitizeError::Malformed("unclosed element"));
+    }
+    Ok(out.into_bytes())
+}
+
+struct StartTag {
+    name: String,
+    attrs: Vec<(String, String)>,
+    self_closing: bool,
+}
+
+fn parse_start_tag(input: &str) -> Result<(StartTag, &str), SvgSanitizeError> {
+    let name_end = input
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .ok_or(SvgSanitizeError::Malformed("unterminated start tag"))?;
+    let name = &input[..name_end];
+    if name.is_empty() {
+        return Err(SvgSanitizeError::Malformed("empty tag name"));
+    }
+    let mut rest = &input[name_end..];
+    let mut attrs = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Ok((
+                StartTag {
+                    name: name.to_string(),
+                    attrs,
+                    self_closing: true,
+                },
+                after,
+            ));
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Ok((
+                StartTag {
+                    name: name.to_string(),
+                    attrs,
+                    self_closing: false,
+                },
+                after,
+            ));
+        }
+        let eq = rest
+            .find('=')
+            .ok_or(SvgSanitizeError::Malformed("attribute without value"))?;
+        let attr_name = rest[..eq].trim();
+        if attr_name.is_empty() || attr_name.contains(|c: char| c.is_whitespace() || c == '>') {
+            return Err(SvgSanitizeError::Malformed("invalid attribute"));
+        }
+        let after_eq = rest[eq + 1..].trim_start();
+        let quote = after_eq
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or(SvgSanitizeError::Malformed("unquoted attribute"))?;
+        let value_end = after_eq[1..]
+            .find(quote)
+            .ok_or(SvgSanitizeError::Malformed("unterminated attribute"))?;
+        attrs.push((
+            attr_name.to_string(),
+            decode_entities(&after_eq[1..1 + value_end]),
+        ));
+        rest = &after_eq[value_end + 2..];
+    }
+}
+
+fn skip_declaration(input: &str) -> Result<&str, SvgSanitizeError> {
+    // DOCTYPE may carry an internal subset in brackets; drop it entirely.
+    let mut depth = 0i32;
+    for (i, c) in input.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            '>' if depth <= 0 => return Ok(&input[i + 1..]),
+            _ => {}
+        }
+    }
+    Err(SvgSanitizeError::Malformed("unterminated declaration"))
+}
+
+fn write_start_tag(out: &mut String, tag: &StartTag) {
+    out.push('<');
+    out.push_str(&tag.name);
+    for (name, value) in &tag.attrs {
+        if !attribute_is_safe(&tag.name, name, value) {
+            continue;
+        }
+        out.push(' ');
+        out.push_str(name);
+        out.push_str("=\"");
+        push_escaped(out, value);
+        out.push('"');
+    }
+    out.push_str(if tag.self_closing { "/>" } else { ">" });
+}
+
+fn attribute_is_safe(element: &str, name: &str, value: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    if lower.starts_with("on") || lower == "xml:base" {
+        return false;
+    }
+    if lower.starts_with("xmlns") {
+        return matches!(
+            value,
+            "http://www.w3.org/2000/svg" | "http://www.w3.org/1999/xlink"
+        );
+    }
+    let compact: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if compact.contains("javascript:") || compact.contains("vbscript:") {
+        return false;
+    }
+    if REFERENCE_ATTRS.contains(&lower.as_str()) {
+        return compact.starts_with('#')
+            || (element == "image" && SAFE_IMAGE_DATA.iter().any(|p| compact.starts_with(p)));
+    }
+    if lower == "style" {
+        return css_is_safe(value);
+    }
+    // Presentation attributes such as fill="url(#grad)" may only reference local ids.
+    !compact.contains("url(") || only_local_urls(&compact)
+}
+
+fn css_is_safe(css: &str) -> bool {
+    let compact: String = css
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    !compact.contains("@import")
+        && !compact.contains("expression(")
+        && !compact.contains("javascript:")
+        && !compact.contains('\\')
+        && only_local_urls(&compact)
+}
+
+fn only_local_urls(compact: &str) -> bool {
+    compact.match_indices("url(").all(|(i, _)| {
+        let target = compact[i + 4..].trim_start_matches(['"', '\'']);
+        target.starts_with('#')
+    })
+}
+
+fn decode_entities(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let after = &rest[amp + 1..];
+        let decoded = after.find(';').and_then(|semi| {
+            let entity = &after[..semi];
+            let c = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|h| u32::from_str_radix(h, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, semi))
+        });
+        match decoded {
+            Some((c, semi)) => {
+                out.push(c);
+                rest = &after[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn push_text(out: &mut String, raw: &str) {
+    push_escaped(out, &decode_entities(raw));
+}
+
+fn push_escaped(out: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clean(input: &str) -> String {
+        String::from_utf8(sanitize_svg(input.as_bytes()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn strips_scripts_handlers_and_external_references() {
+        let out = clean(
+            r#"<?xml version="1.0"?>
+<!DOCTYPE svg [<!ENTITY x SYSTEM "file:///etc/passwd">]>
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" onload="alert(1)">
+  <script>alert(document.cookie)</script>
+  <foreignObject><body><img src="x" onerror="alert(2)"/></body></foreignObject>
+  <a xlink:href="javascript:alert(3)"><text>click</text></a>
+  <use href="https://evil.example/sprite.svg#icon"/>
+  <rect width="10" height="10" fill="url(https://evil.example/x)" onclick="alert(4)"/>
+  <image href="&#106;avascript:alert(5)"/>
+</svg>"#,
+        );
+        for needle in [
+            "script",
+            "alert",
+            "onload",
+            "foreignObject",
+            "evil.example",
+            "javascript",
+            "ENTITY",
+        ] {
+            assert!(!out.contains(needle), "{} survived in {}", needle, out);
+        }
+        assert!(out.starts_with("<svg"));
+        assert!(out.contains(r#"<rect width="10" height="10"/>"#));
+    }
+
+    #[test]
+    fn preserves_benign_icon() {
+        let icon = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" width="24" height="24"><defs><linearGradient id="g"><stop offset="0" stop-color="#fff"/></linearGradient></defs><title>Check &amp; done</title><path d="M9 16.2 4.8 12l-1.4 1.4L9 19 21 7l-1.4-1.4z" fill="url(#g)"/><use href="#g"/></svg>"##;
+        assert_eq!(clean(icon), icon);
+    }
+
+    #[test]
+    fn rejects_non_svg_documents() {
+        assert_eq!(
+            sanitize_svg(b"<html><body/></html>"),
+            Err(SvgSanitizeError::NotSvg)
+        );
+        assert!(sanitize_svg(b"<svg><g></svg>").is_err());
+    }
+}
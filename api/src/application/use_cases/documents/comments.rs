@@ -0,0 +1,579 @@
+use uuid::Uuid;
+
+use crate::application::access::{self, AccessPolicy, Actor, Capability};
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::comment_repository::{
+    CommentAnchor, CommentRepository, DocumentComment, NewComment,
+};
+use crate::application::ports::notifier::Notifier;
+use crate::application::ports::share_access_port::ShareAccessPort;
+use crate::application::services::notifications;
+
+pub const MAX_COMMENT_CHARS: usize = 10_000;
+const MAX_AUTHOR_NAME_CHARS: usize = 64;
+const EXCERPT_CHARS: usize = 140;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CommentError {
+    #[error("comment not found")]
+    NotFound,
+    #[error("permission denied")]
+    Forbidden,
+    #[error("comment body is empty")]
+    EmptyBody,
+    #[error("comment body is too long")]
+    BodyTooLong,
+    #[error("anchor must be a valid source range")]
+    InvalidAnchor,
+    #[error("replies must target a top-level comment on the same document")]
+    InvalidParent,
+    #[error(transparent)]
+    Repository(#[from] anyhow::Error),
+}
+
+/// Returns `NotFound` rather than `Forbidden` when the actor cannot see the document, so
+/// comment endpoints do not reveal which documents exist.
+async fn require<A, S>(
+    access: &A,
+    shares: &S,
+    policy: AccessPolicy,
+    actor: &Actor,
+    doc_id: Uuid,
+    needed: Capability,
+) -> Result<Capability, CommentError>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+{
+    let cap = access::resolve_document(access, shares, policy, actor, doc_id).await;
+    if cap == Capability::None {
+        Err(CommentError::NotFound)
+    } else if cap < needed {
+        Err(CommentError::Forbidden)
+    } else {
+        Ok(cap)
+    }
+}
+
+pub struct ListComments<'a, A, S, C>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    C: CommentRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a S,
+    pub policy: AccessPolicy,
+    pub comments: &'a C,
+}
+
+impl<'a, A, S, C> ListComments<'a, A, S, C>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    C: CommentRepository + ?Sized,
+{
+    pub async fn execute(
+        &self,
+        actor: &Actor,
+        doc_id: Uuid,
+    ) -> Result<Vec<DocumentComment>, CommentError> {
+        require(
+            self.access,
+            self.shares,
+            self.policy,
+            actor,
+            doc_id,
+            Capability::View,
+        )
+        .await?;
+        Ok(self.comments.list_for_document(doc_id).await?)
+    }
+}
+
+pub struct CreateCommentInput {
+    pub parent_id: Option<Uuid>,
+    /// Display name; only used for share-link commenters, users are named by their account.
+    pub author_name: Option<String>,
+    pub anchor: Option<CommentAnchor>,
+    pub body: String,
+}
+
+pub struct CreateComment<'a, A, S, C>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    C: CommentRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a S,
+    pub policy: AccessPolicy,
+    pub comments: &'a C,
+    /// Tells the owner and thread participants; failures are logged and keep the comment.
+    pub notifier: Option<&'a dyn Notifier>,
+}
+
+impl<'a, A, S, C> CreateComment<'a, A, S, C>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    C: CommentRepository + ?Sized,
+{
+    pub async fn execute(
+        &self,
+        actor: &Actor,
+        doc_id: Uuid,
+        input: CreateCommentInput,
+    ) -> Result<DocumentComment, CommentError> {
+        let body = input.body.trim();
+        if body.is_empty() {
+            return Err(CommentError::EmptyBody);
+        }
+        if body.chars().count() > MAX_COMMENT_CHARS {
+            return Err(CommentError::BodyTooLong);
+        }
+        if input.anchor.is_some_and(|a| !a.is_valid()) {
+            return Err(CommentError::InvalidAnchor);
+        }
+        require(
+            self.access,
+            self.shares,
+            self.policy,
+            actor,
+            doc_id,
+            Capability::Comment,
+        )
+        .await?;
+        if let Some(parent_id) = input.parent_id {
+            match self.comments.get(doc_id, parent_id).await? {
+                Some(parent) if parent.parent_id.is_none() => {}
+                _ => return Err(CommentError::InvalidParent),
+            }
+        }
+
+        let author_id = match actor {
+            Actor::User(uid) => Some(*uid),
+            _ => None,
+        };
+        let author_name = input
+            .author_name
+            .map(|n| {
+                n.trim()
+                    .chars()
+                    .take(MAX_AUTHOR_NAME_CHARS)
+                    .collect::<String>()
+            })
+            .filter(|n| !n.is_empty());
+        let comment = self
+            .comments
+            .create(&NewComment {
+                document_id: doc_id,
+                parent_id: input.parent_id,
+                author_id,
+                author_name,
+                // Replies follow their thread's anchor.
+                anchor: if input.parent_id.is_some() {
+                    None
+                } else {
+                    input.anchor
+                },
+                body: body.to_string(),
+            })
+            .await?;
+
+        if let Some(notifier) = self.notifier {
+            self.notify(notifier, &comment).await;
+        }
+        Ok(comment)
+    }
+
+    async fn notify(&self, notifier: &dyn Notifier, comment: &DocumentComment) {
+        let recipients = match self
+            .comments
+            .recipients(comment.document_id, comment.parent_id)
+            .await
+        {
+            Ok(recipients) => recipients,
+            Err(e) => {
+                tracing::warn!(document_id = %comment.document_id, error = ?e, "comment_recipients_failed");
+                return;
+            }
+        };
+        let excerpt: String = comment.body.chars().take(EXCERPT_CHARS).collect();
+        for recipient in recipients {
+            if Some(recipient) == comment.author_id {
+                continue;
+            }
+            let notification = notifications::comment(
+                recipient,
+                comment.document_id,
+                comment.author_id,
+                comment.id,
+                &excerpt,
+            );
+            if let Err(e) = notifier.notify(notification).await {
+                tracing::warn!(document_id = %comment.document_id, user_id = %recipient, error = ?e, "comment_notify_failed");
+            }
+        }
+    }
+}
+
+/// Resolving, reopening and deleting are open to editors and to the comment's author.
+async fn load_for_moderation<A, S, C>(
+    access: &A,
+    shares: &S,
+    policy: AccessPolicy,
+    comments: &C,
+    actor: &Actor,
+    doc_id: Uuid,
+    comment_id: Uuid,
+) -> Result<DocumentComment, CommentError>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    C: CommentRepository + ?Sized,
+{
+    let cap = require(access, shares, policy, actor, doc_id, Capability::View).await?;
+    let comment = comments
+        .get(doc_id, comment_id)
+        .await?
+        .ok_or(CommentError::NotFound)?;
+    let is_author = matches!(actor, Actor::User(uid) if comment.author_id == Some(*uid));
+    if cap < Capability::Edit && !is_author {
+        return Err(CommentError::Forbidden);
+    }
+    Ok(comment)
+}
+
+pub struct ResolveComment<'a, A, S, C>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    C: CommentRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a S,
+    pub policy: AccessPolicy,
+    pub comments: &'a C,
+}
+
+impl<'a, A, S, C> ResolveComment<'a, A, S, C>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    C: CommentRepository + ?Sized,
+{
+    /// Marks the comment resolved (or reopens it) and returns the updated comment.
+    pub async fn execute(
+        &self,
+        actor: &Actor,
+        doc_id: Uuid,
+        comment_id: Uuid,
+        resolved: bool,
+    ) -> Result<DocumentComment, CommentError> {
+        load_for_moderation(
+            self.access,
+            self.shares,
+            self.policy,
+            self.comments,
+            actor,
+            doc_id,
+            comment_id,
+        )
+        .await?;
+        let by = match actor {
+            Actor::User(uid) => Some(*uid),
+            _ => None,
+        };
+        if !self.comments.set_resolved(comment_id, resolved, by).await? {
+            return Err(CommentError::NotFound);
+        }
+        self.comments
+            .get(doc_id, comment_id)
+            .await?
+            .ok_or(CommentError::NotFound)
+    }
+}
+
+pub struct DeleteComment<'a, A, S, C>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    C: CommentRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a S,
+    pub policy: AccessPolicy,
+    pub comments: &'a C,
+}
+
+impl<'a, A, S, C> DeleteComment<'a, A, S, C>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    C: CommentRepository + ?Sized,
+{
+    pub async fn execute(
+        &self,
+        actor: &Actor,
+        doc_id: Uuid,
+        comment_id: Uuid,
+    ) -> Result<(), CommentError> {
+        load_for_moderation(
+            self.access,
+            self.shares,
+            self.policy,
+            self.comments,
+            actor,
+            doc_id,
+            comment_id,
+        )
+        .await?;
+        if !self.comments.delete(comment_id).await? {
+            return Err(CommentError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use chrono::Utc;
+
+    use super::*;
+    use crate::application::ports::notification_repository::NewNotification;
+    use crate::application::ports::shares_repository::SharesRepository;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::shares::MemoryShares;
+
+    /// Comments kept in memory next to a document owned by `owner`, shared through a
+    /// view link and a comment link.
+    struct Store {
+        docs: Arc<MemoryDocuments>,
+        shares: MemoryShares,
+        doc_id: Uuid,
+        owner: Uuid,
+        view_token: String,
+        comment_token: String,
+        comments: Mutex<Vec<DocumentComment>>,
+    }
+
+    impl Store {
+        async fn new() -> Self {
+            let docs = Arc::new(MemoryDocuments::default());
+            let shares = MemoryShares::new(docs.clone());
+            let owner = Uuid::new_v4();
+            let doc_id = docs.add(owner, "Draft", "document", None).id;
+            let token = |permission| shares.create_share(owner, doc_id, permission, None, None);
+            let (view_token, _, _) = token("view").await.unwrap();
+            let (comment_token, _, _) = token("comment").await.unwrap();
+            Self {
+                docs,
+                shares,
+                doc_id,
+                owner,
+                view_token,
+                comment_token,
+                comments: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl CommentRepository for Store {
+        async fn create(&self, c: &NewComment) -> anyhow::Result<DocumentComment> {
+            let comment = DocumentComment {
+                id: Uuid::new_v4(),
+                document_id: c.document_id,
+                parent_id: c.parent_id,
+                author_id: c.author_id,
+                author_name: c.author_name.clone(),
+                anchor: c.anchor,
+                body: c.body.clone(),
+                resolved: false,
+                resolved_by: None,
+                resolved_at: None,
+                created_at: Utc::now(),
+            };
+            self.comments.lock().unwrap().push(comment.clone());
+            Ok(comment)
+        }
+
+        async fn list_for_document(&self, doc_id: Uuid) -> anyhow::Result<Vec<DocumentComment>> {
+            Ok(self
+                .comments
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|c| c.document_id == doc_id)
+                .cloned()
+                .collect())
+        }
+
+        async fn get(&self, doc_id: Uuid, id: Uuid) -> anyhow::Result<Option<DocumentComment>> {
+            Ok(self
+                .comments
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|c| c.id == id && c.document_id == doc_id)
+                .cloned())
+        }
+
+        async fn set_resolved(
+            &self,
+            id: Uuid,
+            resolved: bool,
+            by: Option<Uuid>,
+        ) -> anyhow::Result<bool> {
+            let mut comments = self.comments.lock().unwrap();
+            match comments.iter_mut().find(|c| c.id == id) {
+                Some(c) => {
+                    c.resolved = resolved;
+                    c.resolved_by = by.filter(|_| resolved);
+                    c.resolved_at = resolved.then(Utc::now);
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+
+        async fn delete(&self, id: Uuid) -> anyhow::Result<bool> {
+            let mut comments = self.comments.lock().unwrap();
+            let before = comments.len();
+            comments.retain(|c| c.id != id && c.parent_id != Some(id));
+            Ok(comments.len() < before)
+        }
+
+        async fn recipients(
+            &self,
+            _doc_id: Uuid,
+            _thread_id: Option<Uuid>,
+        ) -> anyhow::Result<Vec<Uuid>> {
+            Ok(vec![self.owner])
+        }
+    }
+
+    #[derive(Default)]
+    struct Sent(Mutex<Vec<NewNotification>>);
+
+    #[async_trait]
+    impl Notifier for Sent {
+        async fn notify(&self, notification: NewNotification) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push(notification);
+            Ok(())
+        }
+    }
+
+    fn input(body: &str) -> CreateCommentInput {
+        CreateCommentInput {
+            parent_id: None,
+            author_name: Some("Guest".to_string()),
+            anchor: Some("3:1-3:12".parse().unwrap()),
+            body: body.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn comment_share_token_can_comment_and_owner_is_notified() {
+        let store = Store::new().await;
+        let sent = Sent::default();
+        let create = CreateComment {
+            access: &*store.docs,
+            shares: &store.shares,
+            policy: AccessPolicy::default(),
+            comments: &store,
+            notifier: Some(&sent),
+        };
+
+        let guest = Actor::ShareToken(store.comment_token.clone());
+        let comment = create
+            .execute(&guest, store.doc_id, input("  Typo here  "))
+            .await
+            .unwrap();
+        assert_eq!(comment.body, "Typo here");
+        assert_eq!(comment.author_id, None);
+        assert_eq!(comment.author_name.as_deref(), Some("Guest"));
+        assert_eq!(comment.anchor.unwrap().to_string(), "3:1-3:12");
+
+        {
+            let sent = sent.0.lock().unwrap();
+            assert_eq!(sent.len(), 1);
+            assert_eq!(sent[0].user_id, store.owner);
+            assert_eq!(sent[0].kind, notifications::KIND_COMMENT);
+        }
+
+        let viewer = Actor::ShareToken(store.view_token.clone());
+        assert!(matches!(
+            create.execute(&viewer, store.doc_id, input("nope")).await,
+            Err(CommentError::Forbidden)
+        ));
+        let stranger = Actor::User(Uuid::new_v4());
+        assert!(matches!(
+            create.execute(&stranger, store.doc_id, input("nope")).await,
+            Err(CommentError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn viewers_list_comments_and_editors_resolve_them() {
+        let store = Store::new().await;
+        let owner = Actor::User(store.owner);
+        let create = CreateComment {
+            access: &*store.docs,
+            shares: &store.shares,
+            policy: AccessPolicy::default(),
+            comments: &store,
+            notifier: None,
+        };
+        let thread = create
+            .execute(&owner, store.doc_id, input("Needs a source"))
+            .await
+            .unwrap();
+        let guest = Actor::ShareToken(store.comment_token.clone());
+        let reply = CreateCommentInput {
+            parent_id: Some(thread.id),
+            ..input("Added one")
+        };
+        create.execute(&guest, store.doc_id, reply).await.unwrap();
+
+        let list = ListComments {
+            access: &*store.docs,
+            shares: &store.shares,
+            policy: AccessPolicy::default(),
+            comments: &store,
+        };
+        let viewer = Actor::ShareToken(store.view_token.clone());
+        let comments = list.execute(&viewer, store.doc_id).await.unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[1].parent_id, Some(thread.id));
+        assert_eq!(comments[1].anchor, None);
+
+        let resolve = ResolveComment {
+            access: &*store.docs,
+            shares: &store.shares,
+            policy: AccessPolicy::default(),
+            comments: &store,
+        };
+        assert!(matches!(
+            resolve.execute(&guest, store.doc_id, thread.id, true).await,
+            Err(CommentError::Forbidden)
+        ));
+        let resolved = resolve
+            .execute(&owner, store.doc_id, thread.id, true)
+            .await
+            .unwrap();
+        assert!(resolved.resolved);
+        assert_eq!(resolved.resolved_by, Some(store.owner));
+
+        let reopened = resolve
+            .execute(&owner, store.doc_id, thread.id, false)
+            .await
+            .unwrap();
+        assert!(!reopened.resolved);
+        assert_eq!(reopened.resolved_by, None);
+    }
+}
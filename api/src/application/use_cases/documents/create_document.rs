@@ -1,13 +1,24 @@
 use uuid::Uuid;
 
 use crate::application::ports::document_repository::DocumentRepository;
+use crate::application::ports::shares_repository::SharesRepository;
+use crate::application::use_cases::shares::materialize_folder_share::InheritFolderShares;
 use crate::domain::documents::document::Document as DomainDocument;
 
-pub struct CreateDocument<'a, R: DocumentRepository + ?Sized> {
+pub struct CreateDocument<'a, R, SR>
+where
+    R: DocumentRepository + ?Sized,
+    SR: SharesRepository + ?Sized,
+{
     pub repo: &'a R,
+    pub shares: &'a SR,
 }
 
-impl<'a, R: DocumentRepository + ?Sized> CreateDocument<'a, R> {
+impl<'a, R, SR> CreateDocument<'a, R, SR>
+where
+    R: DocumentRepository + ?Sized,
+    SR: SharesRepository + ?Sized,
+{
     pub async fn execute(
         &self,
         user_id: Uuid,
@@ -15,8 +26,15 @@ impl<'a, R: DocumentRepository + ?Sized> CreateDocument<'a, R> {
         parent_id: Option<Uuid>,
         doc_type: &str,
     ) -> anyhow::Result<DomainDocument> {
-        self.repo
+        let doc = self
+            .repo
             .create_for_user(user_id, title, parent_id, doc_type)
-            .await
+            .await?;
+        if parent_id.is_some() {
+            InheritFolderShares { repo: self.shares }
+                .execute(doc.id)
+                .await?;
+        }
+        Ok(doc)
     }
 }
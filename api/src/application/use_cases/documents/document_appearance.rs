@@ -0,0 +1,264 @@
+use uuid::Uuid;
+
+use crate::application::access::{self, AccessPolicy, Actor, Capability};
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::document_repository::DocumentRepository;
+use crate::application::ports::share_access_port::ShareAccessPort;
+use crate::domain::documents::document::Document;
+
+/// Named icons the tree knows how to draw, besides single emoji.
+pub const NAMED_ICONS: &[&str] = &[
+    "archive",
+    "book",
+    "bookmark",
+    "bug",
+    "calendar",
+    "code",
+    "file",
+    "flag",
+    "folder",
+    "globe",
+    "heart",
+    "home",
+    "image",
+    "inbox",
+    "lightbulb",
+    "link",
+    "lock",
+    "music",
+    "note",
+    "pin",
+    "rocket",
+    "settings",
+    "star",
+    "tag",
+    "target",
+    "terminal",
+    "users",
+    "zap",
+];
+
+/// Longest emoji sequence accepted, in chars; covers ZWJ sequences such as families.
+const MAX_EMOJI_CHARS: usize = 10;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AppearanceError {
+    #[error("document not found")]
+    NotFound,
+    #[error("edit permission required")]
+    Forbidden,
+    #[error("icon must be an emoji or one of the named icons")]
+    InvalidIcon,
+    #[error("color must be a hex color such as #3b82f6")]
+    InvalidColor,
+    #[error(transparent)]
+    Repository(#[from] anyhow::Error),
+}
+
+pub struct UpdateDocumentAppearance<'a, A, SH, D>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    D: DocumentRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a SH,
+    pub policy: AccessPolicy,
+    pub documents: &'a D,
+}
+
+impl<'a, A, SH, D> UpdateDocumentAppearance<'a, A, SH, D>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    D: DocumentRepository + ?Sized,
+{
+    /// Sets both the icon and the color; a missing or blank value clears it.
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        doc_id: Uuid,
+        icon: Option<&str>,
+        color: Option<&str>,
+    ) -> Result<Document, AppearanceError> {
+        let icon = normalize_icon(icon)?;
+        let color = normalize_color(color)?;
+        let actor = Actor::User(user_id);
+        match access::resolve_document(self.access, self.shares, self.policy, &actor, doc_id).await
+        {
+            Capability::None => return Err(AppearanceError::NotFound),
+            Capability::View | Capability::Comment => return Err(AppearanceError::Forbidden),
+            Capability::Edit => {}
+        }
+        self.documents
+            .set_appearance(doc_id, icon.as_deref(), color.as_deref())
+            .await?
+            .ok_or(AppearanceError::NotFound)
+    }
+}
+
+fn normalize_icon(icon: Option<&str>) -> Result<Option<String>, AppearanceError> {
+    let Some(icon) = icon.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let named = icon.to_ascii_lowercase();
+    if NAMED_ICONS.contains(&named.as_str()) {
+        return Ok(Some(named));
+    }
+    if is_emoji(icon) {
+        return Ok(Some(icon.to_string()));
+    }
+    Err(AppearanceError::InvalidIcon)
+}
+
+/// Accepts `#rgb` and `#rrggbb`, stored as lowercase `#rrggbb`.
+fn normalize_color(color: Option<&str>) -> Result<Option<String>, AppearanceError> {
+    let Some(color) = color.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let hex = color
+        .strip_prefix('#')
+        .filter(|h| (h.len() == 3 || h.len() == 6) && h.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or(AppearanceError::InvalidColor)?
+        .to_ascii_lowercase();
+    if hex.len() == 3 {
+        Ok(Some(hex.chars().fold(String::from("#"), |mut out, c| {
+            out.push(c);
+            out.push(c);
+            out
+        })))
+    } else {
+        Ok(Some(format!("#{}", hex)))
+    }
+}
+
+/// A single emoji, possibly with modifiers, variation selectors or zero-width joiners. Text
+/// characters are rejected so the icon cannot smuggle labels into the tree.
+fn is_emoji(s: &str) -> bool {
+    let pictographic = |c: char| {
+        matches!(c as u32,
+            0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x2300..=0x23FF)
+    };
+    let joiner = |c: char| matches!(c as u32, 0x200D | 0xFE0F | 0x20E3 | 0xE0020..=0xE007F);
+    s.chars().count() <= MAX_EMOJI_CHARS
+        && s.chars().any(pictographic)
+        && s.chars().all(|c| pictographic(c) || joiner(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::application::ports::document_repository::DocumentListFilter;
+    use crate::application::use_cases::documents::list_documents::ListDocuments;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::shares::MemoryShares;
+
+    struct Store {
+        owner: Uuid,
+        viewer: Uuid,
+        doc_id: Uuid,
+        docs: Arc<MemoryDocuments>,
+        shares: MemoryShares,
+    }
+
+    impl Store {
+        fn new() -> Self {
+            let (owner, viewer) = (Uuid::new_v4(), Uuid::new_v4());
+            let docs = Arc::new(MemoryDocuments::default());
+            let doc_id = docs.add(owner, "Roadmap", "folder", None).id;
+            docs.grants
+                .lock()
+                .unwrap()
+                .insert((doc_id, viewer), "view".to_string());
+            Self {
+                owner,
+                viewer,
+                doc_id,
+                shares: MemoryShares::new(docs.clone()),
+                docs,
+            }
+        }
+
+        fn doc(&self) -> Document {
+            self.docs.get(self.doc_id).unwrap().doc
+        }
+
+        async fn update(
+            &self,
+            user_id: Uuid,
+            icon: Option<&str>,
+            color: Option<&str>,
+        ) -> Result<Document, AppearanceError> {
+            UpdateDocumentAppearance {
+                access: &*self.docs,
+                shares: &self.shares,
+                policy: AccessPolicy::default(),
+                documents: &*self.docs,
+            }
+            .execute(user_id, self.doc_id, icon, color)
+            .await
+        }
+    }
+
+    #[tokio::test]
+    async fn appearance_is_stored_and_listed() {
+        let store = Store::new();
+        let doc = store
+            .update(store.owner, Some("🚀"), Some("#3B82F6"))
+            .await
+            .unwrap();
+        assert_eq!(doc.icon.as_deref(), Some("🚀"));
+        assert_eq!(doc.color.as_deref(), Some("#3b82f6"));
+
+        let listing = ListDocuments { repo: &*store.docs }
+            .execute(store.owner, DocumentListFilter::default(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(listing.page.items[0].icon.as_deref(), Some("🚀"));
+        assert_eq!(listing.page.items[0].color.as_deref(), Some("#3b82f6"));
+
+        let cleared = store
+            .update(store.owner, Some("Folder"), None)
+            .await
+            .unwrap();
+        assert_eq!(cleared.icon.as_deref(), Some("folder"));
+        assert_eq!(cleared.color, None);
+    }
+
+    #[tokio::test]
+    async fn invalid_color_and_icon_are_rejected() {
+        let store = Store::new();
+        for color in ["red", "#12345", "#ggg", "3b82f6", "#3b82f6; x"] {
+            assert!(matches!(
+                store.update(store.owner, None, Some(color)).await,
+                Err(AppearanceError::InvalidColor)
+            ));
+        }
+        for icon in ["not-an-icon", "a🚀", "🚀🚀🚀🚀🚀🚀🚀🚀🚀🚀🚀"] {
+            assert!(matches!(
+                store.update(store.owner, Some(icon), None).await,
+                Err(AppearanceError::InvalidIcon)
+            ));
+        }
+        assert_eq!(store.doc().color, None);
+        assert_eq!(
+            normalize_color(Some("#AbC")).unwrap().as_deref(),
+            Some("#aabbcc")
+        );
+    }
+
+    #[tokio::test]
+    async fn viewers_cannot_change_appearance() {
+        let store = Store::new();
+        assert!(matches!(
+            store.update(store.viewer, Some("star"), None).await,
+            Err(AppearanceError::Forbidden)
+        ));
+        assert!(matches!(
+            store.update(Uuid::new_v4(), Some("star"), None).await,
+            Err(AppearanceError::NotFound)
+        ));
+    }
+}
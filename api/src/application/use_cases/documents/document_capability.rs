@@ -0,0 +1,65 @@
+use uuid::Uuid;
+
+use crate::application::access::{self, AccessPolicy, Actor, Capability};
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::share_access_port::ShareAccessPort;
+
+/// What the actor may do with a document right now, so clients can pick a read-only or
+/// editable view before opening a realtime connection.
+pub struct GetDocumentCapability<'a, A, S>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a S,
+    pub policy: AccessPolicy,
+}
+
+impl<'a, A, S> GetDocumentCapability<'a, A, S>
+where
+    A: AccessRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+{
+    pub async fn execute(&self, actor: &Actor, doc_id: Uuid) -> Capability {
+        access::resolve_document(self.access, self.shares, self.policy, actor, doc_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::application::ports::shares_repository::SharesRepository;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::shares::MemoryShares;
+
+    #[tokio::test]
+    async fn capability_reflects_owner_share_and_stranger() {
+        let docs = Arc::new(MemoryDocuments::default());
+        let shares = MemoryShares::new(docs.clone());
+        let owner = Uuid::new_v4();
+        let doc_id = docs.add(owner, "Plan", "document", None).id;
+        let (token, _, _) = shares
+            .create_share(owner, doc_id, "view", None, None)
+            .await
+            .unwrap();
+        let uc = GetDocumentCapability {
+            access: docs.as_ref(),
+            shares: &shares,
+            policy: AccessPolicy::default(),
+        };
+
+        let owner = Actor::User(owner);
+        assert_eq!(uc.execute(&owner, doc_id).await, Capability::Edit);
+
+        let viewer = Actor::ShareToken(token);
+        assert_eq!(uc.execute(&viewer, doc_id).await, Capability::View);
+
+        let stranger = Actor::User(Uuid::new_v4());
+        assert_eq!(uc.execute(&stranger, doc_id).await, Capability::None);
+        let bad_token = Actor::ShareToken("other".to_string());
+        assert_eq!(uc.execute(&bad_token, doc_id).await, Capability::None);
+    }
+}
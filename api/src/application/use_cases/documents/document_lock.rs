@@ -0,0 +1,37 @@
+use uuid::Uuid;
+
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::document_repository::DocumentRepository;
+use crate::application::ports::realtime_port::RealtimeEngine;
+
+/// Locks or unlocks a document. Only the owner may change the lock; while it is set every
+/// actor, the owner included, gets at most view access.
+pub struct SetDocumentLock<'a, A, D, R>
+where
+    A: AccessRepository + ?Sized,
+    D: DocumentRepository + ?Sized,
+    R: RealtimeEngine + ?Sized,
+{
+    pub access: &'a A,
+    pub documents: &'a D,
+    pub realtime: &'a R,
+}
+
+impl<'a, A, D, R> SetDocumentLock<'a, A, D, R>
+where
+    A: AccessRepository + ?Sized,
+    D: DocumentRepository + ?Sized,
+    R: RealtimeEngine + ?Sized,
+{
+    pub async fn execute(&self, doc_id: Uuid, user_id: Uuid, locked: bool) -> anyhow::Result<bool> {
+        if !self.access.user_owns_document(doc_id, user_id).await? {
+            return Ok(false);
+        }
+        self.documents.set_locked(doc_id, locked).await?;
+        if let Err(e) = self.realtime.set_locked(&doc_id.to_string(), locked).await {
+            // The stored flag is authoritative for new connections; only live sessions miss out.
+            tracing::warn!(document_id = %doc_id, error = ?e, "realtime_set_locked_failed");
+        }
+        Ok(true)
+    }
+}
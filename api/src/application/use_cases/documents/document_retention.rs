@@ -0,0 +1,62 @@
+use uuid::Uuid;
+
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::document_retention_repository::{
+    DocumentRetention, DocumentRetentionRepository,
+};
+
+pub struct GetDocumentRetention<'a, A, R>
+where
+    A: AccessRepository + ?Sized,
+    R: DocumentRetentionRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub retention: &'a R,
+}
+
+impl<'a, A, R> GetDocumentRetention<'a, A, R>
+where
+    A: AccessRepository + ?Sized,
+    R: DocumentRetentionRepository + ?Sized,
+{
+    /// Returns `None` when the user does not own the document.
+    pub async fn execute(
+        &self,
+        doc_id: Uuid,
+        user_id: Uuid,
+    ) -> anyhow::Result<Option<DocumentRetention>> {
+        if !self.access.user_owns_document(doc_id, user_id).await? {
+            return Ok(None);
+        }
+        Ok(Some(self.retention.get(doc_id).await?.unwrap_or_default()))
+    }
+}
+
+pub struct UpdateDocumentRetention<'a, A, R>
+where
+    A: AccessRepository + ?Sized,
+    R: DocumentRetentionRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub retention: &'a R,
+}
+
+impl<'a, A, R> UpdateDocumentRetention<'a, A, R>
+where
+    A: AccessRepository + ?Sized,
+    R: DocumentRetentionRepository + ?Sized,
+{
+    /// Replaces the override; clearing both fields reverts the document to the global defaults.
+    pub async fn execute(
+        &self,
+        doc_id: Uuid,
+        user_id: Uuid,
+        retention: DocumentRetention,
+    ) -> anyhow::Result<Option<DocumentRetention>> {
+        if !self.access.user_owns_document(doc_id, user_id).await? {
+            return Ok(None);
+        }
+        self.retention.upsert(doc_id, retention).await?;
+        Ok(Some(retention))
+    }
+}
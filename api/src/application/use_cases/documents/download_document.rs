@@ -3,7 +3,7 @@ use std::path::{Component, PathBuf};
 
 use uuid::Uuid;
 
-use crate::application::access::{self, Actor, Capability};
+use crate::application::access::{self, AccessPolicy, Actor, Capability};
 use crate::application::ports::access_repository::AccessRepository;
 use crate::application::ports::document_repository::DocumentRepository;
 use crate::application::ports::files_repository::FilesRepository;
@@ -11,8 +11,30 @@ use crate::application::ports::realtime_port::RealtimeEngine;
 use crate::application::ports::share_access_port::ShareAccessPort;
 use crate::application::ports::storage_port::StoragePort;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DownloadFormat {
+    /// Zip with the markdown file and its attachments.
+    #[default]
+    Archive,
+    /// The markdown file alone.
+    Markdown,
+}
+
+impl std::str::FromStr for DownloadFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "archive" | "zip" => Ok(DownloadFormat::Archive),
+            "markdown" | "md" => Ok(DownloadFormat::Markdown),
+            other => Err(anyhow::anyhow!("unsupported download format: {}", other)),
+        }
+    }
+}
+
 pub struct DocumentDownload {
     pub filename: String,
+    pub content_type: &'static str,
     pub bytes: Vec<u8>,
 }
 
@@ -31,6 +53,7 @@ where
     pub realtime: &'a RT,
     pub access: &'a A,
     pub shares: &'a SH,
+    pub policy: AccessPolicy,
 }
 
 impl<'a, D, F, S, RT, A, SH> DownloadDocument<'a, D, F, S, RT, A, SH>
@@ -46,8 +69,10 @@ where
         &self,
         actor: &Actor,
         doc_id: Uuid,
+        format: DownloadFormat,
     ) -> anyhow::Result<Option<DocumentDownload>> {
-        let capability = access::resolve_document(self.access, self.shares, actor, doc_id).await;
+        let capability =
+            access::resolve_document(self.access, self.shares, self.policy, actor, doc_id).await;
         if capability < Capability::View {
             return Ok(None);
         }
@@ -69,6 +94,14 @@ where
             .map(PathBuf::from)
             .ok_or_else(|| anyhow::anyhow!("document directory missing"))?;
         let markdown_bytes = self.storage.read_bytes(markdown_path.as_path()).await?;
+        let safe_title = sanitize_filename(&document.title);
+        if format == DownloadFormat::Markdown {
+            return Ok(Some(DocumentDownload {
+                filename: format!("{}.md", safe_title),
+                content_type: "text/markdown; charset=utf-8",
+                bytes: markdown_bytes,
+            }));
+        }
 
         let stored_attachments = self.files.list_storage_paths_for_document(doc_id).await?;
         let mut attachments: Vec<(String, Vec<u8>)> = Vec::new();
@@ -95,7 +128,6 @@ where
             attachments.push((rel_str, data));
         }
 
-        let safe_title = sanitize_filename(&document.title);
         let archive_name = format!("{}.zip", safe_title);
         let markdown_entry = format!("{}/{}.md", safe_title, safe_title);
         let mut cursor = std::io::Cursor::new(Vec::new());
@@ -117,6 +149,7 @@ where
 
         Ok(Some(DocumentDownload {
             filename: archive_name,
+            content_type: "application/zip",
             bytes,
         }))
     }
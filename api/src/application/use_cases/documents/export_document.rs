@@ -0,0 +1,210 @@
+use std::future::Future;
+use std::str::FromStr;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use uuid::Uuid;
+
+use crate::application::access::{self, AccessPolicy, Actor, Capability};
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::document_repository::DocumentRepository;
+use crate::application::ports::pdf_renderer::PdfRenderer;
+use crate::application::ports::realtime_port::RealtimeEngine;
+use crate::application::ports::share_access_port::ShareAccessPort;
+use crate::application::ports::storage_port::StoragePort;
+use crate::application::services::markdown::RenderOptions;
+
+const PRINT_STYLESHEET: &str = r#"
+@page { size: A4; margin: 20mm; }
+body { font-family: "Helvetica Neue", Helvetica, Arial, sans-serif; font-size: 11pt; line-height: 1.5; color: #1f2328; }
+h1, h2 { break-before: page; }
+h1:first-child, h2:first-child { break-before: avoid; }
+h1, h2, h3, h4, h5, h6 { break-after: avoid; line-height: 1.25; }
+pre, blockquote, table, img { break-inside: avoid; }
+pre { background: #f6f8fa; padding: 8pt; font-size: 9.5pt; white-space: pre-wrap; }
+code { font-family: "SFMono-Regular", Menlo, Consolas, monospace; }
+img { max-width: 100%; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #d0d7de; padding: 4pt 8pt; }
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Html,
+    Pdf,
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "html" => Ok(ExportFormat::Html),
+            "pdf" => Ok(ExportFormat::Pdf),
+            other => Err(anyhow::anyhow!("unsupported export format: {}", other)),
+        }
+    }
+}
+
+pub struct DocumentExport {
+    pub filename: String,
+    pub content_type: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+pub struct ExportDocument<'a, D, S, RT, A, SH, P>
+where
+    D: DocumentRepository + ?Sized,
+    S: StoragePort + ?Sized,
+    RT: RealtimeEngine + ?Sized,
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    P: PdfRenderer + ?Sized,
+{
+    pub documents: &'a D,
+    pub storage: &'a S,
+    pub realtime: &'a RT,
+    pub access: &'a A,
+    pub shares: &'a SH,
+    pub policy: AccessPolicy,
+    pub pdf: &'a P,
+}
+
+impl<'a, D, S, RT, A, SH, P> ExportDocument<'a, D, S, RT, A, SH, P>
+where
+    D: DocumentRepository + ?Sized,
+    S: StoragePort + ?Sized,
+    RT: RealtimeEngine + ?Sized,
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    P: PdfRenderer + ?Sized,
+{
+    /// `render` turns the markdown into HTML the way interactive renders do (wikilinks,
+    /// placeholder renderers); `options` carry the instance defaults. Attachments are always
+    /// made absolute and the output sanitized so images can be inlined safely.
+    pub async fn execute<F, Fut>(
+        &self,
+        actor: &Actor,
+        doc_id: Uuid,
+        format: ExportFormat,
+        options: RenderOptions,
+        render: F,
+    ) -> anyhow::Result<Option<DocumentExport>>
+    where
+        F: FnOnce(String, RenderOptions) -> Fut,
+        Fut: Future<Output = anyhow::Result<String>>,
+    {
+        let capability =
+            access::resolve_document(self.access, self.shares, self.policy, actor, doc_id).await;
+        if capability < Capability::View {
+            return Ok(None);
+        }
+        let document = match self.documents.get_by_id(doc_id).await? {
+            Some(doc) if doc.doc_type != "folder" => doc,
+            _ => return Ok(None),
+        };
+
+        let content = self
+            .realtime
+            .get_content(&doc_id.to_string())
+            .await?
+            .unwrap_or_default();
+        let options = RenderOptions {
+            absolute_attachments: Some(true),
+            sanitize: Some(true),
+            ..options.for_document(doc_id)
+        };
+        let rendered = render(content, options).await?;
+        let body = self.inline_images(doc_id, &rendered).await;
+        let html = styled_html(&document.title, &body);
+
+        let base_name = export_basename(&document.title);
+        let export = match format {
+            ExportFormat::Html => DocumentExport {
+                filename: format!("{}.html", base_name),
+                content_type: "text/html; charset=utf-8",
+                bytes: html.into_bytes(),
+            },
+            ExportFormat::Pdf => DocumentExport {
+                filename: format!("{}.pdf", base_name),
+                content_type: "application/pdf",
+                bytes: self.pdf.render_pdf(&html).await?,
+            },
+        };
+        Ok(Some(export))
+    }
+
+    /// Replaces attachment image URLs with data URIs so the export is self-contained.
+    async fn inline_images(&self, doc_id: Uuid, html: &str) -> String {
+        let prefix = format!("src=\"/api/uploads/{}/", doc_id);
+        let mut out = String::with_capacity(html.len());
+        let mut rest = html;
+        while let Some(pos) = rest.find(&prefix) {
+            let after = &rest[pos + prefix.len()..];
+            let Some(end) = after.find('"') else {
+                break;
+            };
+            out.push_str(&rest[..pos]);
+            let raw = &after[..end];
+            let rel = raw.split(['?', '#']).next().unwrap_or(raw);
+            match self.attachment_data_uri(doc_id, rel).await {
+                Some(uri) => {
+                    out.push_str("src=\"");
+                    out.push_str(&uri);
+                    out.push('"');
+                }
+                None => out.push_str(&rest[pos..pos + prefix.len() + end + 1]),
+            }
+            rest = &after[end + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    async fn attachment_data_uri(&self, doc_id: Uuid, rel: &str) -> Option<String> {
+        let rel = urlencoding::decode(rel).ok()?;
+        let path = self.storage.resolve_upload_path(doc_id, &rel).await.ok()?;
+        let mime = mime_guess::from_path(&path).first_or_octet_stream();
+        if mime.type_() != mime_guess::mime::IMAGE {
+            return None;
+        }
+        match self.storage.read_bytes(&path).await {
+            Ok(bytes) => Some(format!(
+                "data:{};base64,{}",
+                mime.essence_str(),
+                BASE64_STANDARD.encode(bytes)
+            )),
+            Err(err) => {
+                tracing::warn!(document_id = %doc_id, error = ?err, "export_inline_image_failed");
+                None
+            }
+        }
+    }
+}
+
+pub fn styled_html(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title><style>{}</style></head>\n<body>\n{}\n</body></html>\n",
+        htmlescape::encode_minimal(title),
+        PRINT_STYLESHEET,
+        body
+    )
+}
+
+fn export_basename(title: &str) -> String {
+    let cleaned: String = title
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '\0' => '-',
+            ' ' => '_',
+            c => c,
+        })
+        .take(100)
+        .collect();
+    if cleaned.is_empty() {
+        "document".to_string()
+    } else {
+        cleaned
+    }
+}
@@ -1,6 +1,6 @@
 use uuid::Uuid;
 
-use crate::application::access::{self, Actor, Capability};
+use crate::application::access::{self, AccessPolicy, Actor, Capability};
 use crate::application::ports::access_repository::AccessRepository;
 use crate::application::ports::document_repository::DocumentRepository;
 use crate::application::ports::share_access_port::ShareAccessPort;
@@ -14,6 +14,7 @@ where
 {
     pub repo: &'a R,
     pub shares: &'a S,
+    pub policy: AccessPolicy,
     pub access: &'a A,
 }
 
@@ -25,7 +26,7 @@ where
 {
     pub async fn execute(&self, actor: &Actor, id: Uuid) -> anyhow::Result<Option<DomainDocument>> {
         // Enforce view permission using existing access policy
-        let cap = access::resolve_document(self.access, self.shares, actor, id).await;
+        let cap = access::resolve_document(self.access, self.shares, self.policy, actor, id).await;
         if cap < Capability::View {
             return Ok(None);
         }
@@ -0,0 +1,219 @@
+use uuid::Uuid;
+
+use crate::application::access::{AccessPolicy, Actor};
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::document_repository::DocumentRepository;
+use crate::application::ports::share_access_port::ShareAccessPort;
+use crate::application::ports::user_repository::UserRepository;
+use crate::application::use_cases::documents::get_document::GetDocument;
+use crate::domain::documents::document::Document as DomainDocument;
+
+#[derive(thiserror::Error, Debug)]
+pub enum HomeDocumentError {
+    #[error("document not found")]
+    NotFound,
+    #[error("only documents can be set as home")]
+    NotADocument,
+    #[error(transparent)]
+    Repository(#[from] anyhow::Error),
+}
+
+pub struct GetHomeDocument<'a, U, R, S, A>
+where
+    U: UserRepository + ?Sized,
+    R: DocumentRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    A: AccessRepository + ?Sized,
+{
+    pub users: &'a U,
+    pub repo: &'a R,
+    pub shares: &'a S,
+    pub access: &'a A,
+    pub policy: AccessPolicy,
+}
+
+impl<'a, U, R, S, A> GetHomeDocument<'a, U, R, S, A>
+where
+    U: UserRepository + ?Sized,
+    R: DocumentRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    A: AccessRepository + ?Sized,
+{
+    /// `None` when no home is set or the user can no longer view it.
+    pub async fn execute(&self, user_id: Uuid) -> anyhow::Result<Option<DomainDocument>> {
+        let Some(doc_id) = self.users.home_document(user_id).await? else {
+            return Ok(None);
+        };
+        GetDocument {
+            repo: self.repo,
+            shares: self.shares,
+            access: self.access,
+            policy: self.policy,
+        }
+        .execute(&Actor::User(user_id), doc_id)
+        .await
+    }
+}
+
+pub struct SetHomeDocument<'a, U, R, S, A>
+where
+    U: UserRepository + ?Sized,
+    R: DocumentRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    A: AccessRepository + ?Sized,
+{
+    pub users: &'a U,
+    pub repo: &'a R,
+    pub shares: &'a S,
+    pub access: &'a A,
+    pub policy: AccessPolicy,
+}
+
+impl<'a, U, R, S, A> SetHomeDocument<'a, U, R, S, A>
+where
+    U: UserRepository + ?Sized,
+    R: DocumentRepository + ?Sized,
+    S: ShareAccessPort + ?Sized,
+    A: AccessRepository + ?Sized,
+{
+    /// Sets (or with `None`, clears) the home document and returns it.
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        doc_id: Option<Uuid>,
+    ) -> Result<Option<DomainDocument>, HomeDocumentError> {
+        let doc = match doc_id {
+            Some(id) => {
+                let doc = GetDocument {
+                    repo: self.repo,
+                    shares: self.shares,
+                    access: self.access,
+                    policy: self.policy,
+                }
+                .execute(&Actor::User(user_id), id)
+                .await?
+                .ok_or(HomeDocumentError::NotFound)?;
+                if doc.doc_type != "document" {
+                    return Err(HomeDocumentError::NotADocument);
+                }
+                Some(doc)
+            }
+            None => None,
+        };
+        self.users.set_home_document(user_id, doc_id).await?;
+        Ok(doc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::shares::MemoryShares;
+    use crate::test_support::users::MemoryUsers;
+
+    struct Store {
+        owner: Uuid,
+        users: MemoryUsers,
+        docs: Arc<MemoryDocuments>,
+        shares: MemoryShares,
+        /// "Start here" and the "Projects" folder.
+        ids: [Uuid; 2],
+    }
+
+    impl Store {
+        fn new() -> Self {
+            let users = MemoryUsers::default();
+            let owner = users.add("Owner", "owner@example.com").id;
+            let docs = Arc::new(MemoryDocuments::default());
+            let ids = [
+                docs.add(owner, "Start here", "document", None).id,
+                docs.add(owner, "Projects", "folder", None).id,
+            ];
+            Self {
+                owner,
+                users,
+                shares: MemoryShares::new(docs.clone()),
+                docs,
+                ids,
+            }
+        }
+
+        fn home(&self) -> Option<Uuid> {
+            self.users.homes.lock().unwrap().get(&self.owner).copied()
+        }
+    }
+
+    type UseCases<'a> = (
+        GetHomeDocument<'a, MemoryUsers, MemoryDocuments, MemoryShares, MemoryDocuments>,
+        SetHomeDocument<'a, MemoryUsers, MemoryDocuments, MemoryShares, MemoryDocuments>,
+    );
+
+    fn use_cases(store: &Store) -> UseCases<'_> {
+        (
+            GetHomeDocument {
+                users: &store.users,
+                repo: &*store.docs,
+                shares: &store.shares,
+                policy: AccessPolicy::default(),
+                access: &*store.docs,
+            },
+            SetHomeDocument {
+                users: &store.users,
+                repo: &*store.docs,
+                shares: &store.shares,
+                policy: AccessPolicy::default(),
+                access: &*store.docs,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn setting_and_clearing_the_home_document() {
+        let store = Store::new();
+        let (get, set) = use_cases(&store);
+        let home = store.ids[0];
+
+        assert!(get.execute(store.owner).await.unwrap().is_none());
+        let doc = set.execute(store.owner, Some(home)).await.unwrap();
+        assert_eq!(doc.map(|d| d.id), Some(home));
+        let resolved = get.execute(store.owner).await.unwrap().unwrap();
+        assert_eq!(resolved.title, "Start here");
+
+        assert!(set.execute(store.owner, None).await.unwrap().is_none());
+        assert!(get.execute(store.owner).await.unwrap().is_none());
+        assert_eq!(store.home(), None);
+    }
+
+    #[tokio::test]
+    async fn unviewable_documents_and_folders_are_rejected() {
+        let store = Store::new();
+        let (get, set) = use_cases(&store);
+        let stranger = Uuid::new_v4();
+
+        assert!(matches!(
+            set.execute(stranger, Some(store.ids[0])).await,
+            Err(HomeDocumentError::NotFound)
+        ));
+        assert!(matches!(
+            set.execute(store.owner, Some(Uuid::new_v4())).await,
+            Err(HomeDocumentError::NotFound)
+        ));
+        assert!(matches!(
+            set.execute(store.owner, Some(store.ids[1])).await,
+            Err(HomeDocumentError::NotADocument)
+        ));
+        assert_eq!(store.home(), None);
+
+        // A home the user lost access to resolves to nothing.
+        store
+            .users
+            .homes
+            .lock()
+            .unwrap()
+            .insert(stranger, store.ids[0]);
+        assert!(get.execute(stranger).await.unwrap().is_none());
+    }
+}
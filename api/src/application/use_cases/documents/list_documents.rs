@@ -1,7 +1,17 @@
 use uuid::Uuid;
 
-use crate::application::ports::document_repository::DocumentRepository;
-use crate::domain::documents::document::Document as DomainDocument;
+use crate::application::ports::document_repository::{
+    DocumentListFilter, DocumentPage, DocumentRepository,
+};
+
+pub const DEFAULT_PAGE_SIZE: i64 = 100;
+pub const MAX_PAGE_SIZE: i64 = 500;
+
+pub struct DocumentListing {
+    pub page: DocumentPage,
+    /// Offset of the next page, if more documents match.
+    pub next_offset: Option<i64>,
+}
 
 pub struct ListDocuments<'a, R: DocumentRepository + ?Sized> {
     pub repo: &'a R,
@@ -11,9 +21,163 @@ impl<'a, R: DocumentRepository + ?Sized> ListDocuments<'a, R> {
     pub async fn execute(
         &self,
         user_id: Uuid,
-        query: Option<String>,
-        tag: Option<String>,
-    ) -> anyhow::Result<Vec<DomainDocument>> {
-        self.repo.list_for_user(user_id, query, tag).await
+        filter: DocumentListFilter,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> anyhow::Result<DocumentListing> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let offset = offset.unwrap_or(0).max(0);
+        let filter = DocumentListFilter {
+            query: filter.query.filter(|s| !s.trim().is_empty()),
+            tag: filter.tag.filter(|s| !s.trim().is_empty()),
+            ..filter
+        };
+        let page = self
+            .repo
+            .list_for_user(user_id, &filter, limit, offset)
+            .await?;
+        let end = offset + page.items.len() as i64;
+        let next_offset = (!page.items.is_empty() && end < page.total).then_some(end);
+        Ok(DocumentListing { page, next_offset })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::ports::document_repository::LinkCounts;
+    use crate::test_support::documents::MemoryDocuments;
+
+    struct Docs {
+        owner: Uuid,
+        repo: MemoryDocuments,
+        ids: Vec<Uuid>,
+    }
+
+    impl Docs {
+        /// Documents edited `i` hours ago, alternating folder/document.
+        fn new(n: i64) -> Self {
+            let owner = Uuid::new_v4();
+            let repo = MemoryDocuments::default();
+            let now = chrono::Utc::now();
+            let ids = (0..n)
+                .map(|i| {
+                    let doc_type = if i % 2 == 0 { "document" } else { "folder" };
+                    let id = repo.add(owner, &format!("doc {}", i), doc_type, None).id;
+                    repo.update(id, |d| d.doc.updated_at = now - chrono::Duration::hours(i));
+                    id
+                })
+                .collect();
+            Self { owner, repo, ids }
+        }
+
+        fn link(self, from: usize, to: usize) -> Self {
+            self.repo.link(self.ids[from], self.ids[to]);
+            self
+        }
+
+        fn updated_at(&self, i: usize) -> chrono::DateTime<chrono::Utc> {
+            self.repo.get(self.ids[i]).unwrap().doc.updated_at
+        }
+    }
+
+    async fn list(
+        docs: &Docs,
+        filter: DocumentListFilter,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> DocumentListing {
+        ListDocuments { repo: &docs.repo }
+            .execute(docs.owner, filter, limit, offset)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pages_until_the_last_item() {
+        let docs = Docs::new(5);
+        let first = list(&docs, DocumentListFilter::default(), Some(2), None).await;
+        assert_eq!(first.page.items.len(), 2);
+        assert_eq!(first.page.total, 5);
+        assert_eq!(first.next_offset, Some(2));
+
+        let last = list(&docs, DocumentListFilter::default(), Some(2), Some(4)).await;
+        assert_eq!(last.page.items.len(), 1);
+        assert_eq!(last.page.items[0].id, docs.ids[4]);
+        assert_eq!(last.next_offset, None);
+
+        // An exact fit leaves no next page, and paging past the end is empty.
+        let exact = list(&docs, DocumentListFilter::default(), Some(5), None).await;
+        assert_eq!(exact.next_offset, None);
+        let beyond = list(&docs, DocumentListFilter::default(), Some(2), Some(10)).await;
+        assert!(beyond.page.items.is_empty());
+        assert_eq!(beyond.next_offset, None);
+
+        // Out-of-range limits are clamped rather than rejected.
+        let clamped = list(&docs, DocumentListFilter::default(), Some(0), Some(-3)).await;
+        assert_eq!(clamped.page.items.len(), 1);
+        assert_eq!(clamped.next_offset, Some(1));
+    }
+
+    #[tokio::test]
+    async fn filters_by_type_and_updated_since() {
+        let docs = Docs::new(6);
+        let folders = list(
+            &docs,
+            DocumentListFilter {
+                doc_type: Some("folder".into()),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await;
+        assert_eq!(folders.page.total, 3);
+        assert!(folders.page.items.iter().all(|d| d.doc_type == "folder"));
+
+        let recent = list(
+            &docs,
+            DocumentListFilter {
+                updated_since: Some(docs.updated_at(2)),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await;
+        let ids: Vec<Uuid> = recent.page.items.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![docs.ids[0], docs.ids[1], docs.ids[2]]);
+    }
+
+    #[tokio::test]
+    async fn link_counts_are_returned_only_on_request() {
+        // 0 -> 1, 0 -> 2, 2 -> 1, and 1 links to itself
+        let docs = Docs::new(4).link(0, 1).link(0, 2).link(2, 1).link(1, 1);
+        let counted = list(
+            &docs,
+            DocumentListFilter {
+                include_link_counts: true,
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await;
+        let counts = counted.page.link_counts.expect("link counts");
+        let expected = [(0, 0, 2), (1, 3, 1), (2, 1, 1), (3, 0, 0)];
+        for (i, backlinks, outgoing) in expected {
+            assert_eq!(
+                counts[&docs.ids[i]],
+                LinkCounts {
+                    backlinks,
+                    outgoing
+                },
+                "doc {}",
+                i
+            );
+        }
+
+        let plain = list(&docs, DocumentListFilter::default(), None, None).await;
+        assert!(plain.page.link_counts.is_none());
     }
 }
@@ -0,0 +1,21 @@
+use uuid::Uuid;
+
+use crate::application::ports::mention_repository::{MentionRepository, MentionRow};
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+pub struct ListMentions<'a, R: MentionRepository + ?Sized> {
+    pub repo: &'a R,
+}
+
+impl<'a, R: MentionRepository + ?Sized> ListMentions<'a, R> {
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        limit: Option<i64>,
+    ) -> anyhow::Result<Vec<MentionRow>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        self.repo.list_for_user(user_id, limit).await
+    }
+}
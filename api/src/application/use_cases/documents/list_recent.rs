@@ -0,0 +1,25 @@
+use uuid::Uuid;
+
+use crate::application::ports::document_repository::DocumentRepository;
+use crate::domain::documents::document::Document as DomainDocument;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+
+pub struct ListRecentDocuments<'a, R: DocumentRepository + ?Sized> {
+    pub repo: &'a R,
+}
+
+impl<'a, R: DocumentRepository + ?Sized> ListRecentDocuments<'a, R> {
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        limit: Option<i64>,
+        include_shared: bool,
+    ) -> anyhow::Result<Vec<DomainDocument>> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        self.repo
+            .list_recent_for_user(user_id, limit, include_shared)
+            .await
+    }
+}
@@ -1,9 +1,22 @@
+pub mod comments;
 pub mod create_document;
 pub mod delete_document;
+pub mod document_appearance;
+pub mod document_capability;
+pub mod document_lock;
+pub mod document_retention;
 pub mod download_document;
+pub mod export_document;
 pub mod get_backlinks;
 pub mod get_document;
 pub mod get_outgoing_links;
+pub mod home_document;
 pub mod list_documents;
+pub mod list_mentions;
+pub mod list_recent;
+pub mod render_tree;
 pub mod search_documents;
 pub mod update_document;
+pub mod user_access;
+pub mod versions;
+pub mod write_content;
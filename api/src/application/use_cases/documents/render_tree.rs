@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::application::access::{self, AccessPolicy, Actor, Capability};
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::realtime_port::RealtimeEngine;
+use crate::application::ports::share_access_port::ShareAccessPort;
+use crate::application::ports::shares_repository::SharesRepository;
+use crate::application::services::markdown::locale::{self, Message};
+use crate::application::services::markdown::{self as md, RenderOptions};
+
+use super::export_document::styled_html;
+
+pub const MAX_TREE_DOCUMENTS: usize = 200;
+pub const MAX_TREE_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct RenderedTreeEntry {
+    pub id: Uuid,
+    pub title: String,
+    pub anchor: String,
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderedTree {
+    pub html: String,
+    pub documents: Vec<RenderedTreeEntry>,
+    /// Documents left out because the actor cannot view them.
+    pub skipped: usize,
+    /// True when the document or size limit cut the output short.
+    pub truncated: bool,
+}
+
+pub struct RenderDocumentTree<'a, T, A, SH, RT>
+where
+    T: SharesRepository + ?Sized,
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    RT: RealtimeEngine + ?Sized,
+{
+    pub tree: &'a T,
+    pub access: &'a A,
+    pub shares: &'a SH,
+    pub policy: AccessPolicy,
+    pub realtime: &'a RT,
+    pub max_documents: usize,
+    pub max_bytes: usize,
+}
+
+impl<'a, T, A, SH, RT> RenderDocumentTree<'a, T, A, SH, RT>
+where
+    T: SharesRepository + ?Sized,
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    RT: RealtimeEngine + ?Sized,
+{
+    pub async fn execute(
+        &self,
+        actor: &Actor,
+        folder_id: Uuid,
+        options: RenderOptions,
+    ) -> anyhow::Result<Option<RenderedTree>> {
+        let capability =
+            access::resolve_document(self.access, self.shares, self.policy, actor, folder_id).await;
+        if capability < Capability::View {
+            return Ok(None);
+        }
+        let nodes = self.tree.list_subtree_nodes(folder_id).await?;
+        let root_title = match nodes.iter().find(|n| n.0 == folder_id) {
+            Some((_, title, dtype, ..)) if dtype == "folder" => title.clone(),
+            _ => return Ok(None),
+        };
+
+        let mut children: HashMap<Uuid, Vec<(Uuid, String, bool)>> = HashMap::new();
+        for (id, title, dtype, parent_id, ..) in nodes.iter().filter(|n| n.0 != folder_id) {
+            if let Some(parent) = parent_id {
+                children
+                    .entry(*parent)
+                    .or_default()
+                    .push((*id, title.clone(), dtype == "folder"));
+            }
+        }
+        for list in children.values_mut() {
+            // Documents before sub-folders, each alphabetically.
+            list.sort_by(|a, b| {
+                a.2.cmp(&b.2)
+                    .then_with(|| a.1.to_lowercase().cmp(&b.1.to_lowercase()))
+            });
+        }
+
+        let mut ordered = Vec::new();
+        collect_documents(&children, folder_id, 0, &mut ordered);
+
+        let mut result = RenderedTree {
+            html: String::new(),
+            documents: Vec::new(),
+            skipped: 0,
+            truncated: false,
+        };
+        let mut sections = String::new();
+        let mut total_bytes = 0usize;
+        for (doc_id, title, depth) in ordered {
+            let capability =
+                access::resolve_document(self.access, self.shares, self.policy, actor, doc_id)
+                    .await;
+            if capability < Capability::View {
+                result.skipped += 1;
+                continue;
+            }
+            if result.documents.len() >= self.max_documents {
+                result.truncated = true;
+                break;
+            }
+            let content = self
+                .realtime
+                .get_content(&doc_id.to_string())
+                .await?
+                .unwrap_or_default();
+            if total_bytes + content.len() > self.max_bytes {
+                result.truncated = true;
+                break;
+            }
+            total_bytes += content.len();
+
+            let rendered = md::render(
+                content,
+                RenderOptions {
+                    doc_id: Some(doc_id),
+                    absolute_attachments: Some(true),
+                    sanitize: Some(true),
+                    ..options.clone()
+                },
+                None,
+            )?;
+            let anchor = format!("doc-{}", doc_id);
+            sections.push_str(&format!(
+                "<section class=\"tree-document\" id=\"{}\">\n<h1>{}</h1>\n{}\n</section>\n",
+                anchor,
+                htmlescape::encode_minimal(&title),
+                rendered.html
+            ));
+            result.documents.push(RenderedTreeEntry {
+                id: doc_id,
+                title,
+                anchor,
+                depth,
+            });
+        }
+
+        let mut toc = format!(
+            "<nav class=\"tree-toc\">\n<h2>{}</h2>\n<ol>\n",
+            locale::message(options.locale.as_deref(), Message::TableOfContents)
+        );
+        for entry in &result.documents {
+            toc.push_str(&format!(
+                "<li class=\"toc-depth-{}\"><a href=\"#{}\">{}</a></li>\n",
+                entry.depth,
+                entry.anchor,
+                htmlescape::encode_minimal(&entry.title)
+            ));
+        }
+        toc.push_str("</ol>\n</nav>\n");
+
+        result.html = styled_html(&root_title, &format!("{}{}", toc, sections));
+        Ok(Some(result))
+    }
+}
+
+/// Pre-order walk collecting `(id, title, depth)` for every document below `parent`;
+/// a folder's own documents come before those of its sub-folders.
+fn collect_documents(
+    children: &HashMap<Uuid, Vec<(Uuid, String, bool)>>,
+    parent: Uuid,
+    depth: usize,
+    out: &mut Vec<(Uuid, String, usize)>,
+) {
+    let Some(list) = children.get(&parent) else {
+        return;
+    };
+    for (id, title, is_folder) in list {
+        if *is_folder {
+            collect_documents(children, *id, depth + 1, out);
+        } else {
+            out.push((*id, title.clone(), depth));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::realtime::MemoryRealtime;
+    use crate::test_support::shares::MemoryShares;
+
+    struct Tree {
+        owner: Uuid,
+        docs: Arc<MemoryDocuments>,
+        shares: MemoryShares,
+        realtime: MemoryRealtime,
+    }
+
+    impl Tree {
+        fn new() -> Self {
+            let docs = Arc::new(MemoryDocuments::default());
+            Self {
+                owner: Uuid::new_v4(),
+                shares: MemoryShares::new(docs.clone()),
+                docs,
+                realtime: MemoryRealtime::default(),
+            }
+        }
+
+        fn add(&mut self, title: &str, dtype: &str, parent: Option<Uuid>, content: &str) -> Uuid {
+            let id = self.docs.add(self.owner, title, dtype, parent).id;
+            self.realtime.set(id, content);
+            id
+        }
+    }
+
+    fn renderer(
+        tree: &Tree,
+    ) -> RenderDocumentTree<'_, MemoryShares, MemoryDocuments, MemoryShares, MemoryRealtime> {
+        RenderDocumentTree {
+            tree: &tree.shares,
+            access: &*tree.docs,
+            shares: &tree.shares,
+            policy: AccessPolicy::default(),
+            realtime: &tree.realtime,
+            max_documents: MAX_TREE_DOCUMENTS,
+            max_bytes: MAX_TREE_BYTES,
+        }
+    }
+
+    #[tokio::test]
+    async fn folder_renders_children_in_order_with_anchors() {
+        let mut tree = Tree::new();
+        let folder = tree.add("Handbook", "folder", None, "");
+        let second = tree.add("Beta", "document", Some(folder), "second body");
+        let first = tree.add("Alpha", "document", Some(folder), "first body");
+        let actor = Actor::User(tree.owner);
+
+        let out = renderer(&tree)
+            .execute(&actor, folder, RenderOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let ids: Vec<Uuid> = out.documents.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![first, second]);
+        assert!(!out.truncated);
+        let html = &out.html;
+        let first_section = html.find(&format!("id=\"doc-{}\"", first)).unwrap();
+        let second_section = html.find(&format!("id=\"doc-{}\"", second)).unwrap();
+        assert!(first_section < second_section);
+        assert!(html.find("first body").unwrap() < html.find("second body").unwrap());
+        // Every TOC link points at a section rendered in the same page.
+        for id in [first, second] {
+            let link = html.find(&format!("href=\"#doc-{}\"", id)).unwrap();
+            assert!(link < first_section);
+        }
+    }
+
+    #[tokio::test]
+    async fn contents_heading_follows_the_locale() {
+        let mut tree = Tree::new();
+        let folder = tree.add("Handbook", "folder", None, "");
+        tree.add("Alpha", "document", Some(folder), "body");
+        let actor = Actor::User(tree.owner);
+
+        let english = renderer(&tree)
+            .execute(&actor, folder, RenderOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(english.html.contains("<h2>Contents</h2>"));
+
+        let german = RenderOptions {
+            locale: Some("de-DE".into()),
+            ..Default::default()
+        };
+        let german = renderer(&tree)
+            .execute(&actor, folder, german)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(german.html.contains("<h2>Inhalt</h2>"));
+        assert!(!german.html.contains("Contents"));
+    }
+
+    #[tokio::test]
+    async fn skips_unviewable_documents_and_honours_limits() {
+        let mut tree = Tree::new();
+        let folder = tree.add("Root", "folder", None, "");
+        let nested = tree.add("Nested", "folder", Some(folder), "");
+        let hidden = tree.add("Hidden", "document", Some(folder), "secret");
+        let a = tree.add("A", "document", Some(nested), "aaa");
+        tree.add("B", "document", Some(nested), "bbb");
+        // Somebody else's document filed under the folder.
+        tree.docs.update(hidden, |d| d.owner = Uuid::new_v4());
+        let actor = Actor::User(tree.owner);
+
+        let out = renderer(&tree)
+            .execute(&actor, folder, RenderOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(out.documents.len(), 2);
+        assert_eq!(out.documents[0].depth, 1);
+        assert_eq!(out.skipped, 1);
+        assert!(!out.html.contains("secret"));
+
+        let limited = RenderDocumentTree {
+            max_documents: 1,
+            ..renderer(&tree)
+        }
+        .execute(&actor, folder, RenderOptions::default())
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(limited.documents.len(), 1);
+        assert_eq!(limited.documents[0].id, a);
+        assert!(limited.truncated);
+
+        assert!(
+            renderer(&tree)
+                .execute(&actor, a, RenderOptions::default())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -2,25 +2,30 @@ use uuid::Uuid;
 
 use crate::application::ports::document_repository::DocumentRepository;
 use crate::application::ports::realtime_port::RealtimeEngine;
+use crate::application::ports::shares_repository::SharesRepository;
 use crate::application::ports::storage_port::StoragePort;
+use crate::application::use_cases::shares::materialize_folder_share::InheritFolderShares;
 use crate::domain::documents::document::Document as DomainDocument;
 
-pub struct UpdateDocument<'a, R, S, RT>
+pub struct UpdateDocument<'a, R, S, RT, SR>
 where
     R: DocumentRepository + ?Sized,
     S: StoragePort + ?Sized,
     RT: RealtimeEngine + ?Sized,
+    SR: SharesRepository + ?Sized,
 {
     pub repo: &'a R,
     pub storage: &'a S,
     pub realtime: &'a RT,
+    pub shares: &'a SR,
 }
 
-impl<'a, R, S, RT> UpdateDocument<'a, R, S, RT>
+impl<'a, R, S, RT, SR> UpdateDocument<'a, R, S, RT, SR>
 where
     R: DocumentRepository + ?Sized,
     S: StoragePort + ?Sized,
     RT: RealtimeEngine + ?Sized,
+    SR: SharesRepository + ?Sized,
 {
     // parent_id: None => not provided; Some(None) => set null; Some(Some(uuid)) => set value
     pub async fn execute(
@@ -30,11 +35,17 @@ where
         title: Option<String>,
         parent_id: Option<Option<Uuid>>,
     ) -> anyhow::Result<Option<DomainDocument>> {
+        let moved_into_folder = matches!(parent_id, Some(Some(_)));
         let row = self
             .repo
             .update_title_and_parent_for_user(id, user_id, title, parent_id)
             .await?;
         if let Some(doc) = &row {
+            if moved_into_folder {
+                InheritFolderShares { repo: self.shares }
+                    .execute(id)
+                    .await?;
+            }
             if doc.doc_type == "folder" {
                 let _ = self.storage.move_folder_subtree(id).await;
             } else {
@@ -0,0 +1,266 @@
+use uuid::Uuid;
+
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::document_user_access_repository::{
+    DocumentUserAccess, DocumentUserAccessRepository,
+};
+use crate::application::ports::notifier::Notifier;
+use crate::application::ports::user_repository::UserRepository;
+use crate::application::services::notifications;
+
+#[derive(thiserror::Error, Debug)]
+pub enum UserAccessError {
+    #[error("document not found")]
+    NotFound,
+    #[error("permission must be view, comment or edit")]
+    InvalidPermission,
+    #[error("owners already have full access")]
+    OwnerGrant,
+    #[error("user not found")]
+    UserNotFound,
+    #[error(transparent)]
+    Repository(#[from] anyhow::Error),
+}
+
+pub struct ListDocumentUserAccess<'a, A, G>
+where
+    A: AccessRepository + ?Sized,
+    G: DocumentUserAccessRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub grants: &'a G,
+}
+
+impl<'a, A, G> ListDocumentUserAccess<'a, A, G>
+where
+    A: AccessRepository + ?Sized,
+    G: DocumentUserAccessRepository + ?Sized,
+{
+    pub async fn execute(
+        &self,
+        owner_id: Uuid,
+        doc_id: Uuid,
+    ) -> Result<Vec<DocumentUserAccess>, UserAccessError> {
+        if !self.access.user_owns_document(doc_id, owner_id).await? {
+            return Err(UserAccessError::NotFound);
+        }
+        Ok(self.grants.list_for_document(doc_id).await?)
+    }
+}
+
+pub struct GrantDocumentUserAccess<'a, A, G, U>
+where
+    A: AccessRepository + ?Sized,
+    G: DocumentUserAccessRepository + ?Sized,
+    U: UserRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub grants: &'a G,
+    pub users: &'a U,
+    /// Tells the grantee about the share; failures are logged and do not undo the grant.
+    pub notifier: Option<&'a dyn Notifier>,
+}
+
+impl<'a, A, G, U> GrantDocumentUserAccess<'a, A, G, U>
+where
+    A: AccessRepository + ?Sized,
+    G: DocumentUserAccessRepository + ?Sized,
+    U: UserRepository + ?Sized,
+{
+    pub async fn execute(
+        &self,
+        owner_id: Uuid,
+        doc_id: Uuid,
+        target_user_id: Uuid,
+        permission: &str,
+    ) -> Result<(), UserAccessError> {
+        let permission = permission.trim().to_ascii_lowercase();
+        if !matches!(permission.as_str(), "view" | "comment" | "edit") {
+            return Err(UserAccessError::InvalidPermission);
+        }
+        if !self.access.user_owns_document(doc_id, owner_id).await? {
+            return Err(UserAccessError::NotFound);
+        }
+        if self
+            .access
+            .user_owns_document(doc_id, target_user_id)
+            .await?
+        {
+            return Err(UserAccessError::OwnerGrant);
+        }
+        if self.users.find_by_id(target_user_id).await?.is_none() {
+            return Err(UserAccessError::UserNotFound);
+        }
+        self.grants
+            .grant(doc_id, target_user_id, &permission, owner_id)
+            .await?;
+        if let Some(notifier) = self.notifier {
+            let notification = notifications::share(target_user_id, doc_id, owner_id, &permission);
+            if let Err(e) = notifier.notify(notification).await {
+                tracing::warn!(document_id = %doc_id, user_id = %target_user_id, error = ?e, "share_notify_failed");
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct RevokeDocumentUserAccess<'a, A, G>
+where
+    A: AccessRepository + ?Sized,
+    G: DocumentUserAccessRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub grants: &'a G,
+}
+
+impl<'a, A, G> RevokeDocumentUserAccess<'a, A, G>
+where
+    A: AccessRepository + ?Sized,
+    G: DocumentUserAccessRepository + ?Sized,
+{
+    /// Returns `false` when the user had no grant on the document.
+    pub async fn execute(
+        &self,
+        owner_id: Uuid,
+        doc_id: Uuid,
+        target_user_id: Uuid,
+    ) -> Result<bool, UserAccessError> {
+        if !self.access.user_owns_document(doc_id, owner_id).await? {
+            return Err(UserAccessError::NotFound);
+        }
+        Ok(self.grants.revoke(doc_id, target_user_id).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::application::access::{self, AccessPolicy, Actor, Capability};
+    use crate::application::ports::document_repository::DocumentListFilter;
+    use crate::application::use_cases::documents::list_documents::ListDocuments;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::shares::MemoryShares;
+    use crate::test_support::users::MemoryUsers;
+
+    struct Fixture {
+        docs: Arc<MemoryDocuments>,
+        shares: MemoryShares,
+        users: MemoryUsers,
+        owner: Uuid,
+        doc_id: Uuid,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let docs = Arc::new(MemoryDocuments::default());
+            let users = MemoryUsers::default();
+            let owner = users.add("Owner", "owner@example.com").id;
+            let doc_id = docs.add(owner, "Plan", "document", None).id;
+            Self {
+                shares: MemoryShares::new(docs.clone()),
+                docs,
+                users,
+                owner,
+                doc_id,
+            }
+        }
+
+        fn grant(
+            &self,
+        ) -> GrantDocumentUserAccess<'_, MemoryDocuments, MemoryDocuments, MemoryUsers> {
+            GrantDocumentUserAccess {
+                access: &*self.docs,
+                grants: &*self.docs,
+                users: &self.users,
+                notifier: None,
+            }
+        }
+
+        async fn capability(&self, user: Uuid) -> Capability {
+            let actor = Actor::User(user);
+            access::resolve_document(
+                &*self.docs,
+                &self.shares,
+                AccessPolicy::default(),
+                &actor,
+                self.doc_id,
+            )
+            .await
+        }
+
+        async fn listed(&self, user: Uuid) -> bool {
+            ListDocuments { repo: &*self.docs }
+                .execute(user, DocumentListFilter::default(), None, None)
+                .await
+                .unwrap()
+                .page
+                .items
+                .iter()
+                .any(|d| d.id == self.doc_id)
+        }
+    }
+
+    #[tokio::test]
+    async fn granted_user_gains_access_until_revoked() {
+        let f = Fixture::new();
+        let friend = f.users.add("Friend", "friend@example.com").id;
+        let grant = f.grant();
+
+        assert_eq!(f.capability(friend).await, Capability::None);
+        assert!(!f.listed(friend).await);
+
+        grant
+            .execute(f.owner, f.doc_id, friend, "view")
+            .await
+            .unwrap();
+        assert_eq!(f.capability(friend).await, Capability::View);
+        assert!(f.listed(friend).await);
+
+        grant
+            .execute(f.owner, f.doc_id, friend, "edit")
+            .await
+            .unwrap();
+        assert_eq!(f.capability(friend).await, Capability::Edit);
+
+        let revoke = RevokeDocumentUserAccess {
+            access: &*f.docs,
+            grants: &*f.docs,
+        };
+        assert!(revoke.execute(f.owner, f.doc_id, friend).await.unwrap());
+        assert_eq!(f.capability(friend).await, Capability::None);
+        assert!(!f.listed(friend).await);
+    }
+
+    #[tokio::test]
+    async fn only_owner_can_grant() {
+        let f = Fixture::new();
+        let grant = f.grant();
+        let stranger = f.users.add("Stranger", "stranger@example.com").id;
+        assert!(matches!(
+            grant.execute(stranger, f.doc_id, stranger, "edit").await,
+            Err(UserAccessError::NotFound)
+        ));
+        assert!(matches!(
+            grant.execute(f.owner, f.doc_id, f.owner, "view").await,
+            Err(UserAccessError::OwnerGrant)
+        ));
+        assert!(matches!(
+            grant.execute(f.owner, f.doc_id, stranger, "admin").await,
+            Err(UserAccessError::InvalidPermission)
+        ));
+    }
+
+    #[tokio::test]
+    async fn granting_to_an_unknown_user_is_rejected() {
+        let f = Fixture::new();
+        assert!(matches!(
+            f.grant()
+                .execute(f.owner, f.doc_id, Uuid::new_v4(), "view")
+                .await,
+            Err(UserAccessError::UserNotFound)
+        ));
+        assert!(f.docs.grants.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,387 @@
+use uuid::Uuid;
+
+use crate::application::access::{self, AccessPolicy, Actor, Capability};
+use crate::application::dto::git::DiffResult;
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::document_version_repository::{
+    DocumentVersion, DocumentVersionRepository,
+};
+use crate::application::ports::realtime_port::RealtimeEngine;
+use crate::application::ports::share_access_port::ShareAccessPort;
+use crate::application::services::diff;
+use crate::application::services::realtime::snapshot::markdown_from_snapshot;
+
+async fn can_edit<A, SH>(
+    access: &A,
+    shares: &SH,
+    policy: AccessPolicy,
+    actor: &Actor,
+    doc_id: Uuid,
+) -> bool
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+{
+    access::resolve_document(access, shares, policy, actor, doc_id).await >= Capability::Edit
+}
+
+pub struct ListDocumentVersions<'a, A, SH, V>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    V: DocumentVersionRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a SH,
+    pub policy: AccessPolicy,
+    pub versions: &'a V,
+}
+
+impl<'a, A, SH, V> ListDocumentVersions<'a, A, SH, V>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    V: DocumentVersionRepository + ?Sized,
+{
+    pub async fn execute(
+        &self,
+        actor: &Actor,
+        doc_id: Uuid,
+    ) -> anyhow::Result<Option<Vec<DocumentVersion>>> {
+        if !can_edit(self.access, self.shares, self.policy, actor, doc_id).await {
+            return Ok(None);
+        }
+        Ok(Some(self.versions.list_versions(doc_id).await?))
+    }
+}
+
+pub struct GetDocumentVersionContent<'a, A, SH, V>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    V: DocumentVersionRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a SH,
+    pub policy: AccessPolicy,
+    pub versions: &'a V,
+}
+
+impl<'a, A, SH, V> GetDocumentVersionContent<'a, A, SH, V>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    V: DocumentVersionRepository + ?Sized,
+{
+    pub async fn execute(
+        &self,
+        actor: &Actor,
+        doc_id: Uuid,
+        version: i64,
+    ) -> anyhow::Result<Option<String>> {
+        if !can_edit(self.access, self.shares, self.policy, actor, doc_id).await {
+            return Ok(None);
+        }
+        match self.versions.get_snapshot(doc_id, version).await? {
+            Some(snapshot) => Ok(Some(markdown_from_snapshot(&snapshot)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+pub struct DiffDocumentVersions<'a, A, SH, V>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    V: DocumentVersionRepository + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a SH,
+    pub policy: AccessPolicy,
+    pub versions: &'a V,
+}
+
+impl<'a, A, SH, V> DiffDocumentVersions<'a, A, SH, V>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    V: DocumentVersionRepository + ?Sized,
+{
+    /// Line diff from version `from` to version `to`; `None` if either is missing.
+    pub async fn execute(
+        &self,
+        actor: &Actor,
+        doc_id: Uuid,
+        from: i64,
+        to: i64,
+    ) -> anyhow::Result<Option<DiffResult>> {
+        let contents = GetDocumentVersionContent {
+            access: self.access,
+            shares: self.shares,
+            policy: self.policy,
+            versions: self.versions,
+        };
+        let Some(old) = contents.execute(actor, doc_id, from).await? else {
+            return Ok(None);
+        };
+        let Some(new) = contents.execute(actor, doc_id, to).await? else {
+            return Ok(None);
+        };
+        Ok(Some(diff::build_diff_result(
+            &doc_id.to_string(),
+            Some(&old),
+            Some(&new),
+        )))
+    }
+}
+
+pub struct RestoreDocumentVersion<'a, A, SH, V, RT>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    V: DocumentVersionRepository + ?Sized,
+    RT: RealtimeEngine + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a SH,
+    pub policy: AccessPolicy,
+    pub versions: &'a V,
+    pub realtime: &'a RT,
+}
+
+impl<'a, A, SH, V, RT> RestoreDocumentVersion<'a, A, SH, V, RT>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    V: DocumentVersionRepository + ?Sized,
+    RT: RealtimeEngine + ?Sized,
+{
+    /// Returns the restored content, or `None` when the version is unknown or not editable.
+    pub async fn execute(
+        &self,
+        actor: &Actor,
+        doc_id: Uuid,
+        version: i64,
+    ) -> anyhow::Result<Option<String>> {
+        let content = GetDocumentVersionContent {
+            access: self.access,
+            shares: self.shares,
+            policy: self.policy,
+            versions: self.versions,
+        }
+        .execute(actor, doc_id, version)
+        .await?;
+        let Some(content) = content else {
+            return Ok(None);
+        };
+        self.realtime
+            .replace_content(&doc_id.to_string(), &content)
+            .await?;
+        Ok(Some(content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use yrs::{Doc, ReadTxn, StateVector, Transact};
+
+    use super::*;
+    use crate::application::services::realtime::snapshot::replace_content;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::realtime::MemoryRealtime;
+    use crate::test_support::shares::MemoryShares;
+
+    /// A public document whose every edit is snapshotted, as the snapshot loop would.
+    struct Store {
+        doc_id: Uuid,
+        docs: Arc<MemoryDocuments>,
+        shares: MemoryShares,
+        realtime: MemoryRealtime,
+        snapshots: Mutex<Vec<(i64, Vec<u8>)>>,
+    }
+
+    impl Store {
+        fn new(owner: Uuid) -> Self {
+            let docs = Arc::new(MemoryDocuments::default());
+            let doc_id = docs.add(owner, "Notes", "document", None).id;
+            docs.update(doc_id, |d| d.public = true);
+            Self {
+                doc_id,
+                shares: MemoryShares::new(docs.clone()),
+                docs,
+                realtime: MemoryRealtime::default(),
+                snapshots: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn edit(&self, content: &str) {
+            self.realtime.set(self.doc_id, content);
+            let doc = Doc::new();
+            replace_content(&doc, content);
+            let bin = doc
+                .transact()
+                .encode_state_as_update_v1(&StateVector::default());
+            let mut snapshots = self.snapshots.lock().unwrap();
+            let version = snapshots.len() as i64 + 1;
+            snapshots.push((version, bin));
+        }
+
+        fn content(&self) -> String {
+            self.realtime.content(self.doc_id).unwrap_or_default()
+        }
+    }
+
+    #[async_trait]
+    impl DocumentVersionRepository for Store {
+        async fn list_versions(&self, _doc_id: Uuid) -> anyhow::Result<Vec<DocumentVersion>> {
+            let now = chrono::Utc::now();
+            Ok(self
+                .snapshots
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .map(|(version, _)| DocumentVersion {
+                    version: *version,
+                    created_at: now,
+                })
+                .collect())
+        }
+
+        async fn get_snapshot(
+            &self,
+            _doc_id: Uuid,
+            version: i64,
+        ) -> anyhow::Result<Option<Vec<u8>>> {
+            Ok(self
+                .snapshots
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(v, _)| *v == version)
+                .map(|(_, bin)| bin.clone()))
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_versions_and_restores_a_prior_one() {
+        let owner = Uuid::new_v4();
+        let store = Store::new(owner);
+        store.edit("# Draft");
+        store.edit("# Draft\n\nSecond pass");
+        store.edit("# Final");
+        let actor = Actor::User(owner);
+
+        let versions = ListDocumentVersions {
+            access: store.docs.as_ref(),
+            shares: &store.shares,
+            policy: AccessPolicy::default(),
+            versions: &store,
+        }
+        .execute(&actor, store.doc_id)
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+
+        let restore = RestoreDocumentVersion {
+            access: store.docs.as_ref(),
+            shares: &store.shares,
+            policy: AccessPolicy::default(),
+            versions: &store,
+            realtime: &store.realtime,
+        };
+        let restored = restore.execute(&actor, store.doc_id, 2).await.unwrap();
+        assert_eq!(restored.as_deref(), Some("# Draft\n\nSecond pass"));
+        assert_eq!(store.content(), "# Draft\n\nSecond pass");
+
+        assert!(
+            restore
+                .execute(&actor, store.doc_id, 9)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn diff_reports_changed_lines_with_line_numbers() {
+        use crate::application::dto::git::DiffLineType;
+
+        let owner = Uuid::new_v4();
+        let store = Store::new(owner);
+        store.edit("# Plan\nkeep\nold step\nend");
+        store.edit("# Plan\nkeep\nnew step\nextra\nend");
+        let diff = DiffDocumentVersions {
+            access: store.docs.as_ref(),
+            shares: &store.shares,
+            policy: AccessPolicy::default(),
+            versions: &store,
+        }
+        .execute(&Actor::User(owner), store.doc_id, 1, 2)
+        .await
+        .unwrap()
+        .unwrap();
+
+        let changed: Vec<_> = diff
+            .diff_lines
+            .iter()
+            .filter(|l| !matches!(l.line_type, DiffLineType::Context))
+            .map(|l| {
+                (
+                    matches!(l.line_type, DiffLineType::Added),
+                    l.old_line_number,
+                    l.new_line_number,
+                    l.content.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                (false, Some(3), None, "old step"),
+                (true, None, Some(3), "new step"),
+                (true, None, Some(4), "extra"),
+            ]
+        );
+        let end = diff.diff_lines.last().unwrap();
+        assert_eq!(
+            (end.old_line_number, end.new_line_number),
+            (Some(4), Some(5))
+        );
+    }
+
+    #[tokio::test]
+    async fn viewers_cannot_see_or_restore_versions() {
+        let store = Store::new(Uuid::new_v4());
+        store.edit("# Draft");
+        let actor = Actor::Public;
+        let list = ListDocumentVersions {
+            access: store.docs.as_ref(),
+            shares: &store.shares,
+            policy: AccessPolicy::default(),
+            versions: &store,
+        };
+        assert!(list.execute(&actor, store.doc_id).await.unwrap().is_none());
+        let restore = RestoreDocumentVersion {
+            access: store.docs.as_ref(),
+            shares: &store.shares,
+            policy: AccessPolicy::default(),
+            versions: &store,
+            realtime: &store.realtime,
+        };
+        assert!(
+            restore
+                .execute(&actor, store.doc_id, 1)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use uuid::Uuid;
+
+use crate::application::access::{self, AccessPolicy, Actor, Capability};
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::realtime_port::{ContentWrite, RealtimeEngine, content_version};
+use crate::application::ports::share_access_port::ShareAccessPort;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WriteContentError {
+    #[error("document not found")]
+    NotFound,
+    #[error("edit permission required")]
+    Forbidden,
+    #[error("document changed since version was read")]
+    Conflict { version: String, content: String },
+    #[error(transparent)]
+    Repository(#[from] anyhow::Error),
+}
+
+/// Version a write is based on, from an `If-Match` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExpectedVersion {
+    /// `If-Match: *`: overwrite whatever is there.
+    Any,
+    Exact(String),
+}
+
+impl ExpectedVersion {
+    /// Parses an `If-Match` value, accepting quoted and weak (`W/"..."`) entity tags.
+    pub fn from_if_match(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value == "*" {
+            return Some(Self::Any);
+        }
+        let tag = value.strip_prefix("W/").unwrap_or(value).trim_matches('"');
+        (!tag.is_empty()).then(|| Self::Exact(tag.to_string()))
+    }
+}
+
+pub struct WriteDocumentContent<'a, A, SH, RT>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    RT: RealtimeEngine + ?Sized,
+{
+    pub access: &'a A,
+    pub shares: &'a SH,
+    pub policy: AccessPolicy,
+    pub realtime: &'a RT,
+}
+
+impl<'a, A, SH, RT> WriteDocumentContent<'a, A, SH, RT>
+where
+    A: AccessRepository + ?Sized,
+    SH: ShareAccessPort + ?Sized,
+    RT: RealtimeEngine + ?Sized,
+{
+    /// Replaces the document content and returns the new version. A stale `expected` version
+    /// fails with the current content so the client can rebase its edit.
+    pub async fn execute(
+        &self,
+        actor: &Actor,
+        doc_id: Uuid,
+        expected: &ExpectedVersion,
+        content: &str,
+    ) -> Result<String, WriteContentError> {
+        match access::resolve_document(self.access, self.shares, self.policy, actor, doc_id).await {
+            Capability::None => return Err(WriteContentError::NotFound),
+            Capability::View | Capability::Comment => return Err(WriteContentError::Forbidden),
+            Capability::Edit => {}
+        }
+        let key = doc_id.to_string();
+        match expected {
+            ExpectedVersion::Any => self.realtime.replace_content(&key, content).await?,
+            ExpectedVersion::Exact(version) => {
+                match self
+                    .realtime
+                    .replace_content_if(&key, version, content)
+                    .await?
+                {
+                    ContentWrite::Applied => {}
+                    ContentWrite::Conflict { current } => {
+                        return Err(WriteContentError::Conflict {
+                            version: content_version(&current),
+                            content: current,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(content_version(content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::realtime::MemoryRealtime;
+    use crate::test_support::shares::MemoryShares;
+
+    /// A public document with a viewer grant.
+    struct Store {
+        owner: Uuid,
+        viewer: Uuid,
+        doc_id: Uuid,
+        docs: Arc<MemoryDocuments>,
+        shares: MemoryShares,
+        realtime: MemoryRealtime,
+    }
+
+    impl Store {
+        fn new(content: &str) -> Self {
+            let (owner, viewer) = (Uuid::new_v4(), Uuid::new_v4());
+            let docs = Arc::new(MemoryDocuments::default());
+            let doc_id = docs.add(owner, "Notes", "document", None).id;
+            docs.update(doc_id, |d| d.public = true);
+            docs.grants
+                .lock()
+                .unwrap()
+                .insert((doc_id, viewer), "view".into());
+            let realtime = MemoryRealtime::default();
+            realtime.set(doc_id, content);
+            Self {
+                owner,
+                viewer,
+                doc_id,
+                shares: MemoryShares::new(docs.clone()),
+                docs,
+                realtime,
+            }
+        }
+
+        fn content(&self) -> String {
+            self.realtime.content(self.doc_id).unwrap()
+        }
+
+        async fn write(
+            &self,
+            actor: Actor,
+            expected: ExpectedVersion,
+            content: &str,
+        ) -> Result<String, WriteContentError> {
+            WriteDocumentContent {
+                access: self.docs.as_ref(),
+                shares: &self.shares,
+                policy: AccessPolicy::default(),
+                realtime: &self.realtime,
+            }
+            .execute(&actor, self.doc_id, &expected, content)
+            .await
+        }
+    }
+
+    #[tokio::test]
+    async fn matching_version_writes_content() {
+        let store = Store::new("draft");
+        let version = content_version("draft");
+        let new_version = store
+            .write(
+                Actor::User(store.owner),
+                ExpectedVersion::Exact(version),
+                "final",
+            )
+            .await
+            .unwrap();
+        assert_eq!(new_version, content_version("final"));
+        assert_eq!(store.content(), "final");
+    }
+
+    #[tokio::test]
+    async fn stale_version_is_rejected_with_latest_content() {
+        let store = Store::new("draft");
+        let stale = content_version("draft");
+        // Someone else edits in between.
+        store.realtime.set(store.doc_id, "draft, edited live");
+
+        let err = store
+            .write(
+                Actor::User(store.owner),
+                ExpectedVersion::Exact(stale),
+                "mine",
+            )
+            .await
+            .unwrap_err();
+        match err {
+            WriteContentError::Conflict { version, content } => {
+                assert_eq!(content, "draft, edited live");
+                assert_eq!(version, content_version("draft, edited live"));
+            }
+            other => panic!("expected conflict, got {:?}", other),
+        }
+        assert_eq!(store.content(), "draft, edited live");
+    }
+
+    #[tokio::test]
+    async fn viewers_cannot_write() {
+        let store = Store::new("draft");
+        let err = store
+            .write(Actor::User(store.viewer), ExpectedVersion::Any, "x")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WriteContentError::Forbidden));
+        let err = store
+            .write(Actor::User(Uuid::new_v4()), ExpectedVersion::Any, "x")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WriteContentError::NotFound));
+        assert_eq!(store.content(), "draft");
+    }
+
+    #[test]
+    fn parses_if_match_values() {
+        assert_eq!(
+            ExpectedVersion::from_if_match("\"abc\""),
+            Some(ExpectedVersion::Exact("abc".into()))
+        );
+        assert_eq!(
+            ExpectedVersion::from_if_match("W/\"abc\""),
+            Some(ExpectedVersion::Exact("abc".into()))
+        );
+        assert_eq!(
+            ExpectedVersion::from_if_match("*"),
+            Some(ExpectedVersion::Any)
+        );
+        assert_eq!(ExpectedVersion::from_if_match("\"\""), None);
+    }
+}
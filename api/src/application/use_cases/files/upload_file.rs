@@ -2,6 +2,7 @@ use uuid::Uuid;
 
 use crate::application::ports::files_repository::FilesRepository;
 use crate::application::ports::storage_port::StoragePort;
+use crate::application::services::uploads::UploadPolicy;
 
 pub struct UploadFile<'a, R, S>
 where
@@ -11,6 +12,7 @@ where
     pub repo: &'a R,
     pub storage: &'a S,
     pub public_base_url: Option<String>,
+    pub policy: &'a UploadPolicy,
 }
 
 pub struct UploadedFile {
@@ -37,6 +39,11 @@ where
         if !self.repo.is_owner_document(doc_id, owner_id).await? {
             return Ok(None);
         }
+        // Policy violations surface as `UploadPolicyError` inside the returned error.
+        let (checked_type, bytes) =
+            self.policy
+                .prepare(orig_filename.as_deref(), content_type.as_deref(), bytes)?;
+        let content_type = Some(checked_type);
         let stored = self
             .storage
             .store_doc_attachment(doc_id, orig_filename.as_deref(), &bytes)
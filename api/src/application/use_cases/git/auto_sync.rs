@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::application::dto::git::GitSyncRequestDto;
+use crate::application::ports::git_repository::{AutoSyncCandidate, GitRepository};
+use crate::application::ports::git_workspace::GitWorkspacePort;
+use crate::application::use_cases::git::sync_now::SyncNow;
+
+/// When batched document changes are committed: once nothing has changed for `quiet_period`,
+/// or once the oldest uncommitted change is `max_interval` old, whichever comes first.
+#[derive(Debug, Clone, Copy)]
+pub struct AutoSyncPolicy {
+    pub quiet_period: Duration,
+    pub max_interval: Duration,
+}
+
+impl AutoSyncPolicy {
+    pub fn from_secs(quiet_secs: u64, max_interval_secs: u64) -> Self {
+        Self {
+            quiet_period: Duration::seconds(quiet_secs as i64),
+            max_interval: Duration::seconds(max_interval_secs as i64),
+        }
+    }
+
+    pub fn is_due(&self, candidate: &AutoSyncCandidate, now: DateTime<Utc>) -> bool {
+        now - candidate.last_change_at >= self.quiet_period
+            || now - candidate.first_change_at >= self.max_interval
+    }
+}
+
+/// One pass of the auto-sync scheduler.
+pub struct RunAutoSync<'a, R, W>
+where
+    R: GitRepository + ?Sized,
+    W: GitWorkspacePort + ?Sized,
+{
+    pub repo: &'a R,
+    pub workspace: &'a W,
+    pub policy: AutoSyncPolicy,
+}
+
+impl<'a, R, W> RunAutoSync<'a, R, W>
+where
+    R: GitRepository + ?Sized,
+    W: GitWorkspacePort + ?Sized,
+{
+    /// Syncs every user whose changes are due and returns them. `attempted` holds the last
+    /// change each user was synced for, so changes that produce no commit (say, to ignored
+    /// documents) are not retried on every pass. A failed sync is not recorded and is
+    /// retried on the next pass.
+    pub async fn execute(
+        &self,
+        now: DateTime<Utc>,
+        attempted: &mut HashMap<Uuid, DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<Uuid>> {
+        let mut synced = Vec::new();
+        for candidate in self.repo.auto_sync_candidates().await? {
+            if attempted
+                .get(&candidate.user_id)
+                .is_some_and(|seen| *seen >= candidate.last_change_at)
+            {
+                continue;
+            }
+            if !self.policy.is_due(&candidate, now) {
+                continue;
+            }
+            let uc = SyncNow {
+                workspace: self.workspace,
+                repo: self.repo,
+            };
+            let req = GitSyncRequestDto {
+                message: None,
+                force: None,
+                dry_run: false,
+            };
+            match uc.execute(candidate.user_id, req).await {
+                Ok(_) => {
+                    attempted.insert(candidate.user_id, candidate.last_change_at);
+                    synced.push(candidate.user_id);
+                }
+                Err(e) => {
+                    tracing::warn!(user_id = %candidate.user_id, error = ?e, "git_auto_sync_failed")
+                }
+            }
+        }
+        Ok(synced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::git::MemoryWorkspace;
+
+    /// One auto-syncing user on a controllable clock; a sync commits every pending edit.
+    struct Store {
+        user_id: Uuid,
+        workspace: MemoryWorkspace,
+    }
+
+    impl Store {
+        fn new(start: DateTime<Utc>) -> Self {
+            let user_id = Uuid::new_v4();
+            let workspace = MemoryWorkspace::default();
+            workspace.git.configure(user_id, true);
+            workspace.at(start);
+            Self { user_id, workspace }
+        }
+
+        fn at(&self, now: DateTime<Utc>) {
+            self.workspace.at(now);
+        }
+
+        fn edit(&self, at: DateTime<Utc>) {
+            self.at(at);
+            self.workspace.edit("notes.md", Some("a\n"), Some("b\n"));
+            self.workspace
+                .git
+                .changes
+                .lock()
+                .unwrap()
+                .push((self.user_id, at));
+        }
+
+        fn commits(&self) -> Vec<DateTime<Utc>> {
+            self.workspace.git.commit_times(self.user_id)
+        }
+
+        async fn tick(&self, policy: AutoSyncPolicy, attempted: &mut HashMap<Uuid, DateTime<Utc>>) {
+            let now = *self.workspace.now.lock().unwrap();
+            RunAutoSync {
+                repo: self.workspace.git.as_ref(),
+                workspace: &self.workspace,
+                policy,
+            }
+            .execute(now, attempted)
+            .await
+            .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn rapid_changes_coalesce_into_one_commit_after_the_quiet_period() {
+        let start = Utc::now();
+        let secs = |s: i64| start + Duration::seconds(s);
+        let store = Store::new(start);
+        let policy = AutoSyncPolicy::from_secs(60, 600);
+        let mut attempted = HashMap::new();
+
+        for s in [0, 10, 20, 30] {
+            store.edit(secs(s));
+            store.tick(policy, &mut attempted).await;
+        }
+        store.at(secs(80));
+        store.tick(policy, &mut attempted).await;
+        assert!(store.commits().is_empty());
+
+        store.at(secs(95));
+        store.tick(policy, &mut attempted).await;
+        store.at(secs(200));
+        store.tick(policy, &mut attempted).await;
+        assert_eq!(store.commits(), vec![secs(95)]);
+    }
+
+    #[tokio::test]
+    async fn continuous_editing_commits_at_the_max_interval() {
+        let start = Utc::now();
+        let secs = |s: i64| start + Duration::seconds(s);
+        let store = Store::new(start);
+        let policy = AutoSyncPolicy::from_secs(60, 600);
+        let mut attempted = HashMap::new();
+
+        // An edit every 30 seconds never leaves a quiet minute.
+        for s in (0..=660).step_by(30) {
+            store.edit(secs(s));
+            store.tick(policy, &mut attempted).await;
+        }
+        assert_eq!(store.commits(), vec![secs(600)]);
+    }
+
+    #[tokio::test]
+    async fn failed_sync_is_retried_on_the_next_pass() {
+        let start = Utc::now();
+        let secs = |s: i64| start + Duration::seconds(s);
+        let store = Store::new(start);
+        let policy = AutoSyncPolicy::from_secs(60, 600);
+        let mut attempted = HashMap::new();
+        *store.workspace.failures.lock().unwrap() = 1;
+
+        store.edit(secs(0));
+        store.at(secs(60));
+        store.tick(policy, &mut attempted).await;
+        assert!(store.commits().is_empty());
+
+        store.at(secs(120));
+        store.tick(policy, &mut attempted).await;
+        assert_eq!(store.commits(), vec![secs(120)]);
+    }
+}
@@ -0,0 +1,17 @@
+use crate::application::dto::git::GitBlameLine;
+use crate::application::ports::git_workspace::GitWorkspacePort;
+use uuid::Uuid;
+
+pub struct GetBlame<'a, W: GitWorkspacePort + ?Sized> {
+    pub workspace: &'a W,
+}
+
+impl<'a, W: GitWorkspacePort + ?Sized> GetBlame<'a, W> {
+    pub async fn execute(&self, user_id: Uuid, path: &str) -> anyhow::Result<Vec<GitBlameLine>> {
+        let path = path.trim_start_matches('/');
+        if path.is_empty() {
+            anyhow::bail!("path is required");
+        }
+        self.workspace.blame(user_id, path).await
+    }
+}
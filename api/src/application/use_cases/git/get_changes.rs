@@ -1,13 +1,38 @@
-use crate::application::dto::git::GitChangeItem;
+use crate::application::dto::git::GitChangesPage;
 use crate::application::ports::git_workspace::GitWorkspacePort;
 use uuid::Uuid;
 
+pub const DEFAULT_CHANGES_PAGE_SIZE: usize = 200;
+pub const MAX_CHANGES_PAGE_SIZE: usize = 1000;
+
 pub struct GetChanges<'a, W: GitWorkspacePort + ?Sized> {
     pub workspace: &'a W,
 }
 
 impl<'a, W: GitWorkspacePort + ?Sized> GetChanges<'a, W> {
-    pub async fn execute(&self, user_id: Uuid) -> anyhow::Result<Vec<GitChangeItem>> {
-        self.workspace.list_changes(user_id).await
+    /// One page of changed files ordered by path; `cursor` is the last path of the previous
+    /// page.
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        cursor: Option<&str>,
+        limit: Option<usize>,
+    ) -> anyhow::Result<GitChangesPage> {
+        let limit = limit
+            .unwrap_or(DEFAULT_CHANGES_PAGE_SIZE)
+            .clamp(1, MAX_CHANGES_PAGE_SIZE);
+        let mut files = self.workspace.list_changes(user_id).await?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let total = files.len();
+        let start = cursor.map_or(0, |after| {
+            files.partition_point(|f| f.path.as_str() <= after)
+        });
+        let end = (start + limit).min(total);
+        let next_cursor = (end < total).then(|| files[end - 1].path.clone());
+        Ok(GitChangesPage {
+            files: files.drain(start..end).collect(),
+            total,
+            next_cursor,
+        })
     }
 }
@@ -8,18 +8,22 @@ pub struct GetGitConfig<'a, R: GitRepository + ?Sized> {
 
 impl<'a, R: GitRepository + ?Sized> GetGitConfig<'a, R> {
     pub async fn execute(&self, user_id: Uuid) -> anyhow::Result<Option<GitConfigDto>> {
-        Ok(self.repo.get_config(user_id).await?.map(
-            |(id, repository_url, branch_name, auth_type, auto_sync, created_at, updated_at)| {
-                GitConfigDto {
-                    id,
-                    repository_url,
-                    branch_name,
-                    auth_type,
-                    auto_sync,
-                    created_at,
-                    updated_at,
-                }
-            },
-        ))
+        let Some((id, repository_url, branch_name, auth_type, auto_sync, created_at, updated_at)) =
+            self.repo.get_config(user_id).await?
+        else {
+            return Ok(None);
+        };
+        let cfg = self.repo.load_user_git_cfg(user_id).await?;
+        Ok(Some(GitConfigDto {
+            id,
+            repository_url,
+            branch_name,
+            auth_type,
+            auto_sync,
+            commit_message_template: cfg.as_ref().and_then(|c| c.commit_message_template.clone()),
+            append_change_summary: cfg.is_some_and(|c| c.append_change_summary),
+            created_at,
+            updated_at,
+        }))
     }
 }
@@ -1,13 +1,103 @@
-use crate::application::dto::git::GitCommitInfo;
+use crate::application::dto::git::{GitHistoryCursor, GitHistoryPage};
 use crate::application::ports::git_workspace::GitWorkspacePort;
 use uuid::Uuid;
 
+pub const DEFAULT_HISTORY_PAGE_SIZE: i64 = 50;
+pub const MAX_HISTORY_PAGE_SIZE: i64 = 200;
+
 pub struct GetHistory<'a, W: GitWorkspacePort + ?Sized> {
     pub workspace: &'a W,
 }
 
 impl<'a, W: GitWorkspacePort + ?Sized> GetHistory<'a, W> {
-    pub async fn execute(&self, user_id: Uuid) -> anyhow::Result<Vec<GitCommitInfo>> {
-        self.workspace.history(user_id).await
+    /// One page of commits, newest first, starting after `cursor`.
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        cursor: Option<&GitHistoryCursor>,
+        limit: Option<i64>,
+    ) -> anyhow::Result<GitHistoryPage> {
+        let limit = limit
+            .unwrap_or(DEFAULT_HISTORY_PAGE_SIZE)
+            .clamp(1, MAX_HISTORY_PAGE_SIZE);
+        // One extra row tells whether another page follows.
+        let mut commits = self.workspace.history(user_id, cursor, limit + 1).await?;
+        let next_cursor = if commits.len() as i64 > limit {
+            commits.truncate(limit as usize);
+            commits.last().map(|c| GitHistoryCursor::of(c).encode())
+        } else {
+            None
+        };
+        Ok(GitHistoryPage {
+            commits,
+            next_cursor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::application::dto::git::GitCommitInfo;
+    use crate::test_support::git::MemoryWorkspace;
+
+    /// `n` commits in insertion order; several share a commit time, as imported history does.
+    fn workspace(n: usize) -> MemoryWorkspace {
+        // Cursors carry microseconds, like Postgres timestamps.
+        let base = chrono::DateTime::from_timestamp_micros(1_700_000_000_000_000).unwrap();
+        let workspace = MemoryWorkspace::default();
+        workspace
+            .commits
+            .lock()
+            .unwrap()
+            .extend((0..n).map(|i| GitCommitInfo {
+                hash: format!("{:040x}", (i * 7919) % 1000),
+                message: format!("commit {}", i),
+                author_name: "a".into(),
+                author_email: "a@example.com".into(),
+                time: base + chrono::Duration::seconds((i / 3) as i64),
+            }));
+        workspace
+    }
+
+    #[tokio::test]
+    async fn paging_yields_every_commit_once_in_order() {
+        let workspace = workspace(23);
+        let uc = GetHistory {
+            workspace: &workspace,
+        };
+        let user_id = Uuid::new_v4();
+
+        let mut seen = Vec::new();
+        let mut cursor: Option<GitHistoryCursor> = None;
+        loop {
+            let page = uc.execute(user_id, cursor.as_ref(), Some(4)).await.unwrap();
+            assert!(page.commits.len() <= 4);
+            seen.extend(page.commits);
+            match page.next_cursor {
+                Some(next) => cursor = Some(GitHistoryCursor::parse(&next).unwrap()),
+                None => break,
+            }
+        }
+
+        let mut expected = workspace.commits.lock().unwrap().clone();
+        expected.sort_by(|a, b| (b.time, &b.hash).cmp(&(a.time, &a.hash)));
+        let hashes: Vec<&str> = seen.iter().map(|c| c.hash.as_str()).collect();
+        let expected: Vec<&str> = expected.iter().map(|c| c.hash.as_str()).collect();
+        assert_eq!(hashes, expected);
+        assert_eq!(hashes.iter().collect::<HashSet<_>>().len(), 23);
+    }
+
+    #[tokio::test]
+    async fn last_full_page_has_no_cursor() {
+        let workspace = workspace(8);
+        let uc = GetHistory {
+            workspace: &workspace,
+        };
+        let page = uc.execute(Uuid::new_v4(), None, Some(8)).await.unwrap();
+        assert_eq!(page.commits.len(), 8);
+        assert_eq!(page.next_cursor, None);
     }
 }
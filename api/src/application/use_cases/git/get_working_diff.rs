@@ -1,4 +1,4 @@
-use crate::application::dto::git::DiffResult;
+use crate::application::dto::git::GitWorkingDiff;
 use crate::application::ports::git_workspace::GitWorkspacePort;
 use uuid::Uuid;
 
@@ -7,7 +7,42 @@ pub struct GetWorkingDiff<'a, W: GitWorkspacePort + ?Sized> {
 }
 
 impl<'a, W: GitWorkspacePort + ?Sized> GetWorkingDiff<'a, W> {
-    pub async fn execute(&self, user_id: Uuid) -> anyhow::Result<Vec<DiffResult>> {
+    pub async fn execute(&self, user_id: Uuid) -> anyhow::Result<GitWorkingDiff> {
         self.workspace.working_diff(user_id).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::dto::git::GitSyncRequestDto;
+    use crate::test_support::git::MemoryWorkspace;
+
+    #[tokio::test]
+    async fn working_diff_reports_the_latest_commit_as_its_base() {
+        let workspace = MemoryWorkspace::default();
+        let user_id = Uuid::new_v4();
+        let uc = GetWorkingDiff {
+            workspace: &workspace,
+        };
+
+        workspace.edit("notes.md", None, Some("a\n"));
+        let before = uc.execute(user_id).await.unwrap();
+        assert_eq!(before.base_commit_hash, None);
+
+        let req = GitSyncRequestDto {
+            message: None,
+            force: None,
+            dry_run: false,
+        };
+        let first = workspace.sync(user_id, &req, None).await.unwrap();
+        workspace.edit("notes.md", Some("a\n"), Some("a\nb\n"));
+        let second = workspace.sync(user_id, &req, None).await.unwrap();
+        assert_ne!(first.commit_hash, second.commit_hash);
+
+        workspace.edit("notes.md", Some("a\nb\n"), Some("a\nb\nc\n"));
+        let diff = uc.execute(user_id).await.unwrap();
+        assert_eq!(diff.base_commit_hash, second.commit_hash);
+        assert_eq!(diff.diffs.len(), 1);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::application::ports::git_workspace::GitWorkspacePort;
 use crate::application::ports::gitignore_port::GitignorePort;
 use crate::application::ports::storage_port::StoragePort;
+use crate::application::services::gitignore::GitignoreMatcher;
 
 pub struct GetGitignorePatterns<'a, G, S>
 where
@@ -23,6 +24,135 @@ where
     }
 }
 
+/// Outcome of adding or removing patterns: what changed and what was left alone.
+#[derive(Debug, Default)]
+pub struct PatternChanges {
+    pub applied: Vec<String>,
+    pub skipped: Vec<SkippedPattern>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedPattern {
+    pub pattern: String,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    Invalid(&'static str),
+    /// Already present (adding) or listed twice in the request.
+    Duplicate,
+    /// Not present (removing).
+    NotFound,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Invalid(why) => f.write_str(why),
+            SkipReason::Duplicate => f.write_str("duplicate"),
+            SkipReason::NotFound => f.write_str("not found"),
+        }
+    }
+}
+
+/// Normalizes a pattern to the repo-relative form `compute_doc_patterns_with` produces:
+/// no leading `/` or `./`, at most one trailing `/`.
+pub fn normalize_pattern(raw: &str) -> Result<String, &'static str> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err("empty pattern");
+    }
+    if trimmed.chars().any(char::is_control) {
+        return Err("contains control characters");
+    }
+    if trimmed.starts_with('#') {
+        return Err("comments are not patterns");
+    }
+    let (negate, body) = match trimmed.strip_prefix('!') {
+        Some(rest) => ("!", rest),
+        None => ("", trimmed),
+    };
+    let mut body = body;
+    loop {
+        if let Some(rest) = body.strip_prefix("./") {
+            body = rest;
+        } else if let Some(rest) = body.strip_prefix('/') {
+            body = rest;
+        } else {
+            break;
+        }
+    }
+    let is_dir = body.ends_with('/');
+    let body = body.trim_end_matches('/');
+    if body.is_empty() {
+        return Err("matches the whole repository");
+    }
+    if body.split('/').any(|seg| seg == "..") {
+        return Err("points outside the repository");
+    }
+    Ok(format!(
+        "{}{}{}",
+        negate,
+        body,
+        if is_dir { "/" } else { "" }
+    ))
+}
+
+fn plan_additions(existing: &[String], requested: Vec<String>) -> PatternChanges {
+    let mut seen: std::collections::HashSet<String> = existing
+        .iter()
+        .map(|p| normalize_pattern(p).unwrap_or_else(|_| p.trim().to_string()))
+        .collect();
+    let mut changes = PatternChanges::default();
+    for raw in requested {
+        match normalize_pattern(&raw) {
+            Err(why) => changes.skipped.push(SkippedPattern {
+                pattern: raw,
+                reason: SkipReason::Invalid(why),
+            }),
+            Ok(pattern) if !seen.insert(pattern.clone()) => changes.skipped.push(SkippedPattern {
+                pattern,
+                reason: SkipReason::Duplicate,
+            }),
+            Ok(pattern) => changes.applied.push(pattern),
+        }
+    }
+    changes
+}
+
+/// Returns the existing lines to delete alongside the per-pattern outcome.
+fn plan_removals(existing: &[String], requested: Vec<String>) -> (Vec<String>, PatternChanges) {
+    let mut lines = Vec::new();
+    let mut changes = PatternChanges::default();
+    for raw in requested {
+        let pattern = match normalize_pattern(&raw) {
+            Ok(p) => p,
+            Err(why) => {
+                changes.skipped.push(SkippedPattern {
+                    pattern: raw,
+                    reason: SkipReason::Invalid(why),
+                });
+                continue;
+            }
+        };
+        let matching: Vec<&String> = existing
+            .iter()
+            .filter(|line| normalize_pattern(line).as_deref() == Ok(pattern.as_str()))
+            .collect();
+        if matching.is_empty() || changes.applied.contains(&pattern) {
+            changes.skipped.push(SkippedPattern {
+                pattern,
+                reason: SkipReason::NotFound,
+            });
+            continue;
+        }
+        lines.extend(matching.into_iter().cloned());
+        changes.applied.push(pattern);
+    }
+    (lines, changes)
+}
+
 pub struct AddGitignorePatterns<'a, G, S, W>
 where
     G: GitignorePort + ?Sized,
@@ -44,15 +174,41 @@ where
         &self,
         owner_id: uuid::Uuid,
         patterns: Vec<String>,
-    ) -> anyhow::Result<usize> {
+    ) -> anyhow::Result<PatternChanges> {
         self.workspace.ensure_repository(owner_id, "main").await?;
         let dir = self.storage.user_repo_dir(owner_id);
         let _ = self.gitignore.ensure_gitignore(&dir).await?;
-        let added = self
-            .gitignore
-            .upsert_gitignore_patterns(&dir, &patterns)
-            .await?;
-        Ok(added)
+        let existing = self.gitignore.read_gitignore_patterns(&dir).await?;
+        let changes = plan_additions(&existing, patterns);
+        if !changes.applied.is_empty() {
+            self.gitignore
+                .upsert_gitignore_patterns(&dir, &changes.applied)
+                .await?;
+        }
+        Ok(changes)
+    }
+}
+
+pub struct RemoveGitignorePatterns<'a, G: GitignorePort + ?Sized, S: StoragePort + ?Sized> {
+    pub storage: &'a S,
+    pub gitignore: &'a G,
+}
+
+impl<'a, G: GitignorePort + ?Sized, S: StoragePort + ?Sized> RemoveGitignorePatterns<'a, G, S> {
+    pub async fn execute(
+        &self,
+        owner_id: uuid::Uuid,
+        patterns: Vec<String>,
+    ) -> anyhow::Result<PatternChanges> {
+        let dir = self.storage.user_repo_dir(owner_id);
+        let existing = self.gitignore.read_gitignore_patterns(&dir).await?;
+        let (lines, changes) = plan_removals(&existing, patterns);
+        if !lines.is_empty() {
+            self.gitignore
+                .remove_gitignore_patterns(&dir, &lines)
+                .await?;
+        }
+        Ok(changes)
     }
 }
 
@@ -65,20 +221,71 @@ impl<'a, G: GitignorePort + ?Sized, S: StoragePort + ?Sized> CheckPathIgnored<'a
     pub async fn execute(&self, owner_id: uuid::Uuid, rel_path: &str) -> anyhow::Result<bool> {
         let dir = self.storage.user_repo_dir(owner_id);
         let patterns = self.gitignore.read_gitignore_patterns(&dir).await?;
-        let p = rel_path.trim_start_matches('/');
-        let mut is_ignored = false;
-        for pat in &patterns {
-            if pat.ends_with('/') {
-                let prefix = pat.trim_end_matches('/');
-                if p.starts_with(prefix) {
-                    is_ignored = true;
-                    break;
-                }
-            } else if pat == p {
-                is_ignored = true;
-                break;
-            }
+        Ok(GitignoreMatcher::new(&patterns).is_ignored(rel_path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn normalizes_slashes() {
+        assert_eq!(normalize_pattern(" /drafts// ").unwrap(), "drafts/");
+        assert_eq!(normalize_pattern("./notes/a.md").unwrap(), "notes/a.md");
+        assert_eq!(normalize_pattern("!/keep.md").unwrap(), "!keep.md");
+        assert_eq!(normalize_pattern("*.tmp").unwrap(), "*.tmp");
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        for raw in ["", "   ", "/", "//", "# note", "a/../../b", "a\nb", "!"] {
+            assert!(
+                normalize_pattern(raw).is_err(),
+                "{:?} should be rejected",
+                raw
+            );
         }
-        Ok(is_ignored)
+        let changes = plan_additions(&[], strings(&["  ", "ok.md"]));
+        assert_eq!(changes.applied, strings(&["ok.md"]));
+        assert_eq!(changes.skipped.len(), 1);
+        assert!(matches!(changes.skipped[0].reason, SkipReason::Invalid(_)));
+    }
+
+    #[test]
+    fn dedups_against_existing_and_request() {
+        let existing = strings(&["*.md.tmp", "/drafts/"]);
+        let changes = plan_additions(
+            &existing,
+            strings(&["drafts", "drafts/", "*.md.tmp", "a.md", "/a.md"]),
+        );
+        assert_eq!(changes.applied, strings(&["drafts", "a.md"]));
+        let skipped: Vec<(&str, SkipReason)> = changes
+            .skipped
+            .iter()
+            .map(|s| (s.pattern.as_str(), s.reason))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                ("drafts/", SkipReason::Duplicate),
+                ("*.md.tmp", SkipReason::Duplicate),
+                ("a.md", SkipReason::Duplicate),
+            ]
+        );
+    }
+
+    #[test]
+    fn removal_matches_normalized_lines() {
+        let existing = strings(&["/drafts/", "a.md", ".DS_Store"]);
+        let (lines, changes) = plan_removals(&existing, strings(&["drafts/", "b.md", "", "a.md"]));
+        assert_eq!(lines, strings(&["/drafts/", "a.md"]));
+        assert_eq!(changes.applied, strings(&["drafts/", "a.md"]));
+        assert_eq!(changes.skipped[0].reason, SkipReason::NotFound);
+        assert!(matches!(changes.skipped[1].reason, SkipReason::Invalid(_)));
     }
 }
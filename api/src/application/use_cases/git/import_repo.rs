@@ -0,0 +1,332 @@
+use std::collections::{BTreeSet, HashMap};
+
+use uuid::Uuid;
+
+use crate::application::dto::git::{GitImportSummary, GitRemoteSnapshot};
+use crate::application::ports::document_repository::DocumentRepository;
+use crate::application::ports::git_repository::GitRepository;
+use crate::application::ports::git_workspace::{FOLDER_MARKER, GitWorkspacePort};
+use crate::application::ports::storage_port::StoragePort;
+
+/// Imports the configured remote branch into an empty repository: every markdown file becomes
+/// a document under folders mirroring its directories (folder markers recreate empty ones), and the remote head is recorded as the
+/// baseline commit so the next sync continues its history. Other files are skipped.
+///
+/// Created documents are recorded as pending until the baseline is set. A failed import
+/// deletes them again, and one that never finished (e.g. the process died) is cleaned up by
+/// the next attempt, so retrying never duplicates documents.
+pub struct ImportRemoteRepo<'a, R, D, S, W>
+where
+    R: GitRepository + ?Sized,
+    D: DocumentRepository + ?Sized,
+    S: StoragePort + ?Sized,
+    W: GitWorkspacePort + ?Sized,
+{
+    pub repo: &'a R,
+    pub documents: &'a D,
+    pub storage: &'a S,
+    pub workspace: &'a W,
+}
+
+impl<'a, R, D, S, W> ImportRemoteRepo<'a, R, D, S, W>
+where
+    R: GitRepository + ?Sized,
+    D: DocumentRepository + ?Sized,
+    S: StoragePort + ?Sized,
+    W: GitWorkspacePort + ?Sized,
+{
+    pub async fn execute(&self, user_id: Uuid) -> anyhow::Result<GitImportSummary> {
+        let Some(cfg) = self.repo.load_user_git_cfg(user_id).await? else {
+            anyhow::bail!("git is not configured");
+        };
+        // Checked up front as well as by `record_baseline`, before any document is created.
+        if !self.workspace.history(user_id, None, 1).await?.is_empty() {
+            // Pending documents left here belong to an import that did record its baseline.
+            self.repo.clear_pending_import(user_id).await?;
+            anyhow::bail!("repository already has history; import only into an empty repository");
+        }
+        self.discard_pending(user_id).await?;
+        let Some(snapshot) = self.workspace.fetch_remote_snapshot(&cfg).await? else {
+            return Ok(GitImportSummary::default());
+        };
+
+        match self.import_snapshot(user_id, &snapshot).await {
+            Ok(summary) => {
+                self.repo.clear_pending_import(user_id).await?;
+                Ok(summary)
+            }
+            Err(e) => {
+                if let Err(cleanup) = self.discard_pending(user_id).await {
+                    tracing::error!(%user_id, error = ?cleanup, "git_import_cleanup_failed");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn import_snapshot(
+        &self,
+        user_id: Uuid,
+        snapshot: &GitRemoteSnapshot,
+    ) -> anyhow::Result<GitImportSummary> {
+        let mut summary = GitImportSummary {
+            commit_hash: Some(snapshot.commit.hash.clone()),
+            ..Default::default()
+        };
+        let mut documents = Vec::new();
+        let mut dirs = BTreeSet::new();
+        for path in snapshot.files.keys() {
+            if let Some(dir) = path.strip_suffix(&format!("/{FOLDER_MARKER}")) {
+                insert_with_ancestors(&mut dirs, dir);
+                continue;
+            }
+            let Some(stem) = path.strip_suffix(".md") else {
+                summary.skipped.push(path.clone());
+                continue;
+            };
+            let (dir, title) = match stem.rsplit_once('/') {
+                Some((dir, title)) => (Some(dir), title),
+                None => (None, stem),
+            };
+            if let Some(dir) = dir {
+                insert_with_ancestors(&mut dirs, dir);
+            }
+            documents.push((path, dir, title));
+        }
+
+        // Sorted, so every folder comes after its parent.
+        let mut folders: HashMap<&str, Uuid> = HashMap::new();
+        for dir in dirs {
+            let (parent, name) = match dir.rsplit_once('/') {
+                Some((parent, name)) => (folders.get(parent).copied(), name),
+                None => (None, dir),
+            };
+            let folder = self
+                .documents
+                .create_for_user(user_id, name, parent, "folder")
+                .await?;
+            self.repo
+                .add_pending_import_document(user_id, folder.id)
+                .await?;
+            self.storage.sync_doc_paths(folder.id).await?;
+            folders.insert(dir, folder.id);
+            summary.folders += 1;
+        }
+
+        for (path, dir, title) in documents {
+            let parent = dir.and_then(|dir| folders.get(dir).copied());
+            let doc = self
+                .documents
+                .create_for_user(user_id, title, parent, "document")
+                .await?;
+            self.repo
+                .add_pending_import_document(user_id, doc.id)
+                .await?;
+            self.storage.sync_doc_paths(doc.id).await?;
+            let file = self.storage.build_doc_file_path(doc.id).await?;
+            self.storage
+                .write_bytes(file.as_path(), &snapshot.files[path])
+                .await?;
+            summary.documents += 1;
+        }
+
+        self.workspace.record_baseline(user_id, snapshot).await?;
+        Ok(summary)
+    }
+
+    /// Deletes the documents of an unfinished import.
+    async fn discard_pending(&self, user_id: Uuid) -> anyhow::Result<()> {
+        for id in self.repo.pending_import_documents(user_id).await? {
+            match self.documents.delete_owned(id, user_id).await? {
+                Some(kind) if kind == "folder" => {
+                    let _ = self.storage.delete_folder_physical(id).await;
+                }
+                Some(_) => {
+                    let _ = self.storage.delete_doc_physical(id).await;
+                }
+                None => {}
+            }
+        }
+        self.repo.clear_pending_import(user_id).await
+    }
+}
+
+fn insert_with_ancestors<'p>(dirs: &mut BTreeSet<&'p str>, dir: &'p str) {
+    let mut end = 0;
+    for segment in dir.split('/') {
+        end += segment.len();
+        dirs.insert(&dir[..end]);
+        end += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::application::dto::git::GitCommitInfo;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::git::{MemoryGit, MemoryWorkspace};
+    use crate::test_support::storage::MemoryStorage;
+
+    struct Store {
+        user_id: Uuid,
+        workspace: MemoryWorkspace,
+        git: Arc<MemoryGit>,
+        docs: MemoryDocuments,
+        storage: MemoryStorage,
+    }
+
+    impl Store {
+        /// A remote whose head holds `files`.
+        fn new(files: &[(&str, &str)]) -> Self {
+            let user_id = Uuid::new_v4();
+            let workspace = MemoryWorkspace::default();
+            workspace.git.configure(user_id, false);
+            *workspace.remote.lock().unwrap() = Some(GitRemoteSnapshot {
+                commit: GitCommitInfo {
+                    hash: "ab".repeat(20),
+                    message: "Initial notes".into(),
+                    author_name: "someone".into(),
+                    author_email: "someone@example.com".into(),
+                    time: chrono::Utc::now(),
+                },
+                files: files
+                    .iter()
+                    .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+                    .collect(),
+                pack: Vec::new(),
+            });
+            Self {
+                user_id,
+                git: workspace.git.clone(),
+                workspace,
+                docs: MemoryDocuments::default(),
+                storage: MemoryStorage::default(),
+            }
+        }
+
+        fn import(
+            &self,
+        ) -> ImportRemoteRepo<'_, MemoryGit, MemoryDocuments, MemoryStorage, MemoryWorkspace>
+        {
+            ImportRemoteRepo {
+                repo: self.git.as_ref(),
+                documents: &self.docs,
+                storage: &self.storage,
+                workspace: &self.workspace,
+            }
+        }
+
+        fn titled(&self, title: &str) -> Uuid {
+            let docs = self.docs.docs.lock().unwrap();
+            docs.iter().find(|d| d.doc.title == title).unwrap().doc.id
+        }
+
+        /// Folder path and type of the document titled `title`.
+        fn placed(&self, title: &str) -> (String, String) {
+            let id = self.titled(title);
+            let doc_type = self.docs.get(id).unwrap().doc.doc_type;
+            (self.docs.folder_path(id).join("/"), doc_type)
+        }
+
+        fn doc_count(&self) -> usize {
+            self.docs.docs.lock().unwrap().len()
+        }
+    }
+
+    #[tokio::test]
+    async fn import_recreates_nested_folders_and_sets_the_baseline() {
+        let store = Store::new(&[
+            ("README.md", "# Notes\n"),
+            ("projects/refmd/plan.md", "# Plan\n"),
+            ("projects/refmd/retro.md", "# Retro\n"),
+            ("projects/ideas.md", "# Ideas\n"),
+            ("projects/refmd/chart.png", "\u{89}PNG"),
+        ]);
+        let summary = store.import().execute(store.user_id).await.unwrap();
+
+        assert_eq!(
+            summary.commit_hash.as_deref(),
+            Some("ab".repeat(20).as_str())
+        );
+        assert_eq!((summary.folders, summary.documents), (2, 4));
+        assert_eq!(
+            summary.skipped,
+            vec!["projects/refmd/chart.png".to_string()]
+        );
+        assert_eq!(
+            store.workspace.baseline.lock().unwrap().as_deref(),
+            summary.commit_hash.as_deref()
+        );
+
+        let doc = |path: &str| (path.to_string(), "document".to_string());
+        assert_eq!(store.placed("README"), doc(""));
+        assert_eq!(store.placed("plan"), doc("projects/refmd"));
+        assert_eq!(store.placed("retro"), doc("projects/refmd"));
+        assert_eq!(store.placed("ideas"), doc("projects"));
+        assert_eq!(store.placed("refmd"), ("projects".into(), "folder".into()));
+
+        let plan = store.titled("plan");
+        assert_eq!(
+            store.storage.read(PathBuf::from(format!("{}.md", plan))),
+            Some(b"# Plan\n".to_vec())
+        );
+    }
+
+    #[tokio::test]
+    async fn folder_markers_recreate_empty_folders() {
+        let store = Store::new(&[
+            ("archive/2024/.gitkeep", ""),
+            ("notes/.gitkeep", ""),
+            ("notes/todo.md", "- [ ] ship\n"),
+        ]);
+        let summary = store.import().execute(store.user_id).await.unwrap();
+
+        assert_eq!((summary.folders, summary.documents), (3, 1));
+        assert!(summary.skipped.is_empty());
+        assert_eq!(store.placed("2024"), ("archive".into(), "folder".into()));
+        assert_eq!(store.placed("todo"), ("notes".into(), "document".into()));
+    }
+
+    #[tokio::test]
+    async fn failed_import_removes_its_documents_and_can_be_retried() {
+        let store = Store::new(&[
+            ("notes/todo.md", "- [ ] ship\n"),
+            ("README.md", "# Notes\n"),
+        ]);
+        let import = store.import();
+        store.workspace.fail_baseline.store(true, Ordering::SeqCst);
+        assert!(import.execute(store.user_id).await.is_err());
+        assert_eq!(store.doc_count(), 0);
+        assert!(store.storage.files.lock().unwrap().is_empty());
+        assert!(store.git.pending(store.user_id).is_empty());
+
+        store.workspace.fail_baseline.store(false, Ordering::SeqCst);
+        let summary = import.execute(store.user_id).await.unwrap();
+        assert_eq!((summary.folders, summary.documents), (1, 2));
+        assert_eq!(store.doc_count(), 3);
+        assert!(store.git.pending(store.user_id).is_empty());
+    }
+
+    #[tokio::test]
+    async fn unfinished_import_is_cleaned_up_by_the_next_attempt() {
+        let store = Store::new(&[("README.md", "# Notes\n")]);
+        let user = store.user_id;
+        // Left behind by an import that died before recording its baseline.
+        let orphan = store.docs.add(user, "README", "document", None);
+        store
+            .git
+            .add_pending_import_document(user, orphan.id)
+            .await
+            .unwrap();
+
+        store.import().execute(user).await.unwrap();
+
+        assert_eq!(store.doc_count(), 1);
+        assert_ne!(store.titled("README"), orphan.id);
+    }
+}
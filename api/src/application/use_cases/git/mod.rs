@@ -1,4 +1,6 @@
+pub mod auto_sync;
 pub mod delete_config;
+pub mod get_blame;
 pub mod get_changes;
 pub mod get_commit_diff;
 pub mod get_config;
@@ -9,6 +11,9 @@ pub mod gitignore_patterns;
 pub mod helpers;
 pub mod ignore_document;
 pub mod ignore_folder;
+pub mod import_repo;
 pub mod init_repo;
+pub mod storage_usage;
 pub mod sync_now;
+pub mod test_connection;
 pub mod upsert_config;
@@ -0,0 +1,58 @@
+use uuid::Uuid;
+
+use crate::application::ports::git_storage::{GitStorage, GitStorageUsage};
+
+/// Commits whose snapshots `PruneGitSnapshots` keeps when the caller does not say.
+pub const DEFAULT_KEEP_COMMITS: usize = 20;
+
+pub struct GetGitStorageUsage<'a, G: GitStorage + ?Sized> {
+    pub storage: &'a G,
+}
+
+impl<'a, G: GitStorage + ?Sized> GetGitStorageUsage<'a, G> {
+    pub async fn execute(&self, user_id: Uuid) -> anyhow::Result<GitStorageUsage> {
+        self.storage.usage(user_id).await
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GitGcOutcome {
+    pub commits_pruned: u64,
+    pub blobs_deleted: u64,
+    pub bytes_freed: u64,
+}
+
+/// Deletes the file snapshots of all but the newest `keep` commits. Packs are kept, so the
+/// full history and its diffs stay available; older diffs are computed from packs only.
+pub struct PruneGitSnapshots<'a, G: GitStorage + ?Sized> {
+    pub storage: &'a G,
+}
+
+impl<'a, G: GitStorage + ?Sized> PruneGitSnapshots<'a, G> {
+    pub async fn execute(&self, user_id: Uuid, keep: usize) -> anyhow::Result<GitGcOutcome> {
+        // The latest commit's snapshots seed the next sync; they are always kept.
+        let keep = keep.max(1);
+        let mut outcome = GitGcOutcome::default();
+        let mut next = self.storage.latest_commit(user_id).await?;
+        let mut depth = 0;
+        while let Some(meta) = next {
+            if depth >= keep {
+                let (blobs, bytes) = self
+                    .storage
+                    .delete_commit_blobs(user_id, &meta.commit_id)
+                    .await?;
+                if blobs > 0 {
+                    outcome.commits_pruned += 1;
+                    outcome.blobs_deleted += blobs;
+                    outcome.bytes_freed += bytes;
+                }
+            }
+            depth += 1;
+            next = match meta.parent_commit_id {
+                Some(parent) => self.storage.commit_meta(user_id, &parent).await?,
+                None => None,
+            };
+        }
+        Ok(outcome)
+    }
+}
@@ -26,20 +26,30 @@ where
         let cfg = self.repo.load_user_git_cfg(user_id).await?;
         let outcome: GitSyncOutcome = self.workspace.sync(user_id, &req, cfg.as_ref()).await?;
 
-        if let Some(cfg) = cfg.as_ref() {
-            if !cfg.repository_url.is_empty() {
-                let status = if outcome.pushed { "success" } else { "error" };
-                let _ = self
-                    .repo
-                    .log_sync_operation(
-                        user_id,
-                        "push",
-                        status,
-                        Some(&outcome.message),
-                        outcome.commit_hash.as_deref(),
-                    )
-                    .await;
-            }
+        if req.dry_run {
+            return Ok(GitSyncResponseDto {
+                success: true,
+                message: outcome.message,
+                commit_hash: outcome.commit_hash,
+                files_changed: outcome.files_changed,
+                preview: outcome.preview,
+            });
+        }
+
+        if let Some(cfg) = cfg.as_ref()
+            && !cfg.repository_url.is_empty()
+        {
+            let status = if outcome.pushed { "success" } else { "error" };
+            let _ = self
+                .repo
+                .log_sync_operation(
+                    user_id,
+                    "push",
+                    status,
+                    Some(&outcome.message),
+                    outcome.commit_hash.as_deref(),
+                )
+                .await;
         }
 
         let success = outcome.files_changed == 0 || outcome.pushed || outcome.commit_hash.is_some();
@@ -49,6 +59,58 @@ where
             message: outcome.message,
             commit_hash: outcome.commit_hash,
             files_changed: outcome.files_changed,
+            preview: None,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::dto::git::GitDiffStats;
+    use crate::test_support::git::MemoryWorkspace;
+
+    fn request(dry_run: bool) -> GitSyncRequestDto {
+        GitSyncRequestDto {
+            message: None,
+            force: None,
+            dry_run,
+        }
+    }
+
+    #[tokio::test]
+    async fn dry_run_reports_delta_without_committing() {
+        let workspace = MemoryWorkspace::default();
+        let repo = workspace.git.clone();
+        let user_id = Uuid::new_v4();
+        repo.configure(user_id, false);
+        workspace.edit("notes.md", Some("a\nb\n"), Some("a\nc\nd\n"));
+        let uc = SyncNow {
+            workspace: &workspace,
+            repo: repo.as_ref(),
+        };
+
+        let out = uc.execute(user_id, request(true)).await.unwrap();
+        assert!(out.success);
+        assert_eq!(out.files_changed, 1);
+        assert!(out.commit_hash.is_none());
+        let preview = out.preview.expect("preview");
+        assert_eq!(preview.files[0].path, "notes.md");
+        assert_eq!(
+            preview.stats,
+            GitDiffStats {
+                files_modified: 1,
+                lines_added: 2,
+                lines_deleted: 1,
+                ..Default::default()
+            }
+        );
+        assert!(workspace.commits.lock().unwrap().is_empty());
+        assert!(repo.operations(user_id).is_empty());
+
+        let out = uc.execute(user_id, request(false)).await.unwrap();
+        assert!(out.preview.is_none());
+        assert_eq!(workspace.commits.lock().unwrap().len(), 1);
+        assert_eq!(repo.operations(user_id), vec!["push".to_string()]);
+    }
+}
@@ -0,0 +1,36 @@
+use uuid::Uuid;
+
+use crate::application::dto::git::{GitConnectionCheck, GitConnectionError};
+use crate::application::ports::git_repository::GitRepository;
+use crate::application::ports::git_workspace::GitWorkspacePort;
+
+pub struct TestGitConnection<'a, R, W>
+where
+    R: GitRepository + ?Sized,
+    W: GitWorkspacePort + ?Sized,
+{
+    pub repo: &'a R,
+    pub workspace: &'a W,
+}
+
+impl<'a, R, W> TestGitConnection<'a, R, W>
+where
+    R: GitRepository + ?Sized,
+    W: GitWorkspacePort + ?Sized,
+{
+    pub async fn execute(&self, user_id: Uuid) -> anyhow::Result<GitConnectionCheck> {
+        match self.repo.load_user_git_cfg(user_id).await? {
+            Some(cfg) if !cfg.repository_url.trim().is_empty() => {
+                self.workspace.test_connection(&cfg).await
+            }
+            cfg => Ok(GitConnectionCheck {
+                branch: cfg.map(|c| c.branch_name).unwrap_or_default(),
+                reachable: false,
+                authenticated: false,
+                branch_exists: false,
+                error: Some(GitConnectionError::NotConfigured),
+                message: Some("no remote repository configured".to_string()),
+            }),
+        }
+    }
+}
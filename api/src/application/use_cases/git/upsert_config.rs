@@ -36,6 +36,11 @@ where
         if req.auth_type == "token" && !req.repository_url.starts_with("https://") {
             anyhow::bail!("bad_request");
         }
+        let template = req
+            .commit_message_template
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty());
         let (id, repository_url, branch_name, auth_type, auto_sync, created_at, updated_at) = self
             .repo
             .upsert_config(
@@ -45,6 +50,8 @@ where
                 &req.auth_type,
                 &req.auth_data,
                 req.auto_sync,
+                template,
+                req.append_change_summary,
             )
             .await?;
         self.workspace
@@ -58,6 +65,8 @@ where
             branch_name,
             auth_type,
             auto_sync,
+            commit_message_template: template.map(str::to_string),
+            append_change_summary: req.append_change_summary.unwrap_or(false),
             created_at,
             updated_at,
         })
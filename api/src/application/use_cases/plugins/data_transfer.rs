@@ -0,0 +1,105 @@
+use uuid::Uuid;
+
+use crate::application::dto::plugins::PluginDataExport;
+use crate::application::ports::plugin_repository::{
+    PluginKvEntry, PluginRecordImport, PluginRepository,
+};
+
+const DOC_SCOPE: &str = "doc";
+
+pub struct ExportPluginData<'a, R: PluginRepository + ?Sized> {
+    pub repo: &'a R,
+}
+
+impl<'a, R: PluginRepository + ?Sized> ExportPluginData<'a, R> {
+    pub async fn execute(&self, plugin: &str, doc_id: Uuid) -> anyhow::Result<PluginDataExport> {
+        let records = self
+            .repo
+            .list_all_records(plugin, DOC_SCOPE, doc_id)
+            .await?;
+        let kv = self.repo.list_kv(plugin, DOC_SCOPE, Some(doc_id)).await?;
+        Ok(PluginDataExport {
+            plugin: plugin.to_string(),
+            doc_id,
+            records,
+            kv,
+        })
+    }
+}
+
+pub struct ImportPluginData<'a, R: PluginRepository + ?Sized> {
+    pub repo: &'a R,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub records: usize,
+    pub kv: usize,
+}
+
+impl<'a, R: PluginRepository + ?Sized> ImportPluginData<'a, R> {
+    /// Restores `records` and `kv` into the doc scope. Record ids are kept
+    /// only with `preserve_ids`; otherwise fresh ids are generated.
+    pub async fn execute(
+        &self,
+        plugin: &str,
+        doc_id: Uuid,
+        records: Vec<PluginRecordImport>,
+        kv: Vec<PluginKvEntry>,
+        preserve_ids: bool,
+    ) -> anyhow::Result<ImportSummary> {
+        let records = prepare_records(records, preserve_ids);
+        let written = self
+            .repo
+            .import_scope(plugin, DOC_SCOPE, doc_id, &records, &kv)
+            .await?;
+        Ok(ImportSummary {
+            records: written,
+            kv: kv.len(),
+        })
+    }
+}
+
+fn prepare_records(
+    records: Vec<PluginRecordImport>,
+    preserve_ids: bool,
+) -> Vec<PluginRecordImport> {
+    records
+        .into_iter()
+        .map(|record| PluginRecordImport {
+            id: if preserve_ids { record.id } else { None },
+            ..record
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Vec<PluginRecordImport> {
+        vec![PluginRecordImport {
+            id: Some(Uuid::new_v4()),
+            kind: "comment".into(),
+            data: json!({ "text": "hi" }),
+            created_at: None,
+        }]
+    }
+
+    #[test]
+    fn regenerates_ids_for_fresh_import() {
+        let prepared = prepare_records(sample(), false);
+        assert_eq!(prepared.len(), 1);
+        assert!(prepared[0].id.is_none());
+        assert_eq!(prepared[0].data, json!({ "text": "hi" }));
+    }
+
+    #[test]
+    fn keeps_ids_when_preserving() {
+        let original = sample();
+        let id = original[0].id;
+        let prepared = prepare_records(original, true);
+        assert_eq!(prepared[0].id, id);
+    }
+}
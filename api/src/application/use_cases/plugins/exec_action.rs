@@ -2,15 +2,24 @@ use std::collections::HashSet;
 
 use uuid::Uuid;
 
+use crate::application::access::{self, AccessPolicy, Actor};
 use crate::application::dto::plugins::ExecResult;
+use crate::application::ports::access_repository::AccessRepository;
 use crate::application::ports::document_repository::DocumentRepository;
-use crate::application::ports::plugin_repository::PluginRepository;
+use crate::application::ports::plugin_repository::{PluginDataEffect, PluginRepository};
 use crate::application::ports::plugin_runtime::PluginRuntime;
+use crate::application::ports::plugin_secret_repository::PluginSecretRepository;
+use crate::application::ports::share_access_port::ShareAccessPort;
+use crate::application::ports::shares_repository::SharesRepository;
+use crate::application::use_cases::plugins::secrets::secrets_for_invocation;
+use crate::application::use_cases::shares::materialize_folder_share::InheritFolderShares;
 
 const PERMISSION_DOC_WRITE: &str = "doc.write";
 
 enum PluginEffectError {
     PermissionDenied { permission: String },
+    Invalid { index: usize, reason: String },
+    Forbidden { index: usize },
     Other(anyhow::Error),
 }
 
@@ -20,22 +29,241 @@ impl From<anyhow::Error> for PluginEffectError {
     }
 }
 
-pub struct ExecutePluginAction<'a, RT, PR, DR>
+/// Target document of a data effect before `createDocument` has been applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DocTarget {
+    Existing(Uuid),
+    Created,
+}
+
+/// Validated form of a plugin effect, parsed before anything is applied.
+#[derive(Debug, Clone, PartialEq)]
+enum PlannedEffect {
+    Log,
+    CreateDocument {
+        title: String,
+        doc_type: String,
+        parent_id: Option<Uuid>,
+    },
+    PutKv {
+        doc: DocTarget,
+        key: String,
+        value: serde_json::Value,
+    },
+    CreateRecord {
+        doc: DocTarget,
+        kind: String,
+        data: serde_json::Value,
+    },
+    UpdateRecord {
+        record_id: Uuid,
+        patch: serde_json::Value,
+    },
+    DeleteRecord {
+        record_id: Uuid,
+    },
+    Passthrough,
+}
+
+fn required_permission(effect_type: &str) -> Option<&'static str> {
+    match effect_type {
+        "createDocument" | "putKv" | "createRecord" | "updateRecord" | "deleteRecord" => {
+            Some(PERMISSION_DOC_WRITE)
+        }
+        _ => None,
+    }
+}
+
+fn ensure_permission(
+    permissions: &HashSet<String>,
+    permission: &str,
+) -> Result<(), PluginEffectError> {
+    if permissions.contains(permission) {
+        Ok(())
+    } else {
+        Err(PluginEffectError::PermissionDenied {
+            permission: permission.to_string(),
+        })
+    }
+}
+
+// Checked before any effect is applied so a denied effect never leaves
+// earlier effects of the same result half-applied.
+fn ensure_effects_permitted(
+    effects: &[serde_json::Value],
+    permissions: &HashSet<String>,
+) -> Result<(), PluginEffectError> {
+    for effect in effects {
+        let Some(effect_type) = effect.get("type").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if let Some(permission) = required_permission(effect_type) {
+            ensure_permission(permissions, permission)?;
+        }
+    }
+    Ok(())
+}
+
+fn uuid_field(effect: &serde_json::Value, key: &str) -> Result<Option<Uuid>, String> {
+    match effect.get(key) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(value) => value
+            .as_str()
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .map(Some)
+            .ok_or_else(|| format!("{key} must be a uuid")),
+    }
+}
+
+fn required_str(effect: &serde_json::Value, key: &str) -> Result<String, String> {
+    effect
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .ok_or_else(|| format!("{key} is required"))
+}
+
+fn plan_effects(effects: &[serde_json::Value]) -> Result<Vec<PlannedEffect>, PluginEffectError> {
+    let mut planned = Vec::with_capacity(effects.len());
+    let mut creates_document = false;
+
+    for (index, effect) in effects.iter().enumerate() {
+        let invalid = |reason: String| PluginEffectError::Invalid { index, reason };
+        let created_before = creates_document;
+        let doc_target = |effect: &serde_json::Value| -> Result<DocTarget, PluginEffectError> {
+            match uuid_field(effect, "docId").map_err(invalid)? {
+                Some(id) => Ok(DocTarget::Existing(id)),
+                None if created_before => Ok(DocTarget::Created),
+                None => Err(invalid("docId is required".to_string())),
+            }
+        };
+
+        let Some(effect_type) = effect.get("type").and_then(|v| v.as_str()) else {
+            planned.push(PlannedEffect::Passthrough);
+            continue;
+        };
+
+        let step = match effect_type {
+            "log" => PlannedEffect::Log,
+            "createDocument" => {
+                if creates_document {
+                    return Err(invalid("only one createDocument effect is allowed".into()));
+                }
+                creates_document = true;
+                PlannedEffect::CreateDocument {
+                    title: effect
+                        .get("title")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("Untitled")
+                        .to_string(),
+                    doc_type: effect
+                        .get("docType")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("document")
+                        .to_string(),
+                    parent_id: uuid_field(effect, "parentId").map_err(invalid)?,
+                }
+            }
+            "putKv" => PlannedEffect::PutKv {
+                doc: doc_target(effect)?,
+                key: required_str(effect, "key").map_err(invalid)?,
+                value: effect
+                    .get("value")
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null),
+            },
+            "createRecord" => PlannedEffect::CreateRecord {
+                doc: doc_target(effect)?,
+                kind: required_str(effect, "kind").map_err(invalid)?,
+                data: effect
+                    .get("data")
+                    .cloned()
+                    .unwrap_or_else(|| serde_json::json!({})),
+            },
+            "updateRecord" => PlannedEffect::UpdateRecord {
+                record_id: uuid_field(effect, "recordId")
+                    .map_err(invalid)?
+                    .ok_or_else(|| invalid("recordId is required".into()))?,
+                patch: effect
+                    .get("patch")
+                    .cloned()
+                    .unwrap_or_else(|| serde_json::json!({})),
+            },
+            "deleteRecord" => PlannedEffect::DeleteRecord {
+                record_id: uuid_field(effect, "recordId")
+                    .map_err(invalid)?
+                    .ok_or_else(|| invalid("recordId is required".into()))?,
+            },
+            _ => PlannedEffect::Passthrough,
+        };
+        planned.push(step);
+    }
+
+    Ok(planned)
+}
+
+fn permission_denied_result(permission: String) -> ExecResult {
+    ExecResult {
+        ok: false,
+        data: None,
+        effects: vec![],
+        error: Some(serde_json::json!({
+            "code": "PERMISSION_DENIED",
+            "permission": permission,
+        })),
+        applied: vec![],
+    }
+}
+
+fn effect_error_result(code: &str, index: usize, reason: Option<String>) -> ExecResult {
+    let mut error = serde_json::json!({ "code": code, "effect": index });
+    if let Some(reason) = reason {
+        error["reason"] = serde_json::Value::String(reason);
+    }
+    ExecResult {
+        ok: false,
+        data: None,
+        effects: vec![],
+        error: Some(error),
+        applied: vec![],
+    }
+}
+
+struct AppliedEffects {
+    passthrough: Vec<serde_json::Value>,
+    applied: Vec<serde_json::Value>,
+}
+
+pub struct ExecutePluginAction<'a, RT, PR, DR, AR, SA, SR, SC>
 where
     RT: PluginRuntime + ?Sized,
     PR: PluginRepository + ?Sized,
     DR: DocumentRepository + ?Sized,
+    AR: AccessRepository + ?Sized,
+    SA: ShareAccessPort + ?Sized,
+    SR: SharesRepository + ?Sized,
+    SC: PluginSecretRepository + ?Sized,
 {
     pub runtime: &'a RT,
     pub plugin_repo: &'a PR,
     pub document_repo: &'a DR,
+    pub access_repo: &'a AR,
+    pub share_access: &'a SA,
+    pub policy: AccessPolicy,
+    pub shares_repo: &'a SR,
+    pub secrets: &'a SC,
 }
 
-impl<'a, RT, PR, DR> ExecutePluginAction<'a, RT, PR, DR>
+impl<'a, RT, PR, DR, AR, SA, SR, SC> ExecutePluginAction<'a, RT, PR, DR, AR, SA, SR, SC>
 where
     RT: PluginRuntime + ?Sized,
     PR: PluginRepository + ?Sized,
     DR: DocumentRepository + ?Sized,
+    AR: AccessRepository + ?Sized,
+    SA: ShareAccessPort + ?Sized,
+    SR: SharesRepository + ?Sized,
+    SC: PluginSecretRepository + ?Sized,
 {
     pub async fn execute(
         &self,
@@ -52,207 +280,242 @@ where
             .unwrap_or_default()
             .into_iter()
             .collect::<HashSet<String>>();
+        let secrets = secrets_for_invocation(self.secrets, user_id, plugin, &permissions).await?;
         let try_result = self
             .runtime
-            .execute(Some(user_id), plugin, action, &payload)
+            .execute(Some(user_id), plugin, action, &payload, &secrets)
             .await?;
         let Some(res) = try_result else {
             return Ok(None);
         };
 
-        if !res.effects.is_empty() {
-            match self
-                .apply_server_effects(user_id, plugin, &res.effects, &permissions)
-                .await
-            {
-                Ok(passthrough) => {
-                    return Ok(Some(ExecResult {
-                        ok: true,
-                        data: res.data,
-                        effects: passthrough,
-                        error: None,
-                    }));
-                }
-                Err(PluginEffectError::PermissionDenied { permission }) => {
-                    self.log_only(&res.effects);
-                    return Ok(Some(ExecResult {
-                        ok: false,
-                        data: None,
-                        effects: vec![],
-                        error: Some(serde_json::json!({
-                            "code": "PERMISSION_DENIED",
-                            "permission": permission,
-                        })),
-                    }));
-                }
-                Err(PluginEffectError::Other(err)) => {
-                    self.log_only(&res.effects);
-                    return Err(err);
-                }
+        if let Err(PluginEffectError::PermissionDenied { permission }) =
+            ensure_effects_permitted(&res.effects, &permissions)
+        {
+            self.log_only(&res.effects);
+            return Ok(Some(permission_denied_result(permission)));
+        }
+
+        if res.effects.is_empty() {
+            return Ok(Some(res));
+        }
+
+        match self
+            .apply_server_effects(user_id, plugin, &res.effects)
+            .await
+        {
+            Ok(outcome) => Ok(Some(ExecResult {
+                ok: true,
+                data: res.data,
+                effects: outcome.passthrough,
+                error: None,
+                applied: outcome.applied,
+            })),
+            Err(PluginEffectError::PermissionDenied { permission }) => {
+                self.log_only(&res.effects);
+                Ok(Some(permission_denied_result(permission)))
+            }
+            Err(PluginEffectError::Invalid { index, reason }) => {
+                self.log_only(&res.effects);
+                Ok(Some(effect_error_result(
+                    "INVALID_EFFECT",
+                    index,
+                    Some(reason),
+                )))
+            }
+            Err(PluginEffectError::Forbidden { index }) => {
+                self.log_only(&res.effects);
+                Ok(Some(effect_error_result("FORBIDDEN", index, None)))
+            }
+            Err(PluginEffectError::Other(err)) => {
+                self.log_only(&res.effects);
+                Err(err)
             }
         }
+    }
 
-        self.log_only(&res.effects);
-        Ok(Some(res))
+    async fn require_doc_edit(
+        &self,
+        user_id: Uuid,
+        doc_id: Uuid,
+        index: usize,
+    ) -> Result<(), PluginEffectError> {
+        access::require_edit(
+            self.access_repo,
+            self.share_access,
+            self.policy,
+            &Actor::User(user_id),
+            doc_id,
+        )
+        .await
+        .map_err(|_| PluginEffectError::Forbidden { index })
     }
 
+    // Effects are validated up front, then data effects are written in one
+    // transaction. A document created by the same result is removed again
+    // if that transaction fails, so the whole result applies or nothing does.
     async fn apply_server_effects(
         &self,
         user_id: Uuid,
         plugin: &str,
         effects: &[serde_json::Value],
-        permissions: &HashSet<String>,
-    ) -> Result<Vec<serde_json::Value>, PluginEffectError> {
-        let mut doc_id_created: Option<Uuid> = None;
-        let mut passthrough: Vec<serde_json::Value> = Vec::new();
+    ) -> Result<AppliedEffects, PluginEffectError> {
+        let planned = plan_effects(effects)?;
 
-        for effect in effects {
-            let Some(effect_type) = effect.get("type").and_then(|v| v.as_str()) else {
-                passthrough.push(effect.clone());
-                continue;
-            };
-
-            match effect_type {
-                "log" => {
-                    self.log_effect(effect);
+        for (index, step) in planned.iter().enumerate() {
+            match step {
+                PlannedEffect::PutKv {
+                    doc: DocTarget::Existing(doc_id),
+                    ..
                 }
-                "createDocument" => {
-                    self.ensure_permission(permissions, PERMISSION_DOC_WRITE)?;
-                    let title = effect
-                        .get("title")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("Untitled");
-                    let doc_type = effect
-                        .get("docType")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("document");
-                    let parent_id = effect
-                        .get("parentId")
-                        .and_then(|v| v.as_str())
-                        .and_then(|s| Uuid::parse_str(s).ok());
-                    let doc = self
-                        .document_repo
-                        .create_for_user(user_id, title, parent_id, doc_type)
-                        .await
-                        .map_err(PluginEffectError::from)?;
-                    doc_id_created = Some(doc.id);
+                | PlannedEffect::CreateRecord {
+                    doc: DocTarget::Existing(doc_id),
+                    ..
+                } => {
+                    self.require_doc_edit(user_id, *doc_id, index).await?;
                 }
-                "putKv" => {
-                    self.ensure_permission(permissions, PERMISSION_DOC_WRITE)?;
-                    let Some(key) = effect.get("key").and_then(|v| v.as_str()) else {
-                        continue;
-                    };
-                    let value = effect
-                        .get("value")
-                        .cloned()
-                        .unwrap_or(serde_json::Value::Null);
-                    let doc_id = effect
-                        .get("docId")
-                        .and_then(|v| v.as_str())
-                        .and_then(|s| Uuid::parse_str(s).ok())
-                        .or(doc_id_created);
-                    if let Some(did) = doc_id {
-                        self.plugin_repo
-                            .kv_set(plugin, "doc", Some(did), key, &value)
-                            .await
-                            .map_err(PluginEffectError::from)?;
-                    }
+                PlannedEffect::UpdateRecord { record_id, .. }
+                | PlannedEffect::DeleteRecord { record_id } => {
+                    let record = self
+                        .plugin_repo
+                        .get_record(*record_id)
+                        .await?
+                        .filter(|r| r.plugin == plugin)
+                        .ok_or_else(|| PluginEffectError::Invalid {
+                            index,
+                            reason: "record not found".to_string(),
+                        })?;
+                    self.require_doc_edit(user_id, record.scope_id, index)
+                        .await?;
                 }
-                "createRecord" => {
-                    self.ensure_permission(permissions, PERMISSION_DOC_WRITE)?;
-                    let Some(kind) = effect.get("kind").and_then(|v| v.as_str()) else {
-                        continue;
-                    };
-                    let data = effect
-                        .get("data")
-                        .cloned()
-                        .unwrap_or_else(|| serde_json::json!({}));
-                    let doc_id = effect
-                        .get("docId")
-                        .and_then(|v| v.as_str())
-                        .and_then(|s| Uuid::parse_str(s).ok())
-                        .or(doc_id_created);
-                    if let Some(did) = doc_id {
-                        let _ = self
-                            .plugin_repo
-                            .insert_record(plugin, "doc", did, kind, &data)
-                            .await
-                            .map_err(PluginEffectError::from)?;
-                    }
+                _ => {}
+            }
+        }
+
+        let mut doc_id_created: Option<Uuid> = None;
+        for (index, step) in planned.iter().enumerate() {
+            if let PlannedEffect::CreateDocument {
+                title,
+                doc_type,
+                parent_id,
+            } = step
+            {
+                if let Some(parent) = parent_id {
+                    self.require_doc_edit(user_id, *parent, index).await?;
                 }
-                "updateRecord" => {
-                    self.ensure_permission(permissions, PERMISSION_DOC_WRITE)?;
-                    if let Some(record_id) = effect
-                        .get("recordId")
-                        .and_then(|v| v.as_str())
-                        .and_then(|s| Uuid::parse_str(s).ok())
-                    {
-                        let patch = effect
-                            .get("patch")
-                            .cloned()
-                            .unwrap_or_else(|| serde_json::json!({}));
-                        let _ = self
-                            .plugin_repo
-                            .update_record_data(record_id, &patch)
-                            .await
-                            .map_err(PluginEffectError::from)?;
+                let doc = self
+                    .document_repo
+                    .create_for_user(user_id, title, *parent_id, doc_type)
+                    .await?;
+                if parent_id.is_some() {
+                    InheritFolderShares {
+                        repo: self.shares_repo,
                     }
+                    .execute(doc.id)
+                    .await?;
                 }
-                "deleteRecord" => {
-                    self.ensure_permission(permissions, PERMISSION_DOC_WRITE)?;
-                    if let Some(record_id) = effect
-                        .get("recordId")
-                        .and_then(|v| v.as_str())
-                        .and_then(|s| Uuid::parse_str(s).ok())
+                doc_id_created = Some(doc.id);
+            }
+        }
+
+        let resolve = |doc: &DocTarget| match doc {
+            DocTarget::Existing(id) => *id,
+            // plan_effects only yields `Created` after a createDocument effect
+            DocTarget::Created => doc_id_created.unwrap_or_default(),
+        };
+
+        let mut data_effects: Vec<PluginDataEffect> = Vec::new();
+        let mut applied: Vec<serde_json::Value> = Vec::new();
+        let mut passthrough: Vec<serde_json::Value> = Vec::new();
+
+        for (effect, step) in effects.iter().zip(planned.iter()) {
+            match step {
+                PlannedEffect::Log => self.log_effect(effect),
+                PlannedEffect::CreateDocument { .. } => {
+                    let mut applied_effect = effect.clone();
+                    if let (Some(obj), Some(id)) = (applied_effect.as_object_mut(), doc_id_created)
                     {
-                        let _ = self
-                            .plugin_repo
-                            .delete_record(record_id)
-                            .await
-                            .map_err(PluginEffectError::from)?;
+                        obj.insert("docId".into(), serde_json::json!(id));
                     }
+                    applied.push(applied_effect);
                 }
-                "navigate" => {
-                    if let Some(doc_id) = doc_id_created {
-                        if let Some(to) = effect.get("to").and_then(|v| v.as_str()) {
-                            if to.contains(":createdDocId") {
-                                let mut cloned = effect.clone();
-                                if let Some(obj) = cloned.as_object_mut() {
-                                    obj.insert(
-                                        "to".into(),
-                                        serde_json::Value::String(
-                                            to.replace(":createdDocId", &doc_id.to_string()),
-                                        ),
-                                    );
-                                }
-                                passthrough.push(cloned);
-                                continue;
-                            }
-                        }
-                    }
-                    passthrough.push(effect.clone());
+                PlannedEffect::PutKv { doc, key, value } => {
+                    data_effects.push(PluginDataEffect::PutKv {
+                        doc_id: resolve(doc),
+                        key: key.clone(),
+                        value: value.clone(),
+                    });
+                    applied.push(effect.clone());
+                }
+                PlannedEffect::CreateRecord { doc, kind, data } => {
+                    data_effects.push(PluginDataEffect::CreateRecord {
+                        doc_id: resolve(doc),
+                        kind: kind.clone(),
+                        data: data.clone(),
+                    });
+                    applied.push(effect.clone());
+                }
+                PlannedEffect::UpdateRecord { record_id, patch } => {
+                    data_effects.push(PluginDataEffect::UpdateRecord {
+                        record_id: *record_id,
+                        patch: patch.clone(),
+                    });
+                    applied.push(effect.clone());
                 }
-                _ => {
-                    passthrough.push(effect.clone());
+                PlannedEffect::DeleteRecord { record_id } => {
+                    data_effects.push(PluginDataEffect::DeleteRecord {
+                        record_id: *record_id,
+                    });
+                    applied.push(effect.clone());
                 }
+                PlannedEffect::Passthrough => {
+                    passthrough.push(Self::substitute_created_doc(effect, doc_id_created));
+                }
+            }
+        }
+
+        if !data_effects.is_empty()
+            && let Err(err) = self.plugin_repo.apply_effects(plugin, &data_effects).await
+        {
+            if let Some(doc_id) = doc_id_created
+                && let Err(cleanup_err) = self.document_repo.delete_owned(doc_id, user_id).await
+            {
+                tracing::warn!(
+                    error = ?cleanup_err,
+                    doc_id = %doc_id,
+                    "plugin_effect_rollback_delete_failed"
+                );
             }
+            return Err(PluginEffectError::Other(err));
         }
 
-        Ok(passthrough)
+        Ok(AppliedEffects {
+            passthrough,
+            applied,
+        })
     }
 
-    fn ensure_permission(
-        &self,
-        permissions: &HashSet<String>,
-        permission: &str,
-    ) -> Result<(), PluginEffectError> {
-        if permissions.iter().any(|p| p == permission) {
-            Ok(())
-        } else {
-            Err(PluginEffectError::PermissionDenied {
-                permission: permission.to_string(),
-            })
+    fn substitute_created_doc(
+        effect: &serde_json::Value,
+        doc_id_created: Option<Uuid>,
+    ) -> serde_json::Value {
+        let Some(doc_id) = doc_id_created else {
+            return effect.clone();
+        };
+        if effect.get("type").and_then(|v| v.as_str()) != Some("navigate") {
+            return effect.clone();
+        }
+        let mut cloned = effect.clone();
+        if let Some(to) = effect.get("to").and_then(|v| v.as_str())
+            && to.contains(":createdDocId")
+            && let Some(obj) = cloned.as_object_mut()
+        {
+            obj.insert(
+                "to".into(),
+                serde_json::Value::String(to.replace(":createdDocId", &doc_id.to_string())),
+            );
         }
+        cloned
     }
 
     fn log_only(&self, effects: &[serde_json::Value]) {
@@ -277,3 +540,261 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    use super::*;
+    use crate::application::ports::plugin_runtime::{PluginScheduleSpec, PluginSecrets};
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::plugins::MemoryPluginData;
+    use crate::test_support::shares::MemoryShares;
+
+    /// A plugin declaring `permissions` whose every action returns `effects`.
+    struct Runtime {
+        permissions: Vec<String>,
+        effects: Vec<serde_json::Value>,
+    }
+
+    #[async_trait]
+    impl PluginRuntime for Runtime {
+        async fn execute(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+            _: &str,
+            _: &serde_json::Value,
+            _: &PluginSecrets,
+        ) -> anyhow::Result<Option<ExecResult>> {
+            Ok(Some(ExecResult {
+                ok: true,
+                data: None,
+                effects: self.effects.clone(),
+                error: None,
+                applied: vec![],
+            }))
+        }
+        async fn render_placeholder(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+            _: &str,
+            _: &serde_json::Value,
+        ) -> anyhow::Result<Option<serde_json::Value>> {
+            unimplemented!()
+        }
+        async fn permissions(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+        ) -> anyhow::Result<Option<Vec<String>>> {
+            Ok(Some(self.permissions.clone()))
+        }
+        async fn schedules(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+        ) -> anyhow::Result<Option<Vec<PluginScheduleSpec>>> {
+            unimplemented!()
+        }
+    }
+
+    /// Never consulted: the test plugins do not declare the `secrets` permission.
+    struct NoSecrets;
+
+    #[async_trait]
+    impl PluginSecretRepository for NoSecrets {
+        async fn put_secret(&self, _: Uuid, _: &str, _: &str, _: &str) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn delete_secret(&self, _: Uuid, _: &str, _: &str) -> anyhow::Result<bool> {
+            unimplemented!()
+        }
+        async fn list_secret_keys(&self, _: Uuid, _: &str) -> anyhow::Result<Vec<String>> {
+            unimplemented!()
+        }
+        async fn load_secrets(&self, _: Uuid, _: &str) -> anyhow::Result<HashMap<String, String>> {
+            unimplemented!()
+        }
+    }
+
+    struct Fixture {
+        owner: Uuid,
+        doc: Uuid,
+        documents: Arc<MemoryDocuments>,
+        shares: MemoryShares,
+        data: MemoryPluginData,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let owner = Uuid::new_v4();
+            let documents = Arc::new(MemoryDocuments::default());
+            let doc = documents.add(owner, "Board", "document", None).id;
+            Self {
+                owner,
+                doc,
+                shares: MemoryShares::new(documents.clone()),
+                documents,
+                data: MemoryPluginData::default(),
+            }
+        }
+
+        async fn exec(&self, runtime: &Runtime) -> ExecResult {
+            ExecutePluginAction {
+                runtime,
+                plugin_repo: &self.data,
+                document_repo: self.documents.as_ref(),
+                access_repo: self.documents.as_ref(),
+                share_access: &self.shares,
+                policy: AccessPolicy::default(),
+                shares_repo: &self.shares,
+                secrets: &NoSecrets,
+            }
+            .execute(self.owner, "kanban", "add", None)
+            .await
+            .unwrap()
+            .expect("plugin result")
+        }
+    }
+
+    fn write_effects(doc: Uuid) -> Vec<serde_json::Value> {
+        vec![
+            json!({ "type": "createRecord", "kind": "card", "docId": doc, "data": { "title": "a" } }),
+            json!({ "type": "putKv", "docId": doc, "key": "layout", "value": "grid" }),
+            json!({ "type": "createDocument", "title": "Archive" }),
+        ]
+    }
+
+    #[test]
+    fn rejects_write_effect_without_permission() {
+        let effects = vec![json!({ "type": "createRecord", "kind": "note", "docId": Uuid::nil() })];
+        let permissions = HashSet::new();
+
+        match ensure_effects_permitted(&effects, &permissions) {
+            Err(PluginEffectError::PermissionDenied { permission }) => {
+                assert_eq!(permission, PERMISSION_DOC_WRITE);
+            }
+            _ => panic!("expected permission denied"),
+        }
+    }
+
+    #[test]
+    fn accepts_write_effect_with_permission() {
+        let effects = vec![
+            json!({ "type": "createRecord", "kind": "note", "docId": Uuid::nil() }),
+            json!({ "type": "navigate", "to": "/documents/:createdDocId" }),
+        ];
+        let permissions = HashSet::from([PERMISSION_DOC_WRITE.to_string()]);
+
+        assert!(ensure_effects_permitted(&effects, &permissions).is_ok());
+    }
+
+    #[tokio::test]
+    async fn write_effects_are_denied_without_doc_write() {
+        let fx = Fixture::new();
+        let runtime = Runtime {
+            permissions: vec![],
+            effects: write_effects(fx.doc),
+        };
+
+        let result = fx.exec(&runtime).await;
+
+        assert!(!result.ok);
+        assert_eq!(
+            result.error,
+            Some(json!({ "code": "PERMISSION_DENIED", "permission": PERMISSION_DOC_WRITE }))
+        );
+        assert!(result.applied.is_empty());
+        let records = fx
+            .data
+            .list_all_records("kanban", "doc", fx.doc)
+            .await
+            .unwrap();
+        assert!(records.is_empty());
+        let layout = fx
+            .data
+            .kv_get("kanban", "doc", Some(fx.doc), "layout")
+            .await
+            .unwrap();
+        assert_eq!(layout, None);
+        assert_eq!(fx.documents.docs.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn write_effects_are_applied_with_doc_write() {
+        let fx = Fixture::new();
+        let runtime = Runtime {
+            permissions: vec![PERMISSION_DOC_WRITE.to_string()],
+            effects: write_effects(fx.doc),
+        };
+
+        let result = fx.exec(&runtime).await;
+
+        assert!(result.ok, "{:?}", result.error);
+        assert_eq!(result.applied.len(), 3);
+        let records = fx
+            .data
+            .list_all_records("kanban", "doc", fx.doc)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].data, json!({ "title": "a" }));
+        let layout = fx
+            .data
+            .kv_get("kanban", "doc", Some(fx.doc), "layout")
+            .await
+            .unwrap();
+        assert_eq!(layout, Some(json!("grid")));
+        assert_eq!(fx.documents.docs.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn plans_multiple_effects_against_created_document() {
+        let effects = vec![
+            json!({ "type": "createDocument", "title": "Board" }),
+            json!({ "type": "createRecord", "kind": "card", "data": { "title": "a" } }),
+            json!({ "type": "putKv", "key": "layout", "value": "grid" }),
+            json!({ "type": "navigate", "to": "/document/:createdDocId" }),
+        ];
+
+        let Ok(planned) = plan_effects(&effects) else {
+            panic!("expected valid effects");
+        };
+        assert_eq!(planned.len(), 4);
+        assert!(matches!(
+            planned[1],
+            PlannedEffect::CreateRecord {
+                doc: DocTarget::Created,
+                ..
+            }
+        ));
+        assert!(matches!(
+            planned[2],
+            PlannedEffect::PutKv {
+                doc: DocTarget::Created,
+                ..
+            }
+        ));
+        assert_eq!(planned[3], PlannedEffect::Passthrough);
+    }
+
+    #[test]
+    fn invalid_effect_rejects_whole_batch() {
+        let doc_id = Uuid::new_v4();
+        let effects = vec![
+            json!({ "type": "createRecord", "kind": "card", "docId": doc_id }),
+            json!({ "type": "putKv", "docId": doc_id }),
+        ];
+
+        match plan_effects(&effects) {
+            Err(PluginEffectError::Invalid { index, .. }) => assert_eq!(index, 1),
+            _ => panic!("expected invalid effect"),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use uuid::Uuid;
+
+use crate::application::ports::plugin_exec_log_repository::{
+    PluginExecLogEntry, PluginExecLogRepository,
+};
+
+const MAX_LIMIT: i64 = 200;
+
+pub struct ListPluginExecLog<'a, R: PluginExecLogRepository + ?Sized> {
+    pub repo: &'a R,
+}
+
+impl<'a, R: PluginExecLogRepository + ?Sized> ListPluginExecLog<'a, R> {
+    /// The caller's own invocations of `plugin`, most recent first.
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        plugin: &str,
+        limit: i64,
+    ) -> anyhow::Result<Vec<PluginExecLogEntry>> {
+        self.repo
+            .recent(user_id, plugin, limit.clamp(1, MAX_LIMIT))
+            .await
+    }
+}
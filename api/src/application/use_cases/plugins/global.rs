@@ -0,0 +1,333 @@
+use uuid::Uuid;
+
+use crate::application::ports::plugin_event_publisher::{PluginEventPublisher, PluginScopedEvent};
+use crate::application::ports::plugin_installer::{
+    InstalledPlugin, PluginInstallError, PluginInstaller,
+};
+use crate::application::ports::plugin_package_fetcher::PluginPackageFetcher;
+use crate::application::ports::user_repository::UserRepository;
+
+#[derive(thiserror::Error, Debug)]
+pub enum GlobalPluginError {
+    #[error("only administrators can manage global plugins")]
+    Forbidden,
+    #[error("global plugin is not installed")]
+    NotInstalled,
+    #[error("failed to download plugin package")]
+    Download(#[source] anyhow::Error),
+    #[error("failed to install plugin package")]
+    Install(#[source] PluginInstallError),
+    #[error("failed to update global plugin")]
+    Storage(#[source] anyhow::Error),
+    #[error("failed to publish plugin event")]
+    Event(#[source] anyhow::Error),
+}
+
+/// Admins are the users whose email appears in `admin_emails` (already lowercased).
+async fn ensure_admin<U>(
+    users: &U,
+    admin_emails: &[String],
+    user_id: Uuid,
+) -> Result<(), GlobalPluginError>
+where
+    U: UserRepository + ?Sized,
+{
+    let user = users
+        .find_by_id(user_id)
+        .await
+        .map_err(GlobalPluginError::Storage)?;
+    match user {
+        Some(user) if admin_emails.contains(&user.email.to_ascii_lowercase()) => Ok(()),
+        _ => Err(GlobalPluginError::Forbidden),
+    }
+}
+
+/// Global events carry no user, so every subscriber sees them.
+async fn publish_global<E>(events: &E, payload: serde_json::Value) -> Result<(), GlobalPluginError>
+where
+    E: PluginEventPublisher + ?Sized,
+{
+    let event = PluginScopedEvent {
+        user_id: None,
+        payload,
+    };
+    events
+        .publish(&event)
+        .await
+        .map_err(GlobalPluginError::Event)
+}
+
+pub struct InstallGlobalPlugin<'a, U, F, I, E>
+where
+    U: UserRepository + ?Sized,
+    F: PluginPackageFetcher + ?Sized,
+    I: PluginInstaller + ?Sized,
+    E: PluginEventPublisher + ?Sized,
+{
+    pub users: &'a U,
+    pub admin_emails: &'a [String],
+    pub fetcher: &'a F,
+    pub installer: &'a I,
+    pub events: &'a E,
+}
+
+impl<'a, U, F, I, E> InstallGlobalPlugin<'a, U, F, I, E>
+where
+    U: UserRepository + ?Sized,
+    F: PluginPackageFetcher + ?Sized,
+    I: PluginInstaller + ?Sized,
+    E: PluginEventPublisher + ?Sized,
+{
+    pub async fn execute(
+        &self,
+        actor_id: Uuid,
+        url: &str,
+        token: Option<&str>,
+    ) -> Result<InstalledPlugin, GlobalPluginError> {
+        ensure_admin(self.users, self.admin_emails, actor_id).await?;
+        let bytes = self
+            .fetcher
+            .fetch(url, token)
+            .await
+            .map_err(GlobalPluginError::Download)?;
+        let installed = self
+            .installer
+            .install_global(&bytes)
+            .await
+            .map_err(GlobalPluginError::Install)?;
+        publish_global(
+            self.events,
+            serde_json::json!({
+                "event": "installed",
+                "scope": "global",
+                "id": installed.id,
+                "version": installed.version,
+            }),
+        )
+        .await?;
+        Ok(installed)
+    }
+}
+
+pub struct UninstallGlobalPlugin<'a, U, I, E>
+where
+    U: UserRepository + ?Sized,
+    I: PluginInstaller + ?Sized,
+    E: PluginEventPublisher + ?Sized,
+{
+    pub users: &'a U,
+    pub admin_emails: &'a [String],
+    pub installer: &'a I,
+    pub events: &'a E,
+}
+
+impl<'a, U, I, E> UninstallGlobalPlugin<'a, U, I, E>
+where
+    U: UserRepository + ?Sized,
+    I: PluginInstaller + ?Sized,
+    E: PluginEventPublisher + ?Sized,
+{
+    pub async fn execute(&self, actor_id: Uuid, plugin_id: &str) -> Result<(), GlobalPluginError> {
+        ensure_admin(self.users, self.admin_emails, actor_id).await?;
+        let removed = self
+            .installer
+            .uninstall_global(plugin_id)
+            .await
+            .map_err(GlobalPluginError::Storage)?;
+        if !removed {
+            return Err(GlobalPluginError::NotInstalled);
+        }
+        publish_global(
+            self.events,
+            serde_json::json!({ "event": "uninstalled", "scope": "global", "id": plugin_id }),
+        )
+        .await
+    }
+}
+
+pub struct SetGlobalPluginEnabled<'a, U, I, E>
+where
+    U: UserRepository + ?Sized,
+    I: PluginInstaller + ?Sized,
+    E: PluginEventPublisher + ?Sized,
+{
+    pub users: &'a U,
+    pub admin_emails: &'a [String],
+    pub installer: &'a I,
+    pub events: &'a E,
+}
+
+impl<'a, U, I, E> SetGlobalPluginEnabled<'a, U, I, E>
+where
+    U: UserRepository + ?Sized,
+    I: PluginInstaller + ?Sized,
+    E: PluginEventPublisher + ?Sized,
+{
+    pub async fn execute(
+        &self,
+        actor_id: Uuid,
+        plugin_id: &str,
+        enabled: bool,
+    ) -> Result<(), GlobalPluginError> {
+        ensure_admin(self.users, self.admin_emails, actor_id).await?;
+        let found = self
+            .installer
+            .set_global_enabled(plugin_id, enabled)
+            .await
+            .map_err(GlobalPluginError::Storage)?;
+        if !found {
+            return Err(GlobalPluginError::NotInstalled);
+        }
+        let kind = if enabled { "enabled" } else { "disabled" };
+        publish_global(
+            self.events,
+            serde_json::json!({ "event": kind, "scope": "global", "id": plugin_id }),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::test_support::users::MemoryUsers;
+
+    struct Package;
+
+    #[async_trait]
+    impl PluginPackageFetcher for Package {
+        async fn fetch(&self, _: &str, _: Option<&str>) -> anyhow::Result<Vec<u8>> {
+            Ok(b"archive".to_vec())
+        }
+    }
+
+    /// Global plugins by id -> (version, enabled).
+    #[derive(Default)]
+    struct Store(Mutex<HashMap<String, (String, bool)>>);
+
+    #[async_trait]
+    impl PluginInstaller for Store {
+        async fn install_for_user(
+            &self,
+            _: Uuid,
+            _: &[u8],
+        ) -> Result<InstalledPlugin, PluginInstallError> {
+            unimplemented!()
+        }
+        async fn install_global(&self, _: &[u8]) -> Result<InstalledPlugin, PluginInstallError> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert("mermaid".into(), ("1.0.0".into(), true));
+            Ok(InstalledPlugin {
+                id: "mermaid".into(),
+                version: "1.0.0".into(),
+            })
+        }
+        async fn uninstall_global(&self, plugin_id: &str) -> anyhow::Result<bool> {
+            Ok(self.0.lock().unwrap().remove(plugin_id).is_some())
+        }
+        async fn set_global_enabled(&self, plugin_id: &str, enabled: bool) -> anyhow::Result<bool> {
+            Ok(match self.0.lock().unwrap().get_mut(plugin_id) {
+                Some(entry) => {
+                    entry.1 = enabled;
+                    true
+                }
+                None => false,
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct Events(Mutex<Vec<PluginScopedEvent>>);
+
+    #[async_trait]
+    impl PluginEventPublisher for Events {
+        async fn publish(&self, event: &PluginScopedEvent) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn admins_install_global_plugins_for_everyone() {
+        let users = MemoryUsers::default();
+        let admin = users.add("Ops", "Ops@example.com");
+        let member = users.add("Member", "member@example.com");
+        let admin_emails = vec!["ops@example.com".to_string()];
+        let (store, events) = (Store::default(), Events::default());
+        let install = InstallGlobalPlugin {
+            users: &users,
+            admin_emails: &admin_emails,
+            fetcher: &Package,
+            installer: &store,
+            events: &events,
+        };
+
+        let err = install
+            .execute(member.id, "https://plugins.example.com/mermaid.zip", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GlobalPluginError::Forbidden));
+        assert!(store.0.lock().unwrap().is_empty());
+
+        let installed = install
+            .execute(admin.id, "https://plugins.example.com/mermaid.zip", None)
+            .await
+            .unwrap();
+        assert_eq!(installed.id, "mermaid");
+        let published = events.0.lock().unwrap();
+        assert_eq!(published.len(), 1);
+        // Broadcast rather than scoped to the installing admin.
+        assert_eq!(published[0].user_id, None);
+        assert_eq!(published[0].payload["scope"], "global");
+    }
+
+    #[tokio::test]
+    async fn only_admins_toggle_or_remove_global_plugins() {
+        let users = MemoryUsers::default();
+        let admin = users.add("Ops", "ops@example.com");
+        let member = users.add("Member", "member@example.com");
+        let admin_emails = vec!["ops@example.com".to_string()];
+        let (store, events) = (Store::default(), Events::default());
+        store
+            .0
+            .lock()
+            .unwrap()
+            .insert("mermaid".into(), ("1.0.0".into(), true));
+
+        let toggle = SetGlobalPluginEnabled {
+            users: &users,
+            admin_emails: &admin_emails,
+            installer: &store,
+            events: &events,
+        };
+        assert!(matches!(
+            toggle.execute(member.id, "mermaid", false).await,
+            Err(GlobalPluginError::Forbidden)
+        ));
+        toggle.execute(admin.id, "mermaid", false).await.unwrap();
+        assert!(!store.0.lock().unwrap()["mermaid"].1);
+
+        let uninstall = UninstallGlobalPlugin {
+            users: &users,
+            admin_emails: &admin_emails,
+            installer: &store,
+            events: &events,
+        };
+        assert!(matches!(
+            uninstall.execute(member.id, "mermaid").await,
+            Err(GlobalPluginError::Forbidden)
+        ));
+        uninstall.execute(admin.id, "mermaid").await.unwrap();
+        assert!(matches!(
+            uninstall.execute(admin.id, "mermaid").await,
+            Err(GlobalPluginError::NotInstalled)
+        ));
+    }
+}
@@ -1,4 +1,9 @@
+pub mod data_transfer;
 pub mod exec_action;
+pub mod exec_log;
+pub mod global;
 pub mod install_from_url;
 pub mod kv;
 pub mod records;
+pub mod secrets;
+pub mod versions;
@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use crate::application::ports::plugin_runtime::PluginSecrets;
+use crate::application::ports::plugin_secret_repository::PluginSecretRepository;
+
+pub const PERMISSION_SECRETS: &str = "secrets";
+
+/// Secrets handed to a plugin invocation: the user's secrets for `plugin` when it declares
+/// the `secrets` permission, nothing otherwise.
+pub async fn secrets_for_invocation<S>(
+    repo: &S,
+    user_id: Uuid,
+    plugin: &str,
+    permissions: &HashSet<String>,
+) -> anyhow::Result<PluginSecrets>
+where
+    S: PluginSecretRepository + ?Sized,
+{
+    if !permissions.contains(PERMISSION_SECRETS) {
+        return Ok(PluginSecrets::new());
+    }
+    repo.load_secrets(user_id, plugin).await
+}
+
+pub struct PutPluginSecret<'a, S: PluginSecretRepository + ?Sized> {
+    pub repo: &'a S,
+}
+
+impl<'a, S: PluginSecretRepository + ?Sized> PutPluginSecret<'a, S> {
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        plugin: &str,
+        key: &str,
+        value: &str,
+    ) -> anyhow::Result<()> {
+        self.repo.put_secret(user_id, plugin, key, value).await
+    }
+}
+
+pub struct DeletePluginSecret<'a, S: PluginSecretRepository + ?Sized> {
+    pub repo: &'a S,
+}
+
+impl<'a, S: PluginSecretRepository + ?Sized> DeletePluginSecret<'a, S> {
+    pub async fn execute(&self, user_id: Uuid, plugin: &str, key: &str) -> anyhow::Result<bool> {
+        self.repo.delete_secret(user_id, plugin, key).await
+    }
+}
+
+/// Lists secret names only; values are never returned to clients.
+pub struct ListPluginSecretKeys<'a, S: PluginSecretRepository + ?Sized> {
+    pub repo: &'a S,
+}
+
+impl<'a, S: PluginSecretRepository + ?Sized> ListPluginSecretKeys<'a, S> {
+    pub async fn execute(&self, user_id: Uuid, plugin: &str) -> anyhow::Result<Vec<String>> {
+        self.repo.list_secret_keys(user_id, plugin).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Secrets {
+        values: Mutex<BTreeMap<(Uuid, String, String), String>>,
+    }
+
+    #[async_trait]
+    impl PluginSecretRepository for Secrets {
+        async fn put_secret(
+            &self,
+            user_id: Uuid,
+            plugin: &str,
+            key: &str,
+            value: &str,
+        ) -> anyhow::Result<()> {
+            self.values.lock().unwrap().insert(
+                (user_id, plugin.to_string(), key.to_string()),
+                value.to_string(),
+            );
+            Ok(())
+        }
+
+        async fn delete_secret(
+            &self,
+            user_id: Uuid,
+            plugin: &str,
+            key: &str,
+        ) -> anyhow::Result<bool> {
+            Ok(self
+                .values
+                .lock()
+                .unwrap()
+                .remove(&(user_id, plugin.to_string(), key.to_string()))
+                .is_some())
+        }
+
+        async fn list_secret_keys(
+            &self,
+            user_id: Uuid,
+            plugin: &str,
+        ) -> anyhow::Result<Vec<String>> {
+            Ok(self
+                .values
+                .lock()
+                .unwrap()
+                .keys()
+                .filter(|(u, p, _)| *u == user_id && p == plugin)
+                .map(|(_, _, key)| key.clone())
+                .collect())
+        }
+
+        async fn load_secrets(
+            &self,
+            user_id: Uuid,
+            plugin: &str,
+        ) -> anyhow::Result<HashMap<String, String>> {
+            Ok(self
+                .values
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|((u, p, _), _)| *u == user_id && p == plugin)
+                .map(|((_, _, key), value)| (key.clone(), value.clone()))
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn listing_returns_names_but_never_values() {
+        let repo = Secrets::default();
+        let user = Uuid::new_v4();
+        let put = PutPluginSecret { repo: &repo };
+        put.execute(user, "github", "token", "ghp_secret")
+            .await
+            .unwrap();
+        put.execute(user, "github", "webhook", "whsec_secret")
+            .await
+            .unwrap();
+
+        let keys = ListPluginSecretKeys { repo: &repo }
+            .execute(user, "github")
+            .await
+            .unwrap();
+        assert_eq!(keys, vec!["token", "webhook"]);
+
+        let removed = DeletePluginSecret { repo: &repo }
+            .execute(user, "github", "webhook")
+            .await
+            .unwrap();
+        assert!(removed);
+    }
+
+    #[tokio::test]
+    async fn only_plugins_with_the_secrets_permission_receive_them() {
+        let repo = Secrets::default();
+        let (user, other) = (Uuid::new_v4(), Uuid::new_v4());
+        PutPluginSecret { repo: &repo }
+            .execute(user, "github", "token", "ghp_secret")
+            .await
+            .unwrap();
+
+        let permitted = HashSet::from([PERMISSION_SECRETS.to_string()]);
+        let secrets = secrets_for_invocation(&repo, user, "github", &permitted)
+            .await
+            .unwrap();
+        assert_eq!(secrets.get("token").map(String::as_str), Some("ghp_secret"));
+
+        let unpermitted = HashSet::from(["doc.read".to_string()]);
+        let secrets = secrets_for_invocation(&repo, user, "github", &unpermitted)
+            .await
+            .unwrap();
+        assert!(secrets.is_empty());
+
+        // Secrets are per user.
+        let secrets = secrets_for_invocation(&repo, other, "github", &permitted)
+            .await
+            .unwrap();
+        assert!(secrets.is_empty());
+    }
+}
@@ -0,0 +1,345 @@
+use uuid::Uuid;
+
+use crate::application::ports::plugin_asset_store::PluginAssetStore;
+use crate::application::ports::plugin_event_publisher::{PluginEventPublisher, PluginScopedEvent};
+use crate::application::ports::plugin_installation_repository::{
+    PluginInstallation, PluginInstallationRepository,
+};
+use crate::application::ports::plugin_installer::{PluginInstallError, PluginInstaller};
+use crate::application::ports::plugin_package_fetcher::PluginPackageFetcher;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PluginVersionError {
+    #[error("plugin is not installed")]
+    NotInstalled,
+    #[error("plugin version is not installed")]
+    VersionNotFound,
+    #[error("plugin installation has no origin url")]
+    MissingOrigin,
+    #[error("package contains plugin {0}")]
+    PluginMismatch(String),
+    #[error("failed to download plugin package")]
+    Download(#[source] anyhow::Error),
+    #[error("failed to install plugin package")]
+    Install(#[source] PluginInstallError),
+    #[error("failed to read installed plugin versions")]
+    Storage(#[source] anyhow::Error),
+    #[error("failed to persist plugin installation")]
+    Persist(#[source] anyhow::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct ActivePluginVersion {
+    pub id: String,
+    pub version: String,
+    pub pinned: bool,
+}
+
+async fn find_installation<R>(
+    installations: &R,
+    user_id: Uuid,
+    plugin_id: &str,
+) -> Result<PluginInstallation, PluginVersionError>
+where
+    R: PluginInstallationRepository + ?Sized,
+{
+    installations
+        .list_for_user(user_id)
+        .await
+        .map_err(PluginVersionError::Persist)?
+        .into_iter()
+        .find(|inst| inst.plugin_id == plugin_id)
+        .ok_or(PluginVersionError::NotInstalled)
+}
+
+async fn publish_updated<E>(events: &E, user_id: Uuid, active: &ActivePluginVersion)
+where
+    E: PluginEventPublisher + ?Sized,
+{
+    let event = PluginScopedEvent {
+        user_id: Some(user_id),
+        payload: serde_json::json!({
+            "event": "updated",
+            "id": active.id,
+            "version": active.version,
+            "pinned": active.pinned,
+        }),
+    };
+    // The switch already happened; a lost event only delays other nodes until
+    // their next refresh.
+    if let Err(err) = events.publish(&event).await {
+        tracing::warn!(error = ?err, plugin = active.id.as_str(), "plugin_version_event_failed");
+    }
+}
+
+pub struct PinPluginVersion<'a, A, E, R>
+where
+    A: PluginAssetStore + ?Sized,
+    E: PluginEventPublisher + ?Sized,
+    R: PluginInstallationRepository + ?Sized,
+{
+    pub assets: &'a A,
+    pub events: &'a E,
+    pub installations: &'a R,
+}
+
+impl<'a, A, E, R> PinPluginVersion<'a, A, E, R>
+where
+    A: PluginAssetStore + ?Sized,
+    E: PluginEventPublisher + ?Sized,
+    R: PluginInstallationRepository + ?Sized,
+{
+    /// Pins the plugin to `version`, or unpins it (following the newest
+    /// installed version) when `version` is `None`. The installation row is
+    /// what the runtime reads, so the switch is the single row update.
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        plugin_id: &str,
+        version: Option<&str>,
+    ) -> Result<ActivePluginVersion, PluginVersionError> {
+        find_installation(self.installations, user_id, plugin_id).await?;
+
+        let active_version = match version {
+            Some(version) => {
+                let manifest = self
+                    .assets
+                    .load_user_manifest(&user_id, plugin_id, version)
+                    .await
+                    .map_err(PluginVersionError::Storage)?;
+                if manifest.is_none() {
+                    return Err(PluginVersionError::VersionNotFound);
+                }
+                version.to_string()
+            }
+            None => self
+                .assets
+                .latest_user_version(&user_id, plugin_id)
+                .await
+                .map_err(PluginVersionError::Storage)?
+                .ok_or(PluginVersionError::VersionNotFound)?,
+        };
+
+        let pinned = version.is_some();
+        let updated = self
+            .installations
+            .set_version(user_id, plugin_id, &active_version, pinned)
+            .await
+            .map_err(PluginVersionError::Persist)?;
+        if !updated {
+            return Err(PluginVersionError::NotInstalled);
+        }
+
+        let active = ActivePluginVersion {
+            id: plugin_id.to_string(),
+            version: active_version,
+            pinned,
+        };
+        publish_updated(self.events, user_id, &active).await;
+        Ok(active)
+    }
+}
+
+pub struct UpdatePluginFromUrl<'a, F, I, E, R>
+where
+    F: PluginPackageFetcher + ?Sized,
+    I: PluginInstaller + ?Sized,
+    E: PluginEventPublisher + ?Sized,
+    R: PluginInstallationRepository + ?Sized,
+{
+    pub fetcher: &'a F,
+    pub installer: &'a I,
+    pub events: &'a E,
+    pub installations: &'a R,
+}
+
+impl<'a, F, I, E, R> UpdatePluginFromUrl<'a, F, I, E, R>
+where
+    F: PluginPackageFetcher + ?Sized,
+    I: PluginInstaller + ?Sized,
+    E: PluginEventPublisher + ?Sized,
+    R: PluginInstallationRepository + ?Sized,
+{
+    /// Installs a new version of an installed plugin and makes it active. The
+    /// installation row moves to the new version only once the package is
+    /// unpacked, so the previous version keeps serving when any step fails.
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        plugin_id: &str,
+        url: Option<&str>,
+        token: Option<&str>,
+    ) -> Result<ActivePluginVersion, PluginVersionError> {
+        let previous = find_installation(self.installations, user_id, plugin_id).await?;
+        let url = url
+            .map(str::to_string)
+            .or(previous.origin_url)
+            .ok_or(PluginVersionError::MissingOrigin)?;
+
+        let bytes = self
+            .fetcher
+            .fetch(&url, token)
+            .await
+            .map_err(PluginVersionError::Download)?;
+
+        let active = self.install(user_id, plugin_id, &url, &bytes).await?;
+        publish_updated(self.events, user_id, &active).await;
+        Ok(active)
+    }
+
+    async fn install(
+        &self,
+        user_id: Uuid,
+        plugin_id: &str,
+        url: &str,
+        bytes: &[u8],
+    ) -> Result<ActivePluginVersion, PluginVersionError> {
+        let installed = self
+            .installer
+            .install_for_user(user_id, bytes)
+            .await
+            .map_err(PluginVersionError::Install)?;
+        if installed.id != plugin_id {
+            return Err(PluginVersionError::PluginMismatch(installed.id));
+        }
+        self.installations
+            .upsert(
+                user_id,
+                &installed.id,
+                &installed.version,
+                "user",
+                Some(url),
+                "enabled",
+            )
+            .await
+            .map_err(PluginVersionError::Persist)?;
+        Ok(ActivePluginVersion {
+            id: installed.id,
+            version: installed.version,
+            pinned: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::application::ports::plugin_installer::InstalledPlugin;
+    use crate::test_support::plugins::MemoryPluginInstallations;
+
+    /// Serves a package whose contents are `id@version`.
+    struct Package(&'static str);
+
+    #[async_trait]
+    impl PluginPackageFetcher for Package {
+        async fn fetch(&self, _: &str, _: Option<&str>) -> anyhow::Result<Vec<u8>> {
+            Ok(self.0.as_bytes().to_vec())
+        }
+    }
+
+    struct Installer;
+
+    #[async_trait]
+    impl PluginInstaller for Installer {
+        async fn install_for_user(
+            &self,
+            _: Uuid,
+            archive: &[u8],
+        ) -> Result<InstalledPlugin, PluginInstallError> {
+            let (id, version) = std::str::from_utf8(archive)
+                .unwrap()
+                .split_once('@')
+                .unwrap();
+            Ok(InstalledPlugin {
+                id: id.into(),
+                version: version.into(),
+            })
+        }
+        async fn install_global(&self, _: &[u8]) -> Result<InstalledPlugin, PluginInstallError> {
+            unimplemented!()
+        }
+        async fn uninstall_global(&self, _: &str) -> anyhow::Result<bool> {
+            unimplemented!()
+        }
+        async fn set_global_enabled(&self, _: &str, _: bool) -> anyhow::Result<bool> {
+            unimplemented!()
+        }
+    }
+
+    #[derive(Default)]
+    struct Events(Mutex<Vec<serde_json::Value>>);
+
+    #[async_trait]
+    impl PluginEventPublisher for Events {
+        async fn publish(&self, event: &PluginScopedEvent) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push(event.payload.clone());
+            Ok(())
+        }
+    }
+
+    async fn pinned_install(user_id: Uuid) -> MemoryPluginInstallations {
+        let installations = MemoryPluginInstallations::default();
+        installations
+            .upsert(
+                user_id,
+                "marp",
+                "1.0.0",
+                "user",
+                Some("https://example.com/marp.zip"),
+                "enabled",
+            )
+            .await
+            .unwrap();
+        installations
+            .set_version(user_id, "marp", "1.0.0", true)
+            .await
+            .unwrap();
+        installations
+    }
+
+    #[tokio::test]
+    async fn update_switches_the_installation_to_the_new_version() {
+        let user_id = Uuid::new_v4();
+        let installations = pinned_install(user_id).await;
+        let events = Events::default();
+        let uc = UpdatePluginFromUrl {
+            fetcher: &Package("marp@2.0.0"),
+            installer: &Installer,
+            events: &events,
+            installations: &installations,
+        };
+
+        let active = uc.execute(user_id, "marp", None, None).await.unwrap();
+        assert_eq!(active.version, "2.0.0");
+
+        let row = installations.get(user_id, "marp").unwrap();
+        assert_eq!(row.version, "2.0.0");
+        assert!(!row.pinned);
+        assert_eq!(events.0.lock().unwrap()[0]["version"], "2.0.0");
+    }
+
+    #[tokio::test]
+    async fn failed_update_leaves_the_previous_version_active() {
+        let user_id = Uuid::new_v4();
+        let installations = pinned_install(user_id).await;
+        let events = Events::default();
+        let uc = UpdatePluginFromUrl {
+            fetcher: &Package("mermaid@2.0.0"),
+            installer: &Installer,
+            events: &events,
+            installations: &installations,
+        };
+
+        let err = uc.execute(user_id, "marp", None, None).await.unwrap_err();
+        assert!(matches!(err, PluginVersionError::PluginMismatch(id) if id == "mermaid"));
+
+        let row = installations.get(user_id, "marp").unwrap();
+        assert_eq!(row.version, "1.0.0");
+        assert!(row.pinned);
+        assert!(events.0.lock().unwrap().is_empty());
+    }
+}
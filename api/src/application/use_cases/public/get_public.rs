@@ -26,6 +26,8 @@ impl<'a, R: PublicRepository + ?Sized> GetPublicByOwnerAndId<'a, R> {
                 created_at,
                 updated_at,
                 path,
+                icon: None,
+                color: None,
             }))
         } else {
             Ok(None)
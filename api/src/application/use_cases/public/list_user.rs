@@ -7,13 +7,18 @@ pub struct PublicDocumentSummaryDto {
     pub published_at: chrono::DateTime<chrono::Utc>,
 }
 
+const LIST_LIMIT: i64 = 200;
+
 pub struct ListUserPublic<'a, R: PublicRepository + ?Sized> {
     pub repo: &'a R,
 }
 
 impl<'a, R: PublicRepository + ?Sized> ListUserPublic<'a, R> {
     pub async fn execute(&self, owner_name: &str) -> anyhow::Result<Vec<PublicDocumentSummaryDto>> {
-        let rows = self.repo.list_user_public_documents(owner_name).await?;
+        let rows = self
+            .repo
+            .list_user_public_documents(owner_name, LIST_LIMIT)
+            .await?;
         Ok(rows
             .into_iter()
             .map(
@@ -2,4 +2,6 @@ pub mod get_public;
 pub mod get_status;
 pub mod list_user;
 pub mod publish;
+pub mod sitemap;
 pub mod unpublish;
+pub mod view_stats;
@@ -1,6 +1,7 @@
 use uuid::Uuid;
 
 use crate::application::ports::public_repository::PublicRepository;
+use crate::application::services::public_listing::PublicListingCache;
 #[derive(Debug, Clone)]
 pub struct PublishResponseDto {
     pub slug: String,
@@ -21,6 +22,7 @@ fn sanitize_title_local(name: &str) -> String {
 
 pub struct PublishDocument<'a, R: PublicRepository + ?Sized> {
     pub repo: &'a R,
+    pub listings: &'a PublicListingCache,
 }
 
 impl<'a, R: PublicRepository + ?Sized> PublishDocument<'a, R> {
@@ -48,6 +50,7 @@ impl<'a, R: PublicRepository + ?Sized> PublishDocument<'a, R> {
             i += 1;
         }
         self.repo.upsert_public_document(doc_id, &slug).await?;
+        self.listings.invalidate();
         let public_url = format!("/u/{}/{}", owner_name, doc_id);
         Ok(Some(PublishResponseDto { slug, public_url }))
     }
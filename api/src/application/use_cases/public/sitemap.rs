@@ -0,0 +1,227 @@
+use crate::application::ports::public_repository::PublicRepository;
+use crate::application::services::public_listing::{self, PublicListingCache};
+use crate::application::use_cases::public::list_user::PublicDocumentSummaryDto;
+
+const MAX_PER_PAGE: usize = 500;
+
+pub struct GetPublicSitemap<'a, R: PublicRepository + ?Sized> {
+    pub repo: &'a R,
+    pub listings: &'a PublicListingCache,
+}
+
+impl<'a, R: PublicRepository + ?Sized> GetPublicSitemap<'a, R> {
+    pub async fn execute(&self, owner_name: &str, base_url: &str) -> anyhow::Result<String> {
+        let rows = self.listings.published(self.repo, owner_name).await?;
+        Ok(public_listing::render_sitemap(base_url, owner_name, &rows))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PublicIndexPageDto {
+    pub items: Vec<PublicDocumentSummaryDto>,
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+}
+
+pub struct GetPublicIndex<'a, R: PublicRepository + ?Sized> {
+    pub repo: &'a R,
+    pub listings: &'a PublicListingCache,
+}
+
+impl<'a, R: PublicRepository + ?Sized> GetPublicIndex<'a, R> {
+    /// `page` is 1-based.
+    pub async fn execute(
+        &self,
+        owner_name: &str,
+        page: usize,
+        per_page: usize,
+    ) -> anyhow::Result<PublicIndexPageDto> {
+        let page = page.max(1);
+        let per_page = per_page.clamp(1, MAX_PER_PAGE);
+        let rows = self.listings.published(self.repo, owner_name).await?;
+        let items = rows
+            .iter()
+            .skip((page - 1) * per_page)
+            .take(per_page)
+            .map(
+                |(id, title, updated_at, published_at)| PublicDocumentSummaryDto {
+                    id: *id,
+                    title: title.clone(),
+                    updated_at: *updated_at,
+                    published_at: *published_at,
+                },
+            )
+            .collect();
+        Ok(PublicIndexPageDto {
+            items,
+            total: rows.len(),
+            page,
+            per_page,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::application::ports::public_repository::PublicDocumentRow;
+    use crate::application::use_cases::public::unpublish::UnpublishDocument;
+
+    /// One owner ("alice") whose documents are published when listed in `published`.
+    struct Repo {
+        owner_id: Uuid,
+        published: Mutex<Vec<PublicDocumentRow>>,
+    }
+
+    #[async_trait]
+    impl PublicRepository for Repo {
+        async fn ensure_ownership_and_owner_name(
+            &self,
+            _: Uuid,
+            _: Uuid,
+        ) -> anyhow::Result<Option<(String, String)>> {
+            unimplemented!()
+        }
+        async fn upsert_public_document(&self, _: Uuid, _: &str) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn slug_exists(&self, _: &str) -> anyhow::Result<bool> {
+            unimplemented!()
+        }
+        async fn is_owner_document(&self, _: Uuid, owner_id: Uuid) -> anyhow::Result<bool> {
+            Ok(owner_id == self.owner_id)
+        }
+        async fn delete_public_document(&self, doc_id: Uuid) -> anyhow::Result<bool> {
+            let mut published = self.published.lock().unwrap();
+            let before = published.len();
+            published.retain(|(id, ..)| *id != doc_id);
+            Ok(published.len() < before)
+        }
+        async fn get_publish_status(
+            &self,
+            _: Uuid,
+            _: Uuid,
+        ) -> anyhow::Result<Option<(String, String)>> {
+            unimplemented!()
+        }
+        async fn list_user_public_documents(
+            &self,
+            owner_name: &str,
+            limit: i64,
+        ) -> anyhow::Result<Vec<PublicDocumentRow>> {
+            if owner_name != "alice" {
+                return Ok(Vec::new());
+            }
+            let published = self.published.lock().unwrap();
+            Ok(published.iter().take(limit as usize).cloned().collect())
+        }
+        async fn get_public_meta_by_owner_and_id(
+            &self,
+            _: &str,
+            _: Uuid,
+        ) -> anyhow::Result<
+            Option<(
+                Uuid,
+                String,
+                Option<Uuid>,
+                String,
+                chrono::DateTime<Utc>,
+                chrono::DateTime<Utc>,
+                Option<String>,
+            )>,
+        > {
+            unimplemented!()
+        }
+        async fn public_exists_by_owner_and_id(&self, _: &str, _: Uuid) -> anyhow::Result<bool> {
+            unimplemented!()
+        }
+        async fn add_public_views(
+            &self,
+            _: &[(Uuid, chrono::NaiveDate, i64)],
+        ) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn public_view_stats(
+            &self,
+            _: Uuid,
+            _: chrono::NaiveDate,
+        ) -> anyhow::Result<(i64, i64)> {
+            unimplemented!()
+        }
+    }
+
+    fn repo(docs: &[Uuid]) -> Repo {
+        let now = Utc::now();
+        Repo {
+            owner_id: Uuid::new_v4(),
+            published: Mutex::new(
+                docs.iter()
+                    .map(|id| (*id, format!("doc {id}"), now, now))
+                    .collect(),
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn sitemap_lists_only_published_documents() {
+        let (kept, withdrawn) = (Uuid::new_v4(), Uuid::new_v4());
+        let repo = repo(&[kept, withdrawn]);
+        let listings = PublicListingCache::new(Duration::from_secs(300));
+        let sitemap = GetPublicSitemap {
+            repo: &repo,
+            listings: &listings,
+        };
+
+        let xml = sitemap
+            .execute("alice", "https://md.example.com/")
+            .await
+            .unwrap();
+        assert!(xml.contains(&format!(
+            "<loc>https://md.example.com/u/alice/{}</loc>",
+            kept
+        )));
+        assert!(xml.contains(&withdrawn.to_string()));
+        assert!(xml.contains("<lastmod>"));
+
+        let unpublished = UnpublishDocument {
+            repo: &repo,
+            listings: &listings,
+        }
+        .execute(repo.owner_id, withdrawn)
+        .await
+        .unwrap();
+        assert!(unpublished);
+
+        // The cached listing is dropped on unpublish, so the change shows up immediately.
+        let xml = sitemap
+            .execute("alice", "https://md.example.com")
+            .await
+            .unwrap();
+        assert!(xml.contains(&kept.to_string()));
+        assert!(!xml.contains(&withdrawn.to_string()));
+    }
+
+    #[tokio::test]
+    async fn index_is_paginated() {
+        let docs: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let repo = repo(&docs);
+        let listings = PublicListingCache::new(Duration::from_secs(300));
+        let index = GetPublicIndex {
+            repo: &repo,
+            listings: &listings,
+        };
+
+        let page = index.execute("alice", 2, 2).await.unwrap();
+        assert_eq!(page.total, 5);
+        let ids: Vec<Uuid> = page.items.iter().map(|d| d.id).collect();
+        assert_eq!(ids, docs[2..4]);
+    }
+}
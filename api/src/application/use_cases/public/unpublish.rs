@@ -1,9 +1,11 @@
 use uuid::Uuid;
 
 use crate::application::ports::public_repository::PublicRepository;
+use crate::application::services::public_listing::PublicListingCache;
 
 pub struct UnpublishDocument<'a, R: PublicRepository + ?Sized> {
     pub repo: &'a R,
+    pub listings: &'a PublicListingCache,
 }
 
 impl<'a, R: PublicRepository + ?Sized> UnpublishDocument<'a, R> {
@@ -11,6 +13,8 @@ impl<'a, R: PublicRepository + ?Sized> UnpublishDocument<'a, R> {
         if !self.repo.is_owner_document(doc_id, owner_id).await? {
             return Ok(false);
         }
-        self.repo.delete_public_document(doc_id).await
+        let removed = self.repo.delete_public_document(doc_id).await?;
+        self.listings.invalidate();
+        Ok(removed)
     }
 }
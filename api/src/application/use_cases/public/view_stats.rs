@@ -0,0 +1,39 @@
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::application::ports::public_repository::PublicRepository;
+use crate::application::services::public_views::{PublicViewCounter, RECENT_DAYS};
+
+#[derive(Debug, Clone)]
+pub struct PublicViewStatsDto {
+    pub total_views: i64,
+    pub recent_views: i64,
+    pub recent_days: i64,
+}
+
+pub struct GetPublicViewStats<'a, R: PublicRepository + ?Sized> {
+    pub repo: &'a R,
+    pub views: &'a PublicViewCounter,
+}
+
+impl<'a, R: PublicRepository + ?Sized> GetPublicViewStats<'a, R> {
+    /// Stored counts plus views not yet flushed; `None` unless `owner_id` owns the document.
+    pub async fn execute(
+        &self,
+        owner_id: Uuid,
+        doc_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Option<PublicViewStatsDto>> {
+        if !self.repo.is_owner_document(doc_id, owner_id).await? {
+            return Ok(None);
+        }
+        let since = (now - Duration::days(RECENT_DAYS - 1)).date_naive();
+        let (stored_total, stored_recent) = self.repo.public_view_stats(doc_id, since).await?;
+        let (pending_total, pending_recent) = self.views.pending(doc_id, since);
+        Ok(Some(PublicViewStatsDto {
+            total_views: stored_total + pending_total,
+            recent_views: stored_recent + pending_recent,
+            recent_days: RECENT_DAYS,
+        }))
+    }
+}
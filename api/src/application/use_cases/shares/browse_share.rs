@@ -12,10 +12,10 @@ impl<'a, R: SharesRepository + ?Sized> BrowseShare<'a, R> {
             Some(r) => r,
             None => return Ok(None),
         };
-        if let Some(exp) = expires_at {
-            if exp < chrono::Utc::now() {
-                return Ok(None);
-            }
+        if let Some(exp) = expires_at
+            && exp < chrono::Utc::now()
+        {
+            return Ok(None);
         }
         // If token targets a document (not folder), return single node
         if shared_type != "folder" {
@@ -0,0 +1,16 @@
+use crate::application::ports::shares_repository::SharesRepository;
+
+/// Deletes share links past their expiry so they stop piling up on documents.
+pub struct CleanupExpiredShares<'a, R: SharesRepository + ?Sized> {
+    pub repo: &'a R,
+}
+
+impl<'a, R: SharesRepository + ?Sized> CleanupExpiredShares<'a, R> {
+    pub async fn execute(&self, now: chrono::DateTime<chrono::Utc>) -> anyhow::Result<u64> {
+        let removed = self.repo.delete_expired_shares(now).await?;
+        if removed > 0 {
+            tracing::info!(removed, "expired_shares_pruned");
+        }
+        Ok(removed)
+    }
+}
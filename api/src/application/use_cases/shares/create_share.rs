@@ -1,13 +1,49 @@
+use rand::Rng;
 use uuid::Uuid;
 
-use crate::application::ports::shares_repository::SharesRepository;
+use crate::application::ports::shares_repository::{
+    FolderShareGrant, NewShare, ShareLimitReached, SharesRepository,
+};
+use crate::application::use_cases::shares::list_document_shares::counts_towards_limit;
+use crate::application::use_cases::shares::materialize_folder_share::MaterializeFolderShare;
+
+const SHORT_CODE_ALPHABET: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+/// 12 base62 characters carry about 71 bits, too many to enumerate against the API.
+const SHORT_CODE_LEN: usize = 12;
+/// Most documents one bulk request may share.
+pub const MAX_BULK_SHARES: usize = 100;
+
+/// Random alias for a share token. It never contains `-`, so it can't be mistaken for a full
+/// (UUID) token.
+pub fn generate_short_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..SHORT_CODE_LEN)
+        .map(|_| SHORT_CODE_ALPHABET[rng.gen_range(0..SHORT_CODE_ALPHABET.len())] as char)
+        .collect()
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CreateShareError {
+    #[error("document already has {0} active share links")]
+    LimitReached(usize),
+    #[error("no documents to share")]
+    EmptyBatch,
+    #[error("too many documents to share at once")]
+    BatchTooLarge,
+    #[error(transparent)]
+    Repository(#[from] anyhow::Error),
+}
 
 pub struct CreateShare<'a, R: SharesRepository + ?Sized> {
     pub repo: &'a R,
+    /// Most unexpired share links per document; 0 means no limit.
+    pub max_per_document: usize,
 }
 
 pub struct CreateShareResult {
     pub token: String,
+    pub short_code: Option<String>,
     pub document_id: Uuid,
     pub document_type: String,
 }
@@ -19,15 +55,324 @@ impl<'a, R: SharesRepository + ?Sized> CreateShare<'a, R> {
         document_id: Uuid,
         permission: &str,
         expires_at: Option<chrono::DateTime<chrono::Utc>>,
-    ) -> anyhow::Result<CreateShareResult> {
-        let (token, _share_id, dtype) = self
+        with_short_code: bool,
+    ) -> Result<CreateShareResult, CreateShareError> {
+        self.ensure_below_limit(owner_id, document_id).await?;
+        let short_code = with_short_code.then(generate_short_code);
+        let (token, share_id, dtype) = self
             .repo
-            .create_share(owner_id, document_id, permission, expires_at)
+            .create_share(
+                owner_id,
+                document_id,
+                permission,
+                expires_at,
+                short_code.as_deref(),
+            )
             .await?;
+        if dtype == "folder" {
+            self.materialize(owner_id, share_id, document_id, permission, expires_at)
+                .await?;
+        }
         Ok(CreateShareResult {
             token,
+            short_code,
             document_id,
             document_type: dtype,
         })
     }
+
+    /// Shares every document in `document_ids` with the same settings, all or nothing: the
+    /// limit checks, the shares and the materialization of folder shares happen in one
+    /// repository transaction. Duplicate ids are shared once; results follow the order of
+    /// first appearance.
+    pub async fn execute_bulk(
+        &self,
+        owner_id: Uuid,
+        document_ids: &[Uuid],
+        permission: &str,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        with_short_code: bool,
+    ) -> Result<Vec<CreateShareResult>, CreateShareError> {
+        let mut ids: Vec<Uuid> = Vec::with_capacity(document_ids.len());
+        for id in document_ids {
+            if !ids.contains(id) {
+                ids.push(*id);
+            }
+        }
+        if ids.is_empty() {
+            return Err(CreateShareError::EmptyBatch);
+        }
+        if ids.len() > MAX_BULK_SHARES {
+            return Err(CreateShareError::BatchTooLarge);
+        }
+        let batch: Vec<NewShare> = ids
+            .iter()
+            .map(|id| NewShare {
+                document_id: *id,
+                short_code: with_short_code.then(generate_short_code),
+            })
+            .collect();
+        let created = self
+            .repo
+            .create_shares(
+                owner_id,
+                &batch,
+                permission,
+                expires_at,
+                self.max_per_document,
+            )
+            .await
+            .map_err(|e| match e.downcast_ref::<ShareLimitReached>() {
+                Some(ShareLimitReached(max)) => CreateShareError::LimitReached(*max),
+                None => CreateShareError::Repository(e),
+            })?;
+        Ok(batch
+            .into_iter()
+            .zip(created)
+            .map(|(share, (token, _, dtype))| CreateShareResult {
+                token,
+                short_code: share.short_code,
+                document_id: share.document_id,
+                document_type: dtype,
+            })
+            .collect())
+    }
+
+    async fn ensure_below_limit(
+        &self,
+        owner_id: Uuid,
+        document_id: Uuid,
+    ) -> Result<(), CreateShareError> {
+        if self.max_per_document == 0 {
+            return Ok(());
+        }
+        let now = chrono::Utc::now();
+        let active = self
+            .repo
+            .list_document_shares(owner_id, document_id)
+            .await?
+            .iter()
+            .filter(|r| counts_towards_limit(r, now))
+            .count();
+        if active >= self.max_per_document {
+            return Err(CreateShareError::LimitReached(self.max_per_document));
+        }
+        Ok(())
+    }
+
+    async fn materialize(
+        &self,
+        owner_id: Uuid,
+        share_id: Uuid,
+        folder_id: Uuid,
+        permission: &str,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> anyhow::Result<()> {
+        let grant = FolderShareGrant {
+            share_id,
+            folder_id,
+            permission: permission.to_string(),
+            expires_at,
+            created_by: owner_id,
+        };
+        MaterializeFolderShare { repo: self.repo }
+            .execute(&grant)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::application::use_cases::shares::cleanup_expired::CleanupExpiredShares;
+    use crate::application::use_cases::shares::list_document_shares::ListDocumentShares;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::shares::MemoryShares;
+
+    struct Fixture {
+        owner: Uuid,
+        docs: Arc<MemoryDocuments>,
+        shares: MemoryShares,
+        /// A document somebody else owns.
+        foreign_doc: Uuid,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let docs = Arc::new(MemoryDocuments::default());
+            let foreign_doc = docs.add(Uuid::new_v4(), "Theirs", "document", None).id;
+            Self {
+                owner: Uuid::new_v4(),
+                shares: MemoryShares::new(docs.clone()),
+                docs,
+                foreign_doc,
+            }
+        }
+
+        fn doc(&self) -> Uuid {
+            self.docs.add(self.owner, "Notes", "document", None).id
+        }
+    }
+
+    #[tokio::test]
+    async fn creating_past_the_limit_is_rejected() {
+        let f = Fixture::new();
+        let repo = &f.shares;
+        let doc_id = f.doc();
+        let uc = CreateShare {
+            repo,
+            max_per_document: 2,
+        };
+        let past = chrono::Utc::now() - chrono::Duration::hours(1);
+        repo.create_share(f.owner, doc_id, "view", Some(past), None)
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            uc.execute(f.owner, doc_id, "view", None, false)
+                .await
+                .unwrap();
+        }
+        assert!(matches!(
+            uc.execute(f.owner, doc_id, "view", None, false).await,
+            Err(CreateShareError::LimitReached(2))
+        ));
+        // Other documents have their own budget.
+        uc.execute(f.owner, f.doc(), "view", None, false)
+            .await
+            .unwrap();
+
+        let listed = ListDocumentShares { repo }
+            .execute(f.owner, doc_id)
+            .await
+            .unwrap();
+        assert_eq!(listed.items.len(), 3);
+        assert_eq!(listed.active_count, 2);
+    }
+
+    #[tokio::test]
+    async fn expired_shares_are_pruned() {
+        let f = Fixture::new();
+        let repo = &f.shares;
+        let doc_id = f.doc();
+        let now = chrono::Utc::now();
+        for expires_at in [
+            Some(now - chrono::Duration::minutes(5)),
+            Some(now + chrono::Duration::days(1)),
+            None,
+        ] {
+            repo.create_share(f.owner, doc_id, "view", expires_at, None)
+                .await
+                .unwrap();
+        }
+
+        let removed = CleanupExpiredShares { repo }.execute(now).await.unwrap();
+        assert_eq!(removed, 1);
+        let left = repo.list_document_shares(f.owner, doc_id).await.unwrap();
+        assert_eq!(left.len(), 2);
+        assert!(
+            left.iter()
+                .all(|r| r.expires_at.is_none_or(|exp| exp > now))
+        );
+    }
+
+    #[tokio::test]
+    async fn short_code_and_token_resolve_to_the_same_share() {
+        let f = Fixture::new();
+        let repo = &f.shares;
+        let doc_id = f.doc();
+        let created = CreateShare {
+            repo,
+            max_per_document: 0,
+        }
+        .execute(f.owner, doc_id, "edit", None, true)
+        .await
+        .unwrap();
+        let code = created.short_code.expect("short code requested");
+        assert_eq!(code.len(), SHORT_CODE_LEN);
+        assert!(code.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(code, created.token);
+
+        let by_token = repo.resolve_share_by_token(&created.token).await.unwrap();
+        let by_code = repo.resolve_share_by_token(&code).await.unwrap();
+        assert!(by_token.is_some());
+        assert_eq!(by_token, by_code);
+        assert_eq!(by_code.unwrap().3, doc_id);
+        assert_ne!(generate_short_code(), generate_short_code());
+    }
+
+    #[tokio::test]
+    async fn bulk_creates_one_share_per_document() {
+        let f = Fixture::new();
+        let repo = &f.shares;
+        let (a, b) = (f.doc(), f.doc());
+        let created = CreateShare {
+            repo,
+            max_per_document: 5,
+        }
+        .execute_bulk(f.owner, &[a, b, a], "edit", None, true)
+        .await
+        .unwrap();
+
+        let docs: Vec<Uuid> = created.iter().map(|c| c.document_id).collect();
+        assert_eq!(docs, vec![a, b]);
+        assert!(created.iter().all(|c| c.short_code.is_some()));
+        let rows = repo.rows.lock().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|r| r.permission == "edit"));
+        assert!(
+            created
+                .iter()
+                .all(|c| rows.iter().any(|r| r.token == c.token))
+        );
+    }
+
+    #[tokio::test]
+    async fn unauthorized_document_fails_the_whole_batch() {
+        let f = Fixture::new();
+        let repo = &f.shares;
+        let uc = CreateShare {
+            repo,
+            max_per_document: 0,
+        };
+        let ids = [f.doc(), f.foreign_doc, f.doc()];
+        let err = uc
+            .execute_bulk(f.owner, &ids, "view", None, false)
+            .await
+            .err()
+            .expect("batch rejected");
+        assert!(matches!(err, CreateShareError::Repository(e) if e.to_string() == "forbidden"));
+        assert!(repo.rows.lock().unwrap().is_empty());
+
+        assert!(matches!(
+            uc.execute_bulk(f.owner, &[], "view", None, false).await,
+            Err(CreateShareError::EmptyBatch)
+        ));
+    }
+
+    #[tokio::test]
+    async fn document_at_its_limit_fails_the_whole_batch() {
+        let f = Fixture::new();
+        let repo = &f.shares;
+        let (free, full) = (f.doc(), f.doc());
+        repo.create_share(f.owner, full, "view", None, None)
+            .await
+            .unwrap();
+        let err = CreateShare {
+            repo,
+            max_per_document: 1,
+        }
+        .execute_bulk(f.owner, &[free, full], "view", None, false)
+        .await
+        .err()
+        .expect("batch rejected");
+
+        assert!(matches!(err, CreateShareError::LimitReached(1)));
+        let rows = repo.rows.lock().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].document_id, full);
+    }
 }
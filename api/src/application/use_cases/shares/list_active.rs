@@ -15,6 +15,7 @@ impl<'a, R: SharesRepository + ?Sized> ListActiveShares<'a, R> {
             items.push(ActiveShareItemDto {
                 id: r.id,
                 token: r.token,
+                short_code: r.short_code,
                 permission: r.permission,
                 expires_at: r.expires_at,
                 created_at: r.created_at,
@@ -26,10 +26,10 @@ impl<'a, R: SharesRepository + ?Sized> ListApplicableShares<'a, R> {
             .await?;
         let mut out = Vec::new();
         for (token, permission, expires_at) in rows.into_iter() {
-            if let Some(exp) = expires_at {
-                if exp < chrono::Utc::now() {
-                    continue;
-                }
+            if let Some(exp) = expires_at
+                && exp < chrono::Utc::now()
+            {
+                continue;
             }
             out.push(ApplicableShareDto {
                 token,
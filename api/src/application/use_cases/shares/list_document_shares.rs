@@ -1,11 +1,12 @@
 use uuid::Uuid;
 
-use crate::application::ports::shares_repository::SharesRepository;
+use crate::application::ports::shares_repository::{ShareRow, SharesRepository};
 
 #[derive(Debug, Clone)]
 pub struct ShareItemDto {
     pub id: Uuid,
     pub token: String,
+    pub short_code: Option<String>,
     pub permission: String,
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
     pub document_id: Uuid,
@@ -14,6 +15,17 @@ pub struct ShareItemDto {
     pub parent_share_id: Option<Uuid>,
 }
 
+pub struct DocumentShares {
+    pub items: Vec<ShareItemDto>,
+    /// Unexpired shares created on the document itself, the ones the per-document limit counts.
+    pub active_count: usize,
+}
+
+/// Whether `row` counts towards the document's share limit at `now`.
+pub(crate) fn counts_towards_limit(row: &ShareRow, now: chrono::DateTime<chrono::Utc>) -> bool {
+    row.parent_share_id.is_none() && row.expires_at.is_none_or(|exp| exp > now)
+}
+
 pub struct ListDocumentShares<'a, R: SharesRepository + ?Sized> {
     pub repo: &'a R,
 }
@@ -23,16 +35,19 @@ impl<'a, R: SharesRepository + ?Sized> ListDocumentShares<'a, R> {
         &self,
         owner_id: Uuid,
         document_id: Uuid,
-    ) -> anyhow::Result<Vec<ShareItemDto>> {
+    ) -> anyhow::Result<DocumentShares> {
         let rows = self
             .repo
             .list_document_shares(owner_id, document_id)
             .await?;
-        Ok(rows
+        let now = chrono::Utc::now();
+        let active_count = rows.iter().filter(|r| counts_towards_limit(r, now)).count();
+        let items = rows
             .into_iter()
             .map(|r| ShareItemDto {
                 id: r.id,
                 token: r.token.clone(),
+                short_code: r.short_code,
                 permission: r.permission,
                 expires_at: r.expires_at,
                 document_id: r.document_id,
@@ -40,6 +55,10 @@ impl<'a, R: SharesRepository + ?Sized> ListDocumentShares<'a, R> {
                 document_title: r.document_title,
                 parent_share_id: r.parent_share_id,
             })
-            .collect())
+            .collect();
+        Ok(DocumentShares {
+            items,
+            active_count,
+        })
     }
 }
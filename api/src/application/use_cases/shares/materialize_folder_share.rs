@@ -0,0 +1,164 @@
+use uuid::Uuid;
+
+use crate::application::ports::shares_repository::{FolderShareGrant, SharesRepository};
+
+/// Materializes a folder share onto every descendant (documents and subfolders, at any depth).
+pub struct MaterializeFolderShare<'a, R: SharesRepository + ?Sized> {
+    pub repo: &'a R,
+}
+
+impl<'a, R: SharesRepository + ?Sized> MaterializeFolderShare<'a, R> {
+    pub async fn execute(&self, grant: &FolderShareGrant) -> anyhow::Result<i64> {
+        let nodes = self.repo.list_subtree_nodes(grant.folder_id).await?;
+        let targets: Vec<Uuid> = nodes
+            .into_iter()
+            .map(|(id, ..)| id)
+            .filter(|id| *id != grant.folder_id)
+            .collect();
+        self.repo.insert_materialized_shares(grant, &targets).await
+    }
+
+    pub async fn execute_for_token(&self, owner_id: Uuid, token: &str) -> anyhow::Result<i64> {
+        let grant = self
+            .repo
+            .get_folder_share_for_owner(owner_id, token)
+            .await?;
+        self.execute(&grant).await
+    }
+}
+
+/// Extends folder shares on a document's ancestors to the document (and its subtree, after a
+/// move), so items added to a shared folder are reachable through the folder's token.
+pub struct InheritFolderShares<'a, R: SharesRepository + ?Sized> {
+    pub repo: &'a R,
+}
+
+impl<'a, R: SharesRepository + ?Sized> InheritFolderShares<'a, R> {
+    pub async fn execute(&self, doc_id: Uuid) -> anyhow::Result<i64> {
+        let grants = self.repo.list_ancestor_folder_shares(doc_id).await?;
+        if grants.is_empty() {
+            return Ok(0);
+        }
+        let targets: Vec<Uuid> = self
+            .repo
+            .list_subtree_nodes(doc_id)
+            .await?
+            .into_iter()
+            .map(|(id, ..)| id)
+            .collect();
+        let mut created = 0;
+        for grant in &grants {
+            created += self
+                .repo
+                .insert_materialized_shares(grant, &targets)
+                .await?;
+        }
+        Ok(created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::application::access::{self, AccessPolicy, Actor, Capability};
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::shares::MemoryShares;
+
+    struct Tree {
+        owner: Uuid,
+        docs: Arc<MemoryDocuments>,
+        shares: MemoryShares,
+    }
+
+    impl Tree {
+        fn new() -> Self {
+            let docs = Arc::new(MemoryDocuments::default());
+            Self {
+                owner: Uuid::new_v4(),
+                shares: MemoryShares::new(docs.clone()),
+                docs,
+            }
+        }
+
+        fn add(&self, typ: &str, parent: Option<Uuid>) -> Uuid {
+            self.docs.add(self.owner, "", typ, parent).id
+        }
+
+        async fn share(&self, folder_id: Uuid, permission: &str) -> (String, FolderShareGrant) {
+            let (token, _, _) = self
+                .shares
+                .create_share(self.owner, folder_id, permission, None, None)
+                .await
+                .unwrap();
+            let grant = self
+                .shares
+                .get_folder_share_for_owner(self.owner, &token)
+                .await
+                .unwrap();
+            (token, grant)
+        }
+    }
+
+    async fn capability(tree: &Tree, token: &str, doc_id: Uuid) -> Capability {
+        // Edit-level shares are asserted as such, so let anonymous actors keep them.
+        let policy = AccessPolicy {
+            allow_anonymous_edit: true,
+        };
+        let actor = Actor::ShareToken(token.to_string());
+        access::resolve_document(&*tree.docs, &tree.shares, policy, &actor, doc_id).await
+    }
+
+    #[tokio::test]
+    async fn folder_share_reaches_every_nested_descendant() {
+        let tree = Tree::new();
+        let root = tree.add("folder", None);
+        let top_doc = tree.add("document", Some(root));
+        let level1 = tree.add("folder", Some(root));
+        let level1_doc = tree.add("document", Some(level1));
+        let level2 = tree.add("folder", Some(level1));
+        let level2_doc = tree.add("document", Some(level2));
+        let outside = tree.add("document", None);
+        let (token, _) = tree.share(root, "edit").await;
+
+        let uc = MaterializeFolderShare { repo: &tree.shares };
+        assert_eq!(uc.execute_for_token(tree.owner, &token).await.unwrap(), 5);
+        // Idempotent: nothing new on a second run
+        assert_eq!(uc.execute_for_token(tree.owner, &token).await.unwrap(), 0);
+
+        for id in [top_doc, level1, level1_doc, level2, level2_doc] {
+            assert_eq!(capability(&tree, &token, id).await, Capability::Edit);
+        }
+        assert_eq!(capability(&tree, &token, outside).await, Capability::None);
+    }
+
+    #[tokio::test]
+    async fn later_added_descendants_inherit_the_share() {
+        let tree = Tree::new();
+        let root = tree.add("folder", None);
+        let level1 = tree.add("folder", Some(root));
+        let level2 = tree.add("folder", Some(level1));
+        let (token, grant) = tree.share(root, "view").await;
+        MaterializeFolderShare { repo: &tree.shares }
+            .execute(&grant)
+            .await
+            .unwrap();
+
+        let late_doc = tree.add("document", Some(level2));
+        assert_eq!(capability(&tree, &token, late_doc).await, Capability::None);
+        let inherit = InheritFolderShares { repo: &tree.shares };
+        assert_eq!(inherit.execute(late_doc).await.unwrap(), 1);
+        assert_eq!(capability(&tree, &token, late_doc).await, Capability::View);
+
+        // A folder moved in with its own subtree brings its children along.
+        let moved = tree.add("folder", None);
+        let moved_child = tree.add("document", Some(moved));
+        tree.docs.update(moved, |d| d.doc.parent_id = Some(level1));
+        assert_eq!(inherit.execute(moved).await.unwrap(), 2);
+        assert_eq!(
+            capability(&tree, &token, moved_child).await,
+            Capability::View
+        );
+    }
+}
@@ -1,7 +1,9 @@
 pub mod browse_share;
+pub mod cleanup_expired;
 pub mod create_share;
 pub mod delete_share;
 pub mod list_active;
 pub mod list_applicable;
 pub mod list_document_shares;
+pub mod materialize_folder_share;
 pub mod validate_share;
@@ -10,10 +10,10 @@ impl<'a, R: SharesRepository + ?Sized> ValidateShare<'a, R> {
         if let Some((document_id, permission, expires_at, title)) =
             self.repo.validate_share_token(token).await?
         {
-            if let Some(exp) = expires_at {
-                if exp < chrono::Utc::now() {
-                    return Ok(None);
-                }
+            if let Some(exp) = expires_at
+                && exp < chrono::Utc::now()
+            {
+                return Ok(None);
             }
             Ok(Some(ShareDocumentDto {
                 id: document_id,
@@ -2,9 +2,12 @@ use uuid::Uuid;
 
 use crate::application::dto::tags::TagItemDto;
 use crate::application::ports::tag_repository::TagRepository;
+use crate::application::services::tagging::is_hidden_tag;
 
 pub struct ListTags<'a, R: TagRepository + ?Sized> {
     pub repo: &'a R,
+    /// Configured hidden tags; `_`-prefixed tags are always hidden.
+    pub hidden_tags: &'a [String],
 }
 
 impl<'a, R: TagRepository + ?Sized> ListTags<'a, R> {
@@ -12,11 +15,69 @@ impl<'a, R: TagRepository + ?Sized> ListTags<'a, R> {
         &self,
         owner_id: Uuid,
         filter: Option<String>,
+        include_hidden: bool,
     ) -> anyhow::Result<Vec<TagItemDto>> {
         let rows = self.repo.list_tags(owner_id, filter).await?;
         Ok(rows
             .into_iter()
+            .filter(|(name, _)| include_hidden || !is_hidden_tag(name, self.hidden_tags))
             .map(|(name, count)| TagItemDto { name, count })
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+
+    struct Tags(Vec<(String, i64)>);
+
+    #[async_trait]
+    impl TagRepository for Tags {
+        async fn list_tags(
+            &self,
+            _: Uuid,
+            filter: Option<String>,
+        ) -> anyhow::Result<Vec<(String, i64)>> {
+            let filter = filter.unwrap_or_default();
+            Ok(self
+                .0
+                .iter()
+                .filter(|(name, _)| name.contains(&filter))
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn names(items: Vec<TagItemDto>) -> Vec<String> {
+        items.into_iter().map(|t| t.name).collect()
+    }
+
+    #[tokio::test]
+    async fn hidden_tags_are_listed_only_when_asked_for() {
+        let repo = Tags(vec![
+            ("rust".into(), 4),
+            ("todo".into(), 3),
+            ("_draft".into(), 2),
+        ]);
+        let hidden = vec!["todo".to_string()];
+        let uc = ListTags {
+            repo: &repo,
+            hidden_tags: &hidden,
+        };
+        let owner = Uuid::new_v4();
+
+        let listed = uc.execute(owner, None, false).await.unwrap();
+        assert_eq!(names(listed), vec!["rust"]);
+
+        let drafts = uc.execute(owner, Some("_dr".into()), false).await.unwrap();
+        assert!(drafts.is_empty());
+        let drafts = uc.execute(owner, Some("_dr".into()), true).await.unwrap();
+        assert_eq!(names(drafts), vec!["_draft"]);
+
+        let all = uc.execute(owner, None, true).await.unwrap();
+        assert_eq!(names(all), vec!["rust", "todo", "_draft"]);
+    }
+}
@@ -1,5 +1,8 @@
 use api::presentation::{
-    http::{auth, documents, files, git, health, markdown, plugins, public, shares, tags},
+    http::{
+        auth, capabilities, comments, documents, files, git, health, markdown, notifications,
+        plugins, public, shares, tags,
+    },
     ws,
 };
 use utoipa::OpenApi;
@@ -14,13 +17,43 @@ use utoipa::OpenApi;
         auth::delete_account,
         ws::axum_ws_entry,
         tags::list_tags,
+        capabilities::get_capabilities,
         documents::list_documents,
+        documents::list_recent_documents,
+        documents::list_mentions,
+        documents::get_home_document,
+        documents::set_home_document,
+        notifications::list_notifications,
+        notifications::mark_notification_read,
         documents::create_document,
         documents::get_document,
         documents::update_document,
         documents::delete_document,
+        documents::update_document_appearance,
+        documents::get_document_capability,
+        comments::list_document_comments,
+        comments::create_document_comment,
+        comments::resolve_document_comment,
+        comments::reopen_document_comment,
+        comments::delete_document_comment,
+        documents::lock_document,
+        documents::unlock_document,
         documents::get_document_content,
+        documents::update_document_content,
+        documents::get_document_presence,
         documents::download_document,
+        documents::export_document,
+        documents::get_document_retention,
+        documents::update_document_retention,
+        documents::list_document_user_access,
+        documents::grant_document_user_access,
+        documents::revoke_document_user_access,
+        documents::list_document_versions,
+        documents::get_document_version_content,
+        documents::diff_document_versions,
+        documents::render_document,
+        documents::render_document_tree,
+        documents::restore_document_version,
         documents::search_documents,
         documents::get_backlinks,
         documents::get_outgoing_links,
@@ -28,6 +61,7 @@ use utoipa::OpenApi;
         files::get_file,
         files::get_file_by_name,
         shares::create_share,
+        shares::create_shares_bulk,
         shares::delete_share,
         shares::list_document_shares,
         shares::validate_share_token,
@@ -39,26 +73,36 @@ use utoipa::OpenApi;
         public::unpublish_document,
         public::get_publish_status,
         public::list_user_public_documents,
+        public::get_user_public_sitemap,
+        public::get_user_public_index,
         public::get_public_by_owner_and_id,
         public::get_public_content_by_owner_and_id,
+        public::get_public_view_stats,
         git::get_config,
         git::create_or_update_config,
+        git::import_repository,
         git::delete_config,
         git::get_status,
         git::get_changes,
         git::get_history,
+        git::get_blame,
+        git::get_storage_usage,
+        git::collect_garbage,
         git::get_working_diff,
         git::get_commit_diff,
         git::sync_now,
+        git::test_connection,
         git::init_repository,
         git::deinit_repository,
         git::ignore_document,
         git::ignore_folder,
         git::get_gitignore_patterns,
         git::add_gitignore_patterns,
+        git::remove_gitignore_patterns,
         git::check_path_ignored,
         markdown::render_markdown,
         markdown::render_markdown_many,
+        markdown::list_renderers,
         plugins::get_manifest,
         plugins::exec_action,
         plugins::list_records,
@@ -67,8 +111,20 @@ use utoipa::OpenApi;
         plugins::delete_record,
         plugins::get_kv_value,
         plugins::put_kv_value,
+        plugins::export_data,
+        plugins::import_data,
         plugins::install_from_url,
         plugins::uninstall,
+        plugins::pin_version,
+        plugins::update_plugin,
+        plugins::list_schedules,
+        plugins::list_exec_log,
+        plugins::install_global_from_url,
+        plugins::uninstall_global,
+        plugins::list_secret_keys,
+        plugins::put_secret,
+        plugins::delete_secret,
+        plugins::set_global_enabled,
         plugins::sse_updates,
         health::health,
     ),
@@ -80,19 +136,52 @@ use utoipa::OpenApi;
         tags::TagItem,
         documents::Document,
         documents::DocumentListResponse,
+        documents::MentionItem,
+        documents::MentionListResponse,
+        documents::HomeDocumentResponse,
+        documents::SetHomeDocumentRequest,
+        capabilities::CapabilitiesResponse,
+        capabilities::StorageBackendKind,
+        comments::CommentItem,
+        comments::CreateCommentRequest,
+        notifications::NotificationItem,
+        notifications::NotificationListResponse,
         documents::CreateDocumentRequest,
         documents::UpdateDocumentRequest,
+        documents::UpdateDocumentAppearanceRequest,
+        documents::DocumentCapability,
+        documents::DocumentCapabilityResponse,
+        documents::UpdateDocumentRetentionRequest,
+        documents::DocumentRetentionResponse,
+        documents::DocumentUserAccessItem,
+        documents::GrantDocumentAccessRequest,
+        documents::DocumentVersionItem,
+        documents::DocumentVersionContentResponse,
+        documents::RenderDocumentRequest,
+        documents::RenderTreeRequest,
+        documents::RenderTreeDocument,
+        documents::RenderTreeResponse,
+        documents::DocumentContentResponse,
+        documents::UpdateDocumentContentRequest,
+        documents::PresenceClient,
+        documents::DocumentPresenceResponse,
         documents::SearchResult,
         documents::BacklinkInfo,
         documents::BacklinksResponse,
         documents::OutgoingLink,
         documents::OutgoingLinksResponse,
         documents::DocumentArchiveBinary,
+        documents::DocumentExportBinary,
         files::UploadFileResponse,
+        files::UploadRejectedResponse,
         files::UploadFileMultipart,
         shares::CreateShareRequest,
         shares::CreateShareResponse,
+        shares::BulkCreateShareRequest,
+        shares::BulkCreateShareResponse,
+        shares::BulkShareItem,
         shares::ShareItem,
+        shares::DocumentSharesResponse,
         shares::ShareDocumentResponse,
         shares::ShareBrowseTreeItem,
         shares::ShareBrowseResponse,
@@ -101,19 +190,31 @@ use utoipa::OpenApi;
         shares::MaterializeResponse,
         public::PublishResponse,
         public::PublicDocumentSummary,
+        public::PublicDocumentIndex,
+        public::PublicViewStats,
         git::GitConfigResponse,
+        git::GitImportResponse,
         git::CreateGitConfigRequest,
         git::UpdateGitConfigRequest,
         git::GitStatus,
         git::GitSyncRequest,
         git::GitSyncResponse,
+        git::GitConnectionTestResponse,
+        git::GitSyncPreview,
+        git::GitDiffStats,
         git::GitChangeItem,
         git::GitChangesResponse,
         git::GitCommitItem,
         git::GitHistoryResponse,
+        git::GitBlameLineItem,
+        git::GitBlameResponse,
+        git::GitStorageUsageResponse,
+        git::GitGcRequest,
+        git::GitGcResponse,
         git::GitDiffLineType,
         git::GitDiffLine,
         git::GitDiffResult,
+        git::GitWorkingDiffResponse,
         git::AddPatternsRequest,
         git::CheckIgnoredRequest,
         markdown::RenderOptionsPayload,
@@ -122,30 +223,52 @@ use utoipa::OpenApi;
         markdown::RenderRequest,
         markdown::RenderManyRequest,
         markdown::RenderManyResponse,
+        markdown::RendererSpecPayload,
+        markdown::RendererSpecsResponse,
         plugins::ManifestItem,
         plugins::RecordsResponse,
         plugins::CreateRecordBody,
         plugins::UpdateRecordBody,
         plugins::KvValueResponse,
         plugins::KvValueBody,
+        plugins::PluginRecordExport,
+        plugins::PluginKvExport,
+        plugins::PluginDataExportBody,
+        plugins::PluginDataImportBody,
+        plugins::PluginDataImportResponse,
         plugins::ExecBody,
         plugins::ExecResultResponse,
         plugins::InstallFromUrlBody,
         plugins::InstallResponse,
         plugins::UninstallBody,
+        plugins::PinVersionBody,
+        plugins::UpdatePluginBody,
+        plugins::ActiveVersionResponse,
+        plugins::GlobalPluginEnabledBody,
+        plugins::SecretPath,
+        plugins::SecretValueBody,
+        plugins::SecretKeysResponse,
+        plugins::PluginScheduleResponse,
+        plugins::PluginSchedulesResponse,
+        plugins::PluginExecLogItem,
+        plugins::PluginExecLogResponse,
         health::HealthResp,
+        health::DependencyStatus,
     )),
     tags(
         (name = "Auth", description = "Authentication"),
         (name = "Documents", description = "Documents management"),
+        (name = "Comments", description = "Document comment threads"),
         (name = "Files", description = "File management"),
         (name = "Sharing", description = "Document sharing"),
         (name = "Public Documents", description = "Public pages"),
         (name = "Realtime", description = "Yjs WebSocket endpoint (/yjs/:id)"),
         (name = "Git", description = "Git integration"),
         (name = "Markdown", description = "Markdown rendering"),
+        (name = "Notifications", description = "User notifications"),
         (name = "Plugins", description = "Plugins management & data APIs"),
-        (name = "Health", description = "System health checks")
+        (name = "Health", description = "System health checks"),
+        (name = "Server", description = "Server capabilities")
     )
 )]
 struct ApiDoc;
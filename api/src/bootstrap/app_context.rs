@@ -1,41 +1,67 @@
 use std::sync::Arc;
 
+use crate::application::access::AccessPolicy;
 use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::comment_repository::CommentRepository;
 use crate::application::ports::document_repository::DocumentRepository;
+use crate::application::ports::document_retention_repository::DocumentRetentionRepository;
+use crate::application::ports::document_user_access_repository::DocumentUserAccessRepository;
+use crate::application::ports::document_version_repository::DocumentVersionRepository;
 use crate::application::ports::files_repository::FilesRepository;
 use crate::application::ports::git_repository::GitRepository;
 use crate::application::ports::git_storage::GitStorage;
 use crate::application::ports::git_workspace::GitWorkspacePort;
 use crate::application::ports::gitignore_port::GitignorePort;
+use crate::application::ports::linkgraph_repository::LinkGraphRepository;
+use crate::application::ports::mention_repository::MentionRepository;
+use crate::application::ports::pdf_renderer::PdfRenderer;
 use crate::application::ports::plugin_asset_store::PluginAssetStore;
 use crate::application::ports::plugin_event_publisher::{PluginEventPublisher, PluginScopedEvent};
+use crate::application::ports::plugin_exec_log_repository::PluginExecLogRepository;
 use crate::application::ports::plugin_installation_repository::PluginInstallationRepository;
 use crate::application::ports::plugin_installer::PluginInstaller;
 use crate::application::ports::plugin_package_fetcher::PluginPackageFetcher;
 use crate::application::ports::plugin_repository::PluginRepository;
 use crate::application::ports::plugin_runtime::PluginRuntime;
+use crate::application::ports::plugin_secret_repository::PluginSecretRepository;
 use crate::application::ports::public_repository::PublicRepository;
 use crate::application::ports::realtime_port::RealtimeEngine;
-pub use crate::application::ports::realtime_types::{DynRealtimeSink, DynRealtimeStream};
+pub use crate::application::ports::realtime_types::{
+    DynRealtimeSink, DynRealtimeStream, RealtimeProtocol, RealtimeSession,
+};
 use crate::application::ports::share_access_port::ShareAccessPort;
 use crate::application::ports::shares_repository::SharesRepository;
 use crate::application::ports::storage_port::StoragePort;
 use crate::application::ports::tag_repository::TagRepository;
 use crate::application::ports::user_repository::UserRepository;
+use crate::application::services::notifications::NotificationService;
+use crate::application::services::public_listing::PublicListingCache;
+use crate::application::services::public_views::PublicViewCounter;
+use crate::application::services::rate_limit::{RequestRateLimits, ShareRateLimits};
+use crate::application::services::token_cache::TokenCache;
 use crate::bootstrap::config::Config;
 use futures_util::stream::BoxStream;
 
 use crate::infrastructure::plugins::event_bus_pg::PgPluginEventBus;
+use crate::infrastructure::plugins::event_replay::PluginEventReplay;
+
+const PUBLIC_LISTING_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+/// Repeat views of a public document by one client within this window count once.
+const PUBLIC_VIEW_DEBOUNCE: std::time::Duration = std::time::Duration::from_secs(30 * 60);
 
 #[derive(Clone)]
 pub struct AppContext {
     pub cfg: Config,
     services: Arc<AppServices>,
+    rate_limits: Arc<RequestRateLimits>,
+    token_cache: Arc<TokenCache>,
 }
 
 #[derive(Clone)]
 pub struct AppServices {
     document_repo: Arc<dyn DocumentRepository>,
+    linkgraph_repo: Arc<dyn LinkGraphRepository>,
+    mention_repo: Arc<dyn MentionRepository>,
     shares_repo: Arc<dyn SharesRepository>,
     share_access_port: Arc<dyn ShareAccessPort>,
     access_repo: Arc<dyn AccessRepository>,
@@ -51,18 +77,31 @@ pub struct AppServices {
     realtime_engine: Arc<dyn RealtimeEngine>,
     plugin_repo: Arc<dyn PluginRepository>,
     plugin_installations: Arc<dyn PluginInstallationRepository>,
+    plugin_secrets: Arc<dyn PluginSecretRepository>,
+    plugin_exec_log: Arc<dyn PluginExecLogRepository>,
     plugin_runtime: Arc<dyn PluginRuntime>,
     plugin_installer: Arc<dyn PluginInstaller>,
     plugin_fetcher: Arc<dyn PluginPackageFetcher>,
     plugin_event_bus: Arc<PgPluginEventBus>,
+    plugin_event_replay: Arc<PluginEventReplay>,
     plugin_event_publisher: Arc<dyn PluginEventPublisher>,
     plugin_assets: Arc<dyn PluginAssetStore>,
+    pdf_renderer: Arc<dyn PdfRenderer>,
+    document_retention_repo: Arc<dyn DocumentRetentionRepository>,
+    document_user_access_repo: Arc<dyn DocumentUserAccessRepository>,
+    document_version_repo: Arc<dyn DocumentVersionRepository>,
+    comment_repo: Arc<dyn CommentRepository>,
+    notifications: Arc<NotificationService>,
+    public_listings: Arc<PublicListingCache>,
+    public_views: Arc<PublicViewCounter>,
 }
 
 impl AppServices {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         document_repo: Arc<dyn DocumentRepository>,
+        linkgraph_repo: Arc<dyn LinkGraphRepository>,
+        mention_repo: Arc<dyn MentionRepository>,
         shares_repo: Arc<dyn SharesRepository>,
         share_access_port: Arc<dyn ShareAccessPort>,
         access_repo: Arc<dyn AccessRepository>,
@@ -78,15 +117,26 @@ impl AppServices {
         realtime_engine: Arc<dyn RealtimeEngine>,
         plugin_repo: Arc<dyn PluginRepository>,
         plugin_installations: Arc<dyn PluginInstallationRepository>,
+        plugin_secrets: Arc<dyn PluginSecretRepository>,
+        plugin_exec_log: Arc<dyn PluginExecLogRepository>,
         plugin_runtime: Arc<dyn PluginRuntime>,
         plugin_installer: Arc<dyn PluginInstaller>,
         plugin_fetcher: Arc<dyn PluginPackageFetcher>,
         plugin_event_bus: Arc<PgPluginEventBus>,
+        plugin_event_replay: Arc<PluginEventReplay>,
         plugin_event_publisher: Arc<dyn PluginEventPublisher>,
         plugin_assets: Arc<dyn PluginAssetStore>,
+        pdf_renderer: Arc<dyn PdfRenderer>,
+        document_retention_repo: Arc<dyn DocumentRetentionRepository>,
+        document_user_access_repo: Arc<dyn DocumentUserAccessRepository>,
+        document_version_repo: Arc<dyn DocumentVersionRepository>,
+        comment_repo: Arc<dyn CommentRepository>,
+        notifications: Arc<NotificationService>,
     ) -> Self {
         Self {
             document_repo,
+            linkgraph_repo,
+            mention_repo,
             shares_repo,
             share_access_port,
             access_repo,
@@ -102,21 +152,48 @@ impl AppServices {
             realtime_engine,
             plugin_repo,
             plugin_installations,
+            plugin_secrets,
+            plugin_exec_log,
             plugin_runtime,
             plugin_installer,
             plugin_fetcher,
             plugin_event_bus,
+            plugin_event_replay,
             plugin_event_publisher,
             plugin_assets,
+            pdf_renderer,
+            document_retention_repo,
+            document_user_access_repo,
+            document_version_repo,
+            comment_repo,
+            notifications,
+            public_listings: Arc::new(PublicListingCache::new(PUBLIC_LISTING_TTL)),
+            public_views: Arc::new(PublicViewCounter::new(PUBLIC_VIEW_DEBOUNCE)),
         }
     }
 }
 
 impl AppContext {
     pub fn new(cfg: Config, services: AppServices) -> Self {
+        let rate_limits = Arc::new(
+            RequestRateLimits::new(
+                cfg.rate_limit_per_minute,
+                cfg.rate_limit_authenticated_per_minute,
+            )
+            .with_share_limits(ShareRateLimits::new(
+                cfg.share_rate_limit_per_token_per_minute,
+                cfg.share_rate_limit_per_address_per_minute,
+            )),
+        );
+        let token_cache = Arc::new(TokenCache::new(
+            std::time::Duration::from_secs(cfg.auth_token_cache_ttl_secs),
+            cfg.auth_token_cache_capacity,
+        ));
         Self {
             cfg,
             services: Arc::new(services),
+            rate_limits,
+            token_cache,
         }
     }
 
@@ -124,6 +201,14 @@ impl AppContext {
         self.services.document_repo.clone()
     }
 
+    pub fn linkgraph_repo(&self) -> Arc<dyn LinkGraphRepository> {
+        self.services.linkgraph_repo.clone()
+    }
+
+    pub fn mention_repo(&self) -> Arc<dyn MentionRepository> {
+        self.services.mention_repo.clone()
+    }
+
     pub fn shares_repo(&self) -> Arc<dyn SharesRepository> {
         self.services.shares_repo.clone()
     }
@@ -136,6 +221,12 @@ impl AppContext {
         self.services.access_repo.clone()
     }
 
+    pub fn access_policy(&self) -> AccessPolicy {
+        AccessPolicy {
+            allow_anonymous_edit: self.cfg.allow_anonymous_edit,
+        }
+    }
+
     pub fn files_repo(&self) -> Arc<dyn FilesRepository> {
         self.services.files_repo.clone()
     }
@@ -184,6 +275,14 @@ impl AppContext {
         self.services.plugin_installations.clone()
     }
 
+    pub fn plugin_secrets(&self) -> Arc<dyn PluginSecretRepository> {
+        self.services.plugin_secrets.clone()
+    }
+
+    pub fn plugin_exec_log(&self) -> Arc<dyn PluginExecLogRepository> {
+        self.services.plugin_exec_log.clone()
+    }
+
     pub fn plugin_runtime(&self) -> Arc<dyn PluginRuntime> {
         self.services.plugin_runtime.clone()
     }
@@ -208,22 +307,66 @@ impl AppContext {
         self.services.plugin_assets.clone()
     }
 
+    pub fn pdf_renderer(&self) -> Arc<dyn PdfRenderer> {
+        self.services.pdf_renderer.clone()
+    }
+
+    pub fn document_retention_repo(&self) -> Arc<dyn DocumentRetentionRepository> {
+        self.services.document_retention_repo.clone()
+    }
+
+    pub fn document_user_access_repo(&self) -> Arc<dyn DocumentUserAccessRepository> {
+        self.services.document_user_access_repo.clone()
+    }
+
+    pub fn document_version_repo(&self) -> Arc<dyn DocumentVersionRepository> {
+        self.services.document_version_repo.clone()
+    }
+
+    pub fn comment_repo(&self) -> Arc<dyn CommentRepository> {
+        self.services.comment_repo.clone()
+    }
+
+    pub fn notifications(&self) -> Arc<NotificationService> {
+        self.services.notifications.clone()
+    }
+
+    pub fn public_listings(&self) -> Arc<PublicListingCache> {
+        self.services.public_listings.clone()
+    }
+
+    pub fn public_views(&self) -> Arc<PublicViewCounter> {
+        self.services.public_views.clone()
+    }
+
+    pub fn rate_limits(&self) -> Arc<RequestRateLimits> {
+        self.rate_limits.clone()
+    }
+
+    pub fn token_cache(&self) -> &TokenCache {
+        &self.token_cache
+    }
+
     pub async fn subscribe_plugin_events(
         &self,
     ) -> anyhow::Result<BoxStream<'static, PluginScopedEvent>> {
         self.services.plugin_event_bus.subscribe().await
     }
 
+    pub fn plugin_event_replay(&self) -> Arc<PluginEventReplay> {
+        self.services.plugin_event_replay.clone()
+    }
+
     pub async fn subscribe_realtime(
         &self,
         doc_id: &str,
         sink: DynRealtimeSink,
         stream: DynRealtimeStream,
-        can_edit: bool,
+        session: RealtimeSession,
     ) -> anyhow::Result<()> {
         self.services
             .realtime_engine
-            .subscribe(doc_id, sink, stream, can_edit)
+            .subscribe(doc_id, sink, stream, session)
             .await
     }
 }
@@ -1,17 +1,30 @@
 use std::env;
+use std::net::IpAddr;
 use std::str::FromStr;
 
+use crate::application::services::markdown::{RenderBudget, RenderOptions};
+use crate::application::services::uploads;
+
 fn env_var(keys: &[&str]) -> Option<String> {
     for key in keys {
-        if let Ok(value) = env::var(key) {
-            if !value.trim().is_empty() {
-                return Some(value);
-            }
+        if let Ok(value) = env::var(key)
+            && !value.trim().is_empty()
+        {
+            return Some(value);
         }
     }
     None
 }
 
+/// Splits a comma-separated setting into lowercase, non-empty entries.
+fn env_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_ascii_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum StorageBackend {
     Filesystem,
@@ -30,6 +43,110 @@ impl FromStr for StorageBackend {
     }
 }
 
+/// How documents are laid out in users' git repositories.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GitPathLayout {
+    /// Mirror the storage paths.
+    #[default]
+    Storage,
+    /// Slugified folder hierarchy and titles, unique per folder.
+    Hierarchy,
+}
+
+impl FromStr for GitPathLayout {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "storage" => Ok(GitPathLayout::Storage),
+            "hierarchy" | "friendly" => Ok(GitPathLayout::Hierarchy),
+            other => Err(anyhow::anyhow!("unsupported git path layout: {}", other)),
+        }
+    }
+}
+
+/// Address range such as `10.0.0.0/8` or `::1`; a bare address covers just that address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpCidr {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.trim().split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s.trim(), None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| anyhow::anyhow!("invalid IP address in trusted proxy: {}", s))?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix =
+            match prefix {
+                Some(p) => p.parse::<u8>().ok().filter(|p| *p <= max).ok_or_else(|| {
+                    anyhow::anyhow!("invalid prefix length in trusted proxy: {}", s)
+                })?,
+                None => max,
+            };
+        Ok(Self { addr, prefix })
+    }
+}
+
+/// Server-side encryption requested on S3 uploads.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum S3Encryption {
+    /// SSE-S3 (`AES256`).
+    S3,
+    /// SSE-KMS, with the bucket's default key unless `key_id` is set.
+    Kms { key_id: Option<String> },
+}
+
+impl S3Encryption {
+    fn parse(mode: &str, kms_key_id: Option<String>) -> anyhow::Result<Option<Self>> {
+        match mode.trim().to_lowercase().as_str() {
+            "" | "none" | "off" => Ok(None),
+            "aes256" | "sse-s3" | "s3" => Ok(Some(S3Encryption::S3)),
+            "aws:kms" | "sse-kms" | "kms" => Ok(Some(S3Encryption::Kms { key_id: kms_key_id })),
+            other => Err(anyhow::anyhow!(
+                "unsupported S3 server-side encryption: {}",
+                other
+            )),
+        }
+    }
+}
+
+/// Rejects endpoints the S3 client would only fail on at first use.
+fn validate_s3_endpoint(endpoint: &str) -> anyhow::Result<()> {
+    let url = reqwest::Url::parse(endpoint)
+        .map_err(|e| anyhow::anyhow!("S3_ENDPOINT is not a valid URL ({}): {}", e, endpoint))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        anyhow::bail!(
+            "S3_ENDPOINT must be an http(s) URL with a host: {}",
+            endpoint
+        );
+    }
+    Ok(())
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub api_port: u16,
@@ -37,6 +154,13 @@ pub struct Config {
     pub database_url: String,
     pub jwt_secret_pem: String,
     pub jwt_expires_secs: i64,
+    /// Clock skew tolerated when checking `exp`/`nbf`; never affects signature checks.
+    pub jwt_leeway_secs: u64,
+    /// How long a validated bearer token is trusted without re-checking it; 0 disables caching.
+    pub auth_token_cache_ttl_secs: u64,
+    pub auth_token_cache_capacity: usize,
+    /// Lowercased emails of users allowed to manage global plugins.
+    pub admin_emails: Vec<String>,
     pub snapshot_interval_secs: u64,
     pub snapshot_keep_versions: i64,
     pub updates_keep_window: i64,
@@ -48,21 +172,102 @@ pub struct Config {
     pub s3_access_key: Option<String>,
     pub s3_secret_key: Option<String>,
     pub s3_use_path_style: bool,
+    pub s3_encryption: Option<S3Encryption>,
+    /// Abort startup when the storage self-test fails instead of only logging it.
+    pub storage_self_test_strict: bool,
     pub plugin_dir: String,
     pub plugin_timeout_secs: u64,
     pub plugin_memory_max_mb: u64,
     pub plugin_fuel_limit: Option<u64>,
+    pub plugin_pool_size: usize,
+    pub plugin_max_instances: usize,
+    pub plugin_event_replay_size: usize,
+    pub plugin_event_replay_ttl_secs: u64,
+    /// Record one in every N successful plugin calls; failures are always recorded. 0 records
+    /// failures only.
+    pub plugin_exec_log_sample_every: u64,
+    /// Keep (redacted) call payloads in the plugin exec log.
+    pub plugin_exec_log_payloads: bool,
+    /// Scheduled plugin runs executed at once; 0 disables the scheduler on this node.
+    pub plugin_schedule_concurrency: usize,
+    /// Wall-clock limit for one placeholder renderer call during a markdown render; on
+    /// timeout the placeholder is left for the client.
+    pub plugin_render_timeout_ms: u64,
     pub encryption_key: String,
     pub upload_max_bytes: usize,
+    /// Request body limit for API routes without a dedicated limit.
+    pub json_body_max_bytes: usize,
+    /// Request body limit for plugin routes (installs, data imports).
+    pub plugin_body_max_bytes: usize,
+    pub upload_allowed_types: Vec<String>,
+    pub upload_denied_types: Vec<String>,
+    pub upload_denied_extensions: Vec<String>,
+    pub upload_sanitize_svg: bool,
     pub public_base_url: Option<String>,
+    /// Count views of published documents.
+    pub public_analytics_enabled: bool,
+    /// Per-minute request budget per client address on the markdown and public routes; 0 disables.
+    pub rate_limit_per_minute: u32,
+    /// Per-minute budget per signed-in user on the same routes; 0 exempts signed-in users.
+    pub rate_limit_authenticated_per_minute: u32,
+    /// Per-minute budget per share token for anonymous requests carrying one; 0 disables.
+    pub share_rate_limit_per_token_per_minute: u32,
+    /// Per-minute budget per client address for the same requests, across tokens; 0 disables.
+    pub share_rate_limit_per_address_per_minute: u32,
+    /// Reverse proxies whose `Forwarded`/`X-Forwarded-For` headers are believed. Empty means
+    /// the socket peer is always taken as the client.
+    pub trusted_proxies: Vec<IpCidr>,
+    /// Longest an API request may take before it is answered with `504`; 0 disables.
+    pub request_timeout_secs: u64,
+    /// Same for known long operations (git sync, plugin actions, exports); 0 disables.
+    pub long_request_timeout_secs: u64,
     pub is_production: bool,
     pub cluster_mode: bool,
     pub redis_url: Option<String>,
     pub redis_stream_prefix: String,
     pub redis_min_message_lifetime_ms: u64,
     pub redis_task_debounce_ms: u64,
-    pub redis_awareness_ttl_ms: u64,
+    /// Awareness clients (cursors) silent for longer than this are removed; both engines.
+    pub realtime_awareness_ttl_ms: u64,
+    /// How often awareness clients past the TTL are dropped and their removal broadcast.
+    pub realtime_awareness_sweep_interval_ms: u64,
     pub redis_stream_max_len: usize,
+    /// How often cluster nodes sweep for unsnapshotted documents; 0 disables the sweep.
+    pub redis_reconcile_interval_secs: u64,
+    /// How long a document's updates must be idle before the sweep snapshots it.
+    pub redis_reconcile_idle_secs: u64,
+    pub realtime_max_update_frame_bytes: usize,
+    pub realtime_max_awareness_frame_bytes: usize,
+    pub realtime_close_on_oversized_frame: bool,
+    /// Recent update frames remembered per document; repeats of them are dropped by both engines.
+    /// 0 disables deduplication.
+    pub realtime_update_dedup_window: usize,
+    /// Initial document states larger than this are sent in chunks to clients that support it.
+    /// 0 always sends a single frame.
+    pub realtime_initial_sync_chunk_bytes: usize,
+    pub weasyprint_bin: String,
+    /// Directory of extra `.sublime-syntax` and `.tmTheme` files for code highlighting.
+    pub highlight_assets_dir: Option<String>,
+    /// Instance-wide render style; explicit request options take precedence.
+    pub render_defaults: RenderOptions,
+    /// Replace placeholder titles ("Untitled") with the content's front matter title or first H1.
+    pub derive_title_from_content: bool,
+    /// Let edit share links grant edit to anonymous visitors. Off by default, so a share
+    /// created with edit permission is view-only until an operator opts in.
+    pub allow_anonymous_edit: bool,
+    /// Most unexpired share links a document may have at once; 0 means no limit.
+    pub max_shares_per_document: usize,
+    /// How often expired share links are deleted; 0 disables the cleanup on this node.
+    pub share_cleanup_interval_secs: u64,
+    /// Tags left out of tag listings unless asked for, in addition to `_`-prefixed ones.
+    pub hidden_tags: Vec<String>,
+    pub git_path_layout: GitPathLayout,
+    /// How often users with auto-sync on are checked for changes to commit; 0 disables.
+    pub git_auto_sync_interval_secs: u64,
+    /// Auto-sync commits once no document has changed for this long...
+    pub git_auto_sync_quiet_secs: u64,
+    /// ...or once the oldest uncommitted change is this old, whichever comes first.
+    pub git_auto_sync_max_interval_secs: u64,
 }
 
 impl Config {
@@ -79,6 +284,18 @@ impl Config {
         let jwt_expires_secs = env_var(&["JWT_EXPIRES_SECS"])
             .and_then(|s| s.parse().ok())
             .unwrap_or(60 * 60);
+        let jwt_leeway_secs = env_var(&["JWT_LEEWAY_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(60);
+        let auth_token_cache_ttl_secs = env_var(&["AUTH_TOKEN_CACHE_TTL_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(60);
+        let auth_token_cache_capacity = env_var(&["AUTH_TOKEN_CACHE_CAPACITY"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(10_000);
+        let admin_emails = env_var(&["ADMIN_EMAILS"])
+            .map(|s| env_list(&s))
+            .unwrap_or_default();
         let snapshot_interval_secs = env_var(&["SNAPSHOT_INTERVAL_SECS"])
             .and_then(|s| s.parse().ok())
             .unwrap_or(300);
@@ -102,6 +319,13 @@ impl Config {
         let s3_use_path_style = env_var(&["S3_USE_PATH_STYLE"])
             .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true"))
             .unwrap_or(false);
+        let s3_encryption = match env_var(&["S3_SSE"]) {
+            Some(mode) => S3Encryption::parse(&mode, env_var(&["S3_SSE_KMS_KEY_ID"]))?,
+            None => None,
+        };
+        if let Some(endpoint) = s3_endpoint.as_deref() {
+            validate_s3_endpoint(endpoint)?;
+        }
         let plugin_dir = env_var(&["PLUGINS_DIR"]).unwrap_or_else(|| "./plugins".into());
         let plugin_timeout_secs = env_var(&["PLUGIN_TIMEOUT_SECS"])
             .and_then(|s| s.parse().ok())
@@ -117,10 +341,67 @@ impl Config {
                 trimmed.parse().ok()
             }
         });
+        let plugin_pool_size = env_var(&["PLUGIN_POOL_SIZE"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(4);
+        let plugin_max_instances = env_var(&["PLUGIN_MAX_INSTANCES"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(32);
+        let plugin_event_replay_size = env_var(&["PLUGIN_EVENT_REPLAY_SIZE"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(256);
+        let plugin_event_replay_ttl_secs = env_var(&["PLUGIN_EVENT_REPLAY_TTL_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(300);
+        let plugin_exec_log_sample_every = env_var(&["PLUGIN_EXEC_LOG_SAMPLE_EVERY"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(10);
+        let plugin_exec_log_payloads = env_var(&["PLUGIN_EXEC_LOG_PAYLOADS"])
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        let plugin_schedule_concurrency = env_var(&["PLUGIN_SCHEDULE_CONCURRENCY"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(4);
+        let plugin_render_timeout_ms = env_var(&["PLUGIN_RENDER_TIMEOUT_MS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(3000);
         let encryption_key = env_var(&["ENCRYPTION_KEY"]).unwrap_or_else(|| jwt_secret_pem.clone());
         let upload_max_bytes = env_var(&["UPLOAD_MAX_BYTES"])
             .and_then(|s| s.parse().ok())
             .unwrap_or(25 * 1024 * 1024);
+        let json_body_max_bytes = env_var(&["JSON_BODY_MAX_BYTES"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(2 * 1024 * 1024);
+        let plugin_body_max_bytes = env_var(&["PLUGIN_BODY_MAX_BYTES"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(10 * 1024 * 1024);
+        let upload_allowed_types = env_var(&["UPLOAD_ALLOWED_TYPES"])
+            .map(|s| env_list(&s))
+            .unwrap_or_default();
+        let upload_denied_types = env_var(&["UPLOAD_DENIED_TYPES"])
+            .map(|s| env_list(&s))
+            .unwrap_or_else(|| {
+                uploads::DEFAULT_DENIED_TYPES
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect()
+            });
+        let upload_sanitize_svg = env_var(&["UPLOAD_SANITIZE_SVG"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(true);
+        let upload_denied_extensions = env_var(&["UPLOAD_DENIED_EXTENSIONS"])
+            .map(|s| {
+                env_list(&s)
+                    .into_iter()
+                    .map(|e| e.trim_start_matches('.').to_string())
+                    .collect()
+            })
+            .unwrap_or_else(|| {
+                uploads::DEFAULT_DENIED_EXTENSIONS
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect()
+            });
         let public_base_url =
             env_var(&["BACKEND_URL", "API_URL", "PUBLIC_BASE_URL", "PUBLIC_ORIGIN"])
                 .and_then(|v| {
@@ -134,8 +415,38 @@ impl Config {
                     }
                 })
                 .or_else(|| frontend_url.clone());
+        let public_analytics_enabled = env_var(&["PUBLIC_ANALYTICS_ENABLED"])
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(true);
+        let rate_limit_per_minute = env_var(&["RATE_LIMIT_PER_MINUTE"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(120);
+        let rate_limit_authenticated_per_minute = env_var(&["RATE_LIMIT_AUTHENTICATED_PER_MINUTE"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(1200);
+        let share_rate_limit_per_token_per_minute = env_var(&["SHARE_RATE_LIMIT_PER_TOKEN"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(300);
+        let share_rate_limit_per_address_per_minute = env_var(&["SHARE_RATE_LIMIT_PER_ADDRESS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(120);
+        let trusted_proxies = env_var(&["TRUSTED_PROXIES"])
+            .map(|s| env_list(&s))
+            .unwrap_or_default()
+            .iter()
+            .map(|s| s.parse())
+            .collect::<anyhow::Result<Vec<IpCidr>>>()?;
+        let request_timeout_secs = env_var(&["REQUEST_TIMEOUT_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(30);
+        let long_request_timeout_secs = env_var(&["LONG_REQUEST_TIMEOUT_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(300);
         let runtime_env = env_var(&["RUST_ENV", "APP_ENV"]).unwrap_or_else(|| "production".into());
         let is_production = matches!(runtime_env.as_str(), "production" | "prod" | "release");
+        let storage_self_test_strict = env_var(&["STORAGE_SELF_TEST_STRICT"])
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(is_production);
 
         let cluster_mode = env_var(&["CLUSTER_MODE"])
             .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
@@ -148,20 +459,113 @@ impl Config {
         let redis_task_debounce_ms = env_var(&["REDIS_TASK_DEBOUNCE_MS"])
             .and_then(|s| s.parse().ok())
             .unwrap_or(10_000);
-        let redis_awareness_ttl_ms = env_var(&["REDIS_AWARENESS_TTL_MS"])
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(45_000);
+        let realtime_awareness_ttl_ms =
+            env_var(&["REALTIME_AWARENESS_TTL_MS", "REDIS_AWARENESS_TTL_MS"])
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(45_000);
+        let realtime_awareness_sweep_interval_ms = env_var(&[
+            "REALTIME_AWARENESS_SWEEP_INTERVAL_MS",
+            "REDIS_AWARENESS_SWEEP_INTERVAL_MS",
+        ])
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(5_000);
         let redis_stream_max_len = env_var(&["REDIS_STREAM_MAX_LEN"])
             .and_then(|s| s.parse().ok())
             .unwrap_or(4096);
+        let redis_reconcile_interval_secs = env_var(&["REDIS_RECONCILE_INTERVAL_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(60);
+        let redis_reconcile_idle_secs = env_var(&["REDIS_RECONCILE_IDLE_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(60);
+        let realtime_max_update_frame_bytes = env_var(&["REALTIME_MAX_UPDATE_FRAME_BYTES"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(8 * 1024 * 1024);
+        let realtime_max_awareness_frame_bytes = env_var(&["REALTIME_MAX_AWARENESS_FRAME_BYTES"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(64 * 1024);
+        let realtime_close_on_oversized_frame = env_var(&["REALTIME_CLOSE_ON_OVERSIZED_FRAME"])
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(true);
+        let realtime_update_dedup_window = env_var(&["REALTIME_UPDATE_DEDUP_WINDOW"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(32);
+        let realtime_initial_sync_chunk_bytes = env_var(&["REALTIME_INITIAL_SYNC_CHUNK_BYTES"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(256 * 1024);
+        let weasyprint_bin = env_var(&["WEASYPRINT_BIN"]).unwrap_or_else(|| "weasyprint".into());
+        let highlight_assets_dir = env_var(&["HIGHLIGHT_ASSETS_DIR"])
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let derive_title_from_content = env_var(&["DERIVE_TITLE_FROM_CONTENT"])
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(true);
+        let allow_anonymous_edit = env_var(&["ALLOW_ANONYMOUS_EDIT"])
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        let max_shares_per_document = env_var(&["MAX_SHARES_PER_DOCUMENT"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(50);
+        let share_cleanup_interval_secs = env_var(&["SHARE_CLEANUP_INTERVAL_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(3600);
+        let git_path_layout = env_var(&["GIT_PATH_LAYOUT"])
+            .map(|s| s.parse::<GitPathLayout>())
+            .transpose()?
+            .unwrap_or_default();
+        let git_auto_sync_interval_secs = env_var(&["GIT_AUTO_SYNC_INTERVAL_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(60);
+        let git_auto_sync_quiet_secs = env_var(&["GIT_AUTO_SYNC_QUIET_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(300);
+        let git_auto_sync_max_interval_secs = env_var(&["GIT_AUTO_SYNC_MAX_INTERVAL_SECS"])
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(3600);
+        let hidden_tags = env_var(&["HIDDEN_TAGS"])
+            .map(|s| {
+                env_list(&s)
+                    .into_iter()
+                    .map(|t| t.trim_start_matches('#').to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let default_budget = RenderBudget::default();
+        let render_defaults = RenderOptions {
+            flavor: env_var(&["RENDER_DEFAULT_FLAVOR"]).map(|s| s.trim().to_ascii_lowercase()),
+            theme: env_var(&["RENDER_DEFAULT_THEME"]).map(|s| s.trim().to_string()),
+            features: env_var(&["RENDER_DEFAULT_FEATURES"]).map(|s| env_list(&s)),
+            sanitize: env_var(&["RENDER_DEFAULT_SANITIZE"])
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes")),
+            allow_raw_html: env_var(&["RENDER_DEFAULT_ALLOW_RAW_HTML"])
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes")),
+            autolink: env_var(&["RENDER_DEFAULT_AUTOLINK"])
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes")),
+            smart_punctuation: env_var(&["RENDER_DEFAULT_SMART_PUNCTUATION"])
+                .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes")),
+            locale: env_var(&["RENDER_DEFAULT_LOCALE"]).map(|s| s.trim().to_string()),
+            asset_base: env_var(&["RENDER_ASSET_BASE", "ASSET_BASE_URL"])
+                .map(|s| s.trim().to_string()),
+            budget: Some(RenderBudget {
+                max_nodes: env_var(&["RENDER_MAX_NODES"])
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(default_budget.max_nodes),
+                max_highlighted_blocks: env_var(&["RENDER_MAX_HIGHLIGHTED_BLOCKS"])
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(default_budget.max_highlighted_blocks),
+                max_html_bytes: env_var(&["RENDER_MAX_HTML_BYTES"])
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(default_budget.max_html_bytes),
+            }),
+            ..Default::default()
+        };
 
         // Production hardening: require proper FRONTEND_URL and robust secrets
         if is_production {
-            if frontend_url
+            if !frontend_url
                 .as_deref()
                 .map(|u| u.starts_with("http"))
                 .unwrap_or(false)
-                == false
             {
                 anyhow::bail!(
                     "FRONTEND_URL must be set to a full origin in production (e.g., https://app.example.com)"
@@ -199,6 +603,10 @@ impl Config {
             database_url,
             jwt_secret_pem,
             jwt_expires_secs,
+            jwt_leeway_secs,
+            auth_token_cache_ttl_secs,
+            auth_token_cache_capacity,
+            admin_emails,
             snapshot_interval_secs,
             snapshot_keep_versions,
             updates_keep_window,
@@ -210,21 +618,65 @@ impl Config {
             s3_access_key,
             s3_secret_key,
             s3_use_path_style,
+            s3_encryption,
+            storage_self_test_strict,
             plugin_dir,
             plugin_timeout_secs,
             plugin_memory_max_mb,
             plugin_fuel_limit,
+            plugin_pool_size,
+            plugin_max_instances,
+            plugin_event_replay_size,
+            plugin_event_replay_ttl_secs,
+            plugin_exec_log_sample_every,
+            plugin_exec_log_payloads,
+            plugin_schedule_concurrency,
+            plugin_render_timeout_ms,
             encryption_key,
             upload_max_bytes,
+            json_body_max_bytes,
+            plugin_body_max_bytes,
+            upload_allowed_types,
+            upload_denied_types,
+            upload_denied_extensions,
+            upload_sanitize_svg,
             public_base_url,
+            public_analytics_enabled,
+            rate_limit_per_minute,
+            rate_limit_authenticated_per_minute,
+            share_rate_limit_per_token_per_minute,
+            share_rate_limit_per_address_per_minute,
+            request_timeout_secs,
+            long_request_timeout_secs,
+            trusted_proxies,
             is_production,
             cluster_mode,
             redis_url,
             redis_stream_prefix,
             redis_min_message_lifetime_ms,
             redis_task_debounce_ms,
-            redis_awareness_ttl_ms,
+            realtime_awareness_ttl_ms,
+            realtime_awareness_sweep_interval_ms,
             redis_stream_max_len,
+            redis_reconcile_interval_secs,
+            redis_reconcile_idle_secs,
+            realtime_max_update_frame_bytes,
+            realtime_max_awareness_frame_bytes,
+            realtime_close_on_oversized_frame,
+            realtime_update_dedup_window,
+            realtime_initial_sync_chunk_bytes,
+            weasyprint_bin,
+            highlight_assets_dir,
+            render_defaults,
+            derive_title_from_content,
+            allow_anonymous_edit,
+            max_shares_per_document,
+            share_cleanup_interval_secs,
+            hidden_tags,
+            git_path_layout,
+            git_auto_sync_interval_secs,
+            git_auto_sync_quiet_secs,
+            git_auto_sync_max_interval_secs,
         })
     }
 }
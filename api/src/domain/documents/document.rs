@@ -9,6 +9,10 @@ pub struct Document {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub path: Option<String>,
+    /// Tree decoration: an emoji or a name from the named icon set.
+    pub icon: Option<String>,
+    /// Tree decoration: a `#rrggbb` color.
+    pub color: Option<String>,
 }
 
 #[derive(Debug, Clone)]
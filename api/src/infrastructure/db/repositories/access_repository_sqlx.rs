@@ -1,7 +1,8 @@
 use async_trait::async_trait;
+use sqlx::Row;
 use uuid::Uuid;
 
-use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::access_repository::{AccessRepository, UserDocumentAccess};
 use crate::infrastructure::db::PgPool;
 
 pub struct SqlxAccessRepository {
@@ -36,4 +37,34 @@ impl AccessRepository for SqlxAccessRepository {
         .await?;
         Ok(count > 0)
     }
+
+    async fn user_document_access(
+        &self,
+        doc_id: Uuid,
+        user_id: Uuid,
+    ) -> anyhow::Result<Option<UserDocumentAccess>> {
+        let row = sqlx::query(
+            r#"SELECT d.owner_id = $2 AS owned, a.permission, d.locked
+               FROM documents d
+               LEFT JOIN document_user_access a ON a.document_id = d.id AND a.user_id = $2
+               WHERE d.id = $1"#,
+        )
+        .bind(doc_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|r| UserDocumentAccess {
+            owned: r.get("owned"),
+            permission: r.get("permission"),
+            locked: r.get("locked"),
+        }))
+    }
+
+    async fn is_document_locked(&self, doc_id: Uuid) -> anyhow::Result<bool> {
+        let locked = sqlx::query_scalar::<_, bool>("SELECT locked FROM documents WHERE id = $1")
+            .bind(doc_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(locked.unwrap_or(false))
+    }
 }
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::application::ports::comment_repository::{
+    CommentAnchor, CommentRepository, DocumentComment, NewComment,
+};
+use crate::infrastructure::db::PgPool;
+
+const COLUMNS: &str = "id, document_id, parent_id, author_id, author_name, anchor_start_line, \
+                       anchor_start_col, anchor_end_line, anchor_end_col, body, resolved, \
+                       resolved_by, resolved_at, created_at";
+
+pub struct SqlxCommentRepository {
+    pub pool: PgPool,
+}
+
+impl SqlxCommentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn to_comment(r: PgRow) -> DocumentComment {
+    let anchor = match (
+        r.get::<Option<i32>, _>("anchor_start_line"),
+        r.get::<Option<i32>, _>("anchor_start_col"),
+        r.get::<Option<i32>, _>("anchor_end_line"),
+        r.get::<Option<i32>, _>("anchor_end_col"),
+    ) {
+        (Some(start_line), Some(start_col), Some(end_line), Some(end_col)) => Some(CommentAnchor {
+            start_line,
+            start_col,
+            end_line,
+            end_col,
+        }),
+        _ => None,
+    };
+    DocumentComment {
+        id: r.get("id"),
+        document_id: r.get("document_id"),
+        parent_id: r.get("parent_id"),
+        author_id: r.get("author_id"),
+        author_name: r.get("author_name"),
+        anchor,
+        body: r.get("body"),
+        resolved: r.get("resolved"),
+        resolved_by: r.get("resolved_by"),
+        resolved_at: r.get("resolved_at"),
+        created_at: r.get("created_at"),
+    }
+}
+
+#[async_trait]
+impl CommentRepository for SqlxCommentRepository {
+    async fn create(&self, comment: &NewComment) -> anyhow::Result<DocumentComment> {
+        let anchor = comment.anchor;
+        let row = sqlx::query(&format!(
+            r#"INSERT INTO document_comments (document_id, parent_id, author_id, author_name,
+                   anchor_start_line, anchor_start_col, anchor_end_line, anchor_end_col, body)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING {COLUMNS}"#
+        ))
+        .bind(comment.document_id)
+        .bind(comment.parent_id)
+        .bind(comment.author_id)
+        .bind(comment.author_name.as_deref())
+        .bind(anchor.map(|a| a.start_line))
+        .bind(anchor.map(|a| a.start_col))
+        .bind(anchor.map(|a| a.end_line))
+        .bind(anchor.map(|a| a.end_col))
+        .bind(&comment.body)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(to_comment(row))
+    }
+
+    async fn list_for_document(&self, doc_id: Uuid) -> anyhow::Result<Vec<DocumentComment>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {COLUMNS} FROM document_comments WHERE document_id = $1 ORDER BY created_at ASC"
+        ))
+        .bind(doc_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(to_comment).collect())
+    }
+
+    async fn get(&self, doc_id: Uuid, id: Uuid) -> anyhow::Result<Option<DocumentComment>> {
+        let row = sqlx::query(&format!(
+            "SELECT {COLUMNS} FROM document_comments WHERE id = $1 AND document_id = $2"
+        ))
+        .bind(id)
+        .bind(doc_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(to_comment))
+    }
+
+    async fn set_resolved(
+        &self,
+        id: Uuid,
+        resolved: bool,
+        by: Option<Uuid>,
+    ) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"UPDATE document_comments
+               SET resolved = $2,
+                   resolved_by = CASE WHEN $2 THEN $3 ELSE NULL END,
+                   resolved_at = CASE WHEN $2 THEN now() ELSE NULL END
+               WHERE id = $1"#,
+        )
+        .bind(id)
+        .bind(resolved)
+        .bind(by)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn delete(&self, id: Uuid) -> anyhow::Result<bool> {
+        let res = sqlx::query("DELETE FROM document_comments WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn recipients(&self, doc_id: Uuid, thread_id: Option<Uuid>) -> anyhow::Result<Vec<Uuid>> {
+        let rows = sqlx::query(
+            r#"SELECT owner_id AS user_id FROM documents WHERE id = $1
+               UNION
+               SELECT author_id FROM document_comments
+               WHERE $2::uuid IS NOT NULL AND author_id IS NOT NULL
+                 AND (id = $2 OR parent_id = $2)"#,
+        )
+        .bind(doc_id)
+        .bind(thread_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|r| r.get("user_id")).collect())
+    }
+}
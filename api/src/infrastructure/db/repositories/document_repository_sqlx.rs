@@ -2,8 +2,11 @@ use async_trait::async_trait;
 use sqlx::Row;
 use uuid::Uuid;
 
+use crate::application::linkgraph;
 use crate::application::ports::document_repository::DocMeta;
-use crate::application::ports::document_repository::DocumentRepository;
+use crate::application::ports::document_repository::{
+    DocumentListFilter, DocumentPage, DocumentRepository, LinkCounts,
+};
 use crate::domain::documents::document::{
     BacklinkInfo as DomBacklinkInfo, Document as DomainDocument, OutgoingLink as DomOutgoingLink,
     SearchHit,
@@ -25,46 +28,72 @@ impl DocumentRepository for SqlxDocumentRepository {
     async fn list_for_user(
         &self,
         user_id: Uuid,
-        query: Option<String>,
-        tag: Option<String>,
-    ) -> anyhow::Result<Vec<DomainDocument>> {
-        let rows = if let Some(t) = tag.as_ref().filter(|s| !s.trim().is_empty()) {
-            sqlx::query(
-                r#"SELECT d.id, d.title, d.parent_id, d.type, d.created_at, d.updated_at, d.path
-                           FROM document_tags dt
-                           JOIN tags t ON t.id = dt.tag_id
-                           JOIN documents d ON d.id = dt.document_id
-                           WHERE d.owner_id = $1 AND t.name ILIKE $2
-                           ORDER BY d.updated_at DESC LIMIT 100"#,
-            )
-            .bind(user_id)
-            .bind(t)
-            .fetch_all(&self.pool)
-            .await?
-        } else if let Some(ref qq) = query.as_ref().filter(|s| !s.trim().is_empty()) {
-            let like = format!("%{}%", qq);
-            sqlx::query(
-                r#"SELECT id, title, parent_id, type, created_at, updated_at, path
-                           FROM documents
-                           WHERE owner_id = $1 AND title ILIKE $2
-                           ORDER BY updated_at DESC LIMIT 100"#,
-            )
+        filter: &DocumentListFilter,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<DocumentPage> {
+        const FILTERED: &str = r#"FROM documents d
+                       WHERE (d.owner_id = $1 OR EXISTS (
+                               SELECT 1 FROM document_user_access a
+                               WHERE a.document_id = d.id AND a.user_id = $1))
+                         AND ($2::text IS NULL OR d.title ILIKE $2)
+                         AND ($3::text IS NULL OR EXISTS (
+                               SELECT 1 FROM document_tags dt JOIN tags t ON t.id = dt.tag_id
+                               WHERE dt.document_id = d.id AND t.name ILIKE $3))
+                         AND ($4::text IS NULL OR d.type = $4)
+                         AND ($5::timestamptz IS NULL OR d.updated_at >= $5)"#;
+        let like = filter.query.as_ref().map(|q| format!("%{}%", q));
+
+        let total = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) {}", FILTERED))
             .bind(user_id)
-            .bind(like)
-            .fetch_all(&self.pool)
-            .await?
+            .bind(&like)
+            .bind(&filter.tag)
+            .bind(&filter.doc_type)
+            .bind(filter.updated_since)
+            .fetch_one(&self.pool)
+            .await?;
+        // Counts follow `backlinks_for`/`outgoing_links_for`: only links whose other end
+        // belongs to the same owner.
+        let counts = if filter.include_link_counts {
+            r#", (SELECT COUNT(*) FROM document_links dl
+                          JOIN documents s ON s.id = dl.source_document_id
+                          WHERE dl.target_document_id = d.id AND s.owner_id = d.owner_id) AS backlink_count,
+                         (SELECT COUNT(*) FROM document_links dl
+                          JOIN documents t ON t.id = dl.target_document_id
+                          WHERE dl.source_document_id = d.id AND t.owner_id = d.owner_id) AS outgoing_count"#
         } else {
-            sqlx::query(
-                r#"SELECT id, title, parent_id, type, created_at, updated_at, path
-                           FROM documents
-                           WHERE owner_id = $1
-                           ORDER BY updated_at DESC LIMIT 100"#,
-            )
-            .bind(user_id)
-            .fetch_all(&self.pool)
-            .await?
+            ""
         };
+        let rows = sqlx::query(&format!(
+            r#"SELECT d.id, d.title, d.parent_id, d.type, d.created_at, d.updated_at, d.path, d.icon, d.color{}
+                       {}
+                       ORDER BY d.updated_at DESC, d.id
+                       LIMIT $6 OFFSET $7"#,
+            counts, FILTERED
+        ))
+        .bind(user_id)
+        .bind(&like)
+        .bind(&filter.tag)
+        .bind(&filter.doc_type)
+        .bind(filter.updated_since)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
 
+        let link_counts = filter.include_link_counts.then(|| {
+            rows.iter()
+                .map(|r| {
+                    (
+                        r.get::<Uuid, _>("id"),
+                        LinkCounts {
+                            backlinks: r.get("backlink_count"),
+                            outgoing: r.get("outgoing_count"),
+                        },
+                    )
+                })
+                .collect()
+        });
         let items = rows
             .into_iter()
             .map(|r| DomainDocument {
@@ -75,9 +104,51 @@ impl DocumentRepository for SqlxDocumentRepository {
                 created_at: r.get("created_at"),
                 updated_at: r.get("updated_at"),
                 path: r.try_get("path").ok(),
+                icon: r.try_get("icon").ok(),
+                color: r.try_get("color").ok(),
             })
             .collect();
-        Ok(items)
+        Ok(DocumentPage {
+            items,
+            total,
+            link_counts,
+        })
+    }
+
+    async fn list_recent_for_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        include_shared: bool,
+    ) -> anyhow::Result<Vec<DomainDocument>> {
+        let rows = sqlx::query(
+            r#"SELECT d.id, d.title, d.parent_id, d.type, d.created_at, d.updated_at, d.path, d.icon, d.color
+                       FROM documents d
+                       WHERE d.type <> 'folder'
+                         AND (d.owner_id = $1 OR ($2 AND EXISTS (
+                               SELECT 1 FROM document_user_access a
+                               WHERE a.document_id = d.id AND a.user_id = $1)))
+                       ORDER BY d.updated_at DESC LIMIT $3"#,
+        )
+        .bind(user_id)
+        .bind(include_shared)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| DomainDocument {
+                id: r.get("id"),
+                title: r.get("title"),
+                parent_id: r.get("parent_id"),
+                doc_type: r.get("type"),
+                created_at: r.get("created_at"),
+                updated_at: r.get("updated_at"),
+                path: r.try_get("path").ok(),
+                icon: r.try_get("icon").ok(),
+                color: r.try_get("color").ok(),
+            })
+            .collect())
     }
 
     async fn list_ids_for_user(&self, user_id: Uuid) -> anyhow::Result<Vec<Uuid>> {
@@ -90,7 +161,7 @@ impl DocumentRepository for SqlxDocumentRepository {
 
     async fn get_by_id(&self, id: Uuid) -> anyhow::Result<Option<DomainDocument>> {
         let row = sqlx::query(
-            r#"SELECT id, title, parent_id, type, created_at, updated_at, path
+            r#"SELECT id, title, parent_id, type, created_at, updated_at, path, icon, color
                FROM documents WHERE id = $1"#,
         )
         .bind(id)
@@ -104,6 +175,8 @@ impl DocumentRepository for SqlxDocumentRepository {
             created_at: r.get("created_at"),
             updated_at: r.get("updated_at"),
             path: r.try_get("path").ok(),
+            icon: r.try_get("icon").ok(),
+            color: r.try_get("color").ok(),
         }))
     }
 
@@ -161,14 +234,15 @@ impl DocumentRepository for SqlxDocumentRepository {
         doc_type: &str,
     ) -> anyhow::Result<DomainDocument> {
         let row = sqlx::query(
-            r#"INSERT INTO documents (title, owner_id, parent_id, type, path)
-               VALUES ($1, $2, $3, $4, NULL)
-               RETURNING id, title, parent_id, type, created_at, updated_at, path"#,
+            r#"INSERT INTO documents (title, owner_id, parent_id, type, path, title_key)
+               VALUES ($1, $2, $3, $4, NULL, $5)
+               RETURNING id, title, parent_id, type, created_at, updated_at, path, icon, color"#,
         )
         .bind(title)
         .bind(user_id)
         .bind(parent_id)
         .bind(doc_type)
+        .bind(linkgraph::title_key(title))
         .fetch_one(&self.pool)
         .await?;
         Ok(DomainDocument {
@@ -179,6 +253,8 @@ impl DocumentRepository for SqlxDocumentRepository {
             created_at: row.get("created_at"),
             updated_at: row.get("updated_at"),
             path: row.try_get("path").ok(),
+            icon: row.try_get("icon").ok(),
+            color: row.try_get("color").ok(),
         })
     }
 
@@ -189,18 +265,21 @@ impl DocumentRepository for SqlxDocumentRepository {
         title: Option<String>,
         parent_id: Option<Option<Uuid>>,
     ) -> anyhow::Result<Option<DomainDocument>> {
+        let title_key = title.as_deref().map(linkgraph::title_key);
         let row = match parent_id {
             None => {
                 sqlx::query(
                     r#"UPDATE documents SET
                             title = COALESCE($1, title),
+                            title_key = COALESCE($4, title_key),
                             updated_at = now()
                         WHERE id = $2 AND owner_id = $3
-                        RETURNING id, title, parent_id, type, created_at, updated_at, path"#,
+                        RETURNING id, title, parent_id, type, created_at, updated_at, path, icon, color"#,
                 )
                 .bind(title)
                 .bind(id)
                 .bind(user_id)
+                .bind(&title_key)
                 .fetch_optional(&self.pool)
                 .await?
             }
@@ -208,15 +287,17 @@ impl DocumentRepository for SqlxDocumentRepository {
                 sqlx::query(
                     r#"UPDATE documents SET
                             title = COALESCE($1, title),
+                            title_key = COALESCE($5, title_key),
                             parent_id = $2,
                             updated_at = now()
                         WHERE id = $3 AND owner_id = $4
-                        RETURNING id, title, parent_id, type, created_at, updated_at, path"#,
+                        RETURNING id, title, parent_id, type, created_at, updated_at, path, icon, color"#,
                 )
                 .bind(title)
                 .bind(newp)
                 .bind(id)
                 .bind(user_id)
+                .bind(&title_key)
                 .fetch_optional(&self.pool)
                 .await?
             }
@@ -229,6 +310,46 @@ impl DocumentRepository for SqlxDocumentRepository {
             created_at: r.get("created_at"),
             updated_at: r.get("updated_at"),
             path: r.try_get("path").ok(),
+            icon: r.try_get("icon").ok(),
+            color: r.try_get("color").ok(),
+        }))
+    }
+
+    async fn set_locked(&self, id: Uuid, locked: bool) -> anyhow::Result<()> {
+        sqlx::query("UPDATE documents SET locked = $2 WHERE id = $1")
+            .bind(id)
+            .bind(locked)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn set_appearance(
+        &self,
+        id: Uuid,
+        icon: Option<&str>,
+        color: Option<&str>,
+    ) -> anyhow::Result<Option<DomainDocument>> {
+        let row = sqlx::query(
+            r#"UPDATE documents SET icon = $2, color = $3
+               WHERE id = $1
+               RETURNING id, title, parent_id, type, created_at, updated_at, path, icon, color"#,
+        )
+        .bind(id)
+        .bind(icon)
+        .bind(color)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|r| DomainDocument {
+            id: r.get("id"),
+            title: r.get("title"),
+            parent_id: r.get("parent_id"),
+            doc_type: r.get("type"),
+            created_at: r.get("created_at"),
+            updated_at: r.get("updated_at"),
+            path: r.try_get("path").ok(),
+            icon: r.try_get("icon").ok(),
+            color: r.try_get("color").ok(),
         }))
     }
 
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::application::ports::document_retention_repository::{
+    DocumentRetention, DocumentRetentionRepository,
+};
+use crate::infrastructure::db::PgPool;
+
+pub struct SqlxDocumentRetentionRepository {
+    pub pool: PgPool,
+}
+
+impl SqlxDocumentRetentionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn row_to_retention(row: &sqlx::postgres::PgRow) -> DocumentRetention {
+    DocumentRetention {
+        snapshot_keep_versions: row.get("snapshot_keep_versions"),
+        updates_keep_window: row.get("updates_keep_window"),
+    }
+}
+
+#[async_trait]
+impl DocumentRetentionRepository for SqlxDocumentRetentionRepository {
+    async fn get(&self, doc_id: Uuid) -> anyhow::Result<Option<DocumentRetention>> {
+        let row = sqlx::query(
+            "SELECT snapshot_keep_versions, updates_keep_window FROM document_retention WHERE document_id = $1",
+        )
+        .bind(doc_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.as_ref().map(row_to_retention))
+    }
+
+    async fn get_many(&self, doc_ids: &[Uuid]) -> anyhow::Result<HashMap<Uuid, DocumentRetention>> {
+        if doc_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let rows = sqlx::query(
+            "SELECT document_id, snapshot_keep_versions, updates_keep_window FROM document_retention WHERE document_id = ANY($1)",
+        )
+        .bind(doc_ids)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|r| (r.get("document_id"), row_to_retention(r)))
+            .collect())
+    }
+
+    async fn upsert(&self, doc_id: Uuid, retention: DocumentRetention) -> anyhow::Result<()> {
+        if retention == DocumentRetention::default() {
+            sqlx::query("DELETE FROM document_retention WHERE document_id = $1")
+                .bind(doc_id)
+                .execute(&self.pool)
+                .await?;
+            return Ok(());
+        }
+        sqlx::query(
+            r#"INSERT INTO document_retention (document_id, snapshot_keep_versions, updates_keep_window)
+               VALUES ($1, $2, $3)
+               ON CONFLICT (document_id) DO UPDATE
+               SET snapshot_keep_versions = EXCLUDED.snapshot_keep_versions,
+                   updates_keep_window = EXCLUDED.updates_keep_window,
+                   updated_at = now()"#,
+        )
+        .bind(doc_id)
+        .bind(retention.snapshot_keep_versions)
+        .bind(retention.updates_keep_window)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
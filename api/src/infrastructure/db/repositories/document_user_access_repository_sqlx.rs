@@ -0,0 +1,76 @@
+use async_trait::async_trait;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::application::ports::document_user_access_repository::{
+    DocumentUserAccess, DocumentUserAccessRepository,
+};
+use crate::infrastructure::db::PgPool;
+
+pub struct SqlxDocumentUserAccessRepository {
+    pub pool: PgPool,
+}
+
+impl SqlxDocumentUserAccessRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DocumentUserAccessRepository for SqlxDocumentUserAccessRepository {
+    async fn grant(
+        &self,
+        doc_id: Uuid,
+        user_id: Uuid,
+        permission: &str,
+        granted_by: Uuid,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"INSERT INTO document_user_access (document_id, user_id, permission, granted_by)
+               VALUES ($1, $2, $3, $4)
+               ON CONFLICT (document_id, user_id) DO UPDATE
+               SET permission = EXCLUDED.permission, granted_by = EXCLUDED.granted_by"#,
+        )
+        .bind(doc_id)
+        .bind(user_id)
+        .bind(permission)
+        .bind(granted_by)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn revoke(&self, doc_id: Uuid, user_id: Uuid) -> anyhow::Result<bool> {
+        let res =
+            sqlx::query("DELETE FROM document_user_access WHERE document_id = $1 AND user_id = $2")
+                .bind(doc_id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn list_for_document(&self, doc_id: Uuid) -> anyhow::Result<Vec<DocumentUserAccess>> {
+        let rows = sqlx::query(
+            r#"SELECT a.user_id, u.email, u.name, a.permission, a.created_at
+               FROM document_user_access a
+               JOIN users u ON u.id = a.user_id
+               WHERE a.document_id = $1
+               ORDER BY a.created_at ASC"#,
+        )
+        .bind(doc_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| DocumentUserAccess {
+                user_id: r.get("user_id"),
+                email: r.get("email"),
+                name: r.get("name"),
+                permission: r.get("permission"),
+                created_at: r.get("created_at"),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::application::ports::document_version_repository::{
+    DocumentVersion, DocumentVersionRepository,
+};
+use crate::infrastructure::db::PgPool;
+
+pub struct SqlxDocumentVersionRepository {
+    pub pool: PgPool,
+}
+
+impl SqlxDocumentVersionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DocumentVersionRepository for SqlxDocumentVersionRepository {
+    async fn list_versions(&self, doc_id: Uuid) -> anyhow::Result<Vec<DocumentVersion>> {
+        let rows = sqlx::query(
+            "SELECT version, created_at FROM document_snapshots WHERE document_id = $1 ORDER BY version DESC",
+        )
+        .bind(doc_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| DocumentVersion {
+                version: r.get::<i32, _>("version") as i64,
+                created_at: r.get("created_at"),
+            })
+            .collect())
+    }
+
+    async fn get_snapshot(&self, doc_id: Uuid, version: i64) -> anyhow::Result<Option<Vec<u8>>> {
+        let snapshot = sqlx::query_scalar::<_, Vec<u8>>(
+            "SELECT snapshot FROM document_snapshots WHERE document_id = $1 AND version = $2",
+        )
+        .bind(doc_id)
+        .bind(version as i32)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(snapshot)
+    }
+}
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use sqlx::Row;
 use uuid::Uuid;
 
-use crate::application::ports::git_repository::{GitRepository, UserGitCfg};
+use crate::application::ports::git_repository::{AutoSyncCandidate, GitRepository, UserGitCfg};
 use crate::infrastructure::crypto;
 use crate::infrastructure::db::PgPool;
 
@@ -61,6 +61,8 @@ impl GitRepository for SqlxGitRepository {
         auth_type: &str,
         auth_data: &serde_json::Value,
         auto_sync: Option<bool>,
+        commit_message_template: Option<&str>,
+        append_change_summary: Option<bool>,
     ) -> anyhow::Result<(
         Uuid,
         String,
@@ -72,14 +74,17 @@ impl GitRepository for SqlxGitRepository {
     )> {
         let enc_auth = crypto::encrypt_auth_data(&self.encryption_key, auth_data);
         let row = sqlx::query(
-            r#"INSERT INTO git_configs (user_id, repository_url, branch_name, auth_type, auth_data, auto_sync)
-               VALUES ($1, $2, COALESCE($3, 'main'), $4, $5, COALESCE($6, true))
+            r#"INSERT INTO git_configs (user_id, repository_url, branch_name, auth_type, auth_data, auto_sync,
+                                        commit_message_template, append_change_summary)
+               VALUES ($1, $2, COALESCE($3, 'main'), $4, $5, COALESCE($6, true), $7, COALESCE($8, false))
                ON CONFLICT ON CONSTRAINT git_configs_user_id_unique DO UPDATE SET
                  repository_url = EXCLUDED.repository_url,
                  branch_name = EXCLUDED.branch_name,
                  auth_type = EXCLUDED.auth_type,
                  auth_data = EXCLUDED.auth_data,
                  auto_sync = EXCLUDED.auto_sync,
+                 commit_message_template = EXCLUDED.commit_message_template,
+                 append_change_summary = EXCLUDED.append_change_summary,
                  updated_at = now()
                RETURNING id, repository_url, branch_name, auth_type, auto_sync, created_at, updated_at"#
         )
@@ -89,6 +94,8 @@ impl GitRepository for SqlxGitRepository {
         .bind(auth_type)
         .bind(&enc_auth)
         .bind(auto_sync)
+        .bind(commit_message_template)
+        .bind(append_change_summary)
         .fetch_one(&self.pool)
         .await?;
         Ok((
@@ -111,7 +118,7 @@ impl GitRepository for SqlxGitRepository {
     }
 
     async fn load_user_git_cfg(&self, user_id: Uuid) -> anyhow::Result<Option<UserGitCfg>> {
-        let row = sqlx::query("SELECT repository_url, branch_name, auth_type, auth_data, auto_sync FROM git_configs WHERE user_id = $1 LIMIT 1")
+        let row = sqlx::query("SELECT repository_url, branch_name, auth_type, auth_data, auto_sync, commit_message_template, append_change_summary FROM git_configs WHERE user_id = $1 LIMIT 1")
             .bind(user_id)
             .fetch_optional(&self.pool)
             .await?;
@@ -128,6 +135,8 @@ impl GitRepository for SqlxGitRepository {
                 auth_type,
                 auth_data,
                 auto_sync,
+                commit_message_template: r.try_get("commit_message_template").ok().flatten(),
+                append_change_summary: r.try_get("append_change_summary").unwrap_or(false),
             }
         }))
     }
@@ -191,4 +200,65 @@ impl GitRepository for SqlxGitRepository {
             .await?;
         Ok(())
     }
+
+    async fn pending_import_documents(&self, user_id: Uuid) -> anyhow::Result<Vec<Uuid>> {
+        let rows = sqlx::query("SELECT document_id FROM git_pending_imports WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows.into_iter().map(|r| r.get("document_id")).collect())
+    }
+
+    async fn add_pending_import_document(
+        &self,
+        user_id: Uuid,
+        document_id: Uuid,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            "INSERT INTO git_pending_imports (user_id, document_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(document_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn clear_pending_import(&self, user_id: Uuid) -> anyhow::Result<()> {
+        sqlx::query("DELETE FROM git_pending_imports WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn auto_sync_candidates(&self) -> anyhow::Result<Vec<AutoSyncCandidate>> {
+        let rows = sqlx::query(
+            r#"SELECT c.user_id,
+                      MIN(d.updated_at) AS first_change_at,
+                      MAX(d.updated_at) AS last_change_at
+               FROM git_configs c
+               JOIN git_repository_state s ON s.user_id = c.user_id AND s.initialized
+               LEFT JOIN LATERAL (
+                   SELECT MAX(committed_at) AS committed_at
+                   FROM git_commits
+                   WHERE user_id = c.user_id
+               ) lc ON true
+               JOIN documents d
+                 ON d.owner_id = c.user_id
+                AND (lc.committed_at IS NULL OR d.updated_at > lc.committed_at)
+               WHERE c.auto_sync
+               GROUP BY c.user_id"#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| AutoSyncCandidate {
+                user_id: r.get("user_id"),
+                first_change_at: r.get("first_change_at"),
+                last_change_at: r.get("last_change_at"),
+            })
+            .collect())
+    }
 }
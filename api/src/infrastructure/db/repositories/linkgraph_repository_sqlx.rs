@@ -2,7 +2,10 @@ use async_trait::async_trait;
 use sqlx::Row;
 use uuid::Uuid;
 
-use crate::application::ports::linkgraph_repository::LinkGraphRepository;
+use crate::application::linkgraph;
+use crate::application::ports::linkgraph_repository::{
+    LinkGraphRepository, NewDocumentLink, TitleCandidate,
+};
 use crate::infrastructure::db::PgPool;
 
 pub struct SqlxLinkGraphRepository {
@@ -25,48 +28,90 @@ impl LinkGraphRepository for SqlxLinkGraphRepository {
         Ok(())
     }
 
-    async fn exists_doc_for_owner(&self, doc_id: Uuid, owner_id: Uuid) -> anyhow::Result<bool> {
-        let n = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(1) FROM documents WHERE id = $1 AND owner_id = $2",
+    async fn existing_docs_for_owner(
+        &self,
+        owner_id: Uuid,
+        doc_ids: &[Uuid],
+    ) -> anyhow::Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM documents WHERE owner_id = $1 AND id = ANY($2)",
         )
-        .bind(doc_id)
         .bind(owner_id)
-        .fetch_one(&self.pool)
+        .bind(doc_ids)
+        .fetch_all(&self.pool)
         .await?;
-        Ok(n > 0)
+        Ok(ids)
     }
 
-    async fn find_doc_id_by_owner_and_title(
+    async fn find_docs_by_owner_and_title_keys(
         &self,
         owner_id: Uuid,
-        title: &str,
-    ) -> anyhow::Result<Option<Uuid>> {
-        let row = sqlx::query(
-            r#"SELECT id FROM documents 
-               WHERE owner_id = $1 AND LOWER(title) = LOWER($2)
-               ORDER BY updated_at DESC LIMIT 1"#,
+        title_keys: &[String],
+    ) -> anyhow::Result<Vec<TitleCandidate>> {
+        // Rows created before title keys existed are keyed on first lookup.
+        let unkeyed = sqlx::query(
+            "SELECT id, title FROM documents WHERE owner_id = $1 AND title_key IS NULL",
         )
         .bind(owner_id)
-        .bind(title)
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
-        Ok(row.map(|r| r.get::<Uuid, _>("id")))
+        if !unkeyed.is_empty() {
+            let (ids, keys): (Vec<Uuid>, Vec<String>) = unkeyed
+                .iter()
+                .map(|r| {
+                    (
+                        r.get::<Uuid, _>("id"),
+                        linkgraph::title_key(&r.get::<String, _>("title")),
+                    )
+                })
+                .unzip();
+            sqlx::query(
+                r#"UPDATE documents d SET title_key = k.key
+                   FROM UNNEST($1::uuid[], $2::text[]) AS k(id, key)
+                   WHERE d.id = k.id AND d.title_key IS NULL"#,
+            )
+            .bind(&ids)
+            .bind(&keys)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        let rows = sqlx::query(
+            r#"SELECT id, title, updated_at FROM documents
+               WHERE owner_id = $1 AND title_key = ANY($2)"#,
+        )
+        .bind(owner_id)
+        .bind(title_keys)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| TitleCandidate {
+                id: r.get("id"),
+                title: r.get("title"),
+                updated_at: r.get("updated_at"),
+            })
+            .collect())
     }
 
-    async fn upsert_link(
-        &self,
-        source_id: Uuid,
-        target_id: Uuid,
-        link_type: &str,
-        link_text: Option<String>,
-        position_start: i32,
-        position_end: i32,
-    ) -> anyhow::Result<()> {
+    async fn upsert_links(&self, source_id: Uuid, links: &[NewDocumentLink]) -> anyhow::Result<()> {
+        if links.is_empty() {
+            return Ok(());
+        }
+        let targets: Vec<Uuid> = links.iter().map(|l| l.target_id).collect();
+        let types: Vec<&str> = links.iter().map(|l| l.link_type.as_str()).collect();
+        let texts: Vec<Option<&str>> = links.iter().map(|l| l.link_text.as_deref()).collect();
+        let starts: Vec<i32> = links.iter().map(|l| l.position_start).collect();
+        let ends: Vec<i32> = links.iter().map(|l| l.position_end).collect();
         sqlx::query(
             r#"INSERT INTO document_links (
                     source_document_id, target_document_id, link_type,
                     link_text, position_start, position_end, created_at, updated_at
-                ) VALUES ($1, $2, $3, $4, $5, $6, now(), now())
+                )
+                SELECT $1, l.target, l.link_type, l.link_text, l.position_start, l.position_end,
+                       now(), now()
+                FROM UNNEST($2::uuid[], $3::text[], $4::text[], $5::int4[], $6::int4[])
+                     AS l(target, link_type, link_text, position_start, position_end)
                 ON CONFLICT (source_document_id, target_document_id, position_start)
                 DO UPDATE SET link_type = EXCLUDED.link_type,
                               link_text = EXCLUDED.link_text,
@@ -75,13 +120,45 @@ impl LinkGraphRepository for SqlxLinkGraphRepository {
             "#,
         )
         .bind(source_id)
-        .bind(target_id)
-        .bind(link_type)
-        .bind(link_text)
-        .bind(position_start)
-        .bind(position_end)
+        .bind(&targets)
+        .bind(&types)
+        .bind(&texts)
+        .bind(&starts)
+        .bind(&ends)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
+
+    async fn replace_block_ids(&self, doc_id: Uuid, block_ids: &[String]) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM document_blocks WHERE document_id = $1")
+            .bind(doc_id)
+            .execute(&mut *tx)
+            .await?;
+        if !block_ids.is_empty() {
+            sqlx::query(
+                r#"INSERT INTO document_blocks (document_id, block_id)
+                   SELECT $1, UNNEST($2::text[])
+                   ON CONFLICT DO NOTHING"#,
+            )
+            .bind(doc_id)
+            .bind(block_ids)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn has_block(&self, doc_id: Uuid, block_id: &str) -> anyhow::Result<bool> {
+        let n = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(1) FROM document_blocks WHERE document_id = $1 AND block_id = $2",
+        )
+        .bind(doc_id)
+        .bind(block_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(n > 0)
+    }
 }
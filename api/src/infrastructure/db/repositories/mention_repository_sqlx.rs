@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::application::ports::mention_repository::{MentionRepository, MentionRow};
+use crate::infrastructure::db::PgPool;
+
+pub struct SqlxMentionRepository {
+    pub pool: PgPool,
+}
+
+impl SqlxMentionRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl MentionRepository for SqlxMentionRepository {
+    async fn replace_mentions(
+        &self,
+        doc_id: Uuid,
+        mentioned_by: Uuid,
+        user_ids: &[Uuid],
+    ) -> anyhow::Result<Vec<Uuid>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "DELETE FROM document_mentions WHERE document_id = $1 AND NOT (user_id = ANY($2))",
+        )
+        .bind(doc_id)
+        .bind(user_ids)
+        .execute(&mut *tx)
+        .await?;
+        let added = sqlx::query_scalar::<_, Uuid>(
+            r#"INSERT INTO document_mentions (document_id, user_id, mentioned_by)
+               SELECT $1, UNNEST($2::uuid[]), $3
+               ON CONFLICT (document_id, user_id) DO NOTHING
+               RETURNING user_id"#,
+        )
+        .bind(doc_id)
+        .bind(user_ids)
+        .bind(mentioned_by)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(added)
+    }
+
+    async fn list_for_user(&self, user_id: Uuid, limit: i64) -> anyhow::Result<Vec<MentionRow>> {
+        let rows = sqlx::query(
+            r#"SELECT m.document_id, d.title, m.mentioned_by, u.name AS mentioned_by_name,
+                      m.created_at
+               FROM document_mentions m
+               JOIN documents d ON d.id = m.document_id
+               LEFT JOIN users u ON u.id = m.mentioned_by
+               WHERE m.user_id = $1
+               ORDER BY m.created_at DESC
+               LIMIT $2"#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| MentionRow {
+                document_id: r.get("document_id"),
+                title: r.get("title"),
+                mentioned_by: r.get("mentioned_by"),
+                mentioned_by_name: r.get("mentioned_by_name"),
+                created_at: r.get("created_at"),
+            })
+            .collect())
+    }
+}
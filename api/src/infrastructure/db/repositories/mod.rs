@@ -1,10 +1,18 @@
 pub mod access_repository_sqlx;
+pub mod comment_repository_sqlx;
 pub mod document_repository_sqlx;
+pub mod document_retention_repository_sqlx;
+pub mod document_user_access_repository_sqlx;
+pub mod document_version_repository_sqlx;
 pub mod files_repository_sqlx;
 pub mod git_repository_sqlx;
 pub mod linkgraph_repository_sqlx;
+pub mod mention_repository_sqlx;
+pub mod notification_repository_sqlx;
+pub mod plugin_exec_log_repository_sqlx;
 pub mod plugin_installation_repository_sqlx;
 pub mod plugin_repository_sqlx;
+pub mod plugin_secret_repository_sqlx;
 pub mod public_repository_sqlx;
 pub mod shares_repository_sqlx;
 pub mod tag_repository_sqlx;
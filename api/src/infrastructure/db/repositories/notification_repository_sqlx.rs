@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use uuid::Uuid;
+
+use crate::application::ports::notification_repository::{
+    NewNotification, NotificationRepository, NotificationRow,
+};
+use crate::infrastructure::db::PgPool;
+
+pub struct SqlxNotificationRepository {
+    pub pool: PgPool,
+}
+
+impl SqlxNotificationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_row(row: PgRow) -> anyhow::Result<NotificationRow> {
+    Ok(NotificationRow {
+        id: row.try_get("id")?,
+        user_id: row.try_get("user_id")?,
+        kind: row.try_get("kind")?,
+        document_id: row.try_get("document_id")?,
+        actor_id: row.try_get("actor_id")?,
+        data: row.try_get("data")?,
+        read_at: row.try_get("read_at")?,
+        created_at: row.try_get("created_at")?,
+    })
+}
+
+#[async_trait]
+impl NotificationRepository for SqlxNotificationRepository {
+    async fn insert(&self, notification: &NewNotification) -> anyhow::Result<NotificationRow> {
+        let row = sqlx::query(
+            r#"INSERT INTO notifications (user_id, kind, document_id, actor_id, data)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING id, user_id, kind, document_id, actor_id, data, read_at, created_at"#,
+        )
+        .bind(notification.user_id)
+        .bind(&notification.kind)
+        .bind(notification.document_id)
+        .bind(notification.actor_id)
+        .bind(&notification.data)
+        .fetch_one(&self.pool)
+        .await?;
+        map_row(row)
+    }
+
+    async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        unread_only: bool,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<NotificationRow>> {
+        let rows = sqlx::query(
+            r#"SELECT id, user_id, kind, document_id, actor_id, data, read_at, created_at
+               FROM notifications
+               WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+               ORDER BY created_at DESC, id
+               LIMIT $3 OFFSET $4"#,
+        )
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.into_iter().map(map_row).collect()
+    }
+
+    async fn count_unread(&self, user_id: Uuid) -> anyhow::Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    async fn mark_read(&self, user_id: Uuid, id: Uuid) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"UPDATE notifications SET read_at = COALESCE(read_at, now())
+               WHERE id = $1 AND user_id = $2"#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::application::ports::plugin_exec_log_repository::{
+    PluginExecLogEntry, PluginExecLogRepository,
+};
+use crate::infrastructure::db::PgPool;
+
+/// Entries kept per user and plugin; older ones are dropped as new ones arrive.
+const MAX_ENTRIES_PER_PLUGIN: i64 = 200;
+
+pub struct SqlxPluginExecLogRepository {
+    pub pool: PgPool,
+}
+
+impl SqlxPluginExecLogRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl PluginExecLogRepository for SqlxPluginExecLogRepository {
+    async fn append(&self, entry: &PluginExecLogEntry) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"INSERT INTO plugin_exec_log
+               (user_id, plugin, function, input_bytes, output_bytes, duration_ms, ok, error, payload, created_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+        )
+        .bind(entry.user_id)
+        .bind(&entry.plugin)
+        .bind(&entry.function)
+        .bind(entry.input_bytes)
+        .bind(entry.output_bytes)
+        .bind(entry.duration_ms)
+        .bind(entry.ok)
+        .bind(&entry.error)
+        .bind(&entry.payload)
+        .bind(entry.created_at)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"DELETE FROM plugin_exec_log
+               WHERE user_id IS NOT DISTINCT FROM $1 AND plugin = $2
+                 AND id NOT IN (
+                   SELECT id FROM plugin_exec_log
+                   WHERE user_id IS NOT DISTINCT FROM $1 AND plugin = $2
+                   ORDER BY created_at DESC, id DESC
+                   LIMIT $3
+                 )"#,
+        )
+        .bind(entry.user_id)
+        .bind(&entry.plugin)
+        .bind(MAX_ENTRIES_PER_PLUGIN)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn recent(
+        &self,
+        user_id: Uuid,
+        plugin: &str,
+        limit: i64,
+    ) -> anyhow::Result<Vec<PluginExecLogEntry>> {
+        let rows = sqlx::query(
+            r#"SELECT user_id, plugin, function, input_bytes, output_bytes, duration_ms, ok, error, payload, created_at
+               FROM plugin_exec_log
+               WHERE user_id = $1 AND plugin = $2
+               ORDER BY created_at DESC, id DESC
+               LIMIT $3"#,
+        )
+        .bind(user_id)
+        .bind(plugin)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            entries.push(PluginExecLogEntry {
+                user_id: row.try_get("user_id")?,
+                plugin: row.try_get("plugin")?,
+                function: row.try_get("function")?,
+                input_bytes: row.try_get("input_bytes")?,
+                output_bytes: row.try_get("output_bytes")?,
+                duration_ms: row.try_get("duration_ms")?,
+                ok: row.try_get("ok")?,
+                error: row.try_get("error")?,
+                payload: row.try_get("payload")?,
+                created_at: row.try_get("created_at")?,
+            });
+        }
+        Ok(entries)
+    }
+}
@@ -38,6 +38,7 @@ impl PluginInstallationRepository for SqlxPluginInstallationRepository {
                  scope = EXCLUDED.scope,
                  origin_url = EXCLUDED.origin_url,
                  status = EXCLUDED.status,
+                 pinned = false,
                  updated_at = now()"#,
         )
         .bind(user_id)
@@ -51,9 +52,30 @@ impl PluginInstallationRepository for SqlxPluginInstallationRepository {
         Ok(())
     }
 
+    async fn set_version(
+        &self,
+        user_id: Uuid,
+        plugin_id: &str,
+        version: &str,
+        pinned: bool,
+    ) -> anyhow::Result<bool> {
+        let res = sqlx::query(
+            r#"UPDATE plugin_installations
+               SET version = $3, pinned = $4, updated_at = now()
+               WHERE user_id = $1 AND plugin_id = $2"#,
+        )
+        .bind(user_id)
+        .bind(plugin_id)
+        .bind(version)
+        .bind(pinned)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected() > 0)
+    }
+
     async fn list_for_user(&self, user_id: Uuid) -> anyhow::Result<Vec<PluginInstallation>> {
         let rows = sqlx::query(
-            r#"SELECT user_id, plugin_id, version, scope, origin_url, status, installed_at, updated_at
+            r#"SELECT user_id, plugin_id, version, scope, origin_url, status, pinned, installed_at, updated_at
                FROM plugin_installations
                WHERE user_id = $1"#,
         )
@@ -70,6 +92,7 @@ impl PluginInstallationRepository for SqlxPluginInstallationRepository {
                 scope: row.get("scope"),
                 origin_url: row.try_get("origin_url").ok(),
                 status: row.get("status"),
+                pinned: row.get("pinned"),
                 installed_at: row.get("installed_at"),
                 updated_at: row.get("updated_at"),
             });
@@ -80,7 +103,7 @@ impl PluginInstallationRepository for SqlxPluginInstallationRepository {
 
     async fn list_all(&self) -> anyhow::Result<Vec<PluginInstallation>> {
         let rows = sqlx::query(
-            r#"SELECT user_id, plugin_id, version, scope, origin_url, status, installed_at, updated_at
+            r#"SELECT user_id, plugin_id, version, scope, origin_url, status, pinned, installed_at, updated_at
                FROM plugin_installations"#,
         )
         .fetch_all(&self.pool)
@@ -95,6 +118,7 @@ impl PluginInstallationRepository for SqlxPluginInstallationRepository {
                 scope: row.get("scope"),
                 origin_url: row.try_get("origin_url").ok(),
                 status: row.get("status"),
+                pinned: row.get("pinned"),
                 installed_at: row.get("installed_at"),
                 updated_at: row.get("updated_at"),
             });
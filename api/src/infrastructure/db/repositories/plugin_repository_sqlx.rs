@@ -3,7 +3,9 @@ use serde_json::Value as JsonValue;
 use sqlx::Row;
 use uuid::Uuid;
 
-use crate::application::ports::plugin_repository::{PluginRecord, PluginRepository};
+use crate::application::ports::plugin_repository::{
+    PluginDataEffect, PluginKvEntry, PluginRecord, PluginRecordImport, PluginRepository,
+};
 use crate::infrastructure::db::PgPool;
 
 pub struct SqlxPluginRepository {
@@ -188,6 +190,199 @@ impl PluginRepository for SqlxPluginRepository {
         Ok(out)
     }
 
+    async fn list_all_records(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Uuid,
+    ) -> anyhow::Result<Vec<PluginRecord>> {
+        let rows = sqlx::query(
+            r#"SELECT id, plugin, scope, scope_id, kind, data, created_at, updated_at
+               FROM plugin_records
+               WHERE plugin = $1 AND scope = $2 AND scope_id = $3
+               ORDER BY kind, created_at"#,
+        )
+        .bind(plugin)
+        .bind(scope)
+        .bind(scope_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| PluginRecord {
+                id: r.get("id"),
+                plugin: r.get("plugin"),
+                scope: r.get("scope"),
+                scope_id: r.get("scope_id"),
+                kind: r.get("kind"),
+                data: r.get("data"),
+                created_at: r.get("created_at"),
+                updated_at: r.get("updated_at"),
+            })
+            .collect())
+    }
+
+    async fn list_kv(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Option<Uuid>,
+    ) -> anyhow::Result<Vec<PluginKvEntry>> {
+        let rows = sqlx::query(
+            r#"SELECT key, value FROM plugin_kv
+               WHERE plugin = $1 AND scope = $2 AND scope_id IS NOT DISTINCT FROM $3
+               ORDER BY key"#,
+        )
+        .bind(plugin)
+        .bind(scope)
+        .bind(scope_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| PluginKvEntry {
+                key: r.get("key"),
+                value: r.get("value"),
+            })
+            .collect())
+    }
+
+    async fn import_scope(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Uuid,
+        records: &[PluginRecordImport],
+        kv: &[PluginKvEntry],
+    ) -> anyhow::Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        for record in records {
+            match record.id {
+                Some(id) => {
+                    // Only overwrite an existing id when it already belongs to this scope.
+                    let res = sqlx::query(
+                        r#"INSERT INTO plugin_records (id, plugin, scope, scope_id, kind, data, created_at)
+                           VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, now()))
+                           ON CONFLICT (id) DO UPDATE
+                           SET kind = EXCLUDED.kind, data = EXCLUDED.data, updated_at = now()
+                           WHERE plugin_records.plugin = EXCLUDED.plugin
+                             AND plugin_records.scope = EXCLUDED.scope
+                             AND plugin_records.scope_id = EXCLUDED.scope_id"#,
+                    )
+                    .bind(id)
+                    .bind(plugin)
+                    .bind(scope)
+                    .bind(scope_id)
+                    .bind(&record.kind)
+                    .bind(&record.data)
+                    .bind(record.created_at)
+                    .execute(&mut *tx)
+                    .await?;
+                    if res.rows_affected() == 0 {
+                        anyhow::bail!("plugin record {} belongs to another scope", id);
+                    }
+                }
+                None => {
+                    sqlx::query(
+                        r#"INSERT INTO plugin_records (plugin, scope, scope_id, kind, data, created_at)
+                           VALUES ($1, $2, $3, $4, $5, COALESCE($6, now()))"#,
+                    )
+                    .bind(plugin)
+                    .bind(scope)
+                    .bind(scope_id)
+                    .bind(&record.kind)
+                    .bind(&record.data)
+                    .bind(record.created_at)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+        }
+        for entry in kv {
+            sqlx::query(
+                r#"INSERT INTO plugin_kv (plugin, scope, scope_id, key, value)
+                   VALUES ($1, $2, $3, $4, $5)
+                   ON CONFLICT (plugin, scope, scope_id, key)
+                   DO UPDATE SET value = EXCLUDED.value, updated_at = now()"#,
+            )
+            .bind(plugin)
+            .bind(scope)
+            .bind(scope_id)
+            .bind(&entry.key)
+            .bind(&entry.value)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(records.len())
+    }
+
+    async fn apply_effects(
+        &self,
+        plugin: &str,
+        effects: &[PluginDataEffect],
+    ) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for effect in effects {
+            match effect {
+                PluginDataEffect::PutKv { doc_id, key, value } => {
+                    sqlx::query(
+                        r#"INSERT INTO plugin_kv (plugin, scope, scope_id, key, value)
+                           VALUES ($1, 'doc', $2, $3, $4)
+                           ON CONFLICT (plugin, scope, scope_id, key)
+                           DO UPDATE SET value = EXCLUDED.value, updated_at = now()"#,
+                    )
+                    .bind(plugin)
+                    .bind(doc_id)
+                    .bind(key)
+                    .bind(value)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                PluginDataEffect::CreateRecord { doc_id, kind, data } => {
+                    sqlx::query(
+                        r#"INSERT INTO plugin_records (plugin, scope, scope_id, kind, data)
+                           VALUES ($1, 'doc', $2, $3, $4)"#,
+                    )
+                    .bind(plugin)
+                    .bind(doc_id)
+                    .bind(kind)
+                    .bind(data)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                PluginDataEffect::UpdateRecord { record_id, patch } => {
+                    let res = sqlx::query(
+                        r#"UPDATE plugin_records SET data = data || $3::jsonb, updated_at = now()
+                           WHERE id = $1 AND plugin = $2"#,
+                    )
+                    .bind(record_id)
+                    .bind(plugin)
+                    .bind(patch)
+                    .execute(&mut *tx)
+                    .await?;
+                    if res.rows_affected() == 0 {
+                        anyhow::bail!("plugin record {} not found", record_id);
+                    }
+                }
+                PluginDataEffect::DeleteRecord { record_id } => {
+                    let res =
+                        sqlx::query("DELETE FROM plugin_records WHERE id = $1 AND plugin = $2")
+                            .bind(record_id)
+                            .bind(plugin)
+                            .execute(&mut *tx)
+                            .await?;
+                    if res.rows_affected() == 0 {
+                        anyhow::bail!("plugin record {} not found", record_id);
+                    }
+                }
+            }
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
     async fn delete_scoped_kv(&self, scope: &str, scope_ids: &[Uuid]) -> anyhow::Result<()> {
         if scope_ids.is_empty() {
             return Ok(());
@@ -212,3 +407,74 @@ impl PluginRepository for SqlxPluginRepository {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::infrastructure::db::{connect_pool, migrate};
+
+    /// Run with `TEST_DATABASE_URL=postgres://... cargo test -- --ignored`.
+    async fn test_pool() -> PgPool {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL is not set");
+        let pool = connect_pool(&url).await.unwrap();
+        migrate(&pool).await.unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Postgres database in TEST_DATABASE_URL"]
+    async fn failing_effect_undoes_the_effects_before_it() {
+        let repo = SqlxPluginRepository::new(test_pool().await);
+        let plugin = format!("effects-{}", Uuid::new_v4().simple());
+        let doc_id = Uuid::new_v4();
+
+        let err = repo
+            .apply_effects(
+                &plugin,
+                &[
+                    PluginDataEffect::PutKv {
+                        doc_id,
+                        key: "status".into(),
+                        value: json!("done"),
+                    },
+                    PluginDataEffect::DeleteRecord {
+                        record_id: Uuid::new_v4(),
+                    },
+                ],
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not found"));
+        assert!(
+            repo.list_kv(&plugin, "doc", Some(doc_id))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        repo.apply_effects(
+            &plugin,
+            &[
+                PluginDataEffect::PutKv {
+                    doc_id,
+                    key: "status".into(),
+                    value: json!("done"),
+                },
+                PluginDataEffect::CreateRecord {
+                    doc_id,
+                    kind: "task".into(),
+                    data: json!({ "title": "Ship" }),
+                },
+            ],
+        )
+        .await
+        .unwrap();
+        let kv = repo.list_kv(&plugin, "doc", Some(doc_id)).await.unwrap();
+        assert_eq!(kv.len(), 1);
+        assert_eq!(kv[0].value, json!("done"));
+        let records = repo.list_all_records(&plugin, "doc", doc_id).await.unwrap();
+        assert_eq!(records.len(), 1);
+    }
+}
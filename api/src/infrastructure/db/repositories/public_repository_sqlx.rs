@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use sqlx::Row;
 use uuid::Uuid;
 
-use crate::application::ports::public_repository::PublicRepository;
+use crate::application::ports::public_repository::{PublicDocumentRow, PublicRepository};
 use crate::infrastructure::db::PgPool;
 
 pub struct SqlxPublicRepository {
@@ -89,23 +89,18 @@ impl PublicRepository for SqlxPublicRepository {
     async fn list_user_public_documents(
         &self,
         owner_name: &str,
-    ) -> anyhow::Result<
-        Vec<(
-            Uuid,
-            String,
-            chrono::DateTime<chrono::Utc>,
-            chrono::DateTime<chrono::Utc>,
-        )>,
-    > {
+        limit: i64,
+    ) -> anyhow::Result<Vec<PublicDocumentRow>> {
         let rows = sqlx::query(
             r#"SELECT d.id, d.title, d.updated_at, p.published_at
                FROM public_documents p
                JOIN documents d ON p.document_id = d.id
                JOIN users u ON d.owner_id = u.id
                WHERE u.name = $1
-               ORDER BY d.updated_at DESC LIMIT 200"#,
+               ORDER BY d.updated_at DESC LIMIT $2"#,
         )
         .bind(owner_name)
+        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
         Ok(rows
@@ -178,4 +173,47 @@ impl PublicRepository for SqlxPublicRepository {
         .await?;
         Ok(n > 0)
     }
+
+    async fn add_public_views(
+        &self,
+        counts: &[(Uuid, chrono::NaiveDate, i64)],
+    ) -> anyhow::Result<()> {
+        if counts.is_empty() {
+            return Ok(());
+        }
+        let docs: Vec<Uuid> = counts.iter().map(|(id, _, _)| *id).collect();
+        let days: Vec<chrono::NaiveDate> = counts.iter().map(|(_, day, _)| *day).collect();
+        let views: Vec<i64> = counts.iter().map(|(_, _, n)| *n).collect();
+        sqlx::query(
+            r#"INSERT INTO public_document_views (document_id, day, views)
+               SELECT v.document_id, v.day, v.views
+               FROM UNNEST($1::uuid[], $2::date[], $3::bigint[]) AS v(document_id, day, views)
+               JOIN documents d ON d.id = v.document_id
+               ON CONFLICT (document_id, day)
+               DO UPDATE SET views = public_document_views.views + EXCLUDED.views"#,
+        )
+        .bind(&docs)
+        .bind(&days)
+        .bind(&views)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn public_view_stats(
+        &self,
+        doc_id: Uuid,
+        since: chrono::NaiveDate,
+    ) -> anyhow::Result<(i64, i64)> {
+        let row = sqlx::query(
+            r#"SELECT COALESCE(SUM(views), 0)::BIGINT AS total,
+                      COALESCE(SUM(views) FILTER (WHERE day >= $2), 0)::BIGINT AS recent
+               FROM public_document_views WHERE document_id = $1"#,
+        )
+        .bind(doc_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok((row.get("total"), row.get("recent")))
+    }
 }
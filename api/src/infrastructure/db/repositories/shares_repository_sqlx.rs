@@ -3,7 +3,9 @@ use sqlx::Row;
 use uuid::Uuid;
 
 use crate::application::ports::share_access_port::ShareAccessPort;
-use crate::application::ports::shares_repository::{ShareRow, SharesRepository};
+use crate::application::ports::shares_repository::{
+    FolderShareGrant, NewShare, ShareLimitReached, ShareRow, SharesRepository,
+};
 use crate::infrastructure::db::PgPool;
 
 pub struct SqlxSharesRepository {
@@ -31,7 +33,7 @@ impl SqlxSharesRepository {
             r#"SELECT s.id as share_id, s.permission, s.expires_at, d.id as shared_id, d.type as shared_type
                FROM shares s
                JOIN documents d ON s.document_id = d.id
-               WHERE s.token = $1"#,
+               WHERE (s.token = $1 OR s.short_code = $1)"#,
         )
         .bind(token)
         .fetch_optional(&self.pool)
@@ -56,6 +58,7 @@ impl SharesRepository for SqlxSharesRepository {
         document_id: Uuid,
         permission: &str,
         expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        short_code: Option<&str>,
     ) -> anyhow::Result<(String, Uuid, String)> {
         // Verify ownership and type
         let dtype: String =
@@ -66,47 +69,87 @@ impl SharesRepository for SqlxSharesRepository {
                 .await?
                 .ok_or_else(|| anyhow::anyhow!("forbidden"))?;
         let token = Uuid::new_v4().to_string();
-        let row = sqlx::query("INSERT INTO shares (document_id, token, permission, created_by, expires_at) VALUES ($1, $2, $3, $4, $5) RETURNING id, token")
+        let row = sqlx::query("INSERT INTO shares (document_id, token, permission, created_by, expires_at, short_code) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id, token")
             .bind(document_id)
             .bind(&token)
             .bind(permission)
             .bind(owner_id)
             .bind(expires_at)
+            .bind(short_code)
             .fetch_one(&self.pool)
             .await?;
         let token_saved: String = row.get("token");
         let share_id: Uuid = row.get("id");
-        if dtype == "folder" {
-            // Materialize per-document shares for folder subtree
-            let _created: i64 = sqlx::query_scalar(
-                r#"
-                WITH RECURSIVE subtree AS (
-                  SELECT id, type FROM documents WHERE id = $1
-                  UNION ALL
-                  SELECT d.id, d.type FROM documents d JOIN subtree sb ON d.parent_id = sb.id
-                ),
-                targets AS (
-                  SELECT id FROM subtree WHERE type <> 'folder'
-                ),
-                inserted AS (
-                  INSERT INTO shares (document_id, token, permission, created_by, expires_at, parent_share_id)
-                  SELECT t.id, gen_random_uuid()::text, $2, $3, $4, $5
-                  FROM targets t
-                  WHERE NOT EXISTS (SELECT 1 FROM shares s2 WHERE s2.document_id = t.id AND s2.created_by = $3)
-                  RETURNING 1
-                )
-                SELECT COALESCE(COUNT(*),0) FROM inserted
-                "#
+        Ok((token_saved, share_id, dtype))
+    }
+
+    async fn create_shares(
+        &self,
+        owner_id: Uuid,
+        shares: &[NewShare],
+        permission: &str,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        max_per_document: usize,
+    ) -> anyhow::Result<Vec<(String, Uuid, String)>> {
+        // Dropping the transaction on any early return rolls the whole batch back.
+        let mut tx = self.pool.begin().await?;
+        let mut out = Vec::with_capacity(shares.len());
+        for share in shares {
+            // The row lock keeps concurrent requests from both passing the limit check.
+            let dtype: String = sqlx::query_scalar(
+                "SELECT type FROM documents WHERE id = $1 AND owner_id = $2 FOR UPDATE",
             )
-            .bind(document_id)
-            .bind(permission)
+            .bind(share.document_id)
             .bind(owner_id)
-            .bind(expires_at)
-            .bind(share_id)
-            .fetch_one(&self.pool)
-            .await?;
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("forbidden"))?;
+            if max_per_document > 0 {
+                let active: i64 = sqlx::query_scalar(
+                    "SELECT COUNT(*) FROM shares WHERE document_id = $1 AND parent_share_id IS NULL AND (expires_at IS NULL OR expires_at > now())",
+                )
+                .bind(share.document_id)
+                .fetch_one(&mut *tx)
+                .await?;
+                if active as usize >= max_per_document {
+                    return Err(ShareLimitReached(max_per_document).into());
+                }
+            }
+            let token = Uuid::new_v4().to_string();
+            let row = sqlx::query("INSERT INTO shares (document_id, token, permission, created_by, expires_at, short_code) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id, token")
+                .bind(share.document_id)
+                .bind(&token)
+                .bind(permission)
+                .bind(owner_id)
+                .bind(expires_at)
+                .bind(share.short_code.as_deref())
+                .fetch_one(&mut *tx)
+                .await?;
+            let share_id: Uuid = row.get("id");
+            if dtype == "folder" {
+                sqlx::query(
+                    r#"
+                    WITH RECURSIVE subtree AS (
+                        SELECT id FROM documents WHERE parent_id = $1
+                        UNION ALL
+                        SELECT d.id FROM documents d JOIN subtree s ON d.parent_id = s.id
+                    )
+                    INSERT INTO shares (document_id, token, permission, created_by, expires_at, parent_share_id)
+                    SELECT id, gen_random_uuid()::text, $2, $3, $4, $5 FROM subtree
+                    "#,
+                )
+                .bind(share.document_id)
+                .bind(permission)
+                .bind(owner_id)
+                .bind(expires_at)
+                .bind(share_id)
+                .execute(&mut *tx)
+                .await?;
+            }
+            out.push((row.get("token"), share_id, dtype));
         }
-        Ok((token_saved, share_id, dtype))
+        tx.commit().await?;
+        Ok(out)
     }
 
     async fn list_document_shares(
@@ -115,7 +158,7 @@ impl SharesRepository for SqlxSharesRepository {
         document_id: Uuid,
     ) -> anyhow::Result<Vec<ShareRow>> {
         let rows = sqlx::query(
-            r#"SELECT s.id, s.token, s.permission, s.expires_at, s.parent_share_id, s.created_at,
+            r#"SELECT s.id, s.token, s.short_code, s.permission, s.expires_at, s.parent_share_id, s.created_at,
                       d.id as document_id, d.title as document_title, d.type as document_type
                FROM shares s JOIN documents d ON d.id = s.document_id
                WHERE s.document_id = $1 AND d.owner_id = $2
@@ -130,6 +173,7 @@ impl SharesRepository for SqlxSharesRepository {
             out.push(ShareRow {
                 id: r.get("id"),
                 token: r.get("token"),
+                short_code: r.try_get("short_code").ok(),
                 permission: r.get("permission"),
                 expires_at: r.try_get("expires_at").ok(),
                 parent_share_id: r.try_get("parent_share_id").ok(),
@@ -143,7 +187,7 @@ impl SharesRepository for SqlxSharesRepository {
     }
 
     async fn delete_share(&self, owner_id: Uuid, token: &str) -> anyhow::Result<bool> {
-        let res = sqlx::query("DELETE FROM shares s USING documents d WHERE s.token = $1 AND s.document_id = d.id AND d.owner_id = $2")
+        let res = sqlx::query("DELETE FROM shares s USING documents d WHERE (s.token = $1 OR s.short_code = $1) AND s.document_id = d.id AND d.owner_id = $2")
             .bind(token)
             .bind(owner_id)
             .execute(&self.pool)
@@ -158,7 +202,7 @@ impl SharesRepository for SqlxSharesRepository {
         let row = sqlx::query(
             r#"SELECT s.document_id, s.permission, s.expires_at, d.title
                FROM shares s JOIN documents d ON d.id = s.document_id
-               WHERE s.token = $1"#,
+               WHERE (s.token = $1 OR s.short_code = $1)"#,
         )
         .bind(token)
         .fetch_optional(&self.pool)
@@ -202,7 +246,7 @@ impl SharesRepository for SqlxSharesRepository {
 
     async fn list_active_shares(&self, owner_id: Uuid) -> anyhow::Result<Vec<ShareRow>> {
         let rows = sqlx::query(
-            r#"SELECT s.id, s.token, s.permission, s.expires_at, s.created_at, s.parent_share_id,
+            r#"SELECT s.id, s.token, s.short_code, s.permission, s.expires_at, s.created_at, s.parent_share_id,
                       d.id as document_id, d.title as document_title, d.type as document_type
                FROM shares s
                JOIN documents d ON d.id = s.document_id
@@ -217,6 +261,7 @@ impl SharesRepository for SqlxSharesRepository {
             out.push(ShareRow {
                 id: r.get("id"),
                 token: r.get("token"),
+                short_code: r.try_get("short_code").ok(),
                 permission: r.get("permission"),
                 expires_at: r.try_get("expires_at").ok(),
                 parent_share_id: r.try_get("parent_share_id").ok(),
@@ -294,11 +339,15 @@ impl SharesRepository for SqlxSharesRepository {
         Ok(ids)
     }
 
-    async fn materialize_folder_share(&self, owner_id: Uuid, token: &str) -> anyhow::Result<i64> {
+    async fn get_folder_share_for_owner(
+        &self,
+        owner_id: Uuid,
+        token: &str,
+    ) -> anyhow::Result<FolderShareGrant> {
         let row = sqlx::query(
             r#"SELECT s.id as share_id, s.permission, s.expires_at, d.id as folder_id, d.owner_id, d.type
                FROM shares s JOIN documents d ON d.id = s.document_id
-               WHERE s.token = $1"#
+               WHERE (s.token = $1 OR s.short_code = $1)"#
         )
         .bind(token)
         .fetch_optional(&self.pool)
@@ -315,39 +364,90 @@ impl SharesRepository for SqlxSharesRepository {
         if dtype != "folder" {
             anyhow::bail!("bad_request");
         }
-        let folder_id: Uuid = row.get("folder_id");
-        let share_id: Uuid = row.get("share_id");
-        let permission: String = row.get("permission");
-        let expires_at: Option<chrono::DateTime<chrono::Utc>> = row.try_get("expires_at").ok();
+        Ok(FolderShareGrant {
+            share_id: row.get("share_id"),
+            folder_id: row.get("folder_id"),
+            permission: row.get("permission"),
+            expires_at: row.try_get("expires_at").ok(),
+            created_by: owner,
+        })
+    }
 
-        let created = sqlx::query_scalar::<_, i64>(
+    async fn list_ancestor_folder_shares(
+        &self,
+        doc_id: Uuid,
+    ) -> anyhow::Result<Vec<FolderShareGrant>> {
+        let rows = sqlx::query(
             r#"
-            WITH RECURSIVE subtree AS (
-              SELECT id, type FROM documents WHERE id = $1
-              UNION ALL
-              SELECT d.id, d.type FROM documents d JOIN subtree sb ON d.parent_id = sb.id
-            ),
-            targets AS (
-              SELECT id FROM subtree WHERE type <> 'folder'
-            ),
-            inserted AS (
-              INSERT INTO shares (document_id, token, permission, created_by, expires_at, parent_share_id)
-              SELECT t.id, gen_random_uuid()::text, $3, $4, $5, $2
-              FROM targets t
-              WHERE NOT EXISTS (SELECT 1 FROM shares s2 WHERE s2.document_id = t.id AND s2.created_by = $4)
-              RETURNING 1
+            WITH RECURSIVE ancestors AS (
+              SELECT parent_id AS id FROM documents WHERE id = $1 AND parent_id IS NOT NULL
+              UNION
+              SELECT d.parent_id FROM documents d JOIN ancestors a ON d.id = a.id
+              WHERE d.parent_id IS NOT NULL
             )
-            SELECT COALESCE(COUNT(*),0) FROM inserted
-            "#
+            SELECT s.id AS share_id, s.document_id AS folder_id, s.permission, s.expires_at, s.created_by
+            FROM shares s
+            JOIN ancestors a ON a.id = s.document_id
+            JOIN documents f ON f.id = s.document_id
+            WHERE f.type = 'folder' AND s.parent_share_id IS NULL
+              AND (s.expires_at IS NULL OR s.expires_at > now())
+            "#,
         )
-        .bind(folder_id)
-        .bind(share_id)
-        .bind(&permission)
-        .bind(owner_id)
-        .bind(expires_at)
-        .fetch_one(&self.pool)
+        .bind(doc_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| FolderShareGrant {
+                share_id: r.get("share_id"),
+                folder_id: r.get("folder_id"),
+                permission: r.get("permission"),
+                expires_at: r.try_get("expires_at").ok(),
+                created_by: r.get("created_by"),
+            })
+            .collect())
+    }
+
+    async fn insert_materialized_shares(
+        &self,
+        grant: &FolderShareGrant,
+        doc_ids: &[Uuid],
+    ) -> anyhow::Result<i64> {
+        if doc_ids.is_empty() {
+            return Ok(0);
+        }
+        let res = sqlx::query(
+            r#"
+            INSERT INTO shares (document_id, token, permission, created_by, expires_at, parent_share_id)
+            SELECT t.id, gen_random_uuid()::text, $2, $3, $4, $5
+            FROM UNNEST($1::uuid[]) AS t(id)
+            WHERE NOT EXISTS (
+              SELECT 1 FROM shares s2 WHERE s2.parent_share_id = $5 AND s2.document_id = t.id
+            )
+            "#,
+        )
+        .bind(doc_ids)
+        .bind(&grant.permission)
+        .bind(grant.created_by)
+        .bind(grant.expires_at)
+        .bind(grant.share_id)
+        .execute(&self.pool)
         .await?;
-        Ok(created)
+        Ok(res.rows_affected() as i64)
+    }
+
+    async fn delete_expired_shares(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> anyhow::Result<u64> {
+        // Children go with their parent through ON DELETE CASCADE.
+        let res = sqlx::query(
+            "DELETE FROM shares WHERE parent_share_id IS NULL AND expires_at IS NOT NULL AND expires_at < $1",
+        )
+        .bind(now)
+        .execute(&self.pool)
+        .await?;
+        Ok(res.rows_affected())
     }
 }
 
@@ -383,3 +483,56 @@ impl ShareAccessPort for SqlxSharesRepository {
         Ok(perm)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::db::{connect_pool, migrate};
+
+    /// Run with `TEST_DATABASE_URL=postgres://... cargo test -- --ignored`.
+    async fn test_pool() -> PgPool {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL is not set");
+        let pool = connect_pool(&url).await.unwrap();
+        migrate(&pool).await.unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Postgres database in TEST_DATABASE_URL"]
+    async fn short_code_is_accepted_wherever_the_token_is() {
+        let pool = test_pool().await;
+        let owner: Uuid = sqlx::query_scalar(
+            "INSERT INTO users (email, name, password_hash) VALUES ($1, 'Owner', '') RETURNING id",
+        )
+        .bind(format!("{}@example.com", Uuid::new_v4()))
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let folder: Uuid = sqlx::query_scalar(
+            "INSERT INTO documents (title, owner_id, type) VALUES ('Shared', $1, 'folder') RETURNING id",
+        )
+        .bind(owner)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let repo = SqlxSharesRepository::new(pool);
+        let code = Uuid::new_v4().simple().to_string()[..12].to_string();
+        let (token, share_id, _) = repo
+            .create_share(owner, folder, "edit", None, Some(&code))
+            .await
+            .unwrap();
+
+        for key in [token.as_str(), code.as_str()] {
+            let validated = repo.validate_share_token(key).await.unwrap();
+            assert_eq!(validated.map(|row| row.0), Some(folder));
+            let resolved = ShareAccessPort::resolve_share_by_token(&repo, key)
+                .await
+                .unwrap();
+            assert_eq!(resolved.map(|row| row.0), Some(share_id));
+            let grant = repo.get_folder_share_for_owner(owner, key).await.unwrap();
+            assert_eq!(grant.share_id, share_id);
+        }
+        assert!(repo.delete_share(owner, &code).await.unwrap());
+        assert!(repo.validate_share_token(&token).await.unwrap().is_none());
+    }
+}
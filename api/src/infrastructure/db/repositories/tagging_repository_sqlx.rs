@@ -1,5 +1,4 @@
 use async_trait::async_trait;
-use sqlx::Row;
 use uuid::Uuid;
 
 use crate::application::ports::tagging_repository::TaggingRepository;
@@ -17,20 +16,47 @@ impl SqlxTaggingRepository {
 
 #[async_trait]
 impl TaggingRepository for SqlxTaggingRepository {
-    async fn clear_document_tags(&self, doc_id: Uuid) -> anyhow::Result<()> {
-        sqlx::query("DELETE FROM document_tags WHERE document_id = $1")
-            .bind(doc_id)
-            .execute(&self.pool)
-            .await?;
+    async fn document_tag_names(&self, doc_id: Uuid) -> anyhow::Result<Vec<String>> {
+        let names = sqlx::query_scalar::<_, String>(
+            r#"SELECT t.name FROM document_tags dt
+               JOIN tags t ON t.id = dt.tag_id
+               WHERE dt.document_id = $1"#,
+        )
+        .bind(doc_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(names)
+    }
+
+    async fn remove_document_tags(&self, doc_id: Uuid, names: &[String]) -> anyhow::Result<()> {
+        if names.is_empty() {
+            return Ok(());
+        }
+        sqlx::query(
+            r#"DELETE FROM document_tags dt
+               USING tags t
+               WHERE dt.tag_id = t.id AND dt.document_id = $1 AND t.name = ANY($2)"#,
+        )
+        .bind(doc_id)
+        .bind(names)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
-    async fn upsert_tag_return_id(&self, name: &str) -> anyhow::Result<i64> {
-        let row = sqlx::query("INSERT INTO tags(name) VALUES ($1) ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name RETURNING id")
-            .bind(name)
-            .fetch_one(&self.pool)
-            .await?;
-        Ok(row.get("id"))
+    async fn upsert_tags_return_ids(&self, names: &[String]) -> anyhow::Result<Vec<i64>> {
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids = sqlx::query_scalar::<_, i64>(
+            r#"INSERT INTO tags(name) SELECT UNNEST($1::text[])
+               ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+               RETURNING id"#,
+        )
+        .bind(names)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
     }
 
     async fn owner_doc_exists(&self, doc_id: Uuid, owner_id: Uuid) -> anyhow::Result<bool> {
@@ -44,12 +70,19 @@ impl TaggingRepository for SqlxTaggingRepository {
         Ok(n > 0)
     }
 
-    async fn associate_document_tag(&self, doc_id: Uuid, tag_id: i64) -> anyhow::Result<()> {
-        sqlx::query("INSERT INTO document_tags(document_id, tag_id) VALUES ($1, $2)")
-            .bind(doc_id)
-            .bind(tag_id)
-            .execute(&self.pool)
-            .await?;
+    async fn associate_document_tags(&self, doc_id: Uuid, tag_ids: &[i64]) -> anyhow::Result<()> {
+        if tag_ids.is_empty() {
+            return Ok(());
+        }
+        sqlx::query(
+            r#"INSERT INTO document_tags(document_id, tag_id)
+               SELECT $1, UNNEST($2::bigint[])
+               ON CONFLICT DO NOTHING"#,
+        )
+        .bind(doc_id)
+        .bind(tag_ids)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 }
@@ -67,6 +67,28 @@ impl UserRepository for SqlxUserRepository {
         }))
     }
 
+    async fn find_by_names_or_emails(&self, keys: &[String]) -> anyhow::Result<Vec<UserRow>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let rows = sqlx::query(
+            r#"SELECT id, email, name FROM users
+               WHERE lower(email::text) = ANY($1) OR lower(name) = ANY($1)"#,
+        )
+        .bind(keys)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|r| UserRow {
+                id: r.get("id"),
+                email: r.get("email"),
+                name: r.get("name"),
+                password_hash: None,
+            })
+            .collect())
+    }
+
     async fn delete_user(&self, id: Uuid) -> anyhow::Result<bool> {
         let res = sqlx::query("DELETE FROM users WHERE id = $1")
             .bind(id)
@@ -74,4 +96,21 @@ impl UserRepository for SqlxUserRepository {
             .await?;
         Ok(res.rows_affected() > 0)
     }
+
+    async fn home_document(&self, user_id: Uuid) -> anyhow::Result<Option<Uuid>> {
+        let row = sqlx::query("SELECT home_document_id FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.and_then(|r| r.get("home_document_id")))
+    }
+
+    async fn set_home_document(&self, user_id: Uuid, doc_id: Option<Uuid>) -> anyhow::Result<()> {
+        sqlx::query("UPDATE users SET home_document_id = $2, updated_at = now() WHERE id = $1")
+            .bind(user_id)
+            .bind(doc_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
 }
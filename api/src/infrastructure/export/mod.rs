@@ -0,0 +1,3 @@
+pub mod pdf_writer;
+#[cfg(feature = "weasyprint")]
+pub mod weasyprint;
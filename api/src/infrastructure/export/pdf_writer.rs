@@ -0,0 +1,749 @@
+use async_trait::async_trait;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+
+use std::collections::BTreeSet;
+
+use crate::application::ports::pdf_renderer::{PdfRenderer, UnsupportedPdfText};
+
+// A4 in points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const BODY_SIZE: f32 = 11.0;
+const CODE_SIZE: f32 = 9.5;
+const LINE_SPACING: f32 = 1.4;
+// Average glyph widths as a fraction of the font size, used for line wrapping.
+const HELVETICA_WIDTH: f32 = 0.52;
+const COURIER_WIDTH: f32 = 0.6;
+
+/// Dependency-free PDF writer for exported notes. It understands the subset of
+/// HTML produced by the markdown renderer: headings, paragraphs, lists, code
+/// blocks, rules and JPEG/PNG images inlined as data URIs. Text is set in the
+/// standard Type 1 fonts, so only WinAnsi characters can be exported; other
+/// scripts need the `weasyprint` renderer.
+pub struct BuiltinPdfRenderer;
+
+#[async_trait]
+impl PdfRenderer for BuiltinPdfRenderer {
+    async fn render_pdf(&self, html: &str) -> anyhow::Result<Vec<u8>> {
+        let html = html.to_string();
+        let pdf = tokio::task::spawn_blocking(move || html_to_pdf(&html))
+            .await
+            .map_err(|e| anyhow::anyhow!(e))??;
+        Ok(pdf)
+    }
+}
+
+pub fn html_to_pdf(html: &str) -> Result<Vec<u8>, UnsupportedPdfText> {
+    let blocks = parse_blocks(html);
+    let unsupported: BTreeSet<char> = blocks
+        .iter()
+        .filter_map(Block::text)
+        .flat_map(str::chars)
+        .filter(|&c| !c.is_control() && win_ansi(c).is_none())
+        .collect();
+    if !unsupported.is_empty() {
+        return Err(UnsupportedPdfText(unsupported.into_iter().collect()));
+    }
+    let mut layout = Layout::new();
+    for block in &blocks {
+        layout.place(block);
+    }
+    Ok(layout.finish())
+}
+
+#[derive(Debug)]
+enum Block {
+    Heading(u8, String),
+    Paragraph(String),
+    ListItem(String),
+    Code(String),
+    Image(Image),
+    Rule,
+}
+
+impl Block {
+    fn text(&self) -> Option<&str> {
+        match self {
+            Block::Heading(_, text)
+            | Block::Paragraph(text)
+            | Block::ListItem(text)
+            | Block::Code(text) => Some(text),
+            Block::Image(_) | Block::Rule => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Image {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+    dict: String,
+}
+
+#[derive(Default)]
+struct BlockParser {
+    blocks: Vec<Block>,
+    text: String,
+    heading: Option<u8>,
+    list_depth: usize,
+    in_pre: bool,
+    skip_until: Option<String>,
+}
+
+impl BlockParser {
+    fn flush(&mut self) {
+        let text = std::mem::take(&mut self.text);
+        if self.in_pre {
+            let code = text.trim_end_matches('\n').to_string();
+            if !code.is_empty() {
+                self.blocks.push(Block::Code(code));
+            }
+            return;
+        }
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if collapsed.is_empty() {
+            return;
+        }
+        let block = match self.heading {
+            Some(level) => Block::Heading(level, collapsed),
+            None if self.list_depth > 0 => Block::ListItem(collapsed),
+            None => Block::Paragraph(collapsed),
+        };
+        self.blocks.push(block);
+    }
+
+    fn push_text(&mut self, raw: &str) {
+        if self.skip_until.is_some() {
+            return;
+        }
+        self.text.push_str(&decode_entities(raw));
+    }
+
+    fn tag(&mut self, tag: &str) {
+        let closing = tag.starts_with('/');
+        let body = tag.trim_start_matches('/').trim_end_matches('/');
+        let name = body
+            .split(|c: char| c.is_whitespace())
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+
+        if let Some(until) = &self.skip_until {
+            if closing && *until == name {
+                self.skip_until = None;
+            }
+            return;
+        }
+
+        match name.as_str() {
+            "head" | "script" | "style" | "svg" if !closing => self.skip_until = Some(name.clone()),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.flush();
+                self.heading = if closing {
+                    None
+                } else {
+                    name[1..].parse().ok()
+                };
+            }
+            "ul" | "ol" => {
+                self.flush();
+                if closing {
+                    self.list_depth = self.list_depth.saturating_sub(1);
+                } else {
+                    self.list_depth += 1;
+                }
+            }
+            "pre" => {
+                self.flush();
+                self.in_pre = !closing;
+            }
+            "p" | "li" | "div" | "blockquote" | "tr" | "table" | "section" => self.flush(),
+            "br" => self.text.push('\n'),
+            "td" | "th" if closing => self.text.push_str("  "),
+            "hr" => {
+                self.flush();
+                self.blocks.push(Block::Rule);
+            }
+            "img" if !closing => {
+                self.flush();
+                let src = attribute(body, "src").unwrap_or_default();
+                match decode_image(&src) {
+                    Some(image) => self.blocks.push(Block::Image(image)),
+                    None => {
+                        let alt = attribute(body, "alt").unwrap_or_default();
+                        let label = if alt.is_empty() { src } else { alt };
+                        self.blocks
+                            .push(Block::Paragraph(format!("[image: {}]", label)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn parse_blocks(html: &str) -> Vec<Block> {
+    let mut parser = BlockParser::default();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        parser.push_text(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('>') else {
+            parser.push_text(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let tag = &after[..end];
+        if !tag.starts_with('!') {
+            parser.tag(tag);
+        }
+        rest = &after[end + 1..];
+    }
+    parser.push_text(rest);
+    parser.flush();
+    parser.blocks
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let mut search_from = 0;
+    while let Some(pos) = lower[search_from..].find(name) {
+        let idx = search_from + pos;
+        search_from = idx + name.len();
+        let preceded_by_space = idx > 0 && lower.as_bytes()[idx - 1].is_ascii_whitespace();
+        let value = tag[idx + name.len()..].trim_start();
+        if !preceded_by_space || !value.starts_with('=') {
+            continue;
+        }
+        let value = value[1..].trim_start();
+        let quote = value.chars().next()?;
+        let out = if quote == '"' || quote == '\'' {
+            let inner = &value[1..];
+            &inner[..inner.find(quote)?]
+        } else {
+            value.split_whitespace().next().unwrap_or("")
+        };
+        return Some(decode_entities(out));
+    }
+    None
+}
+
+fn decode_entities(s: &str) -> String {
+    if !s.contains('&') {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let tail = &rest[amp..];
+        let Some(semi) = tail.find(';').filter(|i| *i <= 10) else {
+            out.push('&');
+            rest = &tail[1..];
+            continue;
+        };
+        let entity = &tail[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(ch) => {
+                out.push(ch);
+                rest = &tail[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &tail[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_image(src: &str) -> Option<Image> {
+    let data_uri = src.strip_prefix("data:")?;
+    let (meta, payload) = data_uri.split_once(',')?;
+    if !meta.ends_with(";base64") {
+        return None;
+    }
+    let bytes = BASE64_STANDARD.decode(payload.trim()).ok()?;
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        jpeg_image(bytes)
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        png_image(&bytes)
+    } else {
+        None
+    }
+}
+
+fn jpeg_image(data: Vec<u8>) -> Option<Image> {
+    let mut i = 2;
+    while i + 9 < data.len() {
+        if data[i] != 0xFF {
+            i += 1;
+            continue;
+        }
+        let marker = data[i + 1];
+        let len = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        let is_sof = (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+        if is_sof {
+            let height = u16::from_be_bytes([data[i + 5], data[i + 6]]) as u32;
+            let width = u16::from_be_bytes([data[i + 7], data[i + 8]]) as u32;
+            let color_space = match data[i + 9] {
+                1 => "/DeviceGray",
+                4 => "/DeviceCMYK",
+                _ => "/DeviceRGB",
+            };
+            return Some(Image {
+                width,
+                height,
+                dict: format!(
+                    "/ColorSpace {} /BitsPerComponent 8 /Filter /DCTDecode",
+                    color_space
+                ),
+                data,
+            });
+        }
+        i += 2 + len;
+    }
+    None
+}
+
+// PNG scanlines are already a zlib stream with per-row filters, which PDF can
+// decode directly through the PNG predictor; only 8-bit gray/RGB without
+// interlacing or alpha is supported this way.
+fn png_image(data: &[u8]) -> Option<Image> {
+    let mut i = 8;
+    let mut header: Option<(u32, u32, u8, u8, u8)> = None;
+    let mut idat = Vec::new();
+    while i + 8 <= data.len() {
+        let len = u32::from_be_bytes(data[i..i + 4].try_into().ok()?) as usize;
+        let kind = &data[i + 4..i + 8];
+        let body = data.get(i + 8..i + 8 + len)?;
+        match kind {
+            b"IHDR" if len >= 13 => {
+                header = Some((
+                    u32::from_be_bytes(body[0..4].try_into().ok()?),
+                    u32::from_be_bytes(body[4..8].try_into().ok()?),
+                    body[8],
+                    body[9],
+                    body[12],
+                ));
+            }
+            b"IDAT" => idat.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        i += 12 + len;
+    }
+    let (width, height, depth, color_type, interlace) = header?;
+    let (colors, color_space) = match color_type {
+        0 => (1, "/DeviceGray"),
+        2 => (3, "/DeviceRGB"),
+        _ => return None,
+    };
+    if depth != 8 || interlace != 0 || idat.is_empty() {
+        return None;
+    }
+    Some(Image {
+        width,
+        height,
+        dict: format!(
+            "/ColorSpace {} /BitsPerComponent 8 /Filter /FlateDecode \
+             /DecodeParms << /Predictor 15 /Colors {} /BitsPerComponent 8 /Columns {} >>",
+            color_space, colors, width
+        ),
+        data: idat,
+    })
+}
+
+struct Layout {
+    pages: Vec<String>,
+    current: String,
+    y: f32,
+    images: Vec<(Vec<u8>, String, u32, u32)>,
+    page_images: Vec<Vec<usize>>,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            current: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+            images: Vec::new(),
+            page_images: vec![Vec::new()],
+        }
+    }
+
+    fn page_is_empty(&self) -> bool {
+        self.current.is_empty()
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(std::mem::take(&mut self.current));
+        self.page_images.push(Vec::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN && !self.page_is_empty() {
+            self.new_page();
+        }
+    }
+
+    fn place(&mut self, block: &Block) {
+        match block {
+            Block::Heading(level, text) => {
+                let size = match level {
+                    1 => 22.0,
+                    2 => 18.0,
+                    3 => 15.0,
+                    _ => 13.0,
+                };
+                // Top-level sections start on a fresh page; others must at
+                // least keep a few body lines below them.
+                if *level <= 2 && !self.page_is_empty() {
+                    self.new_page();
+                } else {
+                    self.ensure_space(size * LINE_SPACING + BODY_SIZE * LINE_SPACING * 3.0);
+                }
+                self.y -= size * 0.4;
+                self.text_lines("F2", size, HELVETICA_WIDTH, MARGIN, text);
+                self.y -= size * 0.3;
+            }
+            Block::Paragraph(text) => {
+                self.text_lines("F1", BODY_SIZE, HELVETICA_WIDTH, MARGIN, text);
+                self.y -= BODY_SIZE * 0.6;
+            }
+            Block::ListItem(text) => {
+                let bulleted = format!("\u{2022} {}", text);
+                self.text_lines("F1", BODY_SIZE, HELVETICA_WIDTH, MARGIN + 14.0, &bulleted);
+                self.y -= BODY_SIZE * 0.3;
+            }
+            Block::Code(code) => {
+                for line in code.lines() {
+                    self.text_lines("F3", CODE_SIZE, COURIER_WIDTH, MARGIN + 8.0, line);
+                }
+                self.y -= BODY_SIZE * 0.6;
+            }
+            Block::Rule => {
+                self.ensure_space(BODY_SIZE);
+                self.y -= BODY_SIZE * 0.5;
+                self.current.push_str(&format!(
+                    "0.7 G {:.2} {:.2} m {:.2} {:.2} l S 0 G\n",
+                    MARGIN,
+                    self.y,
+                    PAGE_WIDTH - MARGIN,
+                    self.y
+                ));
+                self.y -= BODY_SIZE * 0.5;
+            }
+            Block::Image(image) => self.image(image),
+        }
+    }
+
+    fn text_lines(&mut self, font: &str, size: f32, glyph: f32, x: f32, text: &str) {
+        let max_chars = (((PAGE_WIDTH - MARGIN - x) / (size * glyph)) as usize).max(8);
+        let line_height = size * LINE_SPACING;
+        for line in wrap(text, max_chars) {
+            self.ensure_space(line_height);
+            self.y -= line_height;
+            self.current.push_str(&format!(
+                "BT /{} {:.1} Tf {:.2} {:.2} Td ({}) Tj ET\n",
+                font,
+                size,
+                x,
+                self.y,
+                pdf_string(&line)
+            ));
+        }
+    }
+
+    fn image(&mut self, image: &Image) {
+        let max_width = PAGE_WIDTH - 2.0 * MARGIN;
+        let max_height = PAGE_HEIGHT - 2.0 * MARGIN;
+        let (mut w, mut h) = (image.width as f32, image.height as f32);
+        if w <= 0.0 || h <= 0.0 {
+            return;
+        }
+        let scale = (max_width / w).min(max_height / h).min(1.0);
+        w *= scale;
+        h *= scale;
+        self.ensure_space(h);
+        self.y -= h;
+        let index = self.images.len();
+        self.images.push((
+            image.data.clone(),
+            image.dict.clone(),
+            image.width,
+            image.height,
+        ));
+        if let Some(used) = self.page_images.last_mut() {
+            used.push(index);
+        }
+        self.current.push_str(&format!(
+            "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im{} Do Q\n",
+            w, h, MARGIN, self.y, index
+        ));
+        self.y -= BODY_SIZE * 0.6;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if !self.current.is_empty() || self.pages.is_empty() {
+            self.pages.push(std::mem::take(&mut self.current));
+        }
+
+        // Object numbers: 1 catalog, 2 page tree, 3-5 fonts, then images,
+        // then a content stream and page object per page.
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        let image_base = 6;
+        let page_base = image_base + self.images.len();
+        let page_ids: Vec<usize> = (0..self.pages.len())
+            .map(|i| page_base + i * 2 + 1)
+            .collect();
+
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        let kids = page_ids
+            .iter()
+            .map(|id| format!("{} 0 R", id))
+            .collect::<Vec<_>>()
+            .join(" ");
+        objects.push(
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids,
+                page_ids.len()
+            )
+            .into_bytes(),
+        );
+        for font in ["Helvetica", "Helvetica-Bold", "Courier"] {
+            objects.push(
+                format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                    font
+                )
+                .into_bytes(),
+            );
+        }
+        for (data, dict, width, height) in &self.images {
+            let mut obj = format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} {} /Length {} >>\nstream\n",
+                width,
+                height,
+                dict,
+                data.len()
+            )
+            .into_bytes();
+            obj.extend_from_slice(data);
+            obj.extend_from_slice(b"\nendstream");
+            objects.push(obj);
+        }
+        for (page, used) in self.pages.iter().zip(self.page_images.iter()) {
+            let content = page.as_bytes();
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend_from_slice(content);
+            stream.extend_from_slice(b"\nendstream");
+            let content_id = objects.len() + 1;
+            objects.push(stream);
+
+            let xobjects = used
+                .iter()
+                .map(|i| format!("/Im{} {} 0 R", i, image_base + i))
+                .collect::<Vec<_>>()
+                .join(" ");
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> /XObject << {} >> >> \
+                     /Contents {} 0 R >>",
+                    PAGE_WIDTH, PAGE_HEIGHT, xobjects, content_id
+                )
+                .into_bytes(),
+            );
+        }
+
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, obj) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(obj);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref_at = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n", objects.len() + 1).as_bytes());
+        out.extend_from_slice(b"0000000000 65535 f \n");
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref_at
+            )
+            .as_bytes(),
+        );
+        out
+    }
+}
+
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let mut word = word.to_string();
+            while word.chars().count() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                let split_at = word
+                    .char_indices()
+                    .nth(max_chars)
+                    .map(|(i, _)| i)
+                    .unwrap_or(word.len());
+                lines.push(word[..split_at].to_string());
+                word = word[split_at..].to_string();
+            }
+            let needed =
+                line.chars().count() + usize::from(!line.is_empty()) + word.chars().count();
+            if needed > max_chars && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// The WinAnsi code of `ch`, if it has one.
+fn win_ansi(ch: char) -> Option<u8> {
+    let byte = match ch {
+        '\u{2022}' => 0x95,
+        '\u{2013}' => 0x96,
+        '\u{2014}' => 0x97,
+        '\u{2018}' => 0x91,
+        '\u{2019}' => 0x92,
+        '\u{201C}' => 0x93,
+        '\u{201D}' => 0x94,
+        '\u{20AC}' => 0x80,
+        c if (0x20..0x7F).contains(&(c as u32)) => c as u8,
+        c if (0xA0..=0xFF).contains(&(c as u32)) => c as u32 as u8,
+        _ => return None,
+    };
+    Some(byte)
+}
+
+/// Escapes `text` as a PDF literal string in WinAnsi encoding. `html_to_pdf`
+/// rejects documents with characters outside WinAnsi before layout, so only
+/// control characters are dropped here.
+fn pdf_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        let byte = match ch {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(ch);
+                continue;
+            }
+            '\t' => b' ',
+            c => match win_ansi(c) {
+                Some(byte) => byte,
+                None => continue,
+            },
+        };
+        if byte.is_ascii() {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("\\{:03o}", byte));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page_count(pdf: &[u8]) -> usize {
+        String::from_utf8_lossy(pdf)
+            .matches("/Type /Page /Parent")
+            .count()
+    }
+
+    #[test]
+    fn produces_valid_pdf_for_small_document() {
+        let html = "<h1>Title</h1><p>Hello <strong>world</strong> &amp; friends</p>\
+                    <ul><li>one</li><li>two</li></ul><pre><code>fn main() {}</code></pre>";
+        let pdf = html_to_pdf(html).unwrap();
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+        assert_eq!(page_count(&pdf), 1);
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("(Hello world & friends) Tj"));
+        assert!(text.contains("(fn main\\(\\) {}) Tj"));
+    }
+
+    #[test]
+    fn top_level_headings_start_new_pages() {
+        let pdf =
+            html_to_pdf("<h1>One</h1><p>a</p><h2>Two</h2><p>b</p><h3>Three</h3><p>c</p>").unwrap();
+        assert_eq!(page_count(&pdf), 2);
+    }
+
+    #[test]
+    fn embeds_jpeg_data_uri_images() {
+        // Minimal SOF0 header: 2x3 px, 3 components.
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00, 0x03, 0x00, 0x02, 0x03, 0x01, 0x22,
+            0x00, 0x02, 0x11, 0x01, 0x03, 0x11, 0x01, 0xFF, 0xD9,
+        ];
+        let html = format!(
+            "<p><img src=\"data:image/jpeg;base64,{}\" alt=\"pic\"></p>",
+            BASE64_STANDARD.encode(jpeg)
+        );
+        let pdf = html_to_pdf(&html).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/Subtype /Image /Width 2 /Height 3"));
+        assert!(text.contains("/Im0 Do"));
+    }
+
+    #[test]
+    fn latin_1_text_is_encoded_in_win_ansi() {
+        let pdf = html_to_pdf("<p>Caf\u{e9} \u{2014} na\u{ef}ve \u{20ac}5</p>").unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("(Caf\\351 \\227 na\\357ve \\2005) Tj"));
+    }
+
+    #[test]
+    fn non_latin_text_is_rejected_instead_of_dropped() {
+        let html = "<h1>\u{65e5}\u{672c}\u{8a9e}</h1><p>\u{41f}\u{440}\u{438}\u{432}\u{435}\u{442} \u{3b1}\u{3b2}</p>\
+                    <ul><li>ok \u{1f600}</li></ul>";
+        let err = html_to_pdf(html).unwrap_err();
+        for ch in ['\u{65e5}', '\u{41f}', '\u{3b1}', '\u{1f600}'] {
+            assert!(err.0.contains(&ch), "{:?} not reported", ch);
+        }
+        assert!(!err.0.contains(&'o'));
+    }
+}
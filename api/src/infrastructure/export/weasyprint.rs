@@ -0,0 +1,54 @@
+use std::process::Stdio;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::application::ports::pdf_renderer::PdfRenderer;
+
+/// Converts HTML with an external `weasyprint` binary, which honours the
+/// print stylesheet (page breaks, margins) fully.
+pub struct WeasyprintPdfRenderer {
+    binary: String,
+}
+
+impl WeasyprintPdfRenderer {
+    pub fn new(binary: impl Into<String>) -> Self {
+        Self {
+            binary: binary.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl PdfRenderer for WeasyprintPdfRenderer {
+    async fn render_pdf(&self, html: &str) -> anyhow::Result<Vec<u8>> {
+        let mut child = Command::new(&self.binary)
+            .args(["--encoding", "utf-8", "-", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("spawn {}", self.binary))?;
+
+        let mut stdin = child.stdin.take().context("weasyprint_stdin")?;
+        let input = html.as_bytes().to_vec();
+        let writer = tokio::spawn(async move {
+            stdin.write_all(&input).await?;
+            stdin.shutdown().await
+        });
+
+        let output = child.wait_with_output().await.context("weasyprint_wait")?;
+        writer.await.context("weasyprint_stdin_join")??;
+        if !output.status.success() {
+            anyhow::bail!(
+                "weasyprint exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(output.stdout)
+    }
+}
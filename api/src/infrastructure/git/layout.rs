@@ -0,0 +1,323 @@
+//! Maps documents to paths in the user's git repository.
+//!
+//! Under the storage layout a repository path is the document's storage path without the
+//! owner prefix. The hierarchy layout derives paths from the folder tree and titles instead:
+//! every segment is slugified and siblings that slugify alike get `-2`, `-3`, ... in creation
+//! order, so two documents never share a path. The paths of the last commit are recorded and
+//! reused, which keeps a path stable until its document is renamed or moved; a rename then
+//! shows up in git as a moved file. Storage itself is unaffected.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::anyhow;
+use uuid::Uuid;
+
+use crate::application::ports::git_workspace::FOLDER_MARKER;
+
+const MAX_SLUG_CHARS: usize = 80;
+
+#[derive(Debug, Clone)]
+pub struct LayoutNode {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub title: String,
+    pub is_folder: bool,
+}
+
+/// Repository paths for one user's documents. Empty under the storage layout.
+#[derive(Debug, Default)]
+pub struct RepoLayout {
+    paths: HashMap<Uuid, String>,
+}
+
+impl RepoLayout {
+    /// Paths by folder hierarchy and title. `nodes` must be in creation order; it decides
+    /// which of two colliding siblings keeps the plain name. A path in `persisted` (from the
+    /// last commit) is kept while the document's folder and title still produce it, so a
+    /// suffixed name does not shift when its sibling goes away.
+    pub fn hierarchy(nodes: &[LayoutNode], persisted: &HashMap<Uuid, String>) -> Self {
+        let folders: HashSet<Uuid> = nodes.iter().filter(|n| n.is_folder).map(|n| n.id).collect();
+        let mut children: HashMap<Option<Uuid>, Vec<&LayoutNode>> = HashMap::new();
+        for node in nodes {
+            // Documents whose parent is gone or not a folder are placed at the root.
+            let parent = node
+                .parent_id
+                .filter(|p| *p != node.id && folders.contains(p));
+            children.entry(parent).or_default().push(node);
+        }
+
+        let mut paths = HashMap::new();
+        let mut dirs = vec![(None, String::new())];
+        while let Some((parent, dir)) = dirs.pop() {
+            let Some(siblings) = children.get(&parent) else {
+                continue;
+            };
+            let join = |name: &str| {
+                if dir.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{dir}/{name}")
+                }
+            };
+            let mut taken = HashSet::new();
+            let mut unplaced = Vec::new();
+            for node in siblings {
+                let (slug, ext) = (slugify(&node.title), extension(node));
+                let kept = persisted
+                    .get(&node.id)
+                    .and_then(|path| path.strip_prefix(&join("")))
+                    .filter(|name| fits(name, &slug, ext) && !taken.contains(*name));
+                match kept {
+                    Some(name) => {
+                        taken.insert(name.to_string());
+                        paths.insert(node.id, join(name));
+                    }
+                    None => unplaced.push((node, slug, ext)),
+                }
+            }
+            for (node, slug, ext) in unplaced {
+                let mut name = format!("{slug}{ext}");
+                let mut n = 1;
+                while taken.contains(&name) {
+                    n += 1;
+                    name = format!("{slug}-{n}{ext}");
+                }
+                paths.insert(node.id, join(&name));
+                taken.insert(name);
+            }
+            for node in siblings.iter().filter(|n| n.is_folder) {
+                dirs.push((Some(node.id), paths[&node.id].clone()));
+            }
+        }
+        Self { paths }
+    }
+
+    /// Every mapped path by document or folder id.
+    pub fn paths(&self) -> &HashMap<Uuid, String> {
+        &self.paths
+    }
+
+    /// Repository path of a document stored at `storage_path` (relative to the uploads root).
+    pub fn document_path(&self, doc_id: Uuid, storage_path: &str) -> anyhow::Result<String> {
+        match self.paths.get(&doc_id) {
+            Some(path) => Ok(path.clone()),
+            None => repo_relative_path(storage_path),
+        }
+    }
+
+    /// Repository path of the marker file of folder `folder_id`, whose own directory in
+    /// storage is `storage_dir`.
+    pub fn folder_marker_path(&self, folder_id: Uuid, storage_dir: &str) -> anyhow::Result<String> {
+        match self.paths.get(&folder_id) {
+            Some(dir) => Ok(format!("{dir}/{FOLDER_MARKER}")),
+            None => repo_relative_path(&format!(
+                "{}/{FOLDER_MARKER}",
+                storage_dir.trim_end_matches('/')
+            )),
+        }
+    }
+
+    /// Repository path of an attachment of `doc_id`, kept in an `attachments/` directory next
+    /// to the document so relative links from its markdown still resolve.
+    pub fn attachment_path(&self, doc_id: Uuid, storage_path: &str) -> anyhow::Result<String> {
+        let Some(doc_path) = self.paths.get(&doc_id) else {
+            return repo_relative_path(storage_path);
+        };
+        let file_name = storage_path
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| anyhow!("invalid storage path for repository: {storage_path}"))?;
+        Ok(match doc_path.rsplit_once('/') {
+            Some((dir, _)) => format!("{dir}/attachments/{file_name}"),
+            None => format!("attachments/{file_name}"),
+        })
+    }
+}
+
+/// A storage path relative to the uploads root, without its leading owner directory.
+pub fn repo_relative_path(path: &str) -> anyhow::Result<String> {
+    let trimmed = path.trim_start_matches('/');
+    let mut parts = trimmed.splitn(2, '/');
+    let leading = parts.next().unwrap_or("");
+    if let Some(rest) = parts.next() {
+        Ok(rest.to_string())
+    } else if !leading.is_empty() {
+        Ok(leading.to_string())
+    } else {
+        Err(anyhow!("invalid storage path for repository: {path}"))
+    }
+}
+
+fn extension(node: &LayoutNode) -> &'static str {
+    if node.is_folder { "" } else { ".md" }
+}
+
+/// Whether `name` is one `hierarchy` could assign for `slug`: the plain name or a `-N` variant.
+fn fits(name: &str, slug: &str, ext: &str) -> bool {
+    if name.contains('/') {
+        return false;
+    }
+    let Some(rest) = name.strip_suffix(ext) else {
+        return false;
+    };
+    rest == slug
+        || rest
+            .strip_prefix(slug)
+            .and_then(|r| r.strip_prefix('-'))
+            .and_then(|n| n.parse::<u32>().ok())
+            .is_some_and(|n| n >= 2)
+}
+
+fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for ch in title.trim().chars().flat_map(char::to_lowercase) {
+        if ch.is_alphanumeric() || ch == '_' {
+            slug.push(ch);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug
+        .trim_end_matches('-')
+        .chars()
+        .take(MAX_SLUG_CHARS)
+        .collect();
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "untitled".into()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(parent_id: Option<Uuid>, title: &str, is_folder: bool) -> LayoutNode {
+        LayoutNode {
+            id: Uuid::new_v4(),
+            parent_id,
+            title: title.into(),
+            is_folder,
+        }
+    }
+
+    fn hierarchy(nodes: &[LayoutNode]) -> RepoLayout {
+        RepoLayout::hierarchy(nodes, &HashMap::new())
+    }
+
+    #[test]
+    fn paths_follow_the_folder_hierarchy() {
+        let projects = node(None, "Projects", true);
+        let refmd = node(Some(projects.id), "RefMD / 2025", true);
+        let plan = node(Some(refmd.id), "Release Plan: v2?", false);
+        let inbox = node(None, "Inbox", false);
+        let layout = hierarchy(&[projects.clone(), refmd.clone(), plan.clone(), inbox.clone()]);
+
+        let path = |n: &LayoutNode| layout.document_path(n.id, "ignored").unwrap();
+        assert_eq!(path(&plan), "projects/refmd-2025/release-plan-v2.md");
+        assert_eq!(path(&inbox), "inbox.md");
+        assert_eq!(path(&refmd), "projects/refmd-2025");
+        assert_eq!(
+            layout
+                .folder_marker_path(refmd.id, "owner/Projects/RefMD_-_2025")
+                .unwrap(),
+            "projects/refmd-2025/.gitkeep"
+        );
+        assert_eq!(
+            layout
+                .attachment_path(plan.id, "owner/Projects/attachments/chart.png")
+                .unwrap(),
+            "projects/refmd-2025/attachments/chart.png"
+        );
+        assert_eq!(
+            layout
+                .attachment_path(inbox.id, "owner/attachments/a.png")
+                .unwrap(),
+            "attachments/a.png"
+        );
+    }
+
+    #[test]
+    fn colliding_siblings_get_distinct_paths() {
+        let folder = node(None, "Notes", true);
+        let first = node(Some(folder.id), "Meeting notes", false);
+        let second = node(Some(folder.id), "meeting  NOTES!", false);
+        let elsewhere = node(None, "Meeting notes", false);
+        let untitled = node(Some(folder.id), "???", false);
+        let layout = hierarchy(&[
+            folder.clone(),
+            first.clone(),
+            second.clone(),
+            elsewhere.clone(),
+            untitled.clone(),
+        ]);
+
+        let path = |n: &LayoutNode| layout.document_path(n.id, "ignored").unwrap();
+        assert_eq!(path(&first), "notes/meeting-notes.md");
+        assert_eq!(path(&second), "notes/meeting-notes-2.md");
+        assert_eq!(path(&elsewhere), "meeting-notes.md");
+        assert_eq!(path(&untitled), "notes/untitled.md");
+    }
+
+    #[test]
+    fn document_titled_my_note_maps_to_my_note_md() {
+        let folder = node(None, "Journal", true);
+        let note = node(Some(folder.id), "My Note", false);
+        let layout = hierarchy(&[folder, note.clone()]);
+        assert_eq!(
+            layout.document_path(note.id, "ignored").unwrap(),
+            "journal/my-note.md"
+        );
+    }
+
+    #[test]
+    fn persisted_paths_stay_put_until_the_title_or_folder_changes() {
+        let folder = node(None, "Notes", true);
+        let first = node(Some(folder.id), "Meeting notes", false);
+        let second = node(Some(folder.id), "Meeting notes", false);
+        let layout = hierarchy(&[folder.clone(), first.clone(), second.clone()]);
+        let persisted = layout.paths().clone();
+        assert_eq!(persisted[&second.id], "notes/meeting-notes-2.md");
+
+        // The suffix does not move when the first document is deleted...
+        let layout = RepoLayout::hierarchy(&[folder.clone(), second.clone()], &persisted);
+        assert_eq!(layout.paths()[&second.id], "notes/meeting-notes-2.md");
+
+        // ...but a rename gives the document a new path and frees the old one.
+        let renamed = LayoutNode {
+            title: "Retro".into(),
+            ..second.clone()
+        };
+        let layout = RepoLayout::hierarchy(&[folder.clone(), first.clone(), renamed], &persisted);
+        assert_eq!(layout.paths()[&first.id], "notes/meeting-notes.md");
+        assert_eq!(layout.paths()[&second.id], "notes/retro.md");
+
+        // Moving to the root keeps the name but not the folder.
+        let moved = LayoutNode {
+            parent_id: None,
+            ..first.clone()
+        };
+        let layout = RepoLayout::hierarchy(&[folder, moved, second], &persisted);
+        assert_eq!(layout.paths()[&first.id], "meeting-notes.md");
+    }
+
+    #[test]
+    fn unknown_documents_fall_back_to_the_storage_path() {
+        let layout = RepoLayout::default();
+        let doc = Uuid::new_v4();
+        assert_eq!(
+            layout.document_path(doc, "owner/Notes/Todo.md").unwrap(),
+            "Notes/Todo.md"
+        );
+        assert!(layout.document_path(doc, "/").is_err());
+        assert_eq!(
+            layout
+                .folder_marker_path(doc, "owner/Archive/2024/")
+                .unwrap(),
+            "Archive/2024/.gitkeep"
+        );
+    }
+}
@@ -1,2 +1,4 @@
+pub mod layout;
 pub mod storage;
+pub mod sync_lock;
 pub mod workspace;
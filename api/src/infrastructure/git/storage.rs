@@ -10,9 +10,10 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::application::ports::git_storage::{
-    BlobKey, CommitMeta, GitStorage, PackBlob, PackStream, encode_commit_id,
+    BlobKey, CommitMeta, GitStorage, GitStorageUsage, PackBlob, PackStream, encode_commit_id,
 };
 use crate::bootstrap::config::{Config, StorageBackend};
+use crate::infrastructure::storage::s3::S3Settings;
 
 pub async fn build_git_storage(cfg: &Config) -> anyhow::Result<Arc<dyn GitStorage>> {
     match cfg.storage_backend {
@@ -59,6 +60,10 @@ impl FilesystemGitStorage {
         self.user_dir(user_id).join("latest.json")
     }
 
+    fn commit_blobs_dir(&self, user_id: Uuid, commit_hex: &str) -> PathBuf {
+        self.blobs_root().join(user_id.to_string()).join(commit_hex)
+    }
+
     async fn load_meta_or_err(
         &self,
         user_id: Uuid,
@@ -159,7 +164,7 @@ impl GitStorage for FilesystemGitStorage {
         }
         let storage = self.clone();
         let storage_for_stream = storage.clone();
-        let stream = stream::iter(metas.into_iter()).then(move |meta| {
+        let stream = stream::iter(metas).then(move |meta| {
             let storage = storage_for_stream.clone();
             async move {
                 let commit_hex = encode_commit_id(&meta.commit_id);
@@ -256,6 +261,64 @@ impl GitStorage for FilesystemGitStorage {
         }
         Ok(())
     }
+
+    async fn usage(&self, user_id: Uuid) -> anyhow::Result<GitStorageUsage> {
+        let mut usage = GitStorageUsage::default();
+        for (path, size) in files_in(&self.user_dir(user_id)).await? {
+            if path.extension().is_some_and(|ext| ext == "pack") {
+                usage.packs += 1;
+                usage.pack_bytes += size;
+            }
+        }
+        let mut commit_dirs =
+            read_dir_if_exists(&self.blobs_root().join(user_id.to_string())).await?;
+        while let Some(dir) = commit_dirs.pop() {
+            for (_, size) in files_in(&dir).await? {
+                usage.snapshot_blobs += 1;
+                usage.snapshot_bytes += size;
+            }
+        }
+        Ok(usage)
+    }
+
+    async fn delete_commit_blobs(
+        &self,
+        user_id: Uuid,
+        commit_id: &[u8],
+    ) -> anyhow::Result<(u64, u64)> {
+        let dir = self.commit_blobs_dir(user_id, &encode_commit_id(commit_id));
+        let files = files_in(&dir).await?;
+        if files.is_empty() {
+            return Ok((0, 0));
+        }
+        fs::remove_dir_all(&dir).await?;
+        Ok((files.len() as u64, files.iter().map(|(_, size)| size).sum()))
+    }
+}
+
+async fn read_dir_if_exists(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        paths.push(entry.path());
+    }
+    Ok(paths)
+}
+
+/// Regular files directly in `dir` with their sizes; a missing directory has none.
+async fn files_in(dir: &Path) -> anyhow::Result<Vec<(PathBuf, u64)>> {
+    let mut files = Vec::new();
+    for path in read_dir_if_exists(dir).await? {
+        let meta = fs::metadata(&path).await?;
+        if meta.is_file() {
+            files.push((path, meta.len()));
+        }
+    }
+    Ok(files)
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -326,6 +389,7 @@ fn sanitize_blob_path(root: &Path, key: &str) -> anyhow::Result<PathBuf> {
 #[derive(Clone)]
 pub struct S3GitStorage {
     client: aws_sdk_s3::Client,
+    settings: S3Settings,
     bucket: String,
     root_prefix: String,
     // Mutex to serialize latest pointer updates to avoid race when multiple tasks update latest.json concurrently.
@@ -338,31 +402,11 @@ impl S3GitStorage {
             .s3_bucket
             .clone()
             .context("S3 bucket must be configured for S3 storage backend")?;
-        let mut loader = aws_config::defaults(aws_config::BehaviorVersion::latest());
-        if let Some(region) = &cfg.s3_region {
-            loader = loader.region(aws_sdk_s3::config::Region::new(region.clone()));
-        }
-        let shared = loader.load().await;
-        let mut builder = aws_sdk_s3::config::Builder::from(&shared);
-        if let (Some(access), Some(secret)) = (&cfg.s3_access_key, &cfg.s3_secret_key) {
-            let creds = aws_sdk_s3::config::Credentials::new(
-                access,
-                secret,
-                None,
-                None,
-                "git-storage-static",
-            );
-            builder = builder.credentials_provider(creds);
-        }
-        if let Some(endpoint) = &cfg.s3_endpoint {
-            builder = builder.endpoint_url(endpoint.clone());
-        }
-        if cfg.s3_use_path_style {
-            builder = builder.force_path_style(true);
-        }
-        let client = aws_sdk_s3::Client::from_conf(builder.build());
+        let settings = S3Settings::from_config(cfg);
+        let client = settings.client("git-storage-static").await;
         Ok(Self {
             client,
+            settings,
             bucket,
             root_prefix: cfg.storage_root.clone(),
             latest_lock: Arc::new(Mutex::new(())),
@@ -418,17 +462,50 @@ impl S3GitStorage {
     }
 
     async fn put_object(&self, key: &str, bytes: &[u8]) -> anyhow::Result<()> {
-        self.client
+        let put = self
+            .client
             .put_object()
             .bucket(&self.bucket)
             .key(key)
-            .body(aws_sdk_s3::primitives::ByteStream::from(bytes.to_vec()))
+            .body(aws_sdk_s3::primitives::ByteStream::from(bytes.to_vec()));
+        self.settings
+            .encrypt(put)
             .send()
             .await
             .with_context(|| format!("failed to upload {key}"))?;
         Ok(())
     }
 
+    /// Keys and sizes of the objects under `prefix`.
+    async fn list_prefix(&self, prefix: &str) -> anyhow::Result<Vec<(String, u64)>> {
+        let mut objects = Vec::new();
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut req = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(prefix);
+            if let Some(token) = continuation.as_ref() {
+                req = req.continuation_token(token.clone());
+            }
+            let resp = req
+                .send()
+                .await
+                .with_context(|| format!("failed to list {prefix}"))?;
+            for obj in resp.contents() {
+                if let Some(key) = obj.key() {
+                    objects.push((key.to_string(), obj.size().unwrap_or(0).max(0) as u64));
+                }
+            }
+            if !resp.is_truncated().unwrap_or(false) {
+                break;
+            }
+            continuation = resp.next_continuation_token().map(|s| s.to_string());
+        }
+        Ok(objects)
+    }
+
     async fn delete_prefix(&self, prefix: &str) -> anyhow::Result<()> {
         let mut continuation: Option<String> = None;
         loop {
@@ -546,7 +623,7 @@ impl GitStorage for S3GitStorage {
         }
         let storage = self.clone();
         let storage_for_stream = storage.clone();
-        let stream = stream::iter(metas.into_iter()).then(move |meta| {
+        let stream = stream::iter(metas).then(move |meta| {
             let storage = storage_for_stream.clone();
             async move {
                 let commit_hex = encode_commit_id(&meta.commit_id);
@@ -655,4 +732,144 @@ impl GitStorage for S3GitStorage {
         self.delete_prefix(&blob_prefix).await?;
         self.set_latest_commit(user_id, None).await
     }
+
+    async fn usage(&self, user_id: Uuid) -> anyhow::Result<GitStorageUsage> {
+        let mut usage = GitStorageUsage::default();
+        let pack_prefix = format!("{}/git/packs/{}/", self.root_prefix, user_id);
+        for (key, size) in self.list_prefix(&pack_prefix).await? {
+            if key.ends_with(".pack") {
+                usage.packs += 1;
+                usage.pack_bytes += size;
+            }
+        }
+        let blob_prefix = format!("{}/git/blobs/{}/", self.root_prefix, user_id);
+        for (_, size) in self.list_prefix(&blob_prefix).await? {
+            usage.snapshot_blobs += 1;
+            usage.snapshot_bytes += size;
+        }
+        Ok(usage)
+    }
+
+    async fn delete_commit_blobs(
+        &self,
+        user_id: Uuid,
+        commit_id: &[u8],
+    ) -> anyhow::Result<(u64, u64)> {
+        let prefix = format!(
+            "{}/git/blobs/{}/{}/",
+            self.root_prefix,
+            user_id,
+            encode_commit_id(commit_id)
+        );
+        let objects = self.list_prefix(&prefix).await?;
+        if objects.is_empty() {
+            return Ok((0, 0));
+        }
+        self.delete_prefix(&prefix).await?;
+        Ok((
+            objects.len() as u64,
+            objects.iter().map(|(_, size)| size).sum(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+
+    use super::*;
+    use crate::application::use_cases::git::storage_usage::{
+        GetGitStorageUsage, PruneGitSnapshots,
+    };
+
+    fn meta(user_id: Uuid, n: u8, parent: Option<u8>) -> CommitMeta {
+        CommitMeta {
+            commit_id: vec![n; 20],
+            parent_commit_id: parent.map(|p| vec![p; 20]),
+            message: Some(format!("commit {n}")),
+            author_name: None,
+            author_email: None,
+            committed_at: chrono::Utc::now(),
+            pack_key: format!("git/packs/{user_id}/{}.pack", encode_commit_id(&[n; 20])),
+            file_hash_index: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Stores `count` chained commits, each with a 100-byte pack and two 10-byte snapshots.
+    async fn commit_history(storage: &FilesystemGitStorage, user_id: Uuid, count: u8) {
+        for n in 1..=count {
+            let meta = meta(user_id, n, (n > 1).then(|| n - 1));
+            storage.store_pack(user_id, &[0; 100], &meta).await.unwrap();
+            for path in ["notes.md", "todo.md"] {
+                let key = BlobKey {
+                    path: format!("{user_id}/{}/{path}", encode_commit_id(&meta.commit_id)),
+                };
+                storage.put_blob(&key, &[n; 10]).await.unwrap();
+            }
+            storage
+                .set_latest_commit(user_id, Some(&meta))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn storage_usage_reflects_stored_packs_and_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FilesystemGitStorage::new(dir.path());
+        let (user, other) = (Uuid::new_v4(), Uuid::new_v4());
+        commit_history(&storage, user, 3).await;
+        commit_history(&storage, other, 1).await;
+
+        let usage = GetGitStorageUsage { storage: &storage }
+            .execute(user)
+            .await
+            .unwrap();
+        assert_eq!(
+            usage,
+            GitStorageUsage {
+                packs: 3,
+                pack_bytes: 300,
+                snapshot_blobs: 6,
+                snapshot_bytes: 60,
+            }
+        );
+        let empty = storage.usage(Uuid::new_v4()).await.unwrap();
+        assert_eq!(empty, GitStorageUsage::default());
+    }
+
+    #[tokio::test]
+    async fn gc_prunes_old_snapshots_but_keeps_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = FilesystemGitStorage::new(dir.path());
+        let user = Uuid::new_v4();
+        commit_history(&storage, user, 5).await;
+
+        let gc = PruneGitSnapshots { storage: &storage };
+        let outcome = gc.execute(user, 2).await.unwrap();
+        assert_eq!(outcome.commits_pruned, 3);
+        assert_eq!(outcome.blobs_deleted, 6);
+        assert_eq!(outcome.bytes_freed, 60);
+
+        let usage = storage.usage(user).await.unwrap();
+        assert_eq!((usage.packs, usage.snapshot_blobs), (5, 4));
+        // Recent snapshots are still readable and every pack is still in the chain.
+        let latest = BlobKey {
+            path: format!("{user}/{}/notes.md", encode_commit_id(&[5; 20])),
+        };
+        assert_eq!(storage.fetch_blob(&latest).await.unwrap(), vec![5; 10]);
+        let packs: Vec<PackBlob> = storage
+            .load_pack_chain(user, None)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(packs.len(), 5);
+
+        // Nothing is left to prune, and the latest commit is never pruned.
+        assert_eq!(gc.execute(user, 2).await.unwrap().blobs_deleted, 0);
+        assert_eq!(gc.execute(user, 0).await.unwrap().commits_pruned, 1);
+        assert_eq!(storage.usage(user).await.unwrap().snapshot_blobs, 2);
+    }
 }
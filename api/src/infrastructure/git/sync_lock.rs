@@ -0,0 +1,89 @@
+//! Serializes git syncs per user within the process.
+//!
+//! Syncs on other nodes are caught when the commit is recorded: the `FOR UPDATE` lock on
+//! `git_repository_state` is taken only for that step, and a sync whose head moved underneath
+//! it fails. This lock keeps requests on one node from racing each other into that failure.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+use uuid::Uuid;
+
+#[derive(Default)]
+pub struct UserSyncLocks {
+    locks: Mutex<HashMap<Uuid, Weak<AsyncMutex<()>>>>,
+}
+
+impl UserSyncLocks {
+    /// Waits for any other sync of `user_id` in this process; the lock is held until the
+    /// guard is dropped.
+    pub async fn lock(&self, user_id: Uuid) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            // Entries nobody holds or waits for are dropped as we go.
+            locks.retain(|_, lock| lock.strong_count() > 0);
+            match locks.get(&user_id).and_then(Weak::upgrade) {
+                Some(lock) => lock,
+                None => {
+                    let lock = Arc::new(AsyncMutex::new(()));
+                    locks.insert(user_id, Arc::downgrade(&lock));
+                    lock
+                }
+            }
+        };
+        lock.lock_owned().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn concurrent_syncs_for_one_user_serialize() {
+        let locks = Arc::new(UserSyncLocks::default());
+        let user = Uuid::new_v4();
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+
+        let sync = |locks: Arc<UserSyncLocks>| {
+            let (active, max_active) = (active.clone(), max_active.clone());
+            tokio::spawn(async move {
+                let _guard = locks.lock(user).await;
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                max_active.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                active.fetch_sub(1, Ordering::SeqCst);
+            })
+        };
+        let (first, second) = (sync(locks.clone()), sync(locks.clone()));
+        tokio::time::timeout(Duration::from_secs(5), async {
+            first.await.unwrap();
+            second.await.unwrap();
+        })
+        .await
+        .expect("syncs deadlocked");
+
+        assert_eq!(max_active.load(Ordering::SeqCst), 1);
+        // Both released; the next sync does not wait.
+        tokio::time::timeout(Duration::from_millis(100), locks.lock(user))
+            .await
+            .expect("lock was not released");
+    }
+
+    #[tokio::test]
+    async fn syncs_for_different_users_do_not_wait() {
+        let locks = UserSyncLocks::default();
+        let held = locks.lock(Uuid::new_v4()).await;
+        tokio::time::timeout(Duration::from_millis(100), locks.lock(Uuid::new_v4()))
+            .await
+            .expect("unrelated user waited");
+        drop(held);
+        let _ = locks.lock(Uuid::new_v4()).await;
+        assert_eq!(locks.locks.lock().unwrap().len(), 1);
+    }
+}
@@ -12,26 +12,43 @@ use git2::{
     CertificateCheckStatus, Commit, Cred, FetchOptions, FileMode, Indexer, ObjectType, PushOptions,
     RemoteCallbacks, Repository, Signature, Time, TreeWalkMode, TreeWalkResult,
 };
-use similar::{Algorithm, ChangeTag, TextDiff};
 use sqlx::{Row, types::Json};
 use tempfile::{Builder as TempDirBuilder, TempDir};
 use tracing::warn;
 use uuid::Uuid;
 
 use crate::application::dto::git::{
-    DiffLine, DiffLineType, DiffResult, GitChangeItem, GitCommitInfo, GitSyncOutcome,
-    GitSyncRequestDto, GitWorkspaceStatus,
+    DiffResult, GitBlameLine, GitChangeItem, GitCommitInfo, GitConnectionCheck, GitConnectionError,
+    GitDiffStats, GitHistoryCursor, GitRemoteSnapshot, GitSyncOutcome, GitSyncPreview,
+    GitSyncRequestDto, GitWorkingDiff, GitWorkspaceStatus,
 };
 use crate::application::ports::git_repository::UserGitCfg;
-use crate::application::ports::git_storage::{BlobKey, CommitMeta, GitStorage, encode_commit_id};
+use crate::application::ports::git_storage::{
+    BlobKey, CommitMeta, GitStorage, decode_commit_id, encode_commit_id,
+};
 use crate::application::ports::git_workspace::GitWorkspacePort;
+use crate::application::ports::gitignore_port::GitignorePort;
 use crate::application::ports::storage_port::StoragePort;
+use crate::application::services::commit_message;
+use crate::application::services::diff;
+use crate::application::services::gitignore::GitignoreMatcher;
+use crate::bootstrap::config::GitPathLayout;
 use crate::infrastructure::db::PgPool;
+use crate::infrastructure::git::layout::{LayoutNode, RepoLayout};
+use crate::infrastructure::git::sync_lock::UserSyncLocks;
+use crate::infrastructure::storage::sanitize_title;
+
+/// Commits walked back from the latest one when computing a blame; older lines are
+/// attributed to the oldest commit reached.
+const MAX_BLAME_DEPTH: usize = 200;
 
 pub struct GitWorkspaceService {
     pool: PgPool,
     git_storage: Arc<dyn GitStorage>,
     storage: Arc<dyn StoragePort>,
+    gitignore: Arc<dyn GitignorePort>,
+    path_layout: GitPathLayout,
+    sync_locks: UserSyncLocks,
 }
 
 impl GitWorkspaceService {
@@ -39,14 +56,56 @@ impl GitWorkspaceService {
         pool: PgPool,
         git_storage: Arc<dyn GitStorage>,
         storage: Arc<dyn StoragePort>,
+        gitignore: Arc<dyn GitignorePort>,
     ) -> anyhow::Result<Self> {
         Ok(Self {
             pool,
             git_storage,
             storage,
+            gitignore,
+            path_layout: GitPathLayout::default(),
+            sync_locks: UserSyncLocks::default(),
         })
     }
 
+    pub fn with_path_layout(mut self, path_layout: GitPathLayout) -> Self {
+        self.path_layout = path_layout;
+        self
+    }
+
+    /// Repository paths for `user_id`'s documents under the configured layout.
+    async fn repo_layout(&self, user_id: Uuid) -> anyhow::Result<RepoLayout> {
+        if self.path_layout == GitPathLayout::Storage {
+            return Ok(RepoLayout::default());
+        }
+        let rows = sqlx::query(
+            r#"SELECT id, parent_id, title, type FROM documents
+               WHERE owner_id = $1
+               ORDER BY created_at, id"#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        let nodes: Vec<LayoutNode> = rows
+            .into_iter()
+            .map(|row| LayoutNode {
+                id: row.get("id"),
+                parent_id: row.get("parent_id"),
+                title: row.get("title"),
+                is_folder: row.get::<String, _>("type") == "folder",
+            })
+            .collect();
+        let persisted: HashMap<Uuid, String> =
+            sqlx::query("SELECT document_id, path FROM git_repo_paths WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_all(&self.pool)
+                .await?
+                .into_iter()
+                .map(|row| (row.get("document_id"), row.get("path")))
+                .collect();
+        Ok(RepoLayout::hierarchy(&nodes, &persisted))
+    }
+
     async fn load_repository_state(&self, user_id: Uuid) -> anyhow::Result<Option<(bool, String)>> {
         let row = sqlx::query(
             "SELECT initialized, default_branch FROM git_repository_state WHERE user_id = $1",
@@ -70,7 +129,7 @@ impl GitWorkspaceService {
         .fetch_optional(&self.pool)
         .await?;
 
-        row.map(|r| row_to_commit_meta(r)).transpose()
+        row.map(row_to_commit_meta).transpose()
     }
 
     async fn load_commit_meta_ref(
@@ -106,7 +165,7 @@ impl GitWorkspaceService {
         .bind(commit_id)
         .fetch_optional(&self.pool)
         .await?;
-        row.map(|row| row_to_commit_meta(row)).transpose()
+        row.map(row_to_commit_meta).transpose()
     }
 
     async fn commit_meta_by_hex(
@@ -126,7 +185,7 @@ impl GitWorkspaceService {
         .bind(bytes)
         .fetch_optional(&self.pool)
         .await?;
-        row.map(|r| row_to_commit_meta(r)).transpose()
+        row.map(row_to_commit_meta).transpose()
     }
 
     async fn ensure_latest_meta(&self, user_id: Uuid) -> anyhow::Result<Option<CommitMeta>> {
@@ -202,50 +261,63 @@ impl GitWorkspaceService {
         Ok(())
     }
 
+    /// Files the next commit would contain. `previous` is the latest commit's hash index;
+    /// documents whose recorded content hash still matches it are not read.
     async fn collect_current_state(
         &self,
         user_id: Uuid,
+        previous: &HashMap<String, String>,
     ) -> anyhow::Result<HashMap<String, FileSnapshot>> {
-        let mut state: HashMap<String, FileSnapshot> = HashMap::new();
+        let layout = self.repo_layout(user_id).await?;
+        self.collect_state(user_id, &layout, previous).await
+    }
+
+    async fn collect_state(
+        &self,
+        user_id: Uuid,
+        layout: &RepoLayout,
+        previous: &HashMap<String, String>,
+    ) -> anyhow::Result<HashMap<String, FileSnapshot>> {
+        let doc_rows = sqlx::query(
+            "SELECT id, content_hash FROM documents WHERE owner_id = $1 AND type <> 'folder'",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        let docs: Vec<(Uuid, Option<String>)> = doc_rows
+            .into_iter()
+            .map(|row| (row.get("id"), row.get("content_hash")))
+            .collect();
+        let mut state =
+            read_document_snapshots(self.storage.as_ref(), layout, docs, previous).await?;
 
-        let doc_rows =
-            sqlx::query("SELECT id FROM documents WHERE owner_id = $1 AND type <> 'folder'")
+        // Folders are committed as a marker file so empty ones are not lost.
+        let folder_rows =
+            sqlx::query("SELECT id, title FROM documents WHERE owner_id = $1 AND type = 'folder'")
                 .bind(user_id)
                 .fetch_all(&self.pool)
                 .await?;
-
-        for row in doc_rows {
-            let doc_id: Uuid = row.get("id");
-            let path = self.storage.build_doc_file_path(doc_id).await?;
-            let bytes = match self.storage.read_bytes(path.as_path()).await {
-                Ok(bytes) => bytes,
-                Err(err) => {
-                    if let Some(io_err) = err.downcast_ref::<std::io::Error>() {
-                        if io_err.kind() == std::io::ErrorKind::NotFound {
-                            continue;
-                        }
-                    }
-                    if err.to_string().contains("not found") {
-                        continue;
-                    }
-                    return Err(err);
-                }
-            };
-            let hash = sha256_hex(&bytes);
-            let relative = self.storage.relative_from_uploads(path.as_path());
-            let repo_path = repo_relative_path(&relative)?;
+        for row in folder_rows {
+            let folder_id: Uuid = row.get("id");
+            let title: String = row.get("title");
+            let dir = self
+                .storage
+                .build_doc_dir(folder_id)
+                .await?
+                .join(sanitize_title(&title));
+            let relative = self.storage.relative_from_uploads(dir.as_path());
             state.insert(
-                repo_path,
+                layout.folder_marker_path(folder_id, &relative)?,
                 FileSnapshot {
-                    hash,
-                    data: FileSnapshotData::Inline(bytes),
+                    hash: sha256_hex(b""),
+                    data: FileSnapshotData::Inline(Vec::new()),
                     is_text: true,
                 },
             );
         }
 
         let attachment_rows = sqlx::query(
-            r#"SELECT f.storage_path, f.content_hash
+            r#"SELECT f.document_id, f.storage_path, f.content_hash
                FROM files f
                JOIN documents d ON d.id = f.document_id
                WHERE d.owner_id = $1"#,
@@ -257,7 +329,7 @@ impl GitWorkspaceService {
         for row in attachment_rows {
             let storage_path: String = row.get("storage_path");
             let hash: String = row.get("content_hash");
-            let repo_path = repo_relative_path(&storage_path)?;
+            let repo_path = layout.attachment_path(row.get("document_id"), &storage_path)?;
             state.insert(
                 repo_path,
                 FileSnapshot {
@@ -268,37 +340,14 @@ impl GitWorkspaceService {
             );
         }
 
-        Ok(state)
-    }
-
-    fn compute_deltas(
-        &self,
-        current: &HashMap<String, FileSnapshot>,
-        previous: &HashMap<String, String>,
-    ) -> FileDeltaSummary {
-        let mut added = Vec::new();
-        let mut modified = Vec::new();
-        let mut deleted = Vec::new();
-
-        for (path, snapshot) in current.iter() {
-            match previous.get(path) {
-                None => added.push(path.clone()),
-                Some(prev_hash) if prev_hash != &snapshot.hash => modified.push(path.clone()),
-                _ => {}
-            }
-        }
-
-        for path in previous.keys() {
-            if !current.contains_key(path) {
-                deleted.push(path.clone());
-            }
-        }
+        // Read on every call so pattern edits show up in the next status, diff and sync.
+        let patterns = self
+            .gitignore
+            .read_gitignore_patterns(&self.storage.user_repo_dir(user_id))
+            .await?;
+        exclude_ignored(&mut state, &GitignoreMatcher::new(&patterns));
 
-        FileDeltaSummary {
-            added,
-            modified,
-            deleted,
-        }
+        Ok(state)
     }
 
     async fn store_commit_snapshots(
@@ -322,6 +371,63 @@ impl GitWorkspaceService {
         Ok(stored)
     }
 
+    /// Diffs of the files in `delta` against the latest commit, as `working_diff` reports them.
+    async fn delta_diffs(
+        &self,
+        user_id: Uuid,
+        latest: Option<&CommitMeta>,
+        previous_index: &HashMap<String, String>,
+        current: &HashMap<String, FileSnapshot>,
+        delta: &FileDeltaSummary,
+    ) -> anyhow::Result<Vec<DiffResult>> {
+        let mut results = Vec::new();
+
+        let latest_commit_id = latest.map(|c| c.commit_id.clone());
+
+        for path in delta.added.iter().chain(delta.modified.iter()) {
+            if let Some(snapshot) = current.get(path) {
+                if snapshot.is_text {
+                    let new_bytes = self.snapshot_bytes(snapshot).await?;
+                    let new_content = String::from_utf8_lossy(&new_bytes).to_string();
+                    let old_bytes = match (&latest_commit_id, previous_index.get(path)) {
+                        (Some(commit_id), Some(_)) => {
+                            self.load_file_snapshot(user_id, commit_id.as_slice(), path)
+                                .await?
+                        }
+                        _ => None,
+                    };
+                    let old_text = old_bytes.and_then(|b| String::from_utf8(b).ok());
+                    results.push(diff::build_diff_result(
+                        path,
+                        old_text.as_deref(),
+                        Some(&new_content),
+                    ));
+                } else {
+                    results.push(DiffResult {
+                        file_path: path.clone(),
+                        diff_lines: Vec::new(),
+                        old_content: None,
+                        new_content: None,
+                    });
+                }
+            }
+        }
+
+        for path in delta.deleted.iter() {
+            let old_bytes =
+                if let (Some(commit_id), Some(_)) = (&latest_commit_id, previous_index.get(path)) {
+                    self.load_file_snapshot(user_id, commit_id.as_slice(), path)
+                        .await?
+                } else {
+                    None
+                };
+            let old_text = old_bytes.and_then(|b| String::from_utf8(b).ok());
+            results.push(diff::build_diff_result(path, old_text.as_deref(), None));
+        }
+
+        Ok(results)
+    }
+
     async fn snapshot_bytes(&self, snapshot: &FileSnapshot) -> anyhow::Result<Vec<u8>> {
         match &snapshot.data {
             FileSnapshotData::Inline(bytes) => Ok(bytes.clone()),
@@ -343,10 +449,10 @@ impl GitWorkspaceService {
             Ok(bytes) => Ok(Some(bytes)),
             Err(err) => {
                 // Treat missing blob as absence (e.g., binary or not stored).
-                if let Some(io_err) = err.downcast_ref::<std::io::Error>() {
-                    if io_err.kind() == std::io::ErrorKind::NotFound {
-                        return Ok(None);
-                    }
+                if let Some(io_err) = err.downcast_ref::<std::io::Error>()
+                    && io_err.kind() == std::io::ErrorKind::NotFound
+                {
+                    return Ok(None);
                 }
                 if err.to_string().contains("not found") {
                     return Ok(None);
@@ -356,70 +462,6 @@ impl GitWorkspaceService {
         }
     }
 
-    fn build_diff_result(
-        &self,
-        path: &str,
-        old_content: Option<&str>,
-        new_content: Option<&str>,
-    ) -> DiffResult {
-        match (old_content, new_content) {
-            (Some(old), Some(new)) => {
-                let diff = TextDiff::configure()
-                    .algorithm(Algorithm::Myers)
-                    .diff_lines(old, new);
-                let mut lines = Vec::new();
-                let mut old_line = 0u32;
-                let mut new_line = 0u32;
-                for op in diff.ops() {
-                    for change in diff.iter_changes(op) {
-                        match change.tag() {
-                            ChangeTag::Delete => {
-                                old_line += 1;
-                                lines.push(DiffLine {
-                                    line_type: DiffLineType::Deleted,
-                                    old_line_number: Some(old_line),
-                                    new_line_number: None,
-                                    content: change.to_string().trim_end().to_string(),
-                                });
-                            }
-                            ChangeTag::Insert => {
-                                new_line += 1;
-                                lines.push(DiffLine {
-                                    line_type: DiffLineType::Added,
-                                    old_line_number: None,
-                                    new_line_number: Some(new_line),
-                                    content: change.to_string().trim_end().to_string(),
-                                });
-                            }
-                            ChangeTag::Equal => {
-                                old_line += 1;
-                                new_line += 1;
-                                lines.push(DiffLine {
-                                    line_type: DiffLineType::Context,
-                                    old_line_number: Some(old_line),
-                                    new_line_number: Some(new_line),
-                                    content: change.to_string().trim_end().to_string(),
-                                });
-                            }
-                        }
-                    }
-                }
-                DiffResult {
-                    file_path: path.to_string(),
-                    diff_lines: lines,
-                    old_content: Some(old.to_string()),
-                    new_content: Some(new.to_string()),
-                }
-            }
-            _ => DiffResult {
-                file_path: path.to_string(),
-                diff_lines: Vec::new(),
-                old_content: old_content.map(|s| s.to_string()),
-                new_content: new_content.map(|s| s.to_string()),
-            },
-        }
-    }
-
     async fn commit_diff_via_packs(
         &self,
         user_id: Uuid,
@@ -503,11 +545,11 @@ impl GitWorkspaceService {
                 .map(|s| s.to_string());
             if old_content.is_none() && new_content.is_none() {
                 if old_bytes.is_some() || new_bytes.is_some() {
-                    results.push(self.build_diff_result(&path, None, None));
+                    results.push(diff::build_diff_result(&path, None, None));
                 }
                 continue;
             }
-            results.push(self.build_diff_result(
+            results.push(diff::build_diff_result(
                 &path,
                 old_content.as_deref(),
                 new_content.as_deref(),
@@ -516,6 +558,84 @@ impl GitWorkspaceService {
         Ok(results)
     }
 
+    /// Contents of `path` at each of `commits`, read from the pack chain of the newest one and
+    /// from stored snapshots when the packs are unavailable.
+    async fn file_revisions(
+        &self,
+        user_id: Uuid,
+        path: &str,
+        commits: &[CommitMeta],
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        let Some(newest) = commits.first() else {
+            return Ok(Vec::new());
+        };
+        match self
+            .file_revisions_via_packs(user_id, path, newest, commits)
+            .await
+        {
+            Ok(revisions) => return Ok(revisions),
+            Err(err) => {
+                warn!(%err, path, "failed to read file history from pack data, using stored snapshots");
+            }
+        }
+        let mut revisions = Vec::with_capacity(commits.len());
+        for meta in commits {
+            let bytes = self
+                .load_file_snapshot(user_id, meta.commit_id.as_slice(), path)
+                .await?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "missing snapshot of {path} at commit {}",
+                        encode_commit_id(&meta.commit_id)
+                    )
+                })?;
+            revisions.push(bytes);
+        }
+        Ok(revisions)
+    }
+
+    async fn file_revisions_via_packs(
+        &self,
+        user_id: Uuid,
+        path: &str,
+        newest: &CommitMeta,
+        commits: &[CommitMeta],
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        let (pack_dir, pack_paths) = persist_pack_chain(
+            self.git_storage.as_ref(),
+            user_id,
+            Some(newest.commit_id.as_slice()),
+        )
+        .await?
+        .ok_or_else(|| {
+            anyhow!(
+                "missing pack data for commit {}",
+                encode_commit_id(&newest.commit_id)
+            )
+        })?;
+        let temp_dir = TempDirBuilder::new()
+            .prefix("git-blame-")
+            .tempdir()
+            .map_err(|e| anyhow::anyhow!(e))?;
+        let repo = Repository::init_bare(temp_dir.path())?;
+        apply_pack_files(&repo, &pack_paths)?;
+        let revisions = commits
+            .iter()
+            .map(|meta| {
+                read_commit_file(&repo, meta.commit_id.as_slice(), path)?.ok_or_else(|| {
+                    anyhow!(
+                        "{path} is missing from commit {}",
+                        encode_commit_id(&meta.commit_id)
+                    )
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>();
+        drop(repo);
+        let _ = temp_dir.close();
+        drop(pack_dir);
+        revisions
+    }
+
     async fn commit_diff_from_storage(
         &self,
         user_id: Uuid,
@@ -536,10 +656,10 @@ impl GitWorkspaceService {
         for path in paths {
             let old_hash = from_meta.and_then(|meta| meta.file_hash_index.get(&path));
             let new_hash = to_meta.file_hash_index.get(&path);
-            if let (Some(old), Some(new)) = (old_hash, new_hash) {
-                if old == new {
-                    continue;
-                }
+            if let (Some(old), Some(new)) = (old_hash, new_hash)
+                && old == new
+            {
+                continue;
             }
 
             let old_bytes = match (from_meta, old_hash) {
@@ -568,10 +688,10 @@ impl GitWorkspaceService {
 
             if old_text.is_none() && new_text.is_none() {
                 if old_bytes.is_some() || new_bytes.is_some() {
-                    results.push(self.build_diff_result(&path, None, None));
+                    results.push(diff::build_diff_result(&path, None, None));
                 }
             } else {
-                results.push(self.build_diff_result(
+                results.push(diff::build_diff_result(
                     &path,
                     old_text.as_deref(),
                     new_text.as_deref(),
@@ -642,8 +762,8 @@ impl GitWorkspacePort for GitWorkspaceService {
             .as_ref()
             .map(|c| c.file_hash_index.clone())
             .unwrap_or_default();
-        let current = self.collect_current_state(user_id).await?;
-        let delta = self.compute_deltas(&current, &previous_index);
+        let current = self.collect_current_state(user_id, &previous_index).await?;
+        let delta = compute_deltas(&current, &previous_index);
         Ok(GitWorkspaceStatus {
             repository_initialized: true,
             current_branch: Some(branch),
@@ -658,85 +778,26 @@ impl GitWorkspacePort for GitWorkspaceService {
             .as_ref()
             .map(|c| c.file_hash_index.clone())
             .unwrap_or_default();
-        let current = self.collect_current_state(user_id).await?;
-        let delta = self.compute_deltas(&current, &previous_index);
-        let mut changes = Vec::new();
-        for path in delta.added {
-            changes.push(GitChangeItem {
-                path,
-                status: "untracked".to_string(),
-            });
-        }
-        for path in delta.modified {
-            changes.push(GitChangeItem {
-                path,
-                status: "modified".to_string(),
-            });
-        }
-        for path in delta.deleted {
-            changes.push(GitChangeItem {
-                path,
-                status: "deleted".to_string(),
-            });
-        }
-        Ok(changes)
+        let current = self.collect_current_state(user_id, &previous_index).await?;
+        let delta = compute_deltas(&current, &previous_index);
+        Ok(change_items(&delta))
     }
 
-    async fn working_diff(&self, user_id: Uuid) -> anyhow::Result<Vec<DiffResult>> {
+    async fn working_diff(&self, user_id: Uuid) -> anyhow::Result<GitWorkingDiff> {
         let latest = self.latest_commit_meta(user_id).await?;
         let previous_index = latest
             .as_ref()
             .map(|c| c.file_hash_index.clone())
             .unwrap_or_default();
-        let current = self.collect_current_state(user_id).await?;
-        let delta = self.compute_deltas(&current, &previous_index);
-        let mut results = Vec::new();
-
-        let latest_commit_id = latest.as_ref().map(|c| c.commit_id.clone());
-
-        for path in delta.added.iter().chain(delta.modified.iter()) {
-            if let Some(snapshot) = current.get(path) {
-                if snapshot.is_text {
-                    let new_bytes = self.snapshot_bytes(snapshot).await?;
-                    let new_content = String::from_utf8_lossy(&new_bytes).to_string();
-                    let old_bytes = match (&latest_commit_id, previous_index.get(path)) {
-                        (Some(commit_id), Some(_)) => {
-                            self.load_file_snapshot(user_id, commit_id.as_slice(), path)
-                                .await?
-                        }
-                        _ => None,
-                    };
-                    let old_text = old_bytes.and_then(|b| String::from_utf8(b).ok());
-                    results.push(self.build_diff_result(
-                        path,
-                        old_text.as_deref(),
-                        Some(&new_content),
-                    ));
-                } else {
-                    results.push(DiffResult {
-                        file_path: path.clone(),
-                        diff_lines: Vec::new(),
-                        old_content: None,
-                        new_content: None,
-                    });
-                }
-            }
-        }
-
-        for path in delta.deleted {
-            let old_bytes = if let (Some(commit_id), Some(_)) =
-                (&latest_commit_id, previous_index.get(&path))
-            {
-                self.load_file_snapshot(user_id, commit_id.as_slice(), &path)
-                    .await?
-            } else {
-                None
-            };
-            let old_text = old_bytes.and_then(|b| String::from_utf8(b).ok());
-            results.push(self.build_diff_result(&path, old_text.as_deref(), None));
-        }
-
-        Ok(results)
+        let current = self.collect_current_state(user_id, &previous_index).await?;
+        let delta = compute_deltas(&current, &previous_index);
+        let diffs = self
+            .delta_diffs(user_id, latest.as_ref(), &previous_index, &current, &delta)
+            .await?;
+        Ok(GitWorkingDiff {
+            base_commit_hash: latest.map(|c| encode_commit_id(&c.commit_id)),
+            diffs,
+        })
     }
 
     async fn commit_diff(
@@ -772,53 +833,125 @@ impl GitWorkspacePort for GitWorkspaceService {
             .await
     }
 
-    async fn history(&self, user_id: Uuid) -> anyhow::Result<Vec<GitCommitInfo>> {
+    async fn history(
+        &self,
+        user_id: Uuid,
+        before: Option<&GitHistoryCursor>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<GitCommitInfo>> {
+        let before_commit = before.map(|c| decode_commit_id(&c.hash)).transpose()?;
         let rows = sqlx::query(
             r#"SELECT commit_id, message, author_name, author_email, committed_at
                FROM git_commits
                WHERE user_id = $1
-               ORDER BY committed_at DESC
-               LIMIT 200"#,
+                 AND ($2::timestamptz IS NULL OR (committed_at, commit_id) < ($2, $3))
+               ORDER BY committed_at DESC, commit_id DESC
+               LIMIT $4"#,
         )
         .bind(user_id)
+        .bind(before.map(|c| c.committed_at))
+        .bind(before_commit)
+        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
         let history = rows
             .into_iter()
-            .filter_map(|row| {
+            .map(|row| {
                 let commit_id: Vec<u8> = row.get("commit_id");
                 let message: Option<String> = row.try_get("message").ok();
                 let author_name: Option<String> = row.try_get("author_name").ok();
                 let author_email: Option<String> = row.try_get("author_email").ok();
                 let committed_at: DateTime<Utc> = row.get("committed_at");
-                Some(GitCommitInfo {
+                GitCommitInfo {
                     hash: encode_commit_id(&commit_id),
                     message: message.unwrap_or_default(),
                     author_name: author_name.unwrap_or_default(),
                     author_email: author_email.unwrap_or_default(),
                     time: committed_at,
-                })
+                }
             })
             .collect();
         Ok(history)
     }
 
+    async fn blame(&self, user_id: Uuid, path: &str) -> anyhow::Result<Vec<GitBlameLine>> {
+        let Some(latest) = self.ensure_latest_meta(user_id).await? else {
+            return Ok(Vec::new());
+        };
+        let Some(mut hash) = latest.file_hash_index.get(path).cloned() else {
+            return Ok(Vec::new());
+        };
+
+        // Commits that changed the file, newest first. Commits that left it untouched are
+        // skipped by comparing the recorded content hashes.
+        let mut changes = Vec::new();
+        let mut current = latest;
+        let mut depth = 1;
+        loop {
+            let parent = match &current.parent_commit_id {
+                Some(id) if depth < MAX_BLAME_DEPTH => {
+                    self.commit_meta_by_id(user_id, id.as_slice()).await?
+                }
+                _ => None,
+            };
+            depth += 1;
+            let Some(parent) = parent else {
+                changes.push(current);
+                break;
+            };
+            let Some(parent_hash) = parent.file_hash_index.get(path).cloned() else {
+                changes.push(current);
+                break;
+            };
+            if parent_hash != hash {
+                changes.push(current);
+                hash = parent_hash;
+            }
+            current = parent;
+        }
+
+        let revisions = self.file_revisions(user_id, path, &changes).await?;
+        let texts = revisions
+            .iter()
+            .rev()
+            .map(|bytes| std::str::from_utf8(bytes))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| anyhow!("{path} is not a text file"))?;
+        let owners = diff::blame_lines(&texts);
+        let latest_text = texts.last().copied().unwrap_or_default();
+        let oldest = changes.len() - 1;
+        Ok(latest_text
+            .lines()
+            .zip(owners)
+            .enumerate()
+            .map(|(i, (content, owner))| GitBlameLine {
+                line_number: i as u32 + 1,
+                content: content.to_string(),
+                commit: commit_info(&changes[oldest - owner]),
+            })
+            .collect())
+    }
+
     async fn sync(
         &self,
         user_id: Uuid,
         req: &GitSyncRequestDto,
         cfg: Option<&UserGitCfg>,
     ) -> anyhow::Result<GitSyncOutcome> {
-        let mut tx = self.pool.begin().await?;
+        // Only one sync per user runs in this process. Across nodes, the fetch/push and the
+        // commit below are arbitrated instead: the push is never forced, so a node working from
+        // a stale head is rejected by the remote, and the row lock taken before recording the
+        // commit catches one that raced us without a remote. No transaction is held while we
+        // talk to the remote.
+        let _sync_guard = self.sync_locks.lock(user_id).await;
         let repo_row = sqlx::query(
-            "SELECT initialized, default_branch FROM git_repository_state WHERE user_id = $1 FOR UPDATE",
+            "SELECT initialized, default_branch FROM git_repository_state WHERE user_id = $1",
         )
         .bind(user_id)
-        .fetch_optional(&mut *tx)
+        .fetch_optional(&self.pool)
         .await?;
         let Some(repo_row) = repo_row else {
-            tx.rollback().await.ok();
             anyhow::bail!("repository not initialized")
         };
         let initialized: bool = repo_row.get("initialized");
@@ -827,7 +960,6 @@ impl GitWorkspacePort for GitWorkspaceService {
             .map(|c| c.branch_name.clone())
             .unwrap_or(default_branch.clone());
         if !initialized {
-            tx.rollback().await.ok();
             anyhow::bail!("repository not initialized")
         }
 
@@ -839,7 +971,6 @@ impl GitWorkspacePort for GitWorkspaceService {
             .map(|m| encode_commit_id(&m.commit_id));
         let db_commit_hex = latest_meta.as_ref().map(|m| encode_commit_id(&m.commit_id));
         if storage_commit_hex != db_commit_hex {
-            tx.rollback().await.ok();
             anyhow::bail!(
                 "repository latest commit mismatch between database ({db_commit_hex:?}) and storage ({storage_commit_hex:?})"
             );
@@ -849,25 +980,54 @@ impl GitWorkspacePort for GitWorkspaceService {
             .as_ref()
             .map(|c| c.file_hash_index.clone())
             .unwrap_or_default();
-        let current = self.collect_current_state(user_id).await?;
-        let delta = self.compute_deltas(&current, &previous_index);
+        let layout = self.repo_layout(user_id).await?;
+        let current = self
+            .collect_state(user_id, &layout, &previous_index)
+            .await?;
+        let delta = compute_deltas(&current, &previous_index);
+        if req.dry_run {
+            let files = change_items(&delta);
+            let diffs = self
+                .delta_diffs(
+                    user_id,
+                    latest_meta.as_ref(),
+                    &previous_index,
+                    &current,
+                    &delta,
+                )
+                .await?;
+            let stats = GitDiffStats::from_changes(&files, &diffs);
+            return Ok(GitSyncOutcome {
+                files_changed: files.len() as u32,
+                commit_hash: latest_meta.map(|c| encode_commit_id(&c.commit_id)),
+                pushed: false,
+                message: format!("dry run: {} file(s) would be committed", files.len()),
+                preview: Some(GitSyncPreview {
+                    files,
+                    diffs,
+                    stats,
+                }),
+            });
+        }
         if delta.added.is_empty() && delta.modified.is_empty() && delta.deleted.is_empty() {
-            tx.commit().await?;
             return Ok(GitSyncOutcome {
                 files_changed: 0,
                 commit_hash: latest_meta.map(|c| encode_commit_id(&c.commit_id)),
                 pushed: false,
                 message: "nothing to commit".to_string(),
+                preview: None,
             });
         }
 
         let committed_at = Utc::now();
         let author_name = "RefMD".to_string();
         let author_email = "refmd@example.com".to_string();
-        let message = req
-            .message
-            .clone()
-            .unwrap_or_else(|| "RefMD sync".to_string());
+        let message = commit_message::compose(
+            req.message.as_deref(),
+            cfg,
+            &change_items(&delta),
+            committed_at,
+        );
 
         let files_changed = (delta.added.len() + delta.modified.len() + delta.deleted.len()) as u32;
 
@@ -901,15 +1061,10 @@ impl GitWorkspacePort for GitWorkspaceService {
                 apply_pack_files(&repo, pack_paths)?;
             }
 
-            if let Some(cfg) = cfg {
-                if !cfg.repository_url.is_empty() {
-                    fetch_remote_and_verify(
-                        &repo,
-                        cfg,
-                        branch_name.as_str(),
-                        latest_meta.as_ref(),
-                    )?;
-                }
+            if let Some(cfg) = cfg
+                && !cfg.repository_url.is_empty()
+            {
+                fetch_remote_and_verify(&repo, cfg, branch_name.as_str(), latest_meta.as_ref())?;
             }
 
             let mut changed_paths: HashSet<String> = delta.added.iter().cloned().collect();
@@ -989,10 +1144,10 @@ impl GitWorkspacePort for GitWorkspaceService {
             };
 
             let mut pushed = false;
-            if let Some(cfg) = cfg {
-                if !cfg.repository_url.is_empty() {
-                    pushed = perform_push(&repo, cfg, &branch_name, commit_oid)?;
-                }
+            if let Some(cfg) = cfg
+                && !cfg.repository_url.is_empty()
+            {
+                pushed = perform_push(&repo, cfg, &branch_name, commit_oid)?;
             }
 
             drop(repo);
@@ -1005,6 +1160,22 @@ impl GitWorkspacePort for GitWorkspaceService {
             drop(dir);
         }
 
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT 1 FROM git_repository_state WHERE user_id = $1 FOR UPDATE")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        let head: Option<Vec<u8>> = sqlx::query_scalar(
+            "SELECT commit_id FROM git_commits WHERE user_id = $1 ORDER BY committed_at DESC LIMIT 1",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if head.as_deref() != latest_meta.as_ref().map(|m| m.commit_id.as_slice()) {
+            tx.rollback().await.ok();
+            anyhow::bail!("repository changed by a concurrent sync; retry");
+        }
+
         sqlx::query(
             r#"INSERT INTO git_commits (
                     commit_id,
@@ -1035,6 +1206,27 @@ impl GitWorkspacePort for GitWorkspaceService {
             .execute(&mut *tx)
             .await?;
 
+        if self.path_layout == GitPathLayout::Hierarchy {
+            let (ids, paths): (Vec<Uuid>, Vec<String>) = layout
+                .paths()
+                .iter()
+                .map(|(id, path)| (*id, path.clone()))
+                .unzip();
+            sqlx::query("DELETE FROM git_repo_paths WHERE user_id = $1")
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                r#"INSERT INTO git_repo_paths (user_id, document_id, path)
+                   SELECT $1, * FROM UNNEST($2::uuid[], $3::text[])"#,
+            )
+            .bind(user_id)
+            .bind(&ids)
+            .bind(&paths)
+            .execute(&mut *tx)
+            .await?;
+        }
+
         let snapshot_keys = match self
             .store_commit_snapshots(user_id, &meta.commit_id, &current)
             .await
@@ -1091,8 +1283,281 @@ impl GitWorkspacePort for GitWorkspaceService {
             } else {
                 "commit created".to_string()
             },
+            preview: None,
         })
     }
+
+    async fn test_connection(&self, cfg: &UserGitCfg) -> anyhow::Result<GitConnectionCheck> {
+        let cfg = cfg.clone();
+        Ok(tokio::task::spawn_blocking(move || check_remote(&cfg)).await?)
+    }
+
+    async fn fetch_remote_snapshot(
+        &self,
+        cfg: &UserGitCfg,
+    ) -> anyhow::Result<Option<GitRemoteSnapshot>> {
+        let cfg = cfg.clone();
+        tokio::task::spawn_blocking(move || fetch_remote_snapshot(&cfg)).await?
+    }
+
+    async fn record_baseline(
+        &self,
+        user_id: Uuid,
+        snapshot: &GitRemoteSnapshot,
+    ) -> anyhow::Result<()> {
+        let _sync_guard = self.sync_locks.lock(user_id).await;
+        let mut tx = self.pool.begin().await?;
+        let initialized: Option<bool> = sqlx::query_scalar(
+            "SELECT initialized FROM git_repository_state WHERE user_id = $1 FOR UPDATE",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if initialized != Some(true) {
+            tx.rollback().await.ok();
+            anyhow::bail!("repository not initialized");
+        }
+        if self.ensure_latest_meta(user_id).await?.is_some() {
+            tx.rollback().await.ok();
+            anyhow::bail!("repository already has history; import only into an empty repository");
+        }
+
+        let commit_id =
+            crate::application::ports::git_storage::decode_commit_id(&snapshot.commit.hash)?;
+        let state: HashMap<String, FileSnapshot> = snapshot
+            .files
+            .iter()
+            .map(|(path, bytes)| {
+                let file = FileSnapshot {
+                    hash: sha256_hex(bytes),
+                    is_text: std::str::from_utf8(bytes).is_ok(),
+                    data: FileSnapshotData::Inline(bytes.to_vec()),
+                };
+                (path.clone(), file)
+            })
+            .collect();
+        let meta = CommitMeta {
+            commit_id: commit_id.clone(),
+            parent_commit_id: None,
+            message: Some(snapshot.commit.message.clone()).filter(|m| !m.trim().is_empty()),
+            author_name: Some(snapshot.commit.author_name.clone()),
+            author_email: Some(snapshot.commit.author_email.clone()),
+            committed_at: snapshot.commit.time,
+            pack_key: format!("git/packs/{}/{}.pack", user_id, snapshot.commit.hash),
+            file_hash_index: state
+                .iter()
+                .map(|(path, file)| (path.clone(), file.hash.clone()))
+                .collect(),
+        };
+
+        sqlx::query(
+            r#"INSERT INTO git_commits (
+                    commit_id,
+                    parent_commit_id,
+                    user_id,
+                    message,
+                    author_name,
+                    author_email,
+                    committed_at,
+                    pack_key,
+                    file_hash_index
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#,
+        )
+        .bind(meta.commit_id.clone())
+        .bind(meta.parent_commit_id.clone())
+        .bind(user_id)
+        .bind(meta.message.clone())
+        .bind(meta.author_name.clone())
+        .bind(meta.author_email.clone())
+        .bind(meta.committed_at)
+        .bind(meta.pack_key.clone())
+        .bind(Json(&meta.file_hash_index))
+        .execute(&mut *tx)
+        .await?;
+
+        let snapshot_keys = match self
+            .store_commit_snapshots(user_id, &commit_id, &state)
+            .await
+        {
+            Ok(keys) => keys,
+            Err(err) => {
+                tx.rollback().await.ok();
+                return Err(err);
+            }
+        };
+        let stored = async {
+            self.git_storage
+                .store_pack(user_id, &snapshot.pack, &meta)
+                .await?;
+            self.git_storage
+                .set_latest_commit(user_id, Some(&meta))
+                .await
+        }
+        .await;
+        if let Err(err) = stored {
+            let _ = self.git_storage.delete_pack(user_id, &commit_id).await;
+            for key in snapshot_keys.iter().rev() {
+                let _ = self.git_storage.delete_blob(key).await;
+            }
+            tx.rollback().await.ok();
+            return Err(err);
+        }
+        if let Err(err) = tx.commit().await {
+            let _ = self.git_storage.set_latest_commit(user_id, None).await;
+            let _ = self.git_storage.delete_pack(user_id, &commit_id).await;
+            for key in snapshot_keys.iter().rev() {
+                let _ = self.git_storage.delete_blob(key).await;
+            }
+            return Err(err.into());
+        }
+        Ok(())
+    }
+}
+
+/// Connects to the remote and lists its refs; failures are reported in the result rather
+/// than as errors so callers can show what went wrong.
+fn check_remote(cfg: &UserGitCfg) -> GitConnectionCheck {
+    let mut check = GitConnectionCheck {
+        branch: cfg.branch_name.clone(),
+        reachable: false,
+        authenticated: false,
+        branch_exists: false,
+        error: None,
+        message: None,
+    };
+    if cfg.repository_url.trim().is_empty() {
+        check.error = Some(GitConnectionError::NotConfigured);
+        return check;
+    }
+    let listed =
+        git2::Remote::create_detached(cfg.repository_url.as_str()).and_then(|mut remote| {
+            let conn = remote.connect_auth(
+                git2::Direction::Fetch,
+                Some(build_remote_callbacks(cfg)),
+                None,
+            )?;
+            Ok(conn
+                .list()?
+                .iter()
+                .map(|head| head.name().to_string())
+                .collect::<Vec<_>>())
+        });
+    match listed {
+        Ok(refs) => {
+            check.reachable = true;
+            check.authenticated = true;
+            let branch_ref = format!("refs/heads/{}", cfg.branch_name);
+            check.branch_exists = refs.contains(&branch_ref);
+            if !check.branch_exists {
+                check.error = Some(GitConnectionError::BranchMissing);
+                check.message = Some(format!("branch {} not found on remote", cfg.branch_name));
+            }
+        }
+        Err(err) => {
+            let category = classify_remote_error(&err);
+            // A rejected login still proves the host answered.
+            check.reachable = category == GitConnectionError::AuthFailed;
+            check.error = Some(category);
+            check.message = Some(err.message().to_string());
+        }
+    }
+    check
+}
+
+fn classify_remote_error(err: &git2::Error) -> GitConnectionError {
+    use git2::{ErrorClass, ErrorCode};
+    let message = err.message().to_ascii_lowercase();
+    if err.code() == ErrorCode::Auth
+        || message.contains("authentication")
+        || message.contains("401")
+        || message.contains("403")
+    {
+        return GitConnectionError::AuthFailed;
+    }
+    let network_class = matches!(err.class(), ErrorClass::Net | ErrorClass::Os);
+    if network_class
+        || err.code() == ErrorCode::NotFound
+        || [
+            "resolve",
+            "could not connect",
+            "timed out",
+            "connection refused",
+        ]
+        .iter()
+        .any(|needle| message.contains(needle))
+    {
+        return GitConnectionError::HostUnreachable;
+    }
+    GitConnectionError::Other
+}
+
+/// Document reads in flight at once while collecting workspace state.
+const DOC_READ_CONCURRENCY: usize = 16;
+
+/// Snapshots `(id, recorded content hash)` documents, reading concurrently; documents without a
+/// stored file are skipped.
+async fn read_document_snapshots(
+    storage: &dyn StoragePort,
+    layout: &RepoLayout,
+    docs: Vec<(Uuid, Option<String>)>,
+    previous: &HashMap<String, String>,
+) -> anyhow::Result<HashMap<String, FileSnapshot>> {
+    let mut reads = futures_util::stream::iter(docs)
+        .map(|(doc_id, hash)| read_document_snapshot(storage, layout, doc_id, hash, previous))
+        .buffer_unordered(DOC_READ_CONCURRENCY);
+    let mut state = HashMap::new();
+    while let Some(entry) = reads.next().await {
+        if let Some((path, snapshot)) = entry? {
+            state.insert(path, snapshot);
+        }
+    }
+    Ok(state)
+}
+
+async fn read_document_snapshot(
+    storage: &dyn StoragePort,
+    layout: &RepoLayout,
+    doc_id: Uuid,
+    recorded_hash: Option<String>,
+    previous: &HashMap<String, String>,
+) -> anyhow::Result<Option<(String, FileSnapshot)>> {
+    let path = storage.build_doc_file_path(doc_id).await?;
+    let relative = storage.relative_from_uploads(path.as_path());
+    let repo_path = layout.document_path(doc_id, &relative)?;
+    if let Some(hash) = recorded_hash.filter(|hash| previous.get(&repo_path) == Some(hash)) {
+        // Unchanged since the last commit; bytes are loaded lazily if a sync needs them.
+        return Ok(Some((
+            repo_path,
+            FileSnapshot {
+                hash,
+                data: FileSnapshotData::StoragePath(relative),
+                is_text: true,
+            },
+        )));
+    }
+    let bytes = match storage.read_bytes(path.as_path()).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            if let Some(io_err) = err.downcast_ref::<std::io::Error>()
+                && io_err.kind() == std::io::ErrorKind::NotFound
+            {
+                return Ok(None);
+            }
+            if err.to_string().contains("not found") {
+                return Ok(None);
+            }
+            return Err(err);
+        }
+    };
+    let hash = sha256_hex(&bytes);
+    Ok(Some((
+        repo_path,
+        FileSnapshot {
+            hash,
+            data: FileSnapshotData::Inline(bytes),
+            is_text: true,
+        },
+    )))
 }
 
 fn row_to_commit_meta(row: sqlx::postgres::PgRow) -> anyhow::Result<CommitMeta> {
@@ -1240,14 +1705,12 @@ fn fetch_remote_and_verify(
     }
     let remote_head = fetch_remote_head(repo, cfg, branch)?;
     match (latest_meta, remote_head) {
-        (Some(meta), Some(oid)) => {
-            if oid.as_bytes() != meta.commit_id.as_slice() {
-                anyhow::bail!(
-                    "remote repository state diverged: remote head {} does not match latest recorded commit {}",
-                    oid.to_string(),
-                    encode_commit_id(&meta.commit_id)
-                );
-            }
+        (Some(meta), Some(oid)) if oid.as_bytes() != meta.commit_id.as_slice() => {
+            anyhow::bail!(
+                "remote repository state diverged: remote head {} does not match latest recorded commit {}",
+                oid.to_string(),
+                encode_commit_id(&meta.commit_id)
+            );
         }
         (None, Some(oid)) => {
             anyhow::bail!(
@@ -1260,6 +1723,43 @@ fn fetch_remote_and_verify(
     Ok(())
 }
 
+/// Fetches `cfg`'s branch into a scratch repository and packs its head with all history.
+fn fetch_remote_snapshot(cfg: &UserGitCfg) -> anyhow::Result<Option<GitRemoteSnapshot>> {
+    let temp_dir = TempDirBuilder::new()
+        .prefix("git-import-")
+        .tempdir()
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let repo = Repository::init_bare(temp_dir.path())?;
+    let Some(oid) = fetch_remote_head(&repo, cfg, &cfg.branch_name)? else {
+        return Ok(None);
+    };
+    let files = read_commit_files(&repo, oid.as_bytes())?
+        .into_iter()
+        .collect();
+
+    let commit = repo.find_commit(oid)?;
+    let author = commit.author();
+    let info = GitCommitInfo {
+        hash: encode_commit_id(oid.as_bytes()),
+        message: commit.message().unwrap_or_default().to_string(),
+        author_name: author.name().unwrap_or_default().to_string(),
+        author_email: author.email().unwrap_or_default().to_string(),
+        time: DateTime::from_timestamp(commit.time().seconds(), 0).unwrap_or_else(Utc::now),
+    };
+
+    let mut walk = repo.revwalk()?;
+    walk.push(oid)?;
+    let mut pack_builder = repo.packbuilder()?;
+    pack_builder.insert_walk(&mut walk)?;
+    let mut pack_buf = git2::Buf::new();
+    pack_builder.write_buf(&mut pack_buf)?;
+    Ok(Some(GitRemoteSnapshot {
+        commit: info,
+        files,
+        pack: pack_buf.to_vec(),
+    }))
+}
+
 fn read_commit_files(
     repo: &Repository,
     commit_id: &[u8],
@@ -1269,19 +1769,44 @@ fn read_commit_files(
     let tree = commit.tree()?;
     let mut files = HashMap::new();
     tree.walk(TreeWalkMode::PreOrder, |root, entry| {
-        if entry.kind() == Some(ObjectType::Blob) {
-            if let Some(name) = entry.name() {
-                if let Ok(blob) = repo.find_blob(entry.id()) {
-                    let key = format!("{}{}", root, name);
-                    files.insert(key, blob.content().to_vec());
-                }
-            }
+        if entry.kind() == Some(ObjectType::Blob)
+            && let Some(name) = entry.name()
+            && let Ok(blob) = repo.find_blob(entry.id())
+        {
+            let key = format!("{}{}", root, name);
+            files.insert(key, blob.content().to_vec());
         }
         TreeWalkResult::Ok
     })?;
     Ok(files)
 }
 
+fn read_commit_file(
+    repo: &Repository,
+    commit_id: &[u8],
+    path: &str,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let oid = git2::Oid::from_bytes(commit_id)?;
+    let tree = repo.find_commit(oid)?.tree()?;
+    let entry = match tree.get_path(std::path::Path::new(path)) {
+        Ok(entry) => entry,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let blob = repo.find_blob(entry.id())?;
+    Ok(Some(blob.content().to_vec()))
+}
+
+fn commit_info(meta: &CommitMeta) -> GitCommitInfo {
+    GitCommitInfo {
+        hash: encode_commit_id(&meta.commit_id),
+        message: meta.message.clone().unwrap_or_default(),
+        author_name: meta.author_name.clone().unwrap_or_default(),
+        author_email: meta.author_email.clone().unwrap_or_default(),
+        time: meta.committed_at,
+    }
+}
+
 fn perform_push(
     repo: &Repository,
     cfg: &UserGitCfg,
@@ -1405,19 +1930,56 @@ struct FileDeltaSummary {
     deleted: Vec<String>,
 }
 
-fn repo_relative_path(path: &str) -> anyhow::Result<String> {
-    let trimmed = path.trim_start_matches('/');
-    let mut parts = trimmed.splitn(2, '/');
-    let leading = parts.next().unwrap_or("");
-    if let Some(rest) = parts.next() {
-        Ok(rest.to_string())
-    } else if !leading.is_empty() {
-        Ok(leading.to_string())
-    } else {
-        Err(anyhow!("invalid storage path for repository: {path}"))
+fn compute_deltas(
+    current: &HashMap<String, FileSnapshot>,
+    previous: &HashMap<String, String>,
+) -> FileDeltaSummary {
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    let mut deleted = Vec::new();
+
+    for (path, snapshot) in current.iter() {
+        match previous.get(path) {
+            None => added.push(path.clone()),
+            Some(prev_hash) if prev_hash != &snapshot.hash => modified.push(path.clone()),
+            _ => {}
+        }
+    }
+
+    for path in previous.keys() {
+        if !current.contains_key(path) {
+            deleted.push(path.clone());
+        }
+    }
+
+    FileDeltaSummary {
+        added,
+        modified,
+        deleted,
     }
 }
 
+fn change_items(delta: &FileDeltaSummary) -> Vec<GitChangeItem> {
+    let tagged = [
+        (&delta.added, "untracked"),
+        (&delta.modified, "modified"),
+        (&delta.deleted, "deleted"),
+    ];
+    tagged
+        .into_iter()
+        .flat_map(|(paths, status)| {
+            paths.iter().map(move |path| GitChangeItem {
+                path: path.clone(),
+                status: status.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn exclude_ignored(state: &mut HashMap<String, FileSnapshot>, ignored: &GitignoreMatcher) {
+    state.retain(|path, _| !ignored.is_ignored(path));
+}
+
 fn sha256_hex(bytes: &[u8]) -> String {
     use sha2::Digest;
     let mut hasher = sha2::Sha256::new();
@@ -1432,3 +1994,419 @@ fn blob_key(user_id: Uuid, commit_id: &[u8], path: &str) -> BlobKey {
         path: format!("{}/{}/{}", user_id, commit_hex, encoded_path),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(hash: &str) -> FileSnapshot {
+        FileSnapshot {
+            hash: hash.to_string(),
+            data: FileSnapshotData::Inline(Vec::new()),
+            is_text: true,
+        }
+    }
+
+    fn workspace() -> HashMap<String, FileSnapshot> {
+        HashMap::from([
+            ("notes/a.md".to_string(), snapshot("a1")),
+            ("notes/b.md".to_string(), snapshot("b1")),
+            ("attachments/b/img.png".to_string(), snapshot("i1")),
+        ])
+    }
+
+    fn sorted(mut paths: Vec<String>) -> Vec<String> {
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn ignored_paths_leave_the_sync_delta() {
+        let committed: HashMap<String, String> =
+            HashMap::from([("notes/a.md".to_string(), "a0".to_string())]);
+
+        let mut current = workspace();
+        exclude_ignored(
+            &mut current,
+            &GitignoreMatcher::new(&["notes/b.md", "attachments/b/"]),
+        );
+        let delta = compute_deltas(&current, &committed);
+        assert!(delta.added.is_empty());
+        assert_eq!(delta.modified, vec!["notes/a.md".to_string()]);
+        assert!(delta.deleted.is_empty());
+
+        // Un-ignoring brings the document and its attachment back as new files.
+        let mut current = workspace();
+        exclude_ignored(&mut current, &GitignoreMatcher::new::<&str>(&[]));
+        let delta = compute_deltas(&current, &committed);
+        assert_eq!(
+            sorted(delta.added),
+            vec![
+                "attachments/b/img.png".to_string(),
+                "notes/b.md".to_string()
+            ]
+        );
+    }
+
+    /// Serves `doc-<id>.md` for every document except `missing`, tracking concurrent reads.
+    #[derive(Default)]
+    struct SlowStorage {
+        missing: Option<Uuid>,
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_in_flight: std::sync::atomic::AtomicUsize,
+        reads: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl StoragePort for SlowStorage {
+        async fn move_folder_subtree(&self, _: Uuid) -> anyhow::Result<usize> {
+            unimplemented!()
+        }
+        async fn delete_doc_physical(&self, _: Uuid) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn delete_folder_physical(&self, _: Uuid) -> anyhow::Result<usize> {
+            unimplemented!()
+        }
+        async fn build_doc_dir(&self, _: Uuid) -> anyhow::Result<PathBuf> {
+            unimplemented!()
+        }
+        async fn build_doc_file_path(&self, doc_id: Uuid) -> anyhow::Result<PathBuf> {
+            Ok(PathBuf::from(format!("/uploads/owner/doc-{}.md", doc_id)))
+        }
+        fn relative_from_uploads(&self, abs: &std::path::Path) -> String {
+            abs.strip_prefix("/uploads")
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        }
+        fn user_repo_dir(&self, _: Uuid) -> String {
+            unimplemented!()
+        }
+        fn absolute_from_relative(&self, _: &str) -> PathBuf {
+            unimplemented!()
+        }
+        async fn sync_doc_paths(&self, _: Uuid) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn resolve_upload_path(&self, _: Uuid, _: &str) -> anyhow::Result<PathBuf> {
+            unimplemented!()
+        }
+        async fn read_bytes(&self, abs_path: &std::path::Path) -> anyhow::Result<Vec<u8>> {
+            use std::sync::atomic::Ordering;
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            for _ in 0..4 {
+                tokio::task::yield_now().await;
+            }
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            let name = abs_path.to_string_lossy().to_string();
+            if self
+                .missing
+                .is_some_and(|id| name.contains(&id.to_string()))
+            {
+                return Err(std::io::Error::from(std::io::ErrorKind::NotFound).into());
+            }
+            Ok(name.into_bytes())
+        }
+        async fn stat(
+            &self,
+            _: &std::path::Path,
+        ) -> anyhow::Result<crate::application::ports::storage_port::StoredObjectMeta> {
+            unimplemented!()
+        }
+        async fn read_range(&self, _: &std::path::Path, _: u64, _: u64) -> anyhow::Result<Vec<u8>> {
+            unimplemented!()
+        }
+        async fn write_bytes(&self, _: &std::path::Path, _: &[u8]) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn store_doc_attachment(
+            &self,
+            _: Uuid,
+            _: Option<&str>,
+            _: &[u8],
+        ) -> anyhow::Result<crate::application::ports::storage_port::StoredAttachment> {
+            unimplemented!()
+        }
+        async fn self_test(&self) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn documents_are_read_concurrently() {
+        let ids: Vec<Uuid> = (0..50).map(|_| Uuid::new_v4()).collect();
+        let storage = SlowStorage {
+            missing: Some(ids[7]),
+            ..Default::default()
+        };
+        let docs = ids.iter().map(|id| (*id, None)).collect();
+        let state =
+            read_document_snapshots(&storage, &RepoLayout::default(), docs, &HashMap::new())
+                .await
+                .unwrap();
+
+        assert_eq!(state.len(), ids.len() - 1);
+        for id in ids.iter().filter(|id| **id != ids[7]) {
+            let path = format!("doc-{}.md", id);
+            let snapshot = state.get(&path).expect("document in state");
+            let expected = format!("/uploads/owner/{}", path);
+            assert_eq!(snapshot.hash, sha256_hex(expected.as_bytes()));
+        }
+        let max = storage
+            .max_in_flight
+            .load(std::sync::atomic::Ordering::SeqCst);
+        assert!(max > 1, "reads were sequential");
+        assert!(max <= DOC_READ_CONCURRENCY);
+    }
+
+    #[tokio::test]
+    async fn documents_matching_the_last_commit_are_not_read() {
+        let storage = SlowStorage::default();
+        let (unchanged, edited, unhashed) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let committed = |id: Uuid| sha256_hex(format!("/uploads/owner/doc-{}.md", id).as_bytes());
+        let previous: HashMap<String, String> = [unchanged, edited, unhashed]
+            .into_iter()
+            .map(|id| (format!("doc-{}.md", id), committed(id)))
+            .collect();
+        let docs = vec![
+            (unchanged, Some(committed(unchanged))),
+            (edited, Some("stale".to_string())),
+            (unhashed, None),
+        ];
+
+        let state = read_document_snapshots(&storage, &RepoLayout::default(), docs, &previous)
+            .await
+            .unwrap();
+
+        assert_eq!(storage.reads.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert!(matches!(
+            state[&format!("doc-{}.md", unchanged)].data,
+            FileSnapshotData::StoragePath(_)
+        ));
+        let delta = compute_deltas(&state, &previous);
+        assert!(delta.added.is_empty() && delta.modified.is_empty() && delta.deleted.is_empty());
+    }
+
+    #[tokio::test]
+    async fn git_tree_follows_the_folder_hierarchy_under_the_hierarchy_layout() {
+        let storage = SlowStorage::default();
+        let folder = |title: &str, parent_id| LayoutNode {
+            id: Uuid::new_v4(),
+            parent_id,
+            title: title.into(),
+            is_folder: true,
+        };
+        let doc = |title: &str, parent_id| LayoutNode {
+            is_folder: false,
+            ..folder(title, parent_id)
+        };
+        let work = folder("Work", None);
+        let meetings = folder("Meetings", Some(work.id));
+        let standup = doc("Daily Standup", Some(meetings.id));
+        let standup_copy = doc("Daily standup", Some(meetings.id));
+        let readme = doc("README", None);
+        let nodes = [
+            work.clone(),
+            meetings.clone(),
+            standup.clone(),
+            standup_copy.clone(),
+            readme.clone(),
+        ];
+        let layout = RepoLayout::hierarchy(&nodes, &HashMap::new());
+        let docs = [&standup, &standup_copy, &readme]
+            .iter()
+            .map(|n| (n.id, None))
+            .collect();
+
+        let state = read_document_snapshots(&storage, &layout, docs, &HashMap::new())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            sorted(state.keys().cloned().collect()),
+            vec![
+                "readme.md",
+                "work/meetings/daily-standup-2.md",
+                "work/meetings/daily-standup.md",
+            ]
+        );
+        // Contents are still read from the id-based storage paths.
+        let expected = format!("/uploads/owner/doc-{}.md", standup.id);
+        assert_eq!(
+            state["work/meetings/daily-standup.md"].hash,
+            sha256_hex(expected.as_bytes())
+        );
+    }
+
+    fn remote_cfg(url: &str, branch: &str) -> UserGitCfg {
+        UserGitCfg {
+            repository_url: url.to_string(),
+            branch_name: branch.to_string(),
+            auth_type: Some("token".into()),
+            auth_data: None,
+            auto_sync: false,
+            commit_message_template: None,
+            append_change_summary: false,
+        }
+    }
+
+    #[test]
+    fn connection_check_lists_local_remote() {
+        let dir = TempDirBuilder::new().prefix("remote-").tempdir().unwrap();
+        let repo = Repository::init_bare(dir.path()).unwrap();
+        let tree_oid = repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = repo.find_tree(tree_oid).unwrap();
+        let sig = Signature::now("RefMD", "refmd@example.com").unwrap();
+        repo.commit(Some("refs/heads/main"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+        let url = dir.path().to_str().unwrap();
+
+        let ok = check_remote(&remote_cfg(url, "main"));
+        assert!(ok.ok(), "{:?}", ok.message);
+        assert!(ok.reachable && ok.authenticated && ok.branch_exists);
+
+        let missing = check_remote(&remote_cfg(url, "publish"));
+        assert_eq!(missing.error, Some(GitConnectionError::BranchMissing));
+        assert!(missing.reachable && !missing.branch_exists);
+    }
+
+    #[test]
+    fn connection_failures_are_categorized() {
+        let gone = check_remote(&remote_cfg("/nonexistent/refmd/remote.git", "main"));
+        assert_eq!(gone.error, Some(GitConnectionError::HostUnreachable));
+        assert!(!gone.reachable && !gone.authenticated);
+
+        let unset = check_remote(&remote_cfg("", "main"));
+        assert_eq!(unset.error, Some(GitConnectionError::NotConfigured));
+
+        let auth = git2::Error::new(
+            git2::ErrorCode::Auth,
+            git2::ErrorClass::Http,
+            "authentication required but no callback set",
+        );
+        assert_eq!(classify_remote_error(&auth), GitConnectionError::AuthFailed);
+        let dns = git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Net,
+            "failed to resolve address for git.invalid",
+        );
+        assert_eq!(
+            classify_remote_error(&dns),
+            GitConnectionError::HostUnreachable
+        );
+        let other = git2::Error::new(
+            git2::ErrorCode::GenericError,
+            git2::ErrorClass::Odb,
+            "corrupt object",
+        );
+        assert_eq!(classify_remote_error(&other), GitConnectionError::Other);
+    }
+
+    #[test]
+    fn ignoring_a_committed_path_reports_it_deleted() {
+        let committed: HashMap<String, String> = workspace()
+            .into_iter()
+            .map(|(path, snap)| (path, snap.hash))
+            .collect();
+        let mut current = workspace();
+        exclude_ignored(&mut current, &GitignoreMatcher::new(&["a.md"]));
+        let delta = compute_deltas(&current, &committed);
+        assert_eq!(delta.deleted, vec!["notes/a.md".to_string()]);
+        assert!(delta.added.is_empty() && delta.modified.is_empty());
+    }
+
+    #[test]
+    fn renaming_a_document_commits_as_a_git_rename() {
+        let folder = LayoutNode {
+            id: Uuid::new_v4(),
+            parent_id: None,
+            title: "Journal".into(),
+            is_folder: true,
+        };
+        let note = LayoutNode {
+            id: Uuid::new_v4(),
+            parent_id: Some(folder.id),
+            title: "My Note".into(),
+            is_folder: false,
+        };
+        let content = b"# My Note\n\nSome thoughts worth keeping around.\n".to_vec();
+        let temp_dir = TempDirBuilder::new()
+            .prefix("git-layout-")
+            .tempdir()
+            .unwrap();
+        let repo = Repository::init_bare(temp_dir.path()).unwrap();
+        let tree_for = |layout: &RepoLayout| {
+            let path = layout.document_path(note.id, "ignored").unwrap();
+            let entries = BTreeMap::from([(path, content.clone())]);
+            repo.find_tree(build_tree_from_entries(&repo, &entries).unwrap())
+                .unwrap()
+        };
+
+        let before = RepoLayout::hierarchy(&[folder.clone(), note.clone()], &HashMap::new());
+        assert_eq!(
+            before.document_path(note.id, "ignored").unwrap(),
+            "journal/my-note.md"
+        );
+        let renamed = LayoutNode {
+            title: "My Renamed Note".into(),
+            ..note.clone()
+        };
+        let after = RepoLayout::hierarchy(&[folder, renamed], before.paths());
+
+        let mut diff = repo
+            .diff_tree_to_tree(Some(&tree_for(&before)), Some(&tree_for(&after)), None)
+            .unwrap();
+        diff.find_similar(None).unwrap();
+        let deltas: Vec<_> = diff.deltas().collect();
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].status(), git2::Delta::Renamed);
+        assert_eq!(
+            deltas[0].old_file().path().unwrap(),
+            std::path::Path::new("journal/my-note.md")
+        );
+        assert_eq!(
+            deltas[0].new_file().path().unwrap(),
+            std::path::Path::new("journal/my-renamed-note.md")
+        );
+    }
+
+    #[test]
+    fn blame_attributes_lines_across_two_commits() {
+        let temp_dir = TempDirBuilder::new()
+            .prefix("git-blame-")
+            .tempdir()
+            .unwrap();
+        let repo = Repository::init_bare(temp_dir.path()).unwrap();
+        let sig = Signature::now("refmd", "refmd@example.com").unwrap();
+        let commit = |content: &str, parent: Option<&Commit>| {
+            let entries = BTreeMap::from([("notes/plan.md".to_string(), content.into())]);
+            let tree = repo
+                .find_tree(build_tree_from_entries(&repo, &entries).unwrap())
+                .unwrap();
+            let parents: Vec<&Commit> = parent.into_iter().collect();
+            let oid = repo
+                .commit(None, &sig, &sig, "sync", &tree, &parents)
+                .unwrap();
+            repo.find_commit(oid).unwrap()
+        };
+        let first = commit("# Plan\nalpha\nbeta\n", None);
+        let second = commit("# Plan\nalpha (edited)\ngamma\nbeta\n", Some(&first));
+
+        let read = |c: &Commit| {
+            let bytes = read_commit_file(&repo, c.id().as_bytes(), "notes/plan.md")
+                .unwrap()
+                .unwrap();
+            String::from_utf8(bytes).unwrap()
+        };
+        let (old, new) = (read(&first), read(&second));
+        assert_eq!(diff::blame_lines(&[&old, &new]), vec![0, 1, 1, 0]);
+        assert!(
+            read_commit_file(&repo, second.id().as_bytes(), "notes/missing.md")
+                .unwrap()
+                .is_none()
+        );
+    }
+}
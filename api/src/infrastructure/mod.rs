@@ -1,5 +1,6 @@
 pub mod crypto;
 pub mod db;
+pub mod export;
 pub mod git;
 pub mod plugins;
 pub mod realtime;
@@ -0,0 +1,188 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use futures_util::StreamExt;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::application::ports::plugin_event_publisher::PluginScopedEvent;
+use crate::infrastructure::plugins::event_bus_pg::PgPluginEventBus;
+
+const LIVE_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone)]
+pub struct SequencedEvent {
+    pub id: u64,
+    pub event: PluginScopedEvent,
+}
+
+struct Buffered {
+    at: Instant,
+    event: SequencedEvent,
+}
+
+/// Numbers plugin events as they arrive from the bus and keeps a short,
+/// expiring history per user so SSE clients can resume after a reconnect.
+///
+/// Ids are only meaningful within one process; they start from the wall clock
+/// so ids handed out before a restart sort below the new ones.
+pub struct PluginEventReplay {
+    next_id: AtomicU64,
+    capacity: usize,
+    ttl: Duration,
+    // Keyed by recipient; `None` holds events broadcast to every user.
+    buffers: Mutex<HashMap<Option<Uuid>, VecDeque<Buffered>>>,
+    live: broadcast::Sender<SequencedEvent>,
+}
+
+impl PluginEventReplay {
+    pub fn new(capacity: usize, ttl: Duration) -> Arc<Self> {
+        let start = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let (live, _) = broadcast::channel(LIVE_CHANNEL_CAPACITY);
+        Arc::new(Self {
+            next_id: AtomicU64::new(start.max(1)),
+            capacity: capacity.max(1),
+            ttl,
+            buffers: Mutex::new(HashMap::new()),
+            live,
+        })
+    }
+
+    /// Assigns the next id to `event`, buffers it and fans it out to live subscribers.
+    pub fn record(&self, event: PluginScopedEvent) -> SequencedEvent {
+        let now = Instant::now();
+        let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        let sequenced = SequencedEvent {
+            id: self.next_id.fetch_add(1, Ordering::SeqCst),
+            event,
+        };
+        let buffer = buffers.entry(sequenced.event.user_id).or_default();
+        buffer.push_back(Buffered {
+            at: now,
+            event: sequenced.clone(),
+        });
+        while buffer.len() > self.capacity {
+            buffer.pop_front();
+        }
+        self.expire(&mut buffers, now);
+        // Sending under the lock keeps `subscribe` from seeing an event both in
+        // the replay and on the live channel.
+        let _ = self.live.send(sequenced.clone());
+        sequenced
+    }
+
+    /// Returns the buffered events visible to `user_id` newer than
+    /// `last_event_id`, together with a receiver for everything after them.
+    pub fn subscribe(
+        &self,
+        user_id: Uuid,
+        last_event_id: Option<u64>,
+    ) -> (Vec<SequencedEvent>, broadcast::Receiver<SequencedEvent>) {
+        let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        self.expire(&mut buffers, Instant::now());
+        let mut missed = Vec::new();
+        if let Some(last) = last_event_id {
+            for key in [Some(user_id), None] {
+                if let Some(buffer) = buffers.get(&key) {
+                    missed.extend(
+                        buffer
+                            .iter()
+                            .filter(|b| b.event.id > last)
+                            .map(|b| b.event.clone()),
+                    );
+                }
+            }
+            missed.sort_by_key(|e| e.id);
+        }
+        (missed, self.live.subscribe())
+    }
+
+    fn expire(&self, buffers: &mut HashMap<Option<Uuid>, VecDeque<Buffered>>, now: Instant) {
+        buffers.retain(|_, buffer| {
+            while buffer
+                .front()
+                .is_some_and(|b| now.duration_since(b.at) > self.ttl)
+            {
+                buffer.pop_front();
+            }
+            !buffer.is_empty()
+        });
+    }
+
+    pub fn spawn_forwarder(self: &Arc<Self>, bus: Arc<PgPluginEventBus>) {
+        let replay = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                match bus.subscribe().await {
+                    Ok(mut stream) => {
+                        while let Some(event) = stream.next().await {
+                            replay.record(event);
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!(error = ?err, "plugin_event_replay_subscribe_failed");
+                    }
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(user_id: Option<Uuid>, n: u64) -> PluginScopedEvent {
+        PluginScopedEvent {
+            user_id,
+            payload: serde_json::json!({ "n": n }),
+        }
+    }
+
+    #[tokio::test]
+    async fn replays_events_missed_since_last_id() {
+        let replay = PluginEventReplay::new(16, Duration::from_secs(60));
+        let user = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        let (_, mut first) = replay.subscribe(user, None);
+        let seen = replay.record(event(Some(user), 1));
+        assert_eq!(first.recv().await.unwrap().id, seen.id);
+        drop(first);
+
+        // Emitted while the client is disconnected.
+        let missed_own = replay.record(event(Some(user), 2));
+        replay.record(event(Some(other), 3));
+        let missed_global = replay.record(event(None, 4));
+
+        let (missed, mut live) = replay.subscribe(user, Some(seen.id));
+        let ids: Vec<u64> = missed.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![missed_own.id, missed_global.id]);
+
+        let next = replay.record(event(Some(user), 5));
+        assert_eq!(live.recv().await.unwrap().id, next.id);
+    }
+
+    #[test]
+    fn buffer_is_bounded_and_expires() {
+        let replay = PluginEventReplay::new(2, Duration::from_secs(60));
+        let user = Uuid::new_v4();
+        let first = replay.record(event(Some(user), 1));
+        replay.record(event(Some(user), 2));
+        replay.record(event(Some(user), 3));
+        let (missed, _) = replay.subscribe(user, Some(first.id - 1));
+        assert_eq!(missed.len(), 2);
+
+        let replay = PluginEventReplay::new(8, Duration::ZERO);
+        let first = replay.record(event(Some(user), 1));
+        std::thread::sleep(Duration::from_millis(5));
+        let (missed, _) = replay.subscribe(user, Some(first.id - 1));
+        assert!(missed.is_empty());
+    }
+}
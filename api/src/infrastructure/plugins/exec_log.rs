@@ -0,0 +1,412 @@
+//! Records plugin invocations so operators and plugin owners can see what a plugin was asked
+//! to do and how it went.
+//!
+//! Failures are always recorded; successful calls are sampled deterministically (every
+//! `sample_every`-th) so busy plugins do not flood the log. Entries go to the `plugin_exec`
+//! tracing target and to the exec log repository. Payloads are only kept when enabled, with
+//! the user's secret values and secret-looking fields redacted.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::application::dto::plugins::ExecResult;
+use crate::application::ports::plugin_exec_log_repository::{
+    PluginExecLogEntry, PluginExecLogRepository,
+};
+use crate::application::ports::plugin_runtime::{PluginRuntime, PluginScheduleSpec, PluginSecrets};
+
+pub const LOG_TARGET: &str = "plugin_exec";
+
+const REDACTED: &str = "[redacted]";
+const SENSITIVE_FIELDS: [&str; 5] = ["password", "secret", "token", "apikey", "authorization"];
+
+#[derive(Debug, Clone, Copy)]
+pub struct PluginExecLogOptions {
+    /// Record one in every `sample_every` successful calls; 0 records failures only.
+    pub sample_every: u64,
+    pub log_payloads: bool,
+}
+
+pub struct LoggingPluginRuntime {
+    inner: Arc<dyn PluginRuntime>,
+    log: Arc<dyn PluginExecLogRepository>,
+    options: PluginExecLogOptions,
+    successes: AtomicU64,
+}
+
+struct Outcome {
+    ok: bool,
+    output_bytes: Option<i64>,
+    error: Option<String>,
+}
+
+impl LoggingPluginRuntime {
+    pub fn new(
+        inner: Arc<dyn PluginRuntime>,
+        log: Arc<dyn PluginExecLogRepository>,
+        options: PluginExecLogOptions,
+    ) -> Self {
+        Self {
+            inner,
+            log,
+            options,
+            successes: AtomicU64::new(0),
+        }
+    }
+
+    fn sampled(&self, ok: bool) -> bool {
+        if !ok {
+            return true;
+        }
+        match self.options.sample_every {
+            0 => false,
+            n => self
+                .successes
+                .fetch_add(1, Ordering::Relaxed)
+                .is_multiple_of(n),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn record(
+        &self,
+        user_id: Option<Uuid>,
+        plugin: &str,
+        function: String,
+        input: &serde_json::Value,
+        started: Instant,
+        outcome: Outcome,
+        secrets: &PluginSecrets,
+    ) {
+        if !self.sampled(outcome.ok) {
+            return;
+        }
+        let entry = PluginExecLogEntry {
+            user_id,
+            plugin: plugin.to_string(),
+            function,
+            input_bytes: serde_json::to_vec(input).map_or(0, |bytes| bytes.len() as i64),
+            output_bytes: outcome.output_bytes,
+            duration_ms: started.elapsed().as_millis() as i64,
+            ok: outcome.ok,
+            error: outcome.error.map(|error| redact_str(&error, secrets)),
+            payload: self.options.log_payloads.then(|| redact(input, secrets)),
+            created_at: Utc::now(),
+        };
+        tracing::info!(
+            target: LOG_TARGET,
+            user_id = ?entry.user_id,
+            plugin = entry.plugin.as_str(),
+            function = entry.function.as_str(),
+            input_bytes = entry.input_bytes,
+            output_bytes = ?entry.output_bytes,
+            duration_ms = entry.duration_ms,
+            ok = entry.ok,
+            error = ?entry.error,
+            "plugin_exec"
+        );
+        if let Err(err) = self.log.append(&entry).await {
+            tracing::warn!(error = ?err, plugin = plugin, "plugin_exec_log_append_failed");
+        }
+    }
+}
+
+fn output_len<T: serde::Serialize>(value: &T) -> Option<i64> {
+    serde_json::to_vec(value)
+        .ok()
+        .map(|bytes| bytes.len() as i64)
+}
+
+fn is_sensitive_field(name: &str) -> bool {
+    let normalized: String = name
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect();
+    SENSITIVE_FIELDS
+        .iter()
+        .any(|field| normalized.contains(field))
+}
+
+fn redact_str(text: &str, secrets: &PluginSecrets) -> String {
+    secrets
+        .values()
+        .filter(|secret| !secret.is_empty())
+        .fold(text.to_string(), |text, secret| {
+            text.replace(secret.as_str(), REDACTED)
+        })
+}
+
+fn redact(value: &serde_json::Value, secrets: &PluginSecrets) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(name, value)| {
+                    let value = if is_sensitive_field(name) {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        redact(value, secrets)
+                    };
+                    (name.clone(), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(|v| redact(v, secrets)).collect()),
+        Value::String(text) => Value::String(redact_str(text, secrets)),
+        other => other.clone(),
+    }
+}
+
+#[async_trait]
+impl PluginRuntime for LoggingPluginRuntime {
+    async fn execute(
+        &self,
+        user_id: Option<Uuid>,
+        plugin: &str,
+        action: &str,
+        payload: &serde_json::Value,
+        secrets: &PluginSecrets,
+    ) -> anyhow::Result<Option<ExecResult>> {
+        let started = Instant::now();
+        let result = self
+            .inner
+            .execute(user_id, plugin, action, payload, secrets)
+            .await;
+        let outcome = match &result {
+            Ok(None) => return result,
+            Ok(Some(res)) => Outcome {
+                ok: res.ok,
+                output_bytes: output_len(res),
+                error: res.error.as_ref().map(|error| error.to_string()),
+            },
+            Err(err) => Outcome {
+                ok: false,
+                output_bytes: None,
+                error: Some(format!("{err:#}")),
+            },
+        };
+        self.record(
+            user_id,
+            plugin,
+            format!("exec:{action}"),
+            payload,
+            started,
+            outcome,
+            secrets,
+        )
+        .await;
+        result
+    }
+
+    async fn render_placeholder(
+        &self,
+        user_id: Option<Uuid>,
+        plugin: &str,
+        function: &str,
+        request: &serde_json::Value,
+    ) -> anyhow::Result<Option<serde_json::Value>> {
+        let started = Instant::now();
+        let result = self
+            .inner
+            .render_placeholder(user_id, plugin, function, request)
+            .await;
+        let outcome = match &result {
+            Ok(None) => return result,
+            Ok(Some(value)) => Outcome {
+                ok: true,
+                output_bytes: output_len(value),
+                error: None,
+            },
+            Err(err) => Outcome {
+                ok: false,
+                output_bytes: None,
+                error: Some(format!("{err:#}")),
+            },
+        };
+        self.record(
+            user_id,
+            plugin,
+            function.to_string(),
+            request,
+            started,
+            outcome,
+            &PluginSecrets::new(),
+        )
+        .await;
+        result
+    }
+
+    async fn permissions(
+        &self,
+        user_id: Option<Uuid>,
+        plugin: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        self.inner.permissions(user_id, plugin).await
+    }
+
+    async fn schedules(
+        &self,
+        user_id: Option<Uuid>,
+        plugin: &str,
+    ) -> anyhow::Result<Option<Vec<PluginScheduleSpec>>> {
+        self.inner.schedules(user_id, plugin).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use serde_json::json;
+
+    use super::*;
+
+    struct Runtime;
+
+    #[async_trait]
+    impl PluginRuntime for Runtime {
+        async fn execute(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+            action: &str,
+            _: &serde_json::Value,
+            _: &PluginSecrets,
+        ) -> anyhow::Result<Option<ExecResult>> {
+            match action {
+                "sync" => Ok(Some(ExecResult {
+                    ok: true,
+                    data: Some(json!({ "synced": 3 })),
+                    effects: vec![],
+                    error: None,
+                    applied: vec![],
+                })),
+                _ => anyhow::bail!("extism call error: unreachable executed"),
+            }
+        }
+        async fn render_placeholder(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+            _: &str,
+            _: &serde_json::Value,
+        ) -> anyhow::Result<Option<serde_json::Value>> {
+            unimplemented!()
+        }
+        async fn permissions(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+        ) -> anyhow::Result<Option<Vec<String>>> {
+            unimplemented!()
+        }
+        async fn schedules(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+        ) -> anyhow::Result<Option<Vec<PluginScheduleSpec>>> {
+            unimplemented!()
+        }
+    }
+
+    #[derive(Default)]
+    struct Log(Mutex<Vec<PluginExecLogEntry>>);
+
+    #[async_trait]
+    impl PluginExecLogRepository for Log {
+        async fn append(&self, entry: &PluginExecLogEntry) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push(entry.clone());
+            Ok(())
+        }
+        async fn recent(
+            &self,
+            _: Uuid,
+            _: &str,
+            _: i64,
+        ) -> anyhow::Result<Vec<PluginExecLogEntry>> {
+            unimplemented!()
+        }
+    }
+
+    fn runtime(log: Arc<Log>, sample_every: u64) -> LoggingPluginRuntime {
+        LoggingPluginRuntime::new(
+            Arc::new(Runtime),
+            log,
+            PluginExecLogOptions {
+                sample_every,
+                log_payloads: true,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn successful_and_failing_execs_are_logged() {
+        let log = Arc::new(Log::default());
+        let runtime = runtime(log.clone(), 1);
+        let user = Uuid::new_v4();
+        let secrets = PluginSecrets::from([("token".to_string(), "ghp_live".to_string())]);
+        let payload = json!({ "repo": "refmd", "auth": "Bearer ghp_live", "apiKey": "k-1" });
+
+        let ok = runtime
+            .execute(Some(user), "github", "sync", &payload, &secrets)
+            .await;
+        assert!(ok.unwrap().unwrap().ok);
+        let failed = runtime
+            .execute(Some(user), "github", "boom", &payload, &secrets)
+            .await;
+        assert!(failed.is_err());
+
+        let entries = log.0.lock().unwrap();
+        assert_eq!(entries.len(), 2);
+        let (ok, failed) = (&entries[0], &entries[1]);
+        assert_eq!(ok.user_id, Some(user));
+        assert_eq!(ok.plugin, "github");
+        assert_eq!(ok.function, "exec:sync");
+        assert!(ok.ok);
+        assert_eq!(
+            ok.input_bytes,
+            serde_json::to_vec(&payload).unwrap().len() as i64
+        );
+        assert!(ok.output_bytes.is_some_and(|n| n > 0));
+        assert_eq!(ok.error, None);
+        assert_eq!(failed.function, "exec:boom");
+        assert!(!failed.ok);
+        assert_eq!(failed.output_bytes, None);
+        assert!(failed.error.as_deref().unwrap().contains("unreachable"));
+
+        // Secret values and secret-looking fields never reach the log.
+        let payload = ok.payload.as_ref().unwrap();
+        assert_eq!(payload["repo"], "refmd");
+        assert_eq!(payload["auth"], "Bearer [redacted]");
+        assert_eq!(payload["apiKey"], "[redacted]");
+    }
+
+    #[tokio::test]
+    async fn successes_are_sampled_but_failures_always_logged() {
+        let log = Arc::new(Log::default());
+        let runtime = runtime(log.clone(), 3);
+        let secrets = PluginSecrets::new();
+        for _ in 0..6 {
+            runtime
+                .execute(None, "github", "sync", &json!(null), &secrets)
+                .await
+                .unwrap();
+        }
+        runtime
+            .execute(None, "github", "boom", &json!(null), &secrets)
+            .await
+            .unwrap_err();
+
+        let entries = log.0.lock().unwrap();
+        let oks = entries.iter().filter(|e| e.ok).count();
+        assert_eq!(oks, 2);
+        assert_eq!(entries.len(), 3);
+    }
+}
@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 use anyhow::{Context, bail};
 use async_trait::async_trait;
 use chrono::Utc;
-use extism::{Manifest, Plugin, PluginBuilder, Wasm};
+use extism::{CurrentPlugin, Manifest, PTR, Plugin, PluginBuilder, UserData, Val, Wasm};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use semver::Version;
@@ -17,25 +17,65 @@ use uuid::Uuid;
 
 use crate::application::dto::plugins::ExecResult;
 use crate::application::ports::plugin_asset_store::PluginAssetStore;
+use crate::application::ports::plugin_installation_repository::PluginInstallationRepository;
 use crate::application::ports::plugin_installer::{
     InstalledPlugin, PluginInstallError, PluginInstaller,
 };
-use crate::application::ports::plugin_runtime::PluginRuntime;
+use crate::application::ports::plugin_runtime::{
+    PluginInvocationError, PluginRuntime, PluginScheduleSpec, PluginSecrets,
+};
+use crate::infrastructure::plugins::instance_pool::{InstanceBudget, InstancePool};
 
 static PLUGIN_ID_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^[A-Za-z0-9_-]+$").expect("valid regex"));
 static PLUGIN_VERSION_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^[A-Za-z0-9._-]+$").expect("valid regex"));
 
+const PERMISSION_DOC_READ: &str = "doc.read";
+const PERMISSION_DOC_WRITE: &str = "doc.write";
+const PERMISSION_SECRETS: &str = "secrets";
+/// Host function through which plugins read the invoking user's secrets.
+const SECRET_GET_FN: &str = "secret_get";
+/// Present in a global plugin's directory while an admin has it disabled.
+pub(crate) const GLOBAL_DISABLED_FILE: &str = ".disabled";
+
 pub struct FilesystemPluginStore {
     root: PathBuf,
     plugin_cache: Arc<RwLock<HashMap<PathBuf, CachedPlugin>>>,
+    instance_budget: Arc<InstanceBudget>,
     limits: PluginExecutionLimits,
+    installations: Option<Arc<dyn PluginInstallationRepository>>,
 }
 
 struct CachedPlugin {
     modified: SystemTime,
-    plugin: Arc<Mutex<Plugin>>,
+    wasm: Arc<Vec<u8>>,
+    pool: Arc<InstancePool<PluginInstance>>,
+}
+
+/// A plugin instance and the secrets its `secret_get` host function serves. The slot is
+/// filled only while the instance is checked out for a call, and such instances are not
+/// pooled.
+struct PluginInstance {
+    plugin: Plugin,
+    secrets: UserData<PluginSecrets>,
+}
+
+// Unknown keys read as an empty string.
+fn secret_get(
+    plugin: &mut CurrentPlugin,
+    inputs: &[Val],
+    outputs: &mut [Val],
+    secrets: UserData<PluginSecrets>,
+) -> Result<(), extism::Error> {
+    let key: String = plugin.memory_get_val(&inputs[0])?;
+    let value = {
+        let secrets = secrets.get()?;
+        let secrets = secrets.lock().unwrap_or_else(|e| e.into_inner());
+        secrets.get(&key).cloned().unwrap_or_default()
+    };
+    plugin.memory_set_val(&mut outputs[0], value)?;
+    Ok(())
 }
 
 #[derive(Clone, Copy)]
@@ -43,6 +83,10 @@ pub struct PluginExecutionLimits {
     pub timeout: Option<Duration>,
     pub memory_max_pages: Option<u32>,
     pub fuel_limit: Option<u64>,
+    /// Instances kept per wasm module; bounds concurrent calls into one plugin.
+    pub pool_size: usize,
+    /// Instances across all modules; bounds total plugin memory.
+    pub max_instances: usize,
 }
 
 impl PluginExecutionLimits {
@@ -55,17 +99,25 @@ impl PluginExecutionLimits {
             timeout,
             memory_max_pages,
             fuel_limit,
+            pool_size: 4,
+            max_instances: 32,
         }
     }
+
+    pub const fn with_pool(mut self, pool_size: usize, max_instances: usize) -> Self {
+        self.pool_size = pool_size;
+        self.max_instances = max_instances;
+        self
+    }
 }
 
 impl Default for PluginExecutionLimits {
     fn default() -> Self {
-        Self {
-            timeout: Some(Duration::from_secs(10)),
-            memory_max_pages: Some(4096), // ~256 MiB
-            fuel_limit: Some(50_000_000),
-        }
+        Self::new(
+            Some(Duration::from_secs(10)),
+            Some(4096), // ~256 MiB
+            Some(50_000_000),
+        )
     }
 }
 
@@ -84,6 +136,17 @@ impl InvocationKind {
     }
 }
 
+// extism hands wasm traps back as the `wasmtime::Trap` that stopped the call,
+// but turns an epoch interrupt into an untyped "timeout" message, so a timeout
+// is recognised by the call having run for the whole configured limit.
+fn classify_call_error(err: &anyhow::Error, timed_out: bool) -> Option<PluginInvocationError> {
+    if timed_out {
+        return Some(PluginInvocationError::Timeout);
+    }
+    err.downcast_ref::<wasmtime::Trap>()
+        .map(|trap| PluginInvocationError::Trap(trap.to_string()))
+}
+
 impl FilesystemPluginStore {
     pub(crate) fn is_valid_plugin_id(plugin_id: &str) -> bool {
         !plugin_id.is_empty() && PLUGIN_ID_RE.is_match(plugin_id)
@@ -102,10 +165,22 @@ impl FilesystemPluginStore {
         Ok(Self {
             root,
             plugin_cache: Arc::new(RwLock::new(HashMap::new())),
+            instance_budget: InstanceBudget::new(limits.max_instances),
             limits,
+            installations: None,
         })
     }
 
+    /// Runs each user's plugin at the version recorded on their installation
+    /// row instead of the newest version on disk.
+    pub fn with_installations(
+        mut self,
+        installations: Arc<dyn PluginInstallationRepository>,
+    ) -> Self {
+        self.installations = Some(installations);
+        self
+    }
+
     pub fn root(&self) -> &Path {
         &self.root
     }
@@ -190,7 +265,32 @@ impl FilesystemPluginStore {
         Ok(best.map(|(path, _, _)| path))
     }
 
-    fn locate_plugin_dir(
+    /// Version directory serving the user's plugin: the version on their
+    /// installation row, or the newest installed version when there is no row
+    /// or its files are not present yet.
+    async fn user_version_dir(
+        &self,
+        user_id: Uuid,
+        plugin: &str,
+    ) -> anyhow::Result<Option<PathBuf>> {
+        let base = self.user_root(&user_id).join(plugin);
+        if let Some(installations) = &self.installations
+            && let Some(installed) = installations
+                .list_for_user(user_id)
+                .await?
+                .into_iter()
+                .find(|inst| inst.plugin_id == plugin)
+            && PLUGIN_VERSION_RE.is_match(&installed.version)
+        {
+            let dir = base.join(&installed.version);
+            if dir.is_dir() {
+                return Ok(Some(dir));
+            }
+        }
+        self.latest_version_dir(&base)
+    }
+
+    async fn locate_plugin_dir(
         &self,
         user_id: Option<Uuid>,
         plugin: &str,
@@ -198,23 +298,33 @@ impl FilesystemPluginStore {
         if !Self::is_valid_plugin_id(plugin) {
             return Ok(None);
         }
-        if let Some(uid) = user_id {
-            let base = self.user_root(&uid).join(plugin);
-            if let Some(dir) = self.latest_version_dir(&base)? {
-                return Ok(Some(dir));
-            }
+        if let Some(uid) = user_id
+            && let Some(dir) = self.user_version_dir(uid, plugin).await?
+        {
+            return Ok(Some(dir));
         }
         let base = self.global_root().join(plugin);
+        if Self::is_global_disabled(&base) {
+            return Ok(None);
+        }
         self.latest_version_dir(&base)
     }
 
-    async fn resolve_backend_wasm_path(&self, plugin_dir: &Path) -> anyhow::Result<PathBuf> {
+    fn is_global_disabled(base: &Path) -> bool {
+        base.join(GLOBAL_DISABLED_FILE).exists()
+    }
+
+    async fn read_plugin_manifest(plugin_dir: &Path) -> anyhow::Result<JsonValue> {
         let manifest_path = plugin_dir.join("plugin.json");
         let manifest_str = tokio::fs::read_to_string(&manifest_path)
             .await
             .with_context(|| format!("read plugin manifest at {}", manifest_path.display()))?;
-        let manifest: JsonValue = serde_json::from_str(&manifest_str)
-            .with_context(|| format!("parse plugin manifest at {}", manifest_path.display()))?;
+        serde_json::from_str(&manifest_str)
+            .with_context(|| format!("parse plugin manifest at {}", manifest_path.display()))
+    }
+
+    async fn resolve_backend_wasm_path(&self, plugin_dir: &Path) -> anyhow::Result<PathBuf> {
+        let manifest = Self::read_plugin_manifest(plugin_dir).await?;
 
         let wasm_rel = manifest
             .get("backend")
@@ -235,10 +345,59 @@ impl FilesystemPluginStore {
                     .filter_map(|item| item.as_str().map(|s| s.to_string()))
                     .collect::<Vec<String>>()
             })
-            .unwrap_or_else(Vec::new)
+            .unwrap_or_default()
+    }
+
+    fn extract_schedules(manifest: &JsonValue) -> Vec<PluginScheduleSpec> {
+        manifest
+            .get("schedules")
+            .and_then(|value| value.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| {
+                        Some(PluginScheduleSpec {
+                            cron: item.get("cron")?.as_str()?.to_string(),
+                            action: item.get("action")?.as_str()?.to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn build_plugin(
+        wasm_bytes: &[u8],
+        limits: PluginExecutionLimits,
+    ) -> anyhow::Result<PluginInstance> {
+        let mut manifest = Manifest::new([Wasm::data(wasm_bytes.to_vec())]);
+        if let Some(timeout) = limits.timeout {
+            manifest = manifest.with_timeout(timeout);
+        }
+        if let Some(memory_max) = limits.memory_max_pages {
+            manifest = manifest.with_memory_max(memory_max);
+        }
+        let secrets = UserData::new(PluginSecrets::new());
+        let builder = PluginBuilder::new(manifest).with_wasi(true).with_function(
+            SECRET_GET_FN,
+            [PTR],
+            [PTR],
+            secrets.clone(),
+            secret_get,
+        );
+        let builder = if let Some(fuel_limit) = limits.fuel_limit {
+            builder.with_fuel_limit(fuel_limit)
+        } else {
+            builder
+        };
+        let plugin = builder.build().context("create plugin")?;
+        Ok(PluginInstance { plugin, secrets })
     }
 
-    async fn load_plugin_instance(&self, plugin_dir: &Path) -> anyhow::Result<Arc<Mutex<Plugin>>> {
+    async fn load_plugin_pool(
+        &self,
+        plugin_dir: &Path,
+    ) -> anyhow::Result<(Arc<InstancePool<PluginInstance>>, Arc<Vec<u8>>)> {
         let wasm_path = self.resolve_backend_wasm_path(plugin_dir).await?;
         let metadata = tokio::fs::metadata(&wasm_path)
             .await
@@ -247,47 +406,36 @@ impl FilesystemPluginStore {
 
         {
             let cache = self.plugin_cache.read().await;
-            if let Some(entry) = cache.get(&wasm_path) {
-                if entry.modified == modified {
-                    return Ok(entry.plugin.clone());
-                }
+            if let Some(entry) = cache.get(&wasm_path)
+                && entry.modified == modified
+            {
+                return Ok((entry.pool.clone(), entry.wasm.clone()));
             }
         }
 
         let wasm_bytes = tokio::fs::read(&wasm_path)
             .await
             .with_context(|| format!("read wasm module at {}", wasm_path.display()))?;
-        let wasm_key = wasm_path.clone();
-        let limits = self.limits;
-        let plugin = task::spawn_blocking(move || -> anyhow::Result<Plugin> {
-            let mut manifest = Manifest::new([Wasm::data(wasm_bytes)]);
-            if let Some(timeout) = limits.timeout {
-                manifest = manifest.with_timeout(timeout);
-            }
-            if let Some(memory_max) = limits.memory_max_pages {
-                manifest = manifest.with_memory_max(memory_max);
-            }
-            let builder = PluginBuilder::new(manifest).with_wasi(true);
-            let builder = if let Some(fuel_limit) = limits.fuel_limit {
-                builder.with_fuel_limit(fuel_limit)
-            } else {
-                builder
-            };
-            builder.build().context("create plugin")
-        })
-        .await
-        .context("join extism initialization task")??;
+        let wasm = Arc::new(wasm_bytes);
 
-        let plugin_arc = Arc::new(Mutex::new(plugin));
         let mut cache = self.plugin_cache.write().await;
+        if let Some(entry) = cache.get(&wasm_path)
+            && entry.modified == modified
+        {
+            return Ok((entry.pool.clone(), entry.wasm.clone()));
+        }
+        // Replacing the entry retires the previous pool; its instances are
+        // dropped once in-flight calls return them.
+        let pool = InstancePool::new(self.limits.pool_size, self.instance_budget.clone());
         cache.insert(
-            wasm_key,
+            wasm_path,
             CachedPlugin {
                 modified,
-                plugin: plugin_arc.clone(),
+                wasm: wasm.clone(),
+                pool: pool.clone(),
             },
         );
-        Ok(plugin_arc)
+        Ok((pool, wasm))
     }
 
     async fn invoke_plugin(
@@ -295,17 +443,50 @@ impl FilesystemPluginStore {
         plugin_dir: &Path,
         function: &str,
         input: Vec<u8>,
+        secrets: PluginSecrets,
     ) -> anyhow::Result<Vec<u8>> {
-        let plugin = self.load_plugin_instance(plugin_dir).await?;
+        let (pool, wasm) = self.load_plugin_pool(plugin_dir).await?;
+        let limits = self.limits;
+        let create = || async move {
+            task::spawn_blocking(move || Self::build_plugin(&wasm, limits))
+                .await
+                .context("join extism initialization task")?
+        };
+        // A plugin could keep what `secret_get` returned in its memory, so an instance that
+        // has seen secrets is never handed to another call.
+        let mut checkout = if secrets.is_empty() {
+            pool.checkout(create).await?
+        } else {
+            pool.checkout_fresh(create).await?
+        };
         let function = function.to_string();
         let output = task::spawn_blocking(move || -> anyhow::Result<Vec<u8>> {
-            let mut guard = plugin
-                .lock()
-                .map_err(|_| anyhow::anyhow!("extism plugin mutex poisoned"))?;
-            let bytes: &[u8] = guard
-                .call(&function, &input)
-                .map_err(|err| anyhow::anyhow!(format!("extism call error: {err}")))?;
-            Ok(bytes.to_vec())
+            let started = Instant::now();
+            let instance = checkout.get_mut();
+            let slot = instance.secrets.get()?;
+            *slot.lock().unwrap_or_else(|e| e.into_inner()) = secrets;
+            let result = {
+                let bytes: Result<&[u8], _> = instance.plugin.call(&function, &input);
+                bytes.map(|bytes| bytes.to_vec())
+            };
+            slot.lock().unwrap_or_else(|e| e.into_inner()).clear();
+            match result {
+                Ok(bytes) => Ok(bytes),
+                Err(err) => {
+                    // A failed call may leave the instance mid-execution; do not reuse it.
+                    checkout.discard();
+                    let timed_out = limits
+                        .timeout
+                        .is_some_and(|timeout| started.elapsed() >= timeout);
+                    let kind = classify_call_error(&err, timed_out);
+                    let message = format!("{err:#}");
+                    match kind {
+                        Some(kind) => Err(anyhow::Error::new(kind)
+                            .context(format!("extism call error: {message}"))),
+                        None => Err(anyhow::anyhow!(format!("extism call error: {message}"))),
+                    }
+                }
+            }
         })
         .await
         .context("join extism call task")??;
@@ -327,12 +508,23 @@ impl FilesystemPluginStore {
         Ok(without_root.to_string())
     }
 
+    fn has_doc_access(permissions: &[String]) -> bool {
+        permissions
+            .iter()
+            .any(|p| p == PERMISSION_DOC_READ || p == PERMISSION_DOC_WRITE)
+    }
+
+    // Only context covered by the manifest permissions is handed to the plugin;
+    // document identifiers are withheld unless the plugin may read or write docs.
+    // Host functions are not gated here: the manifest limits what the plugin is
+    // told (context, secrets) and which effects are applied, not which host calls it makes.
     fn build_invocation_context(
         user_id: Option<Uuid>,
         plugin: &str,
         invocation: &str,
         doc_id: Option<Uuid>,
         kind: InvocationKind,
+        permissions: &[String],
     ) -> JsonValue {
         let timestamp = Utc::now().to_rfc3339();
         let mut ctx = JsonMap::new();
@@ -351,10 +543,11 @@ impl FilesystemPluginStore {
             ctx.insert("user".to_string(), json!({ "id": uid }));
             ctx.insert("user_id".to_string(), json!(uid));
         }
-        if let Some(doc) = doc_id {
+        if let Some(doc) = doc_id.filter(|_| Self::has_doc_access(permissions)) {
             ctx.insert("doc".to_string(), json!({ "id": doc }));
             ctx.insert("doc_id".to_string(), json!(doc));
         }
+        ctx.insert("permissions".to_string(), json!(permissions));
         ctx.insert("kind".to_string(), json!(kind.as_str()));
         JsonValue::Object(ctx)
     }
@@ -364,19 +557,19 @@ impl FilesystemPluginStore {
             JsonValue::Object(map) => {
                 let direct_keys = ["docId", "doc_id", "doc", "document"];
                 for key in direct_keys {
-                    if let Some(candidate) = map.get(key) {
-                        if let Some(id) = Self::value_to_uuid(candidate) {
-                            return Some(id);
-                        }
+                    if let Some(candidate) = map.get(key)
+                        && let Some(id) = Self::value_to_uuid(candidate)
+                    {
+                        return Some(id);
                     }
                 }
 
                 let nested_keys = ["options", "payload", "context", "meta"]; // fallback search
                 for key in nested_keys {
-                    if let Some(nested) = map.get(key) {
-                        if let Some(id) = Self::extract_doc_id(nested) {
-                            return Some(id);
-                        }
+                    if let Some(nested) = map.get(key)
+                        && let Some(id) = Self::extract_doc_id(nested)
+                    {
+                        return Some(id);
                     }
                 }
                 None
@@ -394,6 +587,28 @@ impl FilesystemPluginStore {
         }
     }
 
+    /// Removes every document id `extract_doc_id` would find, so a plugin without doc
+    /// permissions cannot read one out of its input either.
+    fn scrub_doc_id(value: &mut JsonValue) {
+        match value {
+            JsonValue::Object(map) => {
+                for key in ["docId", "doc_id", "doc", "document"] {
+                    if map.get(key).and_then(Self::value_to_uuid).is_some() {
+                        map.remove(key);
+                    }
+                }
+                for key in ["options", "payload", "context", "meta"] {
+                    if let Some(nested) = map.get_mut(key) {
+                        Self::scrub_doc_id(nested);
+                    }
+                }
+            }
+            JsonValue::String(s) if Uuid::parse_str(s).is_ok() => *value = JsonValue::Null,
+            JsonValue::Array(items) => items.iter_mut().for_each(Self::scrub_doc_id),
+            _ => {}
+        }
+    }
+
     fn value_to_uuid(value: &JsonValue) -> Option<Uuid> {
         match value {
             JsonValue::String(s) => Uuid::parse_str(s).ok(),
@@ -449,10 +664,10 @@ impl FilesystemPluginStore {
                 continue;
             };
 
-            if let Some(mode) = file.unix_mode() {
-                if (mode & 0o170000) == 0o120000 {
-                    continue;
-                }
+            if let Some(mode) = file.unix_mode()
+                && (mode & 0o170000) == 0o120000
+            {
+                continue;
             }
 
             let outpath = dest_root.join(&rel_path);
@@ -512,34 +727,47 @@ impl FilesystemPluginStore {
             .and_then(|s| serde_json::from_str(&s).ok())
     }
 
-    pub fn remove_user_plugin_dir(&self, user_id: &Uuid, plugin_id: &str) -> anyhow::Result<()> {
+    /// Removes every installed version of a global plugin; returns whether it was installed.
+    pub fn remove_global_plugin_dir(&self, plugin_id: &str) -> anyhow::Result<bool> {
         Self::ensure_valid_plugin_id(plugin_id)?;
-        let root = self.user_root(user_id);
-        let path = root.join(plugin_id);
-        if !path.starts_with(&root) {
-            bail!("invalid plugin path");
+        let path = self.global_root().join(plugin_id);
+        if !path.exists() {
+            return Ok(false);
         }
-        if path.exists() {
-            std::fs::remove_dir_all(&path)?;
+        std::fs::remove_dir_all(&path)?;
+        Ok(true)
+    }
+
+    /// Toggles the disabled marker of an installed global plugin; returns whether it exists.
+    pub fn set_global_enabled(&self, plugin_id: &str, enabled: bool) -> anyhow::Result<bool> {
+        Self::ensure_valid_plugin_id(plugin_id)?;
+        let base = self.global_root().join(plugin_id);
+        if self.latest_version_dir(&base)?.is_none() {
+            return Ok(false);
         }
-        Ok(())
+        let marker = base.join(GLOBAL_DISABLED_FILE);
+        if enabled {
+            match std::fs::remove_file(&marker) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        } else {
+            std::fs::write(&marker, b"")?;
+        }
+        Ok(true)
     }
-}
 
-#[async_trait]
-impl PluginInstaller for FilesystemPluginStore {
-    async fn install_for_user(
+    /// Unpacks `archive` into `<owner_root>/<id>/<version>`, replacing an existing copy.
+    async fn install_archive(
         &self,
-        user_id: Uuid,
+        owner_root: PathBuf,
         archive: &[u8],
     ) -> Result<InstalledPlugin, PluginInstallError> {
         let archive_vec = archive.to_vec();
         let (_manifest, installed) = Self::read_manifest_from_archive(&archive_vec)?;
 
-        let dest_root = self
-            .user_root(&user_id)
-            .join(&installed.id)
-            .join(&installed.version);
+        let dest_root = owner_root.join(&installed.id).join(&installed.version);
 
         match tokio::fs::metadata(&dest_root).await {
             Ok(_) => {
@@ -569,6 +797,43 @@ impl PluginInstaller for FilesystemPluginStore {
 
         Ok(installed)
     }
+
+    pub fn remove_user_plugin_dir(&self, user_id: &Uuid, plugin_id: &str) -> anyhow::Result<()> {
+        Self::ensure_valid_plugin_id(plugin_id)?;
+        let root = self.user_root(user_id);
+        let path = root.join(plugin_id);
+        if !path.starts_with(&root) {
+            bail!("invalid plugin path");
+        }
+        if path.exists() {
+            std::fs::remove_dir_all(&path)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PluginInstaller for FilesystemPluginStore {
+    async fn install_for_user(
+        &self,
+        user_id: Uuid,
+        archive: &[u8],
+    ) -> Result<InstalledPlugin, PluginInstallError> {
+        self.install_archive(self.user_root(&user_id), archive)
+            .await
+    }
+
+    async fn install_global(&self, archive: &[u8]) -> Result<InstalledPlugin, PluginInstallError> {
+        self.install_archive(self.global_root(), archive).await
+    }
+
+    async fn uninstall_global(&self, plugin_id: &str) -> anyhow::Result<bool> {
+        self.remove_global_plugin_dir(plugin_id)
+    }
+
+    async fn set_global_enabled(&self, plugin_id: &str, enabled: bool) -> anyhow::Result<bool> {
+        FilesystemPluginStore::set_global_enabled(self, plugin_id, enabled)
+    }
 }
 
 #[async_trait]
@@ -605,6 +870,20 @@ impl PluginAssetStore for FilesystemPluginStore {
         FilesystemPluginStore::remove_user_plugin_dir(self, user_id, plugin_id)
     }
 
+    async fn latest_user_version(
+        &self,
+        user_id: &Uuid,
+        plugin_id: &str,
+    ) -> anyhow::Result<Option<String>> {
+        Self::ensure_valid_plugin_id(plugin_id)?;
+        let base = self.user_root(user_id).join(plugin_id);
+        Ok(self.latest_version_dir(&base)?.and_then(|dir| {
+            dir.file_name()
+                .and_then(|name| name.to_str())
+                .map(str::to_string)
+        }))
+    }
+
     async fn list_latest_global_manifests(
         &self,
     ) -> anyhow::Result<Vec<(String, String, serde_json::Value)>> {
@@ -624,6 +903,9 @@ impl PluginAssetStore for FilesystemPluginStore {
 
             let plugin_id = entry.file_name().to_string_lossy().to_string();
             let base = entry.path();
+            if Self::is_global_disabled(&base) {
+                continue;
+            }
             let best = match self.latest_version_dir(&base) {
                 Ok(Some(path)) => path,
                 Ok(None) => continue,
@@ -693,48 +975,6 @@ impl PluginAssetStore for FilesystemPluginStore {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
-
-    #[test]
-    fn prefers_semver_when_available() {
-        let temp = TempDir::new().unwrap();
-        let root = temp.path().join("plugins_test");
-        std::fs::create_dir_all(root.as_path()).unwrap();
-
-        let store =
-            FilesystemPluginStore::new(root.to_str().unwrap(), PluginExecutionLimits::default())
-                .unwrap();
-
-        let base = store.root().join("marp");
-        std::fs::create_dir_all(base.join("1.9.0")).unwrap();
-        std::fs::create_dir_all(base.join("1.10.0")).unwrap();
-
-        let latest = store.latest_version_dir(&base).unwrap().unwrap();
-        assert_eq!(latest.file_name().unwrap(), "1.10.0");
-    }
-
-    #[test]
-    fn falls_back_to_lexical_for_non_semver() {
-        let temp = TempDir::new().unwrap();
-        let root = temp.path().join("plugins_test_non_semver");
-        std::fs::create_dir_all(root.as_path()).unwrap();
-
-        let store =
-            FilesystemPluginStore::new(root.to_str().unwrap(), PluginExecutionLimits::default())
-                .unwrap();
-
-        let base = store.root().join("example");
-        std::fs::create_dir_all(base.join("beta")).unwrap();
-        std::fs::create_dir_all(base.join("alpha")).unwrap();
-
-        let latest = store.latest_version_dir(&base).unwrap().unwrap();
-        assert_eq!(latest.file_name().unwrap(), "beta");
-    }
-}
-
 #[async_trait]
 impl PluginRuntime for FilesystemPluginStore {
     async fn execute(
@@ -743,24 +983,42 @@ impl PluginRuntime for FilesystemPluginStore {
         plugin: &str,
         action: &str,
         payload: &serde_json::Value,
+        secrets: &PluginSecrets,
     ) -> anyhow::Result<Option<ExecResult>> {
-        let plugin_dir = self.locate_plugin_dir(user_id, plugin)?;
+        let plugin_dir = self.locate_plugin_dir(user_id, plugin).await?;
 
         let Some(plugin_dir) = plugin_dir else {
             return Ok(None);
         };
 
+        let permissions =
+            Self::extract_permissions(&Self::read_plugin_manifest(&plugin_dir).await?);
         let doc_hint = Self::extract_doc_id(payload);
-        let ctx =
-            Self::build_invocation_context(user_id, plugin, action, doc_hint, InvocationKind::Exec);
+        let ctx = Self::build_invocation_context(
+            user_id,
+            plugin,
+            action,
+            doc_hint,
+            InvocationKind::Exec,
+            &permissions,
+        );
+        let mut payload = payload.clone();
+        if !Self::has_doc_access(&permissions) {
+            Self::scrub_doc_id(&mut payload);
+        }
         let input = json!({
             "action": action,
             "payload": payload,
             "ctx": ctx
         });
 
+        let secrets = if permissions.iter().any(|p| p == PERMISSION_SECRETS) {
+            secrets.clone()
+        } else {
+            PluginSecrets::new()
+        };
         let out = self
-            .invoke_plugin(&plugin_dir, "exec", serde_json::to_vec(&input)?)
+            .invoke_plugin(&plugin_dir, "exec", serde_json::to_vec(&input)?, secrets)
             .await?;
 
         if out.is_empty() {
@@ -778,11 +1036,13 @@ impl PluginRuntime for FilesystemPluginStore {
         function: &str,
         request: &serde_json::Value,
     ) -> anyhow::Result<Option<serde_json::Value>> {
-        let plugin_dir = self.locate_plugin_dir(user_id, plugin)?;
+        let plugin_dir = self.locate_plugin_dir(user_id, plugin).await?;
         let Some(plugin_dir) = plugin_dir else {
             return Ok(None);
         };
 
+        let permissions =
+            Self::extract_permissions(&Self::read_plugin_manifest(&plugin_dir).await?);
         let doc_hint = Self::extract_doc_id(request);
 
         let ctx = Self::build_invocation_context(
@@ -791,9 +1051,14 @@ impl PluginRuntime for FilesystemPluginStore {
             function,
             doc_hint,
             InvocationKind::Render,
+            &permissions,
         );
 
-        let envelope = match request.clone() {
+        let mut request = request.clone();
+        if !Self::has_doc_access(&permissions) {
+            Self::scrub_doc_id(&mut request);
+        }
+        let envelope = match request {
             JsonValue::Object(mut map) => {
                 map.insert("context".to_string(), ctx);
                 JsonValue::Object(map)
@@ -805,7 +1070,12 @@ impl PluginRuntime for FilesystemPluginStore {
         };
 
         let out = self
-            .invoke_plugin(&plugin_dir, function, serde_json::to_vec(&envelope)?)
+            .invoke_plugin(
+                &plugin_dir,
+                function,
+                serde_json::to_vec(&envelope)?,
+                PluginSecrets::new(),
+            )
             .await?;
         if out.is_empty() {
             return Ok(None);
@@ -819,18 +1089,284 @@ impl PluginRuntime for FilesystemPluginStore {
         user_id: Option<Uuid>,
         plugin: &str,
     ) -> anyhow::Result<Option<Vec<String>>> {
-        let plugin_dir = self.locate_plugin_dir(user_id, plugin)?;
+        let plugin_dir = self.locate_plugin_dir(user_id, plugin).await?;
         let Some(plugin_dir) = plugin_dir else {
             return Ok(None);
         };
 
-        let manifest_path = plugin_dir.join("plugin.json");
-        let manifest_str = tokio::fs::read_to_string(&manifest_path)
+        let manifest = Self::read_plugin_manifest(&plugin_dir).await?;
+        Ok(Some(Self::extract_permissions(&manifest)))
+    }
+
+    async fn schedules(
+        &self,
+        user_id: Option<Uuid>,
+        plugin: &str,
+    ) -> anyhow::Result<Option<Vec<PluginScheduleSpec>>> {
+        let Some(plugin_dir) = self.locate_plugin_dir(user_id, plugin).await? else {
+            return Ok(None);
+        };
+        let manifest = Self::read_plugin_manifest(&plugin_dir).await?;
+        Ok(Some(Self::extract_schedules(&manifest)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::plugins::MemoryPluginInstallations;
+    use tempfile::TempDir;
+
+    #[test]
+    fn prefers_semver_when_available() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("plugins_test");
+        std::fs::create_dir_all(root.as_path()).unwrap();
+
+        let store =
+            FilesystemPluginStore::new(root.to_str().unwrap(), PluginExecutionLimits::default())
+                .unwrap();
+
+        let base = store.root().join("marp");
+        std::fs::create_dir_all(base.join("1.9.0")).unwrap();
+        std::fs::create_dir_all(base.join("1.10.0")).unwrap();
+
+        let latest = store.latest_version_dir(&base).unwrap().unwrap();
+        assert_eq!(latest.file_name().unwrap(), "1.10.0");
+    }
+
+    #[test]
+    fn falls_back_to_lexical_for_non_semver() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("plugins_test_non_semver");
+        std::fs::create_dir_all(root.as_path()).unwrap();
+
+        let store =
+            FilesystemPluginStore::new(root.to_str().unwrap(), PluginExecutionLimits::default())
+                .unwrap();
+
+        let base = store.root().join("example");
+        std::fs::create_dir_all(base.join("beta")).unwrap();
+        std::fs::create_dir_all(base.join("alpha")).unwrap();
+
+        let latest = store.latest_version_dir(&base).unwrap().unwrap();
+        assert_eq!(latest.file_name().unwrap(), "beta");
+    }
+
+    #[test]
+    fn scrubs_every_doc_id_the_context_would_use() {
+        let doc = Uuid::new_v4();
+        let mut payload = json!({
+            "docId": doc,
+            "title": "Notes",
+            "options": { "document": { "id": doc }, "theme": "dark" },
+            "meta": [doc.to_string(), "keep"],
+        });
+
+        FilesystemPluginStore::scrub_doc_id(&mut payload);
+
+        assert_eq!(FilesystemPluginStore::extract_doc_id(&payload), None);
+        assert_eq!(
+            payload,
+            json!({
+                "title": "Notes",
+                "options": { "theme": "dark" },
+                "meta": [null, "keep"],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn pinned_version_stays_active_when_newer_is_installed() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("plugins_test_pinned");
+        std::fs::create_dir_all(root.as_path()).unwrap();
+
+        let installations = Arc::new(MemoryPluginInstallations::default());
+        let store =
+            FilesystemPluginStore::new(root.to_str().unwrap(), PluginExecutionLimits::default())
+                .unwrap()
+                .with_installations(installations.clone());
+
+        let user_id = Uuid::new_v4();
+        let base = store.user_root(&user_id).join("marp");
+        for version in ["1.0.0", "2.0.0"] {
+            std::fs::create_dir_all(base.join(version)).unwrap();
+            std::fs::write(base.join(version).join("plugin.json"), "{}").unwrap();
+        }
+        async fn located(store: &FilesystemPluginStore, user_id: Uuid) -> String {
+            let dir = store.locate_plugin_dir(Some(user_id), "marp").await;
+            dir.unwrap()
+                .unwrap()
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        }
+
+        // Without a row the newest version on disk serves.
+        assert_eq!(located(&store, user_id).await, "2.0.0");
+
+        installations
+            .upsert(user_id, "marp", "2.0.0", "user", None, "enabled")
             .await
-            .with_context(|| format!("read plugin manifest at {}", manifest_path.display()))?;
-        let manifest: JsonValue = serde_json::from_str(&manifest_str)
-            .with_context(|| format!("parse plugin manifest at {}", manifest_path.display()))?;
+            .unwrap();
+        assert!(
+            installations
+                .set_version(user_id, "marp", "1.0.0", true)
+                .await
+                .unwrap()
+        );
+        assert_eq!(located(&store, user_id).await, "1.0.0");
+
+        // A newer version unpacked on disk does not move a pinned plugin.
+        std::fs::create_dir_all(base.join("3.0.0")).unwrap();
+        std::fs::write(base.join("3.0.0").join("plugin.json"), "{}").unwrap();
+        assert_eq!(located(&store, user_id).await, "1.0.0");
+        assert_eq!(
+            store
+                .latest_user_version(&user_id, "marp")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("3.0.0")
+        );
 
-        Ok(Some(Self::extract_permissions(&manifest)))
+        installations
+            .set_version(user_id, "marp", "3.0.0", false)
+            .await
+            .unwrap();
+        assert_eq!(located(&store, user_id).await, "3.0.0");
+    }
+
+    fn plugin_archive(id: &str, version: &str) -> Vec<u8> {
+        use std::io::Write;
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        zip.start_file("plugin.json", zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(
+            json!({ "id": id, "version": version })
+                .to_string()
+                .as_bytes(),
+        )
+        .unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[tokio::test]
+    async fn global_installs_are_visible_to_every_user_until_disabled() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().join("plugins_test_global");
+        std::fs::create_dir_all(root.as_path()).unwrap();
+        let store =
+            FilesystemPluginStore::new(root.to_str().unwrap(), PluginExecutionLimits::default())
+                .unwrap();
+
+        let installed = store
+            .install_global(&plugin_archive("mermaid", "1.0.0"))
+            .await
+            .unwrap();
+        assert_eq!(installed.version, "1.0.0");
+
+        let listed = store.list_latest_global_manifests().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].0, "mermaid");
+        for user in [Uuid::new_v4(), Uuid::new_v4()] {
+            assert!(
+                store
+                    .locate_plugin_dir(Some(user), "mermaid")
+                    .await
+                    .unwrap()
+                    .is_some()
+            );
+        }
+
+        assert!(FilesystemPluginStore::set_global_enabled(&store, "mermaid", false).unwrap());
+        assert!(
+            store
+                .list_latest_global_manifests()
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            store
+                .locate_plugin_dir(None, "mermaid")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(FilesystemPluginStore::set_global_enabled(&store, "mermaid", true).unwrap());
+        assert_eq!(store.list_latest_global_manifests().await.unwrap().len(), 1);
+
+        assert!(store.uninstall_global("mermaid").await.unwrap());
+        assert!(!store.uninstall_global("mermaid").await.unwrap());
+    }
+
+    /// Store holding a user plugin "probe" whose backend is `wat`.
+    fn probe_plugin(
+        temp: &TempDir,
+        wat: &str,
+        limits: PluginExecutionLimits,
+    ) -> (FilesystemPluginStore, PathBuf) {
+        let root = temp.path().join("plugins_test_calls");
+        std::fs::create_dir_all(root.as_path()).unwrap();
+        let store = FilesystemPluginStore::new(root.to_str().unwrap(), limits).unwrap();
+        let dir = store.user_root(&Uuid::new_v4()).join("probe").join("1.0.0");
+        std::fs::create_dir_all(dir.join("backend")).unwrap();
+        std::fs::write(
+            dir.join("plugin.json"),
+            r#"{"id":"probe","version":"1.0.0"}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("backend").join("plugin.wasm"), wat).unwrap();
+        (store, dir)
+    }
+
+    #[tokio::test]
+    async fn plugin_running_past_its_time_limit_times_out() {
+        let temp = TempDir::new().unwrap();
+        let limits = PluginExecutionLimits::new(Some(Duration::from_millis(200)), None, None);
+        let (store, dir) = probe_plugin(
+            &temp,
+            r#"(module (func (export "exec") (result i32) (loop $spin (br $spin)) i32.const 0))"#,
+            limits,
+        );
+
+        let err = store
+            .invoke_plugin(&dir, "exec", Vec::new(), PluginSecrets::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PluginInvocationError>(),
+            Some(PluginInvocationError::Timeout)
+        ));
+    }
+
+    #[tokio::test]
+    async fn trapping_plugin_reports_the_trap() {
+        let temp = TempDir::new().unwrap();
+        let (store, dir) = probe_plugin(
+            &temp,
+            r#"(module (func (export "exec") (result i32) unreachable))"#,
+            PluginExecutionLimits::default(),
+        );
+
+        let err = store
+            .invoke_plugin(&dir, "exec", Vec::new(), PluginSecrets::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<PluginInvocationError>(),
+            Some(PluginInvocationError::Trap(message)) if message.contains("unreachable")
+        ));
+
+        // Failures that are neither are left untyped.
+        let err = store
+            .invoke_plugin(&dir, "missing", Vec::new(), PluginSecrets::new())
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<PluginInvocationError>().is_none());
     }
 }
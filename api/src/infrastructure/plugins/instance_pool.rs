@@ -0,0 +1,276 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+// How long a checkout waits for the global budget before retrying eviction.
+const BUDGET_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+trait EvictIdle: Send + Sync {
+    fn evict_idle(&self) -> bool;
+}
+
+/// Upper bound on live instances shared by every pool of a store.
+pub struct InstanceBudget {
+    permits: Arc<Semaphore>,
+    waiting: AtomicUsize,
+    pools: Mutex<Vec<Weak<dyn EvictIdle>>>,
+}
+
+impl InstanceBudget {
+    pub fn new(max_instances: usize) -> Arc<Self> {
+        Arc::new(Self {
+            permits: Arc::new(Semaphore::new(max_instances.max(1))),
+            waiting: AtomicUsize::new(0),
+            pools: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn register(&self, pool: Weak<dyn EvictIdle>) {
+        let mut pools = self.pools.lock().unwrap_or_else(|e| e.into_inner());
+        pools.retain(|p| p.strong_count() > 0);
+        pools.push(pool);
+    }
+
+    fn evict_any(&self) -> bool {
+        let pools: Vec<Arc<dyn EvictIdle>> = {
+            let guard = self.pools.lock().unwrap_or_else(|e| e.into_inner());
+            guard.iter().filter_map(|p| p.upgrade()).collect()
+        };
+        pools.iter().any(|p| p.evict_idle())
+    }
+
+    async fn acquire(&self) -> OwnedSemaphorePermit {
+        loop {
+            if let Ok(permit) = self.permits.clone().try_acquire_owned() {
+                return permit;
+            }
+            if self.evict_any() {
+                continue;
+            }
+            self.waiting.fetch_add(1, Ordering::SeqCst);
+            let waited =
+                tokio::time::timeout(BUDGET_RETRY_INTERVAL, self.permits.clone().acquire_owned())
+                    .await;
+            self.waiting.fetch_sub(1, Ordering::SeqCst);
+            if let Ok(Ok(permit)) = waited {
+                return permit;
+            }
+        }
+    }
+
+    fn has_waiters(&self) -> bool {
+        self.waiting.load(Ordering::SeqCst) > 0
+    }
+}
+
+struct Instance<T> {
+    value: T,
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Fixed-size set of interchangeable instances checked out one call at a time.
+pub struct InstancePool<T: Send + 'static> {
+    idle: Mutex<Vec<Instance<T>>>,
+    slots: Arc<Semaphore>,
+    budget: Arc<InstanceBudget>,
+}
+
+impl<T: Send + 'static> InstancePool<T> {
+    pub fn new(size: usize, budget: Arc<InstanceBudget>) -> Arc<Self> {
+        let pool = Arc::new(Self {
+            idle: Mutex::new(Vec::new()),
+            slots: Arc::new(Semaphore::new(size.max(1))),
+            budget: budget.clone(),
+        });
+        let weak: Weak<dyn EvictIdle> = Arc::downgrade(&(pool.clone() as Arc<dyn EvictIdle>));
+        budget.register(weak);
+        pool
+    }
+
+    pub fn idle_len(&self) -> usize {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Waits for a free slot and hands out an idle instance, creating one with
+    /// `create` when none is idle and the shared budget allows it.
+    pub async fn checkout<F, Fut>(self: &Arc<Self>, create: F) -> anyhow::Result<Checkout<T>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        self.checkout_inner(create, true).await
+    }
+
+    /// Like [`checkout`](Self::checkout), but always creates a new instance and drops it
+    /// afterwards, so no state is shared with any other checkout.
+    pub async fn checkout_fresh<F, Fut>(self: &Arc<Self>, create: F) -> anyhow::Result<Checkout<T>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        self.checkout_inner(create, false).await
+    }
+
+    async fn checkout_inner<F, Fut>(
+        self: &Arc<Self>,
+        create: F,
+        shared: bool,
+    ) -> anyhow::Result<Checkout<T>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let slot = self
+            .slots
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| anyhow::anyhow!("plugin pool closed"))?;
+
+        let reused = if shared {
+            self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop()
+        } else {
+            None
+        };
+        let instance = match reused {
+            Some(instance) => instance,
+            None => {
+                let permit = self.budget.acquire().await;
+                let value = create().await?;
+                Instance {
+                    value,
+                    _permit: permit,
+                }
+            }
+        };
+
+        Ok(Checkout {
+            pool: self.clone(),
+            instance: Some(instance),
+            discard: !shared,
+            _slot: slot,
+        })
+    }
+
+    fn check_in(&self, instance: Instance<T>) {
+        // Free the budget instead of parking the instance when another pool is
+        // waiting to create one.
+        if self.budget.has_waiters() {
+            return;
+        }
+        self.idle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(instance);
+    }
+}
+
+impl<T: Send + 'static> EvictIdle for InstancePool<T> {
+    fn evict_idle(&self) -> bool {
+        self.idle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop()
+            .is_some()
+    }
+}
+
+/// Exclusive use of one pooled instance; returned to the pool on drop.
+pub struct Checkout<T: Send + 'static> {
+    pool: Arc<InstancePool<T>>,
+    instance: Option<Instance<T>>,
+    discard: bool,
+    _slot: OwnedSemaphorePermit,
+}
+
+impl<T: Send + 'static> Checkout<T> {
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self
+            .instance
+            .as_mut()
+            .expect("checked out instance present")
+            .value
+    }
+
+    /// Drops the instance instead of returning it, e.g. after a trap left it unusable.
+    pub fn discard(&mut self) {
+        self.discard = true;
+    }
+}
+
+impl<T: Send + 'static> Drop for Checkout<T> {
+    fn drop(&mut self) {
+        if let Some(instance) = self.instance.take()
+            && !self.discard
+        {
+            self.pool.check_in(instance);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn allows_parallel_checkouts_up_to_pool_size() {
+        let budget = InstanceBudget::new(8);
+        let pool = InstancePool::<usize>::new(2, budget);
+        let created = Arc::new(AtomicUsize::new(0));
+
+        let make =
+            |created: Arc<AtomicUsize>| async move { Ok(created.fetch_add(1, Ordering::SeqCst)) };
+        let first = pool.checkout(|| make(created.clone())).await.unwrap();
+        let second = pool.checkout(|| make(created.clone())).await.unwrap();
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+
+        let third = tokio::time::timeout(
+            Duration::from_millis(100),
+            pool.checkout(|| make(created.clone())),
+        )
+        .await;
+        assert!(third.is_err(), "third checkout should wait for a free slot");
+
+        drop(first);
+        let third = pool.checkout(|| make(created.clone())).await.unwrap();
+        assert_eq!(created.load(Ordering::SeqCst), 2, "idle instance is reused");
+        drop(second);
+        drop(third);
+        assert_eq!(pool.idle_len(), 2);
+    }
+
+    #[tokio::test]
+    async fn evicts_idle_instances_when_budget_is_exhausted() {
+        let budget = InstanceBudget::new(1);
+        let a = InstancePool::<u8>::new(2, budget.clone());
+        let b = InstancePool::<u8>::new(2, budget);
+
+        drop(a.checkout(|| async { Ok(1) }).await.unwrap());
+        assert_eq!(a.idle_len(), 1);
+
+        let checkout = b.checkout(|| async { Ok(2) }).await.unwrap();
+        assert_eq!(a.idle_len(), 0);
+        drop(checkout);
+        assert_eq!(b.idle_len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fresh_checkouts_never_share_instances() {
+        let budget = InstanceBudget::new(8);
+        let pool = InstancePool::<usize>::new(2, budget);
+        let created = Arc::new(AtomicUsize::new(0));
+
+        let make =
+            |created: Arc<AtomicUsize>| async move { Ok(created.fetch_add(1, Ordering::SeqCst)) };
+        drop(pool.checkout(|| make(created.clone())).await.unwrap());
+        assert_eq!(pool.idle_len(), 1);
+
+        let mut fresh = pool.checkout_fresh(|| make(created.clone())).await.unwrap();
+        assert_eq!(*fresh.get_mut(), 1, "idle instance is not reused");
+        drop(fresh);
+        assert_eq!(pool.idle_len(), 1, "fresh instance is not kept");
+    }
+}
@@ -1,4 +1,7 @@
 pub mod event_bus_pg;
+pub mod event_replay;
+pub mod exec_log;
 pub mod filesystem_store;
+pub mod instance_pool;
 pub mod package_fetcher_reqwest;
 pub mod s3_store;
@@ -6,6 +6,12 @@ pub struct ReqwestPluginPackageFetcher {
     client: reqwest::Client,
 }
 
+impl Default for ReqwestPluginPackageFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ReqwestPluginPackageFetcher {
     pub fn new() -> Self {
         Self {
@@ -23,14 +23,15 @@ use walkdir::WalkDir;
 use crate::application::dto::plugins::ExecResult;
 use crate::application::ports::plugin_asset_store::PluginAssetStore;
 use crate::application::ports::plugin_event_publisher::PluginScopedEvent;
+use crate::application::ports::plugin_installation_repository::PluginInstallationRepository;
 use crate::application::ports::plugin_installer::{
     InstalledPlugin, PluginInstallError, PluginInstaller,
 };
-use crate::application::ports::plugin_runtime::PluginRuntime;
+use crate::application::ports::plugin_runtime::{PluginRuntime, PluginScheduleSpec, PluginSecrets};
 use crate::bootstrap::config::Config;
 use crate::infrastructure::plugins::event_bus_pg::PgPluginEventBus;
 use crate::infrastructure::plugins::filesystem_store::{
-    FilesystemPluginStore, PluginExecutionLimits,
+    FilesystemPluginStore, GLOBAL_DISABLED_FILE, PluginExecutionLimits,
 };
 
 const PLUGINS_PREFIX: &str = "plugins";
@@ -87,7 +88,13 @@ fn is_manifest_affecting_event(event: &PluginScopedEvent) -> bool {
     if let Some(kind) = event.payload.get("event").and_then(|value| value.as_str()) {
         matches!(
             kind,
-            "installed" | "uninstalled" | "updated" | "publish" | "unpublish"
+            "installed"
+                | "uninstalled"
+                | "updated"
+                | "enabled"
+                | "disabled"
+                | "publish"
+                | "unpublish"
         )
     } else {
         false
@@ -305,8 +312,11 @@ impl S3BackedPluginStore {
         configured_dir: &str,
         cfg: &Config,
         limits: PluginExecutionLimits,
+        installations: Arc<dyn PluginInstallationRepository>,
     ) -> anyhow::Result<Self> {
-        let local = Arc::new(FilesystemPluginStore::new(configured_dir, limits)?);
+        let local = Arc::new(
+            FilesystemPluginStore::new(configured_dir, limits)?.with_installations(installations),
+        );
         let bucket = cfg
             .s3_bucket
             .clone()
@@ -432,17 +442,18 @@ impl S3BackedPluginStore {
             }
         }
 
-        if base_dir.exists() && !force_refresh {
-            if self.local.latest_version_dir(&base_dir)?.is_some() {
-                return Ok(());
-            }
+        if base_dir.exists()
+            && !force_refresh
+            && self.local.latest_version_dir(&base_dir)?.is_some()
+        {
+            return Ok(());
         }
 
         let result = download_prefix(&self.client, &self.bucket, &prefix, self.local.root()).await;
-        if result.is_err() {
-            if let Some(key) = retry_key {
-                self.requeue_user_plugin_dirty(key);
-            }
+        if result.is_err()
+            && let Some(key) = retry_key
+        {
+            self.requeue_user_plugin_dirty(key);
         }
         result
     }
@@ -467,6 +478,14 @@ impl S3BackedPluginStore {
                     self.schedule_refresh_user_plugin(key);
                 }
             }
+        } else if is_manifest_affecting_event(event) {
+            // Downloads only add files, so drop the local copy of a changed global plugin and
+            // let the next manifest refresh pull whatever the bucket now holds.
+            if let Some(plugin_id) = event.payload.get("id").and_then(|v| v.as_str())
+                && let Err(err) = self.local.remove_global_plugin_dir(plugin_id)
+            {
+                tracing::warn!(error = ?err, plugin = plugin_id, "drop_global_plugin_failed");
+            }
         }
     }
 
@@ -525,6 +544,15 @@ impl PluginAssetStore for S3BackedPluginStore {
         Ok(())
     }
 
+    async fn latest_user_version(
+        &self,
+        user_id: &Uuid,
+        plugin_id: &str,
+    ) -> anyhow::Result<Option<String>> {
+        self.ensure_local(Some(*user_id), plugin_id).await?;
+        self.local.latest_user_version(user_id, plugin_id).await
+    }
+
     async fn list_latest_global_manifests(
         &self,
     ) -> anyhow::Result<Vec<(String, String, serde_json::Value)>> {
@@ -576,6 +604,58 @@ impl PluginInstaller for S3BackedPluginStore {
         self.global_cache.invalidate();
         Ok(installed)
     }
+
+    async fn install_global(&self, archive: &[u8]) -> Result<InstalledPlugin, PluginInstallError> {
+        let installed = self.local.install_global(archive).await?;
+        let install_dir = self
+            .local
+            .global_root()
+            .join(&installed.id)
+            .join(&installed.version);
+        upload_directory(&self.client, &self.bucket, self.local.root(), &install_dir)
+            .await
+            .map_err(PluginInstallError::Storage)?;
+        self.global_cache.invalidate();
+        Ok(installed)
+    }
+
+    async fn uninstall_global(&self, plugin_id: &str) -> anyhow::Result<bool> {
+        FilesystemPluginStore::ensure_valid_plugin_id(plugin_id)?;
+        let prefix = format!("global/{}/", plugin_id);
+        let existed = !list_keys(&self.client, &self.bucket, &key_for(&prefix))
+            .await?
+            .is_empty();
+        delete_prefix(&self.client, &self.bucket, &prefix).await?;
+        let existed = self.local.remove_global_plugin_dir(plugin_id)? || existed;
+        self.global_cache.invalidate();
+        Ok(existed)
+    }
+
+    async fn set_global_enabled(&self, plugin_id: &str, enabled: bool) -> anyhow::Result<bool> {
+        self.ensure_local(None, plugin_id).await?;
+        if !self.local.set_global_enabled(plugin_id, enabled)? {
+            return Ok(false);
+        }
+        let key = key_for(&format!("global/{}/{}", plugin_id, GLOBAL_DISABLED_FILE));
+        if enabled {
+            self.client
+                .delete_object()
+                .bucket(&self.bucket)
+                .key(key)
+                .send()
+                .await?;
+        } else {
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(key)
+                .body(ByteStream::from(Vec::new()))
+                .send()
+                .await?;
+        }
+        self.global_cache.invalidate();
+        Ok(true)
+    }
 }
 
 #[async_trait]
@@ -586,12 +666,15 @@ impl PluginRuntime for S3BackedPluginStore {
         plugin: &str,
         action: &str,
         payload: &serde_json::Value,
+        secrets: &PluginSecrets,
     ) -> anyhow::Result<Option<ExecResult>> {
         if !FilesystemPluginStore::is_valid_plugin_id(plugin) {
             return Ok(None);
         }
         self.ensure_local(user_id, plugin).await?;
-        self.local.execute(user_id, plugin, action, payload).await
+        self.local
+            .execute(user_id, plugin, action, payload, secrets)
+            .await
     }
 
     async fn render_placeholder(
@@ -621,4 +704,16 @@ impl PluginRuntime for S3BackedPluginStore {
         self.ensure_local(user_id, plugin).await?;
         self.local.permissions(user_id, plugin).await
     }
+
+    async fn schedules(
+        &self,
+        user_id: Option<Uuid>,
+        plugin: &str,
+    ) -> anyhow::Result<Option<Vec<PluginScheduleSpec>>> {
+        if !FilesystemPluginStore::is_valid_plugin_id(plugin) {
+            return Ok(None);
+        }
+        self.ensure_local(user_id, plugin).await?;
+        self.local.schedules(user_id, plugin).await
+    }
 }
@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use sqlx::Row;
 use uuid::Uuid;
 
+use crate::application::linkgraph;
 use crate::application::ports::realtime_persistence_port::DocPersistencePort;
 use crate::infrastructure::db::PgPool;
 
@@ -99,4 +100,41 @@ impl DocPersistencePort for SqlxDocPersistenceAdapter {
             .await?;
         Ok(())
     }
+
+    async fn touch_document(&self, doc_id: &Uuid) -> anyhow::Result<()> {
+        sqlx::query("UPDATE documents SET updated_at = now() WHERE id = $1")
+            .bind(doc_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn record_content_hash(&self, doc_id: &Uuid, hash: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            "UPDATE documents SET content_hash = $2 WHERE id = $1 AND content_hash IS DISTINCT FROM $2",
+        )
+        .bind(doc_id)
+        .bind(hash)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn clear_content_hash(&self, doc_id: &Uuid) -> anyhow::Result<()> {
+        sqlx::query("UPDATE documents SET content_hash = NULL WHERE id = $1")
+            .bind(doc_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_title(&self, doc_id: &Uuid, title: &str) -> anyhow::Result<()> {
+        sqlx::query("UPDATE documents SET title = $2, title_key = $3 WHERE id = $1")
+            .bind(doc_id)
+            .bind(title)
+            .bind(linkgraph::title_key(title))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
 }
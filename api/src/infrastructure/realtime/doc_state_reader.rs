@@ -1,5 +1,6 @@
 use anyhow::Context;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use futures_util::TryStreamExt;
 use sqlx::Row;
 use uuid::Uuid;
@@ -44,6 +45,16 @@ impl DocStateReader for SqlxDocStateReader {
         }
     }
 
+    async fn latest_snapshot_at(&self, doc_id: &Uuid) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let row = sqlx::query(
+            "SELECT MAX(created_at) AS created_at FROM document_snapshots WHERE document_id = $1",
+        )
+        .bind(doc_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.try_get("created_at")?)
+    }
+
     async fn updates_since(&self, doc_id: &Uuid, from_seq: i64) -> anyhow::Result<Vec<DocUpdate>> {
         let mut rows = sqlx::query(
             "SELECT seq, update FROM document_updates WHERE document_id = $1 AND seq > $2 ORDER BY seq ASC",
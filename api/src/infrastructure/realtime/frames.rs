@@ -0,0 +1,407 @@
+//! Realtime frame handling shared by the local hub and the Redis engine: inbound checks and
+//! the chunked initial sync.
+
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+use std::hash::BuildHasher;
+use std::sync::{Arc, Mutex};
+
+use futures_util::StreamExt;
+use yrs::encoding::read::Cursor;
+use yrs::encoding::write::Write as YWrite;
+use yrs::sync::protocol::{MSG_SYNC, MSG_SYNC_UPDATE};
+use yrs::sync::{Message, MessageReader, SyncMessage};
+use yrs::updates::decoder::DecoderV1;
+use yrs::updates::encoder::{Encoder, EncoderV1};
+
+use crate::application::ports::realtime_types::{DynRealtimeStream, RealtimeProtocol};
+use crate::bootstrap::config::Config;
+
+fn analyse_frame(frame: &[u8]) -> anyhow::Result<FrameSummary> {
+    let mut decoder = DecoderV1::new(Cursor::new(frame));
+    let reader = MessageReader::new(&mut decoder);
+    let mut summary = FrameSummary::default();
+    for message in reader {
+        match message? {
+            Message::Sync(SyncMessage::Update(_)) | Message::Sync(SyncMessage::SyncStep2(_)) => {
+                summary.has_update = true;
+            }
+            Message::Awareness(_) => {
+                summary.has_awareness = true;
+            }
+            _ => {}
+        }
+    }
+    Ok(summary)
+}
+
+#[derive(Default)]
+pub(crate) struct FrameSummary {
+    pub has_update: bool,
+    pub has_awareness: bool,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum FrameRejection {
+    #[error("update frame of {size} bytes exceeds limit of {limit}")]
+    UpdateTooLarge { size: usize, limit: usize },
+    #[error("awareness frame of {size} bytes exceeds limit of {limit}")]
+    AwarenessTooLarge { size: usize, limit: usize },
+    #[error("frame could not be decoded: {0}")]
+    Decode(anyhow::Error),
+}
+
+/// Inbound frame size caps, checked before anything is applied or published.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FrameLimits {
+    pub(crate) max_update_bytes: usize,
+    pub(crate) max_awareness_bytes: usize,
+    pub(crate) close_on_violation: bool,
+}
+
+impl FrameLimits {
+    pub(crate) fn from_config(cfg: &Config) -> Self {
+        Self {
+            max_update_bytes: cfg.realtime_max_update_frame_bytes,
+            max_awareness_bytes: cfg.realtime_max_awareness_frame_bytes,
+            close_on_violation: cfg.realtime_close_on_oversized_frame,
+        }
+    }
+
+    pub(crate) fn admit(&self, frame: &[u8]) -> Result<FrameSummary, FrameRejection> {
+        let size = frame.len();
+        // Nothing larger than both caps can pass, so skip decoding it at all.
+        let ceiling = self.max_update_bytes.max(self.max_awareness_bytes);
+        if size > ceiling {
+            return Err(if self.max_update_bytes >= self.max_awareness_bytes {
+                FrameRejection::UpdateTooLarge {
+                    size,
+                    limit: self.max_update_bytes,
+                }
+            } else {
+                FrameRejection::AwarenessTooLarge {
+                    size,
+                    limit: self.max_awareness_bytes,
+                }
+            });
+        }
+        let summary = analyse_frame(frame).map_err(FrameRejection::Decode)?;
+        if summary.has_update && size > self.max_update_bytes {
+            return Err(FrameRejection::UpdateTooLarge {
+                size,
+                limit: self.max_update_bytes,
+            });
+        }
+        if summary.has_awareness && size > self.max_awareness_bytes {
+            return Err(FrameRejection::AwarenessTooLarge {
+                size,
+                limit: self.max_awareness_bytes,
+            });
+        }
+        Ok(summary)
+    }
+}
+
+/// Documents tracked at once; past this the table starts over, which at worst lets a few
+/// repeats through.
+const DEDUP_MAX_DOCUMENTS: usize = 4096;
+
+/// Remembers hashes of the last few update frames published per document, so a frame that is
+/// replayed (reconnect storms, clients resending unacknowledged updates) is not appended to the
+/// stream again. Identical bytes carry identical yjs operations, so dropping a repeat never
+/// loses an edit.
+pub(crate) struct UpdateDedup {
+    window: usize,
+    hasher: RandomState,
+    recent: Mutex<HashMap<String, VecDeque<(u64, usize)>>>,
+}
+
+impl UpdateDedup {
+    pub(crate) fn new(window: usize) -> Self {
+        Self {
+            window,
+            hasher: RandomState::new(),
+            recent: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `frame` should be published for `doc_id`; records it if so.
+    pub(crate) fn admit(&self, doc_id: &str, frame: &[u8]) -> bool {
+        if self.window == 0 {
+            return true;
+        }
+        let key = (self.hasher.hash_one(frame), frame.len());
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() >= DEDUP_MAX_DOCUMENTS && !recent.contains_key(doc_id) {
+            recent.clear();
+        }
+        let seen = recent.entry(doc_id.to_string()).or_default();
+        if seen.contains(&key) {
+            return false;
+        }
+        if seen.len() >= self.window {
+            seen.pop_front();
+        }
+        seen.push_back(key);
+        true
+    }
+
+    /// Drops `frame` from the window, e.g. after publishing it failed.
+    pub(crate) fn forget(&self, doc_id: &str, frame: &[u8]) {
+        let key = (self.hasher.hash_one(frame), frame.len());
+        if let Some(seen) = self.recent.lock().unwrap().get_mut(doc_id) {
+            seen.retain(|k| *k != key);
+        }
+    }
+}
+
+/// Repeat suppression for one connection's inbound updates.
+pub(crate) struct InboundDedup {
+    pub dedup: Arc<UpdateDedup>,
+    /// Whether the connection's updates are applied right now. Frames that are ignored (read-only
+    /// or locked) are not remembered, so resending them once editing is allowed still works.
+    pub applies_updates: Box<dyn Fn() -> bool + Send + Sync>,
+}
+
+/// Drops oversized frames from a connection's inbound stream before the hub applies them, and
+/// ends the stream (closing the connection) on the first one if `limits` say so. With `dedup`,
+/// update-only frames repeating a recent one are dropped as well. Frames that do not decode are
+/// passed on for the sync protocol to reject.
+pub(crate) fn guard_inbound(
+    stream: DynRealtimeStream,
+    limits: FrameLimits,
+    dedup: Option<InboundDedup>,
+    doc_id: String,
+) -> DynRealtimeStream {
+    let dedup = dedup.map(Arc::new);
+    Box::pin(futures_util::stream::unfold(stream, move |mut stream| {
+        let doc_id = doc_id.clone();
+        let dedup = dedup.clone();
+        async move {
+            loop {
+                let item = stream.next().await?;
+                let Ok(bytes) = &item else {
+                    return Some((item, stream));
+                };
+                match limits.admit(bytes) {
+                    Ok(summary) => {
+                        let repeated = summary.has_update
+                            && !summary.has_awareness
+                            && dedup.as_ref().is_some_and(|d| {
+                                (d.applies_updates)() && !d.dedup.admit(&doc_id, bytes)
+                            });
+                        if repeated {
+                            tracing::debug!(document_id = %doc_id, "realtime_duplicate_update_dropped");
+                            continue;
+                        }
+                        return Some((item, stream));
+                    }
+                    Err(FrameRejection::Decode(_)) => return Some((item, stream)),
+                    Err(rejection) => {
+                        tracing::warn!(
+                            document_id = %doc_id,
+                            reason = %rejection,
+                            close = limits.close_on_violation,
+                            "realtime_frame_rejected"
+                        );
+                        if limits.close_on_violation {
+                            return None;
+                        }
+                    }
+                }
+            }
+        }
+    }))
+}
+
+/// Message type of one piece of a chunked initial state (protocol v2). Layout: type, chunk
+/// index, chunk count, then a length-prefixed slice of the v1 update. The client concatenates
+/// the slices in order and applies the result as a single update.
+pub(crate) const MSG_SYNC_CHUNK: u32 = 100;
+
+/// Frames carrying a document's full state `update`: one regular sync update frame, or for v2
+/// clients and states over `chunk_bytes`, a run of `MSG_SYNC_CHUNK` frames.
+pub(crate) fn initial_sync_frames(
+    update: &[u8],
+    protocol: RealtimeProtocol,
+    chunk_bytes: usize,
+) -> Vec<Vec<u8>> {
+    if protocol == RealtimeProtocol::V1 || chunk_bytes == 0 || update.len() <= chunk_bytes {
+        let mut enc = EncoderV1::new();
+        enc.write_var(MSG_SYNC);
+        enc.write_var(MSG_SYNC_UPDATE);
+        enc.write_buf(update);
+        return vec![enc.to_vec()];
+    }
+    let total = update.len().div_ceil(chunk_bytes);
+    update
+        .chunks(chunk_bytes)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let mut enc = EncoderV1::new();
+            enc.write_var(MSG_SYNC_CHUNK);
+            enc.write_var(index as u32);
+            enc.write_var(total as u32);
+            enc.write_buf(chunk);
+            enc.to_vec()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+    use yrs::updates::encoder::Encode;
+    use yrs::{Doc, GetString, ReadTxn, StateVector, Text, Transact};
+
+    use crate::application::ports::realtime_port::RealtimeError;
+
+    fn update_frame(content: &str) -> Vec<u8> {
+        let doc = Doc::new();
+        let text = doc.get_or_insert_text("content");
+        let mut txn = doc.transact_mut();
+        text.insert(&mut txn, 0, content);
+        let update = txn.encode_update_v1();
+        Message::Sync(SyncMessage::Update(update)).encode_v1()
+    }
+
+    fn limits(max_update_bytes: usize, max_awareness_bytes: usize) -> FrameLimits {
+        FrameLimits {
+            max_update_bytes,
+            max_awareness_bytes,
+            close_on_violation: true,
+        }
+    }
+
+    fn inbound(frames: Vec<Vec<u8>>) -> DynRealtimeStream {
+        Box::pin(futures_util::stream::iter(
+            frames.into_iter().map(Ok::<_, RealtimeError>),
+        ))
+    }
+
+    async fn passed(stream: DynRealtimeStream) -> Vec<Vec<u8>> {
+        stream.map(|item| item.unwrap()).collect().await
+    }
+
+    #[test]
+    fn admits_frames_within_limits() {
+        let frame = update_frame("hello");
+        let summary = limits(1024, 64).admit(&frame).unwrap();
+        assert!(summary.has_update);
+        assert!(!summary.has_awareness);
+    }
+
+    #[test]
+    fn rejects_oversized_update_frame_before_publishing() {
+        let frame = update_frame(&"x".repeat(4096));
+        // The update cap applies even though the frame is under the awareness cap.
+        let rejected = limits(1024, 1 << 20).admit(&frame);
+        assert!(matches!(
+            rejected,
+            Err(FrameRejection::UpdateTooLarge { limit: 1024, .. })
+        ));
+
+        let rejected = limits(1024, 512).admit(&frame);
+        assert!(matches!(
+            rejected,
+            Err(FrameRejection::UpdateTooLarge { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn hub_inbound_drops_oversized_frames() {
+        let small = update_frame("hello");
+        let large = update_frame(&"x".repeat(4096));
+        let frames = vec![small.clone(), large, small.clone()];
+
+        let keep_open = FrameLimits {
+            close_on_violation: false,
+            ..limits(1024, 64)
+        };
+        let stream = guard_inbound(inbound(frames.clone()), keep_open, None, "doc".into());
+        assert_eq!(passed(stream).await, vec![small.clone(), small.clone()]);
+
+        // Closing ends the stream at the oversized frame; nothing after it is applied.
+        let stream = guard_inbound(inbound(frames), limits(1024, 64), None, "doc".into());
+        assert_eq!(passed(stream).await, vec![small]);
+    }
+
+    #[test]
+    fn duplicate_update_frame_is_published_once() {
+        let dedup = UpdateDedup::new(8);
+        let frame = update_frame("hello");
+        let other = update_frame("world");
+        assert!(dedup.admit("doc", &frame));
+        assert!(!dedup.admit("doc", &frame));
+        assert!(dedup.admit("doc", &other));
+        // The same frame on another document is unrelated.
+        assert!(dedup.admit("other-doc", &frame));
+
+        // A frame whose publish failed may be sent again.
+        dedup.forget("doc", &other);
+        assert!(dedup.admit("doc", &other));
+    }
+
+    #[tokio::test]
+    async fn hub_inbound_drops_repeated_updates_it_applies() {
+        let frame = update_frame("hello");
+        let other = update_frame("world");
+        let applies = Arc::new(AtomicBool::new(false));
+        let dedup = Arc::new(UpdateDedup::new(8));
+        let guard = |frames: Vec<Vec<u8>>| {
+            let applies = applies.clone();
+            let dedup = InboundDedup {
+                dedup: dedup.clone(),
+                applies_updates: Box::new(move || applies.load(Ordering::SeqCst)),
+            };
+            guard_inbound(inbound(frames), limits(1024, 64), Some(dedup), "doc".into())
+        };
+
+        // Ignored while read-only, so nothing is remembered.
+        let frames = vec![frame.clone(), frame.clone()];
+        assert_eq!(passed(guard(frames)).await.len(), 2);
+
+        applies.store(true, Ordering::SeqCst);
+        let frames = vec![frame.clone(), frame.clone(), other.clone()];
+        assert_eq!(passed(guard(frames)).await, vec![frame, other]);
+    }
+
+    #[test]
+    fn large_initial_sync_is_chunked_and_reassembles() {
+        use yrs::encoding::read::{Cursor, Read};
+        use yrs::updates::decoder::Decode;
+
+        let doc = Doc::new();
+        let text = doc.get_or_insert_text("content");
+        let content = "lorem ipsum dolor sit amet ".repeat(4000);
+        text.insert(&mut doc.transact_mut(), 0, &content);
+        let state = doc
+            .transact()
+            .encode_state_as_update_v1(&StateVector::default());
+
+        assert_eq!(
+            initial_sync_frames(&state, RealtimeProtocol::V1, 4096).len(),
+            1
+        );
+        let frames = initial_sync_frames(&state, RealtimeProtocol::V2, 4096);
+        assert!(frames.len() > 1);
+
+        let mut reassembled = Vec::new();
+        for (i, frame) in frames.iter().enumerate() {
+            assert!(frame.len() <= 4096 + 16);
+            let mut cursor = Cursor::new(frame.as_slice());
+            assert_eq!(cursor.read_var::<u32>().unwrap(), MSG_SYNC_CHUNK);
+            assert_eq!(cursor.read_var::<u32>().unwrap(), i as u32);
+            assert_eq!(cursor.read_var::<u32>().unwrap(), frames.len() as u32);
+            reassembled.extend_from_slice(cursor.read_buf().unwrap());
+        }
+        let copy = Doc::new();
+        copy.transact_mut()
+            .apply_update(yrs::Update::decode_v1(&reassembled).unwrap())
+            .unwrap();
+        let copy_text = copy.get_or_insert_text("content");
+        assert_eq!(copy_text.get_string(&copy.transact()), content);
+    }
+}
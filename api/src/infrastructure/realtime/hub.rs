@@ -1,31 +1,54 @@
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
 
+use futures_util::SinkExt;
 use tokio::sync::mpsc;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{Mutex, RwLock, watch};
 use tokio::time::{Duration, sleep};
 use uuid::Uuid;
 use yrs::GetString;
+use yrs::block::ClientID;
 use yrs::encoding::write::Write as YWrite;
 use yrs::sync::protocol::{MSG_SYNC, MSG_SYNC_UPDATE};
+use yrs::sync::time::SystemClock;
+use yrs::sync::{Clock, Protocol};
 use yrs::updates::decoder::Decode;
 use yrs::updates::encoder::{Encoder, EncoderV1};
 use yrs::{Doc, ReadTxn, StateVector, Transact, Update};
 use yrs_warp::AwarenessRef;
 use yrs_warp::broadcast::BroadcastGroup;
 
+use crate::application::ports::awareness_port::{PresenceEntry, PresenceIdentity};
+use crate::application::ports::document_retention_repository::DocumentRetentionRepository;
 use crate::application::ports::linkgraph_repository::LinkGraphRepository;
+use crate::application::ports::mention_repository::MentionRepository;
+use crate::application::ports::notifier::Notifier;
 use crate::application::ports::realtime_hydration_port::{DocStateReader, RealtimeBacklogReader};
 use crate::application::ports::realtime_persistence_port::DocPersistencePort;
+use crate::application::ports::realtime_port::ContentWrite;
+use crate::application::ports::realtime_types::{RealtimeProtocol, RealtimeSession};
 use crate::application::ports::storage_port::StoragePort;
 use crate::application::ports::tagging_repository::TaggingRepository;
+use crate::application::ports::user_repository::UserRepository;
 use crate::application::services::realtime::doc_hydration::{
     DocHydrationService, HydrationOptions,
 };
-use crate::application::services::realtime::snapshot::{SnapshotPersistOptions, SnapshotService};
+use crate::application::services::realtime::doc_lock::encode_lock_frame;
+use crate::application::services::realtime::presence::PresenceDirectory;
+use crate::application::services::realtime::snapshot::{
+    self, RetentionPolicy, SnapshotPersistOptions, SnapshotService,
+};
+use crate::bootstrap::config::Config;
 use crate::infrastructure::db::PgPool;
+use crate::infrastructure::db::repositories::document_retention_repository_sqlx::SqlxDocumentRetentionRepository;
 use crate::infrastructure::db::repositories::linkgraph_repository_sqlx::SqlxLinkGraphRepository;
+use crate::infrastructure::db::repositories::mention_repository_sqlx::SqlxMentionRepository;
 use crate::infrastructure::db::repositories::tagging_repository_sqlx::SqlxTaggingRepository;
+use crate::infrastructure::db::repositories::user_repository_sqlx::SqlxUserRepository;
+use crate::infrastructure::realtime::frames::{
+    FrameLimits, InboundDedup, UpdateDedup, guard_inbound, initial_sync_frames,
+};
 use crate::infrastructure::realtime::{
     DynRealtimeSink, DynRealtimeStream, NoopBacklogReader, SqlxDocPersistenceAdapter,
     SqlxDocStateReader,
@@ -39,6 +62,10 @@ pub struct DocumentRoom {
     #[allow(dead_code)]
     persist_sub: yrs::Subscription,
     pub seq: Arc<Mutex<i64>>, // latest persisted seq
+    /// Document lock; checked on every update so locking applies to open connections.
+    pub locked: Arc<AtomicBool>,
+    /// Turns true once the persisted state has been loaded into `doc` (or loading failed).
+    pub hydrated: watch::Receiver<bool>,
 }
 
 #[derive(Clone)]
@@ -48,13 +75,24 @@ pub struct Hub {
     snapshot_service: Arc<SnapshotService>,
     persistence: Arc<dyn DocPersistencePort>,
     save_flags: Arc<Mutex<HashMap<String, bool>>>,
+    presence: Option<PresenceDirectory>,
+    frame_limits: FrameLimits,
+    update_dedup: Arc<UpdateDedup>,
+    initial_sync_chunk_bytes: usize,
+    awareness_ttl: Duration,
+    awareness_sweep_interval: Duration,
 }
 
 impl Hub {
-    pub fn new(pool: PgPool, storage: Arc<dyn StoragePort>) -> Self {
+    pub fn new(
+        cfg: &Config,
+        pool: PgPool,
+        storage: Arc<dyn StoragePort>,
+        notifier: Arc<dyn Notifier>,
+    ) -> Self {
         let doc_state_reader: Arc<dyn DocStateReader> =
             Arc::new(SqlxDocStateReader::new(pool.clone()));
-        let backlog_reader: Arc<dyn RealtimeBacklogReader> = Arc::new(NoopBacklogReader::default());
+        let backlog_reader: Arc<dyn RealtimeBacklogReader> = Arc::new(NoopBacklogReader);
         let hydration_service = Arc::new(DocHydrationService::new(
             doc_state_reader.clone(),
             backlog_reader,
@@ -64,14 +102,25 @@ impl Hub {
             Arc::new(SqlxDocPersistenceAdapter::new(pool.clone()));
         let linkgraph_repo: Arc<dyn LinkGraphRepository> =
             Arc::new(SqlxLinkGraphRepository::new(pool.clone()));
-        let tagging_repo: Arc<dyn TaggingRepository> = Arc::new(SqlxTaggingRepository::new(pool));
-        let snapshot_service = Arc::new(SnapshotService::new(
-            doc_state_reader,
-            persistence.clone(),
-            storage,
-            linkgraph_repo,
-            tagging_repo,
-        ));
+        let tagging_repo: Arc<dyn TaggingRepository> =
+            Arc::new(SqlxTaggingRepository::new(pool.clone()));
+        let retention_repo: Arc<dyn DocumentRetentionRepository> =
+            Arc::new(SqlxDocumentRetentionRepository::new(pool.clone()));
+        let user_repo: Arc<dyn UserRepository> = Arc::new(SqlxUserRepository::new(pool.clone()));
+        let mention_repo: Arc<dyn MentionRepository> = Arc::new(SqlxMentionRepository::new(pool));
+        let snapshot_service = Arc::new(
+            SnapshotService::new(
+                doc_state_reader,
+                persistence.clone(),
+                storage,
+                linkgraph_repo,
+                tagging_repo,
+                retention_repo,
+            )
+            .with_title_from_content(cfg.derive_title_from_content)
+            .with_mentions(user_repo, mention_repo)
+            .with_notifier(notifier),
+        );
 
         Self {
             inner: Arc::new(RwLock::new(HashMap::new())),
@@ -79,6 +128,26 @@ impl Hub {
             snapshot_service,
             persistence,
             save_flags: Arc::new(Mutex::new(HashMap::new())),
+            presence: None,
+            frame_limits: FrameLimits::from_config(cfg),
+            update_dedup: Arc::new(UpdateDedup::new(cfg.realtime_update_dedup_window)),
+            initial_sync_chunk_bytes: cfg.realtime_initial_sync_chunk_bytes,
+            awareness_ttl: Duration::from_millis(cfg.realtime_awareness_ttl_ms),
+            awareness_sweep_interval: Duration::from_millis(
+                cfg.realtime_awareness_sweep_interval_ms,
+            ),
+        }
+    }
+
+    pub fn with_presence(mut self, presence: PresenceDirectory) -> Self {
+        self.presence = Some(presence);
+        self
+    }
+
+    pub async fn presence(&self, doc_id: &str) -> anyhow::Result<Vec<PresenceEntry>> {
+        match &self.presence {
+            Some(directory) => directory.presence(doc_id).await,
+            None => Ok(Vec::new()),
         }
     }
     pub async fn get_or_create(&self, doc_id: &str) -> anyhow::Result<Arc<DocumentRoom>> {
@@ -92,6 +161,7 @@ impl Hub {
 
         let awareness: AwarenessRef = Arc::new(yrs::sync::Awareness::new(doc.clone()));
         let bcast = Arc::new(BroadcastGroup::new(awareness.clone(), 64).await);
+        self.spawn_awareness_sweep(Arc::downgrade(&awareness));
 
         let save_flags = self.save_flags.clone();
         let start_seq = self
@@ -123,8 +193,8 @@ impl Hub {
                         "persist_document_update_failed"
                     );
                 }
-                if s % 100 == 0 {
-                    if let Err(e) = snapshot_service
+                if s % 100 == 0
+                    && let Err(e) = snapshot_service
                         .persist_snapshot(
                             &persist_doc,
                             &doc_for_snap,
@@ -134,14 +204,13 @@ impl Hub {
                             },
                         )
                         .await
-                    {
-                        tracing::error!(
-                            document_id = %persist_doc,
-                            version = s,
-                            error = ?e,
-                            "persist_document_snapshot_failed"
-                        );
-                    }
+                {
+                    tracing::error!(
+                        document_id = %persist_doc,
+                        version = s,
+                        error = ?e,
+                        "persist_document_snapshot_failed"
+                    );
                 }
             }
         });
@@ -174,36 +243,41 @@ impl Hub {
                         let mut m = save_flags.lock().await;
                         m.remove(&doc_id_s).is_some()
                     };
-                    if should_run {
-                        if let Ok(doc_uuid) = Uuid::parse_str(&doc_id_s) {
-                            if let Err(e) = hub_clone
-                                .snapshot_service
-                                .write_markdown(&doc_uuid, &doc_for_markdown)
-                                .await
-                            {
-                                tracing::error!(
-                                    document_id = %doc_id_s,
-                                    error = ?e,
-                                    "debounced_save_failed"
-                                );
-                            }
-                        }
+                    if should_run
+                        && let Ok(doc_uuid) = Uuid::parse_str(&doc_id_s)
+                        && let Err(e) = hub_clone
+                            .snapshot_service
+                            .write_markdown(&doc_uuid, &doc_for_markdown)
+                            .await
+                    {
+                        tracing::error!(
+                            document_id = %doc_id_s,
+                            error = ?e,
+                            "debounced_save_failed"
+                        );
                     }
                 });
             })
             .unwrap();
 
+        let (hydrated_tx, hydrated) = watch::channel(false);
         let room = Arc::new(DocumentRoom {
             doc: doc.clone(),
             awareness: awareness.clone(),
             broadcast: bcast.clone(),
             persist_sub,
             seq: seq.clone(),
+            locked: Arc::new(AtomicBool::new(false)),
+            hydrated,
         });
-        self.inner
-            .write()
-            .await
-            .insert(doc_id.to_string(), room.clone());
+        {
+            // Another caller may have registered the room while we built ours.
+            let mut rooms = self.inner.write().await;
+            if let Some(existing) = rooms.get(doc_id) {
+                return Ok(existing.clone());
+            }
+            rooms.insert(doc_id.to_string(), room.clone());
+        }
         // Hydrate in background (snapshot + updates). Non-blocking for WS subscription
         let bcast_h = bcast.clone();
         let hydration = self.hydration_service.clone();
@@ -270,52 +344,51 @@ impl Hub {
                     tracing::error!(document_id = %doc_uuid, error = ?e, "hydrate_failed");
                 }
             }
+            hydrated_tx.send_replace(true);
         });
         Ok(room)
     }
 
     pub async fn get_content(&self, doc_id: &str) -> anyhow::Result<Option<String>> {
-        let map = self.inner.read().await;
-        let room = match map.get(doc_id) {
-            Some(room) => room.clone(),
-            None => return Ok(None),
+        let room = self.inner.read().await.get(doc_id).cloned();
+        let doc = match room {
+            Some(room) => room.doc.clone(),
+            // Not open in this process: read the persisted state instead.
+            None => {
+                let uuid = Uuid::parse_str(doc_id)?;
+                self.hydration_service
+                    .hydrate(&uuid, HydrationOptions::default())
+                    .await?
+                    .doc
+            }
         };
-        let txt = room.doc.get_or_insert_text("content");
-        let txn = room.doc.transact();
+        let txt = doc.get_or_insert_text("content");
+        let txn = doc.transact();
         Ok(Some(txt.get_string(&txn)))
     }
 }
 
 impl Hub {
-    pub async fn snapshot_all(
-        &self,
-        keep_versions: i64,
-        updates_keep_window: i64,
-    ) -> anyhow::Result<()> {
-        let rooms: Vec<(String, Arc<DocumentRoom>)> = {
+    pub async fn snapshot_all(&self, defaults: RetentionPolicy) -> anyhow::Result<()> {
+        let rooms: Vec<(Uuid, Arc<DocumentRoom>)> = {
             let map = self.inner.read().await;
-            map.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+            map.iter()
+                .filter_map(|(k, v)| Uuid::parse_str(k).ok().map(|id| (id, v.clone())))
+                .collect()
         };
-        for (doc_id, room) in rooms {
-            let doc_uuid = match Uuid::parse_str(&doc_id) {
-                Ok(x) => x,
-                Err(_) => continue,
-            };
+        let doc_ids: Vec<Uuid> = rooms.iter().map(|(id, _)| *id).collect();
+        let policies = self
+            .snapshot_service
+            .resolve_retention(&doc_ids, defaults)
+            .await;
+        for (doc_uuid, room) in rooms {
             let current_seq = {
                 let guard = room.seq.lock().await;
                 *guard
             };
-            let cutoff = (current_seq - updates_keep_window).max(0);
+            let policy = policies.get(&doc_uuid).copied().unwrap_or(defaults);
             self.snapshot_service
-                .persist_snapshot(
-                    &doc_uuid,
-                    &room.doc,
-                    SnapshotPersistOptions {
-                        clear_updates: false,
-                        prune_snapshots: Some(keep_versions),
-                        prune_updates_before: Some(cutoff),
-                    },
-                )
+                .persist_snapshot(&doc_uuid, &room.doc, policy.persist_options(current_seq))
                 .await?;
         }
         Ok(())
@@ -339,32 +412,300 @@ impl Hub {
         Ok(())
     }
 
+    pub async fn replace_content(&self, doc_id: &str, content: &str) -> anyhow::Result<()> {
+        let uuid = Uuid::parse_str(doc_id)?;
+        if let Some(room) = self.inner.read().await.get(doc_id).cloned() {
+            // The room's observers persist, broadcast and save the change.
+            snapshot::replace_content(&room.doc, content);
+            return Ok(());
+        }
+        let hydrated = self
+            .hydration_service
+            .hydrate(&uuid, HydrationOptions::default())
+            .await?;
+        let update = snapshot::replace_content(&hydrated.doc, content);
+        let seq = self
+            .persistence
+            .latest_update_seq(&uuid)
+            .await?
+            .unwrap_or(0)
+            + 1;
+        self.persistence
+            .append_update_with_seq(&uuid, seq, &update)
+            .await?;
+        self.snapshot_service
+            .write_markdown(&uuid, &hydrated.doc)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn replace_content_if(
+        &self,
+        doc_id: &str,
+        expected_version: &str,
+        content: &str,
+    ) -> anyhow::Result<ContentWrite> {
+        // Check and write in one transaction on the shared room doc; hydrating a private copy
+        // would let two writers both pass the check.
+        let room = self.get_or_create(doc_id).await?;
+        let mut hydrated = room.hydrated.clone();
+        let _ = hydrated.wait_for(|done| *done).await;
+        // The room's observers persist, broadcast and save the change.
+        Ok(
+            match snapshot::replace_content_if(&room.doc, expected_version, content) {
+                Ok(_) => ContentWrite::Applied,
+                Err(current) => ContentWrite::Conflict { current },
+            },
+        )
+    }
+
+    pub async fn set_locked(&self, doc_id: &str, locked: bool) -> anyhow::Result<()> {
+        let Some(room) = self.inner.read().await.get(doc_id).cloned() else {
+            return Ok(());
+        };
+        room.locked.store(locked, Ordering::SeqCst);
+        // Fails only when nobody is connected, in which case there is no one to tell.
+        let _ = room.broadcast.broadcast(encode_lock_frame(locked));
+        Ok(())
+    }
+
+    /// Sends a large document state as chunks to clients that negotiated them, and returns the
+    /// state vector the chunks cover so the SyncStep1 reply only adds what came after.
+    async fn send_chunked_state(
+        &self,
+        room: &DocumentRoom,
+        sink: &DynRealtimeSink,
+        protocol: RealtimeProtocol,
+    ) -> anyhow::Result<Option<StateVector>> {
+        if protocol == RealtimeProtocol::V1 || self.initial_sync_chunk_bytes == 0 {
+            return Ok(None);
+        }
+        // Subscribing before hydration finishes would deliver the loaded state as one broadcast
+        // frame instead.
+        let mut hydrated = room.hydrated.clone();
+        let _ = hydrated.wait_for(|done| *done).await;
+        let (state, sent) = {
+            let txn = room.doc.transact();
+            (
+                txn.encode_state_as_update_v1(&StateVector::default()),
+                txn.state_vector(),
+            )
+        };
+        if state.len() <= self.initial_sync_chunk_bytes {
+            return Ok(None);
+        }
+        let mut guard = sink.lock().await;
+        for frame in initial_sync_frames(&state, protocol, self.initial_sync_chunk_bytes) {
+            guard
+                .send(frame)
+                .await
+                .map_err(|e| anyhow::anyhow!("initial_sync_send_failed: {e}"))?;
+        }
+        Ok(Some(sent))
+    }
+
+    /// Drops awareness clients that went silent without a clean disconnect; the removal is
+    /// broadcast to the room. Ends once the room's awareness is gone.
+    fn spawn_awareness_sweep(&self, awareness: Weak<yrs::sync::Awareness>) {
+        if self.awareness_ttl.is_zero() {
+            return;
+        }
+        let ttl_ms = self.awareness_ttl.as_millis() as u64;
+        let interval = if self.awareness_sweep_interval.is_zero() {
+            self.awareness_ttl / 2
+        } else {
+            self.awareness_sweep_interval
+        };
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let Some(awareness) = awareness.upgrade() else {
+                    break;
+                };
+                expire_stale_clients(&awareness, ttl_ms, SystemClock.now());
+            }
+        });
+    }
+
     pub async fn subscribe(
         &self,
         doc_id: &str,
         sink: DynRealtimeSink,
         stream: DynRealtimeStream,
-        can_edit: bool,
+        session: RealtimeSession,
     ) -> anyhow::Result<()> {
         let room = self.get_or_create(doc_id).await?;
-        let subscription = if can_edit {
-            room.broadcast.subscribe(sink, stream)
-        } else {
-            room.broadcast
-                .subscribe_with(sink, stream, ReadOnlyProtocol)
+        // The session's lock state was just read from the database, so it is current.
+        room.locked.store(session.locked, Ordering::SeqCst);
+        if session.locked {
+            let _ = sink.lock().await.send(encode_lock_frame(true)).await;
+        }
+        let presence = self.presence.clone().map(|directory| ConnectionPresence {
+            directory,
+            doc_id: doc_id.to_string(),
+            identity: session.identity,
+        });
+        let clients = Arc::new(std::sync::Mutex::new(HashSet::new()));
+        let sent_state = self
+            .send_chunked_state(&room, &sink, session.protocol)
+            .await?;
+        let protocol = ConnectionProtocol {
+            can_edit: session.can_edit,
+            locked: room.locked.clone(),
+            presence: presence.clone(),
+            clients: clients.clone(),
+            sent_state,
         };
-
-        subscription
+        // Oversized frames and repeats of recent updates are dropped before yrs applies them.
+        let can_edit = session.can_edit;
+        let locked = room.locked.clone();
+        let dedup = InboundDedup {
+            dedup: self.update_dedup.clone(),
+            applies_updates: Box::new(move || can_edit && !locked.load(Ordering::SeqCst)),
+        };
+        let stream = guard_inbound(stream, self.frame_limits, Some(dedup), doc_id.to_string());
+        let result = room
+            .broadcast
+            .subscribe_with(sink, stream, protocol)
             .completed()
             .await
-            .map_err(|e| anyhow::anyhow!(e))
+            .map_err(|e| anyhow::anyhow!(e));
+        // Clients of this connection leave with it instead of lingering until the TTL.
+        let clients: Vec<_> = clients.lock().unwrap().drain().collect();
+        remove_clients(&room.awareness, &clients);
+        if let Some(presence) = presence {
+            presence.directory.forget(&presence.doc_id, &clients);
+        }
+        result
+    }
+}
+
+/// Presence attribution for the awareness clients one connection publishes.
+#[derive(Clone)]
+struct ConnectionPresence {
+    directory: PresenceDirectory,
+    doc_id: String,
+    identity: PresenceIdentity,
+}
+
+impl ConnectionPresence {
+    fn record(&self, update: &yrs::sync::awareness::AwarenessUpdate) {
+        for (client, entry) in &update.clients {
+            self.directory
+                .record(&self.doc_id, *client, self.identity, &entry.json);
+        }
+    }
+}
+
+/// Removes awareness clients not updated within `ttl_ms` of `now_ms`.
+fn expire_stale_clients(
+    awareness: &yrs::sync::Awareness,
+    ttl_ms: u64,
+    now_ms: u64,
+) -> Vec<ClientID> {
+    let own = awareness.client_id();
+    let stale: Vec<_> = awareness
+        .iter()
+        .filter(|(client, state)| {
+            *client != own
+                && state.data.is_some()
+                && now_ms.saturating_sub(state.last_updated) > ttl_ms
+        })
+        .map(|(client, _)| client)
+        .collect();
+    remove_clients(awareness, &stale);
+    stale
+}
+
+/// Removes the clients' awareness states; already removed clients are left alone so no
+/// duplicate removal is broadcast.
+fn remove_clients(awareness: &yrs::sync::Awareness, clients: &[ClientID]) {
+    let live: HashSet<ClientID> = awareness
+        .iter()
+        .filter(|(_, state)| state.data.is_some())
+        .map(|(client, _)| client)
+        .collect();
+    for client in clients.iter().filter(|client| live.contains(client)) {
+        awareness.remove_state(*client);
+    }
+}
+
+/// Per-connection protocol: drops edits from read-only peers and while the document is
+/// locked, and records presence.
+struct ConnectionProtocol {
+    can_edit: bool,
+    locked: Arc<AtomicBool>,
+    presence: Option<ConnectionPresence>,
+    /// Awareness clients published over this connection, removed when it closes.
+    clients: Arc<std::sync::Mutex<HashSet<ClientID>>>,
+    /// State already sent to the client as initial sync chunks.
+    sent_state: Option<StateVector>,
+}
+
+impl ConnectionProtocol {
+    fn read_only(&self) -> bool {
+        !self.can_edit || self.locked.load(Ordering::SeqCst)
+    }
+}
+
+impl Protocol for ConnectionProtocol {
+    fn handle_sync_step1(
+        &self,
+        awareness: &yrs::sync::Awareness,
+        sv: StateVector,
+    ) -> Result<Option<yrs::sync::Message>, yrs::sync::Error> {
+        let mut sv = sv;
+        if let Some(sent) = &self.sent_state {
+            sv.merge(sent.clone());
+        }
+        yrs::sync::DefaultProtocol.handle_sync_step1(awareness, sv)
+    }
+
+    fn handle_sync_step2(
+        &self,
+        awareness: &yrs::sync::Awareness,
+        update: yrs::Update,
+    ) -> Result<Option<yrs::sync::Message>, yrs::sync::Error> {
+        if self.read_only() {
+            ReadOnlyProtocol.handle_sync_step2(awareness, update)
+        } else {
+            yrs::sync::DefaultProtocol.handle_sync_step2(awareness, update)
+        }
+    }
+
+    fn handle_update(
+        &self,
+        awareness: &yrs::sync::Awareness,
+        update: yrs::Update,
+    ) -> Result<Option<yrs::sync::Message>, yrs::sync::Error> {
+        if self.read_only() {
+            ReadOnlyProtocol.handle_update(awareness, update)
+        } else {
+            yrs::sync::DefaultProtocol.handle_update(awareness, update)
+        }
+    }
+
+    fn handle_awareness_update(
+        &self,
+        awareness: &yrs::sync::Awareness,
+        update: yrs::sync::awareness::AwarenessUpdate,
+    ) -> Result<Option<yrs::sync::Message>, yrs::sync::Error> {
+        self.clients
+            .lock()
+            .unwrap()
+            .extend(update.clients.keys().copied());
+        if let Some(presence) = &self.presence {
+            presence.record(&update);
+        }
+        yrs::sync::DefaultProtocol.handle_awareness_update(awareness, update)
     }
 }
 
 #[derive(Debug, Clone, Copy)]
 struct ReadOnlyProtocol;
 
-impl yrs::sync::Protocol for ReadOnlyProtocol {
+impl Protocol for ReadOnlyProtocol {
     fn handle_sync_step2(
         &self,
         _awareness: &yrs::sync::Awareness,
@@ -381,3 +722,211 @@ impl yrs::sync::Protocol for ReadOnlyProtocol {
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use yrs::{Text, WriteTxn};
+
+    use super::*;
+    use crate::application::ports::realtime_port::content_version;
+    use crate::test_support::realtime::MemoryDocStore;
+
+    fn edit(text: &str) -> Update {
+        let doc = Doc::new();
+        let content = doc.get_or_insert_text("content");
+        let mut txn = doc.transact_mut();
+        content.insert(&mut txn, 0, text);
+        Update::decode_v1(&txn.encode_update_v1()).unwrap()
+    }
+
+    fn content(awareness: &yrs::sync::Awareness) -> String {
+        let doc = awareness.doc();
+        let mut txn = doc.transact_mut();
+        let text = txn.get_or_insert_text("content");
+        text.get_string(&txn)
+    }
+
+    #[test]
+    fn locked_document_rejects_updates_until_unlocked() {
+        let awareness = yrs::sync::Awareness::new(Doc::new());
+        let locked = Arc::new(AtomicBool::new(true));
+        let protocol = ConnectionProtocol {
+            can_edit: true,
+            locked: locked.clone(),
+            presence: None,
+            clients: Default::default(),
+            sent_state: None,
+        };
+
+        protocol.handle_update(&awareness, edit("frozen")).unwrap();
+        assert_eq!(content(&awareness), "");
+
+        locked.store(false, Ordering::SeqCst);
+        protocol.handle_update(&awareness, edit("thawed")).unwrap();
+        assert_eq!(content(&awareness), "thawed");
+    }
+
+    #[test]
+    fn sync_step1_after_chunks_only_sends_later_changes() {
+        let server = yrs::sync::Awareness::new(Doc::new());
+        let text = server.doc().get_or_insert_text("content");
+        text.insert(
+            &mut server.doc().transact_mut(),
+            0,
+            &"chunked ".repeat(1000),
+        );
+        let (chunked, sent) = {
+            let txn = server.doc().transact();
+            (
+                txn.encode_state_as_update_v1(&StateVector::default()),
+                txn.state_vector(),
+            )
+        };
+        // An edit lands between sending the chunks and the client's SyncStep1.
+        text.push(&mut server.doc().transact_mut(), "tail");
+
+        let protocol = ConnectionProtocol {
+            can_edit: true,
+            locked: Arc::new(AtomicBool::new(false)),
+            presence: None,
+            clients: Default::default(),
+            sent_state: Some(sent),
+        };
+        let reply = protocol
+            .handle_sync_step1(&server, StateVector::default())
+            .unwrap();
+        let Some(yrs::sync::Message::Sync(yrs::sync::SyncMessage::SyncStep2(diff))) = reply else {
+            panic!("expected a SyncStep2 reply");
+        };
+        assert!(diff.len() < chunked.len() / 10);
+
+        let client = yrs::sync::Awareness::new(Doc::new());
+        for update in [chunked, diff] {
+            let mut txn = client.doc().transact_mut();
+            txn.apply_update(Update::decode_v1(&update).unwrap())
+                .unwrap();
+        }
+        assert_eq!(content(&client), content(&server));
+    }
+
+    fn cursor_from(client: &yrs::sync::Awareness) -> yrs::sync::AwarenessUpdate {
+        client.set_local_state_raw(r#"{"cursor":1}"#);
+        client.update().unwrap()
+    }
+
+    fn removals(
+        server: &yrs::sync::Awareness,
+    ) -> (yrs::Subscription, Arc<std::sync::Mutex<Vec<ClientID>>>) {
+        let removed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = removed.clone();
+        let sub = server.on_update(move |_, event, _| {
+            sink.lock().unwrap().extend_from_slice(event.removed());
+        });
+        (sub, removed)
+    }
+
+    #[test]
+    fn silent_awareness_clients_are_removed_after_ttl() {
+        let server = yrs::sync::Awareness::with_clock(Doc::new(), || 1_000);
+        let client = yrs::sync::Awareness::new(Doc::new());
+        server.apply_update(cursor_from(&client)).unwrap();
+        let (_sub, removed) = removals(&server);
+
+        assert!(expire_stale_clients(&server, 500, 1_400).is_empty());
+        assert_eq!(
+            expire_stale_clients(&server, 500, 1_600),
+            vec![client.client_id()]
+        );
+        assert_eq!(removed.lock().unwrap().as_slice(), &[client.client_id()]);
+
+        // A removed client is not removed (and broadcast) again.
+        assert!(expire_stale_clients(&server, 500, 2_000).is_empty());
+    }
+
+    #[test]
+    fn closing_connection_removes_its_awareness_clients() {
+        let server = yrs::sync::Awareness::new(Doc::new());
+        let protocol = ConnectionProtocol {
+            can_edit: false,
+            locked: Arc::new(AtomicBool::new(false)),
+            presence: None,
+            clients: Default::default(),
+            sent_state: None,
+        };
+        let mine = yrs::sync::Awareness::new(Doc::new());
+        let other = yrs::sync::Awareness::new(Doc::new());
+        protocol
+            .handle_awareness_update(&server, cursor_from(&mine))
+            .unwrap();
+        server.apply_update(cursor_from(&other)).unwrap();
+        let (_sub, removed) = removals(&server);
+
+        let clients: Vec<_> = protocol.clients.lock().unwrap().drain().collect();
+        remove_clients(&server, &clients);
+        assert_eq!(removed.lock().unwrap().as_slice(), &[mine.client_id()]);
+        assert!(
+            server
+                .state::<serde_json::Value>(other.client_id())
+                .is_some()
+        );
+    }
+
+    fn hub(store: &Arc<MemoryDocStore>) -> Hub {
+        Hub {
+            inner: Arc::new(RwLock::new(HashMap::new())),
+            hydration_service: Arc::new(DocHydrationService::new(
+                store.clone(),
+                Arc::new(NoopBacklogReader),
+                store.storage.clone(),
+            )),
+            snapshot_service: Arc::new(SnapshotService::new(
+                store.clone(),
+                store.clone(),
+                store.storage.clone(),
+                store.clone(),
+                store.clone(),
+                store.clone(),
+            )),
+            persistence: store.clone(),
+            save_flags: Arc::new(Mutex::new(HashMap::new())),
+            presence: None,
+            frame_limits: FrameLimits {
+                max_update_bytes: 1 << 20,
+                max_awareness_bytes: 1 << 16,
+                close_on_violation: false,
+            },
+            update_dedup: Arc::new(UpdateDedup::new(0)),
+            initial_sync_chunk_bytes: 0,
+            awareness_ttl: Duration::ZERO,
+            awareness_sweep_interval: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_conditional_writes_to_an_unloaded_document_apply_once() {
+        let store = Arc::new(MemoryDocStore::default());
+        let doc_id = Uuid::new_v4();
+        store
+            .titles
+            .lock()
+            .unwrap()
+            .insert(doc_id, "Doc".to_string());
+        store
+            .append_update_with_seq(&doc_id, 1, &snapshot::replace_content(&Doc::new(), "v1"))
+            .await
+            .unwrap();
+        let hub = hub(&store);
+        let id = doc_id.to_string();
+        let expected = content_version("v1");
+
+        let (a, b) = tokio::join!(
+            hub.replace_content_if(&id, &expected, "from a"),
+            hub.replace_content_if(&id, &expected, "from b"),
+        );
+        let applied = [a.unwrap(), b.unwrap()]
+            .iter()
+            .filter(|w| matches!(w, ContentWrite::Applied))
+            .count();
+        assert_eq!(applied, 1);
+    }
+}
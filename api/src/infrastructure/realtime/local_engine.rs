@@ -1,5 +1,8 @@
-use crate::application::ports::realtime_port::RealtimeEngine;
-use crate::application::ports::realtime_types::{DynRealtimeSink, DynRealtimeStream};
+use crate::application::ports::awareness_port::PresenceEntry;
+use crate::application::ports::realtime_port::{ContentWrite, RealtimeEngine};
+use crate::application::ports::realtime_types::{
+    DynRealtimeSink, DynRealtimeStream, RealtimeSession,
+};
 
 pub struct LocalRealtimeEngine {
     pub hub: crate::infrastructure::realtime::Hub,
@@ -12,9 +15,17 @@ impl RealtimeEngine for LocalRealtimeEngine {
         doc_id: &str,
         sink: DynRealtimeSink,
         stream: DynRealtimeStream,
-        can_edit: bool,
+        session: RealtimeSession,
     ) -> anyhow::Result<()> {
-        self.hub.subscribe(doc_id, sink, stream, can_edit).await
+        self.hub.subscribe(doc_id, sink, stream, session).await
+    }
+
+    async fn set_locked(&self, doc_id: &str, locked: bool) -> anyhow::Result<()> {
+        self.hub.set_locked(doc_id, locked).await
+    }
+
+    async fn presence(&self, doc_id: &str) -> anyhow::Result<Vec<PresenceEntry>> {
+        self.hub.presence(doc_id).await
     }
 
     async fn get_content(&self, doc_id: &str) -> anyhow::Result<Option<String>> {
@@ -24,4 +35,19 @@ impl RealtimeEngine for LocalRealtimeEngine {
     async fn force_persist(&self, doc_id: &str) -> anyhow::Result<()> {
         self.hub.force_save_to_fs(doc_id).await
     }
+
+    async fn replace_content(&self, doc_id: &str, content: &str) -> anyhow::Result<()> {
+        self.hub.replace_content(doc_id, content).await
+    }
+
+    async fn replace_content_if(
+        &self,
+        doc_id: &str,
+        expected_version: &str,
+        content: &str,
+    ) -> anyhow::Result<ContentWrite> {
+        self.hub
+            .replace_content_if(doc_id, expected_version, content)
+            .await
+    }
 }
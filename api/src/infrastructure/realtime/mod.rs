@@ -2,6 +2,7 @@ pub use crate::application::ports::realtime_types::{DynRealtimeSink, DynRealtime
 
 mod doc_persistence;
 mod doc_state_reader;
+mod frames;
 mod hub;
 mod local_engine;
 mod noop_ports;
@@ -1,19 +1,27 @@
+use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::application::ports::awareness_port::AwarenessPublisher;
 use crate::application::ports::realtime_hydration_port::{RealtimeBacklogReader, StreamFrame};
+use crate::application::ports::realtime_persistence_port::{
+    PendingUpdates, PersistenceTaskProducerPort,
+};
 use anyhow::Context;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use redis::AsyncCommands;
 use redis::streams::{StreamRangeReply, StreamReadOptions, StreamReadReply};
 use tokio::sync::mpsc;
 use tokio::time::sleep;
 use tokio_stream::wrappers::UnboundedReceiverStream;
+use uuid::Uuid;
 
 const FIELD_FRAME: &str = "frame";
 const FIELD_AWARENESS: &str = "awareness";
 const FIELD_TASK_DOC: &str = "doc";
+const RECONNECT_BACKOFF_INITIAL: Duration = Duration::from_millis(100);
+const SCAN_BATCH: usize = 200;
 
 #[derive(Clone)]
 pub struct RedisClusterBus {
@@ -75,18 +83,73 @@ impl RedisClusterBus {
             .context("redis_xadd_update")?;
 
         // Schedule a background persistence task (best effort)
-        let mut task_cmd = redis::cmd("XADD");
-        task_cmd.arg(self.tasks_key());
+        let _ = self.add_task(&mut conn, doc_id).await;
+        Ok(id)
+    }
+
+    async fn add_task(
+        &self,
+        conn: &mut redis::aio::MultiplexedConnection,
+        doc_id: &str,
+    ) -> anyhow::Result<()> {
+        let mut cmd = redis::cmd("XADD");
+        cmd.arg(self.tasks_key());
         if let Some(max_len) = self.stream_max_len {
-            task_cmd.arg("MAXLEN").arg("~").arg(max_len as i64);
+            cmd.arg("MAXLEN").arg("~").arg(max_len as i64);
         }
-        let _ = task_cmd
-            .arg("*")
-            .arg("doc")
+        cmd.arg("*")
+            .arg(FIELD_TASK_DOC)
             .arg(doc_id)
-            .query_async::<()>(&mut conn)
-            .await;
-        Ok(id)
+            .query_async::<()>(conn)
+            .await
+            .context("redis_xadd_task")
+    }
+
+    /// Document ids of every non-empty update stream, with the id of its newest entry.
+    pub async fn update_stream_tails(&self) -> anyhow::Result<Vec<(String, String)>> {
+        let mut conn = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .context("redis_get_async_connection")?;
+        let prefix = format!("{}:", self.stream_prefix);
+        let pattern = format!("{}*:updates", prefix);
+        let mut keys = Vec::new();
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH)
+                .query_async(&mut conn)
+                .await
+                .context("redis_scan_updates")?;
+            keys.extend(batch);
+            if next == 0 {
+                break;
+            }
+            cursor = next;
+        }
+
+        let mut tails = Vec::new();
+        for key in keys {
+            let Some(doc_id) = key
+                .strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(":updates"))
+            else {
+                continue;
+            };
+            let reply: StreamRangeReply = conn
+                .xrevrange_count(&key, "+", "-", 1)
+                .await
+                .context("redis_xrevrange_updates")?;
+            if let Some(entry) = reply.ids.into_iter().next() {
+                tails.push((doc_id.to_string(), entry.id));
+            }
+        }
+        Ok(tails)
     }
 
     pub async fn publish_awareness(
@@ -239,59 +302,22 @@ impl RedisClusterBus {
         Ok(())
     }
 
+    // Retries start quickly and back off up to the configured poll interval.
+    fn reconnect_backoff(&self) -> ReconnectBackoff {
+        ReconnectBackoff {
+            initial: RECONNECT_BACKOFF_INITIAL,
+            max: self.poll_interval.max(RECONNECT_BACKOFF_INITIAL),
+        }
+    }
+
     fn spawn_stream_reader_bytes(
         &self,
         key: String,
         field: &'static str,
         start_id: Option<String>,
     ) -> UnboundedReceiverStream<anyhow::Result<StreamItem>> {
-        let client = self.client.clone();
-        let poll_interval = self.poll_interval;
-        let mut last_id = start_id.unwrap_or_else(|| "$".to_string());
-        let (tx, rx) = mpsc::unbounded_channel();
-
-        tokio::spawn(async move {
-            loop {
-                match client.get_multiplexed_async_connection().await {
-                    Ok(mut conn) => {
-                        let opts = StreamReadOptions::default().block(1000).count(128);
-                        let keys = [key.as_str()];
-                        let ids = [last_id.as_str()];
-                        let reply: redis::RedisResult<StreamReadReply> =
-                            conn.xread_options(&keys, &ids, &opts).await;
-                        match reply {
-                            Ok(data) => {
-                                let mut advanced = false;
-                                for stream_key in data.keys {
-                                    for entry in stream_key.ids {
-                                        if let Some(value) = entry.get::<Vec<u8>>(field) {
-                                            last_id = entry.id.clone();
-                                            advanced = true;
-                                            if tx.send(Ok((entry.id, value))).is_err() {
-                                                return;
-                                            }
-                                        }
-                                    }
-                                }
-                                if !advanced {
-                                    tokio::task::yield_now().await;
-                                }
-                            }
-                            Err(e) => {
-                                tracing::warn!(stream = %key, error = ?e, "redis_stream_read_failed");
-                                sleep(poll_interval).await;
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!(stream = %key, error = ?e, "redis_stream_connect_failed");
-                        sleep(poll_interval).await;
-                    }
-                }
-            }
-        });
-
-        UnboundedReceiverStream::new(rx)
+        let fetcher = RedisStreamFetcher::<Vec<u8>>::new(self.client.clone(), key, field);
+        spawn_stream_reader(fetcher, start_id, self.reconnect_backoff())
     }
 
     fn spawn_stream_reader_strings(
@@ -300,53 +326,186 @@ impl RedisClusterBus {
         field: &'static str,
         start_id: Option<String>,
     ) -> UnboundedReceiverStream<anyhow::Result<TaskItem>> {
-        let client = self.client.clone();
-        let poll_interval = self.poll_interval;
-        let mut last_id = start_id.unwrap_or_else(|| "$".to_string());
-        let (tx, rx) = mpsc::unbounded_channel();
-
-        tokio::spawn(async move {
-            loop {
-                match client.get_multiplexed_async_connection().await {
-                    Ok(mut conn) => {
-                        let opts = StreamReadOptions::default().block(1000).count(128);
-                        let keys = [key.as_str()];
-                        let ids = [last_id.as_str()];
-                        let reply: redis::RedisResult<StreamReadReply> =
-                            conn.xread_options(&keys, &ids, &opts).await;
-                        match reply {
-                            Ok(data) => {
-                                let mut advanced = false;
-                                for stream_key in data.keys {
-                                    for entry in stream_key.ids {
-                                        if let Some(value) = entry.get::<String>(field) {
-                                            last_id = entry.id.clone();
-                                            advanced = true;
-                                            if tx.send(Ok((entry.id, value))).is_err() {
-                                                return;
-                                            }
-                                        }
-                                    }
-                                }
-                                if !advanced {
-                                    tokio::task::yield_now().await;
-                                }
-                            }
-                            Err(e) => {
-                                tracing::warn!(stream = %key, error = ?e, "redis_stream_read_failed");
-                                sleep(poll_interval).await;
-                            }
+        let fetcher = RedisStreamFetcher::<String>::new(self.client.clone(), key, field);
+        spawn_stream_reader(fetcher, start_id, self.reconnect_backoff())
+    }
+}
+
+/// Source of stream entries for a single key, read after a given id.
+#[async_trait]
+trait StreamFetcher: Send + 'static {
+    type Value: Send + 'static;
+
+    fn key(&self) -> &str;
+
+    /// Id of the newest entry, used to turn a `$` start into a concrete id so
+    /// a reconnect does not skip entries written while disconnected.
+    async fn tail_id(&mut self) -> anyhow::Result<String>;
+
+    async fn fetch(&mut self, after_id: &str) -> anyhow::Result<Vec<(String, Self::Value)>>;
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ReconnectBackoff {
+    initial: Duration,
+    max: Duration,
+}
+
+/// Reads `fetcher` into a channel until the receiver is dropped. Failed reads
+/// are retried with exponential backoff, resuming after the last delivered id.
+fn spawn_stream_reader<F>(
+    mut fetcher: F,
+    start_id: Option<String>,
+    backoff: ReconnectBackoff,
+) -> UnboundedReceiverStream<anyhow::Result<(String, F::Value)>>
+where
+    F: StreamFetcher,
+{
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut last_id = start_id.unwrap_or_else(|| "$".to_string());
+
+    tokio::spawn(async move {
+        let mut delay = backoff.initial;
+        let mut failures: u32 = 0;
+        loop {
+            if tx.is_closed() {
+                return;
+            }
+            let result = if last_id == "$" {
+                fetcher.tail_id().await.map(|id| {
+                    last_id = id;
+                    Vec::new()
+                })
+            } else {
+                fetcher.fetch(&last_id).await
+            };
+            match result {
+                Ok(entries) => {
+                    if failures > 0 {
+                        tracing::info!(
+                            stream = fetcher.key(),
+                            failures,
+                            last_id = %last_id,
+                            "redis_stream_reconnected"
+                        );
+                    }
+                    failures = 0;
+                    delay = backoff.initial;
+                    let advanced = !entries.is_empty();
+                    for (id, value) in entries {
+                        last_id = id.clone();
+                        if tx.send(Ok((id, value))).is_err() {
+                            return;
                         }
                     }
-                    Err(e) => {
-                        tracing::error!(stream = %key, error = ?e, "redis_stream_connect_failed");
-                        sleep(poll_interval).await;
+                    if !advanced {
+                        tokio::task::yield_now().await;
                     }
                 }
+                Err(e) => {
+                    failures = failures.saturating_add(1);
+                    tracing::warn!(
+                        stream = fetcher.key(),
+                        error = ?e,
+                        failures,
+                        retry_in_ms = delay.as_millis() as u64,
+                        "redis_stream_read_failed"
+                    );
+                    sleep(delay).await;
+                    delay = (delay * 2).min(backoff.max);
+                }
             }
-        });
+        }
+    });
+
+    UnboundedReceiverStream::new(rx)
+}
 
-        UnboundedReceiverStream::new(rx)
+struct RedisStreamFetcher<T> {
+    client: Arc<redis::Client>,
+    conn: Option<redis::aio::MultiplexedConnection>,
+    key: String,
+    field: &'static str,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<T> RedisStreamFetcher<T> {
+    fn new(client: Arc<redis::Client>, key: String, field: &'static str) -> Self {
+        Self {
+            client,
+            conn: None,
+            key,
+            field,
+            _value: PhantomData,
+        }
+    }
+
+    async fn connection(&mut self) -> anyhow::Result<redis::aio::MultiplexedConnection> {
+        if let Some(conn) = &self.conn {
+            return Ok(conn.clone());
+        }
+        let conn = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .context("redis_get_async_connection")?;
+        self.conn = Some(conn.clone());
+        Ok(conn)
+    }
+}
+
+#[async_trait]
+impl<T> StreamFetcher for RedisStreamFetcher<T>
+where
+    T: redis::FromRedisValue + Send + 'static,
+{
+    type Value = T;
+
+    fn key(&self) -> &str {
+        &self.key
+    }
+
+    async fn tail_id(&mut self) -> anyhow::Result<String> {
+        let mut conn = self.connection().await?;
+        let reply: redis::RedisResult<StreamRangeReply> =
+            conn.xrevrange_count(&self.key, "+", "-", 1).await;
+        match reply {
+            Ok(reply) => Ok(reply
+                .ids
+                .into_iter()
+                .next()
+                .map(|entry| entry.id)
+                .unwrap_or_else(|| "0-0".to_string())),
+            Err(e) => {
+                // Drop the connection so the next attempt reconnects.
+                self.conn = None;
+                Err(e).context("redis_xrevrange_tail")
+            }
+        }
+    }
+
+    async fn fetch(&mut self, after_id: &str) -> anyhow::Result<Vec<(String, T)>> {
+        let mut conn = self.connection().await?;
+        let opts = StreamReadOptions::default().block(1000).count(128);
+        let keys = [self.key.as_str()];
+        let ids = [after_id];
+        let reply: redis::RedisResult<StreamReadReply> =
+            conn.xread_options(&keys, &ids, &opts).await;
+        match reply {
+            Ok(data) => Ok(data
+                .keys
+                .into_iter()
+                .flat_map(|stream_key| stream_key.ids)
+                .filter_map(|entry| {
+                    let value = entry.get::<T>(self.field)?;
+                    Some((entry.id, value))
+                })
+                .collect()),
+            Err(e) => {
+                self.conn = None;
+                Err(e).context("redis_xread")
+            }
+        }
     }
 }
 
@@ -377,6 +536,38 @@ impl RealtimeBacklogReader for RedisClusterBus {
     }
 }
 
+#[async_trait]
+impl PersistenceTaskProducerPort for RedisClusterBus {
+    async fn pending_updates(&self) -> anyhow::Result<Vec<PendingUpdates>> {
+        Ok(self
+            .update_stream_tails()
+            .await?
+            .into_iter()
+            .filter_map(|(doc_id, entry_id)| {
+                Some(PendingUpdates {
+                    document_id: Uuid::parse_str(&doc_id).ok()?,
+                    last_update_at: stream_id_time(&entry_id)?,
+                })
+            })
+            .collect())
+    }
+
+    async fn enqueue_task(&self, doc_id: &Uuid) -> anyhow::Result<()> {
+        let mut conn = self
+            .client
+            .get_multiplexed_async_connection()
+            .await
+            .context("redis_get_async_connection")?;
+        self.add_task(&mut conn, &doc_id.to_string()).await
+    }
+}
+
+/// Stream entry ids are `<unix millis>-<seq>`.
+pub(crate) fn stream_id_time(entry_id: &str) -> Option<DateTime<Utc>> {
+    let millis = entry_id.split('-').next()?.parse().ok()?;
+    DateTime::from_timestamp_millis(millis)
+}
+
 #[async_trait]
 impl AwarenessPublisher for RedisClusterBus {
     async fn publish_awareness(&self, doc_id: &str, frame: Vec<u8>) -> anyhow::Result<()> {
@@ -384,3 +575,69 @@ impl AwarenessPublisher for RedisClusterBus {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+    use tokio_stream::StreamExt;
+
+    type Step = anyhow::Result<Vec<(String, String)>>;
+
+    struct MockFetcher {
+        steps: VecDeque<Step>,
+        seen: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl StreamFetcher for MockFetcher {
+        type Value = String;
+
+        fn key(&self) -> &str {
+            "mock"
+        }
+
+        async fn tail_id(&mut self) -> anyhow::Result<String> {
+            Ok("0-0".to_string())
+        }
+
+        async fn fetch(&mut self, after_id: &str) -> anyhow::Result<Vec<(String, String)>> {
+            self.seen.lock().unwrap().push(after_id.to_string());
+            match self.steps.pop_front() {
+                Some(step) => step,
+                None => {
+                    sleep(Duration::from_millis(5)).await;
+                    Ok(Vec::new())
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn recovers_from_transient_errors_and_resumes_after_last_id() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let fetcher = MockFetcher {
+            steps: VecDeque::from(vec![
+                Ok(vec![("1-0".to_string(), "a".to_string())]),
+                Err(anyhow::anyhow!("connection reset")),
+                Err(anyhow::anyhow!("connection refused")),
+                Ok(vec![("2-0".to_string(), "b".to_string())]),
+            ]),
+            seen: seen.clone(),
+        };
+        let backoff = ReconnectBackoff {
+            initial: Duration::from_millis(1),
+            max: Duration::from_millis(4),
+        };
+
+        let mut stream = spawn_stream_reader(fetcher, None, backoff);
+        let first = stream.next().await.unwrap().unwrap();
+        let second = stream.next().await.unwrap().unwrap();
+        assert_eq!(first, ("1-0".to_string(), "a".to_string()));
+        assert_eq!(second, ("2-0".to_string(), "b".to_string()));
+
+        let seen = seen.lock().unwrap().clone();
+        assert_eq!(&seen[..4], ["0-0", "1-0", "1-0", "1-0"]);
+    }
+}
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, anyhow};
@@ -7,34 +8,51 @@ use tokio::task::JoinHandle;
 use tokio::time::sleep;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use uuid::Uuid;
-use yrs::encoding::read::Cursor;
-use yrs::encoding::write::Write as YWrite;
-use yrs::sync::protocol::{MSG_SYNC, MSG_SYNC_UPDATE};
-use yrs::sync::{Message, MessageReader, SyncMessage};
-use yrs::updates::decoder::DecoderV1;
-use yrs::updates::encoder::{Encoder, EncoderV1};
+use yrs::sync::{Message, SyncMessage};
+use yrs::updates::encoder::Encode;
 use yrs::{Doc, GetString, ReadTxn, StateVector, Transact};
 
-use crate::application::ports::awareness_port::AwarenessPublisher;
+use crate::application::ports::awareness_port::{AwarenessPublisher, PresenceEntry};
+use crate::application::ports::document_retention_repository::DocumentRetentionRepository;
 use crate::application::ports::linkgraph_repository::LinkGraphRepository;
-use crate::application::ports::realtime_hydration_port::{DocStateReader, RealtimeBacklogReader};
+use crate::application::ports::mention_repository::MentionRepository;
+use crate::application::ports::notifier::Notifier;
+use crate::application::ports::realtime_hydration_port::{
+    DocStateReader, RealtimeBacklogReader, StreamFrame,
+};
 use crate::application::ports::realtime_persistence_port::DocPersistencePort;
-use crate::application::ports::realtime_port::RealtimeEngine as RealtimeEngineTrait;
-use crate::application::ports::realtime_types::{DynRealtimeSink, DynRealtimeStream};
+use crate::application::ports::realtime_port::{
+    ContentWrite, RealtimeEngine as RealtimeEngineTrait,
+};
+use crate::application::ports::realtime_types::{
+    DynRealtimeSink, DynRealtimeStream, RealtimeProtocol, RealtimeSession,
+};
 use crate::application::ports::storage_port::StoragePort;
 use crate::application::ports::tagging_repository::TaggingRepository;
+use crate::application::ports::user_repository::UserRepository;
 use crate::application::services::realtime::awareness::{AwarenessService, encode_awareness_state};
 use crate::application::services::realtime::doc_hydration::{
     DocHydrationService, HydrationOptions,
 };
-use crate::application::services::realtime::snapshot::{SnapshotPersistOptions, SnapshotService};
+use crate::application::services::realtime::doc_lock::{decode_lock_frame, encode_lock_frame};
+use crate::application::services::realtime::presence::PresenceDirectory;
+use crate::application::services::realtime::reconcile::SnapshotReconciler;
+use crate::application::services::realtime::snapshot::{
+    self, RetentionPolicy, SnapshotPersistOptions, SnapshotService,
+};
 use crate::bootstrap::config::Config;
 use crate::infrastructure::db::PgPool;
+use crate::infrastructure::db::repositories::document_retention_repository_sqlx::SqlxDocumentRetentionRepository;
 use crate::infrastructure::db::repositories::linkgraph_repository_sqlx::SqlxLinkGraphRepository;
+use crate::infrastructure::db::repositories::mention_repository_sqlx::SqlxMentionRepository;
 use crate::infrastructure::db::repositories::tagging_repository_sqlx::SqlxTaggingRepository;
+use crate::infrastructure::db::repositories::user_repository_sqlx::SqlxUserRepository;
+use crate::infrastructure::realtime::frames::{
+    FrameLimits, FrameRejection, UpdateDedup, initial_sync_frames,
+};
 use crate::infrastructure::realtime::{SqlxDocPersistenceAdapter, SqlxDocStateReader};
 
-use super::cluster_bus::{RedisClusterBus, StreamItem};
+use super::cluster_bus::{RedisClusterBus, StreamItem, stream_id_time};
 
 pub struct RedisRealtimeEngine {
     bus: Arc<RedisClusterBus>,
@@ -42,7 +60,13 @@ pub struct RedisRealtimeEngine {
     snapshot_service: Arc<SnapshotService>,
     task_debounce: Duration,
     awareness_ttl: Duration,
+    awareness_sweep_interval: Duration,
+    frame_limits: FrameLimits,
+    update_dedup: UpdateDedup,
+    initial_sync_chunk_bytes: usize,
+    presence: Option<PresenceDirectory>,
     _worker: Option<JoinHandle<()>>,
+    _reconciler: Option<JoinHandle<()>>,
 }
 
 impl RedisRealtimeEngine {
@@ -50,6 +74,7 @@ impl RedisRealtimeEngine {
         cfg: &Config,
         pool: PgPool,
         storage: Arc<dyn StoragePort>,
+        notifier: Arc<dyn Notifier>,
     ) -> anyhow::Result<Self> {
         let redis_url = cfg
             .redis_url
@@ -65,6 +90,7 @@ impl RedisRealtimeEngine {
         let doc_state_reader: Arc<dyn DocStateReader> =
             Arc::new(SqlxDocStateReader::new(pool.clone()));
         let backlog_reader: Arc<dyn RealtimeBacklogReader> = bus.clone();
+        let reconciler = spawn_reconcile_sweep(cfg, bus.clone(), doc_state_reader.clone());
         let hydration_service = Arc::new(DocHydrationService::new(
             doc_state_reader.clone(),
             backlog_reader,
@@ -77,13 +103,24 @@ impl RedisRealtimeEngine {
             Arc::new(SqlxLinkGraphRepository::new(pool.clone()));
         let tagging_repo: Arc<dyn TaggingRepository> =
             Arc::new(SqlxTaggingRepository::new(pool.clone()));
-        let snapshot_service = Arc::new(SnapshotService::new(
-            doc_state_reader,
-            doc_persistence,
-            storage.clone(),
-            linkgraph_repo,
-            tagging_repo,
-        ));
+        let retention_repo: Arc<dyn DocumentRetentionRepository> =
+            Arc::new(SqlxDocumentRetentionRepository::new(pool.clone()));
+        let user_repo: Arc<dyn UserRepository> = Arc::new(SqlxUserRepository::new(pool.clone()));
+        let mention_repo: Arc<dyn MentionRepository> =
+            Arc::new(SqlxMentionRepository::new(pool.clone()));
+        let snapshot_service = Arc::new(
+            SnapshotService::new(
+                doc_state_reader,
+                doc_persistence,
+                storage.clone(),
+                linkgraph_repo,
+                tagging_repo,
+                retention_repo,
+            )
+            .with_title_from_content(cfg.derive_title_from_content)
+            .with_mentions(user_repo, mention_repo)
+            .with_notifier(notifier),
+        );
 
         let trim_lifetime = if cfg.redis_min_message_lifetime_ms > 0 {
             Some(Duration::from_millis(cfg.redis_min_message_lifetime_ms))
@@ -104,47 +141,75 @@ impl RedisRealtimeEngine {
             hydration_service,
             snapshot_service,
             task_debounce: Duration::from_millis(cfg.redis_task_debounce_ms),
-            awareness_ttl: Duration::from_millis(cfg.redis_awareness_ttl_ms),
+            awareness_ttl: Duration::from_millis(cfg.realtime_awareness_ttl_ms),
+            awareness_sweep_interval: Duration::from_millis(
+                cfg.realtime_awareness_sweep_interval_ms,
+            ),
+            frame_limits: FrameLimits::from_config(cfg),
+            update_dedup: UpdateDedup::new(cfg.realtime_update_dedup_window),
+            initial_sync_chunk_bytes: cfg.realtime_initial_sync_chunk_bytes,
+            presence: None,
             _worker: worker,
+            _reconciler: reconciler,
         })
     }
 
-    async fn send_initial_sync(&self, doc: &Doc, sink: &DynRealtimeSink) -> anyhow::Result<()> {
+    pub fn with_presence(mut self, presence: PresenceDirectory) -> Self {
+        self.presence = Some(presence);
+        self
+    }
+
+    async fn send_initial_sync(
+        &self,
+        doc: &Doc,
+        sink: &DynRealtimeSink,
+        protocol: RealtimeProtocol,
+    ) -> anyhow::Result<()> {
         let bin = {
             let txn = doc.transact();
             txn.encode_state_as_update_v1(&StateVector::default())
         };
-        let mut enc = EncoderV1::new();
-        enc.write_var(MSG_SYNC);
-        enc.write_var(MSG_SYNC_UPDATE);
-        enc.write_buf(&bin);
-        let frame = enc.to_vec();
+        let frames = initial_sync_frames(&bin, protocol, self.initial_sync_chunk_bytes);
 
         let mut guard = sink.lock().await;
-        guard
-            .send(frame)
-            .await
-            .map_err(|e| anyhow!("initial_sync_send_failed: {e}"))?;
+        for frame in frames {
+            guard
+                .send(frame)
+                .await
+                .map_err(|e| anyhow!("initial_sync_send_failed: {e}"))?;
+        }
         Ok(())
     }
 
     async fn flush_awareness_backlog(
         &self,
         sink: &DynRealtimeSink,
-        frames: &[Vec<u8>],
+        frames: &[StreamFrame],
         doc_id: &str,
         awareness_manager: &AwarenessService,
     ) -> anyhow::Result<()> {
-        for payload in frames {
-            awareness_manager.apply_remote_frame(payload).await?;
+        let now = chrono::Utc::now();
+        let mut skipped = 0usize;
+        for frame in frames {
+            let age = stream_id_time(&frame.id)
+                .and_then(|at| (now - at).to_std().ok())
+                .unwrap_or_default();
+            if !awareness_manager
+                .apply_backlog_frame(&frame.payload, age)
+                .await?
+            {
+                skipped += 1;
+                continue;
+            }
             let mut guard = sink.lock().await;
-            if let Err(e) = guard.send(payload.clone()).await {
+            if let Err(e) = guard.send(frame.payload.clone()).await {
                 return Err(anyhow!("initial_awareness_send_failed: {e}"));
             }
         }
         tracing::debug!(
             document_id = doc_id,
-            count = frames.len(),
+            count = frames.len() - skipped,
+            skipped,
             "redis_cluster_awareness_prefill"
         );
         Ok(())
@@ -156,20 +221,24 @@ impl RedisRealtimeEngine {
         doc_id: String,
         channel: &'static str,
         awareness_manager: Option<AwarenessService>,
+        lock: Option<Arc<AtomicBool>>,
     ) -> JoinHandle<()> {
         tokio::spawn(async move {
             while let Some(item) = stream.next().await {
                 match item {
                     Ok((_id, frame)) => {
-                        if let Some(manager) = &awareness_manager {
-                            if let Err(e) = manager.apply_remote_frame(&frame).await {
-                                tracing::debug!(
-                                    document_id = %doc_id,
-                                    channel,
-                                    error = ?e,
-                                    "redis_cluster_awareness_apply_failed"
-                                );
-                            }
+                        if let (Some(lock), Some(locked)) = (&lock, decode_lock_frame(&frame)) {
+                            lock.store(locked, Ordering::SeqCst);
+                        }
+                        if let Some(manager) = &awareness_manager
+                            && let Err(e) = manager.apply_remote_frame(&frame).await
+                        {
+                            tracing::debug!(
+                                document_id = %doc_id,
+                                channel,
+                                error = ?e,
+                                "redis_cluster_awareness_apply_failed"
+                            );
                         }
                         let mut guard = sink.lock().await;
                         if let Err(e) = guard.send(frame).await {
@@ -193,7 +262,7 @@ impl RealtimeEngineTrait for RedisRealtimeEngine {
         doc_id: &str,
         sink: DynRealtimeSink,
         mut stream: DynRealtimeStream,
-        can_edit: bool,
+        session: RealtimeSession,
     ) -> anyhow::Result<()> {
         let doc_uuid = Uuid::parse_str(doc_id)?;
         let hydrated = self
@@ -201,18 +270,25 @@ impl RealtimeEngineTrait for RedisRealtimeEngine {
             .hydrate(&doc_uuid, HydrationOptions::default())
             .await?;
         let awareness_publisher: Arc<dyn AwarenessPublisher> = self.bus.clone();
-        let awareness_service = AwarenessService::new(
+        let mut awareness_service = AwarenessService::new(
             hydrated.doc.clone(),
             self.awareness_ttl,
             awareness_publisher,
             doc_id.to_string(),
-        );
+        )
+        .with_sweep_interval(self.awareness_sweep_interval);
+        if let Some(presence) = &self.presence {
+            awareness_service = awareness_service.with_presence(presence.clone(), session.identity);
+        }
         let ttl_handle = awareness_service.spawn_ttl_task();
+        // Follows lock frames on the awareness stream, published by `set_locked` on any node.
+        let locked = Arc::new(AtomicBool::new(session.locked));
         let mut updates_handle: Option<JoinHandle<()>> = None;
         let mut awareness_handle: Option<JoinHandle<()>> = None;
 
         let result: anyhow::Result<()> = async {
-            self.send_initial_sync(&hydrated.doc, &sink).await?;
+            self.send_initial_sync(&hydrated.doc, &sink, session.protocol)
+                .await?;
             self.flush_awareness_backlog(
                 &sink,
                 &hydrated.awareness_frames,
@@ -224,6 +300,10 @@ impl RealtimeEngineTrait for RedisRealtimeEngine {
                 let mut guard = sink.lock().await;
                 let _ = guard.send(frame).await;
             }
+            if session.locked {
+                let mut guard = sink.lock().await;
+                let _ = guard.send(encode_lock_frame(true)).await;
+            }
 
             let updates_stream = self
                 .bus
@@ -240,6 +320,7 @@ impl RealtimeEngineTrait for RedisRealtimeEngine {
                 doc_id.to_string(),
                 "updates",
                 None,
+                None,
             ));
             awareness_handle = Some(Self::spawn_forward_task(
                 awareness_stream,
@@ -247,21 +328,34 @@ impl RealtimeEngineTrait for RedisRealtimeEngine {
                 doc_id.to_string(),
                 "awareness",
                 Some(awareness_service.clone()),
+                Some(locked.clone()),
             ));
 
             while let Some(frame) = stream.next().await {
                 match frame {
-                    Ok(bytes) => match analyse_frame(&bytes) {
+                    Ok(bytes) => match self.frame_limits.admit(&bytes) {
                         Ok(summary) => {
                             if summary.has_update {
-                                if !can_edit {
+                                if !session.can_edit {
                                     tracing::warn!(
                                         document_id = %doc_id,
                                         "ignored_update_from_readonly_client"
                                     );
+                                } else if locked.load(Ordering::SeqCst) {
+                                    tracing::debug!(
+                                        document_id = %doc_id,
+                                        "ignored_update_on_locked_document"
+                                    );
+                                } else if !self.update_dedup.admit(doc_id, &bytes) {
+                                    tracing::debug!(
+                                        document_id = %doc_id,
+                                        "redis_cluster_duplicate_update_dropped"
+                                    );
                                 } else if let Err(e) =
                                     self.bus.publish_update(doc_id, bytes.clone()).await
                                 {
+                                    // Let the client's retry of this frame through.
+                                    self.update_dedup.forget(doc_id, &bytes);
                                     tracing::warn!(
                                         document_id = %doc_id,
                                         error = ?e,
@@ -289,13 +383,24 @@ impl RealtimeEngineTrait for RedisRealtimeEngine {
                                 );
                             }
                         }
-                        Err(e) => {
+                        Err(FrameRejection::Decode(e)) => {
                             tracing::warn!(
                                 document_id = %doc_id,
                                 error = ?e,
                                 "redis_cluster_frame_decode_failed"
                             );
                         }
+                        Err(rejection) => {
+                            tracing::warn!(
+                                document_id = %doc_id,
+                                reason = %rejection,
+                                close = self.frame_limits.close_on_violation,
+                                "redis_cluster_frame_rejected"
+                            );
+                            if self.frame_limits.close_on_violation {
+                                break;
+                            }
+                        }
                     },
                     Err(e) => {
                         tracing::debug!(
@@ -326,6 +431,20 @@ impl RealtimeEngineTrait for RedisRealtimeEngine {
         result
     }
 
+    async fn set_locked(&self, doc_id: &str, locked: bool) -> anyhow::Result<()> {
+        self.bus
+            .publish_awareness(doc_id, encode_lock_frame(locked))
+            .await?;
+        Ok(())
+    }
+
+    async fn presence(&self, doc_id: &str) -> anyhow::Result<Vec<PresenceEntry>> {
+        match &self.presence {
+            Some(directory) => directory.presence(doc_id).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
     async fn get_content(&self, doc_id: &str) -> anyhow::Result<Option<String>> {
         let uuid = Uuid::parse_str(doc_id)?;
         let hydrated = self
@@ -358,30 +477,40 @@ impl RealtimeEngineTrait for RedisRealtimeEngine {
             .await?;
         Ok(())
     }
-}
 
-fn analyse_frame(frame: &[u8]) -> anyhow::Result<FrameSummary> {
-    let mut decoder = DecoderV1::new(Cursor::new(frame));
-    let mut reader = MessageReader::new(&mut decoder);
-    let mut summary = FrameSummary::default();
-    while let Some(message) = reader.next() {
-        match message? {
-            Message::Sync(SyncMessage::Update(_)) | Message::Sync(SyncMessage::SyncStep2(_)) => {
-                summary.has_update = true;
-            }
-            Message::Awareness(_) => {
-                summary.has_awareness = true;
-            }
-            _ => {}
-        }
+    async fn replace_content(&self, doc_id: &str, content: &str) -> anyhow::Result<()> {
+        let uuid = Uuid::parse_str(doc_id)?;
+        let hydrated = self
+            .hydration_service
+            .hydrate(&uuid, HydrationOptions::default())
+            .await?;
+        let update = snapshot::replace_content(&hydrated.doc, content);
+        let frame = Message::Sync(SyncMessage::Update(update)).encode_v1();
+        self.bus.publish_update(doc_id, frame).await?;
+        Ok(())
     }
-    Ok(summary)
-}
 
-#[derive(Default)]
-struct FrameSummary {
-    has_update: bool,
-    has_awareness: bool,
+    async fn replace_content_if(
+        &self,
+        doc_id: &str,
+        expected_version: &str,
+        content: &str,
+    ) -> anyhow::Result<ContentWrite> {
+        // Checked against the latest persisted and streamed state; edits still in flight on
+        // other nodes can land after the check.
+        let uuid = Uuid::parse_str(doc_id)?;
+        let hydrated = self
+            .hydration_service
+            .hydrate(&uuid, HydrationOptions::default())
+            .await?;
+        let update = match snapshot::replace_content_if(&hydrated.doc, expected_version, content) {
+            Ok(update) => update,
+            Err(current) => return Ok(ContentWrite::Conflict { current }),
+        };
+        let frame = Message::Sync(SyncMessage::Update(update)).encode_v1();
+        self.bus.publish_update(doc_id, frame).await?;
+        Ok(ContentWrite::Applied)
+    }
 }
 
 fn spawn_persistence_worker(
@@ -394,6 +523,10 @@ fn spawn_persistence_worker(
     if !cfg.cluster_mode {
         return None;
     }
+    let retention_defaults = RetentionPolicy {
+        keep_versions: cfg.snapshot_keep_versions,
+        updates_keep_window: cfg.updates_keep_window,
+    };
 
     Some(tokio::spawn(async move {
         tracing::info!("redis_persistence_worker_started");
@@ -424,12 +557,18 @@ fn spawn_persistence_worker(
                                     "redis_worker_markdown_failed"
                                 );
                             }
+                            let policy = snapshot_service
+                                .resolve_retention(&[doc_uuid], retention_defaults)
+                                .await
+                                .remove(&doc_uuid)
+                                .unwrap_or(retention_defaults);
                             if let Err(e) = snapshot_service
                                 .persist_snapshot(
                                     &doc_uuid,
                                     &hydrated.doc,
                                     SnapshotPersistOptions {
                                         clear_updates: true,
+                                        prune_snapshots: Some(policy.keep_versions),
                                         ..Default::default()
                                     },
                                 )
@@ -505,3 +644,34 @@ fn spawn_persistence_worker(
         tracing::info!("redis_persistence_worker_stopped");
     }))
 }
+
+/// Periodically enqueues tasks for documents whose updates were never snapshotted, e.g. because
+/// edits stopped before the worker picked up their last task.
+fn spawn_reconcile_sweep(
+    cfg: &Config,
+    bus: Arc<RedisClusterBus>,
+    state_reader: Arc<dyn DocStateReader>,
+) -> Option<JoinHandle<()>> {
+    if !cfg.cluster_mode || cfg.redis_reconcile_interval_secs == 0 {
+        return None;
+    }
+    let interval = Duration::from_secs(cfg.redis_reconcile_interval_secs);
+    let reconciler = SnapshotReconciler::new(
+        bus,
+        state_reader,
+        chrono::Duration::seconds(cfg.redis_reconcile_idle_secs as i64),
+    );
+
+    Some(tokio::spawn(async move {
+        loop {
+            sleep(interval).await;
+            match reconciler.sweep(chrono::Utc::now()).await {
+                Ok(enqueued) if !enqueued.is_empty() => {
+                    tracing::info!(count = enqueued.len(), "redis_reconcile_enqueued");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = ?e, "redis_reconcile_sweep_failed"),
+            }
+        }
+    }))
+}
@@ -1,3 +1,4 @@
+use anyhow::Context;
 use sqlx::Row;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
@@ -20,6 +21,38 @@ pub fn sanitize_title(name: &str) -> String {
     s
 }
 
+/// Name prefix of the objects written by storage self-tests.
+pub const PROBE_PREFIX: &str = ".refmd-probe-";
+pub const PROBE_CONTENT: &[u8] = b"refmd storage probe";
+
+/// Checks that `root` exists (creating it if needed) and that a file can be written there,
+/// read back and removed.
+pub async fn probe_directory(root: &Path) -> anyhow::Result<()> {
+    tokio::fs::create_dir_all(root)
+        .await
+        .with_context(|| format!("cannot create uploads directory {}", root.display()))?;
+    let probe = root.join(format!("{}{}", PROBE_PREFIX, Uuid::new_v4()));
+    tokio::fs::write(&probe, PROBE_CONTENT)
+        .await
+        .with_context(|| format!("uploads directory {} is not writable", root.display()))?;
+    let read = tokio::fs::read(&probe).await;
+    tokio::fs::remove_file(&probe).await.with_context(|| {
+        format!(
+            "cannot delete files in uploads directory {}",
+            root.display()
+        )
+    })?;
+    let read =
+        read.with_context(|| format!("cannot read files in uploads directory {}", root.display()))?;
+    if read != PROBE_CONTENT {
+        anyhow::bail!(
+            "uploads directory {} returned different bytes than were written",
+            root.display()
+        );
+    }
+    Ok(())
+}
+
 pub async fn build_doc_dir(
     pool: &PgPool,
     uploads_root: &Path,
@@ -165,7 +198,7 @@ pub async fn move_doc_paths(
 
     // Update documents.path
     let new_rel = relative_from_uploads(uploads_root, &new_full);
-    let _ = sqlx::query("UPDATE documents SET path = $2, updated_at = now() WHERE id = $1")
+    let _ = sqlx::query("UPDATE documents SET path = $2 WHERE id = $1")
         .bind(doc_id)
         .bind(&new_rel)
         .execute(pool)
@@ -226,7 +259,7 @@ pub async fn delete_doc_physical(
     }
 
     // Delete the document file itself
-    if let Some(rel) = row.try_get::<String, _>("path").ok() {
+    if let Ok(rel) = row.try_get::<String, _>("path") {
         let full = uploads_root.join(&rel);
         let _ = tokio::fs::remove_file(&full).await;
     }
@@ -260,3 +293,49 @@ pub async fn delete_folder_physical(
     }
     Ok(ids.len())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn writable_directory_passes_the_probe() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("uploads");
+        probe_directory(&root).await.unwrap();
+        // The probe leaves nothing behind.
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn read_only_directory_fails_the_probe_clearly() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("uploads");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::set_permissions(&root, std::fs::Permissions::from_mode(0o555)).unwrap();
+        // Permission bits do not stop root; there is nothing to check then.
+        if std::fs::write(root.join("canary"), b"").is_ok() {
+            return;
+        }
+
+        let err = probe_directory(&root).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("uploads directory {} is not writable", root.display())
+        );
+        std::fs::set_permissions(&root, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[tokio::test]
+    async fn uploads_root_that_is_a_file_fails_the_probe() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("uploads");
+        std::fs::write(&root, b"not a directory").unwrap();
+
+        let err = probe_directory(&root).await.unwrap_err();
+        assert!(err.to_string().contains("uploads directory"), "{err:#}");
+    }
+}
@@ -25,7 +25,7 @@ impl GitignorePort for FsGitignorePort {
                 existing.lines().map(|s| s.to_string()).collect();
             let mut changed = false;
             for d in &defaults {
-                if !lines.contains(&d.to_string()) {
+                if !lines.contains(*d) {
                     lines.insert(d.to_string());
                     changed = true;
                 }
@@ -91,6 +91,32 @@ impl GitignorePort for FsGitignorePort {
         Ok(0)
     }
 
+    async fn remove_gitignore_patterns(
+        &self,
+        dir: &str,
+        patterns: &[String],
+    ) -> anyhow::Result<usize> {
+        let path = std::path::Path::new(dir).join(".gitignore");
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(0);
+        }
+        let existing = tokio::fs::read_to_string(&path).await?;
+        let mut removed = 0;
+        let mut buf = String::new();
+        for line in existing.lines() {
+            if patterns.iter().any(|p| p.trim() == line.trim()) {
+                removed += 1;
+                continue;
+            }
+            buf.push_str(line);
+            buf.push('\n');
+        }
+        if removed > 0 {
+            tokio::fs::write(&path, buf).await?;
+        }
+        Ok(removed)
+    }
+
     async fn read_gitignore_patterns(&self, dir: &str) -> anyhow::Result<Vec<String>> {
         let path = std::path::Path::new(dir).join(".gitignore");
         let content = if tokio::fs::try_exists(&path).await.unwrap_or(false) {
@@ -106,3 +132,29 @@ impl GitignorePort for FsGitignorePort {
         Ok(patterns)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn removes_only_matching_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path().to_str().unwrap();
+        let port = FsGitignorePort;
+        port.ensure_gitignore(dir).await.unwrap();
+        port.upsert_gitignore_patterns(dir, &["drafts/".into(), "notes/todo.md".into()])
+            .await
+            .unwrap();
+
+        let removed = port
+            .remove_gitignore_patterns(dir, &["drafts/".into(), "missing.md".into()])
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        let patterns = port.read_gitignore_patterns(dir).await.unwrap();
+        assert!(patterns.contains(&"notes/todo.md".to_string()));
+        assert!(!patterns.contains(&"drafts/".to_string()));
+        assert!(patterns.contains(&".DS_Store".to_string()));
+    }
+}
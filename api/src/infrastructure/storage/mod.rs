@@ -1,5 +1,6 @@
 mod core;
 mod gitignore_port_impl;
+mod s3_client;
 mod s3_port_impl;
 mod storage_port_impl;
 pub use core::*;
@@ -11,5 +12,6 @@ pub mod gitignore {
     pub use super::gitignore_port_impl::*;
 }
 pub mod s3 {
+    pub use super::s3_client::*;
     pub use super::s3_port_impl::*;
 }
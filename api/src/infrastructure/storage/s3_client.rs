@@ -0,0 +1,207 @@
+//! S3 client setup shared by attachment storage and git storage, so custom endpoints
+//! (MinIO, R2, ...), path-style addressing and server-side encryption apply to both.
+
+use aws_config::BehaviorVersion;
+use aws_sdk_s3::Client;
+use aws_sdk_s3::config::{Builder, Credentials, Region};
+use aws_sdk_s3::operation::copy_object::builders::CopyObjectFluentBuilder;
+use aws_sdk_s3::operation::put_object::builders::PutObjectFluentBuilder;
+use aws_sdk_s3::types::ServerSideEncryption;
+
+use crate::bootstrap::config::{Config, S3Encryption};
+
+#[derive(Debug, Clone, Default)]
+pub struct S3Settings {
+    pub region: Option<String>,
+    pub endpoint: Option<String>,
+    pub path_style: bool,
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub encryption: Option<S3Encryption>,
+}
+
+impl S3Settings {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            region: cfg.s3_region.clone(),
+            endpoint: cfg.s3_endpoint.clone(),
+            path_style: cfg.s3_use_path_style,
+            access_key: cfg.s3_access_key.clone(),
+            secret_key: cfg.s3_secret_key.clone(),
+            encryption: cfg.s3_encryption.clone(),
+        }
+    }
+
+    /// A client for these settings on top of the default AWS configuration chain.
+    pub async fn client(&self, credentials_name: &'static str) -> Client {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(region) = &self.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        let shared = loader.load().await;
+        Client::from_conf(
+            self.configure(Builder::from(&shared), credentials_name)
+                .build(),
+        )
+    }
+
+    pub fn configure(&self, mut builder: Builder, credentials_name: &'static str) -> Builder {
+        if let (Some(access), Some(secret)) = (&self.access_key, &self.secret_key) {
+            let creds = Credentials::new(access, secret, None, None, credentials_name);
+            builder = builder.credentials_provider(creds);
+        }
+        if let Some(endpoint) = &self.endpoint {
+            builder = builder.endpoint_url(endpoint.clone());
+        }
+        if self.path_style {
+            builder = builder.force_path_style(true);
+        }
+        builder
+    }
+
+    /// Applies the configured server-side encryption to an upload.
+    pub fn encrypt(&self, put: PutObjectFluentBuilder) -> PutObjectFluentBuilder {
+        match &self.encryption {
+            None => put,
+            Some(S3Encryption::S3) => put.server_side_encryption(ServerSideEncryption::Aes256),
+            Some(S3Encryption::Kms { key_id }) => put
+                .server_side_encryption(ServerSideEncryption::AwsKms)
+                .set_ssekms_key_id(key_id.clone()),
+        }
+    }
+
+    /// Copies are re-encrypted too; S3 does not carry the source's encryption over.
+    pub fn encrypt_copy(&self, copy: CopyObjectFluentBuilder) -> CopyObjectFluentBuilder {
+        match &self.encryption {
+            None => copy,
+            Some(S3Encryption::S3) => copy.server_side_encryption(ServerSideEncryption::Aes256),
+            Some(S3Encryption::Kms { key_id }) => copy
+                .server_side_encryption(ServerSideEncryption::AwsKms)
+                .set_ssekms_key_id(key_id.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use aws_sdk_s3::config::interceptors::BeforeTransmitInterceptorContextRef;
+    use aws_sdk_s3::config::{ConfigBag, Intercept, RuntimeComponents};
+    use aws_sdk_s3::error::BoxError;
+    use aws_sdk_s3::primitives::ByteStream;
+
+    use super::*;
+
+    type Captured = Option<(String, Vec<(String, String)>)>;
+
+    /// Records the outgoing request and fails it before anything is sent.
+    #[derive(Debug, Default, Clone)]
+    struct Capture(Arc<Mutex<Captured>>);
+
+    impl Intercept for Capture {
+        fn name(&self) -> &'static str {
+            "Capture"
+        }
+
+        fn read_before_transmit(
+            &self,
+            context: &BeforeTransmitInterceptorContextRef<'_>,
+            _: &RuntimeComponents,
+            _: &mut ConfigBag,
+        ) -> Result<(), BoxError> {
+            let request = context.request();
+            let headers = request
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            *self.0.lock().unwrap() = Some((request.uri().to_string(), headers));
+            Err("request captured".into())
+        }
+    }
+
+    async fn put(settings: &S3Settings) -> (String, Vec<(String, String)>) {
+        let capture = Capture::default();
+        let builder = Builder::new()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .interceptor(capture.clone());
+        let client = Client::from_conf(settings.configure(builder, "test").build());
+        let request = client
+            .put_object()
+            .bucket("refmd")
+            .key("uploads/doc.md")
+            .body(ByteStream::from_static(b"# Doc"));
+        let _ = settings.encrypt(request).send().await;
+        let captured = capture.0.lock().unwrap().take();
+        captured.expect("request was not captured")
+    }
+
+    fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn settings() -> S3Settings {
+        S3Settings {
+            endpoint: Some("http://minio.internal:9000".into()),
+            access_key: Some("minio".into()),
+            secret_key: Some("minio-secret".into()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn uploads_carry_the_sse_header() {
+        let (_, headers) = put(&S3Settings {
+            encryption: Some(S3Encryption::S3),
+            ..settings()
+        })
+        .await;
+        assert_eq!(
+            header(&headers, "x-amz-server-side-encryption"),
+            Some("AES256")
+        );
+
+        let (_, headers) = put(&S3Settings {
+            encryption: Some(S3Encryption::Kms {
+                key_id: Some("alias/refmd".into()),
+            }),
+            ..settings()
+        })
+        .await;
+        assert_eq!(
+            header(&headers, "x-amz-server-side-encryption"),
+            Some("aws:kms")
+        );
+        assert_eq!(
+            header(&headers, "x-amz-server-side-encryption-aws-kms-key-id"),
+            Some("alias/refmd")
+        );
+
+        let (_, headers) = put(&settings()).await;
+        assert_eq!(header(&headers, "x-amz-server-side-encryption"), None);
+    }
+
+    #[tokio::test]
+    async fn path_style_addressing_puts_the_bucket_in_the_path() {
+        let (uri, _) = put(&S3Settings {
+            path_style: true,
+            ..settings()
+        })
+        .await;
+        assert!(
+            uri.starts_with("http://minio.internal:9000/refmd/uploads/doc.md"),
+            "{uri}"
+        );
+
+        let (uri, _) = put(&settings()).await;
+        assert!(
+            uri.starts_with("http://refmd.minio.internal:9000/uploads/doc.md"),
+            "{uri}"
+        );
+    }
+}
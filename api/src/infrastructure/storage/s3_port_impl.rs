@@ -2,8 +2,6 @@ use std::path::{Component, Path, PathBuf};
 
 use anyhow::{Context, anyhow};
 use async_trait::async_trait;
-use aws_config::BehaviorVersion;
-use aws_sdk_s3::config::{Credentials, Region};
 use aws_sdk_s3::operation::create_bucket::CreateBucketError;
 use aws_sdk_s3::operation::head_bucket::HeadBucketError;
 use aws_sdk_s3::primitives::ByteStream;
@@ -12,13 +10,15 @@ use sha2::{Digest, Sha256};
 use tokio::io::AsyncReadExt;
 use uuid::Uuid;
 
-use crate::application::ports::storage_port::{StoragePort, StoredAttachment};
+use crate::application::ports::storage_port::{StoragePort, StoredAttachment, StoredObjectMeta};
 use crate::bootstrap::config::Config;
 use crate::infrastructure::db::PgPool;
+use crate::infrastructure::storage::s3::S3Settings;
 
 pub struct S3StoragePort {
     pool: PgPool,
     client: Client,
+    settings: S3Settings,
     bucket: String,
     root: PathBuf,
     root_prefix: String,
@@ -31,36 +31,8 @@ impl S3StoragePort {
             .clone()
             .context("S3 bucket must be configured when using S3 storage backend")?;
 
-        let mut loader = aws_config::defaults(BehaviorVersion::latest());
-
-        if let Some(region) = &cfg.s3_region {
-            loader = loader.region(Region::new(region.clone()));
-        }
-
-        let shared_config = loader.load().await;
-
-        let mut builder = aws_sdk_s3::config::Builder::from(&shared_config);
-
-        if let (Some(access), Some(secret)) = (&cfg.s3_access_key, &cfg.s3_secret_key) {
-            let creds = Credentials::new(
-                access.clone(),
-                secret.clone(),
-                None,
-                None,
-                "refmd-s3-static",
-            );
-            builder = builder.credentials_provider(creds);
-        }
-
-        if let Some(endpoint) = &cfg.s3_endpoint {
-            builder = builder.endpoint_url(endpoint.clone());
-        }
-
-        if cfg.s3_use_path_style {
-            builder = builder.force_path_style(true);
-        }
-
-        let client = Client::from_conf(builder.build());
+        let settings = S3Settings::from_config(cfg);
+        let client = settings.client("refmd-s3-static").await;
 
         let root = PathBuf::from(&cfg.storage_root);
         let root_prefix = normalize_prefix(&root);
@@ -70,6 +42,7 @@ impl S3StoragePort {
         Ok(Self {
             pool,
             client,
+            settings,
             bucket,
             root,
             root_prefix,
@@ -125,11 +98,14 @@ impl S3StoragePort {
             return Ok(());
         }
         let copy_source = format!("{}/{}", &self.bucket, src_key);
-        self.client
+        let copy = self
+            .client
             .copy_object()
             .bucket(&self.bucket)
             .key(dst_key)
-            .copy_source(urlencoding::encode(&copy_source))
+            .copy_source(urlencoding::encode(&copy_source));
+        self.settings
+            .encrypt_copy(copy)
             .send()
             .await
             .with_context(|| format!("failed to copy {src_key} to {dst_key}"))?;
@@ -177,14 +153,14 @@ impl S3StoragePort {
         let new_rel = crate::infrastructure::storage::relative_from_uploads(&self.root, &new_full)
             .replace('\\', "/");
 
-        if let Some(old_rel) = old_rel.clone() {
-            if old_rel != new_rel {
-                let src_key = self.relative_to_key(&old_rel);
-                let dst_key = self.relative_to_key(&new_rel);
-                if self.object_exists(&src_key).await? {
-                    self.copy_object(&src_key, &dst_key).await?;
-                    self.delete_object(&src_key).await?;
-                }
+        if let Some(old_rel) = old_rel.clone()
+            && old_rel != new_rel
+        {
+            let src_key = self.relative_to_key(&old_rel);
+            let dst_key = self.relative_to_key(&new_rel);
+            if self.object_exists(&src_key).await? {
+                self.copy_object(&src_key, &dst_key).await?;
+                self.delete_object(&src_key).await?;
             }
         }
 
@@ -226,7 +202,7 @@ impl S3StoragePort {
             }
         }
 
-        sqlx::query("UPDATE documents SET path = $2, updated_at = now() WHERE id = $1")
+        sqlx::query("UPDATE documents SET path = $2 WHERE id = $1")
             .bind(doc_id)
             .bind(&new_rel)
             .execute(&self.pool)
@@ -275,7 +251,7 @@ impl StoragePort for S3StoragePort {
         if dtype == "folder" {
             return Ok(());
         }
-        if let Some(path) = row.try_get::<String, _>("path").ok() {
+        if let Ok(path) = row.try_get::<String, _>("path") {
             let key = self.relative_to_key(&path);
             let _ = self.delete_object(&key).await;
         }
@@ -381,15 +357,55 @@ impl StoragePort for S3StoragePort {
         Ok(data)
     }
 
+    async fn stat(&self, abs_path: &Path) -> anyhow::Result<StoredObjectMeta> {
+        let key = self.key_from_path(abs_path);
+        let head = self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .context("failed to head object")?;
+        Ok(StoredObjectMeta {
+            size: head.content_length().unwrap_or(0).max(0) as u64,
+            etag: head.e_tag().map(|t| t.to_string()),
+            last_modified: head
+                .last_modified()
+                .and_then(|t| chrono::DateTime::from_timestamp(t.secs(), 0)),
+        })
+    }
+
+    async fn read_range(&self, abs_path: &Path, start: u64, end: u64) -> anyhow::Result<Vec<u8>> {
+        anyhow::ensure!(end >= start, "invalid range");
+        let key = self.key_from_path(abs_path);
+        let object = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .range(format!("bytes={}-{}", start, end))
+            .send()
+            .await
+            .context("failed to get object range")?;
+        let mut reader = object.body.into_async_read();
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await?;
+        Ok(data)
+    }
+
     async fn write_bytes(&self, abs_path: &Path, data: &[u8]) -> anyhow::Result<()> {
         let relative = crate::infrastructure::storage::relative_from_uploads(&self.root, abs_path)
             .replace('\\', "/");
         let key = self.relative_to_key(&relative);
-        self.client
+        let put = self
+            .client
             .put_object()
             .bucket(&self.bucket)
             .key(&key)
-            .body(ByteStream::from(data.to_vec()))
+            .body(ByteStream::from(data.to_vec()));
+        self.settings
+            .encrypt(put)
             .send()
             .await
             .with_context(|| format!("failed to upload object {key}"))?;
@@ -468,11 +484,14 @@ impl StoragePort for S3StoragePort {
             .map(|b| format!("{b:02x}"))
             .collect::<String>();
 
-        self.client
+        let put = self
+            .client
             .put_object()
             .bucket(&self.bucket)
             .key(&key)
-            .body(ByteStream::from(bytes.to_vec()))
+            .body(ByteStream::from(bytes.to_vec()));
+        self.settings
+            .encrypt(put)
             .send()
             .await
             .with_context(|| format!("failed to upload object {key}"))?;
@@ -484,6 +503,44 @@ impl StoragePort for S3StoragePort {
             content_hash,
         })
     }
+
+    async fn self_test(&self) -> anyhow::Result<()> {
+        use crate::infrastructure::storage::{PROBE_CONTENT, PROBE_PREFIX};
+
+        let key = self.relative_to_key(&format!("{}{}", PROBE_PREFIX, Uuid::new_v4()));
+        let put = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .body(ByteStream::from_static(PROBE_CONTENT));
+        self.settings
+            .encrypt(put)
+            .send()
+            .await
+            .with_context(|| format!("cannot write to S3 bucket {} (key {key})", self.bucket))?;
+        let read = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .send()
+            .await;
+        self.delete_object(&key)
+            .await
+            .with_context(|| format!("cannot delete from S3 bucket {}", self.bucket))?;
+        let object = read
+            .with_context(|| format!("cannot read from S3 bucket {} (key {key})", self.bucket))?;
+        let mut data = Vec::new();
+        object.body.into_async_read().read_to_end(&mut data).await?;
+        if data != PROBE_CONTENT {
+            anyhow::bail!(
+                "S3 bucket {} returned different bytes than were written",
+                self.bucket
+            );
+        }
+        Ok(())
+    }
 }
 
 async fn ensure_bucket(client: &Client, bucket: &str) -> anyhow::Result<()> {
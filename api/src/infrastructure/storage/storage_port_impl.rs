@@ -2,7 +2,7 @@ use std::fmt::Write;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-use crate::application::ports::storage_port::{StoragePort, StoredAttachment};
+use crate::application::ports::storage_port::{StoragePort, StoredAttachment, StoredObjectMeta};
 use sha2::{Digest, Sha256};
 
 pub struct FsStoragePort {
@@ -127,6 +127,33 @@ impl StoragePort for FsStoragePort {
         Ok(data)
     }
 
+    async fn stat(&self, abs_path: &Path) -> anyhow::Result<StoredObjectMeta> {
+        let meta = tokio::fs::metadata(abs_path).await?;
+        if !meta.is_file() {
+            anyhow::bail!("not a file");
+        }
+        let modified = meta.modified().ok();
+        let etag = modified
+            .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| format!("\"{:x}-{:x}\"", meta.len(), d.as_nanos()));
+        Ok(StoredObjectMeta {
+            size: meta.len(),
+            etag,
+            last_modified: modified.map(chrono::DateTime::<chrono::Utc>::from),
+        })
+    }
+
+    async fn read_range(&self, abs_path: &Path, start: u64, end: u64) -> anyhow::Result<Vec<u8>> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        anyhow::ensure!(end >= start, "invalid range");
+        let mut file = tokio::fs::File::open(abs_path).await?;
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+        let mut data = Vec::with_capacity((end - start + 1) as usize);
+        file.take(end - start + 1).read_to_end(&mut data).await?;
+        Ok(data)
+    }
+
     async fn write_bytes(&self, abs_path: &Path, data: &[u8]) -> anyhow::Result<()> {
         if let Some(parent) = abs_path.parent() {
             tokio::fs::create_dir_all(parent).await?;
@@ -217,4 +244,8 @@ impl StoragePort for FsStoragePort {
             content_hash,
         })
     }
+
+    async fn self_test(&self) -> anyhow::Result<()> {
+        crate::infrastructure::storage::probe_directory(self.uploads_root.as_path()).await
+    }
 }
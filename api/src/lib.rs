@@ -4,9 +4,13 @@
 // - presentation: HTTP/WS handlers and routing
 // - application: cross-cutting policies and domain services
 // - domain: core models
+// - test_support: in-memory port doubles shared by unit tests
 
 pub mod application;
 pub mod bootstrap;
 pub mod domain;
 pub mod infrastructure;
 pub mod presentation;
+
+#[cfg(test)]
+pub(crate) mod test_support;
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
@@ -13,11 +14,17 @@ use tower_http::services::ServeDir;
 use tower_http::trace::TraceLayer;
 use tracing::{error, info};
 
+use api::application::ports::pdf_renderer::PdfRenderer;
 use api::application::ports::plugin_asset_store::PluginAssetStore;
 use api::application::ports::plugin_event_publisher::PluginEventPublisher;
 use api::application::ports::plugin_installation_repository::PluginInstallationRepository;
 use api::application::ports::plugin_installer::PluginInstaller;
 use api::application::ports::plugin_runtime::PluginRuntime;
+use api::application::services::plugin_scheduler::{PluginScheduler, ScheduledRun};
+use api::application::services::realtime::snapshot::RetentionPolicy;
+use api::application::use_cases::git::auto_sync::{AutoSyncPolicy, RunAutoSync};
+use api::application::use_cases::plugins::exec_action::ExecutePluginAction;
+use api::application::use_cases::shares::cleanup_expired::CleanupExpiredShares;
 use api::bootstrap::app_context::{AppContext, AppServices};
 use api::bootstrap::config::{Config, StorageBackend};
 use api::infrastructure::plugins::filesystem_store::PluginExecutionLimits;
@@ -32,14 +39,44 @@ use utoipa_swagger_ui::SwaggerUi;
             api::presentation::http::auth::logout,
             api::presentation::http::auth::me,
             api::presentation::http::tags::list_tags,
+            api::presentation::http::capabilities::get_capabilities,
             api::presentation::ws::axum_ws_entry,
             api::presentation::http::documents::list_documents,
+            api::presentation::http::documents::list_recent_documents,
+            api::presentation::http::documents::list_mentions,
+            api::presentation::http::documents::get_home_document,
+            api::presentation::http::documents::set_home_document,
+            api::presentation::http::notifications::list_notifications,
+            api::presentation::http::notifications::mark_notification_read,
             api::presentation::http::documents::create_document,
             api::presentation::http::documents::get_document,
             api::presentation::http::documents::update_document,
             api::presentation::http::documents::delete_document,
+            api::presentation::http::documents::update_document_appearance,
+            api::presentation::http::documents::get_document_capability,
+            api::presentation::http::comments::list_document_comments,
+            api::presentation::http::comments::create_document_comment,
+            api::presentation::http::comments::resolve_document_comment,
+            api::presentation::http::comments::reopen_document_comment,
+            api::presentation::http::comments::delete_document_comment,
+            api::presentation::http::documents::lock_document,
+            api::presentation::http::documents::unlock_document,
             api::presentation::http::documents::get_document_content,
+            api::presentation::http::documents::update_document_content,
+            api::presentation::http::documents::get_document_presence,
             api::presentation::http::documents::download_document,
+            api::presentation::http::documents::export_document,
+            api::presentation::http::documents::get_document_retention,
+            api::presentation::http::documents::update_document_retention,
+            api::presentation::http::documents::list_document_user_access,
+            api::presentation::http::documents::grant_document_user_access,
+            api::presentation::http::documents::revoke_document_user_access,
+            api::presentation::http::documents::list_document_versions,
+            api::presentation::http::documents::get_document_version_content,
+            api::presentation::http::documents::diff_document_versions,
+            api::presentation::http::documents::render_document,
+            api::presentation::http::documents::render_document_tree,
+            api::presentation::http::documents::restore_document_version,
             api::presentation::http::documents::search_documents,
             api::presentation::http::documents::get_backlinks,
             api::presentation::http::documents::get_outgoing_links,
@@ -47,6 +84,7 @@ use utoipa_swagger_ui::SwaggerUi;
             api::presentation::http::files::get_file,
             api::presentation::http::files::get_file_by_name,
             api::presentation::http::shares::create_share,
+            api::presentation::http::shares::create_shares_bulk,
             api::presentation::http::shares::delete_share,
             api::presentation::http::shares::list_document_shares,
             api::presentation::http::shares::validate_share_token,
@@ -58,26 +96,36 @@ use utoipa_swagger_ui::SwaggerUi;
             api::presentation::http::public::unpublish_document,
             api::presentation::http::public::get_publish_status,
             api::presentation::http::public::list_user_public_documents,
+            api::presentation::http::public::get_user_public_sitemap,
+            api::presentation::http::public::get_user_public_index,
             api::presentation::http::public::get_public_by_owner_and_id,
             api::presentation::http::public::get_public_content_by_owner_and_id,
+            api::presentation::http::public::get_public_view_stats,
             api::presentation::http::git::get_config,
             api::presentation::http::git::create_or_update_config,
             api::presentation::http::git::delete_config,
             api::presentation::http::git::get_status,
             api::presentation::http::git::get_changes,
             api::presentation::http::git::get_history,
+            api::presentation::http::git::get_blame,
+            api::presentation::http::git::get_storage_usage,
+            api::presentation::http::git::collect_garbage,
             api::presentation::http::git::get_working_diff,
             api::presentation::http::git::get_commit_diff,
             api::presentation::http::git::sync_now,
+            api::presentation::http::git::test_connection,
+            api::presentation::http::git::import_repository,
             api::presentation::http::git::init_repository,
             api::presentation::http::git::deinit_repository,
             api::presentation::http::git::ignore_document,
             api::presentation::http::git::ignore_folder,
             api::presentation::http::git::get_gitignore_patterns,
             api::presentation::http::git::add_gitignore_patterns,
+            api::presentation::http::git::remove_gitignore_patterns,
             api::presentation::http::git::check_path_ignored,
             api::presentation::http::markdown::render_markdown,
             api::presentation::http::markdown::render_markdown_many,
+            api::presentation::http::markdown::list_renderers,
             api::presentation::http::plugins::get_manifest,
             api::presentation::http::plugins::exec_action,
             api::presentation::http::plugins::list_records,
@@ -86,8 +134,20 @@ use utoipa_swagger_ui::SwaggerUi;
             api::presentation::http::plugins::delete_record,
             api::presentation::http::plugins::get_kv_value,
             api::presentation::http::plugins::put_kv_value,
+            api::presentation::http::plugins::export_data,
+            api::presentation::http::plugins::import_data,
             api::presentation::http::plugins::install_from_url,
             api::presentation::http::plugins::uninstall,
+            api::presentation::http::plugins::pin_version,
+            api::presentation::http::plugins::update_plugin,
+            api::presentation::http::plugins::list_schedules,
+            api::presentation::http::plugins::list_exec_log,
+            api::presentation::http::plugins::install_global_from_url,
+            api::presentation::http::plugins::uninstall_global,
+            api::presentation::http::plugins::list_secret_keys,
+            api::presentation::http::plugins::put_secret,
+            api::presentation::http::plugins::delete_secret,
+            api::presentation::http::plugins::set_global_enabled,
             api::presentation::http::plugins::sse_updates,
             api::presentation::http::health::health,
         ),
@@ -99,18 +159,50 @@ use utoipa_swagger_ui::SwaggerUi;
             api::presentation::http::tags::TagItem,
             api::presentation::http::documents::Document,
             api::presentation::http::documents::DocumentListResponse,
+            api::presentation::http::documents::MentionItem,
+            api::presentation::http::documents::MentionListResponse,
+            api::presentation::http::documents::HomeDocumentResponse,
+            api::presentation::http::documents::SetHomeDocumentRequest,
+            api::presentation::http::capabilities::CapabilitiesResponse,
+            api::presentation::http::capabilities::StorageBackendKind,
+            api::presentation::http::comments::CommentItem,
+            api::presentation::http::comments::CreateCommentRequest,
+            api::presentation::http::notifications::NotificationItem,
+            api::presentation::http::notifications::NotificationListResponse,
             api::presentation::http::documents::CreateDocumentRequest,
             api::presentation::http::documents::UpdateDocumentRequest,
+            api::presentation::http::documents::UpdateDocumentAppearanceRequest,
+            api::presentation::http::documents::DocumentCapability,
+            api::presentation::http::documents::DocumentCapabilityResponse,
+            api::presentation::http::documents::UpdateDocumentRetentionRequest,
+            api::presentation::http::documents::DocumentRetentionResponse,
+            api::presentation::http::documents::DocumentUserAccessItem,
+            api::presentation::http::documents::GrantDocumentAccessRequest,
+            api::presentation::http::documents::DocumentVersionItem,
+            api::presentation::http::documents::DocumentVersionContentResponse,
+            api::presentation::http::documents::RenderDocumentRequest,
+            api::presentation::http::documents::RenderTreeRequest,
+            api::presentation::http::documents::RenderTreeDocument,
+            api::presentation::http::documents::RenderTreeResponse,
+            api::presentation::http::documents::DocumentContentResponse,
+            api::presentation::http::documents::UpdateDocumentContentRequest,
+            api::presentation::http::documents::PresenceClient,
+            api::presentation::http::documents::DocumentPresenceResponse,
             api::presentation::http::documents::BacklinkInfo,
             api::presentation::http::documents::BacklinksResponse,
             api::presentation::http::documents::OutgoingLink,
             api::presentation::http::documents::OutgoingLinksResponse,
             api::presentation::http::documents::SearchResult,
             api::presentation::http::files::UploadFileResponse,
+            api::presentation::http::files::UploadRejectedResponse,
             api::presentation::http::files::UploadFileMultipart,
             api::presentation::http::shares::CreateShareRequest,
             api::presentation::http::shares::CreateShareResponse,
+            api::presentation::http::shares::BulkCreateShareRequest,
+            api::presentation::http::shares::BulkCreateShareResponse,
+            api::presentation::http::shares::BulkShareItem,
             api::presentation::http::shares::ShareItem,
+            api::presentation::http::shares::DocumentSharesResponse,
             api::presentation::http::shares::ShareDocumentResponse,
             api::presentation::http::shares::ShareBrowseTreeItem,
             api::presentation::http::shares::ShareBrowseResponse,
@@ -119,50 +211,84 @@ use utoipa_swagger_ui::SwaggerUi;
             api::presentation::http::shares::MaterializeResponse,
             api::presentation::http::public::PublishResponse,
             api::presentation::http::public::PublicDocumentSummary,
+            api::presentation::http::public::PublicDocumentIndex,
+            api::presentation::http::public::PublicViewStats,
             api::presentation::http::git::GitConfigResponse,
+            api::presentation::http::git::GitImportResponse,
             api::presentation::http::git::CreateGitConfigRequest,
             api::presentation::http::git::UpdateGitConfigRequest,
             api::presentation::http::git::GitStatus,
             api::presentation::http::git::GitSyncRequest,
             api::presentation::http::git::GitSyncResponse,
+            api::presentation::http::git::GitConnectionTestResponse,
+            api::presentation::http::git::GitSyncPreview,
+            api::presentation::http::git::GitDiffStats,
             api::presentation::http::git::GitChangeItem,
             api::presentation::http::git::GitChangesResponse,
             api::presentation::http::git::GitCommitItem,
             api::presentation::http::git::GitHistoryResponse,
+            api::presentation::http::git::GitBlameLineItem,
+            api::presentation::http::git::GitBlameResponse,
+            api::presentation::http::git::GitStorageUsageResponse,
+            api::presentation::http::git::GitGcRequest,
+            api::presentation::http::git::GitGcResponse,
             api::presentation::http::git::AddPatternsRequest,
             api::presentation::http::git::CheckIgnoredRequest,
             api::presentation::http::git::GitDiffLineType,
             api::presentation::http::git::GitDiffLine,
             api::presentation::http::git::GitDiffResult,
+            api::presentation::http::git::GitWorkingDiffResponse,
             api::presentation::http::markdown::RenderOptionsPayload,
             api::presentation::http::markdown::PlaceholderItemPayload,
             api::presentation::http::markdown::RenderResponseBody,
             api::presentation::http::markdown::RenderRequest,
             api::presentation::http::markdown::RenderManyRequest,
             api::presentation::http::markdown::RenderManyResponse,
+            api::presentation::http::markdown::RendererSpecPayload,
+            api::presentation::http::markdown::RendererSpecsResponse,
             api::presentation::http::plugins::ManifestItem,
             api::presentation::http::plugins::RecordsResponse,
             api::presentation::http::plugins::CreateRecordBody,
             api::presentation::http::plugins::UpdateRecordBody,
             api::presentation::http::plugins::KvValueResponse,
             api::presentation::http::plugins::KvValueBody,
+            api::presentation::http::plugins::PluginRecordExport,
+            api::presentation::http::plugins::PluginKvExport,
+            api::presentation::http::plugins::PluginDataExportBody,
+            api::presentation::http::plugins::PluginDataImportBody,
+            api::presentation::http::plugins::PluginDataImportResponse,
             api::presentation::http::plugins::ExecBody,
             api::presentation::http::plugins::ExecResultResponse,
             api::presentation::http::plugins::InstallFromUrlBody,
             api::presentation::http::plugins::InstallResponse,
             api::presentation::http::plugins::UninstallBody,
+            api::presentation::http::plugins::PinVersionBody,
+            api::presentation::http::plugins::UpdatePluginBody,
+            api::presentation::http::plugins::ActiveVersionResponse,
+            api::presentation::http::plugins::GlobalPluginEnabledBody,
+            api::presentation::http::plugins::SecretPath,
+            api::presentation::http::plugins::SecretValueBody,
+            api::presentation::http::plugins::SecretKeysResponse,
+            api::presentation::http::plugins::PluginScheduleResponse,
+            api::presentation::http::plugins::PluginSchedulesResponse,
+            api::presentation::http::plugins::PluginExecLogItem,
+            api::presentation::http::plugins::PluginExecLogResponse,
             api::presentation::http::health::HealthResp,
+            api::presentation::http::health::DependencyStatus,
         )),
         tags(
             (name = "Auth", description = "Authentication"),
             (name = "Documents", description = "Documents management"),
+            (name = "Comments", description = "Document comment threads"),
             (name = "Files", description = "File management"),
             (name = "Sharing", description = "Document sharing"),
             (name = "Public Documents", description = "Public pages"),
             (name = "Git", description = "Git integration"),
             (name = "Markdown", description = "Markdown rendering"),
+            (name = "Notifications", description = "User notifications"),
             (name = "Plugins", description = "Plugins management & data APIs"),
             (name = "Health", description = "System health checks"),
+            (name = "Server", description = "Server capabilities"),
         )
     )]
 struct ApiDoc;
@@ -181,6 +307,12 @@ async fn main() -> anyhow::Result<()> {
     let cfg = Config::from_env()?;
     info!(?cfg, "Starting RefMD backend");
 
+    if let Some(dir) = &cfg.highlight_assets_dir {
+        api::application::services::markdown::install_highlight_extensions(std::path::Path::new(
+            dir,
+        ));
+    }
+
     // Database
     let pool = api::infrastructure::db::connect_pool(&cfg.database_url).await?;
     api::infrastructure::db::migrate(&pool).await?;
@@ -197,14 +329,65 @@ async fn main() -> anyhow::Result<()> {
                 api::infrastructure::storage::s3::S3StoragePort::new(pool.clone(), &cfg).await?,
             ),
         };
+    if let Err(err) = storage_port.self_test().await {
+        tracing::error!(
+            error = %format!("{err:#}"),
+            backend = ?cfg.storage_backend,
+            "storage_self_test_failed"
+        );
+        if cfg.storage_self_test_strict {
+            return Err(err.context("storage self-test failed"));
+        }
+    }
+
+    let user_repo = Arc::new(
+        api::infrastructure::db::repositories::user_repository_sqlx::SqlxUserRepository::new(
+            pool.clone(),
+        ),
+    );
+    let presence = api::application::services::realtime::presence::PresenceDirectory::new(
+        user_repo.clone(),
+        Duration::from_secs(60),
+    );
+
+    let plugin_event_bus = Arc::new(
+        api::infrastructure::plugins::event_bus_pg::PgPluginEventBus::new(
+            pool.clone(),
+            "plugin_events",
+        ),
+    );
+    let notifications = Arc::new(
+        api::application::services::notifications::NotificationService::new(Arc::new(
+            api::infrastructure::db::repositories::notification_repository_sqlx::SqlxNotificationRepository::new(
+                pool.clone(),
+            ),
+        ))
+        .with_live_updates(plugin_event_bus.clone()),
+    );
 
     // Build Realtime Hub
-    let hub = api::infrastructure::realtime::Hub::new(pool.clone(), storage_port.clone());
+    let hub = api::infrastructure::realtime::Hub::new(
+        &cfg,
+        pool.clone(),
+        storage_port.clone(),
+        notifications.clone(),
+    )
+    .with_presence(presence.clone());
     let document_repo = Arc::new(
         api::infrastructure::db::repositories::document_repository_sqlx::SqlxDocumentRepository::new(
             pool.clone(),
         ),
     );
+    let linkgraph_repo = Arc::new(
+        api::infrastructure::db::repositories::linkgraph_repository_sqlx::SqlxLinkGraphRepository::new(
+            pool.clone(),
+        ),
+    );
+    let mention_repo = Arc::new(
+        api::infrastructure::db::repositories::mention_repository_sqlx::SqlxMentionRepository::new(
+            pool.clone(),
+        ),
+    );
     let shares_repo_impl = Arc::new(
         api::infrastructure::db::repositories::shares_repository_sqlx::SqlxSharesRepository::new(
             pool.clone(),
@@ -225,11 +408,6 @@ async fn main() -> anyhow::Result<()> {
             pool.clone(),
         ),
     );
-    let user_repo = Arc::new(
-        api::infrastructure::db::repositories::user_repository_sqlx::SqlxUserRepository::new(
-            pool.clone(),
-        ),
-    );
     let tag_repo = Arc::new(
         api::infrastructure::db::repositories::tag_repository_sqlx::SqlxTagRepository::new(
             pool.clone(),
@@ -248,7 +426,9 @@ async fn main() -> anyhow::Result<()> {
             pool.clone(),
             git_storage.clone(),
             storage_port.clone(),
-        )?,
+            gitignore_port.clone(),
+        )?
+        .with_path_layout(cfg.git_path_layout),
     );
     let realtime_engine: Arc<dyn api::application::ports::realtime_port::RealtimeEngine> =
         if cfg.cluster_mode {
@@ -258,7 +438,9 @@ async fn main() -> anyhow::Result<()> {
                     &cfg,
                     pool.clone(),
                     storage_port.clone(),
-                )?,
+                    notifications.clone(),
+                )?
+                .with_presence(presence),
             )
         } else {
             tracing::info!("cluster_mode_disabled_using_local_hub");
@@ -274,6 +456,12 @@ async fn main() -> anyhow::Result<()> {
             pool.clone(),
         ),
     );
+    let plugin_secrets = Arc::new(
+        api::infrastructure::db::repositories::plugin_secret_repository_sqlx::SqlxPluginSecretRepository::new(
+            pool.clone(),
+            cfg.encryption_key.clone(),
+        ),
+    );
     let plugin_limits = {
         let timeout = if cfg.plugin_timeout_secs == 0 {
             None
@@ -290,6 +478,7 @@ async fn main() -> anyhow::Result<()> {
             .plugin_fuel_limit
             .and_then(|limit| if limit == 0 { None } else { Some(limit) });
         PluginExecutionLimits::new(timeout, memory_max_pages, fuel_limit)
+            .with_pool(cfg.plugin_pool_size, cfg.plugin_max_instances)
     };
     let mut s3_plugin_store: Option<
         Arc<api::infrastructure::plugins::s3_store::S3BackedPluginStore>,
@@ -304,7 +493,8 @@ async fn main() -> anyhow::Result<()> {
                 api::infrastructure::plugins::filesystem_store::FilesystemPluginStore::new(
                     &cfg.plugin_dir,
                     plugin_limits,
-                )?,
+                )?
+                .with_installations(plugin_installations.clone()),
             );
             let runtime: Arc<dyn PluginRuntime> = store.clone();
             let installer: Arc<dyn PluginInstaller> = store.clone();
@@ -317,6 +507,7 @@ async fn main() -> anyhow::Result<()> {
                     &cfg.plugin_dir,
                     &cfg,
                     plugin_limits,
+                    plugin_installations.clone(),
                 )
                 .await?,
             );
@@ -327,15 +518,24 @@ async fn main() -> anyhow::Result<()> {
             (runtime, installer, assets)
         }
     };
-    let plugin_fetcher = Arc::new(
-        api::infrastructure::plugins::package_fetcher_reqwest::ReqwestPluginPackageFetcher::new(),
-    );
-    let plugin_event_bus = Arc::new(
-        api::infrastructure::plugins::event_bus_pg::PgPluginEventBus::new(
+    let plugin_exec_log = Arc::new(
+        api::infrastructure::db::repositories::plugin_exec_log_repository_sqlx::SqlxPluginExecLogRepository::new(
             pool.clone(),
-            "plugin_events",
         ),
     );
+    let plugin_runtime: Arc<dyn PluginRuntime> = Arc::new(
+        api::infrastructure::plugins::exec_log::LoggingPluginRuntime::new(
+            plugin_runtime,
+            plugin_exec_log.clone(),
+            api::infrastructure::plugins::exec_log::PluginExecLogOptions {
+                sample_every: cfg.plugin_exec_log_sample_every,
+                log_payloads: cfg.plugin_exec_log_payloads,
+            },
+        ),
+    );
+    let plugin_fetcher = Arc::new(
+        api::infrastructure::plugins::package_fetcher_reqwest::ReqwestPluginPackageFetcher::new(),
+    );
     if let Some(store) = &s3_plugin_store {
         store.spawn_event_listener(plugin_event_bus.clone());
 
@@ -366,9 +566,50 @@ async fn main() -> anyhow::Result<()> {
         });
     }
     let plugin_event_publisher: Arc<dyn PluginEventPublisher> = plugin_event_bus.clone();
+    let plugin_event_replay = api::infrastructure::plugins::event_replay::PluginEventReplay::new(
+        cfg.plugin_event_replay_size,
+        std::time::Duration::from_secs(cfg.plugin_event_replay_ttl_secs),
+    );
+    plugin_event_replay.spawn_forwarder(plugin_event_bus.clone());
+
+    #[cfg(feature = "weasyprint")]
+    let pdf_renderer: Arc<dyn PdfRenderer> = Arc::new(
+        api::infrastructure::export::weasyprint::WeasyprintPdfRenderer::new(
+            cfg.weasyprint_bin.clone(),
+        ),
+    );
+    #[cfg(not(feature = "weasyprint"))]
+    let pdf_renderer: Arc<dyn PdfRenderer> =
+        Arc::new(api::infrastructure::export::pdf_writer::BuiltinPdfRenderer);
+
+    let document_retention_repo = Arc::new(
+        api::infrastructure::db::repositories::document_retention_repository_sqlx::SqlxDocumentRetentionRepository::new(
+            pool.clone(),
+        ),
+    );
+
+    let document_user_access_repo = Arc::new(
+        api::infrastructure::db::repositories::document_user_access_repository_sqlx::SqlxDocumentUserAccessRepository::new(
+            pool.clone(),
+        ),
+    );
+
+    let document_version_repo = Arc::new(
+        api::infrastructure::db::repositories::document_version_repository_sqlx::SqlxDocumentVersionRepository::new(
+            pool.clone(),
+        ),
+    );
+
+    let comment_repo = Arc::new(
+        api::infrastructure::db::repositories::comment_repository_sqlx::SqlxCommentRepository::new(
+            pool.clone(),
+        ),
+    );
 
     let services = AppServices::new(
         document_repo,
+        linkgraph_repo,
+        mention_repo,
         shares_repo_impl.clone(),
         shares_repo_impl,
         access_repo,
@@ -384,16 +625,124 @@ async fn main() -> anyhow::Result<()> {
         realtime_engine.clone(),
         plugin_repo,
         plugin_installations,
+        plugin_secrets,
+        plugin_exec_log,
         plugin_runtime.clone(),
         plugin_installer.clone(),
         plugin_fetcher,
         plugin_event_bus.clone(),
+        plugin_event_replay,
         plugin_event_publisher,
         plugin_assets.clone(),
+        pdf_renderer,
+        document_retention_repo,
+        document_user_access_repo,
+        document_version_repo,
+        comment_repo,
+        notifications,
     );
 
     let ctx = AppContext::new(cfg.clone(), services);
 
+    if cfg.public_analytics_enabled {
+        let views = ctx.public_views();
+        let repo = ctx.public_repo();
+        tokio::spawn(async move {
+            loop {
+                sleep(Duration::from_secs(30)).await;
+                if let Err(e) = views.flush(repo.as_ref()).await {
+                    tracing::warn!(error = ?e, "public_views_flush_failed");
+                }
+            }
+        });
+    }
+
+    if cfg.share_cleanup_interval_secs > 0 {
+        let repo = ctx.shares_repo();
+        let interval = Duration::from_secs(cfg.share_cleanup_interval_secs);
+        tokio::spawn(async move {
+            loop {
+                sleep(interval).await;
+                let uc = CleanupExpiredShares {
+                    repo: repo.as_ref(),
+                };
+                if let Err(e) = uc.execute(chrono::Utc::now()).await {
+                    tracing::warn!(error = ?e, "share_cleanup_failed");
+                }
+            }
+        });
+    }
+
+    if cfg.git_auto_sync_interval_secs > 0 {
+        let repo = ctx.git_repo();
+        let workspace = ctx.git_workspace();
+        let policy = AutoSyncPolicy::from_secs(
+            cfg.git_auto_sync_quiet_secs,
+            cfg.git_auto_sync_max_interval_secs,
+        );
+        let interval = Duration::from_secs(cfg.git_auto_sync_interval_secs);
+        tokio::spawn(async move {
+            let mut attempted = HashMap::new();
+            loop {
+                sleep(interval).await;
+                let uc = RunAutoSync {
+                    repo: repo.as_ref(),
+                    workspace: workspace.as_ref(),
+                    policy,
+                };
+                if let Err(e) = uc.execute(chrono::Utc::now(), &mut attempted).await {
+                    tracing::warn!(error = ?e, "git_auto_sync_pass_failed");
+                }
+            }
+        });
+    }
+
+    if cfg.plugin_schedule_concurrency > 0 {
+        let scheduler = PluginScheduler::new(
+            ctx.plugin_installations(),
+            ctx.plugin_runtime(),
+            cfg.plugin_schedule_concurrency,
+        );
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            loop {
+                let invoke = |run: ScheduledRun| {
+                    let ctx = ctx.clone();
+                    async move {
+                        let runtime = ctx.plugin_runtime();
+                        let plugin_repo = ctx.plugin_repo();
+                        let document_repo = ctx.document_repo();
+                        let access_repo = ctx.access_repo();
+                        let share_access = ctx.share_access_port();
+                        let shares_repo = ctx.shares_repo();
+                        let secrets = ctx.plugin_secrets();
+                        let exec = ExecutePluginAction {
+                            runtime: runtime.as_ref(),
+                            plugin_repo: plugin_repo.as_ref(),
+                            document_repo: document_repo.as_ref(),
+                            access_repo: access_repo.as_ref(),
+                            share_access: share_access.as_ref(),
+                            policy: ctx.access_policy(),
+                            shares_repo: shares_repo.as_ref(),
+                            secrets: secrets.as_ref(),
+                        };
+                        let payload =
+                            serde_json::json!({ "trigger": "schedule", "cron": run.cron });
+                        exec.execute(run.user_id, &run.plugin, &run.action, Some(payload))
+                            .await
+                            .map(|_| ())
+                    }
+                };
+                if let Err(e) = scheduler.tick(chrono::Utc::now(), invoke).await {
+                    tracing::warn!(error = ?e, "plugin_scheduler_tick_failed");
+                }
+                // Wake just after the next minute boundary.
+                let into_minute = chrono::Utc::now().timestamp().rem_euclid(60) as u64;
+                sleep(Duration::from_secs(60 - into_minute + 1)).await;
+            }
+        });
+    }
+
     // Build CORS
     let cors = if let Some(origin) = cfg.frontend_url.clone() {
         match HeaderValue::from_str(&origin) {
@@ -456,10 +805,10 @@ async fn main() -> anyhow::Result<()> {
     };
 
     // Ensure uploads dir exists
-    if matches!(cfg.storage_backend, StorageBackend::Filesystem) {
-        if let Err(e) = tokio::fs::create_dir_all(&cfg.storage_root).await {
-            tracing::warn!(error=?e, dir=%cfg.storage_root, "Failed to create uploads dir");
-        }
+    if matches!(cfg.storage_backend, StorageBackend::Filesystem)
+        && let Err(e) = tokio::fs::create_dir_all(&cfg.storage_root).await
+    {
+        tracing::warn!(error=?e, dir=%cfg.storage_root, "Failed to create uploads dir");
     }
 
     // Build upload router with state
@@ -479,16 +828,26 @@ async fn main() -> anyhow::Result<()> {
             .unwrap_or_else(|| std::path::PathBuf::from("./plugins"))
     };
 
+    let trusted_proxies = cfg.trusted_proxies.clone();
+
     // Build API router
     let api_router = Router::new()
         .nest(
             "/api",
-            api::presentation::http::health::routes(pool.clone()),
+            api::presentation::http::health::routes(pool.clone(), ctx.plugin_assets()),
         )
         .nest(
             "/api",
             api::presentation::http::documents::routes(ctx.clone()),
         )
+        .nest(
+            "/api",
+            api::presentation::http::capabilities::routes(ctx.clone()),
+        )
+        .nest(
+            "/api",
+            api::presentation::http::comments::routes(ctx.clone()),
+        )
         .nest(
             "/api/auth",
             api::presentation::http::auth::routes(ctx.clone()),
@@ -496,6 +855,10 @@ async fn main() -> anyhow::Result<()> {
         .nest("/api", api::presentation::http::shares::routes(ctx.clone()))
         .nest("/api", api::presentation::http::files::routes(ctx.clone()))
         .nest("/api", api::presentation::http::tags::routes(ctx.clone()))
+        .nest(
+            "/api",
+            api::presentation::http::notifications::routes(ctx.clone()),
+        )
         .nest("/api", api::presentation::http::git::routes(ctx.clone()))
         .nest(
             "/api",
@@ -512,10 +875,14 @@ async fn main() -> anyhow::Result<()> {
         .nest_service("/api/plugin-assets", ServeDir::new(plugin_root))
         .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi()))
         .layer(cors)
-        // Global body size limit for uploads (configurable)
-        .layer(DefaultBodyLimit::max(cfg.upload_max_bytes))
+        // Default body limit; upload and plugin routes raise it on their own routers
+        .layer(DefaultBodyLimit::max(cfg.json_body_max_bytes))
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(api::presentation::http::timeout::RequestTimeouts::from_config(&cfg)),
+            api::presentation::http::timeout::limit_request_time,
+        ))
         .layer(
-            TraceLayer::new_for_http().make_span_with(|req: &http::Request<_>| {
+            TraceLayer::new_for_http().make_span_with(move |req: &http::Request<_>| {
                 let method = req.method().clone();
                 let uri = req.uri().clone();
                 let matched = req
@@ -523,7 +890,19 @@ async fn main() -> anyhow::Result<()> {
                     .get::<MatchedPath>()
                     .map(|p| p.as_str().to_string())
                     .unwrap_or_default();
-                tracing::info_span!("http", %method, %uri, matched_path = %matched)
+                let peer = req
+                    .extensions()
+                    .get::<axum::extract::ConnectInfo<SocketAddr>>()
+                    .map(|c| c.0.ip());
+                let client = api::presentation::http::client_ip::ClientIp(
+                    api::presentation::http::client_ip::resolve(
+                        &trusted_proxies,
+                        peer,
+                        req.headers(),
+                    ),
+                )
+                .key();
+                tracing::info_span!("http", %method, %uri, matched_path = %matched, %client)
             }),
         );
 
@@ -542,7 +921,11 @@ async fn main() -> anyhow::Result<()> {
     let app = api_router.merge(ws_router);
 
     let api_handle: JoinHandle<anyhow::Result<()>> = tokio::spawn(async move {
-        axum::serve(listener, app).await?;
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
         Ok(())
     });
 
@@ -556,10 +939,10 @@ async fn main() -> anyhow::Result<()> {
             let interval = Duration::from_secs(cfg_for_snap.snapshot_interval_secs);
             loop {
                 if let Err(e) = hub_for_snap
-                    .snapshot_all(
-                        cfg_for_snap.snapshot_keep_versions,
-                        cfg_for_snap.updates_keep_window,
-                    )
+                    .snapshot_all(RetentionPolicy {
+                        keep_versions: cfg_for_snap.snapshot_keep_versions,
+                        updates_keep_window: cfg_for_snap.updates_keep_window,
+                    })
                     .await
                 {
                     tracing::error!(error = ?e, "snapshot_loop_failed");
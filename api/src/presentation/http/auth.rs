@@ -1,4 +1,5 @@
 use crate::application::access;
+use crate::application::services::token_cache::TokenLookup;
 use crate::application::use_cases::auth::delete_account::DeleteAccount;
 use crate::application::use_cases::auth::login::{Login as LoginUc, LoginRequest as LoginDto};
 use crate::application::use_cases::auth::me::GetMe;
@@ -6,7 +7,6 @@ use crate::application::use_cases::auth::register::{
     Register as RegisterUc, RegisterRequest as RegisterDto,
 };
 use crate::bootstrap::app_context::AppContext;
-use crate::bootstrap::config::Config;
 use axum::{
     Json, Router,
     extract::State,
@@ -48,6 +48,8 @@ pub struct LoginResponse {
 pub struct Claims {
     pub sub: String,
     pub exp: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<usize>,
 }
 
 pub fn routes(ctx: AppContext) -> Router {
@@ -112,6 +114,7 @@ pub async fn login(
     let claims = Claims {
         sub: user.id.to_string(),
         exp: now + (ctx.cfg.jwt_expires_secs as usize),
+        nbf: Some(now),
     };
     let token = jsonwebtoken::encode(
         &Header::default(),
@@ -149,7 +152,7 @@ pub async fn me(
     State(ctx): State<AppContext>,
     bearer: Result<Bearer, StatusCode>,
 ) -> Result<Json<UserResponse>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer?)?;
+    let sub = validate_bearer(&ctx, bearer?)?;
     let id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.user_repo();
     let uc = GetMe {
@@ -172,7 +175,8 @@ pub async fn delete_account(
     State(ctx): State<AppContext>,
     bearer: Bearer,
 ) -> Result<(HeaderMap, StatusCode), StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let token = bearer.0.clone();
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
 
     let user_repo = ctx.user_repo();
@@ -199,6 +203,7 @@ pub async fn delete_account(
         tracing::error!(user_id = %user_id, error = ?err, "account deletion failed");
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
+    revoke_token(&ctx, &token);
 
     let mut headers = HeaderMap::new();
     let secure = ctx
@@ -240,10 +245,9 @@ where
             .headers
             .get(axum::http::header::AUTHORIZATION)
             .and_then(|v| v.to_str().ok())
+            && let Some(t) = auth.strip_prefix("Bearer ")
         {
-            if let Some(t) = auth.strip_prefix("Bearer ") {
-                return Ok(Bearer(t.to_string()));
-            }
+            return Ok(Bearer(t.to_string()));
         }
 
         // 2) Fallback to HttpOnly cookie `access_token`
@@ -251,62 +255,82 @@ where
             .headers
             .get(axum::http::header::COOKIE)
             .and_then(|v| v.to_str().ok())
+            && let Some(token) = get_cookie(cookie_hdr, "access_token")
         {
-            if let Some(token) = get_cookie(cookie_hdr, "access_token") {
-                return Ok(Bearer(token));
-            }
+            return Ok(Bearer(token));
         }
 
         Err(StatusCode::UNAUTHORIZED)
     }
 }
 
-pub(crate) fn validate_bearer(cfg: &Config, bearer: Bearer) -> Result<String, StatusCode> {
-    let token = bearer.0;
-    let data = jsonwebtoken::decode::<Claims>(
-        &token,
-        &DecodingKey::from_secret(cfg.jwt_secret_pem.as_bytes()),
-        &Validation::default(),
+pub(crate) fn validate_bearer(ctx: &AppContext, bearer: Bearer) -> Result<String, StatusCode> {
+    validate_bearer_str(ctx, &bearer.0)
+}
+
+/// Verifies the signature, then checks `exp`/`nbf` with `leeway_secs` of clock skew.
+fn decode_claims(secret: &str, leeway_secs: u64, token: &str) -> Result<Claims, StatusCode> {
+    let mut validation = Validation::default();
+    validation.leeway = leeway_secs;
+    validation.validate_nbf = true;
+    jsonwebtoken::decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &validation,
     )
-    .map_err(|_| StatusCode::UNAUTHORIZED)?;
-    Ok(data.claims.sub)
+    .map(|data| data.claims)
+    .map_err(|_| StatusCode::UNAUTHORIZED)
 }
 
-pub fn validate_bearer_public(cfg: &Config, bearer: Bearer) -> Result<String, StatusCode> {
-    validate_bearer(cfg, bearer)
+pub fn validate_bearer_public(ctx: &AppContext, bearer: Bearer) -> Result<String, StatusCode> {
+    validate_bearer(ctx, bearer)
 }
 
-pub fn validate_bearer_str(cfg: &Config, token: &str) -> Result<String, StatusCode> {
-    let data = jsonwebtoken::decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(cfg.jwt_secret_pem.as_bytes()),
-        &Validation::default(),
-    )
-    .map_err(|_| StatusCode::UNAUTHORIZED)?;
-    Ok(data.claims.sub)
+pub fn validate_bearer_str(ctx: &AppContext, token: &str) -> Result<String, StatusCode> {
+    let cfg = &ctx.cfg;
+    let now = chrono::Utc::now().timestamp();
+    match ctx.token_cache().lookup(token, now) {
+        TokenLookup::Hit(sub) => return Ok(sub),
+        TokenLookup::Revoked => return Err(StatusCode::UNAUTHORIZED),
+        TokenLookup::Miss => {}
+    }
+    let claims = decode_claims(&cfg.jwt_secret_pem, cfg.jwt_leeway_secs, token)?;
+    let valid_until = claims.exp as i64 + cfg.jwt_leeway_secs as i64;
+    ctx.token_cache()
+        .insert(token, &claims.sub, valid_until, now);
+    Ok(claims.sub)
+}
+
+/// Refuses `token` on this node from now on, e.g. after logout.
+fn revoke_token(ctx: &AppContext, token: &str) {
+    let cfg = &ctx.cfg;
+    if let Ok(claims) = decode_claims(&cfg.jwt_secret_pem, cfg.jwt_leeway_secs, token) {
+        let valid_until = claims.exp as i64 + cfg.jwt_leeway_secs as i64;
+        ctx.token_cache()
+            .revoke(token, valid_until, chrono::Utc::now().timestamp());
+    }
 }
 
 pub fn resolve_actor_from_parts(
-    cfg: &Config,
+    ctx: &AppContext,
     bearer: Option<Bearer>,
     share_token: Option<&str>,
 ) -> Option<access::Actor> {
-    if let Some(b) = bearer {
-        if let Ok(sub) = validate_bearer(cfg, b) {
-            if let Ok(uid) = Uuid::parse_str(&sub) {
-                return Some(access::Actor::User(uid));
-            }
-        }
+    if let Some(b) = bearer
+        && let Ok(sub) = validate_bearer(ctx, b)
+        && let Ok(uid) = Uuid::parse_str(&sub)
+    {
+        return Some(access::Actor::User(uid));
     }
-    share_token.and_then(|t| resolve_actor_from_token_str(cfg, t))
+    share_token.and_then(|t| resolve_actor_from_token_str(ctx, t))
 }
 
-pub fn resolve_actor_from_token_str(cfg: &Config, token: &str) -> Option<access::Actor> {
+pub fn resolve_actor_from_token_str(ctx: &AppContext, token: &str) -> Option<access::Actor> {
     let trimmed = token.trim();
     if trimmed.is_empty() {
         return None;
     }
-    if let Ok(sub) = validate_bearer_str(cfg, trimmed) {
+    if let Ok(sub) = validate_bearer_str(ctx, trimmed) {
         if let Ok(uid) = Uuid::parse_str(&sub) {
             return Some(access::Actor::User(uid));
         } else {
@@ -321,10 +345,10 @@ pub fn resolve_actor_from_token_str(cfg: &Config, token: &str) -> Option<access:
 fn get_cookie(cookie_header: &str, name: &str) -> Option<String> {
     for part in cookie_header.split(';') {
         let kv = part.trim();
-        if let Some((k, v)) = kv.split_once('=') {
-            if k.trim() == name {
-                return Some(v.trim().to_string());
-            }
+        if let Some((k, v)) = kv.split_once('=')
+            && k.trim() == name
+        {
+            return Some(v.trim().to_string());
         }
     }
     None
@@ -343,7 +367,13 @@ fn build_access_cookie(token: &str, max_age_secs: i64, secure: bool) -> String {
 }
 
 #[utoipa::path(post, path = "/api/auth/logout", tag = "Auth", responses((status = 204)))]
-pub async fn logout(State(ctx): State<AppContext>) -> Result<(HeaderMap, StatusCode), StatusCode> {
+pub async fn logout(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+) -> Result<(HeaderMap, StatusCode), StatusCode> {
+    if let Some(Bearer(token)) = bearer {
+        revoke_token(&ctx, &token);
+    }
     // Clear cookie by setting it expired
     let mut headers = HeaderMap::new();
     let secure = ctx
@@ -364,3 +394,44 @@ pub async fn logout(State(ctx): State<AppContext>) -> Result<(HeaderMap, StatusC
     );
     Ok((headers, StatusCode::NO_CONTENT))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "test-secret-for-leeway-checks";
+
+    fn token_expired_secs_ago(secs: usize) -> String {
+        let now = chrono::Utc::now().timestamp() as usize;
+        let claims = Claims {
+            sub: "user".into(),
+            exp: now - secs,
+            nbf: Some(now - 3600),
+        };
+        jsonwebtoken::encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(SECRET.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn expiry_is_checked_with_leeway() {
+        let just_expired = token_expired_secs_ago(10);
+        assert_eq!(
+            decode_claims(SECRET, 30, &just_expired).unwrap().sub,
+            "user"
+        );
+        assert_eq!(
+            decode_claims(SECRET, 0, &just_expired).unwrap_err(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let long_expired = token_expired_secs_ago(600);
+        assert!(decode_claims(SECRET, 30, &long_expired).is_err());
+
+        // Leeway never relaxes the signature check.
+        assert!(decode_claims("another-secret-entirely", 30, &just_expired).is_err());
+    }
+}
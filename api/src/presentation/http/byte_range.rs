@@ -0,0 +1,165 @@
+//! Single-range `Range` / `If-Range` handling for file responses (RFC 9110 §14).
+
+use axum::http::{HeaderMap, header};
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeOutcome {
+    /// No usable range; send the whole representation with 200.
+    Full,
+    /// Inclusive byte span to send with 206.
+    Partial { start: u64, end: u64 },
+    /// Malformed or unsatisfiable range; respond with 416.
+    Unsatisfiable,
+}
+
+/// Decides how to answer a request for an object of `size` bytes with the given validators.
+/// Multi-range requests are answered with the full body rather than multipart responses.
+pub fn evaluate(
+    headers: &HeaderMap,
+    size: u64,
+    etag: Option<&str>,
+    last_modified: Option<DateTime<Utc>>,
+) -> RangeOutcome {
+    let Some(raw) = headers.get(header::RANGE).and_then(|v| v.to_str().ok()) else {
+        return RangeOutcome::Full;
+    };
+    if let Some(if_range) = headers.get(header::IF_RANGE) {
+        let validator = if_range.to_str().unwrap_or_default().trim();
+        if !if_range_matches(validator, etag, last_modified) {
+            return RangeOutcome::Full;
+        }
+    }
+    parse_range(raw, size)
+}
+
+fn if_range_matches(
+    validator: &str,
+    etag: Option<&str>,
+    last_modified: Option<DateTime<Utc>>,
+) -> bool {
+    if validator.starts_with('"') || validator.starts_with("W/") {
+        // If-Range requires a strong comparison; weak tags never match.
+        return !validator.starts_with("W/") && etag.is_some_and(|e| e == validator);
+    }
+    match (DateTime::parse_from_rfc2822(validator), last_modified) {
+        (Ok(date), Some(modified)) => date.timestamp() == modified.timestamp(),
+        _ => false,
+    }
+}
+
+fn parse_range(raw: &str, size: u64) -> RangeOutcome {
+    let Some(spec) = raw.trim().strip_prefix("bytes=") else {
+        return RangeOutcome::Unsatisfiable;
+    };
+    if spec.contains(',') {
+        return RangeOutcome::Full;
+    }
+    let Some((first, last)) = spec.trim().split_once('-') else {
+        return RangeOutcome::Unsatisfiable;
+    };
+    let (first, last) = (first.trim(), last.trim());
+    let parse = |s: &str| -> Option<u64> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            None
+        } else {
+            s.parse().ok()
+        }
+    };
+    let (start, end) = if first.is_empty() {
+        // Suffix range: the last N bytes.
+        match parse(last) {
+            Some(0) | None => return RangeOutcome::Unsatisfiable,
+            Some(n) => (size.saturating_sub(n), size.saturating_sub(1)),
+        }
+    } else {
+        let Some(start) = parse(first) else {
+            return RangeOutcome::Unsatisfiable;
+        };
+        let end = if last.is_empty() {
+            size.saturating_sub(1)
+        } else {
+            match parse(last) {
+                Some(end) if end >= start => end.min(size.saturating_sub(1)),
+                _ => return RangeOutcome::Unsatisfiable,
+            }
+        };
+        (start, end)
+    };
+    if size == 0 || start >= size {
+        return RangeOutcome::Unsatisfiable;
+    }
+    RangeOutcome::Partial { start, end }
+}
+
+/// Formats a timestamp as an HTTP-date (IMF-fixdate).
+pub fn http_date(ts: DateTime<Utc>) -> String {
+    ts.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn with_range(range: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RANGE, HeaderValue::from_str(range).unwrap());
+        headers
+    }
+
+    #[test]
+    fn single_range_selects_byte_slice() {
+        let body: Vec<u8> = (0u8..100).collect();
+        let outcome = evaluate(&with_range("bytes=10-19"), body.len() as u64, None, None);
+        let RangeOutcome::Partial { start, end } = outcome else {
+            panic!("expected partial, got {:?}", outcome);
+        };
+        assert_eq!(
+            &body[start as usize..=end as usize],
+            &(10u8..20).collect::<Vec<_>>()[..]
+        );
+
+        assert_eq!(
+            evaluate(&with_range("bytes=-5"), 100, None, None),
+            RangeOutcome::Partial { start: 95, end: 99 }
+        );
+        assert_eq!(
+            evaluate(&with_range("bytes=90-"), 100, None, None),
+            RangeOutcome::Partial { start: 90, end: 99 }
+        );
+    }
+
+    #[test]
+    fn malformed_or_unsatisfiable_range_is_rejected() {
+        for raw in [
+            "bytes=abc",
+            "items=0-1",
+            "bytes=20-10",
+            "bytes=100-",
+            "bytes=-0",
+        ] {
+            assert_eq!(
+                evaluate(&with_range(raw), 100, None, None),
+                RangeOutcome::Unsatisfiable,
+                "{}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn if_range_mismatch_falls_back_to_full_body() {
+        let mut headers = with_range("bytes=0-9");
+        headers.insert(header::IF_RANGE, HeaderValue::from_static("\"old\""));
+        assert_eq!(
+            evaluate(&headers, 100, Some("\"new\""), None),
+            RangeOutcome::Full
+        );
+        headers.insert(header::IF_RANGE, HeaderValue::from_static("\"new\""));
+        assert_eq!(
+            evaluate(&headers, 100, Some("\"new\""), None),
+            RangeOutcome::Partial { start: 0, end: 9 }
+        );
+    }
+}
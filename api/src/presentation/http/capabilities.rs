@@ -0,0 +1,80 @@
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderValue, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::application::services::capabilities::{ServerCapabilities, StorageKind};
+use crate::bootstrap::app_context::AppContext;
+
+/// Capabilities only change on restart; let clients and proxies reuse them for a while.
+const CACHE_CONTROL: &str = "public, max-age=300";
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackendKind {
+    Filesystem,
+    S3,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CapabilitiesResponse {
+    pub storage: StorageBackendKind,
+    /// Realtime state is shared between nodes through Redis.
+    pub cluster_mode: bool,
+    pub git_sync: bool,
+    /// Changes are committed to linked repositories in the background.
+    pub git_auto_sync: bool,
+    pub plugins: bool,
+    /// PDF export goes through weasyprint rather than the built-in writer.
+    pub weasyprint_pdf: bool,
+    pub public_analytics: bool,
+    /// Edit share links grant edit to anonymous visitors.
+    pub anonymous_edit: bool,
+    /// Null when documents may have any number of share links.
+    pub max_shares_per_document: Option<usize>,
+    pub upload_max_bytes: usize,
+}
+
+impl From<ServerCapabilities> for CapabilitiesResponse {
+    fn from(c: ServerCapabilities) -> Self {
+        CapabilitiesResponse {
+            storage: match c.storage {
+                StorageKind::Filesystem => StorageBackendKind::Filesystem,
+                StorageKind::S3 => StorageBackendKind::S3,
+            },
+            cluster_mode: c.cluster_mode,
+            git_sync: c.git_sync,
+            git_auto_sync: c.git_auto_sync,
+            plugins: c.plugins,
+            weasyprint_pdf: c.weasyprint_pdf,
+            public_analytics: c.public_analytics,
+            anonymous_edit: c.anonymous_edit,
+            max_shares_per_document: c.max_shares_per_document,
+            upload_max_bytes: c.upload_max_bytes,
+        }
+    }
+}
+
+#[utoipa::path(get, path = "/api/capabilities", tag = "Server", operation_id = "getCapabilities",
+    security(()),
+    responses((status = 200, body = CapabilitiesResponse)))]
+pub async fn get_capabilities(State(ctx): State<AppContext>) -> Response {
+    let body: CapabilitiesResponse = ServerCapabilities::from_config(&ctx.cfg).into();
+    let mut resp = Json(body).into_response();
+    resp.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(CACHE_CONTROL),
+    );
+    resp
+}
+
+pub fn routes(ctx: AppContext) -> Router {
+    Router::new()
+        .route("/capabilities", get(get_capabilities))
+        .with_state(ctx)
+}
@@ -0,0 +1,188 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{HeaderMap, request::Parts},
+};
+
+use crate::bootstrap::app_context::AppContext;
+use crate::bootstrap::config::IpCidr;
+
+/// Address of the client behind the request. Forwarding headers are only read when the
+/// connecting peer is a configured trusted proxy; otherwise the peer itself is the client.
+/// `None` only when the server was not given connection info.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl ClientIp {
+    /// Key for per-client bookkeeping such as rate limits; empty when the address is unknown.
+    pub fn key(&self) -> String {
+        self.0.map(|ip| ip.to_string()).unwrap_or_default()
+    }
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppContext> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        ctx: &AppContext,
+    ) -> Result<Self, Self::Rejection> {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        Ok(ClientIp(resolve(
+            &ctx.cfg.trusted_proxies,
+            peer,
+            &parts.headers,
+        )))
+    }
+}
+
+/// Walks the forwarding chain back from `peer`, skipping trusted proxies, and returns the
+/// first hop that is not one. `Forwarded` wins over `X-Forwarded-For`, which wins over
+/// `X-Real-IP`.
+pub fn resolve(trusted: &[IpCidr], peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+    let is_trusted = |ip: IpAddr| trusted.iter().any(|net| net.contains(ip));
+    let peer = peer?;
+    if !is_trusted(peer) {
+        return Some(peer);
+    }
+    let chain = forwarded_chain(headers);
+    chain
+        .iter()
+        .rev()
+        .copied()
+        .find(|ip| !is_trusted(*ip))
+        .or_else(|| chain.first().copied())
+        .or(Some(peer))
+}
+
+/// Client addresses reported by proxies, nearest to the original client first.
+fn forwarded_chain(headers: &HeaderMap) -> Vec<IpAddr> {
+    let values = |name: &str| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .collect::<Vec<_>>()
+    };
+    let forwarded: Vec<IpAddr> = values("forwarded")
+        .into_iter()
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (key, value) = pair.trim().split_once('=')?;
+                key.trim()
+                    .eq_ignore_ascii_case("for")
+                    .then(|| parse_node(value))?
+            })
+        })
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+    let x_forwarded: Vec<IpAddr> = values("x-forwarded-for")
+        .into_iter()
+        .filter_map(parse_node)
+        .collect();
+    if !x_forwarded.is_empty() {
+        return x_forwarded;
+    }
+    values("x-real-ip")
+        .into_iter()
+        .filter_map(parse_node)
+        .collect()
+}
+
+/// Parses `192.0.2.1`, `192.0.2.1:8080`, `2001:db8::1` or `"[2001:db8::1]:8080"`.
+/// Obfuscated identifiers and `unknown` yield `None`.
+fn parse_node(value: &str) -> Option<IpAddr> {
+    let value = value.trim().trim_matches('"');
+    if let Some(rest) = value.strip_prefix('[') {
+        return rest.split(']').next()?.parse().ok();
+    }
+    value.parse().ok().or_else(|| {
+        let (host, _port) = value.rsplit_once(':')?;
+        host.parse::<std::net::Ipv4Addr>().ok().map(IpAddr::V4)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut h = HeaderMap::new();
+        for (name, value) in pairs {
+            h.append(*name, value.parse().unwrap());
+        }
+        h
+    }
+
+    #[test]
+    fn forwarded_headers_are_only_believed_from_trusted_proxies() {
+        let trusted: Vec<IpCidr> = vec!["10.0.0.0/8".parse().unwrap()];
+        let spoofed = headers(&[("x-forwarded-for", "203.0.113.9")]);
+
+        // Untrusted peer: the header is ignored.
+        assert_eq!(
+            resolve(&trusted, Some(ip("198.51.100.7")), &spoofed),
+            Some(ip("198.51.100.7"))
+        );
+        // No trusted proxies configured: the socket address is used.
+        assert_eq!(
+            resolve(&[], Some(ip("10.0.0.2")), &spoofed),
+            Some(ip("10.0.0.2"))
+        );
+        // Trusted peer: the forwarded client is used.
+        assert_eq!(
+            resolve(&trusted, Some(ip("10.0.0.2")), &spoofed),
+            Some(ip("203.0.113.9"))
+        );
+    }
+
+    #[test]
+    fn the_nearest_untrusted_hop_wins() {
+        let trusted: Vec<IpCidr> = vec!["10.0.0.0/8".parse().unwrap(), "::1".parse().unwrap()];
+
+        // A client-supplied entry in front of the real one is not taken at face value.
+        let chain = headers(&[("x-forwarded-for", "1.2.3.4, 203.0.113.9, 10.1.1.1")]);
+        assert_eq!(
+            resolve(&trusted, Some(ip("::1")), &chain),
+            Some(ip("203.0.113.9"))
+        );
+
+        let forwarded = headers(&[
+            (
+                "forwarded",
+                r#"for="[2001:db8::7]:4711";proto=https, for=10.0.0.5"#,
+            ),
+            ("x-forwarded-for", "198.51.100.1"),
+        ]);
+        assert_eq!(
+            resolve(&trusted, Some(ip("10.0.0.2")), &forwarded),
+            Some(ip("2001:db8::7"))
+        );
+
+        let with_port = headers(&[("forwarded", "for=192.0.2.60:8080")]);
+        assert_eq!(
+            resolve(&trusted, Some(ip("10.0.0.2")), &with_port),
+            Some(ip("192.0.2.60"))
+        );
+
+        // Nothing forwarded: the proxy is all we know.
+        assert_eq!(
+            resolve(&trusted, Some(ip("10.0.0.2")), &HeaderMap::new()),
+            Some(ip("10.0.0.2"))
+        );
+    }
+}
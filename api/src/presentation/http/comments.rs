@@ -0,0 +1,295 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::access::Actor;
+use crate::application::ports::comment_repository::{CommentAnchor, DocumentComment};
+use crate::application::use_cases::documents::comments::{
+    CommentError, CreateComment, CreateCommentInput, DeleteComment, ListComments, ResolveComment,
+};
+use crate::bootstrap::app_context::AppContext;
+use crate::presentation::http::auth::{self, Bearer};
+use crate::presentation::http::rate_limit;
+
+#[derive(Debug, Deserialize)]
+pub struct CommentsQuery {
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CommentItem {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    /// Set on replies; points at the top-level comment of the thread.
+    pub parent_id: Option<Uuid>,
+    /// Absent for comments left through a share link.
+    pub author_id: Option<Uuid>,
+    pub author_name: Option<String>,
+    /// Commented source range as `start_line:start_col-end_line:end_col`, matching the
+    /// `data-sourcepos` attributes of rendered markdown.
+    pub anchor: Option<String>,
+    pub body: String,
+    pub resolved: bool,
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<DocumentComment> for CommentItem {
+    fn from(c: DocumentComment) -> Self {
+        CommentItem {
+            id: c.id,
+            document_id: c.document_id,
+            parent_id: c.parent_id,
+            author_id: c.author_id,
+            author_name: c.author_name,
+            anchor: c.anchor.map(|a| a.to_string()),
+            body: c.body,
+            resolved: c.resolved,
+            resolved_by: c.resolved_by,
+            resolved_at: c.resolved_at,
+            created_at: c.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateCommentRequest {
+    pub body: String,
+    /// Top-level comment to reply to.
+    pub parent_id: Option<Uuid>,
+    /// Source range in `data-sourcepos` form; ignored on replies.
+    pub anchor: Option<String>,
+    /// Display name for share-link commenters; signed-in users are named by their account.
+    pub author_name: Option<String>,
+}
+
+fn comment_status(err: CommentError) -> StatusCode {
+    match err {
+        CommentError::NotFound => StatusCode::NOT_FOUND,
+        CommentError::Forbidden => StatusCode::FORBIDDEN,
+        CommentError::EmptyBody
+        | CommentError::BodyTooLong
+        | CommentError::InvalidAnchor
+        | CommentError::InvalidParent => StatusCode::BAD_REQUEST,
+        CommentError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[utoipa::path(get, path = "/api/documents/{id}/comments", tag = "Comments", operation_id = "listDocumentComments",
+    params(
+        ("id" = Uuid, Path, description = "Document ID"),
+        ("token" = Option<String>, Query, description = "Share token (optional)")
+    ),
+    responses((status = 200, body = [CommentItem]), (status = 404, description = "Document not found")))]
+pub async fn list_document_comments(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Query(q): Query<CommentsQuery>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<CommentItem>>, StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, q.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let comments = ctx.comment_repo();
+    let uc = ListComments {
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        comments: comments.as_ref(),
+    };
+    let items = uc.execute(&actor, id).await.map_err(comment_status)?;
+    Ok(Json(items.into_iter().map(Into::into).collect()))
+}
+
+#[utoipa::path(post, path = "/api/documents/{id}/comments", tag = "Comments", operation_id = "createDocumentComment",
+    request_body = CreateCommentRequest,
+    params(
+        ("id" = Uuid, Path, description = "Document ID"),
+        ("token" = Option<String>, Query, description = "Share token (optional)")
+    ),
+    responses(
+        (status = 201, body = CommentItem),
+        (status = 400, description = "Invalid comment"),
+        (status = 403, description = "Comment permission required"),
+        (status = 404, description = "Document not found")
+    ))]
+pub async fn create_document_comment(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Query(q): Query<CommentsQuery>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<CreateCommentRequest>,
+) -> Result<(StatusCode, Json<CommentItem>), StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, q.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let anchor = req
+        .anchor
+        .as_deref()
+        .map(str::parse::<CommentAnchor>)
+        .transpose()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let author_name = match &actor {
+        Actor::User(uid) => ctx
+            .user_repo()
+            .find_by_id(*uid)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+            .map(|u| u.name),
+        _ => req.author_name,
+    };
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let comments = ctx.comment_repo();
+    let notifications = ctx.notifications();
+    let uc = CreateComment {
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        comments: comments.as_ref(),
+        notifier: Some(notifications.as_ref()),
+    };
+    let input = CreateCommentInput {
+        parent_id: req.parent_id,
+        author_name,
+        anchor,
+        body: req.body,
+    };
+    let comment = uc
+        .execute(&actor, id, input)
+        .await
+        .map_err(comment_status)?;
+    Ok((StatusCode::CREATED, Json(comment.into())))
+}
+
+async fn set_resolved(
+    ctx: &AppContext,
+    bearer: Option<Bearer>,
+    token: Option<&str>,
+    id: Uuid,
+    comment_id: Uuid,
+    resolved: bool,
+) -> Result<Json<CommentItem>, StatusCode> {
+    let actor =
+        auth::resolve_actor_from_parts(ctx, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let comments = ctx.comment_repo();
+    let uc = ResolveComment {
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        comments: comments.as_ref(),
+    };
+    let comment = uc
+        .execute(&actor, id, comment_id, resolved)
+        .await
+        .map_err(comment_status)?;
+    Ok(Json(comment.into()))
+}
+
+#[utoipa::path(post, path = "/api/documents/{id}/comments/{comment_id}/resolve", tag = "Comments", operation_id = "resolveDocumentComment",
+    params(
+        ("id" = Uuid, Path, description = "Document ID"),
+        ("comment_id" = Uuid, Path, description = "Comment ID"),
+        ("token" = Option<String>, Query, description = "Share token (optional)")
+    ),
+    responses(
+        (status = 200, body = CommentItem),
+        (status = 403, description = "Edit permission or authorship required"),
+        (status = 404, description = "Comment not found")
+    ))]
+pub async fn resolve_document_comment(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Query(q): Query<CommentsQuery>,
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<CommentItem>, StatusCode> {
+    set_resolved(&ctx, bearer, q.token.as_deref(), id, comment_id, true).await
+}
+
+#[utoipa::path(post, path = "/api/documents/{id}/comments/{comment_id}/reopen", tag = "Comments", operation_id = "reopenDocumentComment",
+    params(
+        ("id" = Uuid, Path, description = "Document ID"),
+        ("comment_id" = Uuid, Path, description = "Comment ID"),
+        ("token" = Option<String>, Query, description = "Share token (optional)")
+    ),
+    responses(
+        (status = 200, body = CommentItem),
+        (status = 403, description = "Edit permission or authorship required"),
+        (status = 404, description = "Comment not found")
+    ))]
+pub async fn reopen_document_comment(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Query(q): Query<CommentsQuery>,
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<CommentItem>, StatusCode> {
+    set_resolved(&ctx, bearer, q.token.as_deref(), id, comment_id, false).await
+}
+
+#[utoipa::path(delete, path = "/api/documents/{id}/comments/{comment_id}", tag = "Comments", operation_id = "deleteDocumentComment",
+    params(
+        ("id" = Uuid, Path, description = "Document ID"),
+        ("comment_id" = Uuid, Path, description = "Comment ID"),
+        ("token" = Option<String>, Query, description = "Share token (optional)")
+    ),
+    responses(
+        (status = 204),
+        (status = 403, description = "Edit permission or authorship required"),
+        (status = 404, description = "Comment not found")
+    ))]
+pub async fn delete_document_comment(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Query(q): Query<CommentsQuery>,
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, q.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let comments = ctx.comment_repo();
+    let uc = DeleteComment {
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        comments: comments.as_ref(),
+    };
+    uc.execute(&actor, id, comment_id)
+        .await
+        .map_err(comment_status)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes(ctx: AppContext) -> Router {
+    Router::new()
+        .route(
+            "/documents/:id/comments",
+            get(list_document_comments).post(create_document_comment),
+        )
+        .route(
+            "/documents/:id/comments/:comment_id",
+            delete(delete_document_comment),
+        )
+        .route(
+            "/documents/:id/comments/:comment_id/resolve",
+            post(resolve_document_comment),
+        )
+        .route(
+            "/documents/:id/comments/:comment_id/reopen",
+            post(reopen_document_comment),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            ctx.clone(),
+            rate_limit::limit_share_requests,
+        ))
+        .with_state(ctx)
+}
@@ -0,0 +1,145 @@
+//! Content-Type and Content-Disposition selection for served files.
+
+use std::path::Path;
+
+use axum::http::HeaderValue;
+
+pub const OCTET_STREAM: &str = "application/octet-stream";
+pub const MARKDOWN: &str = "text/markdown; charset=utf-8";
+
+/// Bytes inspected when sniffing a file whose type is otherwise unknown.
+pub const SNIFF_LEN: u64 = 512;
+
+/// Types that can run script in the browser; these are never served inline.
+/// SVG is sanitized on upload and served inline under [`SVG_CSP`] instead.
+const ACTIVE_TYPES: &[&str] = &["text/html", "application/xhtml+xml"];
+
+/// Policy for served SVG: no script, no external loads, opaque origin.
+pub const SVG_CSP: &str = "default-src 'none'; style-src 'unsafe-inline'; img-src data:; sandbox";
+
+pub fn is_svg(content_type: &str) -> bool {
+    essence(content_type) == "image/svg+xml"
+}
+
+fn is_generic(ct: &str) -> bool {
+    let essence = essence(ct);
+    essence.is_empty() || essence == OCTET_STREAM || essence == "binary/octet-stream"
+}
+
+fn essence(ct: &str) -> String {
+    ct.split(';')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Picks the type from the stored value, then the file extension; `None` means sniffing is needed.
+pub fn from_metadata(stored: Option<&str>, path: &Path) -> Option<String> {
+    if let Some(ct) = stored.filter(|ct| !is_generic(ct)) {
+        return Some(with_charset(ct.trim()));
+    }
+    if path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("md") || e.eq_ignore_ascii_case("markdown"))
+    {
+        return Some(MARKDOWN.to_string());
+    }
+    let guessed = mime_guess::from_path(path).first_or_octet_stream();
+    if is_generic(guessed.essence_str()) {
+        None
+    } else {
+        Some(with_charset(guessed.essence_str()))
+    }
+}
+
+/// Detects the type from magic bytes, defaulting to `application/octet-stream`.
+pub fn sniff(head: &[u8]) -> String {
+    infer::get(head)
+        .map(|t| t.mime_type().to_string())
+        .unwrap_or_else(|| OCTET_STREAM.to_string())
+}
+
+fn with_charset(ct: &str) -> String {
+    if ct.starts_with("text/") && !ct.contains("charset") {
+        format!("{}; charset=utf-8", ct)
+    } else {
+        ct.to_string()
+    }
+}
+
+/// Interprets `?download=` query values.
+pub fn wants_download(flag: Option<&str>) -> bool {
+    matches!(
+        flag.map(|f| f.trim().to_ascii_lowercase()).as_deref(),
+        Some("1" | "true" | "yes")
+    )
+}
+
+/// Builds `Content-Disposition`: inline unless a download is requested or the type is active content.
+pub fn disposition(filename: &str, content_type: &str, download: bool) -> HeaderValue {
+    let kind = if download || ACTIVE_TYPES.contains(&essence(content_type).as_str()) {
+        "attachment"
+    } else {
+        "inline"
+    };
+    let ascii_name: String = filename
+        .chars()
+        .map(|c| {
+            if (c.is_ascii_graphic() && c != '"' && c != '\\') || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let value = format!(
+        "{}; filename=\"{}\"; filename*=UTF-8''{}",
+        kind,
+        ascii_name,
+        urlencoding::encode(filename)
+    );
+    HeaderValue::from_str(&value).unwrap_or(HeaderValue::from_static("attachment"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG_HEADER: &[u8] = &[
+        0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', 0, 0, 0, 13,
+    ];
+
+    #[test]
+    fn markdown_documents_are_served_as_utf8_markdown() {
+        assert_eq!(
+            from_metadata(None, Path::new("notes/doc.md")).as_deref(),
+            Some(MARKDOWN)
+        );
+    }
+
+    #[test]
+    fn png_attachment_uses_stored_type_then_sniffs() {
+        assert_eq!(
+            from_metadata(Some("image/png"), Path::new("attachments/blob")).as_deref(),
+            Some("image/png")
+        );
+        assert_eq!(
+            from_metadata(Some(OCTET_STREAM), Path::new("attachments/blob")),
+            None
+        );
+        assert_eq!(sniff(PNG_HEADER), "image/png");
+        assert_eq!(sniff(b"plain bytes"), OCTET_STREAM);
+    }
+
+    #[test]
+    fn disposition_switches_on_download_flag() {
+        let inline = disposition("cat.png", "image/png", wants_download(None));
+        assert!(inline.to_str().unwrap().starts_with("inline;"));
+        let download = disposition("cat.png", "image/png", wants_download(Some("1")));
+        assert!(download.to_str().unwrap().starts_with("attachment;"));
+        let html = disposition("page.html", "text/html; charset=utf-8", false);
+        assert!(html.to_str().unwrap().starts_with("attachment;"));
+    }
+}
@@ -1,27 +1,71 @@
 use axum::{
     Json, Router,
     extract::{Path, Query, State},
-    http::{HeaderMap, HeaderValue, StatusCode},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
-    routing::get,
+    routing::{delete, get, post, put},
 };
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::application::access;
+use crate::application::ports::awareness_port::PresenceIdentity;
+use crate::application::ports::document_repository::DocumentListFilter;
+use crate::application::ports::document_retention_repository::DocumentRetention;
+use crate::application::ports::pdf_renderer::UnsupportedPdfText;
+use crate::application::ports::realtime_port::content_version;
+use crate::application::services::markdown::RenderOptions;
+use crate::application::services::realtime::snapshot::RetentionPolicy;
 use crate::application::use_cases::documents::create_document::CreateDocument;
 use crate::application::use_cases::documents::delete_document::DeleteDocument;
-use crate::application::use_cases::documents::download_document::DownloadDocument as DownloadDocumentUseCase;
+use crate::application::use_cases::documents::document_appearance::{
+    AppearanceError, UpdateDocumentAppearance,
+};
+use crate::application::use_cases::documents::document_capability::GetDocumentCapability;
+use crate::application::use_cases::documents::document_lock::SetDocumentLock;
+use crate::application::use_cases::documents::document_retention::{
+    GetDocumentRetention, UpdateDocumentRetention,
+};
+use crate::application::use_cases::documents::download_document::{
+    DownloadDocument as DownloadDocumentUseCase, DownloadFormat,
+};
+use crate::application::use_cases::documents::export_document::{
+    ExportDocument as ExportDocumentUseCase, ExportFormat,
+};
 use crate::application::use_cases::documents::get_backlinks::GetBacklinks;
 use crate::application::use_cases::documents::get_document::GetDocument;
 use crate::application::use_cases::documents::get_outgoing_links::GetOutgoingLinks;
+use crate::application::use_cases::documents::home_document::{
+    GetHomeDocument, HomeDocumentError, SetHomeDocument,
+};
 use crate::application::use_cases::documents::list_documents::ListDocuments;
+use crate::application::use_cases::documents::list_mentions::ListMentions;
+use crate::application::use_cases::documents::list_recent::ListRecentDocuments;
+use crate::application::use_cases::documents::render_tree::{
+    MAX_TREE_BYTES, MAX_TREE_DOCUMENTS, RenderDocumentTree,
+};
 use crate::application::use_cases::documents::search_documents::SearchDocuments;
 use crate::application::use_cases::documents::update_document::UpdateDocument;
+use crate::application::use_cases::documents::user_access::{
+    GrantDocumentUserAccess, ListDocumentUserAccess, RevokeDocumentUserAccess, UserAccessError,
+};
+use crate::application::use_cases::documents::versions::{
+    DiffDocumentVersions, GetDocumentVersionContent, ListDocumentVersions, RestoreDocumentVersion,
+};
+use crate::application::use_cases::documents::write_content::{
+    ExpectedVersion, WriteContentError, WriteDocumentContent,
+};
 use crate::bootstrap::app_context::AppContext;
 use crate::domain::documents::document as domain;
 use crate::presentation::http::auth::{self, Bearer};
+use crate::presentation::http::content_type;
+use crate::presentation::http::git::GitDiffResult;
+use crate::presentation::http::markdown::{
+    self as markdown_http, RenderOptionsPayload, RenderResponseBody,
+};
+use crate::presentation::http::rate_limit;
+use crate::presentation::http::timeout;
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct Document {
@@ -32,11 +76,25 @@ pub struct Document {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub path: Option<String>,
+    /// Emoji or named icon shown next to the document in the tree.
+    pub icon: Option<String>,
+    /// Hex color (`#rrggbb`) used to tint the document in the tree.
+    pub color: Option<String>,
+    /// Links pointing at this document; only set when `include_link_counts=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backlink_count: Option<i64>,
+    /// Links from this document; only set when `include_link_counts=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outgoing_count: Option<i64>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct DocumentListResponse {
     pub items: Vec<Document>,
+    /// Documents matching the request across all pages.
+    pub total: i64,
+    /// Offset to request the following page with; absent on the last page.
+    pub next_offset: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -63,8 +121,9 @@ impl Default for UpdateDocumentRequest {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum DoubleOption<T> {
+    #[default]
     NotProvided,
     Null,
     Some(T),
@@ -81,44 +140,118 @@ where
     })
 }
 
-impl<T> Default for DoubleOption<T> {
-    fn default() -> Self {
-        DoubleOption::NotProvided
-    }
-}
-
 // Uses AppContext as router state
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct ListDocumentsQuery {
     pub query: Option<String>,
     pub tag: Option<String>,
+    pub r#type: Option<String>,
+    pub updated_since: Option<chrono::DateTime<chrono::Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub include_link_counts: Option<bool>,
 }
 
 #[utoipa::path(get, path = "/api/documents", tag = "Documents",
     params(
         ("query" = Option<String>, Query, description = "Search query"),
-        ("tag" = Option<String>, Query, description = "Filter by tag")
+        ("tag" = Option<String>, Query, description = "Filter by tag"),
+        ("type" = Option<String>, Query, description = "Filter by type: document or folder"),
+        ("updated_since" = Option<chrono::DateTime<chrono::Utc>>, Query, description = "Only documents updated at or after this time"),
+        ("limit" = Option<i64>, Query, description = "Page size (default 100, max 500)"),
+        ("offset" = Option<i64>, Query, description = "Number of documents to skip"),
+        ("include_link_counts" = Option<bool>, Query, description = "Include backlink and outgoing link counts per document")
     ),
-    responses((status = 200, body = DocumentListResponse)))]
+    responses((status = 200, body = DocumentListResponse), (status = 400, description = "Invalid type filter")))]
 pub async fn list_documents(
     State(ctx): State<AppContext>,
     bearer: Bearer,
     q: Option<Query<ListDocumentsQuery>>,
 ) -> Result<Json<DocumentListResponse>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
-    let (qstr, tag) = q.map(|Query(v)| (v.query, v.tag)).unwrap_or((None, None));
+    let q = q.map(|Query(v)| v).unwrap_or_default();
+    let doc_type = match q.r#type.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(t @ ("document" | "folder")) => Some(t.to_string()),
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+    let filter = DocumentListFilter {
+        query: q.query,
+        tag: q.tag,
+        doc_type,
+        updated_since: q.updated_since,
+        include_link_counts: q.include_link_counts.unwrap_or(false),
+    };
 
     let repo = ctx.document_repo();
     let uc = ListDocuments {
         repo: repo.as_ref(),
     };
-    let docs: Vec<domain::Document> = uc
-        .execute(user_id, qstr, tag)
+    let listing = uc
+        .execute(user_id, filter, q.limit, q.offset)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    let link_counts = listing.page.link_counts;
+    let items: Vec<Document> = listing
+        .page
+        .items
+        .into_iter()
+        .map(|d| {
+            let counts = link_counts
+                .as_ref()
+                .map(|c| c.get(&d.id).copied().unwrap_or_default());
+            Document {
+                id: d.id,
+                title: d.title,
+                parent_id: d.parent_id,
+                r#type: d.doc_type,
+                created_at: d.created_at,
+                updated_at: d.updated_at,
+                path: d.path,
+                icon: d.icon,
+                color: d.color,
+                backlink_count: counts.map(|c| c.backlinks),
+                outgoing_count: counts.map(|c| c.outgoing),
+            }
+        })
+        .collect();
+    Ok(Json(DocumentListResponse {
+        items,
+        total: listing.page.total,
+        next_offset: listing.next_offset,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecentDocumentsQuery {
+    pub limit: Option<i64>,
+    pub include_shared: Option<bool>,
+}
+
+#[utoipa::path(get, path = "/api/me/recent", tag = "Documents", operation_id = "listRecentDocuments",
+    params(
+        ("limit" = Option<i64>, Query, description = "Maximum number of documents (default 20, max 100)"),
+        ("include_shared" = Option<bool>, Query, description = "Include documents shared with the caller (default true)")
+    ),
+    responses((status = 200, body = DocumentListResponse)))]
+pub async fn list_recent_documents(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Query(q): Query<RecentDocumentsQuery>,
+) -> Result<Json<DocumentListResponse>, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let repo = ctx.document_repo();
+    let uc = ListRecentDocuments {
+        repo: repo.as_ref(),
+    };
+    let docs = uc
+        .execute(user_id, q.limit, q.include_shared.unwrap_or(true))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     let items: Vec<Document> = docs
         .into_iter()
         .map(|d| Document {
@@ -129,9 +262,159 @@ pub async fn list_documents(
             created_at: d.created_at,
             updated_at: d.updated_at,
             path: d.path,
+            icon: d.icon,
+            color: d.color,
+            backlink_count: None,
+            outgoing_count: None,
+        })
+        .collect();
+    Ok(Json(DocumentListResponse {
+        total: items.len() as i64,
+        items,
+        next_offset: None,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MentionsQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MentionItem {
+    pub document_id: Uuid,
+    pub title: String,
+    pub mentioned_by: Option<Uuid>,
+    pub mentioned_by_name: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MentionListResponse {
+    pub items: Vec<MentionItem>,
+}
+
+#[utoipa::path(get, path = "/api/me/mentions", tag = "Documents", operation_id = "listMentions",
+    params(("limit" = Option<i64>, Query, description = "Maximum number of mentions (default 20, max 100)")),
+    responses((status = 200, body = MentionListResponse)))]
+pub async fn list_mentions(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Query(q): Query<MentionsQuery>,
+) -> Result<Json<MentionListResponse>, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let repo = ctx.mention_repo();
+    let uc = ListMentions {
+        repo: repo.as_ref(),
+    };
+    let rows = uc
+        .execute(user_id, q.limit)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let items = rows
+        .into_iter()
+        .map(|m| MentionItem {
+            document_id: m.document_id,
+            title: m.title,
+            mentioned_by: m.mentioned_by,
+            mentioned_by_name: m.mentioned_by_name,
+            created_at: m.created_at,
         })
         .collect();
-    Ok(Json(DocumentListResponse { items }))
+    Ok(Json(MentionListResponse { items }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HomeDocumentResponse {
+    /// The caller's home document; null when none is set or it is no longer viewable.
+    pub document: Option<Document>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetHomeDocumentRequest {
+    /// Document to open on sign-in; null clears the home document.
+    pub document_id: Option<Uuid>,
+}
+
+fn home_response(doc: Option<domain::Document>) -> Json<HomeDocumentResponse> {
+    Json(HomeDocumentResponse {
+        document: doc.map(|d| Document {
+            id: d.id,
+            title: d.title,
+            parent_id: d.parent_id,
+            r#type: d.doc_type,
+            created_at: d.created_at,
+            updated_at: d.updated_at,
+            path: d.path,
+            icon: d.icon,
+            color: d.color,
+            backlink_count: None,
+            outgoing_count: None,
+        }),
+    })
+}
+
+#[utoipa::path(get, path = "/api/me/home", tag = "Documents", operation_id = "getHomeDocument",
+    responses((status = 200, body = HomeDocumentResponse)))]
+pub async fn get_home_document(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+) -> Result<Json<HomeDocumentResponse>, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let users = ctx.user_repo();
+    let repo = ctx.document_repo();
+    let shares = ctx.share_access_port();
+    let access = ctx.access_repo();
+    let uc = GetHomeDocument {
+        users: users.as_ref(),
+        repo: repo.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        access: access.as_ref(),
+    };
+    let doc = uc
+        .execute(user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(home_response(doc))
+}
+
+#[utoipa::path(put, path = "/api/me/home", tag = "Documents", operation_id = "setHomeDocument",
+    request_body = SetHomeDocumentRequest,
+    responses(
+        (status = 200, body = HomeDocumentResponse),
+        (status = 400, description = "Folders cannot be the home document"),
+        (status = 404, description = "Document not found")
+    ))]
+pub async fn set_home_document(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Json(req): Json<SetHomeDocumentRequest>,
+) -> Result<Json<HomeDocumentResponse>, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let users = ctx.user_repo();
+    let repo = ctx.document_repo();
+    let shares = ctx.share_access_port();
+    let access = ctx.access_repo();
+    let uc = SetHomeDocument {
+        users: users.as_ref(),
+        repo: repo.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        access: access.as_ref(),
+    };
+    let doc = uc
+        .execute(user_id, req.document_id)
+        .await
+        .map_err(|e| match e {
+            HomeDocumentError::NotFound => StatusCode::NOT_FOUND,
+            HomeDocumentError::NotADocument => StatusCode::BAD_REQUEST,
+            HomeDocumentError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        })?;
+    Ok(home_response(doc))
 }
 
 #[utoipa::path(post, path = "/api/documents", tag = "Documents", request_body = CreateDocumentRequest, responses((status = 200, body = Document)))]
@@ -140,14 +423,16 @@ pub async fn create_document(
     bearer: Bearer,
     Json(req): Json<CreateDocumentRequest>,
 ) -> Result<Json<Document>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let title = req.title.unwrap_or_else(|| "Untitled".into());
     let dtype = req.r#type.unwrap_or_else(|| "document".into());
 
     let repo = ctx.document_repo();
+    let shares = ctx.shares_repo();
     let uc = CreateDocument {
         repo: repo.as_ref(),
+        shares: shares.as_ref(),
     };
     let doc = uc
         .execute(user_id, &title, req.parent_id, &dtype)
@@ -162,6 +447,10 @@ pub async fn create_document(
         created_at: doc.created_at,
         updated_at: doc.updated_at,
         path: doc.path,
+        icon: doc.icon,
+        color: doc.color,
+        backlink_count: None,
+        outgoing_count: None,
     }))
 }
 
@@ -176,7 +465,7 @@ pub async fn get_document(
 ) -> Result<Json<Document>, StatusCode> {
     let token = params.get("token").map(|s| s.as_str());
     let actor =
-        auth::resolve_actor_from_parts(&ctx.cfg, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
+        auth::resolve_actor_from_parts(&ctx, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
 
     let repo = ctx.document_repo();
     let share_access = ctx.share_access_port();
@@ -184,6 +473,7 @@ pub async fn get_document(
     let uc = GetDocument {
         repo: repo.as_ref(),
         shares: share_access.as_ref(),
+        policy: ctx.access_policy(),
         access: access_repo.as_ref(),
     };
     let doc = uc
@@ -200,6 +490,10 @@ pub async fn get_document(
         created_at: doc.created_at,
         updated_at: doc.updated_at,
         path: doc.path,
+        icon: doc.icon,
+        color: doc.color,
+        backlink_count: None,
+        outgoing_count: None,
     }))
 }
 
@@ -209,7 +503,7 @@ pub async fn delete_document(
     bearer: Bearer,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.document_repo();
     let storage = ctx.storage_port();
@@ -228,21 +522,102 @@ pub async fn delete_document(
     }
 }
 
-#[utoipa::path(get, path = "/api/documents/{id}/content", tag = "Documents", params(("id" = Uuid, Path, description = "Document ID"),), responses((status = 200)))]
+async fn set_document_lock(
+    ctx: &AppContext,
+    bearer: Bearer,
+    id: Uuid,
+    locked: bool,
+) -> Result<StatusCode, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let documents = ctx.document_repo();
+    let realtime = ctx.realtime_engine();
+    let uc = SetDocumentLock {
+        access: access.as_ref(),
+        documents: documents.as_ref(),
+        realtime: realtime.as_ref(),
+    };
+    let ok = uc.execute(id, user_id, locked).await.map_err(|e| {
+        tracing::error!(document_id = %id, error = ?e, "set_document_lock_failed");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if ok {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[utoipa::path(post, path = "/api/documents/{id}/lock", tag = "Documents", params(("id" = Uuid, Path, description = "Document ID"),), responses((status = 204, description = "Document is read-only for everyone"), (status = 404, description = "Not found or not the owner")))]
+pub async fn lock_document(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode> {
+    set_document_lock(&ctx, bearer, id, true).await
+}
+
+#[utoipa::path(post, path = "/api/documents/{id}/unlock", tag = "Documents", params(("id" = Uuid, Path, description = "Document ID"),), responses((status = 204, description = "Document is editable again"), (status = 404, description = "Not found or not the owner")))]
+pub async fn unlock_document(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode> {
+    set_document_lock(&ctx, bearer, id, false).await
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentContentResponse {
+    pub content: String,
+    /// Content version; send it back as `If-Match` when writing.
+    pub version: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateDocumentContentRequest {
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentContentQuery {
+    pub token: Option<String>,
+}
+
+fn content_response(status: StatusCode, content: String, version: String) -> Response {
+    let mut headers = HeaderMap::new();
+    if let Ok(etag) = HeaderValue::from_str(&format!("\"{}\"", version)) {
+        headers.insert(header::ETAG, etag);
+    }
+    (
+        status,
+        headers,
+        Json(DocumentContentResponse { content, version }),
+    )
+        .into_response()
+}
+
+#[utoipa::path(get, path = "/api/documents/{id}/content", tag = "Documents", params(("id" = Uuid, Path, description = "Document ID"),), responses((status = 200, body = DocumentContentResponse)))]
 pub async fn get_document_content(
     State(ctx): State<AppContext>,
     bearer: Bearer,
     Path(id): Path<Uuid>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+) -> Result<Response, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     // authorization via access policy
     let share_access = ctx.share_access_port();
     let access_repo = ctx.access_repo();
     let actor = access::Actor::User(user_id);
-    access::require_view(access_repo.as_ref(), share_access.as_ref(), &actor, id)
-        .await
-        .map_err(|_| StatusCode::NOT_FOUND)?;
+    access::require_view(
+        access_repo.as_ref(),
+        share_access.as_ref(),
+        ctx.access_policy(),
+        &actor,
+        id,
+    )
+    .await
+    .map_err(|_| StatusCode::NOT_FOUND)?;
     // Load content via realtime engine abstraction
     let realtime = ctx.realtime_engine();
     let content = realtime
@@ -253,7 +628,178 @@ pub async fn get_document_content(
             StatusCode::INTERNAL_SERVER_ERROR
         })?
         .unwrap_or_default();
-    Ok(Json(serde_json::json!({"content": content})))
+    let version = content_version(&content);
+    Ok(content_response(StatusCode::OK, content, version))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PresenceClient {
+    pub client_id: u64,
+    /// Set for signed-in users; share-link and public viewers are anonymous.
+    pub user_id: Option<Uuid>,
+    pub anonymous: bool,
+    /// Name from the user directory, not the one the client reports.
+    pub display_name: Option<String>,
+    /// Awareness state as published by the client.
+    #[schema(value_type = Object)]
+    pub state: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentPresenceResponse {
+    pub clients: Vec<PresenceClient>,
+}
+
+#[utoipa::path(get, path = "/api/documents/{id}/presence", tag = "Documents", params(("id" = Uuid, Path, description = "Document ID"),), responses((status = 200, body = DocumentPresenceResponse)))]
+pub async fn get_document_presence(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DocumentPresenceResponse>, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let share_access = ctx.share_access_port();
+    let access_repo = ctx.access_repo();
+    let actor = access::Actor::User(user_id);
+    access::require_view(
+        access_repo.as_ref(),
+        share_access.as_ref(),
+        ctx.access_policy(),
+        &actor,
+        id,
+    )
+    .await
+    .map_err(|_| StatusCode::NOT_FOUND)?;
+    let entries = ctx
+        .realtime_engine()
+        .presence(&id.to_string())
+        .await
+        .map_err(|e| {
+            tracing::error!(document_id = %id, error = ?e, "realtime_presence_failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let clients = entries
+        .into_iter()
+        .map(|entry| {
+            let user_id = match entry.identity {
+                PresenceIdentity::User(user_id) => Some(user_id),
+                PresenceIdentity::Anonymous => None,
+            };
+            PresenceClient {
+                client_id: entry.client_id,
+                user_id,
+                anonymous: user_id.is_none(),
+                display_name: entry.display_name,
+                state: entry.state,
+            }
+        })
+        .collect();
+    Ok(Json(DocumentPresenceResponse { clients }))
+}
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentCapability {
+    None,
+    View,
+    Comment,
+    Edit,
+}
+
+impl From<access::Capability> for DocumentCapability {
+    fn from(cap: access::Capability) -> Self {
+        match cap {
+            access::Capability::None => Self::None,
+            access::Capability::View => Self::View,
+            access::Capability::Comment => Self::Comment,
+            access::Capability::Edit => Self::Edit,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentCapabilityResponse {
+    /// Effective access for the caller, after sharing policy and the document lock.
+    pub capability: DocumentCapability,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentCapabilityQuery {
+    pub token: Option<String>,
+}
+
+#[utoipa::path(get, path = "/api/documents/{id}/capability", tag = "Documents", operation_id = "getDocumentCapability",
+    params(("id" = Uuid, Path, description = "Document ID"), ("token" = Option<String>, Query, description = "Share token (optional)")),
+    responses((status = 200, body = DocumentCapabilityResponse), (status = 401, description = "Unauthorized")))]
+pub async fn get_document_capability(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Query(q): Query<DocumentCapabilityQuery>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DocumentCapabilityResponse>, StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, q.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let share_access = ctx.share_access_port();
+    let access_repo = ctx.access_repo();
+    let uc = GetDocumentCapability {
+        access: access_repo.as_ref(),
+        shares: share_access.as_ref(),
+        policy: ctx.access_policy(),
+    };
+    let capability = uc.execute(&actor, id).await.into();
+    Ok(Json(DocumentCapabilityResponse { capability }))
+}
+
+#[utoipa::path(put, path = "/api/documents/{id}/content", tag = "Documents", operation_id = "updateDocumentContent",
+    params(
+        ("id" = Uuid, Path, description = "Document ID"),
+        ("If-Match" = String, Header, description = "Version the edit is based on, or * to overwrite"),
+        ("token" = Option<String>, Query, description = "Share token (optional)")
+    ),
+    request_body = UpdateDocumentContentRequest,
+    responses(
+        (status = 200, body = DocumentContentResponse),
+        (status = 403, description = "Edit permission required"),
+        (status = 404, description = "Document not found"),
+        (status = 409, description = "Document changed; body carries the current content", body = DocumentContentResponse),
+        (status = 428, description = "If-Match header required")
+    ))]
+pub async fn update_document_content(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Path(id): Path<Uuid>,
+    Query(q): Query<DocumentContentQuery>,
+    headers: HeaderMap,
+    Json(req): Json<UpdateDocumentContentRequest>,
+) -> Result<Response, StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, q.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let expected = headers
+        .get(header::IF_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(ExpectedVersion::from_if_match)
+        .ok_or(StatusCode::PRECONDITION_REQUIRED)?;
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let realtime = ctx.realtime_engine();
+    let uc = WriteDocumentContent {
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        realtime: realtime.as_ref(),
+    };
+    match uc.execute(&actor, id, &expected, &req.content).await {
+        Ok(version) => Ok(content_response(StatusCode::OK, req.content, version)),
+        Err(WriteContentError::Conflict { version, content }) => {
+            Ok(content_response(StatusCode::CONFLICT, content, version))
+        }
+        Err(WriteContentError::NotFound) => Err(StatusCode::NOT_FOUND),
+        Err(WriteContentError::Forbidden) => Err(StatusCode::FORBIDDEN),
+        Err(WriteContentError::Repository(e)) => {
+            tracing::error!(document_id = %id, error = ?e, "document_content_write_failed");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
 }
 
 #[allow(dead_code)]
@@ -267,10 +813,13 @@ pub struct DocumentArchiveBinary(#[schema(value_type = String, format = Binary)]
     operation_id = "download_document",
     params(
         ("id" = Uuid, Path, description = "Document ID"),
-        ("token" = Option<String>, Query, description = "Share token (optional)")
+        ("token" = Option<String>, Query, description = "Share token (optional)"),
+        ("format" = Option<String>, Query, description = "archive (default, zip with attachments) or markdown"),
+        ("download" = Option<String>, Query, description = "Set to 0 to serve inline instead of as an attachment")
     ),
     responses(
         (status = 200, description = "Document archive", body = DocumentArchiveBinary, content_type = "application/zip"),
+        (status = 400, description = "Unsupported format"),
         (status = 401, description = "Unauthorized"),
         (status = 404, description = "Document not found")
     )
@@ -281,9 +830,18 @@ pub async fn download_document(
     Query(params): Query<std::collections::HashMap<String, String>>,
     Path(id): Path<Uuid>,
 ) -> Result<Response, StatusCode> {
+    let format = match params.get("format") {
+        Some(raw) => raw
+            .parse::<DownloadFormat>()
+            .map_err(|_| StatusCode::BAD_REQUEST)?,
+        None => DownloadFormat::default(),
+    };
+    let download = params
+        .get("download")
+        .is_none_or(|v| content_type::wants_download(Some(v)));
     let token = params.get("token").map(|s| s.as_str());
     let actor =
-        auth::resolve_actor_from_parts(&ctx.cfg, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
+        auth::resolve_actor_from_parts(&ctx, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
 
     let documents = ctx.document_repo();
     let files = ctx.files_repo();
@@ -299,10 +857,11 @@ pub async fn download_document(
         realtime: realtime.as_ref(),
         access: access.as_ref(),
         shares: shares.as_ref(),
+        policy: ctx.access_policy(),
     };
 
-    let download = uc
-        .execute(&actor, id)
+    let file = uc
+        .execute(&actor, id, format)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
@@ -310,37 +869,154 @@ pub async fn download_document(
     let mut headers = HeaderMap::new();
     headers.insert(
         axum::http::header::CONTENT_TYPE,
-        HeaderValue::from_static("application/zip"),
+        HeaderValue::from_static(file.content_type),
     );
     headers.insert(
         axum::http::header::HeaderName::from_static("x-content-type-options"),
         HeaderValue::from_static("nosniff"),
     );
-    let disposition = format!("attachment; filename=\"{}\"", download.filename);
-    let content_disposition =
-        HeaderValue::from_str(&disposition).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    headers.insert(axum::http::header::CONTENT_DISPOSITION, content_disposition);
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        content_type::disposition(&file.filename, file.content_type, download),
+    );
 
-    Ok((headers, download.bytes).into_response())
+    Ok((headers, file.bytes).into_response())
 }
 
-#[utoipa::path(patch, path = "/api/documents/{id}", tag = "Documents", request_body = UpdateDocumentRequest,
-    params(("id" = Uuid, Path, description = "Document ID"),), responses((status = 200, body = Document)))]
-pub async fn update_document(
+#[allow(dead_code)]
+#[derive(ToSchema)]
+pub struct DocumentExportBinary(#[schema(value_type = String, format = Binary)] Vec<u8>);
+
+#[derive(Debug, Deserialize)]
+pub struct ExportDocumentQuery {
+    pub format: Option<String>,
+    pub token: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/export",
+    tag = "Documents",
+    operation_id = "export_document",
+    params(
+        ("id" = Uuid, Path, description = "Document ID"),
+        ("format" = Option<String>, Query, description = "Export format: pdf (default) or html"),
+        ("token" = Option<String>, Query, description = "Share token (optional)")
+    ),
+    responses(
+        (status = 200, description = "Rendered document", body = DocumentExportBinary, content_type = "application/pdf"),
+        (status = 400, description = "Unsupported format"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Document not found"),
+        (status = 422, description = "Document contains characters the PDF renderer cannot encode")
+    )
+)]
+pub async fn export_document(
     State(ctx): State<AppContext>,
-    bearer: Bearer,
+    bearer: Option<Bearer>,
+    Query(params): Query<ExportDocumentQuery>,
     Path(id): Path<Uuid>,
-    Json(req): Json<UpdateDocumentRequest>,
-) -> Result<Json<Document>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+) -> Result<Response, StatusCode> {
+    let format = params
+        .format
+        .as_deref()
+        .unwrap_or("pdf")
+        .parse::<ExportFormat>()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, params.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let documents = ctx.document_repo();
+    let storage = ctx.storage_port();
+    let realtime = ctx.realtime_engine();
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let pdf = ctx.pdf_renderer();
+
+    let uc = ExportDocumentUseCase {
+        documents: documents.as_ref(),
+        storage: storage.as_ref(),
+        realtime: realtime.as_ref(),
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        pdf: pdf.as_ref(),
+    };
+
+    let user_scope = match actor {
+        access::Actor::User(uid) => Some(uid),
+        _ => None,
+    };
+    let options = RenderOptions::default().with_defaults(&ctx.cfg.render_defaults);
+    let export = uc
+        .execute(&actor, id, format, options, |content, options| async {
+            markdown_http::render_in_scope(&ctx, user_scope, content, options)
+                .await
+                .map(|rendered| rendered.html)
+                .map_err(|status| anyhow::anyhow!("markdown render failed: {status}"))
+        })
+        .await
+        .map_err(|e| {
+            if e.downcast_ref::<UnsupportedPdfText>().is_some() {
+                tracing::warn!(document_id = %id, error = %e, "document_export_unsupported_text");
+                return StatusCode::UNPROCESSABLE_ENTITY;
+            }
+            tracing::error!(document_id = %id, error = ?e, "document_export_failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static(export.content_type),
+    );
+    headers.insert(
+        axum::http::header::HeaderName::from_static("x-content-type-options"),
+        HeaderValue::from_static("nosniff"),
+    );
+    let ascii_name: String = export
+        .filename
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let disposition = format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        ascii_name,
+        urlencoding::encode(&export.filename)
+    );
+    let content_disposition =
+        HeaderValue::from_str(&disposition).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    headers.insert(axum::http::header::CONTENT_DISPOSITION, content_disposition);
+
+    Ok((headers, export.bytes).into_response())
+}
+
+#[utoipa::path(patch, path = "/api/documents/{id}", tag = "Documents", request_body = UpdateDocumentRequest,
+    params(("id" = Uuid, Path, description = "Document ID"),), responses((status = 200, body = Document)))]
+pub async fn update_document(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateDocumentRequest>,
+) -> Result<Json<Document>, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.document_repo();
     let storage = ctx.storage_port();
     let realtime = ctx.realtime_engine();
+    let shares = ctx.shares_repo();
     let uc = UpdateDocument {
         repo: repo.as_ref(),
         storage: storage.as_ref(),
         realtime: realtime.as_ref(),
+        shares: shares.as_ref(),
     };
     let parent_opt = match req.parent_id.clone() {
         DoubleOption::NotProvided => None,
@@ -360,23 +1036,631 @@ pub async fn update_document(
         created_at: doc.created_at,
         updated_at: doc.updated_at,
         path: doc.path,
+        icon: doc.icon,
+        color: doc.color,
+        backlink_count: None,
+        outgoing_count: None,
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateDocumentAppearanceRequest {
+    /// An emoji or a named icon (e.g. `folder`, `star`); `null` clears it.
+    pub icon: Option<String>,
+    /// Hex color such as `#3b82f6`; `null` clears it.
+    pub color: Option<String>,
+}
+
+#[utoipa::path(put, path = "/api/documents/{id}/appearance", tag = "Documents", request_body = UpdateDocumentAppearanceRequest,
+    params(("id" = Uuid, Path, description = "Document ID"),),
+    responses((status = 200, body = Document), (status = 400, description = "Invalid icon or color"), (status = 403, description = "Edit permission required"), (status = 404)))]
+pub async fn update_document_appearance(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateDocumentAppearanceRequest>,
+) -> Result<Json<Document>, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let documents = ctx.document_repo();
+    let uc = UpdateDocumentAppearance {
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        documents: documents.as_ref(),
+    };
+    let doc = uc
+        .execute(user_id, id, req.icon.as_deref(), req.color.as_deref())
+        .await
+        .map_err(|e| match e {
+            AppearanceError::NotFound => StatusCode::NOT_FOUND,
+            AppearanceError::Forbidden => StatusCode::FORBIDDEN,
+            AppearanceError::InvalidIcon | AppearanceError::InvalidColor => StatusCode::BAD_REQUEST,
+            AppearanceError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        })?;
+    Ok(Json(Document {
+        id: doc.id,
+        title: doc.title,
+        parent_id: doc.parent_id,
+        r#type: doc.doc_type,
+        created_at: doc.created_at,
+        updated_at: doc.updated_at,
+        path: doc.path,
+        icon: doc.icon,
+        color: doc.color,
+        backlink_count: None,
+        outgoing_count: None,
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateDocumentRetentionRequest {
+    /// Snapshots kept for this document; `null` uses the server default.
+    pub snapshot_keep_versions: Option<i64>,
+    /// Updates kept behind the latest sequence; `null` uses the server default.
+    pub updates_keep_window: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentRetentionResponse {
+    pub snapshot_keep_versions: Option<i64>,
+    pub updates_keep_window: Option<i64>,
+    pub effective_snapshot_keep_versions: i64,
+    pub effective_updates_keep_window: i64,
+}
+
+fn retention_response(ctx: &AppContext, retention: DocumentRetention) -> DocumentRetentionResponse {
+    let effective = RetentionPolicy {
+        keep_versions: ctx.cfg.snapshot_keep_versions,
+        updates_keep_window: ctx.cfg.updates_keep_window,
+    }
+    .with_override(Some(&retention));
+    DocumentRetentionResponse {
+        snapshot_keep_versions: retention.snapshot_keep_versions,
+        updates_keep_window: retention.updates_keep_window,
+        effective_snapshot_keep_versions: effective.keep_versions,
+        effective_updates_keep_window: effective.updates_keep_window,
+    }
+}
+
+#[utoipa::path(get, path = "/api/documents/{id}/retention", tag = "Documents", operation_id = "getDocumentRetention",
+    params(("id" = Uuid, Path, description = "Document ID"),),
+    responses((status = 200, body = DocumentRetentionResponse), (status = 404, description = "Document not found")))]
+pub async fn get_document_retention(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<Uuid>,
+) -> Result<Json<DocumentRetentionResponse>, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let retention = ctx.document_retention_repo();
+    let uc = GetDocumentRetention {
+        access: access.as_ref(),
+        retention: retention.as_ref(),
+    };
+    let current = uc
+        .execute(id, user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(retention_response(&ctx, current)))
+}
+
+#[utoipa::path(put, path = "/api/documents/{id}/retention", tag = "Documents", operation_id = "updateDocumentRetention",
+    request_body = UpdateDocumentRetentionRequest,
+    params(("id" = Uuid, Path, description = "Document ID"),),
+    responses((status = 200, body = DocumentRetentionResponse), (status = 400, description = "Invalid retention"), (status = 404, description = "Document not found")))]
+pub async fn update_document_retention(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateDocumentRetentionRequest>,
+) -> Result<Json<DocumentRetentionResponse>, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    if req.snapshot_keep_versions.is_some_and(|v| v < 1)
+        || req.updates_keep_window.is_some_and(|v| v < 0)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let access = ctx.access_repo();
+    let retention = ctx.document_retention_repo();
+    let uc = UpdateDocumentRetention {
+        access: access.as_ref(),
+        retention: retention.as_ref(),
+    };
+    let updated = uc
+        .execute(
+            id,
+            user_id,
+            DocumentRetention {
+                snapshot_keep_versions: req.snapshot_keep_versions,
+                updates_keep_window: req.updates_keep_window,
+            },
+        )
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(retention_response(&ctx, updated)))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentUserAccessItem {
+    pub user_id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub permission: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GrantDocumentAccessRequest {
+    /// Target user; either `user_id` or `email` must be given.
+    pub user_id: Option<Uuid>,
+    pub email: Option<String>,
+    /// `view` or `edit`
+    pub permission: String,
+}
+
+fn user_access_status(err: UserAccessError) -> StatusCode {
+    match err {
+        UserAccessError::NotFound | UserAccessError::UserNotFound => StatusCode::NOT_FOUND,
+        UserAccessError::InvalidPermission | UserAccessError::OwnerGrant => StatusCode::BAD_REQUEST,
+        UserAccessError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+async fn list_user_access_items(
+    ctx: &AppContext,
+    user_id: Uuid,
+    doc_id: Uuid,
+) -> Result<Vec<DocumentUserAccessItem>, StatusCode> {
+    let access = ctx.access_repo();
+    let grants = ctx.document_user_access_repo();
+    let uc = ListDocumentUserAccess {
+        access: access.as_ref(),
+        grants: grants.as_ref(),
+    };
+    let items = uc
+        .execute(user_id, doc_id)
+        .await
+        .map_err(user_access_status)?;
+    Ok(items
+        .into_iter()
+        .map(|g| DocumentUserAccessItem {
+            user_id: g.user_id,
+            email: g.email,
+            name: g.name,
+            permission: g.permission,
+            created_at: g.created_at,
+        })
+        .collect())
+}
+
+#[utoipa::path(get, path = "/api/documents/{id}/access", tag = "Documents", operation_id = "listDocumentUserAccess",
+    params(("id" = Uuid, Path, description = "Document ID"),),
+    responses((status = 200, body = [DocumentUserAccessItem]), (status = 404, description = "Document not found")))]
+pub async fn list_document_user_access(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<DocumentUserAccessItem>>, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    Ok(Json(list_user_access_items(&ctx, user_id, id).await?))
+}
+
+#[utoipa::path(put, path = "/api/documents/{id}/access", tag = "Documents", operation_id = "grantDocumentUserAccess",
+    request_body = GrantDocumentAccessRequest,
+    params(("id" = Uuid, Path, description = "Document ID"),),
+    responses((status = 200, body = [DocumentUserAccessItem]), (status = 400, description = "Invalid grant"), (status = 404, description = "Document or user not found")))]
+pub async fn grant_document_user_access(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<Uuid>,
+    Json(req): Json<GrantDocumentAccessRequest>,
+) -> Result<Json<Vec<DocumentUserAccessItem>>, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let users = ctx.user_repo();
+    let target_id = match (req.user_id, req.email.as_deref().map(str::trim)) {
+        (Some(target_id), _) => target_id,
+        (None, Some(email)) if !email.is_empty() => {
+            users
+                .find_by_email(email)
+                .await
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+                .ok_or(StatusCode::NOT_FOUND)?
+                .id
+        }
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+    let access = ctx.access_repo();
+    let grants = ctx.document_user_access_repo();
+    let notifications = ctx.notifications();
+    let uc = GrantDocumentUserAccess {
+        access: access.as_ref(),
+        grants: grants.as_ref(),
+        users: users.as_ref(),
+        notifier: Some(notifications.as_ref()),
+    };
+    uc.execute(user_id, id, target_id, &req.permission)
+        .await
+        .map_err(user_access_status)?;
+    Ok(Json(list_user_access_items(&ctx, user_id, id).await?))
+}
+
+#[utoipa::path(delete, path = "/api/documents/{id}/access/{user_id}", tag = "Documents", operation_id = "revokeDocumentUserAccess",
+    params(("id" = Uuid, Path, description = "Document ID"), ("user_id" = Uuid, Path, description = "User whose access is revoked"),),
+    responses((status = 204), (status = 404, description = "Document or grant not found")))]
+pub async fn revoke_document_user_access(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path((id, target_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let grants = ctx.document_user_access_repo();
+    let uc = RevokeDocumentUserAccess {
+        access: access.as_ref(),
+        grants: grants.as_ref(),
+    };
+    let revoked = uc
+        .execute(user_id, id, target_id)
+        .await
+        .map_err(user_access_status)?;
+    if revoked {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentVersionsQuery {
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentVersionItem {
+    pub version: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentVersionContentResponse {
+    pub version: i64,
+    pub content: String,
+}
+
+#[utoipa::path(get, path = "/api/documents/{id}/versions", tag = "Documents", operation_id = "listDocumentVersions",
+    params(("id" = Uuid, Path, description = "Document ID"), ("token" = Option<String>, Query, description = "Share token (optional)")),
+    responses((status = 200, body = [DocumentVersionItem]), (status = 404, description = "Document not found")))]
+pub async fn list_document_versions(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Path(id): Path<Uuid>,
+    Query(q): Query<DocumentVersionsQuery>,
+) -> Result<Json<Vec<DocumentVersionItem>>, StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, q.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let versions = ctx.document_version_repo();
+    let uc = ListDocumentVersions {
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        versions: versions.as_ref(),
+    };
+    let items = uc
+        .execute(&actor, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(
+        items
+            .into_iter()
+            .map(|v| DocumentVersionItem {
+                version: v.version,
+                created_at: v.created_at,
+            })
+            .collect(),
+    ))
+}
+
+#[utoipa::path(get, path = "/api/documents/{id}/versions/{version}/content", tag = "Documents", operation_id = "getDocumentVersionContent",
+    params(("id" = Uuid, Path, description = "Document ID"), ("version" = i64, Path, description = "Snapshot version"), ("token" = Option<String>, Query, description = "Share token (optional)")),
+    responses((status = 200, body = DocumentVersionContentResponse), (status = 404, description = "Version not found")))]
+pub async fn get_document_version_content(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Path((id, version)): Path<(Uuid, i64)>,
+    Query(q): Query<DocumentVersionsQuery>,
+) -> Result<Json<DocumentVersionContentResponse>, StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, q.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let versions = ctx.document_version_repo();
+    let uc = GetDocumentVersionContent {
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        versions: versions.as_ref(),
+    };
+    let content = uc
+        .execute(&actor, id, version)
+        .await
+        .map_err(|e| {
+            tracing::error!(document_id = %id, version, error = ?e, "document_version_read_failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(DocumentVersionContentResponse { version, content }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DocumentVersionDiffQuery {
+    pub from: i64,
+    pub to: i64,
+    pub token: Option<String>,
+}
+
+#[utoipa::path(get, path = "/api/documents/{id}/versions/diff", tag = "Documents", operation_id = "diffDocumentVersions",
+    params(("id" = Uuid, Path, description = "Document ID"), ("from" = i64, Query, description = "Base version"), ("to" = i64, Query, description = "Compared version"), ("token" = Option<String>, Query, description = "Share token (optional)")),
+    responses((status = 200, body = GitDiffResult), (status = 404, description = "Version not found")))]
+pub async fn diff_document_versions(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Path(id): Path<Uuid>,
+    Query(q): Query<DocumentVersionDiffQuery>,
+) -> Result<Json<GitDiffResult>, StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, q.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let versions = ctx.document_version_repo();
+    let uc = DiffDocumentVersions {
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        versions: versions.as_ref(),
+    };
+    let diff = uc
+        .execute(&actor, id, q.from, q.to)
+        .await
+        .map_err(|e| {
+            tracing::error!(document_id = %id, error = ?e, "document_version_diff_failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(GitDiffResult::from(diff)))
+}
+
+#[utoipa::path(post, path = "/api/documents/{id}/versions/{version}/restore", tag = "Documents", operation_id = "restoreDocumentVersion",
+    params(("id" = Uuid, Path, description = "Document ID"), ("version" = i64, Path, description = "Snapshot version"), ("token" = Option<String>, Query, description = "Share token (optional)")),
+    responses((status = 200, body = DocumentVersionContentResponse), (status = 404, description = "Version not found")))]
+pub async fn restore_document_version(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Path((id, version)): Path<(Uuid, i64)>,
+    Query(q): Query<DocumentVersionsQuery>,
+) -> Result<Json<DocumentVersionContentResponse>, StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, q.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let versions = ctx.document_version_repo();
+    let realtime = ctx.realtime_engine();
+    let uc = RestoreDocumentVersion {
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        versions: versions.as_ref(),
+        realtime: realtime.as_ref(),
+    };
+    let content = uc
+        .execute(&actor, id, version)
+        .await
+        .map_err(|e| {
+            tracing::error!(document_id = %id, version, error = ?e, "document_version_restore_failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(DocumentVersionContentResponse { version, content }))
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct RenderDocumentRequest {
+    /// `doc_id` is always the rendered document; attachments are made absolute unless
+    /// `absolute_attachments` is false. `token` also authorizes share access.
+    pub options: RenderOptionsPayload,
+}
+
+#[utoipa::path(post, path = "/api/documents/{id}/render", tag = "Documents", operation_id = "renderDocument",
+    params(("id" = Uuid, Path, description = "Document ID")),
+    request_body = RenderDocumentRequest,
+    responses((status = 200, body = RenderResponseBody), (status = 401, description = "Unauthorized"), (status = 404, description = "Document not found")))]
+pub async fn render_document(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<RenderDocumentRequest>,
+) -> Result<Json<RenderResponseBody>, StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, req.options.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let share_access = ctx.share_access_port();
+    let access_repo = ctx.access_repo();
+    access::require_view(
+        access_repo.as_ref(),
+        share_access.as_ref(),
+        ctx.access_policy(),
+        &actor,
+        id,
+    )
+    .await
+    .map_err(|_| StatusCode::NOT_FOUND)?;
+    let content = ctx
+        .realtime_engine()
+        .get_content(&id.to_string())
+        .await
+        .map_err(|e| {
+            tracing::error!(document_id = %id, error = ?e, "realtime_get_content_failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .unwrap_or_default();
+    let options = RenderOptions::from(req.options)
+        .with_defaults(&ctx.cfg.render_defaults)
+        .for_document(id);
+    let user_scope = match actor {
+        access::Actor::User(uid) => Some(uid),
+        _ => None,
+    };
+    let rendered = markdown_http::render_in_scope(&ctx, user_scope, content, options).await?;
+    Ok(Json(rendered))
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct RenderTreeRequest {
+    /// Render options applied to every document; `token` also authorizes share access.
+    pub options: RenderOptionsPayload,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RenderTreeDocument {
+    pub id: Uuid,
+    pub title: String,
+    pub anchor: String,
+    pub depth: usize,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RenderTreeResponse {
+    pub html: String,
+    pub documents: Vec<RenderTreeDocument>,
+    pub skipped: usize,
+    pub truncated: bool,
+}
+
+#[utoipa::path(post, path = "/api/documents/{id}/render-tree", tag = "Documents", operation_id = "renderDocumentTree",
+    params(("id" = Uuid, Path, description = "Folder ID")),
+    request_body = RenderTreeRequest,
+    responses((status = 200, body = RenderTreeResponse), (status = 401, description = "Unauthorized"), (status = 404, description = "Folder not found")))]
+pub async fn render_document_tree(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<RenderTreeRequest>,
+) -> Result<Json<RenderTreeResponse>, StatusCode> {
+    let actor = auth::resolve_actor_from_parts(&ctx, bearer, req.options.token.as_deref())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let tree = ctx.shares_repo();
+    let access = ctx.access_repo();
+    let shares = ctx.share_access_port();
+    let realtime = ctx.realtime_engine();
+    let uc = RenderDocumentTree {
+        tree: tree.as_ref(),
+        access: access.as_ref(),
+        shares: shares.as_ref(),
+        policy: ctx.access_policy(),
+        realtime: realtime.as_ref(),
+        max_documents: MAX_TREE_DOCUMENTS,
+        max_bytes: MAX_TREE_BYTES,
+    };
+    let rendered = uc
+        .execute(
+            &actor,
+            id,
+            RenderOptions::from(req.options).with_defaults(&ctx.cfg.render_defaults),
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(document_id = %id, error = ?e, "document_tree_render_failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(RenderTreeResponse {
+        html: rendered.html,
+        documents: rendered
+            .documents
+            .into_iter()
+            .map(|d| RenderTreeDocument {
+                id: d.id,
+                title: d.title,
+                anchor: d.anchor,
+                depth: d.depth,
+            })
+            .collect(),
+        skipped: rendered.skipped,
+        truncated: rendered.truncated,
     }))
 }
 
 pub fn routes(ctx: AppContext) -> Router {
+    // Whole-document and subtree exports.
+    let long_running = Router::new()
+        .route("/documents/:id/download", get(download_document))
+        .route("/documents/:id/export", get(export_document))
+        .route("/documents/:id/render-tree", post(render_document_tree))
+        .route_layer(axum::middleware::from_fn(timeout::long_running));
     Router::new()
         .route("/documents", get(list_documents).post(create_document))
+        .route("/me/recent", get(list_recent_documents))
+        .route("/me/mentions", get(list_mentions))
+        .route("/me/home", get(get_home_document).put(set_home_document))
         .route(
             "/documents/:id",
             get(get_document)
                 .delete(delete_document)
                 .patch(update_document),
         )
-        .route("/documents/:id/content", get(get_document_content))
-        .route("/documents/:id/download", get(download_document))
+        .route(
+            "/documents/:id/content",
+            get(get_document_content).put(update_document_content),
+        )
+        .route("/documents/:id/presence", get(get_document_presence))
+        .route("/documents/:id/capability", get(get_document_capability))
+        .route("/documents/:id/appearance", put(update_document_appearance))
+        .route("/documents/:id/lock", post(lock_document))
+        .route("/documents/:id/unlock", post(unlock_document))
+        .route("/documents/:id/render", post(render_document))
+        .route(
+            "/documents/:id/retention",
+            get(get_document_retention).put(update_document_retention),
+        )
+        .route(
+            "/documents/:id/access",
+            get(list_document_user_access).put(grant_document_user_access),
+        )
+        .route(
+            "/documents/:id/access/:user_id",
+            delete(revoke_document_user_access),
+        )
+        .route("/documents/:id/versions", get(list_document_versions))
+        .route("/documents/:id/versions/diff", get(diff_document_versions))
+        .route(
+            "/documents/:id/versions/:version/content",
+            get(get_document_version_content),
+        )
+        .route(
+            "/documents/:id/versions/:version/restore",
+            post(restore_document_version),
+        )
         .route("/documents/:id/backlinks", get(get_backlinks))
         .route("/documents/:id/links", get(get_outgoing_links))
         .route("/documents/search", get(search_documents))
+        .merge(long_running)
+        .route_layer(axum::middleware::from_fn_with_state(
+            ctx.clone(),
+            rate_limit::limit_share_requests,
+        ))
         .with_state(ctx)
 }
 
@@ -402,7 +1686,7 @@ pub async fn search_documents(
     bearer: crate::presentation::http::auth::Bearer,
     q: Option<Query<SearchQuery>>,
 ) -> Result<Json<Vec<SearchResult>>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let query_text = q.and_then(|Query(v)| v.q);
 
@@ -470,14 +1754,20 @@ pub async fn get_backlinks(
     bearer: crate::presentation::http::auth::Bearer,
     Path(id): Path<Uuid>,
 ) -> Result<Json<BacklinksResponse>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let share_access = ctx.share_access_port();
     let access_repo = ctx.access_repo();
     let actor = access::Actor::User(user_id);
-    access::require_view(access_repo.as_ref(), share_access.as_ref(), &actor, id)
-        .await
-        .map_err(|_| StatusCode::NOT_FOUND)?;
+    access::require_view(
+        access_repo.as_ref(),
+        share_access.as_ref(),
+        ctx.access_policy(),
+        &actor,
+        id,
+    )
+    .await
+    .map_err(|_| StatusCode::NOT_FOUND)?;
 
     let repo = ctx.document_repo();
     let uc = GetBacklinks {
@@ -513,14 +1803,20 @@ pub async fn get_outgoing_links(
     bearer: crate::presentation::http::auth::Bearer,
     Path(id): Path<Uuid>,
 ) -> Result<Json<OutgoingLinksResponse>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let share_access = ctx.share_access_port();
     let access_repo = ctx.access_repo();
     let actor = access::Actor::User(user_id);
-    access::require_view(access_repo.as_ref(), share_access.as_ref(), &actor, id)
-        .await
-        .map_err(|_| StatusCode::NOT_FOUND)?;
+    access::require_view(
+        access_repo.as_ref(),
+        share_access.as_ref(),
+        ctx.access_policy(),
+        &actor,
+        id,
+    )
+    .await
+    .map_err(|_| StatusCode::NOT_FOUND)?;
 
     let repo = ctx.document_repo();
     let uc = GetOutgoingLinks {
@@ -1,7 +1,7 @@
 use axum::{
     Json, Router,
-    extract::{Multipart, Path as AxumPath, Query, State},
-    http::{HeaderMap, HeaderValue, StatusCode},
+    extract::{DefaultBodyLimit, Multipart, Path as AxumPath, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
     routing::{get, post},
 };
@@ -10,9 +10,17 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::application::access;
+use crate::application::ports::access_repository::AccessRepository;
+use crate::application::ports::share_access_port::ShareAccessPort;
+use crate::application::ports::storage_port::StoragePort;
+use crate::application::services::uploads::{UploadPolicy, UploadPolicyError};
 use crate::application::use_cases::files::upload_file::UploadFile;
 use crate::bootstrap::app_context::AppContext;
 use crate::presentation::http::auth::{self, Bearer};
+use crate::presentation::http::byte_range::{self, RangeOutcome};
+use crate::presentation::http::content_type;
+use crate::presentation::http::rate_limit;
+use crate::presentation::http::timeout;
 
 // Uses AppContext as router state
 
@@ -36,6 +44,40 @@ pub struct UploadFileMultipart {
     document_id: String,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UploadRejectedResponse {
+    /// Machine-readable reason: denied_type, denied_extension, not_allowed, type_mismatch, invalid_svg
+    pub code: String,
+    pub message: String,
+}
+
+pub enum UploadFileError {
+    Status(StatusCode),
+    Rejected(UploadPolicyError),
+}
+
+impl From<StatusCode> for UploadFileError {
+    fn from(status: StatusCode) -> Self {
+        UploadFileError::Status(status)
+    }
+}
+
+impl IntoResponse for UploadFileError {
+    fn into_response(self) -> Response {
+        match self {
+            UploadFileError::Status(status) => status.into_response(),
+            UploadFileError::Rejected(err) => (
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                Json(UploadRejectedResponse {
+                    code: err.code().to_string(),
+                    message: err.to_string(),
+                }),
+            )
+                .into_response(),
+        }
+    }
+}
+
 /// POST /api/files (multipart/form-data)
 /// Fields:
 /// - file: binary file (required)
@@ -49,16 +91,17 @@ pub struct UploadFileMultipart {
         content_type = "multipart/form-data",
     ),
     responses(
-        (status = 201, description = "File uploaded", body = UploadFileResponse)
+        (status = 201, description = "File uploaded", body = UploadFileResponse),
+        (status = 415, description = "File type rejected by upload policy", body = UploadRejectedResponse)
     )
 )]
 pub async fn upload_file(
     State(ctx): State<AppContext>,
     bearer: Bearer,
     mut multipart: Multipart,
-) -> Result<Json<UploadFileResponse>, StatusCode> {
+) -> Result<Json<UploadFileResponse>, UploadFileError> {
     // Validate user via bearer
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
 
     let mut document_id: Option<Uuid> = None;
@@ -86,7 +129,7 @@ pub async fn upload_file(
                 let data = field.bytes().await.map_err(|_| StatusCode::BAD_REQUEST)?;
                 // Enforce configured max upload size (additional safety besides DefaultBodyLimit)
                 if data.len() > ctx.cfg.upload_max_bytes {
-                    return Err(StatusCode::PAYLOAD_TOO_LARGE);
+                    return Err(StatusCode::PAYLOAD_TOO_LARGE.into());
                 }
                 file_bytes = Some(data.to_vec());
             }
@@ -101,15 +144,23 @@ pub async fn upload_file(
     let repo = ctx.files_repo();
     let storage = ctx.storage_port();
     let public_base_url = ctx.cfg.public_base_url.clone();
+    let policy = UploadPolicy::from_config(&ctx.cfg);
     let uc = UploadFile {
         repo: repo.as_ref(),
         storage: storage.as_ref(),
         public_base_url,
+        policy: &policy,
     };
     let out = uc
         .execute(user_id, doc_id, bytes, orig_filename, content_type.clone())
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|err| match err.downcast::<UploadPolicyError>() {
+            Ok(rejected) => {
+                tracing::info!(document_id = %doc_id, reason = %rejected, "upload_rejected");
+                UploadFileError::Rejected(rejected)
+            }
+            Err(_) => UploadFileError::Status(StatusCode::INTERNAL_SERVER_ERROR),
+        })?;
     let f = out.ok_or(StatusCode::FORBIDDEN)?;
     Ok(Json(UploadFileResponse {
         id: f.id,
@@ -120,20 +171,34 @@ pub async fn upload_file(
     }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DownloadQuery {
+    pub download: Option<String>,
+}
+
 /// GET /api/files/{id} -> bytes (fallback; primary is /uploads/{filename})
 #[utoipa::path(
     get,
     path = "/api/files/{id}",
     tag = "Files",
-    params(("id" = Uuid, Path, description = "File ID")),
-    responses((status = 200, description = "OK", body = Vec<u8>, content_type = "application/octet-stream"))
+    params(
+        ("id" = Uuid, Path, description = "File ID"),
+        ("download" = Option<String>, Query, description = "Set to 1 to force Content-Disposition: attachment")
+    ),
+    responses(
+        (status = 200, description = "OK", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 206, description = "Partial content for a Range request"),
+        (status = 416, description = "Range not satisfiable")
+    )
 )]
 pub async fn get_file(
     State(ctx): State<AppContext>,
     bearer: Bearer,
     AxumPath(id): AxumPath<Uuid>,
+    Query(q): Query<DownloadQuery>,
+    req_headers: HeaderMap,
 ) -> Result<Response, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.files_repo();
     let storage = ctx.storage_port();
@@ -146,28 +211,21 @@ pub async fn get_file(
         return Err(StatusCode::FORBIDDEN);
     }
     let abs_path = storage.absolute_from_relative(&path);
-    let data = storage
-        .read_bytes(&abs_path)
-        .await
-        .map_err(|_| StatusCode::NOT_FOUND)?;
-    let mut headers = HeaderMap::new();
-    if let Some(ct) = ct {
-        headers.insert(
-            axum::http::header::CONTENT_TYPE,
-            HeaderValue::from_str(&ct)
-                .unwrap_or(HeaderValue::from_static("application/octet-stream")),
-        );
-    }
-    headers.insert(
-        axum::http::header::HeaderName::from_static("x-content-type-options"),
-        HeaderValue::from_static("nosniff"),
-    );
-    Ok((headers, data).into_response())
+    let download = content_type::wants_download(q.download.as_deref());
+    file_response(
+        storage.as_ref(),
+        &abs_path,
+        ct.as_deref(),
+        download,
+        &req_headers,
+    )
+    .await
 }
 
 #[derive(Debug, Deserialize)]
 pub struct FileByNameQuery {
     pub document_id: Uuid,
+    pub download: Option<String>,
 }
 
 /// GET /api/files/documents/{filename}?document_id=uuid -> bytes
@@ -175,17 +233,26 @@ pub struct FileByNameQuery {
     get,
     path = "/api/files/documents/{filename}",
     tag = "Files",
-    params(("filename" = String, Path, description = "File name"), ("document_id" = Uuid, Query, description = "Document ID")),
-    responses((status = 200, description = "OK", body = Vec<u8>, content_type = "application/octet-stream"))
+    params(
+        ("filename" = String, Path, description = "File name"),
+        ("document_id" = Uuid, Query, description = "Document ID"),
+        ("download" = Option<String>, Query, description = "Set to 1 to force Content-Disposition: attachment")
+    ),
+    responses(
+        (status = 200, description = "OK", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 206, description = "Partial content for a Range request"),
+        (status = 416, description = "Range not satisfiable")
+    )
 )]
 pub async fn get_file_by_name(
     State(ctx): State<AppContext>,
     bearer: Bearer,
     AxumPath(filename): AxumPath<String>,
     Query(q): Query<FileByNameQuery>,
+    req_headers: HeaderMap,
 ) -> Result<Response, StatusCode> {
     // auth: owner of the document only
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
 
     // authorize: owner must have at least view permission
@@ -195,6 +262,7 @@ pub async fn get_file_by_name(
     access::require_view(
         access_repo.as_ref(),
         share_access.as_ref(),
+        ctx.access_policy(),
         &actor,
         q.document_id,
     )
@@ -210,113 +278,345 @@ pub async fn get_file_by_name(
         .ok_or(StatusCode::NOT_FOUND)?;
     let storage = ctx.storage_port();
     let abs_path = storage.absolute_from_relative(&path);
-    let data = storage
-        .read_bytes(&abs_path)
-        .await
-        .map_err(|_| StatusCode::NOT_FOUND)?;
-    let mut headers = HeaderMap::new();
-    if let Some(ct) = ct {
-        headers.insert(
-            axum::http::header::CONTENT_TYPE,
-            HeaderValue::from_str(&ct)
-                .unwrap_or(HeaderValue::from_static("application/octet-stream")),
-        );
-    }
-    headers.insert(
-        axum::http::header::HeaderName::from_static("x-content-type-options"),
-        HeaderValue::from_static("nosniff"),
-    );
-    Ok((headers, data).into_response())
+    let download = content_type::wants_download(q.download.as_deref());
+    file_response(
+        storage.as_ref(),
+        &abs_path,
+        ct.as_deref(),
+        download,
+        &req_headers,
+    )
+    .await
 }
 
 /// Serve static files from uploads directory with authentication support
-/// Supports both JWT auth and share tokens
+/// Supports both JWT auth (header or `access_token` cookie) and share tokens
 pub async fn serve_upload(
     State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
     AxumPath(path): AxumPath<String>,
     Query(params): Query<std::collections::HashMap<String, String>>,
-    headers: HeaderMap,
+    req_headers: HeaderMap,
 ) -> Result<Response, StatusCode> {
-    // Try to extract token from query params, Authorization header, or HttpOnly cookie `access_token`
-    let token = params
+    // Path must start with document UUID followed by the attachment path.
+    let (doc_id, attachment_path) = parse_upload_path(&path).ok_or(StatusCode::NOT_FOUND)?;
+
+    // Every credential presented is tried; public documents stay readable without any.
+    let mut actors = Vec::new();
+    if let Some(b) = bearer
+        && let Some(actor) = auth::resolve_actor_from_token_str(&ctx, &b.0)
+    {
+        actors.push(actor);
+    }
+    if let Some(actor) = params
         .get("token")
-        .cloned()
-        .or_else(|| {
-            headers
-                .get(axum::http::header::AUTHORIZATION)
-                .and_then(|h| h.to_str().ok())
-                .and_then(|s| s.strip_prefix("Bearer ").map(|s| s.to_string()))
-        })
-        .or_else(|| {
-            headers
-                .get(axum::http::header::COOKIE)
-                .and_then(|h| h.to_str().ok())
-                .and_then(|cookie_hdr| {
-                    for part in cookie_hdr.split(';') {
-                        let kv = part.trim();
-                        if let Some((k, v)) = kv.split_once('=') {
-                            if k.trim() == "access_token" {
-                                return Some(v.trim().to_string());
-                            }
-                        }
-                    }
-                    None
-                })
-        });
-
-    // Path must start with document UUID. If not, reject.
-    let parts: Vec<&str> = path.split('/').collect();
-    if parts.len() < 2 {
-        return Err(StatusCode::FORBIDDEN);
+        .and_then(|t| auth::resolve_actor_from_token_str(&ctx, t))
+    {
+        actors.push(actor);
     }
-    let doc_id = Uuid::parse_str(parts[0]).map_err(|_| StatusCode::FORBIDDEN)?;
+    actors.push(access::Actor::Public);
 
-    // Build actor and require at least view capability (or public)
-    let actor = token
-        .as_deref()
-        .and_then(|t| auth::resolve_actor_from_token_str(&ctx.cfg, t))
-        .unwrap_or(access::Actor::Public);
     let share_access = ctx.share_access_port();
     let access_repo = ctx.access_repo();
-    let _cap = access::require_view(access_repo.as_ref(), share_access.as_ref(), &actor, doc_id)
-        .await
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    authorize_upload(
+        access_repo.as_ref(),
+        share_access.as_ref(),
+        ctx.access_policy(),
+        &actors,
+        doc_id,
+    )
+    .await?;
 
     // Resolve the file path via storage port (includes security checks)
     let storage_port = ctx.storage_port();
-    let attachment_path = parts[1..].join("/");
     let file_path = storage_port
         .resolve_upload_path(doc_id, &attachment_path)
         .await
         .map_err(|_| StatusCode::NOT_FOUND)?;
 
-    let data = storage_port
-        .read_bytes(&file_path)
+    // Prefer the content type recorded at upload time; extension and magic bytes are fallbacks.
+    let stored_type = match file_path.file_name().and_then(|n| n.to_str()) {
+        Some(name) => ctx
+            .files_repo()
+            .get_file_path_by_doc_and_name(doc_id, name)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|(_, ct)| ct),
+        None => None,
+    };
+    let download = content_type::wants_download(params.get("download").map(|s| s.as_str()));
+    file_response(
+        storage_port.as_ref(),
+        &file_path,
+        stored_type.as_deref(),
+        download,
+        &req_headers,
+    )
+    .await
+}
+
+/// Builds a file response with validators, honouring single `Range` requests.
+async fn file_response<S>(
+    storage: &S,
+    path: &std::path::Path,
+    stored_type: Option<&str>,
+    download: bool,
+    req_headers: &HeaderMap,
+) -> Result<Response, StatusCode>
+where
+    S: StoragePort + ?Sized,
+{
+    let meta = storage
+        .stat(path)
         .await
         .map_err(|_| StatusCode::NOT_FOUND)?;
 
-    // Determine content type from extension using mime_guess (fallback to octet-stream)
-    let guessed = mime_guess::from_path(&file_path).first_or_octet_stream();
-    let content_type = guessed.essence_str().to_string();
+    let content_type = match content_type::from_metadata(stored_type, path) {
+        Some(ct) => ct,
+        None if meta.size > 0 => {
+            let end = meta.size.min(content_type::SNIFF_LEN) - 1;
+            let head = storage.read_range(path, 0, end).await.unwrap_or_default();
+            content_type::sniff(&head)
+        }
+        None => content_type::OCTET_STREAM.to_string(),
+    };
+    let filename = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("download");
 
     let mut headers = HeaderMap::new();
     headers.insert(
-        axum::http::header::CONTENT_TYPE,
+        header::CONTENT_DISPOSITION,
+        content_type::disposition(filename, &content_type, download),
+    );
+    headers.insert(
+        header::CONTENT_TYPE,
         HeaderValue::from_str(&content_type)
-            .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+            .unwrap_or(HeaderValue::from_static(content_type::OCTET_STREAM)),
     );
     headers.insert(
-        axum::http::header::HeaderName::from_static("x-content-type-options"),
+        header::HeaderName::from_static("x-content-type-options"),
         HeaderValue::from_static("nosniff"),
     );
+    if content_type::is_svg(&content_type) {
+        headers.insert(
+            header::CONTENT_SECURITY_POLICY,
+            HeaderValue::from_static(content_type::SVG_CSP),
+        );
+    }
+    headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    if let Some(etag) = meta
+        .etag
+        .as_deref()
+        .and_then(|e| HeaderValue::from_str(e).ok())
+    {
+        headers.insert(header::ETAG, etag);
+    }
+    if let Some(modified) = meta.last_modified
+        && let Ok(v) = HeaderValue::from_str(&byte_range::http_date(modified))
+    {
+        headers.insert(header::LAST_MODIFIED, v);
+    }
 
-    Ok((headers, data).into_response())
+    match byte_range::evaluate(
+        req_headers,
+        meta.size,
+        meta.etag.as_deref(),
+        meta.last_modified,
+    ) {
+        RangeOutcome::Full => {
+            let data = storage
+                .read_bytes(path)
+                .await
+                .map_err(|_| StatusCode::NOT_FOUND)?;
+            Ok((headers, data).into_response())
+        }
+        RangeOutcome::Partial { start, end } => {
+            let data = storage
+                .read_range(path, start, end)
+                .await
+                .map_err(|_| StatusCode::NOT_FOUND)?;
+            let content_range = format!("bytes {}-{}/{}", start, end, meta.size);
+            headers.insert(
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&content_range)
+                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+            );
+            Ok((StatusCode::PARTIAL_CONTENT, headers, data).into_response())
+        }
+        RangeOutcome::Unsatisfiable => {
+            let content_range = format!("bytes */{}", meta.size);
+            headers.insert(
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&content_range)
+                    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+            );
+            Ok((StatusCode::RANGE_NOT_SATISFIABLE, headers).into_response())
+        }
+    }
+}
+
+/// Splits `<doc_id>/<attachment path>`, ignoring empty segments from stray slashes.
+fn parse_upload_path(path: &str) -> Option<(Uuid, String)> {
+    let mut segments = path
+        .split(['/', '\\'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && *s != ".");
+    let doc_id = Uuid::parse_str(segments.next()?).ok()?;
+    let rest: Vec<&str> = segments.collect();
+    if rest.is_empty() || rest.contains(&"..") {
+        return None;
+    }
+    Some((doc_id, rest.join("/")))
+}
+
+async fn authorize_upload<A, R>(
+    access_repo: &A,
+    shares: &R,
+    policy: access::AccessPolicy,
+    actors: &[access::Actor],
+    doc_id: Uuid,
+) -> Result<(), StatusCode>
+where
+    A: AccessRepository + ?Sized,
+    R: ShareAccessPort + ?Sized,
+{
+    for actor in actors {
+        if access::require_view(access_repo, shares, policy, actor, doc_id)
+            .await
+            .is_ok()
+        {
+            return Ok(());
+        }
+    }
+    Err(StatusCode::FORBIDDEN)
 }
 
 pub fn routes(ctx: AppContext) -> Router {
+    // The upload body is read before a response is produced, so it gets no deadline.
+    let uploads = Router::new()
+        .route(
+            "/files",
+            post(upload_file).layer(DefaultBodyLimit::max(ctx.cfg.upload_max_bytes)),
+        )
+        .route_layer(axum::middleware::from_fn(timeout::exempt));
     Router::new()
-        .route("/files", post(upload_file))
+        .merge(uploads)
         .route("/files/:id", get(get_file))
         .route("/files/documents/:filename", get(get_file_by_name))
+        .route_layer(axum::middleware::from_fn_with_state(
+            ctx.clone(),
+            rate_limit::limit_share_requests,
+        ))
         .with_state(ctx)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::application::ports::shares_repository::SharesRepository;
+    use crate::test_support::documents::MemoryDocuments;
+    use crate::test_support::shares::MemoryShares;
+
+    #[test]
+    fn parses_document_id_and_attachment_path() {
+        let doc_id = Uuid::new_v4();
+        assert_eq!(
+            parse_upload_path(&format!("/{}//attachments/a.png", doc_id)),
+            Some((doc_id, "attachments/a.png".to_string()))
+        );
+        assert_eq!(parse_upload_path(&doc_id.to_string()), None);
+        assert_eq!(parse_upload_path("not-a-uuid/a.png"), None);
+        assert_eq!(parse_upload_path(&format!("{}/../secret", doc_id)), None);
+    }
+
+    #[tokio::test]
+    async fn denies_unauthorized_actor_and_accepts_share_token() {
+        let docs = Arc::new(MemoryDocuments::default());
+        let shares = MemoryShares::new(docs.clone());
+        let owner = Uuid::new_v4();
+        let doc_id = docs.add(owner, "Notes", "document", None).id;
+        let (token, _, _) = shares
+            .create_share(owner, doc_id, "view", None, None)
+            .await
+            .unwrap();
+        let owners = docs.as_ref();
+        let policy = access::AccessPolicy::default();
+
+        let stranger = [access::Actor::User(Uuid::new_v4()), access::Actor::Public];
+        assert_eq!(
+            authorize_upload(owners, &shares, policy, &stranger, doc_id).await,
+            Err(StatusCode::FORBIDDEN)
+        );
+
+        let wrong_token = [access::Actor::ShareToken("other".to_string())];
+        assert_eq!(
+            authorize_upload(owners, &shares, policy, &wrong_token, doc_id).await,
+            Err(StatusCode::FORBIDDEN)
+        );
+
+        let with_token = [
+            access::Actor::User(Uuid::new_v4()),
+            access::Actor::ShareToken(token),
+        ];
+        assert!(
+            authorize_upload(owners, &shares, policy, &with_token, doc_id)
+                .await
+                .is_ok()
+        );
+        assert!(
+            authorize_upload(
+                owners,
+                &shares,
+                policy,
+                &[access::Actor::User(owner)],
+                doc_id
+            )
+            .await
+            .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn upload_route_limit_overrides_the_default_body_limit() {
+        // Same layering as the API router: a small outer default, raised on the upload route.
+        let app = Router::new()
+            .route(
+                "/json",
+                post(|Json(_): Json<serde_json::Value>| async { StatusCode::OK }),
+            )
+            .route(
+                "/files",
+                post(|body: axum::body::Bytes| async move { body.len().to_string() })
+                    .layer(DefaultBodyLimit::max(64 * 1024)),
+            )
+            .layer(DefaultBodyLimit::max(1024));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let body = format!("\"{}\"", "x".repeat(8 * 1024));
+        let client = reqwest::Client::new();
+        let post = |path: &str| {
+            client
+                .post(format!("http://{}{}", addr, path))
+                .header("content-type", "application/json")
+                .body(body.clone())
+                .send()
+        };
+
+        let json = post("/json").await.unwrap();
+        // reqwest uses an older `http`, so compare raw codes.
+        assert_eq!(
+            json.status().as_u16(),
+            StatusCode::PAYLOAD_TOO_LARGE.as_u16()
+        );
+        let upload = post("/files").await.unwrap();
+        assert_eq!(upload.status().as_u16(), StatusCode::OK.as_u16());
+        assert_eq!(upload.text().await.unwrap(), body.len().to_string());
+    }
+}
@@ -1,6 +1,6 @@
 use axum::{
     Json, Router,
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
     routing::{get, post},
 };
@@ -8,15 +8,18 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::presentation::http::auth::{Bearer, validate_bearer};
+use crate::presentation::http::timeout;
 // Config is no longer needed directly here
 use crate::application::dto::git::{
     DiffLine as DiffLineDto, DiffLineType as DiffLineTypeDto, DiffResult as DiffResultDto,
-    GitChangeItem as GitChangeDto, GitCommitInfo, GitConfigDto, GitStatusDto, GitSyncRequestDto,
-    UpsertGitConfigInput,
+    GitChangeItem as GitChangeDto, GitConfigDto, GitHistoryCursor, GitStatusDto,
+    GitSyncPreview as GitSyncPreviewDto, GitSyncRequestDto, UpsertGitConfigInput,
 };
 use crate::application::use_cases::git::delete_config::DeleteGitConfig;
 use crate::application::use_cases::git::get_config::GetGitConfig;
 use crate::application::use_cases::git::get_status::GetGitStatus;
+use crate::application::use_cases::git::gitignore_patterns::SkippedPattern;
+use crate::application::use_cases::git::import_repo::ImportRemoteRepo;
 use crate::application::use_cases::git::init_repo::{DeinitRepo, InitRepo};
 use crate::application::use_cases::git::upsert_config::UpsertGitConfig;
 use crate::bootstrap::app_context::AppContext;
@@ -25,6 +28,14 @@ use uuid::Uuid;
 // Uses AppContext as router state
 
 pub fn routes(ctx: AppContext) -> Router {
+    // Network round trips to the remote and whole-repository work.
+    let long_running = Router::new()
+        .route("/git/gc", post(collect_garbage))
+        .route("/git/sync", post(sync_now))
+        .route("/git/test-connection", post(test_connection))
+        .route("/git/import", post(import_repository))
+        .route("/git/init", post(init_repository))
+        .route_layer(axum::middleware::from_fn(timeout::long_running));
     Router::new()
         .route(
             "/git/config",
@@ -35,18 +46,21 @@ pub fn routes(ctx: AppContext) -> Router {
         .route("/git/status", get(get_status))
         .route("/git/changes", get(get_changes))
         .route("/git/history", get(get_history))
+        .route("/git/blame", get(get_blame))
         .route("/git/diff/working", get(get_working_diff))
         .route("/git/diff/commits/:from/:to", get(get_commit_diff))
-        .route("/git/sync", post(sync_now))
-        .route("/git/init", post(init_repository))
+        .route("/git/storage", get(get_storage_usage))
         .route("/git/deinit", post(deinit_repository))
         .route("/git/ignore/doc/:id", post(ignore_document))
         .route("/git/ignore/folder/:id", post(ignore_folder))
         .route(
             "/git/gitignore/patterns",
-            get(get_gitignore_patterns).post(add_gitignore_patterns),
+            get(get_gitignore_patterns)
+                .post(add_gitignore_patterns)
+                .delete(remove_gitignore_patterns),
         )
         .route("/git/gitignore/check", post(check_path_ignored))
+        .merge(long_running)
         .with_state(ctx)
 }
 
@@ -57,9 +71,22 @@ pub struct GitConfigResponse {
     pub branch_name: String,
     pub auth_type: String,
     pub auto_sync: bool,
+    pub commit_message_template: Option<String>,
+    pub append_change_summary: bool,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+pub struct GitImportResponse {
+    /// Remote head recorded as the baseline; absent when the branch does not exist yet.
+    pub commit_hash: Option<String>,
+    pub folders: u32,
+    pub documents: u32,
+    /// Non-markdown files that were not imported.
+    pub skipped: Vec<String>,
+}
+
 impl From<GitConfigDto> for GitConfigResponse {
     fn from(d: GitConfigDto) -> Self {
         GitConfigResponse {
@@ -68,6 +95,8 @@ impl From<GitConfigDto> for GitConfigResponse {
             branch_name: d.branch_name,
             auth_type: d.auth_type,
             auto_sync: d.auto_sync,
+            commit_message_template: d.commit_message_template,
+            append_change_summary: d.append_change_summary,
             created_at: d.created_at,
             updated_at: d.updated_at,
         }
@@ -81,6 +110,10 @@ pub struct CreateGitConfigRequest {
     pub auth_type: String,
     pub auth_data: serde_json::Value,
     pub auto_sync: Option<bool>,
+    /// Default sync message; supports `{count}`, `{date}` and `{files}`.
+    pub commit_message_template: Option<String>,
+    /// Append an `A`/`M`/`D` list of changed files to the commit body.
+    pub append_change_summary: Option<bool>,
 }
 impl From<CreateGitConfigRequest> for UpsertGitConfigInput {
     fn from(r: CreateGitConfigRequest) -> Self {
@@ -90,6 +123,8 @@ impl From<CreateGitConfigRequest> for UpsertGitConfigInput {
             auth_type: r.auth_type,
             auth_data: r.auth_data,
             auto_sync: r.auto_sync,
+            commit_message_template: r.commit_message_template,
+            append_change_summary: r.append_change_summary,
         }
     }
 }
@@ -101,6 +136,8 @@ pub struct UpdateGitConfigRequest {
     pub auth_type: Option<String>,
     pub auth_data: Option<serde_json::Value>,
     pub auto_sync: Option<bool>,
+    pub commit_message_template: Option<String>,
+    pub append_change_summary: Option<bool>,
 }
 
 #[utoipa::path(get, path = "/api/git/config", tag = "Git", responses((status = 200, body = Option<GitConfigResponse>)))]
@@ -108,7 +145,7 @@ pub async fn get_config(
     State(ctx): State<AppContext>,
     bearer: Bearer,
 ) -> Result<Json<Option<GitConfigResponse>>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.git_repo();
     let uc = GetGitConfig {
@@ -128,7 +165,7 @@ pub async fn create_or_update_config(
     bearer: Bearer,
     Json(req): Json<CreateGitConfigRequest>,
 ) -> Result<Json<GitConfigResponse>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.git_repo();
     let gitignore = ctx.gitignore_port();
@@ -145,8 +182,39 @@ pub async fn create_or_update_config(
         .execute(user_id, &input)
         .await
         .map_err(|_| StatusCode::BAD_REQUEST)?;
-    let out: GitConfigResponse = resp.into();
-    Ok(Json(out))
+    Ok(Json(resp.into()))
+}
+
+/// Imports the configured remote branch's markdown files as documents and continues its
+/// history. Only allowed while the local repository has no commits; a failed import leaves
+/// no documents behind and can be retried.
+#[utoipa::path(post, path = "/api/git/import", tag = "Git", responses((status = 200, body = GitImportResponse), (status = 409, description = "Import failed or the repository already has history")))]
+pub async fn import_repository(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+) -> Result<Json<GitImportResponse>, StatusCode> {
+    let sub = validate_bearer(&ctx, bearer)?;
+    let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let repo = ctx.git_repo();
+    let documents = ctx.document_repo();
+    let storage = ctx.storage_port();
+    let workspace = ctx.git_workspace();
+    let uc = ImportRemoteRepo {
+        repo: repo.as_ref(),
+        documents: documents.as_ref(),
+        storage: storage.as_ref(),
+        workspace: workspace.as_ref(),
+    };
+    let summary = uc.execute(user_id).await.map_err(|e| {
+        tracing::error!(error=?e, "git_import_failed");
+        StatusCode::CONFLICT
+    })?;
+    Ok(Json(GitImportResponse {
+        commit_hash: summary.commit_hash,
+        folders: summary.folders,
+        documents: summary.documents,
+        skipped: summary.skipped,
+    }))
 }
 
 #[utoipa::path(delete, path = "/api/git/config", tag = "Git", responses((status = 204, description = "Deleted")))]
@@ -154,7 +222,7 @@ pub async fn delete_config(
     State(ctx): State<AppContext>,
     bearer: Bearer,
 ) -> Result<StatusCode, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.git_repo();
     let uc = DeleteGitConfig {
@@ -210,7 +278,7 @@ pub async fn ignore_document(
     bearer: Bearer,
     axum::extract::Path(id): axum::extract::Path<String>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let doc_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
     let gitignore = ctx.gitignore_port();
@@ -240,7 +308,7 @@ pub async fn ignore_folder(
     bearer: Bearer,
     axum::extract::Path(id): axum::extract::Path<String>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let folder_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
     let gitignore = ctx.gitignore_port();
@@ -269,13 +337,20 @@ pub struct AddPatternsRequest {
     pub patterns: Vec<String>,
 }
 
-#[utoipa::path(post, path = "/api/git/gitignore/patterns", tag = "Git", request_body = AddPatternsRequest, responses((status = 200, description = "OK")))]
+fn skipped_json(skipped: &[SkippedPattern]) -> Vec<serde_json::Value> {
+    skipped
+        .iter()
+        .map(|s| serde_json::json!({"pattern": s.pattern, "reason": s.reason.to_string()}))
+        .collect()
+}
+
+#[utoipa::path(post, path = "/api/git/gitignore/patterns", tag = "Git", request_body = AddPatternsRequest, responses((status = 200, description = "Patterns added and skipped")))]
 pub async fn add_gitignore_patterns(
     State(ctx): State<AppContext>,
     bearer: Bearer,
     Json(req): Json<AddPatternsRequest>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let gitignore = ctx.gitignore_port();
     let storage = ctx.storage_port();
@@ -285,11 +360,38 @@ pub async fn add_gitignore_patterns(
         gitignore: gitignore.as_ref(),
         workspace: workspace.as_ref(),
     };
-    let added = uc
+    let changes = uc
+        .execute(user_id, req.patterns)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(serde_json::json!({
+        "added": changes.applied,
+        "skipped": skipped_json(&changes.skipped),
+    })))
+}
+
+#[utoipa::path(delete, path = "/api/git/gitignore/patterns", tag = "Git", request_body = AddPatternsRequest, responses((status = 200, description = "Patterns removed and skipped")))]
+pub async fn remove_gitignore_patterns(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Json(req): Json<AddPatternsRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let sub = validate_bearer(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let gitignore = ctx.gitignore_port();
+    let storage = ctx.storage_port();
+    let uc = crate::application::use_cases::git::gitignore_patterns::RemoveGitignorePatterns {
+        storage: storage.as_ref(),
+        gitignore: gitignore.as_ref(),
+    };
+    let changes = uc
         .execute(user_id, req.patterns)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    Ok(Json(serde_json::json!({"added": added})))
+    Ok(Json(serde_json::json!({
+        "removed": changes.applied,
+        "skipped": skipped_json(&changes.skipped),
+    })))
 }
 
 #[utoipa::path(get, path = "/api/git/gitignore/patterns", tag = "Git", responses((status = 200, description = "OK")))]
@@ -297,7 +399,7 @@ pub async fn get_gitignore_patterns(
     State(ctx): State<AppContext>,
     bearer: Bearer,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let gitignore = ctx.gitignore_port();
     let storage = ctx.storage_port();
@@ -323,7 +425,7 @@ pub async fn check_path_ignored(
     bearer: Bearer,
     Json(req): Json<CheckIgnoredRequest>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let storage = ctx.storage_port();
     let gitignore = ctx.gitignore_port();
@@ -345,7 +447,7 @@ pub async fn get_status(
     State(ctx): State<AppContext>,
     bearer: Bearer,
 ) -> Result<Json<GitStatus>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.git_repo();
     let workspace = ctx.git_workspace();
@@ -365,6 +467,8 @@ pub async fn get_status(
 pub struct GitSyncRequest {
     pub message: Option<String>,
     pub force: Option<bool>,
+    /// Preview the commit without creating or pushing it.
+    pub dry_run: Option<bool>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -373,6 +477,48 @@ pub struct GitSyncResponse {
     pub message: String,
     pub commit_hash: Option<String>,
     pub files_changed: u32,
+    /// Present for dry runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<GitSyncPreview>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GitSyncPreview {
+    pub files: Vec<GitChangeItem>,
+    pub diffs: Vec<GitDiffResult>,
+    pub stats: GitDiffStats,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GitDiffStats {
+    pub files_added: u32,
+    pub files_modified: u32,
+    pub files_deleted: u32,
+    pub lines_added: u32,
+    pub lines_deleted: u32,
+}
+
+impl From<GitSyncPreviewDto> for GitSyncPreview {
+    fn from(p: GitSyncPreviewDto) -> Self {
+        Self {
+            files: p
+                .files
+                .into_iter()
+                .map(|c| GitChangeItem {
+                    path: c.path,
+                    status: c.status,
+                })
+                .collect(),
+            diffs: p.diffs.into_iter().map(Into::into).collect(),
+            stats: GitDiffStats {
+                files_added: p.stats.files_added,
+                files_modified: p.stats.files_modified,
+                files_deleted: p.stats.files_deleted,
+                lines_added: p.stats.lines_added,
+                lines_deleted: p.stats.lines_deleted,
+            },
+        }
+    }
 }
 
 #[utoipa::path(post, path = "/api/git/sync", tag = "Git", request_body = GitSyncRequest, responses((status = 200, body = GitSyncResponse), (status = 409, description = "Conflicts during rebase/pull")))]
@@ -381,7 +527,7 @@ pub async fn sync_now(
     bearer: Bearer,
     Json(req): Json<GitSyncRequest>,
 ) -> Result<Json<GitSyncResponse>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.git_repo();
     let workspace = ctx.git_workspace();
@@ -395,6 +541,7 @@ pub async fn sync_now(
             GitSyncRequestDto {
                 message: req.message.clone(),
                 force: req.force,
+                dry_run: req.dry_run.unwrap_or(false),
             },
         )
         .await
@@ -407,6 +554,47 @@ pub async fn sync_now(
         message: out.message,
         commit_hash: out.commit_hash,
         files_changed: out.files_changed,
+        preview: out.preview.map(Into::into),
+    }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GitConnectionTestResponse {
+    pub ok: bool,
+    pub branch: String,
+    pub reachable: bool,
+    pub authenticated: bool,
+    pub branch_exists: bool,
+    /// One of not_configured, auth_failed, host_unreachable, branch_missing, other.
+    pub error: Option<String>,
+    pub message: Option<String>,
+}
+
+#[utoipa::path(post, path = "/api/git/test-connection", tag = "Git", responses((status = 200, body = GitConnectionTestResponse)))]
+pub async fn test_connection(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+) -> Result<Json<GitConnectionTestResponse>, StatusCode> {
+    let sub = validate_bearer(&ctx, bearer)?;
+    let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let repo = ctx.git_repo();
+    let workspace = ctx.git_workspace();
+    let uc = crate::application::use_cases::git::test_connection::TestGitConnection {
+        repo: repo.as_ref(),
+        workspace: workspace.as_ref(),
+    };
+    let check = uc.execute(user_id).await.map_err(|e| {
+        tracing::error!(error=?e, "git_test_connection_failed");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(GitConnectionTestResponse {
+        ok: check.ok(),
+        branch: check.branch,
+        reachable: check.reachable,
+        authenticated: check.authenticated,
+        branch_exists: check.branch_exists,
+        error: check.error.map(|e| e.as_str().to_string()),
+        message: check.message,
     }))
 }
 
@@ -419,31 +607,56 @@ pub struct GitChangeItem {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct GitChangesResponse {
     pub files: Vec<GitChangeItem>,
+    /// Changed files across all pages.
+    pub total: usize,
+    /// Cursor for the next page; absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitPageQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
 }
 
-#[utoipa::path(get, path = "/api/git/changes", tag = "Git", responses((status = 200, body = GitChangesResponse)))]
+#[utoipa::path(get, path = "/api/git/changes", tag = "Git",
+    params(
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Files per page (default 200, max 1000)")
+    ),
+    responses((status = 200, body = GitChangesResponse)))]
 pub async fn get_changes(
     State(ctx): State<AppContext>,
     bearer: Bearer,
+    Query(q): Query<GitPageQuery>,
 ) -> Result<Json<GitChangesResponse>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let workspace = ctx.git_workspace();
     let uc = crate::application::use_cases::git::get_changes::GetChanges {
         workspace: workspace.as_ref(),
     };
-    let files: Vec<GitChangeDto> = uc
-        .execute(user_id)
+    let page = uc
+        .execute(
+            user_id,
+            q.cursor.as_deref(),
+            q.limit.map(|l| l.max(0) as usize),
+        )
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let items = files
+    let items = page
+        .files
         .into_iter()
-        .map(|c| GitChangeItem {
+        .map(|c: GitChangeDto| GitChangeItem {
             path: c.path,
             status: c.status,
         })
         .collect();
-    Ok(Json(GitChangesResponse { files: items }))
+    Ok(Json(GitChangesResponse {
+        files: items,
+        total: page.total,
+        next_cursor: page.next_cursor,
+    }))
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -458,6 +671,8 @@ pub struct GitCommitItem {
 #[derive(Debug, Serialize, ToSchema)]
 pub struct GitHistoryResponse {
     pub commits: Vec<GitCommitItem>,
+    /// Cursor for the next, older page; absent on the last page.
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema, Clone)]
@@ -520,22 +735,34 @@ impl From<DiffResultDto> for GitDiffResult {
     }
 }
 
-#[utoipa::path(get, path = "/api/git/history", tag = "Git", responses((status = 200, body = GitHistoryResponse)))]
+#[utoipa::path(get, path = "/api/git/history", tag = "Git",
+    params(
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<i64>, Query, description = "Commits per page (default 50, max 200)")
+    ),
+    responses((status = 200, body = GitHistoryResponse), (status = 400, description = "Malformed cursor")))]
 pub async fn get_history(
     State(ctx): State<AppContext>,
     bearer: Bearer,
+    Query(q): Query<GitPageQuery>,
 ) -> Result<Json<GitHistoryResponse>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let cursor = q
+        .cursor
+        .as_deref()
+        .map(|c| GitHistoryCursor::parse(c).ok_or(StatusCode::BAD_REQUEST))
+        .transpose()?;
     let workspace = ctx.git_workspace();
     let uc = crate::application::use_cases::git::get_history::GetHistory {
         workspace: workspace.as_ref(),
     };
-    let commits: Vec<GitCommitInfo> = uc
-        .execute(user_id)
+    let page = uc
+        .execute(user_id, cursor.as_ref(), q.limit)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let out = commits
+    let out = page
+        .commits
         .into_iter()
         .map(|c| GitCommitItem {
             hash: c.hash,
@@ -545,31 +772,108 @@ pub async fn get_history(
             time: c.time,
         })
         .collect();
-    Ok(Json(GitHistoryResponse { commits: out }))
+    Ok(Json(GitHistoryResponse {
+        commits: out,
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitBlameQuery {
+    pub path: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GitBlameLineItem {
+    pub line_number: u32,
+    pub content: String,
+    pub hash: String,
+    pub message: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub time: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GitBlameResponse {
+    pub path: String,
+    pub lines: Vec<GitBlameLineItem>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/git/blame",
+    params(("path" = String, Query, description = "Repository path of the file, e.g. notes/plan.md")),
+    tag = "Git",
+    responses((status = 200, body = GitBlameResponse))
+)]
+pub async fn get_blame(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    axum::extract::Query(q): axum::extract::Query<GitBlameQuery>,
+) -> Result<Json<GitBlameResponse>, StatusCode> {
+    let sub = validate_bearer(&ctx, bearer)?;
+    let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    if q.path.trim_start_matches('/').is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let workspace = ctx.git_workspace();
+    let uc = crate::application::use_cases::git::get_blame::GetBlame {
+        workspace: workspace.as_ref(),
+    };
+    let lines = uc.execute(user_id, &q.path).await.map_err(|e| {
+        tracing::error!(error=?e, "git_blame_failed");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let lines = lines
+        .into_iter()
+        .map(|l| GitBlameLineItem {
+            line_number: l.line_number,
+            content: l.content,
+            hash: l.commit.hash,
+            message: l.commit.message,
+            author_name: l.commit.author_name,
+            author_email: l.commit.author_email,
+            time: l.commit.time,
+        })
+        .collect();
+    Ok(Json(GitBlameResponse {
+        path: q.path,
+        lines,
+    }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GitWorkingDiffResponse {
+    /// Commit the diffs are relative to; absent before the first commit.
+    pub commit_hash: Option<String>,
+    pub diffs: Vec<GitDiffResult>,
 }
 
 #[utoipa::path(
     get,
     path = "/api/git/diff/working",
     tag = "Git",
-    responses((status = 200, body = [GitDiffResult]))
+    responses((status = 200, body = GitWorkingDiffResponse))
 )]
 pub async fn get_working_diff(
     State(ctx): State<AppContext>,
     bearer: Bearer,
-) -> Result<Json<Vec<GitDiffResult>>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+) -> Result<Json<GitWorkingDiffResponse>, StatusCode> {
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let workspace = ctx.git_workspace();
     let uc = crate::application::use_cases::git::get_working_diff::GetWorkingDiff {
         workspace: workspace.as_ref(),
     };
-    let diffs = uc
+    let working = uc
         .execute(user_id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let body = diffs.into_iter().map(GitDiffResult::from).collect();
-    Ok(Json(body))
+    Ok(Json(GitWorkingDiffResponse {
+        commit_hash: working.base_commit_hash,
+        diffs: working.diffs.into_iter().map(GitDiffResult::from).collect(),
+    }))
 }
 
 #[utoipa::path(
@@ -584,7 +888,7 @@ pub async fn get_commit_diff(
     bearer: Bearer,
     axum::extract::Path((from, to)): axum::extract::Path<(String, String)>,
 ) -> Result<Json<Vec<GitDiffResult>>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let workspace = ctx.git_workspace();
     let uc = crate::application::use_cases::git::get_commit_diff::GetCommitDiff {
@@ -598,6 +902,90 @@ pub async fn get_commit_diff(
     Ok(Json(body))
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GitStorageUsageResponse {
+    pub commits: u64,
+    pub pack_bytes: u64,
+    pub snapshot_blobs: u64,
+    pub snapshot_bytes: u64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/git/storage",
+    tag = "Git",
+    responses((status = 200, body = GitStorageUsageResponse))
+)]
+pub async fn get_storage_usage(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+) -> Result<Json<GitStorageUsageResponse>, StatusCode> {
+    let sub = validate_bearer(&ctx, bearer)?;
+    let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let storage = ctx.git_storage();
+    let uc = crate::application::use_cases::git::storage_usage::GetGitStorageUsage {
+        storage: storage.as_ref(),
+    };
+    let usage = uc
+        .execute(user_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(GitStorageUsageResponse {
+        commits: usage.packs,
+        pack_bytes: usage.pack_bytes,
+        snapshot_blobs: usage.snapshot_blobs,
+        snapshot_bytes: usage.snapshot_bytes,
+    }))
+}
+
+#[derive(Debug, Deserialize, ToSchema, Default)]
+pub struct GitGcRequest {
+    /// Number of most recent commits whose snapshots are kept. Defaults to 20.
+    pub keep_commits: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GitGcResponse {
+    pub commits_pruned: u64,
+    pub blobs_deleted: u64,
+    pub bytes_freed: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/git/gc",
+    tag = "Git",
+    request_body = GitGcRequest,
+    responses((status = 200, body = GitGcResponse))
+)]
+pub async fn collect_garbage(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Json(req): Json<GitGcRequest>,
+) -> Result<Json<GitGcResponse>, StatusCode> {
+    use crate::application::use_cases::git::storage_usage::{
+        DEFAULT_KEEP_COMMITS, PruneGitSnapshots,
+    };
+    let sub = validate_bearer(&ctx, bearer)?;
+    let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let storage = ctx.git_storage();
+    let uc = PruneGitSnapshots {
+        storage: storage.as_ref(),
+    };
+    let out = uc
+        .execute(user_id, req.keep_commits.unwrap_or(DEFAULT_KEEP_COMMITS))
+        .await
+        .map_err(|e| {
+            tracing::error!(error=?e, "git_gc_failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(GitGcResponse {
+        commits_pruned: out.commits_pruned,
+        blobs_deleted: out.blobs_deleted,
+        bytes_freed: out.bytes_freed,
+    }))
+}
+
 // pull endpoint intentionally removed in push-only backup mode
 
 #[utoipa::path(post, path = "/api/git/init", tag = "Git", responses((status = 200, description = "OK")))]
@@ -605,7 +993,7 @@ pub async fn init_repository(
     State(ctx): State<AppContext>,
     bearer: Bearer,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.git_repo();
     let gitignore = ctx.gitignore_port();
@@ -628,7 +1016,7 @@ pub async fn deinit_repository(
     State(ctx): State<AppContext>,
     bearer: Bearer,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
-    let sub = validate_bearer(&ctx.cfg, bearer)?;
+    let sub = validate_bearer(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let workspace = ctx.git_workspace();
     let uc = DeinitRepo {
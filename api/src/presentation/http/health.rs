@@ -1,11 +1,41 @@
+use std::sync::Arc;
+
 use axum::{Json, Router, extract::State, routing::get};
 use serde::Serialize;
 use sqlx::PgPool;
 use utoipa::ToSchema;
 
+use crate::application::ports::plugin_asset_store::PluginAssetStore;
+use crate::application::services::health::{self, DependencyHealth};
+
+#[derive(Clone)]
+pub struct HealthState {
+    pool: PgPool,
+    plugin_assets: Arc<dyn PluginAssetStore>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DependencyStatus {
+    pub name: &'static str,
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<DependencyHealth> for DependencyStatus {
+    fn from(value: DependencyHealth) -> Self {
+        Self {
+            name: value.name,
+            status: if value.ok { "ok" } else { "unavailable" },
+            error: value.error,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct HealthResp {
     pub status: &'static str,
+    pub dependencies: Vec<DependencyStatus>,
 }
 
 #[utoipa::path(
@@ -14,15 +44,24 @@ pub struct HealthResp {
     tag = "Health",
     responses((status = 200, body = HealthResp))
 )]
-pub async fn health(State(pool): State<PgPool>) -> Json<HealthResp> {
-    let db_ok = sqlx::query_scalar::<_, i32>("SELECT 1")
-        .fetch_one(&pool)
-        .await
-        .is_ok();
-    let status = if db_ok { "ok" } else { "degraded" };
-    Json(HealthResp { status })
+pub async fn health(State(state): State<HealthState>) -> Json<HealthResp> {
+    let (database, plugins) = tokio::join!(
+        health::probe_database(&state.pool),
+        health::probe_plugin_store(state.plugin_assets.as_ref()),
+    );
+    let dependencies = vec![database, plugins];
+    let status = health::overall_status(&dependencies);
+    Json(HealthResp {
+        status,
+        dependencies: dependencies.into_iter().map(Into::into).collect(),
+    })
 }
 
-pub fn routes(pool: PgPool) -> Router {
-    Router::new().route("/health", get(health)).with_state(pool)
+pub fn routes(pool: PgPool, plugin_assets: Arc<dyn PluginAssetStore>) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .with_state(HealthState {
+            pool,
+            plugin_assets,
+        })
 }
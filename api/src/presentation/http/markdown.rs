@@ -1,11 +1,20 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::application::access;
+use crate::application::linkgraph;
+use crate::application::ports::plugin_runtime::PluginRuntime;
 use crate::application::services::markdown::{PlaceholderItem, RenderOptions, RenderResponse};
 use crate::bootstrap::app_context::AppContext;
 use crate::presentation::http::auth::{self, Bearer};
-use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
+use crate::presentation::http::rate_limit;
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::StatusCode,
+    routing::{get, post},
+};
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
 use serde::{Deserialize, Serialize};
@@ -19,6 +28,11 @@ pub fn routes(ctx: AppContext) -> Router {
     Router::new()
         .route("/markdown/render", post(render_markdown))
         .route("/markdown/render-many", post(render_markdown_many))
+        .route("/markdown/renderers", get(list_renderers))
+        .route_layer(axum::middleware::from_fn_with_state(
+            ctx.clone(),
+            rate_limit::limit_requests,
+        ))
         .with_state(ctx)
 }
 
@@ -27,10 +41,21 @@ pub fn routes(ctx: AppContext) -> Router {
 pub struct RenderOptionsPayload {
     pub flavor: Option<String>,
     pub theme: Option<String>,
+    /// e.g. `gfm`, or single GFM extensions: `table`, `strikethrough`, `tasklist`, `autolink`.
     pub features: Option<Vec<String>>,
     pub sanitize: Option<bool>,
+    /// If false, raw HTML is escaped instead of sanitized.
+    pub allow_raw_html: Option<bool>,
+    /// Link bare URLs; defaults to the gfm feature.
+    pub autolink: Option<bool>,
+    /// Curly quotes, dashes and ellipses; off by default.
+    pub smart_punctuation: Option<bool>,
+    /// Language of generated labels, e.g. `de`; defaults to English.
+    pub locale: Option<String>,
     pub doc_id: Option<uuid::Uuid>,
     pub base_origin: Option<String>,
+    /// Origin for attachment/image URLs, e.g. a CDN; defaults to `base_origin`.
+    pub asset_base: Option<String>,
     pub absolute_attachments: Option<bool>,
     pub token: Option<String>,
 }
@@ -42,10 +67,17 @@ impl From<RenderOptionsPayload> for RenderOptions {
             theme: value.theme,
             features: value.features,
             sanitize: value.sanitize,
+            allow_raw_html: value.allow_raw_html,
+            autolink: value.autolink,
+            smart_punctuation: value.smart_punctuation,
+            locale: value.locale,
             doc_id: value.doc_id,
             base_origin: value.base_origin,
+            asset_base: value.asset_base,
             absolute_attachments: value.absolute_attachments,
             token: value.token,
+            wiki_links: Default::default(),
+            budget: None,
         }
     }
 }
@@ -57,8 +89,13 @@ impl From<RenderOptions> for RenderOptionsPayload {
             theme: value.theme,
             features: value.features,
             sanitize: value.sanitize,
+            allow_raw_html: value.allow_raw_html,
+            autolink: value.autolink,
+            smart_punctuation: value.smart_punctuation,
+            locale: value.locale,
             doc_id: value.doc_id,
             base_origin: value.base_origin,
+            asset_base: value.asset_base,
             absolute_attachments: value.absolute_attachments,
             token: value.token,
         }
@@ -88,6 +125,14 @@ pub struct RenderResponseBody {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub placeholders: Vec<PlaceholderItemPayload>,
     pub hash: String,
+    /// Task list items; only with the `stats` feature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks_total: Option<usize>,
+    /// Checked task list items; only with the `stats` feature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tasks_completed: Option<usize>,
+    /// The document exceeded the render budget; the HTML is incomplete or partly unhighlighted.
+    pub truncated: bool,
 }
 
 impl From<RenderResponse> for RenderResponseBody {
@@ -100,6 +145,9 @@ impl From<RenderResponse> for RenderResponseBody {
                 .map(PlaceholderItemPayload::from)
                 .collect(),
             hash: value.hash,
+            tasks_total: value.tasks_total,
+            tasks_completed: value.tasks_completed,
+            truncated: value.truncated,
         }
     }
 }
@@ -134,11 +182,24 @@ pub async fn render_markdown(
         return Err(StatusCode::PAYLOAD_TOO_LARGE);
     }
     let RenderRequest { text, options } = req;
-    let options: RenderOptions = options.into();
+    let options = RenderOptions::from(options).with_defaults(&ctx.cfg.render_defaults);
 
     let bearer_token = bearer.as_ref().map(|b| b.0.as_str());
-    let user_scope =
-        resolve_user_scope_from_inputs(&ctx.cfg, bearer_token, options.token.as_deref());
+    let user_scope = resolve_user_scope_from_inputs(&ctx, bearer_token, options.token.as_deref());
+    Ok(Json(
+        render_in_scope(&ctx, user_scope, text, options).await?,
+    ))
+}
+
+/// Renders `text` with wikilinks resolved and placeholder renderers applied for `user_scope`
+/// (the caller's own plugins plus global ones).
+pub(crate) async fn render_in_scope(
+    ctx: &AppContext,
+    user_scope: Option<Uuid>,
+    text: String,
+    mut options: RenderOptions,
+) -> Result<RenderResponseBody, StatusCode> {
+    options.wiki_links = resolve_wiki_links(ctx, user_scope, &text).await;
 
     let assets = ctx.plugin_assets();
     let installations = ctx.plugin_installations();
@@ -168,14 +229,20 @@ pub async fn render_markdown(
         placeholder_kinds_ref,
     )
     .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    if !resp.placeholders.is_empty() && !renderer_specs.is_empty() {
-        if let Err(err) =
-            apply_placeholder_renderers(&ctx, &mut resp, &options, &renderer_specs).await
-        {
-            warn!(error = ?err, "markdown_placeholder_render_failed");
-        }
+    if !resp.placeholders.is_empty()
+        && !renderer_specs.is_empty()
+        && let Err(err) = apply_placeholder_renderers(
+            ctx.plugin_runtime().as_ref(),
+            &mut resp,
+            &options,
+            &renderer_specs,
+            Duration::from_millis(ctx.cfg.plugin_render_timeout_ms),
+        )
+        .await
+    {
+        warn!(error = ?err, "markdown_placeholder_render_failed");
     }
-    Ok(Json(RenderResponseBody::from(resp)))
+    Ok(RenderResponseBody::from(resp))
 }
 
 #[utoipa::path(post, path = "/api/markdown/render-many", tag = "Markdown",
@@ -210,13 +277,11 @@ pub async fn render_markdown_many(
             return Err(StatusCode::PAYLOAD_TOO_LARGE);
         }
         let RenderRequest { text, options } = item;
-        let options: RenderOptions = options.into();
+        let mut options = RenderOptions::from(options).with_defaults(&ctx.cfg.render_defaults);
 
-        let user_scope = resolve_user_scope_from_inputs(
-            &ctx.cfg,
-            bearer_token.as_deref(),
-            options.token.as_deref(),
-        );
+        let user_scope =
+            resolve_user_scope_from_inputs(&ctx, bearer_token.as_deref(), options.token.as_deref());
+        options.wiki_links = resolve_wiki_links(&ctx, user_scope, &text).await;
 
         let specs_arc = if let Some(existing) = spec_cache.get(&user_scope) {
             existing.clone()
@@ -255,13 +320,18 @@ pub async fn render_markdown_many(
         )
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-        if !res.placeholders.is_empty() && !specs_arc.is_empty() {
-            if let Err(err) =
-                apply_placeholder_renderers(&ctx, &mut res, &options, specs_arc.as_ref().as_slice())
-                    .await
-            {
-                warn!(error = ?err, "markdown_placeholder_render_failed_many");
-            }
+        if !res.placeholders.is_empty()
+            && !specs_arc.is_empty()
+            && let Err(err) = apply_placeholder_renderers(
+                ctx.plugin_runtime().as_ref(),
+                &mut res,
+                &options,
+                specs_arc.as_ref().as_slice(),
+                Duration::from_millis(ctx.cfg.plugin_render_timeout_ms),
+            )
+            .await
+        {
+            warn!(error = ?err, "markdown_placeholder_render_failed_many");
         }
 
         out.push(RenderResponseBody::from(res));
@@ -269,6 +339,78 @@ pub async fn render_markdown_many(
     Ok(Json(RenderManyResponse { items: out }))
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct RenderersQuery {
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RendererSpecPayload {
+    pub kind: String,
+    pub plugin: String,
+    pub version: String,
+    /// "global" or "user"
+    pub scope: String,
+    /// Whether the server renders this kind; otherwise the placeholder is left for the client.
+    pub server_rendered: bool,
+    pub hydrate_module_url: Option<String>,
+    pub hydrate_export: Option<String>,
+}
+
+impl From<&RendererSpec> for RendererSpecPayload {
+    fn from(spec: &RendererSpec) -> Self {
+        Self {
+            kind: spec.kind.clone(),
+            plugin: spec.plugin_id.clone(),
+            version: spec.plugin_version.clone(),
+            scope: spec.scope.as_str().to_string(),
+            server_rendered: spec.function.is_some(),
+            hydrate_module_url: spec
+                .hydrate
+                .as_ref()
+                .map(|hydrate| build_hydrate_module_url(spec, hydrate)),
+            hydrate_export: spec
+                .hydrate
+                .as_ref()
+                .map(|hydrate| hydrate.export.as_deref().unwrap_or("default").to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RendererSpecsResponse {
+    pub items: Vec<RendererSpecPayload>,
+}
+
+/// Renderers that apply to the caller's render requests: global plugins plus the caller's
+/// enabled user-scoped plugins. Clients use it to skip loading renderers for kinds the server
+/// already renders.
+#[utoipa::path(get, path = "/api/markdown/renderers", tag = "Markdown",
+    params(("token" = Option<String>, Query, description = "Share token (optional)")),
+    responses((status = 200, body = RendererSpecsResponse)))]
+pub async fn list_renderers(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Query(q): Query<RenderersQuery>,
+) -> Result<Json<RendererSpecsResponse>, StatusCode> {
+    let user_scope = resolve_user_scope_from_inputs(
+        &ctx,
+        bearer.as_ref().map(|b| b.0.as_str()),
+        q.token.as_deref(),
+    );
+    let assets = ctx.plugin_assets();
+    let installations = ctx.plugin_installations();
+    let specs = collect_renderer_specs(assets.as_ref(), Some(installations.as_ref()), user_scope)
+        .await
+        .map_err(|err| {
+            warn!(error = ?err, "markdown_renderer_specs_failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(RendererSpecsResponse {
+        items: specs.iter().map(RendererSpecPayload::from).collect(),
+    }))
+}
+
 #[derive(Clone, Debug)]
 struct RendererSpec {
     kind: String,
@@ -280,7 +422,6 @@ struct RendererSpec {
 }
 
 #[derive(Clone, Debug)]
-#[allow(dead_code)]
 enum RendererScope {
     Global,
     User { user_id: Uuid },
@@ -321,18 +462,20 @@ struct RendererPluginResponse {
     warnings: Option<Vec<String>>,
 }
 
+/// Replaces placeholders with plugin-rendered HTML. Candidates for a kind are tried in spec
+/// order (see `collect_renderer_specs`) until one succeeds. A call that takes longer than
+/// `timeout` leaves the placeholder for the client.
 async fn apply_placeholder_renderers(
-    ctx: &AppContext,
+    runtime: &dyn PluginRuntime,
     response: &mut RenderResponse,
     options: &RenderOptions,
     specs: &[RendererSpec],
+    timeout: Duration,
 ) -> anyhow::Result<()> {
     if specs.is_empty() {
         return Ok(());
     }
 
-    let runtime = ctx.plugin_runtime();
-
     let mut html = response.html.clone();
     let mut remaining: Vec<PlaceholderItem> = Vec::new();
     let mut kind_map: HashMap<&str, Vec<&RendererSpec>> = HashMap::new();
@@ -357,11 +500,11 @@ async fn apply_placeholder_renderers(
             let hydrate = spec.hydrate.as_ref();
 
             let Some(function) = spec.function.as_deref() else {
-                if let Some(hydrate) = hydrate {
-                    if attach_hydrate_metadata(&mut html, &placeholder, &request, spec, hydrate) {
-                        handled = true;
-                        break;
-                    }
+                if let Some(hydrate) = hydrate
+                    && attach_hydrate_metadata(&mut html, &placeholder, &request, spec, hydrate)
+                {
+                    handled = true;
+                    break;
                 }
                 continue;
             };
@@ -371,10 +514,18 @@ async fn apply_placeholder_renderers(
                 RendererScope::User { user_id } => Some(*user_id),
             };
 
-            match runtime
-                .render_placeholder(user_scope, &spec.plugin_id, function, &request)
-                .await
-            {
+            let call = runtime.render_placeholder(user_scope, &spec.plugin_id, function, &request);
+            let Ok(result) = tokio::time::timeout(timeout, call).await else {
+                warn!(
+                    plugin = spec.plugin_id.as_str(),
+                    kind = placeholder.kind.as_str(),
+                    id = placeholder.id.as_str(),
+                    timeout_ms = timeout.as_millis() as u64,
+                    "placeholder_renderer_timeout"
+                );
+                break;
+            };
+            match result {
                 Ok(Some(value)) => match serde_json::from_value::<RendererPluginResponse>(value) {
                     Ok(resp) if resp.ok => {
                         if let Some(warnings) = resp.warnings {
@@ -480,6 +631,7 @@ fn build_renderer_request(
     let doc_id = options.doc_id.map(|id| id.to_string());
     let token = options.token.clone();
     let base_origin = options.base_origin.clone();
+    let asset_base = options.asset_base.clone();
     let flavor = options.flavor.clone();
     let theme = options.theme.clone();
     serde_json::json!({
@@ -490,6 +642,7 @@ fn build_renderer_request(
             "doc_id": doc_id,
             "token": token,
             "base_origin": base_origin,
+            "asset_base": asset_base,
             "flavor": flavor,
             "theme": theme,
             "features": features,
@@ -497,23 +650,15 @@ fn build_renderer_request(
     })
 }
 
+/// Renderers available to `user_scope`: the user's enabled plugins first, then global
+/// plugins, so a renderer the user installed takes precedence over a global one for the same
+/// kind and the global one remains as a fallback.
 async fn collect_renderer_specs(
     assets: &dyn crate::application::ports::plugin_asset_store::PluginAssetStore,
     installations: Option<&dyn crate::application::ports::plugin_installation_repository::PluginInstallationRepository>,
     user_scope: Option<Uuid>,
 ) -> anyhow::Result<Vec<RendererSpec>> {
     let mut specs = Vec::new();
-    let manifests = assets.list_latest_global_manifests().await?;
-    for (plugin_id, version, manifest) in manifests {
-        push_renderers_from_manifest(
-            &mut specs,
-            &manifest,
-            &plugin_id,
-            &version,
-            RendererScope::Global,
-        );
-    }
-
     if let (Some(install_repo), Some(user_id)) = (installations, user_scope) {
         let installs = install_repo.list_for_user(user_id).await?;
         for inst in installs.into_iter().filter(|i| i.status == "enabled") {
@@ -532,6 +677,17 @@ async fn collect_renderer_specs(
         }
     }
 
+    let manifests = assets.list_latest_global_manifests().await?;
+    for (plugin_id, version, manifest) in manifests {
+        push_renderers_from_manifest(
+            &mut specs,
+            &manifest,
+            &plugin_id,
+            &version,
+            RendererScope::Global,
+        );
+    }
+
     Ok(specs)
 }
 
@@ -589,24 +745,44 @@ fn build_hydrated_fragment(
     ))
 }
 
+/// Resolves bracket wikilinks against the caller's documents. Links stay unresolved for
+/// anonymous callers or when resolution fails.
+async fn resolve_wiki_links(
+    ctx: &AppContext,
+    user_scope: Option<Uuid>,
+    text: &str,
+) -> BTreeMap<String, Uuid> {
+    let Some(owner_id) = user_scope else {
+        return BTreeMap::new();
+    };
+    if !text.contains("[[") {
+        return BTreeMap::new();
+    }
+    let repo = ctx.linkgraph_repo();
+    linkgraph::resolve_wikilink_targets(repo.as_ref(), owner_id, text)
+        .await
+        .unwrap_or_else(|err| {
+            warn!(error = ?err, "markdown_wikilink_resolution_failed");
+            BTreeMap::new()
+        })
+}
+
 fn resolve_user_scope_from_inputs(
-    cfg: &crate::bootstrap::config::Config,
+    ctx: &AppContext,
     bearer_token: Option<&str>,
     share_token: Option<&str>,
 ) -> Option<Uuid> {
-    if let Some(token) = bearer_token {
-        if let Ok(sub) = auth::validate_bearer_str(cfg, token) {
-            if let Ok(uid) = Uuid::parse_str(&sub) {
-                return Some(uid);
-            }
-        }
+    if let Some(token) = bearer_token
+        && let Ok(sub) = auth::validate_bearer_str(ctx, token)
+        && let Ok(uid) = Uuid::parse_str(&sub)
+    {
+        return Some(uid);
     }
-    if let Some(token) = share_token {
-        if let Some(actor) = auth::resolve_actor_from_token_str(cfg, token) {
-            if let access::Actor::User(uid) = actor {
-                return Some(uid);
-            }
-        }
+    if let Some(token) = share_token
+        && let Some(actor) = auth::resolve_actor_from_token_str(ctx, token)
+        && let access::Actor::User(uid) = actor
+    {
+        return Some(uid);
     }
     None
 }
@@ -722,16 +898,16 @@ fn build_hydrate_module_url(spec: &RendererSpec, hydrate: &HydrateSpec) -> Strin
         .asset_prefix(&spec.plugin_id, &spec.plugin_version);
     let module_path = hydrate.module.trim_start_matches('/');
     let mut url = format!("{}/{}", base.trim_end_matches('/'), module_path);
-    if let Some(etag) = &hydrate.etag {
-        if !etag.is_empty() {
-            let encoded = urlencoding::encode(etag);
-            if url.contains('?') {
-                url.push_str("&v=");
-                url.push_str(&encoded);
-            } else {
-                url.push_str("?v=");
-                url.push_str(&encoded);
-            }
+    if let Some(etag) = &hydrate.etag
+        && !etag.is_empty()
+    {
+        let encoded = urlencoding::encode(etag);
+        if url.contains('?') {
+            url.push_str("&v=");
+            url.push_str(&encoded);
+        } else {
+            url.push_str("?v=");
+            url.push_str(&encoded);
         }
     }
     url
@@ -793,3 +969,272 @@ fn insert_placeholder_attributes(target: &mut String, id: &str, attrs: &str) ->
     target.insert_str(insert_pos, attrs);
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use async_trait::async_trait;
+    use chrono::Utc;
+
+    use super::*;
+    use crate::application::ports::plugin_asset_store::PluginAssetStore;
+    use crate::application::ports::plugin_installation_repository::PluginInstallation;
+    use crate::test_support::plugins::MemoryPluginInstallations;
+
+    /// A global "diagrams" plugin rendering `mermaid` server-side; user-scoped "charts"
+    /// hydrating `chart` on the client and "sheets" rendering `csv`, `mermaid` and `slow`.
+    struct Assets;
+
+    #[async_trait]
+    impl PluginAssetStore for Assets {
+        fn global_root(&self) -> PathBuf {
+            unimplemented!()
+        }
+        fn user_root(&self, _: &Uuid) -> PathBuf {
+            unimplemented!()
+        }
+        fn latest_version_dir(&self, _: &Path) -> anyhow::Result<Option<PathBuf>> {
+            unimplemented!()
+        }
+        fn user_plugin_manifest_path(&self, _: &Uuid, _: &str, _: &str) -> PathBuf {
+            unimplemented!()
+        }
+        fn global_plugin_manifest_path(&self, _: &str, _: &str) -> PathBuf {
+            unimplemented!()
+        }
+        fn remove_user_plugin_dir(&self, _: &Uuid, _: &str) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn latest_user_version(&self, _: &Uuid, _: &str) -> anyhow::Result<Option<String>> {
+            unimplemented!()
+        }
+        async fn list_latest_global_manifests(
+            &self,
+        ) -> anyhow::Result<Vec<(String, String, serde_json::Value)>> {
+            Ok(vec![(
+                "diagrams".into(),
+                "1.2.0".into(),
+                json!({ "renderers": [{ "kind": "Mermaid", "function": "render_mermaid" }] }),
+            )])
+        }
+        async fn load_user_manifest(
+            &self,
+            _: &Uuid,
+            plugin_id: &str,
+            _: &str,
+        ) -> anyhow::Result<Option<serde_json::Value>> {
+            Ok(match plugin_id {
+                "charts" => Some(json!({
+                    "renderers": [{
+                        "kind": "chart",
+                        "hydrate": { "module": "dist/chart.js", "export": "mount" }
+                    }]
+                })),
+                "sheets" => Some(json!({
+                    "renderers": [
+                        { "kind": "csv", "function": "render_csv" },
+                        { "kind": "mermaid" },
+                        { "kind": "slow", "function": "hang" }
+                    ]
+                })),
+                _ => None,
+            })
+        }
+    }
+
+    fn install(user_id: Uuid, plugin_id: &str) -> PluginInstallation {
+        PluginInstallation {
+            user_id,
+            plugin_id: plugin_id.into(),
+            version: "0.3.0".into(),
+            scope: "user".into(),
+            origin_url: None,
+            status: "enabled".into(),
+            pinned: false,
+            installed_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    async fn renderers(
+        user_scope: Option<Uuid>,
+        installs: &MemoryPluginInstallations,
+    ) -> Vec<RendererSpecPayload> {
+        collect_renderer_specs(&Assets, Some(installs), user_scope)
+            .await
+            .unwrap()
+            .iter()
+            .map(RendererSpecPayload::from)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn global_mermaid_renderer_is_listed() {
+        let items = renderers(None, &MemoryPluginInstallations::default()).await;
+        assert_eq!(items.len(), 1);
+        let mermaid = &items[0];
+        assert_eq!(mermaid.kind, "mermaid");
+        assert_eq!(mermaid.plugin, "diagrams");
+        assert_eq!(mermaid.scope, "global");
+        assert!(mermaid.server_rendered);
+        assert_eq!(mermaid.hydrate_module_url, None);
+    }
+
+    #[tokio::test]
+    async fn user_scoped_renderers_are_listed_for_their_owner_only() {
+        let (user, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let installs = MemoryPluginInstallations::new(vec![install(user, "charts")]);
+
+        let items = renderers(Some(user), &installs).await;
+        let chart = items.iter().find(|i| i.kind == "chart").unwrap();
+        assert_eq!(chart.scope, "user");
+        assert!(!chart.server_rendered);
+        assert_eq!(
+            chart.hydrate_module_url.as_deref(),
+            Some(format!("/api/plugin-assets/{user}/charts/0.3.0/dist/chart.js").as_str())
+        );
+        assert_eq!(chart.hydrate_export.as_deref(), Some("mount"));
+        assert!(items.iter().any(|i| i.kind == "mermaid"));
+
+        let items = renderers(Some(other), &installs).await;
+        assert!(items.iter().all(|i| i.kind != "chart"));
+    }
+
+    /// Renders every placeholder as `<p>{plugin}:{function}</p>` and records who asked;
+    /// `hang` never returns in time.
+    #[derive(Default)]
+    struct Runtime {
+        calls: std::sync::Mutex<Vec<(Option<Uuid>, String)>>,
+    }
+
+    #[async_trait]
+    impl PluginRuntime for Runtime {
+        async fn execute(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+            _: &str,
+            _: &serde_json::Value,
+            _: &crate::application::ports::plugin_runtime::PluginSecrets,
+        ) -> anyhow::Result<Option<crate::application::dto::plugins::ExecResult>> {
+            unimplemented!()
+        }
+        async fn render_placeholder(
+            &self,
+            user_id: Option<Uuid>,
+            plugin: &str,
+            function: &str,
+            _: &serde_json::Value,
+        ) -> anyhow::Result<Option<serde_json::Value>> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((user_id, plugin.to_string()));
+            if function == "hang" {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+            Ok(Some(
+                json!({ "ok": true, "html": format!("<p>{plugin}:{function}</p>") }),
+            ))
+        }
+        async fn permissions(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+        ) -> anyhow::Result<Option<Vec<String>>> {
+            unimplemented!()
+        }
+        async fn schedules(
+            &self,
+            _: Option<Uuid>,
+            _: &str,
+        ) -> anyhow::Result<
+            Option<Vec<crate::application::ports::plugin_runtime::PluginScheduleSpec>>,
+        > {
+            unimplemented!()
+        }
+    }
+
+    const TIMEOUT: Duration = Duration::from_millis(200);
+
+    async fn render_with_plugins(
+        text: &str,
+        user_scope: Option<Uuid>,
+        installs: &MemoryPluginInstallations,
+        runtime: &Runtime,
+    ) -> RenderResponse {
+        let specs = collect_renderer_specs(&Assets, Some(installs), user_scope)
+            .await
+            .unwrap();
+        let kinds: HashSet<String> = specs.iter().map(|spec| spec.kind.clone()).collect();
+        let options = RenderOptions::default();
+        let mut resp = crate::application::services::markdown::render(
+            text.into(),
+            options.clone(),
+            Some(&kinds),
+        )
+        .unwrap();
+        apply_placeholder_renderers(runtime, &mut resp, &options, &specs, TIMEOUT)
+            .await
+            .unwrap();
+        resp
+    }
+
+    #[tokio::test]
+    async fn user_installed_renderer_handles_kinds_no_global_plugin_covers() {
+        let user = Uuid::new_v4();
+        let installs = MemoryPluginInstallations::new(vec![install(user, "sheets")]);
+        let runtime = Runtime::default();
+
+        let resp = render_with_plugins("```csv\na,b\n```\n", Some(user), &installs, &runtime).await;
+        assert!(resp.html.contains("<p>sheets:render_csv</p>"));
+        assert!(resp.placeholders.is_empty());
+        assert_eq!(
+            runtime.calls.lock().unwrap().as_slice(),
+            &[(Some(user), "sheets".to_string())]
+        );
+
+        // Without the user's plugins no renderer claims the block; it stays a code block.
+        let resp = render_with_plugins("```csv\na,b\n```\n", None, &installs, &runtime).await;
+        assert!(resp.placeholders.is_empty());
+        assert!(!resp.html.contains("sheets:render_csv"));
+        assert_eq!(runtime.calls.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn user_renderers_take_precedence_over_global_ones() {
+        let user = Uuid::new_v4();
+        let installs = MemoryPluginInstallations::new(vec![install(user, "sheets")]);
+        let runtime = Runtime::default();
+
+        let mermaid = "```mermaid\ngraph TD\n```\n";
+        let resp = render_with_plugins(mermaid, Some(user), &installs, &runtime).await;
+        assert!(resp.html.contains("<p>sheets:render</p>"));
+
+        let resp = render_with_plugins(mermaid, None, &installs, &runtime).await;
+        assert!(resp.html.contains("<p>diagrams:render_mermaid</p>"));
+        assert_eq!(
+            runtime.calls.lock().unwrap()[1],
+            (None, "diagrams".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn hanging_renderer_leaves_the_placeholder_and_render_completes() {
+        let user = Uuid::new_v4();
+        let installs = MemoryPluginInstallations::new(vec![install(user, "sheets")]);
+        let runtime = Runtime::default();
+
+        let text = "```slow\nwait\n```\n\n```csv\na,b\n```\n";
+        let started = std::time::Instant::now();
+        let resp = render_with_plugins(text, Some(user), &installs, &runtime).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        assert_eq!(resp.placeholders.len(), 1);
+        assert_eq!(resp.placeholders[0].kind, "slow");
+        assert!(resp.html.contains("data-placeholder-kind=\"slow\""));
+        // Other placeholders in the same document still render.
+        assert!(resp.html.contains("<p>sheets:render_csv</p>"));
+    }
+}
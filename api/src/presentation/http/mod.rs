@@ -1,10 +1,18 @@
 pub mod auth;
+pub mod byte_range;
+pub mod capabilities;
+pub mod client_ip;
+pub mod comments;
+pub mod content_type;
 pub mod documents;
 pub mod files;
 pub mod git;
 pub mod health;
 pub mod markdown;
+pub mod notifications;
 pub mod plugins;
 pub mod public;
+pub mod rate_limit;
 pub mod shares;
 pub mod tags;
+pub mod timeout;
@@ -0,0 +1,110 @@
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::application::ports::notification_repository::NotificationRow;
+use crate::bootstrap::app_context::AppContext;
+use crate::presentation::http::auth::{self, Bearer};
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationsQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub unread: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationItem {
+    pub id: Uuid,
+    /// `mention`, `share` or `comment`.
+    pub kind: String,
+    pub document_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    /// Kind-specific details, e.g. the document title of a mention.
+    #[schema(value_type = Object)]
+    pub data: serde_json::Value,
+    pub read_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<NotificationRow> for NotificationItem {
+    fn from(n: NotificationRow) -> Self {
+        NotificationItem {
+            id: n.id,
+            kind: n.kind,
+            document_id: n.document_id,
+            actor_id: n.actor_id,
+            data: n.data,
+            read_at: n.read_at,
+            created_at: n.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationListResponse {
+    pub items: Vec<NotificationItem>,
+    pub unread_count: i64,
+    pub next_offset: Option<i64>,
+}
+
+#[utoipa::path(get, path = "/api/me/notifications", tag = "Notifications", operation_id = "listNotifications",
+    params(
+        ("limit" = Option<i64>, Query, description = "Maximum number of notifications (default 20, max 100)"),
+        ("offset" = Option<i64>, Query, description = "Number of notifications to skip"),
+        ("unread" = Option<bool>, Query, description = "Only return unread notifications")
+    ),
+    responses((status = 200, body = NotificationListResponse)))]
+pub async fn list_notifications(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Query(q): Query<NotificationsQuery>,
+) -> Result<Json<NotificationListResponse>, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let page = ctx
+        .notifications()
+        .list(user_id, q.unread.unwrap_or(false), q.limit, q.offset)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(NotificationListResponse {
+        items: page.items.into_iter().map(Into::into).collect(),
+        unread_count: page.unread_count,
+        next_offset: page.next_offset,
+    }))
+}
+
+#[utoipa::path(post, path = "/api/me/notifications/{id}/read", tag = "Notifications", operation_id = "markNotificationRead",
+    params(("id" = Uuid, Path, description = "Notification ID")),
+    responses((status = 204), (status = 404, description = "Notification not found")))]
+pub async fn mark_notification_read(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, StatusCode> {
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let found = ctx
+        .notifications()
+        .mark_read(user_id, id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if found {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+pub fn routes(ctx: AppContext) -> Router {
+    Router::new()
+        .route("/me/notifications", get(list_notifications))
+        .route("/me/notifications/:id/read", post(mark_notification_read))
+        .with_state(ctx)
+}
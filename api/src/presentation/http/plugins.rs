@@ -2,8 +2,8 @@ use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::{
     Json, Router,
     extract::{Path, Query, State},
-    http::StatusCode,
-    routing::{get, patch, post},
+    http::{HeaderMap, StatusCode},
+    routing::{get, patch, post, put},
 };
 use futures_util::stream::{self, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
@@ -11,12 +11,21 @@ use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio_stream::wrappers::BroadcastStream;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::application::access;
-use crate::application::dto::plugins::ExecResult;
+use crate::application::dto::plugins::{ExecResult, PluginDataExport};
+use crate::application::ports::plugin_repository::{PluginKvEntry, PluginRecordImport};
+use crate::application::ports::plugin_runtime::PluginInvocationError;
+use crate::application::services::plugin_scheduler::upcoming_runs;
+use crate::application::use_cases::plugins::data_transfer::{ExportPluginData, ImportPluginData};
 use crate::application::use_cases::plugins::exec_action::ExecutePluginAction;
+use crate::application::use_cases::plugins::exec_log::ListPluginExecLog;
+use crate::application::use_cases::plugins::global::{
+    GlobalPluginError, InstallGlobalPlugin, SetGlobalPluginEnabled, UninstallGlobalPlugin,
+};
 use crate::application::use_cases::plugins::install_from_url::{
     InstallPluginError, InstallPluginFromUrl,
 };
@@ -24,22 +33,45 @@ use crate::application::use_cases::plugins::kv::{GetPluginKv, PutPluginKv};
 use crate::application::use_cases::plugins::records::{
     CreatePluginRecord, DeletePluginRecord, GetPluginRecord, ListPluginRecords, UpdatePluginRecord,
 };
+use crate::application::use_cases::plugins::secrets::{
+    DeletePluginSecret, ListPluginSecretKeys, PERMISSION_SECRETS, PutPluginSecret,
+};
+use crate::application::use_cases::plugins::versions::{
+    ActivePluginVersion, PinPluginVersion, PluginVersionError, UpdatePluginFromUrl,
+};
 use crate::bootstrap::app_context::AppContext;
+use crate::infrastructure::plugins::event_replay::SequencedEvent;
 use crate::presentation::http::auth::{self, Bearer};
+use crate::presentation::http::timeout;
 
 const PERMISSION_DOC_READ: &str = "doc.read";
 const PERMISSION_DOC_WRITE: &str = "doc.write";
 
 pub fn routes(ctx: AppContext) -> Router {
-    Router::new()
-        // Manifest for current user (stubbed)
-        .route("/me/plugins/manifest", get(get_manifest))
-        // SSE updates (stubbed)
-        .route("/me/plugins/updates", get(sse_updates))
+    // Plugin code, package downloads and bulk data transfers.
+    let long_running = Router::new()
         // Generic exec endpoint
         .route("/plugins/:plugin/exec/:action", post(exec_action))
         .route("/me/plugins/install-from-url", post(install_from_url))
+        .route("/me/plugins/:id/update", post(update_plugin))
+        .route("/plugins/:plugin/docs/:doc_id/export", get(export_data))
+        .route("/plugins/:plugin/docs/:doc_id/import", post(import_data))
+        .route(
+            "/admin/plugins/install-from-url",
+            post(install_global_from_url),
+        )
+        .route_layer(axum::middleware::from_fn(timeout::long_running));
+    // SSE updates, resumable with Last-Event-ID; open for as long as the client listens
+    let streaming = Router::new()
+        .route("/me/plugins/updates", get(sse_updates))
+        .route_layer(axum::middleware::from_fn(timeout::exempt));
+    Router::new()
+        // Manifest for current user (stubbed)
+        .route("/me/plugins/manifest", get(get_manifest))
         .route("/me/plugins/uninstall", post(uninstall))
+        .route("/me/plugins/:id/pin", post(pin_version))
+        .route("/me/plugins/:id/schedules", get(list_schedules))
+        .route("/me/plugins/:id/exec-log", get(list_exec_log))
         // Generic records API
         .route(
             "/plugins/:plugin/docs/:doc_id/records/:kind",
@@ -53,6 +85,20 @@ pub fn routes(ctx: AppContext) -> Router {
             "/plugins/:plugin/docs/:doc_id/kv/:key",
             get(get_kv_value).put(put_kv_value),
         )
+        // Per-user secrets, write-only from the client's side
+        .route("/plugins/:plugin/secrets", get(list_secret_keys))
+        .route(
+            "/plugins/:plugin/secrets/:key",
+            put(put_secret).delete(delete_secret),
+        )
+        // Global plugins (admin only)
+        .route("/admin/plugins/uninstall", post(uninstall_global))
+        .route("/admin/plugins/:id/enabled", post(set_global_enabled))
+        .merge(long_running)
+        .merge(streaming)
+        .layer(axum::extract::DefaultBodyLimit::max(
+            ctx.cfg.plugin_body_max_bytes,
+        ))
         .with_state(ctx)
 }
 
@@ -76,6 +122,8 @@ pub struct ExecResultResponse {
     pub effects: Vec<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub applied: Vec<serde_json::Value>,
 }
 
 impl From<ExecResult> for ExecResultResponse {
@@ -85,6 +133,7 @@ impl From<ExecResult> for ExecResultResponse {
             data: value.data,
             effects: value.effects,
             error: value.error,
+            applied: value.applied,
         }
     }
 }
@@ -112,13 +161,14 @@ pub async fn list_records(
     ensure_valid_plugin_id(&p.plugin)?;
     let token = params.get("token").map(|s| s.as_str());
     let actor =
-        auth::resolve_actor_from_parts(&ctx.cfg, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
+        auth::resolve_actor_from_parts(&ctx, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
     // View permission required on doc
     let share_access = ctx.share_access_port();
     let access_repo = ctx.access_repo();
     access::require_view(
         access_repo.as_ref(),
         share_access.as_ref(),
+        ctx.access_policy(),
         &actor,
         p.doc_id,
     )
@@ -197,13 +247,14 @@ pub async fn create_record(
     ensure_valid_plugin_id(&p.plugin)?;
     let token = params.get("token").map(|s| s.as_str());
     let actor =
-        auth::resolve_actor_from_parts(&ctx.cfg, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
+        auth::resolve_actor_from_parts(&ctx, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
     // Edit permission required on doc
     let share_access = ctx.share_access_port();
     let access_repo = ctx.access_repo();
     access::require_edit(
         access_repo.as_ref(),
         share_access.as_ref(),
+        ctx.access_policy(),
         &actor,
         p.doc_id,
     )
@@ -268,7 +319,7 @@ pub async fn update_record(
     Json(body): Json<UpdateRecordBody>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     ensure_valid_plugin_id(&p.plugin)?;
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
 
     let repo = ctx.plugin_repo();
@@ -292,6 +343,7 @@ pub async fn update_record(
     access::require_edit(
         access_repo.as_ref(),
         share_access.as_ref(),
+        ctx.access_policy(),
         &access::Actor::User(user_id),
         rec.scope_id,
     )
@@ -331,7 +383,7 @@ pub async fn delete_record(
     Path(p): Path<UpdateRecordPath>,
 ) -> Result<StatusCode, StatusCode> {
     ensure_valid_plugin_id(&p.plugin)?;
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.plugin_repo();
     // Get record to authorize
@@ -353,6 +405,7 @@ pub async fn delete_record(
     access::require_edit(
         access_repo.as_ref(),
         share_access.as_ref(),
+        ctx.access_policy(),
         &access::Actor::User(user_id),
         rec.scope_id,
     )
@@ -409,13 +462,14 @@ pub async fn get_kv_value(
     ensure_valid_plugin_id(&p.plugin)?;
     let token = params.get("token").map(|s| s.as_str());
     let actor =
-        auth::resolve_actor_from_parts(&ctx.cfg, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
+        auth::resolve_actor_from_parts(&ctx, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
     // View permission required on doc
     let share_access = ctx.share_access_port();
     let access_repo = ctx.access_repo();
     access::require_view(
         access_repo.as_ref(),
         share_access.as_ref(),
+        ctx.access_policy(),
         &actor,
         p.doc_id,
     )
@@ -462,13 +516,14 @@ pub async fn put_kv_value(
     ensure_valid_plugin_id(&p.plugin)?;
     let token = params.get("token").map(|s| s.as_str());
     let actor =
-        auth::resolve_actor_from_parts(&ctx.cfg, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
+        auth::resolve_actor_from_parts(&ctx, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
     // Edit permission required on doc
     let share_access = ctx.share_access_port();
     let access_repo = ctx.access_repo();
     access::require_edit(
         access_repo.as_ref(),
         share_access.as_ref(),
+        ctx.access_policy(),
         &actor,
         p.doc_id,
     )
@@ -495,6 +550,360 @@ pub async fn put_kv_value(
     Ok(StatusCode::NO_CONTENT)
 }
 
+const MAX_SECRET_KEY_LEN: usize = 128;
+const MAX_SECRET_VALUE_LEN: usize = 16 * 1024;
+
+fn ensure_valid_secret_key(key: &str) -> Result<(), StatusCode> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_SECRET_KEY_LEN
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(StatusCode::BAD_REQUEST)
+    }
+}
+
+async fn secrets_caller(
+    ctx: &AppContext,
+    bearer: Bearer,
+    plugin: &str,
+) -> Result<Uuid, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let runtime = ctx.plugin_runtime();
+    ensure_plugin_permission(&runtime, Some(user_id), plugin, PERMISSION_SECRETS).await?;
+    Ok(user_id)
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SecretPath {
+    plugin: String,
+    key: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SecretValueBody {
+    value: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SecretKeysResponse {
+    keys: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/plugins/{plugin}/secrets",
+    params(("plugin" = String, Path, description = "Plugin ID")),
+    responses(
+        (status = 200, body = SecretKeysResponse, description = "Names of stored secrets; values are never returned"),
+        (status = 403, description = "Plugin lacks the secrets permission")
+    ),
+    tag = "Plugins",
+    operation_id = "pluginsListSecrets"
+)]
+pub async fn list_secret_keys(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(plugin): Path<String>,
+) -> Result<Json<SecretKeysResponse>, StatusCode> {
+    let user_id = secrets_caller(&ctx, bearer, &plugin).await?;
+    let repo = ctx.plugin_secrets();
+    let keys = ListPluginSecretKeys {
+        repo: repo.as_ref(),
+    }
+    .execute(user_id, &plugin)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(SecretKeysResponse { keys }))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/plugins/{plugin}/secrets/{key}",
+    request_body = SecretValueBody,
+    params(("plugin" = String, Path, description = "Plugin ID"), ("key" = String, Path, description = "Secret name")),
+    responses(
+        (status = 204),
+        (status = 400, description = "Invalid secret name or value"),
+        (status = 403, description = "Plugin lacks the secrets permission")
+    ),
+    tag = "Plugins",
+    operation_id = "pluginsPutSecret"
+)]
+pub async fn put_secret(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(p): Path<SecretPath>,
+    Json(body): Json<SecretValueBody>,
+) -> Result<StatusCode, StatusCode> {
+    ensure_valid_secret_key(&p.key)?;
+    if body.value.len() > MAX_SECRET_VALUE_LEN {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let user_id = secrets_caller(&ctx, bearer, &p.plugin).await?;
+    let repo = ctx.plugin_secrets();
+    PutPluginSecret {
+        repo: repo.as_ref(),
+    }
+    .execute(user_id, &p.plugin, &p.key, &body.value)
+    .await
+    .map_err(|err| {
+        tracing::error!(error = ?err, plugin = p.plugin.as_str(), "plugin_secret_store_failed");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/plugins/{plugin}/secrets/{key}",
+    params(("plugin" = String, Path, description = "Plugin ID"), ("key" = String, Path, description = "Secret name")),
+    responses(
+        (status = 204),
+        (status = 403, description = "Plugin lacks the secrets permission"),
+        (status = 404, description = "Secret not found")
+    ),
+    tag = "Plugins",
+    operation_id = "pluginsDeleteSecret"
+)]
+pub async fn delete_secret(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(p): Path<SecretPath>,
+) -> Result<StatusCode, StatusCode> {
+    ensure_valid_secret_key(&p.key)?;
+    let user_id = secrets_caller(&ctx, bearer, &p.plugin).await?;
+    let repo = ctx.plugin_secrets();
+    let removed = DeletePluginSecret {
+        repo: repo.as_ref(),
+    }
+    .execute(user_id, &p.plugin, &p.key)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if removed {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DocScopePath {
+    plugin: String,
+    doc_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginRecordExport {
+    #[serde(default)]
+    id: Option<Uuid>,
+    kind: String,
+    data: serde_json::Value,
+    #[serde(default)]
+    created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PluginKvExport {
+    key: String,
+    value: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginDataExportBody {
+    plugin: String,
+    doc_id: Uuid,
+    records: Vec<PluginRecordExport>,
+    kv: Vec<PluginKvExport>,
+}
+
+impl From<PluginDataExport> for PluginDataExportBody {
+    fn from(export: PluginDataExport) -> Self {
+        Self {
+            plugin: export.plugin,
+            doc_id: export.doc_id,
+            records: export
+                .records
+                .into_iter()
+                .map(|r| PluginRecordExport {
+                    id: Some(r.id),
+                    kind: r.kind,
+                    data: r.data,
+                    created_at: Some(r.created_at),
+                    updated_at: Some(r.updated_at),
+                })
+                .collect(),
+            kv: export
+                .kv
+                .into_iter()
+                .map(|e| PluginKvExport {
+                    key: e.key,
+                    value: e.value,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl PluginDataExportBody {
+    fn into_import(self) -> (Vec<PluginRecordImport>, Vec<PluginKvEntry>) {
+        let records = self
+            .records
+            .into_iter()
+            .map(|r| PluginRecordImport {
+                id: r.id,
+                kind: r.kind,
+                data: r.data,
+                created_at: r.created_at,
+            })
+            .collect();
+        let kv = self
+            .kv
+            .into_iter()
+            .map(|e| PluginKvEntry {
+                key: e.key,
+                value: e.value,
+            })
+            .collect();
+        (records, kv)
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginDataImportBody {
+    data: PluginDataExportBody,
+    #[serde(default)]
+    preserve_ids: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PluginDataImportResponse {
+    records: usize,
+    kv: usize,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/plugins/{plugin}/docs/{doc_id}/export",
+    params(("plugin" = String, Path, description = "Plugin ID"), ("doc_id" = Uuid, Path, description = "Document ID"), ("token" = Option<String>, Query, description = "Share token")),
+    responses((status = 200, body = PluginDataExportBody)),
+    tag = "Plugins",
+    operation_id = "pluginsExportData"
+)]
+pub async fn export_data(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Query(params): Query<HashMap<String, String>>,
+    Path(p): Path<DocScopePath>,
+) -> Result<Json<PluginDataExportBody>, StatusCode> {
+    ensure_valid_plugin_id(&p.plugin)?;
+    let token = params.get("token").map(|s| s.as_str());
+    let actor =
+        auth::resolve_actor_from_parts(&ctx, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
+    let share_access = ctx.share_access_port();
+    let access_repo = ctx.access_repo();
+    access::require_view(
+        access_repo.as_ref(),
+        share_access.as_ref(),
+        ctx.access_policy(),
+        &actor,
+        p.doc_id,
+    )
+    .await
+    .map_err(|_| StatusCode::FORBIDDEN)?;
+
+    let runtime = ctx.plugin_runtime();
+    ensure_plugin_permission(
+        &runtime,
+        actor_user_id(&actor),
+        &p.plugin,
+        PERMISSION_DOC_READ,
+    )
+    .await?;
+
+    let repo = ctx.plugin_repo();
+    let export_uc = ExportPluginData {
+        repo: repo.as_ref(),
+    };
+    let export = export_uc
+        .execute(&p.plugin, p.doc_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(export.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/plugins/{plugin}/docs/{doc_id}/import",
+    request_body = PluginDataImportBody,
+    params(("plugin" = String, Path, description = "Plugin ID"), ("doc_id" = Uuid, Path, description = "Document ID"), ("token" = Option<String>, Query, description = "Share token")),
+    responses((status = 200, body = PluginDataImportResponse)),
+    tag = "Plugins",
+    operation_id = "pluginsImportData"
+)]
+pub async fn import_data(
+    State(ctx): State<AppContext>,
+    bearer: Option<Bearer>,
+    Query(params): Query<HashMap<String, String>>,
+    Path(p): Path<DocScopePath>,
+    Json(body): Json<PluginDataImportBody>,
+) -> Result<Json<PluginDataImportResponse>, StatusCode> {
+    ensure_valid_plugin_id(&p.plugin)?;
+    if body.data.plugin != p.plugin {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let token = params.get("token").map(|s| s.as_str());
+    let actor =
+        auth::resolve_actor_from_parts(&ctx, bearer, token).ok_or(StatusCode::UNAUTHORIZED)?;
+    let share_access = ctx.share_access_port();
+    let access_repo = ctx.access_repo();
+    access::require_edit(
+        access_repo.as_ref(),
+        share_access.as_ref(),
+        ctx.access_policy(),
+        &actor,
+        p.doc_id,
+    )
+    .await
+    .map_err(|_| StatusCode::FORBIDDEN)?;
+
+    let runtime = ctx.plugin_runtime();
+    ensure_plugin_permission(
+        &runtime,
+        actor_user_id(&actor),
+        &p.plugin,
+        PERMISSION_DOC_WRITE,
+    )
+    .await?;
+
+    let (records, kv) = body.data.into_import();
+
+    let repo = ctx.plugin_repo();
+    let import_uc = ImportPluginData {
+        repo: repo.as_ref(),
+    };
+    let summary = import_uc
+        .execute(&p.plugin, p.doc_id, records, kv, body.preserve_ids)
+        .await
+        .map_err(|err| {
+            tracing::warn!(error = ?err, plugin = p.plugin.as_str(), "plugin_data_import_failed");
+            StatusCode::CONFLICT
+        })?;
+    Ok(Json(PluginDataImportResponse {
+        records: summary.records,
+        kv: summary.kv,
+    }))
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ManifestItem {
     id: String,
@@ -553,7 +962,7 @@ fn manifest_item_from_json(
                 .filter_map(|v| v.as_str().map(|s| s.to_string()))
                 .collect::<Vec<String>>()
         })
-        .unwrap_or_else(|| vec![]);
+        .unwrap_or_default();
 
     let config = manifest.get("config").cloned().unwrap_or_else(|| json!({}));
     let ui = manifest.get("ui").cloned().unwrap_or_else(|| json!({}));
@@ -613,7 +1022,7 @@ pub async fn get_manifest(
     State(ctx): State<AppContext>,
     bearer: Bearer,
 ) -> Result<Json<Vec<ManifestItem>>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
 
     let store = ctx.plugin_assets();
@@ -645,8 +1054,7 @@ pub async fn get_manifest(
             .load_user_manifest(&user_id, &inst.plugin_id, &inst.version)
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-        {
-            if let Some(item) = manifest_item_from_json(
+            && let Some(item) = manifest_item_from_json(
                 &inst.plugin_id,
                 &inst.version,
                 &json,
@@ -655,9 +1063,9 @@ pub async fn get_manifest(
                     user_id, inst.plugin_id, inst.version
                 ),
                 "user",
-            ) {
-                items.push(item);
-            }
+            )
+        {
+            items.push(item);
         }
     }
 
@@ -682,7 +1090,11 @@ pub struct ExecBody {
     path = "/api/plugins/{plugin}/exec/{action}",
     request_body = ExecBody,
     params(("plugin" = String, Path, description = "Plugin ID"), ("action" = String, Path, description = "Action")),
-    responses((status = 200, body = ExecResultResponse)),
+    responses(
+        (status = 200, body = ExecResultResponse),
+        (status = 422, description = "Plugin trapped", body = ExecResultResponse),
+        (status = 504, description = "Plugin timed out", body = ExecResultResponse)
+    ),
     tag = "Plugins",
     operation_id = "pluginsExecAction"
 )]
@@ -691,71 +1103,128 @@ pub async fn exec_action(
     bearer: Bearer,
     Path((plugin, action)): Path<(String, String)>,
     Json(body): Json<ExecBody>,
-) -> Result<Json<ExecResultResponse>, StatusCode> {
+) -> Result<(StatusCode, Json<ExecResultResponse>), StatusCode> {
     ensure_valid_plugin_id(&plugin)?;
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
 
     let plugin_repo = ctx.plugin_repo();
     let document_repo = ctx.document_repo();
     let runtime_store = ctx.plugin_runtime();
+    let access_repo = ctx.access_repo();
+    let share_access = ctx.share_access_port();
+    let shares_repo = ctx.shares_repo();
+    let secrets = ctx.plugin_secrets();
     let exec_uc = ExecutePluginAction {
         runtime: runtime_store.as_ref(),
         plugin_repo: plugin_repo.as_ref(),
         document_repo: document_repo.as_ref(),
+        access_repo: access_repo.as_ref(),
+        share_access: share_access.as_ref(),
+        policy: ctx.access_policy(),
+        shares_repo: shares_repo.as_ref(),
+        secrets: secrets.as_ref(),
     };
 
-    match exec_uc
+    let outcome = match exec_uc
         .execute(user_id, &plugin, &action, body.payload.clone())
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
     {
-        Some(result) => Ok(Json(ExecResultResponse::from(result))),
-        None => Ok(Json(ExecResultResponse {
+        Ok(outcome) => outcome,
+        Err(err) => return invocation_failure_response(&plugin, &action, err),
+    };
+    match outcome {
+        Some(result) => Ok((StatusCode::OK, Json(ExecResultResponse::from(result)))),
+        None => Ok((
+            StatusCode::OK,
+            Json(ExecResultResponse {
+                ok: false,
+                data: None,
+                effects: vec![],
+                error: Some(json!({ "code": "UNKNOWN_ACTION" })),
+                applied: vec![],
+            }),
+        )),
+    }
+}
+
+fn invocation_failure_response(
+    plugin: &str,
+    action: &str,
+    err: anyhow::Error,
+) -> Result<(StatusCode, Json<ExecResultResponse>), StatusCode> {
+    let (status, error) = match err.downcast_ref::<PluginInvocationError>() {
+        Some(PluginInvocationError::Timeout) => (
+            StatusCode::GATEWAY_TIMEOUT,
+            json!({ "code": "PLUGIN_TIMEOUT" }),
+        ),
+        Some(PluginInvocationError::Trap(message)) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            json!({ "code": "PLUGIN_TRAP", "message": message }),
+        ),
+        None => {
+            tracing::error!(error = ?err, plugin, action, "plugin_exec_failed");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    tracing::warn!(error = ?err, plugin, action, "plugin_exec_aborted");
+    Ok((
+        status,
+        Json(ExecResultResponse {
             ok: false,
             data: None,
             effects: vec![],
-            error: Some(json!({ "code": "UNKNOWN_ACTION" })),
-        })),
-    }
+            error: Some(error),
+            applied: vec![],
+        }),
+    ))
 }
 
 #[utoipa::path(
     get,
     path = "/api/me/plugins/updates",
     tag = "Plugins",
+    params(("Last-Event-ID" = Option<String>, Header, description = "Replay events emitted after this id")),
     responses((status = 200, description = "Plugin event stream", content_type = "text/event-stream"))
 )]
 pub async fn sse_updates(
     State(ctx): State<AppContext>,
     bearer: Bearer,
+    headers: HeaderMap,
 ) -> Result<Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>>, StatusCode> {
     // authenticate user (per-user stream)
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok());
 
+    let (missed, live) = ctx.plugin_event_replay().subscribe(user_id, last_event_id);
     let initial = stream::iter(vec![Ok(Event::default().event("ready").data("{}\n"))]);
-    let event_stream = ctx
-        .subscribe_plugin_events()
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let broadcast = event_stream.filter_map(move |ev| {
-        let user_id = user_id.clone();
-        async move {
-            if ev.user_id.is_some() && ev.user_id != Some(user_id) {
-                return None;
-            }
-            let payload = ev.payload.to_string();
-            Some(Ok(Event::default().event("update").data(payload)))
+    let replayed = stream::iter(missed.into_iter().map(|ev| Ok(update_event(&ev))));
+    let broadcast = BroadcastStream::new(live).filter_map(move |ev| async move {
+        // A lagging receiver skips ahead; the client can reconnect to replay.
+        let ev = ev.ok()?;
+        if ev.event.user_id.is_some() && ev.event.user_id != Some(user_id) {
+            return None;
         }
+        Some(Ok(update_event(&ev)))
     });
-    let merged = initial.chain(broadcast);
+    let merged = initial.chain(replayed).chain(broadcast);
     let keepalive = KeepAlive::new()
         .interval(Duration::from_secs(25))
         .text(":\n");
     Ok(Sse::new(merged).keep_alive(keepalive))
 }
 
+fn update_event(ev: &SequencedEvent) -> Event {
+    Event::default()
+        .id(ev.id.to_string())
+        .event("update")
+        .data(ev.event.payload.to_string())
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct InstallFromUrlBody {
     url: String,
@@ -781,7 +1250,7 @@ pub async fn install_from_url(
     bearer: Bearer,
     Json(body): Json<InstallFromUrlBody>,
 ) -> Result<Json<InstallResponse>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
 
     let fetcher = ctx.plugin_fetcher();
@@ -840,7 +1309,7 @@ pub async fn uninstall(
     bearer: Bearer,
     Json(body): Json<UninstallBody>,
 ) -> Result<StatusCode, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let UninstallBody { id } = body;
     let trimmed_id = id.trim();
@@ -874,6 +1343,257 @@ pub async fn uninstall(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PinVersionBody {
+    /// Version to keep active; omit or null to follow the newest installed version.
+    version: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdatePluginBody {
+    /// Package URL; defaults to the URL the plugin was installed from.
+    url: Option<String>,
+    token: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActiveVersionResponse {
+    id: String,
+    version: String,
+    pinned: bool,
+}
+
+impl From<ActivePluginVersion> for ActiveVersionResponse {
+    fn from(value: ActivePluginVersion) -> Self {
+        Self {
+            id: value.id,
+            version: value.version,
+            pinned: value.pinned,
+        }
+    }
+}
+
+fn version_error_status(err: &PluginVersionError) -> StatusCode {
+    match err {
+        PluginVersionError::NotInstalled | PluginVersionError::VersionNotFound => {
+            StatusCode::NOT_FOUND
+        }
+        PluginVersionError::MissingOrigin | PluginVersionError::PluginMismatch(_) => {
+            StatusCode::BAD_REQUEST
+        }
+        PluginVersionError::Download(_) => StatusCode::BAD_GATEWAY,
+        PluginVersionError::Install(
+            crate::application::ports::plugin_installer::PluginInstallError::InvalidPackage(_),
+        ) => StatusCode::BAD_REQUEST,
+        PluginVersionError::Install(_)
+        | PluginVersionError::Storage(_)
+        | PluginVersionError::Persist(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/me/plugins/{id}/pin",
+    request_body = PinVersionBody,
+    params(("id" = String, Path, description = "Plugin ID")),
+    responses(
+        (status = 200, body = ActiveVersionResponse),
+        (status = 404, description = "Plugin or version not installed")
+    ),
+    tag = "Plugins",
+    operation_id = "pluginsPinVersion"
+)]
+pub async fn pin_version(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<String>,
+    Json(body): Json<PinVersionBody>,
+) -> Result<Json<ActiveVersionResponse>, StatusCode> {
+    ensure_valid_plugin_id(&id)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    let assets = ctx.plugin_assets();
+    let publisher = ctx.plugin_event_publisher();
+    let installations = ctx.plugin_installations();
+    let pin_uc = PinPluginVersion {
+        assets: assets.as_ref(),
+        events: publisher.as_ref(),
+        installations: installations.as_ref(),
+    };
+    let version = body.version.as_deref().map(str::trim);
+    match pin_uc.execute(user_id, &id, version).await {
+        Ok(active) => Ok(Json(active.into())),
+        Err(err) => {
+            tracing::warn!(error = ?err, plugin = id.as_str(), "plugin_pin_failed");
+            Err(version_error_status(&err))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/me/plugins/{id}/update",
+    request_body = UpdatePluginBody,
+    params(("id" = String, Path, description = "Plugin ID")),
+    responses(
+        (status = 200, body = ActiveVersionResponse),
+        (status = 404, description = "Plugin not installed"),
+        (status = 502, description = "Package download failed")
+    ),
+    tag = "Plugins",
+    operation_id = "pluginsUpdate"
+)]
+pub async fn update_plugin(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<String>,
+    Json(body): Json<UpdatePluginBody>,
+) -> Result<Json<ActiveVersionResponse>, StatusCode> {
+    ensure_valid_plugin_id(&id)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    let fetcher = ctx.plugin_fetcher();
+    let installer = ctx.plugin_installer();
+    let publisher = ctx.plugin_event_publisher();
+    let installations = ctx.plugin_installations();
+    let update_uc = UpdatePluginFromUrl {
+        fetcher: fetcher.as_ref(),
+        installer: installer.as_ref(),
+        events: publisher.as_ref(),
+        installations: installations.as_ref(),
+    };
+    match update_uc
+        .execute(user_id, &id, body.url.as_deref(), body.token.as_deref())
+        .await
+    {
+        Ok(active) => Ok(Json(active.into())),
+        Err(err) => {
+            tracing::error!(error = ?err, plugin = id.as_str(), "plugin_update_failed");
+            Err(version_error_status(&err))
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginScheduleResponse {
+    cron: String,
+    action: String,
+    /// Absent when the cron expression is invalid or never matches.
+    next_run_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PluginSchedulesResponse {
+    items: Vec<PluginScheduleResponse>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/me/plugins/{id}/schedules",
+    params(("id" = String, Path, description = "Plugin ID")),
+    responses(
+        (status = 200, body = PluginSchedulesResponse),
+        (status = 404, description = "Plugin not installed")
+    ),
+    tag = "Plugins",
+    operation_id = "pluginsListSchedules"
+)]
+pub async fn list_schedules(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<String>,
+) -> Result<Json<PluginSchedulesResponse>, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    ensure_valid_plugin_id(&id)?;
+
+    let schedules = ctx
+        .plugin_runtime()
+        .schedules(Some(user_id), &id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let items = upcoming_runs(&schedules, chrono::Utc::now())
+        .into_iter()
+        .map(|run| PluginScheduleResponse {
+            cron: run.cron,
+            action: run.action,
+            next_run_at: run.next_run_at,
+        })
+        .collect();
+    Ok(Json(PluginSchedulesResponse { items }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginExecLogItem {
+    function: String,
+    input_bytes: i64,
+    output_bytes: Option<i64>,
+    duration_ms: i64,
+    ok: bool,
+    error: Option<String>,
+    /// Redacted call payload; only recorded when payload logging is enabled.
+    payload: Option<serde_json::Value>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PluginExecLogResponse {
+    items: Vec<PluginExecLogItem>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/me/plugins/{id}/exec-log",
+    params(
+        ("id" = String, Path, description = "Plugin ID"),
+        ("limit" = Option<i64>, Query, description = "Limit")
+    ),
+    responses((status = 200, body = PluginExecLogResponse)),
+    tag = "Plugins",
+    operation_id = "pluginsListExecLog"
+)]
+pub async fn list_exec_log(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<PluginExecLogResponse>, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    ensure_valid_plugin_id(&id)?;
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse::<i64>().ok())
+        .unwrap_or(50);
+
+    let repo = ctx.plugin_exec_log();
+    let entries = ListPluginExecLog {
+        repo: repo.as_ref(),
+    }
+    .execute(user_id, &id, limit)
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let items = entries
+        .into_iter()
+        .map(|entry| PluginExecLogItem {
+            function: entry.function,
+            input_bytes: entry.input_bytes,
+            output_bytes: entry.output_bytes,
+            duration_ms: entry.duration_ms,
+            ok: entry.ok,
+            error: entry.error,
+            payload: entry.payload,
+            created_at: entry.created_at,
+        })
+        .collect();
+    Ok(Json(PluginExecLogResponse { items }))
+}
+
 async fn ensure_plugin_permission(
     runtime: &Arc<dyn crate::application::ports::plugin_runtime::PluginRuntime>,
     user_id: Option<Uuid>,
@@ -901,3 +1621,207 @@ fn actor_user_id(actor: &access::Actor) -> Option<Uuid> {
         _ => None,
     }
 }
+
+fn global_error_status(err: &GlobalPluginError) -> StatusCode {
+    match err {
+        GlobalPluginError::Forbidden => StatusCode::FORBIDDEN,
+        GlobalPluginError::NotInstalled => StatusCode::NOT_FOUND,
+        GlobalPluginError::Download(_) => StatusCode::BAD_GATEWAY,
+        GlobalPluginError::Install(
+            crate::application::ports::plugin_installer::PluginInstallError::InvalidPackage(_),
+        ) => StatusCode::BAD_REQUEST,
+        GlobalPluginError::Install(_)
+        | GlobalPluginError::Storage(_)
+        | GlobalPluginError::Event(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/plugins/install-from-url",
+    request_body = InstallFromUrlBody,
+    responses(
+        (status = 200, body = InstallResponse),
+        (status = 403, description = "Caller is not an administrator"),
+        (status = 502, description = "Package download failed")
+    ),
+    tag = "Plugins",
+    operation_id = "pluginsInstallGlobal"
+)]
+pub async fn install_global_from_url(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Json(body): Json<InstallFromUrlBody>,
+) -> Result<Json<InstallResponse>, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    let users = ctx.user_repo();
+    let fetcher = ctx.plugin_fetcher();
+    let installer = ctx.plugin_installer();
+    let publisher = ctx.plugin_event_publisher();
+    let uc = InstallGlobalPlugin {
+        users: users.as_ref(),
+        admin_emails: &ctx.cfg.admin_emails,
+        fetcher: fetcher.as_ref(),
+        installer: installer.as_ref(),
+        events: publisher.as_ref(),
+    };
+    match uc.execute(user_id, &body.url, body.token.as_deref()).await {
+        Ok(installed) => Ok(Json(InstallResponse {
+            id: installed.id,
+            version: installed.version,
+        })),
+        Err(err) => {
+            tracing::error!(error = ?err, "failed to install global plugin");
+            Err(global_error_status(&err))
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/plugins/uninstall",
+    request_body = UninstallBody,
+    responses(
+        (status = 204),
+        (status = 403, description = "Caller is not an administrator"),
+        (status = 404, description = "Plugin not installed")
+    ),
+    tag = "Plugins",
+    operation_id = "pluginsUninstallGlobal"
+)]
+pub async fn uninstall_global(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Json(body): Json<UninstallBody>,
+) -> Result<StatusCode, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let plugin_id = body.id.trim();
+    ensure_valid_plugin_id(plugin_id)?;
+
+    let users = ctx.user_repo();
+    let installer = ctx.plugin_installer();
+    let publisher = ctx.plugin_event_publisher();
+    let uc = UninstallGlobalPlugin {
+        users: users.as_ref(),
+        admin_emails: &ctx.cfg.admin_emails,
+        installer: installer.as_ref(),
+        events: publisher.as_ref(),
+    };
+    uc.execute(user_id, plugin_id).await.map_err(|err| {
+        tracing::warn!(error = ?err, plugin = plugin_id, "global_plugin_uninstall_failed");
+        global_error_status(&err)
+    })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GlobalPluginEnabledBody {
+    enabled: bool,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/plugins/{id}/enabled",
+    request_body = GlobalPluginEnabledBody,
+    params(("id" = String, Path, description = "Plugin ID")),
+    responses(
+        (status = 204),
+        (status = 403, description = "Caller is not an administrator"),
+        (status = 404, description = "Plugin not installed")
+    ),
+    tag = "Plugins",
+    operation_id = "pluginsSetGlobalEnabled"
+)]
+pub async fn set_global_enabled(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path(id): Path<String>,
+    Json(body): Json<GlobalPluginEnabledBody>,
+) -> Result<StatusCode, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    ensure_valid_plugin_id(&id)?;
+
+    let users = ctx.user_repo();
+    let installer = ctx.plugin_installer();
+    let publisher = ctx.plugin_event_publisher();
+    let uc = SetGlobalPluginEnabled {
+        users: users.as_ref(),
+        admin_emails: &ctx.cfg.admin_emails,
+        installer: installer.as_ref(),
+        events: publisher.as_ref(),
+    };
+    uc.execute(user_id, &id, body.enabled)
+        .await
+        .map_err(|err| {
+            tracing::warn!(error = ?err, plugin = id.as_str(), "global_plugin_toggle_failed");
+            global_error_status(&err)
+        })?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::application::ports::plugin_repository::{PluginRecord, PluginRepository};
+    use crate::test_support::plugins::MemoryPluginData;
+
+    #[tokio::test]
+    async fn exported_data_imports_into_a_fresh_document() {
+        let repo = MemoryPluginData::default();
+        let (source, target) = (Uuid::new_v4(), Uuid::new_v4());
+        for (kind, data) in [
+            ("comment", json!({ "text": "first" })),
+            ("comment", json!({ "text": "second", "pinned": true })),
+            ("vote", json!({ "up": 3 })),
+        ] {
+            repo.insert_record("board", "doc", source, kind, &data)
+                .await
+                .unwrap();
+        }
+        repo.kv_set(
+            "board",
+            "doc",
+            Some(source),
+            "layout",
+            &json!({ "columns": 2 }),
+        )
+        .await
+        .unwrap();
+        let export = ExportPluginData { repo: &repo };
+
+        let body = PluginDataExportBody::from(export.execute("board", source).await.unwrap());
+        let wire = json!({ "data": serde_json::to_value(&body).unwrap() });
+        let import: PluginDataImportBody = serde_json::from_value(wire).unwrap();
+        assert!(!import.preserve_ids);
+        let (records, kv) = import.data.into_import();
+        let summary = ImportPluginData { repo: &repo }
+            .execute("board", target, records, kv, import.preserve_ids)
+            .await
+            .unwrap();
+        assert_eq!((summary.records, summary.kv), (3, 1));
+
+        let original = export.execute("board", source).await.unwrap();
+        let copy = export.execute("board", target).await.unwrap();
+        let contents = |records: &[PluginRecord]| {
+            records
+                .iter()
+                .map(|r| (r.kind.clone(), r.data.clone(), r.created_at))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(contents(&copy.records), contents(&original.records));
+        assert!(
+            copy.records
+                .iter()
+                .all(|r| original.records.iter().all(|o| o.id != r.id))
+        );
+        assert_eq!(copy.kv.len(), 1);
+        assert_eq!(copy.kv[0].key, "layout");
+        assert_eq!(copy.kv[0].value, json!({ "columns": 2 }));
+    }
+}
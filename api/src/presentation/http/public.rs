@@ -1,22 +1,27 @@
 use axum::{
     Json, Router,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
     routing::{get, post},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::bootstrap::app_context::AppContext;
 use crate::presentation::http::auth::Bearer;
+use crate::presentation::http::client_ip::ClientIp;
 use crate::presentation::http::documents::Document;
+use crate::presentation::http::rate_limit;
 // use crate::presentation::http::auth; // not needed explicitly
 use crate::application::use_cases::public::get_public::GetPublicByOwnerAndId;
 use crate::application::use_cases::public::get_status::GetPublishStatus;
 use crate::application::use_cases::public::list_user::{ListUserPublic, PublicDocumentSummaryDto};
 use crate::application::use_cases::public::publish::PublishDocument;
+use crate::application::use_cases::public::sitemap::{GetPublicIndex, GetPublicSitemap};
 use crate::application::use_cases::public::unpublish::UnpublishDocument;
+use crate::application::use_cases::public::view_stats::GetPublicViewStats;
 
 // Uses AppContext as router state
 
@@ -38,11 +43,13 @@ pub async fn publish_document(
     bearer: Bearer,
     Path(id): Path<Uuid>,
 ) -> Result<Json<PublishResponse>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.public_repo();
+    let listings = ctx.public_listings();
     let uc = PublishDocument {
         repo: repo.as_ref(),
+        listings: listings.as_ref(),
     };
     let res = uc
         .execute(user_id, id)
@@ -67,11 +74,13 @@ pub async fn unpublish_document(
     bearer: Bearer,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.public_repo();
+    let listings = ctx.public_listings();
     let uc = UnpublishDocument {
         repo: repo.as_ref(),
+        listings: listings.as_ref(),
     };
     let ok = uc
         .execute(user_id, id)
@@ -97,7 +106,7 @@ pub async fn get_publish_status(
     Path(id): Path<Uuid>,
 ) -> Result<Json<PublishResponse>, StatusCode> {
     // Validate ownership
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.public_repo();
     let uc = GetPublishStatus {
@@ -143,17 +152,107 @@ pub async fn list_user_public_documents(
         .execute(&name)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    Ok(Json(
-        items
-            .into_iter()
-            .map(|d: PublicDocumentSummaryDto| PublicDocumentSummary {
-                id: d.id,
-                title: d.title,
-                updated_at: d.updated_at,
-                published_at: d.published_at,
-            })
-            .collect(),
-    ))
+    Ok(Json(items.into_iter().map(summary_from_dto).collect()))
+}
+
+fn summary_from_dto(d: PublicDocumentSummaryDto) -> PublicDocumentSummary {
+    PublicDocumentSummary {
+        id: d.id,
+        title: d.title,
+        updated_at: d.updated_at,
+        published_at: d.published_at,
+    }
+}
+
+/// Public origin for sitemap links: the configured public base URL, else the request host.
+fn public_base_url(ctx: &AppContext, headers: &HeaderMap) -> Option<String> {
+    if let Some(url) = ctx.cfg.public_base_url.as_deref() {
+        return Some(url.to_string());
+    }
+    let host = headers.get(header::HOST)?.to_str().ok()?;
+    let scheme = headers
+        .get("x-forwarded-proto")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("http");
+    Some(format!("{}://{}", scheme, host))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/public/users/{name}/sitemap.xml",
+    tag = "Public Documents",
+    params(("name" = String, Path, description = "Owner name")),
+    responses((status = 200, description = "Sitemap of the owner's published documents", content_type = "application/xml", body = String))
+)]
+pub async fn get_user_public_sitemap(
+    State(ctx): State<AppContext>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let base_url = public_base_url(&ctx, &headers).ok_or(StatusCode::BAD_REQUEST)?;
+    let repo = ctx.public_repo();
+    let listings = ctx.public_listings();
+    let uc = GetPublicSitemap {
+        repo: repo.as_ref(),
+        listings: listings.as_ref(),
+    };
+    let xml = uc
+        .execute(&name, &base_url)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok((
+        [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+        xml,
+    )
+        .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PublicIndexQuery {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicDocumentIndex {
+    pub items: Vec<PublicDocumentSummary>,
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/public/users/{name}/index",
+    tag = "Public Documents",
+    params(
+        ("name" = String, Path, description = "Owner name"),
+        ("page" = Option<usize>, Query, description = "Page number, starting at 1"),
+        ("per_page" = Option<usize>, Query, description = "Items per page (default 50, max 500)")
+    ),
+    responses((status = 200, description = "Page of the owner's published documents", body = PublicDocumentIndex))
+)]
+pub async fn get_user_public_index(
+    State(ctx): State<AppContext>,
+    Path(name): Path<String>,
+    Query(q): Query<PublicIndexQuery>,
+) -> Result<Json<PublicDocumentIndex>, StatusCode> {
+    let repo = ctx.public_repo();
+    let listings = ctx.public_listings();
+    let uc = GetPublicIndex {
+        repo: repo.as_ref(),
+        listings: listings.as_ref(),
+    };
+    let page = uc
+        .execute(&name, q.page.unwrap_or(1), q.per_page.unwrap_or(50))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(PublicDocumentIndex {
+        items: page.items.into_iter().map(summary_from_dto).collect(),
+        total: page.total,
+        page: page.page,
+        per_page: page.per_page,
+    }))
 }
 
 #[utoipa::path(
@@ -184,6 +283,10 @@ pub async fn get_public_by_owner_and_id(
         created_at: d.created_at,
         updated_at: d.updated_at,
         path: d.path,
+        icon: d.icon,
+        color: d.color,
+        backlink_count: None,
+        outgoing_count: None,
     }))
 }
 
@@ -197,6 +300,8 @@ pub async fn get_public_by_owner_and_id(
 pub async fn get_public_content_by_owner_and_id(
     State(ctx): State<AppContext>,
     Path((name, id)): Path<(String, Uuid)>,
+    client: ClientIp,
+    headers: HeaderMap,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let repo = ctx.public_repo();
     let exists = repo
@@ -206,6 +311,10 @@ pub async fn get_public_content_by_owner_and_id(
     if !exists {
         return Err(StatusCode::NOT_FOUND);
     }
+    if ctx.cfg.public_analytics_enabled {
+        ctx.public_views()
+            .record(id, &viewer_key(client, &headers), chrono::Utc::now());
+    }
     let realtime = ctx.realtime_engine();
     let content = realtime
         .get_content(&id.to_string())
@@ -217,6 +326,62 @@ pub async fn get_public_content_by_owner_and_id(
         .unwrap_or_default();
     Ok(Json(serde_json::json!({"content": content, "id": id})))
 }
+/// Identifies a viewer for debouncing only: client address and user agent.
+fn viewer_key(client: ClientIp, headers: &HeaderMap) -> String {
+    let addr = client.key();
+    let agent = headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    format!("{}|{}", addr, agent)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublicViewStats {
+    pub total_views: i64,
+    pub recent_views: i64,
+    /// Days covered by `recent_views`, including today.
+    pub recent_days: i64,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/public/users/{name}/{id}/stats",
+    tag = "Public Documents",
+    params(("name" = String, Path, description = "Owner name"), ("id" = Uuid, Path, description = "Document ID")),
+    responses(
+        (status = 200, description = "View counts", body = PublicViewStats),
+        (status = 404, description = "Not the owner, or analytics disabled")
+    )
+)]
+pub async fn get_public_view_stats(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Path((_name, id)): Path<(String, Uuid)>,
+) -> Result<Json<PublicViewStats>, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    if !ctx.cfg.public_analytics_enabled {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    let repo = ctx.public_repo();
+    let views = ctx.public_views();
+    let uc = GetPublicViewStats {
+        repo: repo.as_ref(),
+        views: views.as_ref(),
+    };
+    let stats = uc
+        .execute(user_id, id, chrono::Utc::now())
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(PublicViewStats {
+        total_views: stats.total_views,
+        recent_views: stats.recent_views,
+        recent_days: stats.recent_days,
+    }))
+}
+
 pub fn routes(ctx: AppContext) -> Router {
     Router::new()
         .route(
@@ -226,10 +391,17 @@ pub fn routes(ctx: AppContext) -> Router {
                 .get(get_publish_status),
         )
         .route("/users/:name", get(list_user_public_documents))
+        .route("/users/:name/sitemap.xml", get(get_user_public_sitemap))
+        .route("/users/:name/index", get(get_user_public_index))
         .route("/users/:name/:id", get(get_public_by_owner_and_id))
         .route(
             "/users/:name/:id/content",
             get(get_public_content_by_owner_and_id),
         )
+        .route("/users/:name/:id/stats", get(get_public_view_stats))
+        .route_layer(axum::middleware::from_fn_with_state(
+            ctx.clone(),
+            rate_limit::limit_requests,
+        ))
         .with_state(ctx)
 }
@@ -0,0 +1,81 @@
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::bootstrap::app_context::AppContext;
+use crate::presentation::http::auth::{self, Bearer};
+use crate::presentation::http::client_ip::ClientIp;
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let mut res = StatusCode::TOO_MANY_REQUESTS.into_response();
+    res.headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+    res
+}
+
+fn signed_in_user(ctx: &AppContext, bearer: Option<Bearer>) -> Option<Uuid> {
+    bearer
+        .and_then(|b| auth::validate_bearer(ctx, b).ok())
+        .and_then(|sub| Uuid::parse_str(&sub).ok())
+}
+
+/// Throttles per client address, or per user for signed-in callers (who get a larger budget).
+/// Rejected requests get `429` with `Retry-After` in whole seconds.
+pub async fn limit_requests(
+    State(ctx): State<AppContext>,
+    client: ClientIp,
+    bearer: Option<Bearer>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let limits = ctx.rate_limits();
+    let (limiter, key) = match signed_in_user(&ctx, bearer) {
+        Some(user_id) => (limits.authenticated.as_ref(), format!("user:{}", user_id)),
+        None => (limits.anonymous.as_ref(), format!("addr:{}", client.key())),
+    };
+    if let Some(limiter) = limiter
+        && let Err(retry_after) = limiter.check(&key, Instant::now())
+    {
+        return too_many_requests(retry_after);
+    }
+    next.run(req).await
+}
+
+/// Throttles anonymous requests that carry a `token` query parameter, per token and per client
+/// address. Requests without a token, or from signed-in users, pass untouched.
+pub async fn limit_share_requests(
+    State(ctx): State<AppContext>,
+    client: ClientIp,
+    bearer: Option<Bearer>,
+    query: Option<Query<ShareTokenParam>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let token = query.and_then(|Query(q)| q.token);
+    let Some(token) = token.filter(|t| !t.is_empty()) else {
+        return next.run(req).await;
+    };
+    if signed_in_user(&ctx, bearer).is_some() {
+        return next.run(req).await;
+    }
+    let addr = client.key();
+    let limits = ctx.rate_limits();
+    if let Err(retry_after) = limits.shares.check(&token, &addr, Instant::now()) {
+        tracing::debug!(client = %addr, "share_request_throttled");
+        return too_many_requests(retry_after);
+    }
+    next.run(req).await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShareTokenParam {
+    token: Option<String>,
+}
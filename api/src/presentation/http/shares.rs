@@ -12,15 +12,19 @@ use crate::application::access;
 use crate::application::dto::shares::{
     ActiveShareItemDto, ShareBrowseResponseDto, ShareBrowseTreeItemDto, ShareDocumentDto,
 };
-use crate::application::use_cases::shares::create_share::CreateShare;
+use crate::application::use_cases::shares::create_share::{
+    CreateShare, CreateShareError, CreateShareResult,
+};
 use crate::application::use_cases::shares::delete_share::DeleteShare;
 use crate::application::use_cases::shares::list_applicable::ApplicableShareDto;
 use crate::application::use_cases::shares::list_document_shares::{
     ListDocumentShares, ShareItemDto,
 };
+use crate::application::use_cases::shares::materialize_folder_share::MaterializeFolderShare;
 use crate::bootstrap::app_context::AppContext;
 use crate::presentation::http::auth;
 use crate::presentation::http::auth::Bearer;
+use crate::presentation::http::rate_limit;
 
 fn frontend_base(cfg: &crate::bootstrap::config::Config) -> String {
     cfg.frontend_url
@@ -28,6 +32,7 @@ fn frontend_base(cfg: &crate::bootstrap::config::Config) -> String {
         .unwrap_or_else(|| "http://localhost:3000".into())
 }
 
+/// `token` may be the share's short code, which every share endpoint accepts too.
 fn build_share_url(base: &str, document_type: &str, document_id: Uuid, token: &str) -> String {
     let base = base.trim_end_matches('/');
     if document_type == "folder" {
@@ -52,11 +57,16 @@ pub struct CreateShareRequest {
     pub document_id: Uuid,
     pub permission: Option<String>,
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Also issue a short code; the returned URL then uses it instead of the token.
+    #[serde(default)]
+    pub short_code: bool,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct CreateShareResponse {
     pub token: String,
+    /// Alias accepted wherever the token is.
+    pub short_code: Option<String>,
     pub url: String,
 }
 
@@ -65,39 +75,144 @@ pub struct CreateShareResponse {
     path = "/api/shares",
     tag = "Sharing",
     request_body = CreateShareRequest,
-    responses((status = 200, description = "Share link created", body = CreateShareResponse))
+    responses(
+        (status = 200, description = "Share link created", body = CreateShareResponse),
+        (status = 409, description = "Document already has the maximum number of active share links")
+    )
 )]
 pub async fn create_share(
     State(ctx): State<AppContext>,
     bearer: Bearer,
     Json(req): Json<CreateShareRequest>,
 ) -> Result<Json<CreateShareResponse>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.shares_repo();
     let uc = CreateShare {
         repo: repo.as_ref(),
+        max_per_document: ctx.cfg.max_shares_per_document,
     };
     let permission = req.permission.as_deref().unwrap_or("view");
     let res = uc
-        .execute(user_id, req.document_id, permission, req.expires_at)
+        .execute(
+            user_id,
+            req.document_id,
+            permission,
+            req.expires_at,
+            req.short_code,
+        )
         .await
-        .map_err(|e| {
+        .map_err(create_share_status)?;
+    let base = frontend_base(&ctx.cfg);
+    Ok(Json(created_share_response(&base, res)))
+}
+
+fn create_share_status(err: CreateShareError) -> StatusCode {
+    match err {
+        CreateShareError::LimitReached(_) => StatusCode::CONFLICT,
+        CreateShareError::EmptyBatch | CreateShareError::BatchTooLarge => StatusCode::BAD_REQUEST,
+        CreateShareError::Repository(e) => {
             tracing::debug!(error=?e, "create_share_failed");
             StatusCode::FORBIDDEN
-        })?;
-    let base = frontend_base(&ctx.cfg);
-    let url = build_share_url(&base, &res.document_type, res.document_id, &res.token);
-    Ok(Json(CreateShareResponse {
+        }
+    }
+}
+
+fn created_share_response(base: &str, res: CreateShareResult) -> CreateShareResponse {
+    let key = res.short_code.as_deref().unwrap_or(&res.token);
+    let url = build_share_url(base, &res.document_type, res.document_id, key);
+    CreateShareResponse {
         token: res.token,
+        short_code: res.short_code,
         url,
-    }))
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BulkCreateShareRequest {
+    /// Documents to share; duplicates are shared once.
+    pub document_ids: Vec<Uuid>,
+    pub permission: Option<String>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub short_code: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkShareItem {
+    pub document_id: Uuid,
+    pub token: String,
+    pub short_code: Option<String>,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BulkCreateShareResponse {
+    pub shares: Vec<BulkShareItem>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/shares/bulk",
+    tag = "Sharing",
+    request_body = BulkCreateShareRequest,
+    responses(
+        (status = 200, description = "One share link per document", body = BulkCreateShareResponse),
+        (status = 400, description = "No documents, or more than 100"),
+        (status = 403, description = "A document is not owned by the caller; nothing was created"),
+        (status = 409, description = "A document already has the maximum number of active share links")
+    )
+)]
+pub async fn create_shares_bulk(
+    State(ctx): State<AppContext>,
+    bearer: Bearer,
+    Json(req): Json<BulkCreateShareRequest>,
+) -> Result<Json<BulkCreateShareResponse>, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
+    let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let repo = ctx.shares_repo();
+    let uc = CreateShare {
+        repo: repo.as_ref(),
+        max_per_document: ctx.cfg.max_shares_per_document,
+    };
+    let permission = req.permission.as_deref().unwrap_or("view");
+    let created = uc
+        .execute_bulk(
+            user_id,
+            &req.document_ids,
+            permission,
+            req.expires_at,
+            req.short_code,
+        )
+        .await
+        .map_err(create_share_status)?;
+    let base = frontend_base(&ctx.cfg);
+    let shares = created
+        .into_iter()
+        .map(|res| {
+            let document_id = res.document_id;
+            let CreateShareResponse {
+                token,
+                short_code,
+                url,
+            } = created_share_response(&base, res);
+            BulkShareItem {
+                document_id,
+                token,
+                short_code,
+                url,
+            }
+        })
+        .collect();
+    Ok(Json(BulkCreateShareResponse { shares }))
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ShareItem {
     pub id: Uuid,
     pub token: String,
+    /// Alias accepted wherever the token is; `url` uses it when set.
+    pub short_code: Option<String>,
     pub permission: String,
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
     pub url: String,
@@ -107,42 +222,59 @@ pub struct ShareItem {
     pub parent_share_id: Option<Uuid>,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DocumentSharesResponse {
+    pub items: Vec<ShareItem>,
+    /// Unexpired links created on the document itself; these count towards `max_shares`.
+    pub active_count: usize,
+    /// Most active links the document may have; absent when unlimited.
+    pub max_shares: Option<usize>,
+}
+
 #[utoipa::path(
     get,
     path = "/api/shares/documents/{id}",
     tag = "Sharing",
     params(("id" = Uuid, Path, description = "Document ID")),
-    responses((status = 200, description = "OK", body = [ShareItem]))
+    responses((status = 200, description = "OK", body = DocumentSharesResponse))
 )]
 pub async fn list_document_shares(
     State(ctx): State<AppContext>,
     bearer: Bearer,
     axum::extract::Path(id): axum::extract::Path<Uuid>,
-) -> Result<Json<Vec<ShareItem>>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+) -> Result<Json<DocumentSharesResponse>, StatusCode> {
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     // authorization: require edit on the document
     let share_access = ctx.share_access_port();
     let access_repo = ctx.access_repo();
     let actor = access::Actor::User(user_id);
-    access::require_edit(access_repo.as_ref(), share_access.as_ref(), &actor, id)
-        .await
-        .map_err(|_| StatusCode::FORBIDDEN)?;
+    access::require_edit(
+        access_repo.as_ref(),
+        share_access.as_ref(),
+        ctx.access_policy(),
+        &actor,
+        id,
+    )
+    .await
+    .map_err(|_| StatusCode::FORBIDDEN)?;
     let repo = ctx.shares_repo();
     let uc = ListDocumentShares {
         repo: repo.as_ref(),
     };
-    let rows: Vec<ShareItemDto> = uc
+    let shares = uc
         .execute(user_id, id)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     let base = frontend_base(&ctx.cfg);
-    let items: Vec<ShareItem> = rows
+    let items: Vec<ShareItem> = shares
+        .items
         .into_iter()
         .map(|r| {
             let ShareItemDto {
                 id,
                 token,
+                short_code,
                 permission,
                 expires_at,
                 document_id,
@@ -150,10 +282,12 @@ pub async fn list_document_shares(
                 parent_share_id,
                 ..
             } = r;
-            let url = build_share_url(&base, &document_type, document_id, &token);
+            let key = short_code.as_deref().unwrap_or(&token);
+            let url = build_share_url(&base, &document_type, document_id, key);
             ShareItem {
                 id,
                 token,
+                short_code,
                 permission,
                 expires_at,
                 url,
@@ -162,7 +296,12 @@ pub async fn list_document_shares(
             }
         })
         .collect();
-    Ok(Json(items))
+    let max = ctx.cfg.max_shares_per_document;
+    Ok(Json(DocumentSharesResponse {
+        items,
+        active_count: shares.active_count,
+        max_shares: (max > 0).then_some(max),
+    }))
 }
 
 #[utoipa::path(
@@ -177,7 +316,7 @@ pub async fn delete_share(
     bearer: Bearer,
     axum::extract::Path(token): axum::extract::Path<String>,
 ) -> Result<StatusCode, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.shares_repo();
     let uc = DeleteShare {
@@ -227,7 +366,7 @@ pub async fn list_applicable_shares(
     bearer: Bearer,
     Query(q): Query<ApplicableQuery>,
 ) -> Result<Json<Vec<ApplicableShareItem>>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     // authorize: require view on the document
     let share_access = ctx.share_access_port();
@@ -236,6 +375,7 @@ pub async fn list_applicable_shares(
     access::require_view(
         access_repo.as_ref(),
         share_access.as_ref(),
+        ctx.access_policy(),
         &actor,
         q.doc_id,
     )
@@ -307,6 +447,8 @@ pub async fn validate_share_token(
 pub struct ActiveShareItem {
     pub id: Uuid,
     pub token: String,
+    /// Alias accepted wherever the token is; `url` uses it when set.
+    pub short_code: Option<String>,
     pub permission: String,
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -328,7 +470,7 @@ pub async fn list_active_shares(
     State(ctx): State<AppContext>,
     bearer: Bearer,
 ) -> Result<Json<Vec<ActiveShareItem>>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.shares_repo();
     let uc = crate::application::use_cases::shares::list_active::ListActiveShares {
@@ -342,10 +484,12 @@ pub async fn list_active_shares(
     let out: Vec<ActiveShareItem> = items
         .into_iter()
         .map(|r| {
-            let url = build_share_url(&base, &r.document_type, r.document_id, &r.token);
+            let key = r.short_code.as_deref().unwrap_or(&r.token);
+            let url = build_share_url(&base, &r.document_type, r.document_id, key);
             ActiveShareItem {
                 id: r.id,
                 token: r.token,
+                short_code: r.short_code,
                 permission: r.permission,
                 expires_at: r.expires_at,
                 created_at: r.created_at,
@@ -422,6 +566,7 @@ pub async fn browse_share(
 pub fn routes(ctx: AppContext) -> Router {
     Router::new()
         .route("/shares", post(create_share))
+        .route("/shares/bulk", post(create_shares_bulk))
         .route("/shares/browse", get(browse_share))
         .route("/shares/validate", get(validate_share_token))
         .route("/shares/documents/:id", get(list_document_shares))
@@ -432,6 +577,10 @@ pub fn routes(ctx: AppContext) -> Router {
         )
         .route("/shares/active", get(list_active_shares))
         .route("/shares/:token", delete(delete_share))
+        .route_layer(axum::middleware::from_fn_with_state(
+            ctx.clone(),
+            rate_limit::limit_share_requests,
+        ))
         .with_state(ctx)
 }
 
@@ -449,23 +598,23 @@ pub async fn materialize_folder_share(
     bearer: Bearer,
     axum::extract::Path(token): axum::extract::Path<String>,
 ) -> Result<Json<MaterializeResponse>, StatusCode> {
-    let sub = auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = uuid::Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
     let repo = ctx.shares_repo();
-    let created = repo
-        .materialize_folder_share(user_id, &token)
-        .await
-        .map_err(|e| {
-            tracing::debug!(error=?e, "materialize_failed");
-            if e.to_string() == "not_found" {
-                StatusCode::NOT_FOUND
-            } else if e.to_string() == "forbidden" {
-                StatusCode::FORBIDDEN
-            } else if e.to_string() == "bad_request" {
-                StatusCode::BAD_REQUEST
-            } else {
-                StatusCode::INTERNAL_SERVER_ERROR
-            }
-        })?;
+    let uc = MaterializeFolderShare {
+        repo: repo.as_ref(),
+    };
+    let created = uc.execute_for_token(user_id, &token).await.map_err(|e| {
+        tracing::debug!(error=?e, "materialize_failed");
+        if e.to_string() == "not_found" {
+            StatusCode::NOT_FOUND
+        } else if e.to_string() == "forbidden" {
+            StatusCode::FORBIDDEN
+        } else if e.to_string() == "bad_request" {
+            StatusCode::BAD_REQUEST
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    })?;
     Ok(Json(MaterializeResponse { created }))
 }
@@ -28,22 +28,30 @@ impl From<TagItemDto> for TagItem {
 }
 
 #[utoipa::path(get, path = "/api/tags", tag = "Tags",
-    params(("q" = Option<String>, Query, description = "Filter contains")),
+    params(
+        ("q" = Option<String>, Query, description = "Filter contains"),
+        ("include_hidden" = Option<bool>, Query, description = "Include hidden tags (`_`-prefixed or configured); default false")
+    ),
     responses((status = 200, body = [TagItem])))]
 pub async fn list_tags(
     State(ctx): State<AppContext>,
     bearer: crate::presentation::http::auth::Bearer,
     q: Option<Query<std::collections::HashMap<String, String>>>,
 ) -> Result<Json<Vec<TagItem>>, StatusCode> {
-    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx.cfg, bearer)?;
+    let sub = crate::presentation::http::auth::validate_bearer_public(&ctx, bearer)?;
     let user_id = Uuid::parse_str(&sub).map_err(|_| StatusCode::UNAUTHORIZED)?;
-    let filter = q.and_then(|Query(m)| m.get("q").cloned());
+    let params = q.map(|Query(m)| m).unwrap_or_default();
+    let filter = params.get("q").cloned();
+    let include_hidden = params
+        .get("include_hidden")
+        .is_some_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"));
     let repo = ctx.tag_repo();
     let uc = ListTags {
         repo: repo.as_ref(),
+        hidden_tags: &ctx.cfg.hidden_tags,
     };
     let items: Vec<TagItemDto> = uc
-        .execute(user_id, filter)
+        .execute(user_id, filter, include_hidden)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     let out: Vec<TagItem> = items.into_iter().map(Into::into).collect();
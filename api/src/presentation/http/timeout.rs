@@ -0,0 +1,212 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+
+use crate::bootstrap::config::Config;
+
+/// Deadlines for producing a response. Only the wait for the response head is bounded, so a
+/// body that is already streaming is never cut off.
+#[derive(Debug, Clone)]
+pub struct RequestTimeouts {
+    default: Option<Duration>,
+    long: Option<Duration>,
+}
+
+impl RequestTimeouts {
+    /// `0` disables the corresponding timeout.
+    pub fn new(default_secs: u64, long_secs: u64) -> Self {
+        let secs = |s: u64| (s > 0).then(|| Duration::from_secs(s));
+        Self {
+            default: secs(default_secs),
+            long: secs(long_secs),
+        }
+    }
+
+    pub fn from_config(cfg: &Config) -> Self {
+        Self::new(cfg.request_timeout_secs, cfg.long_request_timeout_secs)
+    }
+
+    /// Default deadline for a request, or `None` when it may take as long as it needs. Event
+    /// streams, websocket upgrades and range requests are exempt. Routes adjust it with
+    /// [`long_running`] and [`exempt`].
+    pub fn for_request(&self, headers: &HeaderMap) -> Option<Duration> {
+        let has = |name| headers.contains_key(name);
+        let wants_stream = headers
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("text/event-stream"));
+        if has(header::UPGRADE) || has(header::RANGE) || wants_stream {
+            return None;
+        }
+        self.default
+    }
+}
+
+/// Deadline of the request being handled; route layers adjust it before the handler runs.
+#[derive(Clone)]
+struct Deadline {
+    timeouts: Arc<RequestTimeouts>,
+    limit: Arc<watch::Sender<Option<Duration>>>,
+}
+
+/// Route layer (`Router::route_layer(from_fn(long_running))`) for operations known to run
+/// long: network round trips, plugin code, whole-document exports.
+pub async fn long_running(req: Request, next: Next) -> Response {
+    if let Some(deadline) = req.extensions().get::<Deadline>()
+        && deadline.limit.borrow().is_some()
+    {
+        deadline.limit.send_replace(deadline.timeouts.long);
+    }
+    next.run(req).await
+}
+
+/// Route layer for routes that may take as long as they need: responses that stay open while
+/// the client listens, and uploads whose body is read before the response is produced.
+pub async fn exempt(req: Request, next: Next) -> Response {
+    if let Some(deadline) = req.extensions().get::<Deadline>() {
+        deadline.limit.send_replace(None);
+    }
+    next.run(req).await
+}
+
+fn gateway_timeout(limit: Duration) -> Response {
+    let body = json!({
+        "error": "request_timeout",
+        "message": format!("request did not complete within {}s", limit.as_secs()),
+    });
+    (StatusCode::GATEWAY_TIMEOUT, Json(body)).into_response()
+}
+
+/// Runs `fut`, answering `504` if it has not produced a response within `limit`. The limit
+/// may change while `fut` runs; it always counts from the start.
+pub async fn within<F>(mut limit: watch::Receiver<Option<Duration>>, fut: F) -> Response
+where
+    F: Future<Output = Response>,
+{
+    let started = Instant::now();
+    tokio::pin!(fut);
+    loop {
+        let current = *limit.borrow_and_update();
+        let expired = async {
+            match current {
+                Some(limit) => tokio::time::sleep_until(started + limit).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            res = &mut fut => return res,
+            _ = expired => {
+                let limit = current.unwrap_or_default();
+                tracing::warn!(timeout_secs = limit.as_secs(), "request_timed_out");
+                return gateway_timeout(limit);
+            }
+            Ok(()) = limit.changed() => {}
+        }
+    }
+}
+
+pub async fn limit_request_time(
+    State(timeouts): State<Arc<RequestTimeouts>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let (limit, rx) = watch::channel(timeouts.for_request(req.headers()));
+    req.extensions_mut().insert(Deadline {
+        timeouts,
+        limit: Arc::new(limit),
+    });
+    within(rx, next.run(req)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Router, middleware, routing::get};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn slow_handlers_get_504_and_fast_ones_are_untouched() {
+        let (_limit, rx) = watch::channel(Some(Duration::from_millis(50)));
+
+        let slow = within(rx.clone(), async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            StatusCode::OK.into_response()
+        })
+        .await;
+        assert_eq!(slow.status(), StatusCode::GATEWAY_TIMEOUT);
+
+        let fast = within(rx, async { StatusCode::CREATED.into_response() }).await;
+        assert_eq!(fast.status(), StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn route_layers_extend_or_lift_the_default_deadline() {
+        let timeouts = Arc::new(RequestTimeouts {
+            default: Some(Duration::from_millis(100)),
+            long: Some(Duration::from_secs(5)),
+        });
+        let slow = || async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            StatusCode::OK
+        };
+        // Same layering as the API router: route layers inside the global deadline.
+        let long = Router::new()
+            .route("/long", get(slow))
+            .route_layer(middleware::from_fn(long_running));
+        let exempted = Router::new()
+            .route("/upload", get(slow))
+            .route_layer(middleware::from_fn(exempt));
+        let app = Router::new()
+            .route("/default", get(slow))
+            .merge(long)
+            .merge(exempted)
+            .layer(middleware::from_fn_with_state(timeouts, limit_request_time));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = reqwest::Client::new();
+        let status = |path: &str| {
+            let req = client.get(format!("http://{}{}", addr, path)).send();
+            // reqwest uses an older `http`, so compare raw codes.
+            async move { req.await.unwrap().status().as_u16() }
+        };
+        assert_eq!(
+            status("/default").await,
+            StatusCode::GATEWAY_TIMEOUT.as_u16()
+        );
+        assert_eq!(status("/long").await, StatusCode::OK.as_u16());
+        assert_eq!(status("/upload").await, StatusCode::OK.as_u16());
+    }
+
+    #[test]
+    fn streams_are_exempt_from_the_default_deadline() {
+        let timeouts = RequestTimeouts::new(30, 300);
+        assert_eq!(
+            timeouts.for_request(&HeaderMap::new()),
+            Some(Duration::from_secs(30))
+        );
+
+        let mut sse = HeaderMap::new();
+        sse.insert(header::ACCEPT, "text/event-stream".parse().unwrap());
+        assert_eq!(timeouts.for_request(&sse), None);
+        let mut range = HeaderMap::new();
+        range.insert(header::RANGE, "bytes=0-99".parse().unwrap());
+        assert_eq!(timeouts.for_request(&range), None);
+
+        let disabled = RequestTimeouts::new(0, 300);
+        assert_eq!(disabled.for_request(&HeaderMap::new()), None);
+    }
+}
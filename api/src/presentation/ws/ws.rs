@@ -1,15 +1,20 @@
+use std::borrow::Cow;
 use std::pin::Pin;
 use std::sync::Arc;
 
 use crate::application::access::{self, Capability};
+use crate::application::ports::awareness_port::PresenceIdentity;
 use crate::application::ports::realtime_port::RealtimeError;
-use crate::bootstrap::app_context::{AppContext, DynRealtimeSink, DynRealtimeStream};
+use crate::bootstrap::app_context::{
+    AppContext, DynRealtimeSink, DynRealtimeStream, RealtimeProtocol, RealtimeSession,
+};
 use crate::presentation::http::auth;
-use axum::extract::ws::{Message as AxumMessage, WebSocket, WebSocketUpgrade};
+use axum::extract::ws::{CloseFrame, Message as AxumMessage, WebSocket, WebSocketUpgrade};
 use axum::extract::{Path, Query, State};
 use axum::http::HeaderMap;
 use axum::http::StatusCode;
-use axum::response::IntoResponse;
+use axum::http::header::SEC_WEBSOCKET_PROTOCOL;
+use axum::response::{IntoResponse, Response};
 use futures_util::{Sink, Stream, StreamExt};
 use serde::Deserialize;
 use tokio::sync::Mutex;
@@ -21,6 +26,59 @@ pub struct AuthQuery {
     pub access_token: Option<String>,
 }
 
+/// Realtime protocol versions this server speaks, preferred first. Clients offer the versions
+/// they support in `Sec-WebSocket-Protocol`.
+pub const SUPPORTED_WS_PROTOCOLS: &[&str] = &["refmd.yjs.v2", "refmd.yjs.v1"];
+
+/// Close code sent right after the upgrade when none of the offered protocol versions is
+/// supported.
+pub const CLOSE_UNSUPPORTED_PROTOCOL: u16 = 4406;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProtocolNegotiation {
+    /// No version offered; older clients, which speak the original v1 framing.
+    Unversioned,
+    Selected(&'static str),
+    Unsupported(Vec<String>),
+}
+
+pub fn negotiate_protocol(headers: &HeaderMap) -> ProtocolNegotiation {
+    let offered: Vec<String> = headers
+        .get_all(SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if offered.is_empty() {
+        return ProtocolNegotiation::Unversioned;
+    }
+    SUPPORTED_WS_PROTOCOLS
+        .iter()
+        .find(|supported| offered.iter().any(|p| p == *supported))
+        .map(|supported| ProtocolNegotiation::Selected(supported))
+        .unwrap_or(ProtocolNegotiation::Unsupported(offered))
+}
+
+fn realtime_protocol(negotiated: &ProtocolNegotiation) -> RealtimeProtocol {
+    match negotiated {
+        ProtocolNegotiation::Selected("refmd.yjs.v2") => RealtimeProtocol::V2,
+        _ => RealtimeProtocol::V1,
+    }
+}
+
+fn unsupported_protocol_close() -> CloseFrame<'static> {
+    // Close reasons are capped at 123 bytes, so only name what the server supports.
+    CloseFrame {
+        code: CLOSE_UNSUPPORTED_PROTOCOL,
+        reason: Cow::Owned(format!(
+            "unsupported protocol version; server supports {}",
+            SUPPORTED_WS_PROTOCOLS.join(", ")
+        )),
+    }
+}
+
 // Uses AppContext as router state
 
 #[utoipa::path(
@@ -29,10 +87,11 @@ pub struct AuthQuery {
     params(
         ("id" = String, Path, description = "Document ID (UUID)"),
         ("token" = Option<String>, Query, description = "JWT or share token"),
-        ("Authorization" = Option<String>, Header, description = "Bearer token (JWT or share token)")
+        ("Authorization" = Option<String>, Header, description = "Bearer token (JWT or share token)"),
+        ("Sec-WebSocket-Protocol" = Option<String>, Header, description = "Offered protocol versions, e.g. refmd.yjs.v1")
     ),
     responses(
-        (status = 101, description = "Switching Protocols (WebSocket upgrade)"),
+        (status = 101, description = "Switching Protocols (WebSocket upgrade). Closed with code 4406 if no offered protocol version is supported"),
         (status = 401, description = "Unauthorized")
     ),
     tag = "Realtime"
@@ -43,7 +102,7 @@ pub async fn axum_ws_entry(
     Query(query): Query<AuthQuery>,
     headers: HeaderMap,
     State(state): State<AppContext>,
-) -> Result<impl IntoResponse, StatusCode> {
+) -> Result<Response, StatusCode> {
     let token = query
         .token
         .or(query.access_token)
@@ -61,10 +120,10 @@ pub async fn axum_ws_entry(
                 .and_then(|cookie_hdr| {
                     for part in cookie_hdr.split(';') {
                         let kv = part.trim();
-                        if let Some((k, v)) = kv.split_once('=') {
-                            if k.trim() == "access_token" {
-                                return Some(v.trim().to_string());
-                            }
+                        if let Some((k, v)) = kv.split_once('=')
+                            && k.trim() == "access_token"
+                        {
+                            return Some(v.trim().to_string());
                         }
                     }
                     None
@@ -77,14 +136,17 @@ pub async fn axum_ws_entry(
     // Resolve actor capability
     let actor = token
         .as_deref()
-        .and_then(|t| auth::resolve_actor_from_token_str(&state.cfg, t))
+        .and_then(|t| auth::resolve_actor_from_token_str(&state, t))
         .ok_or(StatusCode::UNAUTHORIZED)?;
 
     let share_access = state.share_access_port();
     let access_repo = state.access_repo();
-    let cap = access::resolve_document(
+    // Resolve the grant without the document lock: a locked document stays subscribable, and
+    // the connection starts admitting edits again as soon as the lock is lifted.
+    let cap = access::resolve_grant(
         access_repo.as_ref(),
         share_access.as_ref(),
+        state.access_policy(),
         &actor,
         doc_uuid,
     )
@@ -93,9 +155,50 @@ pub async fn axum_ws_entry(
         return Err(StatusCode::UNAUTHORIZED);
     }
     let can_edit = matches!(cap, Capability::Edit);
+    let locked = access_repo
+        .is_document_locked(doc_uuid)
+        .await
+        .unwrap_or(false);
+    let identity = match actor {
+        access::Actor::User(user_id) => PresenceIdentity::User(user_id),
+        access::Actor::ShareToken(_) | access::Actor::Public => PresenceIdentity::Anonymous,
+    };
+
+    let negotiated = negotiate_protocol(&headers);
+    let session = RealtimeSession {
+        can_edit,
+        locked,
+        identity,
+        protocol: realtime_protocol(&negotiated),
+    };
+    let ws = match negotiated {
+        ProtocolNegotiation::Unversioned => ws,
+        ProtocolNegotiation::Selected(selected) => ws.protocols([selected]),
+        ProtocolNegotiation::Unsupported(offered) => {
+            tracing::warn!(
+                %doc_id,
+                offered = %offered.join(", "),
+                supported = %SUPPORTED_WS_PROTOCOLS.join(", "),
+                "ws_protocol_version_mismatch"
+            );
+            // Browsers drop a handshake that selects none of the offered protocols without
+            // exposing why, so complete it and close with a code the client can read.
+            let echoed = offered[0].clone();
+            return Ok(ws
+                .protocols([echoed])
+                .on_upgrade(|mut socket| async move {
+                    let _ = socket
+                        .send(AxumMessage::Close(Some(unsupported_protocol_close())))
+                        .await;
+                })
+                .into_response());
+        }
+    };
 
     let ctx = state.clone();
-    Ok(ws.on_upgrade(move |socket| peer_axum(doc_id, socket, ctx, can_edit)))
+    Ok(ws
+        .on_upgrade(move |socket| peer_axum(doc_id, socket, ctx, session))
+        .into_response())
 }
 
 // WebSocket <-> Vec<u8> sink adapter
@@ -180,7 +283,7 @@ impl Stream for WsBinaryStream {
 }
 
 // WS peer using Axum WebSocket
-async fn peer_axum(doc_id: String, ws: WebSocket, ctx: AppContext, can_edit: bool) {
+async fn peer_axum(doc_id: String, ws: WebSocket, ctx: AppContext, session: RealtimeSession) {
     tracing::debug!(%doc_id, "WS peer:upgrade");
     let (sink_raw, stream_raw) = ws.split();
     let sink_box: Pin<Box<WsBinarySink>> = Box::pin(WsBinarySink { inner: sink_raw });
@@ -193,7 +296,7 @@ async fn peer_axum(doc_id: String, ws: WebSocket, ctx: AppContext, can_edit: boo
 
     tracing::debug!(%doc_id, "WS peer:subscribing");
     if let Err(e) = ctx
-        .subscribe_realtime(&doc_id, sink_dyn, stream_dyn, can_edit)
+        .subscribe_realtime(&doc_id, sink_dyn, stream_dyn, session)
         .await
     {
         tracing::warn!(%doc_id, error = %e, "WS subscription ended unexpectedly");
@@ -201,3 +304,45 @@ async fn peer_axum(doc_id: String, ws: WebSocket, ctx: AppContext, can_edit: boo
         tracing::info!(%doc_id, "WS connection closed");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn offering(protocols: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_str(protocols).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn supported_version_is_selected() {
+        assert_eq!(
+            negotiate_protocol(&offering("refmd.yjs.v9, refmd.yjs.v1")),
+            ProtocolNegotiation::Selected("refmd.yjs.v1")
+        );
+        assert_eq!(
+            negotiate_protocol(&offering("refmd.yjs.v1, refmd.yjs.v2")),
+            ProtocolNegotiation::Selected("refmd.yjs.v2")
+        );
+        assert_eq!(
+            negotiate_protocol(&HeaderMap::new()),
+            ProtocolNegotiation::Unversioned
+        );
+    }
+
+    #[test]
+    fn unsupported_version_is_rejected_with_close_code() {
+        assert_eq!(
+            negotiate_protocol(&offering("refmd.yjs.v9")),
+            ProtocolNegotiation::Unsupported(vec!["refmd.yjs.v9".into()])
+        );
+        let close = unsupported_protocol_close();
+        assert_eq!(close.code, CLOSE_UNSUPPORTED_PROTOCOL);
+        assert!(close.reason.len() <= 123);
+    }
+}
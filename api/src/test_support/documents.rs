@@ -0,0 +1,489 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::application::ports::access_repository::{AccessRepository, UserDocumentAccess};
+use crate::application::ports::document_repository::{
+    DocMeta, DocumentListFilter, DocumentPage, DocumentRepository, LinkCounts,
+};
+use crate::application::ports::document_user_access_repository::{
+    DocumentUserAccess, DocumentUserAccessRepository,
+};
+use crate::domain::documents::document::{BacklinkInfo, Document, OutgoingLink, SearchHit};
+
+/// A `documents` row with the columns the domain type leaves out.
+#[derive(Debug, Clone)]
+pub struct StoredDocument {
+    pub doc: Document,
+    pub owner: Uuid,
+    pub public: bool,
+    pub locked: bool,
+}
+
+/// Documents, direct user grants and links kept in memory, following the SQL
+/// repositories' ownership rules. Tags are not tracked, so a tag filter matches nothing,
+/// and grants list without the user's email and name.
+#[derive(Default)]
+pub struct MemoryDocuments {
+    pub docs: Mutex<Vec<StoredDocument>>,
+    /// (document, user) -> permission
+    pub grants: Mutex<HashMap<(Uuid, Uuid), String>>,
+    /// (source, target)
+    pub links: Mutex<Vec<(Uuid, Uuid)>>,
+}
+
+impl MemoryDocuments {
+    pub fn add(&self, owner: Uuid, title: &str, doc_type: &str, parent: Option<Uuid>) -> Document {
+        let now = chrono::Utc::now();
+        let doc = Document {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            parent_id: parent,
+            doc_type: doc_type.to_string(),
+            created_at: now,
+            updated_at: now,
+            path: None,
+            icon: None,
+            color: None,
+        };
+        self.docs.lock().unwrap().push(StoredDocument {
+            doc: doc.clone(),
+            owner,
+            public: false,
+            locked: false,
+        });
+        doc
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<StoredDocument> {
+        self.docs
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|d| d.doc.id == id)
+            .cloned()
+    }
+
+    /// Applies `f` to the stored row of `id`; panics if there is none.
+    pub fn update(&self, id: Uuid, f: impl FnOnce(&mut StoredDocument)) {
+        let mut docs = self.docs.lock().unwrap();
+        f(docs.iter_mut().find(|d| d.doc.id == id).expect("document"));
+    }
+
+    pub fn link(&self, source: Uuid, target: Uuid) {
+        self.links.lock().unwrap().push((source, target));
+    }
+
+    /// Titles of the folders above `id`, outermost first.
+    pub fn folder_path(&self, id: Uuid) -> Vec<String> {
+        let mut path = Vec::new();
+        let mut parent = self.get(id).and_then(|d| d.doc.parent_id);
+        while let Some(folder) = parent.and_then(|p| self.get(p)) {
+            path.insert(0, folder.doc.title);
+            parent = folder.doc.parent_id;
+        }
+        path
+    }
+
+    /// `root` and everything below it.
+    pub fn subtree(&self, root: Uuid) -> Vec<Document> {
+        let docs = self.docs.lock().unwrap();
+        let mut out = Vec::new();
+        let mut pending = vec![root];
+        while let Some(id) = pending.pop() {
+            let Some(stored) = docs.iter().find(|d| d.doc.id == id) else {
+                continue;
+            };
+            out.push(stored.doc.clone());
+            pending.extend(
+                docs.iter()
+                    .filter(|d| d.doc.parent_id == Some(id))
+                    .map(|d| d.doc.id),
+            );
+        }
+        out
+    }
+
+    /// Ids of the folders above `id`, nearest first.
+    pub fn ancestors(&self, id: Uuid) -> Vec<Uuid> {
+        let mut out = Vec::new();
+        let mut cur = self.get(id).and_then(|d| d.doc.parent_id);
+        while let Some(parent) = cur {
+            out.push(parent);
+            cur = self.get(parent).and_then(|d| d.doc.parent_id);
+        }
+        out
+    }
+
+    fn owner_of(&self, id: Uuid) -> Option<Uuid> {
+        self.get(id).map(|d| d.owner)
+    }
+
+    fn visible_to(&self, user_id: Uuid, stored: &StoredDocument) -> bool {
+        stored.owner == user_id
+            || self
+                .grants
+                .lock()
+                .unwrap()
+                .contains_key(&(stored.doc.id, user_id))
+    }
+
+    /// Links between `id` and documents of the same owner; `outgoing` picks the direction.
+    fn same_owner_links(&self, owner_id: Uuid, id: Uuid, outgoing: bool) -> Vec<Document> {
+        let links = self.links.lock().unwrap().clone();
+        links
+            .into_iter()
+            .filter_map(|(source, target)| match outgoing {
+                true if source == id => Some(target),
+                false if target == id => Some(source),
+                _ => None,
+            })
+            .filter_map(|other| self.get(other))
+            .filter(|other| other.owner == owner_id)
+            .map(|other| other.doc)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DocumentRepository for MemoryDocuments {
+    async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        filter: &DocumentListFilter,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<DocumentPage> {
+        let query = filter.query.as_ref().map(|q| q.to_lowercase());
+        let mut matching: Vec<StoredDocument> = self
+            .docs
+            .lock()
+            .unwrap()
+            .clone()
+            .into_iter()
+            .filter(|d| self.visible_to(user_id, d))
+            .filter(|d| {
+                query
+                    .as_ref()
+                    .is_none_or(|q| d.doc.title.to_lowercase().contains(q))
+            })
+            .filter(|_| filter.tag.is_none())
+            .filter(|d| {
+                filter
+                    .doc_type
+                    .as_ref()
+                    .is_none_or(|t| &d.doc.doc_type == t)
+            })
+            .filter(|d| {
+                filter
+                    .updated_since
+                    .is_none_or(|since| d.doc.updated_at >= since)
+            })
+            .collect();
+        matching.sort_by_key(|d| std::cmp::Reverse(d.doc.updated_at));
+        let items: Vec<StoredDocument> = matching
+            .iter()
+            .skip(offset.max(0) as usize)
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect();
+        let link_counts = filter.include_link_counts.then(|| {
+            items
+                .iter()
+                .map(|d| {
+                    let counts = LinkCounts {
+                        backlinks: self.same_owner_links(d.owner, d.doc.id, false).len() as i64,
+                        outgoing: self.same_owner_links(d.owner, d.doc.id, true).len() as i64,
+                    };
+                    (d.doc.id, counts)
+                })
+                .collect()
+        });
+        Ok(DocumentPage {
+            total: matching.len() as i64,
+            items: items.into_iter().map(|d| d.doc).collect(),
+            link_counts,
+        })
+    }
+
+    async fn list_recent_for_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        include_shared: bool,
+    ) -> anyhow::Result<Vec<Document>> {
+        let mut recent: Vec<Document> = self
+            .docs
+            .lock()
+            .unwrap()
+            .clone()
+            .into_iter()
+            .filter(|d| d.doc.doc_type != "folder")
+            .filter(|d| d.owner == user_id || (include_shared && self.visible_to(user_id, d)))
+            .map(|d| d.doc)
+            .collect();
+        recent.sort_by_key(|d| std::cmp::Reverse(d.updated_at));
+        recent.truncate(limit.max(0) as usize);
+        Ok(recent)
+    }
+
+    async fn list_ids_for_user(&self, user_id: Uuid) -> anyhow::Result<Vec<Uuid>> {
+        Ok(self
+            .docs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|d| d.owner == user_id)
+            .map(|d| d.doc.id)
+            .collect())
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> anyhow::Result<Option<Document>> {
+        Ok(self.get(id).map(|d| d.doc))
+    }
+
+    async fn search_for_user(
+        &self,
+        user_id: Uuid,
+        query: Option<String>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<SearchHit>> {
+        let query = query.map(|q| q.to_lowercase());
+        let mut hits: Vec<Document> = self
+            .docs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|d| d.owner == user_id)
+            .filter(|d| {
+                query
+                    .as_ref()
+                    .is_none_or(|q| d.doc.title.to_lowercase().contains(q))
+            })
+            .map(|d| d.doc.clone())
+            .collect();
+        hits.sort_by_key(|d| std::cmp::Reverse(d.updated_at));
+        hits.truncate(limit.max(0) as usize);
+        Ok(hits
+            .into_iter()
+            .map(|d| SearchHit {
+                id: d.id,
+                title: d.title,
+                doc_type: d.doc_type,
+                path: d.path,
+                updated_at: d.updated_at,
+            })
+            .collect())
+    }
+
+    async fn create_for_user(
+        &self,
+        user_id: Uuid,
+        title: &str,
+        parent_id: Option<Uuid>,
+        doc_type: &str,
+    ) -> anyhow::Result<Document> {
+        Ok(self.add(user_id, title, doc_type, parent_id))
+    }
+
+    async fn update_title_and_parent_for_user(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        title: Option<String>,
+        parent_id: Option<Option<Uuid>>,
+    ) -> anyhow::Result<Option<Document>> {
+        let mut docs = self.docs.lock().unwrap();
+        let Some(stored) = docs
+            .iter_mut()
+            .find(|d| d.doc.id == id && d.owner == user_id)
+        else {
+            return Ok(None);
+        };
+        if let Some(title) = title {
+            stored.doc.title = title;
+        }
+        if let Some(parent_id) = parent_id {
+            stored.doc.parent_id = parent_id;
+        }
+        stored.doc.updated_at = chrono::Utc::now();
+        Ok(Some(stored.doc.clone()))
+    }
+
+    async fn set_locked(&self, id: Uuid, locked: bool) -> anyhow::Result<()> {
+        if let Some(stored) = self
+            .docs
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|d| d.doc.id == id)
+        {
+            stored.locked = locked;
+        }
+        Ok(())
+    }
+
+    async fn set_appearance(
+        &self,
+        id: Uuid,
+        icon: Option<&str>,
+        color: Option<&str>,
+    ) -> anyhow::Result<Option<Document>> {
+        let mut docs = self.docs.lock().unwrap();
+        let Some(stored) = docs.iter_mut().find(|d| d.doc.id == id) else {
+            return Ok(None);
+        };
+        stored.doc.icon = icon.map(str::to_string);
+        stored.doc.color = color.map(str::to_string);
+        Ok(Some(stored.doc.clone()))
+    }
+
+    async fn delete_owned(&self, id: Uuid, user_id: Uuid) -> anyhow::Result<Option<String>> {
+        let mut docs = self.docs.lock().unwrap();
+        let Some(at) = docs
+            .iter()
+            .position(|d| d.doc.id == id && d.owner == user_id)
+        else {
+            return Ok(None);
+        };
+        let removed = docs.remove(at);
+        // Children are kept and moved to the root, as `ON DELETE SET NULL` does.
+        for child in docs.iter_mut().filter(|d| d.doc.parent_id == Some(id)) {
+            child.doc.parent_id = None;
+        }
+        self.grants.lock().unwrap().retain(|(doc, _), _| *doc != id);
+        self.links
+            .lock()
+            .unwrap()
+            .retain(|(source, target)| *source != id && *target != id);
+        Ok(Some(removed.doc.doc_type))
+    }
+
+    async fn backlinks_for(
+        &self,
+        owner_id: Uuid,
+        target_id: Uuid,
+    ) -> anyhow::Result<Vec<BacklinkInfo>> {
+        Ok(self
+            .same_owner_links(owner_id, target_id, false)
+            .into_iter()
+            .map(|d| BacklinkInfo {
+                document_id: d.id,
+                title: d.title,
+                document_type: d.doc_type,
+                file_path: d.path,
+                link_type: "reference".to_string(),
+                link_text: None,
+                link_count: 1,
+            })
+            .collect())
+    }
+
+    async fn outgoing_links_for(
+        &self,
+        owner_id: Uuid,
+        source_id: Uuid,
+    ) -> anyhow::Result<Vec<OutgoingLink>> {
+        Ok(self
+            .same_owner_links(owner_id, source_id, true)
+            .into_iter()
+            .map(|d| OutgoingLink {
+                document_id: d.id,
+                title: d.title,
+                document_type: d.doc_type,
+                file_path: d.path,
+                link_type: "reference".to_string(),
+                link_text: None,
+                position_start: None,
+                position_end: None,
+            })
+            .collect())
+    }
+
+    async fn get_meta_for_owner(
+        &self,
+        doc_id: Uuid,
+        owner_id: Uuid,
+    ) -> anyhow::Result<Option<DocMeta>> {
+        Ok(self
+            .get(doc_id)
+            .filter(|d| d.owner == owner_id)
+            .map(|d| DocMeta {
+                doc_type: d.doc.doc_type,
+                path: d.doc.path,
+                title: d.doc.title,
+            }))
+    }
+}
+
+#[async_trait]
+impl AccessRepository for MemoryDocuments {
+    async fn user_owns_document(&self, doc_id: Uuid, user_id: Uuid) -> anyhow::Result<bool> {
+        Ok(self.owner_of(doc_id) == Some(user_id))
+    }
+
+    async fn is_document_public(&self, doc_id: Uuid) -> anyhow::Result<bool> {
+        Ok(self.get(doc_id).is_some_and(|d| d.public))
+    }
+
+    async fn user_document_access(
+        &self,
+        doc_id: Uuid,
+        user_id: Uuid,
+    ) -> anyhow::Result<Option<UserDocumentAccess>> {
+        Ok(self.get(doc_id).map(|d| UserDocumentAccess {
+            owned: d.owner == user_id,
+            permission: self.grants.lock().unwrap().get(&(doc_id, user_id)).cloned(),
+            locked: d.locked,
+        }))
+    }
+
+    async fn is_document_locked(&self, doc_id: Uuid) -> anyhow::Result<bool> {
+        Ok(self.get(doc_id).is_some_and(|d| d.locked))
+    }
+}
+
+#[async_trait]
+impl DocumentUserAccessRepository for MemoryDocuments {
+    async fn grant(
+        &self,
+        doc_id: Uuid,
+        user_id: Uuid,
+        permission: &str,
+        _granted_by: Uuid,
+    ) -> anyhow::Result<()> {
+        self.grants
+            .lock()
+            .unwrap()
+            .insert((doc_id, user_id), permission.to_string());
+        Ok(())
+    }
+
+    async fn revoke(&self, doc_id: Uuid, user_id: Uuid) -> anyhow::Result<bool> {
+        Ok(self
+            .grants
+            .lock()
+            .unwrap()
+            .remove(&(doc_id, user_id))
+            .is_some())
+    }
+
+    async fn list_for_document(&self, doc_id: Uuid) -> anyhow::Result<Vec<DocumentUserAccess>> {
+        Ok(self
+            .grants
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((doc, _), _)| *doc == doc_id)
+            .map(|((_, user_id), permission)| DocumentUserAccess {
+                user_id: *user_id,
+                email: String::new(),
+                name: String::new(),
+                permission: permission.clone(),
+                created_at: chrono::Utc::now(),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,583 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::dto::git::{
+    DiffResult, GitBlameLine, GitChangeItem, GitCommitInfo, GitConnectionCheck, GitDiffStats,
+    GitHistoryCursor, GitRemoteSnapshot, GitSyncOutcome, GitSyncPreview, GitSyncRequestDto,
+    GitWorkingDiff, GitWorkspaceStatus,
+};
+use crate::application::ports::git_repository::{AutoSyncCandidate, GitRepository, UserGitCfg};
+use crate::application::ports::git_workspace::GitWorkspacePort;
+use crate::application::services::diff::build_diff_result;
+
+type ConfigRow = (
+    Uuid,
+    String,
+    String,
+    String,
+    bool,
+    DateTime<Utc>,
+    DateTime<Utc>,
+);
+type LastSyncLog = (
+    Option<DateTime<Utc>>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+#[derive(Debug, Clone)]
+pub struct StoredGitConfig {
+    pub id: Uuid,
+    pub cfg: UserGitCfg,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StoredGitConfig {
+    fn row(&self) -> ConfigRow {
+        (
+            self.id,
+            self.cfg.repository_url.clone(),
+            self.cfg.branch_name.clone(),
+            self.cfg.auth_type.clone().unwrap_or_default(),
+            self.cfg.auto_sync,
+            self.created_at,
+            self.updated_at,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncLogEntry {
+    pub user_id: Uuid,
+    pub operation: String,
+    pub status: String,
+    pub message: Option<String>,
+    pub commit_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Git configuration, sync logs and import bookkeeping kept in memory. Document edits and
+/// commits are plain timestamps per user, which is all `auto_sync_candidates` looks at;
+/// every configured user counts as having an initialized repository.
+#[derive(Default)]
+pub struct MemoryGit {
+    pub configs: Mutex<HashMap<Uuid, StoredGitConfig>>,
+    pub sync_logs: Mutex<Vec<SyncLogEntry>>,
+    pub pending_imports: Mutex<HashMap<Uuid, Vec<Uuid>>>,
+    /// (user, document updated_at)
+    pub changes: Mutex<Vec<(Uuid, DateTime<Utc>)>>,
+    /// (user, committed_at)
+    pub commits: Mutex<Vec<(Uuid, DateTime<Utc>)>>,
+}
+
+impl MemoryGit {
+    /// Configures `user_id` with a remote on `main` and no credentials.
+    pub fn configure(&self, user_id: Uuid, auto_sync: bool) {
+        let now = Utc::now();
+        self.configs.lock().unwrap().insert(
+            user_id,
+            StoredGitConfig {
+                id: Uuid::new_v4(),
+                cfg: UserGitCfg {
+                    repository_url: "https://example.com/notes.git".into(),
+                    branch_name: "main".into(),
+                    auth_type: None,
+                    auth_data: None,
+                    auto_sync,
+                    commit_message_template: None,
+                    append_change_summary: false,
+                },
+                created_at: now,
+                updated_at: now,
+            },
+        );
+    }
+
+    pub fn operations(&self, user_id: Uuid) -> Vec<String> {
+        self.sync_logs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|log| log.user_id == user_id)
+            .map(|log| log.operation.clone())
+            .collect()
+    }
+
+    pub fn commit_times(&self, user_id: Uuid) -> Vec<DateTime<Utc>> {
+        self.commits
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(user, _)| *user == user_id)
+            .map(|(_, at)| *at)
+            .collect()
+    }
+
+    pub fn pending(&self, user_id: Uuid) -> Vec<Uuid> {
+        self.pending_imports
+            .lock()
+            .unwrap()
+            .get(&user_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl GitRepository for MemoryGit {
+    async fn get_config(&self, user_id: Uuid) -> anyhow::Result<Option<ConfigRow>> {
+        Ok(self
+            .configs
+            .lock()
+            .unwrap()
+            .get(&user_id)
+            .map(StoredGitConfig::row))
+    }
+
+    async fn upsert_config(
+        &self,
+        user_id: Uuid,
+        repository_url: &str,
+        branch_name: Option<&str>,
+        auth_type: &str,
+        auth_data: &serde_json::Value,
+        auto_sync: Option<bool>,
+        commit_message_template: Option<&str>,
+        append_change_summary: Option<bool>,
+    ) -> anyhow::Result<ConfigRow> {
+        let now = Utc::now();
+        let mut configs = self.configs.lock().unwrap();
+        let (id, created_at) = configs
+            .get(&user_id)
+            .map_or((Uuid::new_v4(), now), |c| (c.id, c.created_at));
+        let stored = StoredGitConfig {
+            id,
+            cfg: UserGitCfg {
+                repository_url: repository_url.to_string(),
+                branch_name: branch_name.unwrap_or("main").to_string(),
+                auth_type: Some(auth_type.to_string()),
+                auth_data: Some(auth_data.clone()),
+                auto_sync: auto_sync.unwrap_or(true),
+                commit_message_template: commit_message_template.map(str::to_string),
+                append_change_summary: append_change_summary.unwrap_or(false),
+            },
+            created_at,
+            updated_at: now,
+        };
+        let row = stored.row();
+        configs.insert(user_id, stored);
+        Ok(row)
+    }
+
+    async fn delete_config(&self, user_id: Uuid) -> anyhow::Result<bool> {
+        Ok(self.configs.lock().unwrap().remove(&user_id).is_some())
+    }
+
+    async fn load_user_git_cfg(&self, user_id: Uuid) -> anyhow::Result<Option<UserGitCfg>> {
+        Ok(self
+            .configs
+            .lock()
+            .unwrap()
+            .get(&user_id)
+            .map(|c| c.cfg.clone()))
+    }
+
+    async fn get_last_sync_log(&self, user_id: Uuid) -> anyhow::Result<Option<LastSyncLog>> {
+        Ok(self
+            .sync_logs
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|log| log.user_id == user_id)
+            .map(|log| {
+                (
+                    Some(log.created_at),
+                    Some(log.status.clone()),
+                    log.message.clone(),
+                    log.commit_hash.clone(),
+                )
+            }))
+    }
+
+    async fn log_sync_operation(
+        &self,
+        user_id: Uuid,
+        operation: &str,
+        status: &str,
+        message: Option<&str>,
+        commit_hash: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.sync_logs.lock().unwrap().push(SyncLogEntry {
+            user_id,
+            operation: operation.to_string(),
+            status: status.to_string(),
+            message: message.map(str::to_string),
+            commit_hash: commit_hash.map(str::to_string),
+            created_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    async fn delete_sync_logs(&self, user_id: Uuid) -> anyhow::Result<()> {
+        self.sync_logs
+            .lock()
+            .unwrap()
+            .retain(|log| log.user_id != user_id);
+        Ok(())
+    }
+
+    async fn delete_repository_state(&self, user_id: Uuid) -> anyhow::Result<()> {
+        self.commits
+            .lock()
+            .unwrap()
+            .retain(|(user, _)| *user != user_id);
+        Ok(())
+    }
+
+    async fn auto_sync_candidates(&self) -> anyhow::Result<Vec<AutoSyncCandidate>> {
+        let users: Vec<Uuid> = self
+            .configs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, c)| c.cfg.auto_sync)
+            .map(|(user, _)| *user)
+            .collect();
+        let changes = self.changes.lock().unwrap();
+        Ok(users
+            .into_iter()
+            .filter_map(|user_id| {
+                let last_commit = self.commit_times(user_id).into_iter().max();
+                let pending: Vec<DateTime<Utc>> = changes
+                    .iter()
+                    .filter(|(user, at)| *user == user_id && last_commit.is_none_or(|c| *at > c))
+                    .map(|(_, at)| *at)
+                    .collect();
+                Some(AutoSyncCandidate {
+                    user_id,
+                    first_change_at: *pending.iter().min()?,
+                    last_change_at: *pending.iter().max()?,
+                })
+            })
+            .collect())
+    }
+
+    async fn pending_import_documents(&self, user_id: Uuid) -> anyhow::Result<Vec<Uuid>> {
+        Ok(self.pending(user_id))
+    }
+
+    async fn add_pending_import_document(
+        &self,
+        user_id: Uuid,
+        document_id: Uuid,
+    ) -> anyhow::Result<()> {
+        self.pending_imports
+            .lock()
+            .unwrap()
+            .entry(user_id)
+            .or_default()
+            .push(document_id);
+        Ok(())
+    }
+
+    async fn clear_pending_import(&self, user_id: Uuid) -> anyhow::Result<()> {
+        self.pending_imports.lock().unwrap().remove(&user_id);
+        Ok(())
+    }
+}
+
+/// A file changed in the working tree; `None` for the side where it does not exist.
+#[derive(Debug, Clone)]
+pub struct FileEdit {
+    pub path: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl FileEdit {
+    fn change(&self) -> GitChangeItem {
+        let status = match (&self.before, &self.after) {
+            (None, _) => "untracked",
+            (_, None) => "deleted",
+            _ => "modified",
+        };
+        GitChangeItem {
+            path: self.path.clone(),
+            status: status.to_string(),
+        }
+    }
+
+    fn diff(&self) -> DiffResult {
+        build_diff_result(&self.path, self.before.as_deref(), self.after.as_deref())
+    }
+}
+
+/// One user's working tree and history kept in memory. A sync commits the pending edits at
+/// `now` and records the commit in `git`, as the real workspace does in `git_commits`; it
+/// pushes whenever a remote is configured.
+#[derive(Default)]
+pub struct MemoryWorkspace {
+    pub git: Arc<MemoryGit>,
+    pub now: Mutex<DateTime<Utc>>,
+    /// Oldest first.
+    pub commits: Mutex<Vec<GitCommitInfo>>,
+    /// (commit hash, edits it committed)
+    pub committed: Mutex<Vec<(String, Vec<FileEdit>)>>,
+    pub edits: Mutex<Vec<FileEdit>>,
+    /// How many of the next syncs fail before committing anything.
+    pub failures: Mutex<usize>,
+    /// Head of the remote branch; `None` when the branch does not exist.
+    pub remote: Mutex<Option<GitRemoteSnapshot>>,
+    pub baseline: Mutex<Option<String>>,
+    pub fail_baseline: AtomicBool,
+}
+
+impl MemoryWorkspace {
+    pub fn at(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn edit(&self, path: &str, before: Option<&str>, after: Option<&str>) {
+        self.edits.lock().unwrap().push(FileEdit {
+            path: path.to_string(),
+            before: before.map(str::to_string),
+            after: after.map(str::to_string),
+        });
+    }
+
+    pub fn commit_hashes(&self) -> Vec<String> {
+        self.commits
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|c| c.hash.clone())
+            .collect()
+    }
+
+    fn commit(&self, user_id: Uuid, message: String, edits: Vec<FileEdit>) -> GitCommitInfo {
+        let now = *self.now.lock().unwrap();
+        let mut commits = self.commits.lock().unwrap();
+        let commit = GitCommitInfo {
+            hash: format!("c{}", commits.len() + 1),
+            message,
+            author_name: "refmd".into(),
+            author_email: "refmd@example.com".into(),
+            time: now,
+        };
+        commits.push(commit.clone());
+        self.committed
+            .lock()
+            .unwrap()
+            .push((commit.hash.clone(), edits));
+        self.git.commits.lock().unwrap().push((user_id, now));
+        commit
+    }
+}
+
+fn history_key(c: &GitCommitInfo) -> (DateTime<Utc>, &str) {
+    (c.time, c.hash.as_str())
+}
+
+#[async_trait]
+impl GitWorkspacePort for MemoryWorkspace {
+    async fn ensure_repository(&self, _: Uuid, _: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn remove_repository(&self, _: Uuid) -> anyhow::Result<()> {
+        self.commits.lock().unwrap().clear();
+        self.committed.lock().unwrap().clear();
+        self.edits.lock().unwrap().clear();
+        Ok(())
+    }
+
+    async fn status(&self, _: Uuid) -> anyhow::Result<GitWorkspaceStatus> {
+        Ok(GitWorkspaceStatus {
+            repository_initialized: true,
+            current_branch: Some("main".into()),
+            uncommitted_changes: self.edits.lock().unwrap().len() as u32,
+            untracked_files: 0,
+        })
+    }
+
+    async fn list_changes(&self, _: Uuid) -> anyhow::Result<Vec<GitChangeItem>> {
+        Ok(self
+            .edits
+            .lock()
+            .unwrap()
+            .iter()
+            .map(FileEdit::change)
+            .collect())
+    }
+
+    async fn working_diff(&self, _: Uuid) -> anyhow::Result<GitWorkingDiff> {
+        Ok(GitWorkingDiff {
+            base_commit_hash: self.commit_hashes().pop(),
+            diffs: self
+                .edits
+                .lock()
+                .unwrap()
+                .iter()
+                .map(FileEdit::diff)
+                .collect(),
+        })
+    }
+
+    async fn commit_diff(&self, _: Uuid, _: &str, to: &str) -> anyhow::Result<Vec<DiffResult>> {
+        Ok(self
+            .committed
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(hash, _)| hash == to)
+            .map(|(_, edits)| edits.iter().map(FileEdit::diff).collect())
+            .unwrap_or_default())
+    }
+
+    async fn history(
+        &self,
+        _: Uuid,
+        before: Option<&GitHistoryCursor>,
+        limit: i64,
+    ) -> anyhow::Result<Vec<GitCommitInfo>> {
+        let mut commits: Vec<GitCommitInfo> = self
+            .commits
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| before.is_none_or(|b| history_key(c) < (b.committed_at, b.hash.as_str())))
+            .cloned()
+            .collect();
+        commits.sort_by(|a, b| history_key(b).cmp(&history_key(a)));
+        commits.truncate(limit as usize);
+        Ok(commits)
+    }
+
+    /// Attributes every line to the last commit that wrote the file.
+    async fn blame(&self, _: Uuid, path: &str) -> anyhow::Result<Vec<GitBlameLine>> {
+        let committed = self.committed.lock().unwrap();
+        let Some((hash, content)) = committed.iter().rev().find_map(|(hash, edits)| {
+            let edit = edits.iter().find(|e| e.path == path)?;
+            Some((hash, edit.after.clone()))
+        }) else {
+            return Ok(Vec::new());
+        };
+        let commits = self.commits.lock().unwrap();
+        let commit = commits.iter().find(|c| &c.hash == hash).cloned();
+        Ok(content
+            .zip(commit)
+            .map(|(content, commit)| {
+                content
+                    .lines()
+                    .enumerate()
+                    .map(|(i, line)| GitBlameLine {
+                        line_number: i as u32 + 1,
+                        content: line.to_string(),
+                        commit: commit.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn sync(
+        &self,
+        user_id: Uuid,
+        req: &GitSyncRequestDto,
+        cfg: Option<&UserGitCfg>,
+    ) -> anyhow::Result<GitSyncOutcome> {
+        {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                anyhow::bail!("remote unreachable");
+            }
+        }
+        let edits = self.edits.lock().unwrap().clone();
+        let files: Vec<GitChangeItem> = edits.iter().map(FileEdit::change).collect();
+        if req.dry_run {
+            let diffs: Vec<DiffResult> = edits.iter().map(FileEdit::diff).collect();
+            let stats = GitDiffStats::from_changes(&files, &diffs);
+            return Ok(GitSyncOutcome {
+                files_changed: files.len() as u32,
+                commit_hash: None,
+                pushed: false,
+                message: "dry run".into(),
+                preview: Some(GitSyncPreview {
+                    files,
+                    diffs,
+                    stats,
+                }),
+            });
+        }
+        if edits.is_empty() {
+            return Ok(GitSyncOutcome {
+                files_changed: 0,
+                commit_hash: None,
+                pushed: false,
+                message: "nothing to commit".into(),
+                preview: None,
+            });
+        }
+        self.edits.lock().unwrap().clear();
+        let message = req.message.clone().unwrap_or_else(|| "sync".into());
+        let commit = self.commit(user_id, message, edits);
+        let pushed = cfg.is_some_and(|c| !c.repository_url.is_empty());
+        Ok(GitSyncOutcome {
+            files_changed: files.len() as u32,
+            commit_hash: Some(commit.hash),
+            pushed,
+            message: if pushed {
+                "sync completed"
+            } else {
+                "commit created"
+            }
+            .into(),
+            preview: None,
+        })
+    }
+
+    async fn test_connection(&self, cfg: &UserGitCfg) -> anyhow::Result<GitConnectionCheck> {
+        Ok(GitConnectionCheck {
+            branch: cfg.branch_name.clone(),
+            reachable: true,
+            authenticated: true,
+            branch_exists: self.remote.lock().unwrap().is_some(),
+            error: None,
+            message: None,
+        })
+    }
+
+    async fn fetch_remote_snapshot(
+        &self,
+        _: &UserGitCfg,
+    ) -> anyhow::Result<Option<GitRemoteSnapshot>> {
+        Ok(self.remote.lock().unwrap().clone())
+    }
+
+    async fn record_baseline(
+        &self,
+        user_id: Uuid,
+        snapshot: &GitRemoteSnapshot,
+    ) -> anyhow::Result<()> {
+        if self.fail_baseline.load(Ordering::SeqCst) {
+            anyhow::bail!("baseline write failed");
+        }
+        *self.baseline.lock().unwrap() = Some(snapshot.commit.hash.clone());
+        self.commits.lock().unwrap().push(snapshot.commit.clone());
+        self.git
+            .commits
+            .lock()
+            .unwrap()
+            .push((user_id, snapshot.commit.time));
+        Ok(())
+    }
+}
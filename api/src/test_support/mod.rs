@@ -0,0 +1,9 @@
+//! In-memory port doubles shared by unit tests across layers.
+
+pub mod documents;
+pub mod git;
+pub mod plugins;
+pub mod realtime;
+pub mod shares;
+pub mod storage;
+pub mod users;
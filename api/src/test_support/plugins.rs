@@ -0,0 +1,455 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::application::ports::plugin_installation_repository::{
+    PluginInstallation, PluginInstallationRepository,
+};
+use crate::application::ports::plugin_repository::{
+    PluginDataEffect, PluginKvEntry, PluginRecord, PluginRecordImport, PluginRepository,
+};
+
+/// `plugin_installations` rows kept in memory, with the same upsert and
+/// pinning rules as the SQL repository.
+#[derive(Default)]
+pub struct MemoryPluginInstallations {
+    pub rows: Mutex<Vec<PluginInstallation>>,
+}
+
+impl MemoryPluginInstallations {
+    pub fn new(rows: Vec<PluginInstallation>) -> Self {
+        Self {
+            rows: Mutex::new(rows),
+        }
+    }
+
+    pub fn get(&self, user_id: Uuid, plugin_id: &str) -> Option<PluginInstallation> {
+        self.rows
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|row| row.user_id == user_id && row.plugin_id == plugin_id)
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl PluginInstallationRepository for MemoryPluginInstallations {
+    async fn upsert(
+        &self,
+        user_id: Uuid,
+        plugin_id: &str,
+        version: &str,
+        scope: &str,
+        origin_url: Option<&str>,
+        status: &str,
+    ) -> anyhow::Result<()> {
+        let mut rows = self.rows.lock().unwrap();
+        let now = Utc::now();
+        match rows
+            .iter_mut()
+            .find(|row| row.user_id == user_id && row.plugin_id == plugin_id)
+        {
+            Some(row) => {
+                row.version = version.to_string();
+                row.scope = scope.to_string();
+                row.origin_url = origin_url.map(str::to_string);
+                row.status = status.to_string();
+                row.pinned = false;
+                row.updated_at = now;
+            }
+            None => rows.push(PluginInstallation {
+                user_id,
+                plugin_id: plugin_id.to_string(),
+                version: version.to_string(),
+                scope: scope.to_string(),
+                origin_url: origin_url.map(str::to_string),
+                status: status.to_string(),
+                pinned: false,
+                installed_at: now,
+                updated_at: now,
+            }),
+        }
+        Ok(())
+    }
+
+    async fn set_version(
+        &self,
+        user_id: Uuid,
+        plugin_id: &str,
+        version: &str,
+        pinned: bool,
+    ) -> anyhow::Result<bool> {
+        let mut rows = self.rows.lock().unwrap();
+        let Some(row) = rows
+            .iter_mut()
+            .find(|row| row.user_id == user_id && row.plugin_id == plugin_id)
+        else {
+            return Ok(false);
+        };
+        row.version = version.to_string();
+        row.pinned = pinned;
+        row.updated_at = Utc::now();
+        Ok(true)
+    }
+
+    async fn list_for_user(&self, user_id: Uuid) -> anyhow::Result<Vec<PluginInstallation>> {
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|row| row.user_id == user_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn list_all(&self) -> anyhow::Result<Vec<PluginInstallation>> {
+        Ok(self.rows.lock().unwrap().clone())
+    }
+
+    async fn remove(&self, user_id: Uuid, plugin_id: &str) -> anyhow::Result<bool> {
+        let mut rows = self.rows.lock().unwrap();
+        let before = rows.len();
+        rows.retain(|row| !(row.user_id == user_id && row.plugin_id == plugin_id));
+        Ok(rows.len() != before)
+    }
+
+    async fn remove_all_for_user(&self, user_id: Uuid) -> anyhow::Result<()> {
+        self.rows
+            .lock()
+            .unwrap()
+            .retain(|row| row.user_id != user_id);
+        Ok(())
+    }
+}
+
+type KvKey = (String, String, Option<Uuid>, String);
+
+fn new_record(kind: &str, data: &JsonValue) -> PluginRecordImport {
+    PluginRecordImport {
+        id: None,
+        kind: kind.to_string(),
+        data: data.clone(),
+        created_at: None,
+    }
+}
+
+#[derive(Default, Clone)]
+struct PluginTables {
+    records: Vec<PluginRecord>,
+    kv: BTreeMap<KvKey, JsonValue>,
+}
+
+impl PluginTables {
+    fn put_kv(
+        &mut self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Option<Uuid>,
+        key: &str,
+        value: &JsonValue,
+    ) {
+        self.kv.insert(
+            (
+                plugin.to_string(),
+                scope.to_string(),
+                scope_id,
+                key.to_string(),
+            ),
+            value.clone(),
+        );
+    }
+
+    fn insert_record(
+        &mut self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Uuid,
+        record: &PluginRecordImport,
+    ) -> PluginRecord {
+        let now = Utc::now();
+        let record = PluginRecord {
+            id: record.id.unwrap_or_else(Uuid::new_v4),
+            plugin: plugin.to_string(),
+            scope: scope.to_string(),
+            scope_id,
+            kind: record.kind.clone(),
+            data: record.data.clone(),
+            created_at: record.created_at.unwrap_or(now),
+            updated_at: now,
+        };
+        self.records.push(record.clone());
+        record
+    }
+
+    /// Shallow merge, like jsonb `||` on objects.
+    fn patch_record(
+        &mut self,
+        record_id: Uuid,
+        plugin: Option<&str>,
+        patch: &JsonValue,
+    ) -> Option<PluginRecord> {
+        let record = self
+            .records
+            .iter_mut()
+            .find(|r| r.id == record_id && plugin.is_none_or(|p| r.plugin == p))?;
+        match (&mut record.data, patch) {
+            (JsonValue::Object(data), JsonValue::Object(patch)) => {
+                data.extend(patch.clone());
+            }
+            (data, patch) => *data = patch.clone(),
+        }
+        record.updated_at = Utc::now();
+        Some(record.clone())
+    }
+
+    fn delete_record(&mut self, record_id: Uuid, plugin: Option<&str>) -> bool {
+        let before = self.records.len();
+        self.records
+            .retain(|r| !(r.id == record_id && plugin.is_none_or(|p| r.plugin == p)));
+        self.records.len() != before
+    }
+}
+
+/// `plugin_records` and `plugin_kv` kept in memory. Multi-step writes apply to
+/// a copy that replaces the tables only when every step succeeds.
+#[derive(Default)]
+pub struct MemoryPluginData {
+    tables: Mutex<PluginTables>,
+}
+
+impl MemoryPluginData {
+    fn transaction<T>(
+        &self,
+        apply: impl FnOnce(&mut PluginTables) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let mut tables = self.tables.lock().unwrap();
+        let mut draft = tables.clone();
+        let out = apply(&mut draft)?;
+        *tables = draft;
+        Ok(out)
+    }
+}
+
+#[async_trait]
+impl PluginRepository for MemoryPluginData {
+    async fn kv_get(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Option<Uuid>,
+        key: &str,
+    ) -> anyhow::Result<Option<JsonValue>> {
+        let key = (
+            plugin.to_string(),
+            scope.to_string(),
+            scope_id,
+            key.to_string(),
+        );
+        Ok(self.tables.lock().unwrap().kv.get(&key).cloned())
+    }
+
+    async fn kv_set(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Option<Uuid>,
+        key: &str,
+        value: &JsonValue,
+    ) -> anyhow::Result<()> {
+        self.tables
+            .lock()
+            .unwrap()
+            .put_kv(plugin, scope, scope_id, key, value);
+        Ok(())
+    }
+
+    async fn insert_record(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Uuid,
+        kind: &str,
+        data: &JsonValue,
+    ) -> anyhow::Result<PluginRecord> {
+        Ok(self.tables.lock().unwrap().insert_record(
+            plugin,
+            scope,
+            scope_id,
+            &new_record(kind, data),
+        ))
+    }
+
+    async fn update_record_data(
+        &self,
+        record_id: Uuid,
+        patch: &JsonValue,
+    ) -> anyhow::Result<Option<PluginRecord>> {
+        Ok(self
+            .tables
+            .lock()
+            .unwrap()
+            .patch_record(record_id, None, patch))
+    }
+
+    async fn delete_record(&self, record_id: Uuid) -> anyhow::Result<bool> {
+        Ok(self.tables.lock().unwrap().delete_record(record_id, None))
+    }
+
+    async fn get_record(&self, record_id: Uuid) -> anyhow::Result<Option<PluginRecord>> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables.records.iter().find(|r| r.id == record_id).cloned())
+    }
+
+    async fn list_records(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Uuid,
+        kind: &str,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<PluginRecord>> {
+        let mut records: Vec<_> = self
+            .list_all_records(plugin, scope, scope_id)
+            .await?
+            .into_iter()
+            .filter(|r| r.kind == kind)
+            .collect();
+        records.sort_by_key(|r| {
+            let pinned = r.data.get("pinned").and_then(JsonValue::as_bool) == Some(true);
+            (std::cmp::Reverse(pinned), std::cmp::Reverse(r.created_at))
+        });
+        Ok(records
+            .into_iter()
+            .skip(offset.max(0) as usize)
+            .take(limit.max(0) as usize)
+            .collect())
+    }
+
+    async fn list_all_records(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Uuid,
+    ) -> anyhow::Result<Vec<PluginRecord>> {
+        let mut records: Vec<_> = self
+            .tables
+            .lock()
+            .unwrap()
+            .records
+            .iter()
+            .filter(|r| r.plugin == plugin && r.scope == scope && r.scope_id == scope_id)
+            .cloned()
+            .collect();
+        records.sort_by(|a, b| (&a.kind, a.created_at).cmp(&(&b.kind, b.created_at)));
+        Ok(records)
+    }
+
+    async fn list_kv(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Option<Uuid>,
+    ) -> anyhow::Result<Vec<PluginKvEntry>> {
+        Ok(self
+            .tables
+            .lock()
+            .unwrap()
+            .kv
+            .iter()
+            .filter(|((p, s, id, _), _)| p == plugin && s == scope && *id == scope_id)
+            .map(|((_, _, _, key), value)| PluginKvEntry {
+                key: key.clone(),
+                value: value.clone(),
+            })
+            .collect())
+    }
+
+    async fn import_scope(
+        &self,
+        plugin: &str,
+        scope: &str,
+        scope_id: Uuid,
+        records: &[PluginRecordImport],
+        kv: &[PluginKvEntry],
+    ) -> anyhow::Result<usize> {
+        self.transaction(|tables| {
+            for record in records {
+                if let Some(id) = record.id
+                    && let Some(existing) = tables.records.iter_mut().find(|r| r.id == id)
+                {
+                    if existing.plugin != plugin
+                        || existing.scope != scope
+                        || existing.scope_id != scope_id
+                    {
+                        anyhow::bail!("plugin record {} belongs to another scope", id);
+                    }
+                    existing.kind = record.kind.clone();
+                    existing.data = record.data.clone();
+                    existing.updated_at = Utc::now();
+                    continue;
+                }
+                tables.insert_record(plugin, scope, scope_id, record);
+            }
+            for entry in kv {
+                tables.put_kv(plugin, scope, Some(scope_id), &entry.key, &entry.value);
+            }
+            Ok(records.len())
+        })
+    }
+
+    async fn apply_effects(
+        &self,
+        plugin: &str,
+        effects: &[PluginDataEffect],
+    ) -> anyhow::Result<()> {
+        self.transaction(|tables| {
+            for effect in effects {
+                match effect {
+                    PluginDataEffect::PutKv { doc_id, key, value } => {
+                        tables.put_kv(plugin, "doc", Some(*doc_id), key, value);
+                    }
+                    PluginDataEffect::CreateRecord { doc_id, kind, data } => {
+                        tables.insert_record(plugin, "doc", *doc_id, &new_record(kind, data));
+                    }
+                    PluginDataEffect::UpdateRecord { record_id, patch } => {
+                        if tables
+                            .patch_record(*record_id, Some(plugin), patch)
+                            .is_none()
+                        {
+                            anyhow::bail!("plugin record {} not found", record_id);
+                        }
+                    }
+                    PluginDataEffect::DeleteRecord { record_id } => {
+                        if !tables.delete_record(*record_id, Some(plugin)) {
+                            anyhow::bail!("plugin record {} not found", record_id);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    async fn delete_scoped_kv(&self, scope: &str, scope_ids: &[Uuid]) -> anyhow::Result<()> {
+        self.tables.lock().unwrap().kv.retain(|(_, s, id, _), _| {
+            !(s == scope && id.is_some_and(|id| scope_ids.contains(&id)))
+        });
+        Ok(())
+    }
+
+    async fn delete_scoped_records(&self, scope: &str, scope_ids: &[Uuid]) -> anyhow::Result<()> {
+        self.tables
+            .lock()
+            .unwrap()
+            .records
+            .retain(|r| !(r.scope == scope && scope_ids.contains(&r.scope_id)));
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::application::ports::document_retention_repository::{
+    DocumentRetention, DocumentRetentionRepository,
+};
+use crate::application::ports::linkgraph_repository::{
+    LinkGraphRepository, NewDocumentLink, TitleCandidate,
+};
+use crate::application::ports::realtime_hydration_port::{
+    DocSnapshot, DocStateReader, DocUpdate, DocumentRecord,
+};
+use crate::application::ports::realtime_persistence_port::DocPersistencePort;
+use crate::application::ports::realtime_port::RealtimeEngine;
+use crate::application::ports::realtime_types::{
+    DynRealtimeSink, DynRealtimeStream, RealtimeSession,
+};
+use crate::application::ports::tagging_repository::TaggingRepository;
+use crate::test_support::storage::MemoryStorage;
+
+/// Everything the realtime services read and write for a document: the update log, snapshot
+/// versions, titles, and the rendered markdown files in `storage`. Link and tag indexing is
+/// not supported, so records have no owner.
+#[derive(Default)]
+pub struct MemoryDocStore {
+    pub titles: Mutex<HashMap<Uuid, String>>,
+    pub updates: Mutex<HashMap<Uuid, Vec<DocUpdate>>>,
+    pub snapshots: Mutex<HashMap<Uuid, Vec<i64>>>,
+    pub touched: Mutex<Vec<Uuid>>,
+    pub hashes: Mutex<HashMap<Uuid, String>>,
+    /// Makes `record_content_hash` fail.
+    pub fail_hash: Mutex<bool>,
+    pub renamed: Mutex<HashMap<Uuid, String>>,
+    pub storage: Arc<MemoryStorage>,
+}
+
+impl MemoryDocStore {
+    pub fn versions(&self, doc_id: &Uuid) -> Vec<i64> {
+        self.snapshots
+            .lock()
+            .unwrap()
+            .get(doc_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl DocPersistencePort for MemoryDocStore {
+    async fn append_update_with_seq(
+        &self,
+        doc_id: &Uuid,
+        seq: i64,
+        update: &[u8],
+    ) -> anyhow::Result<()> {
+        self.updates
+            .lock()
+            .unwrap()
+            .entry(*doc_id)
+            .or_default()
+            .push(DocUpdate {
+                seq,
+                update: update.to_vec(),
+            });
+        Ok(())
+    }
+
+    async fn latest_update_seq(&self, doc_id: &Uuid) -> anyhow::Result<Option<i64>> {
+        Ok(self
+            .updates
+            .lock()
+            .unwrap()
+            .get(doc_id)
+            .and_then(|updates| updates.iter().map(|u| u.seq).max()))
+    }
+
+    async fn persist_snapshot(&self, doc_id: &Uuid, version: i64, _: &[u8]) -> anyhow::Result<()> {
+        self.snapshots
+            .lock()
+            .unwrap()
+            .entry(*doc_id)
+            .or_default()
+            .push(version);
+        Ok(())
+    }
+
+    async fn latest_snapshot_version(&self, doc_id: &Uuid) -> anyhow::Result<Option<i64>> {
+        Ok(self.versions(doc_id).into_iter().max())
+    }
+
+    async fn prune_snapshots(&self, doc_id: &Uuid, keep_latest: i64) -> anyhow::Result<()> {
+        let mut map = self.snapshots.lock().unwrap();
+        if let Some(versions) = map.get_mut(doc_id) {
+            let excess = versions.len().saturating_sub(keep_latest.max(0) as usize);
+            versions.drain(..excess);
+        }
+        Ok(())
+    }
+
+    async fn prune_updates_before(&self, _: &Uuid, _: i64) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn clear_updates(&self, _: &Uuid) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn touch_document(&self, doc_id: &Uuid) -> anyhow::Result<()> {
+        self.touched.lock().unwrap().push(*doc_id);
+        Ok(())
+    }
+
+    async fn record_content_hash(&self, doc_id: &Uuid, hash: &str) -> anyhow::Result<()> {
+        if *self.fail_hash.lock().unwrap() {
+            anyhow::bail!("database unavailable");
+        }
+        self.hashes
+            .lock()
+            .unwrap()
+            .insert(*doc_id, hash.to_string());
+        Ok(())
+    }
+
+    async fn clear_content_hash(&self, doc_id: &Uuid) -> anyhow::Result<()> {
+        self.hashes.lock().unwrap().remove(doc_id);
+        Ok(())
+    }
+
+    async fn update_title(&self, doc_id: &Uuid, title: &str) -> anyhow::Result<()> {
+        self.renamed
+            .lock()
+            .unwrap()
+            .insert(*doc_id, title.to_string());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DocStateReader for MemoryDocStore {
+    async fn latest_snapshot(&self, _: &Uuid) -> anyhow::Result<Option<DocSnapshot>> {
+        Ok(None)
+    }
+
+    async fn latest_snapshot_at(
+        &self,
+        _: &Uuid,
+    ) -> anyhow::Result<Option<chrono::DateTime<chrono::Utc>>> {
+        Ok(None)
+    }
+
+    async fn updates_since(&self, doc_id: &Uuid, seq: i64) -> anyhow::Result<Vec<DocUpdate>> {
+        let updates = self
+            .updates
+            .lock()
+            .unwrap()
+            .get(doc_id)
+            .map(|updates| updates.iter().filter(|u| u.seq > seq).cloned().collect())
+            .unwrap_or_default();
+        // Give concurrent callers a chance to interleave, as a database round trip would.
+        tokio::task::yield_now().await;
+        Ok(updates)
+    }
+
+    async fn document_record(&self, doc_id: &Uuid) -> anyhow::Result<Option<DocumentRecord>> {
+        Ok(self
+            .titles
+            .lock()
+            .unwrap()
+            .get(doc_id)
+            .map(|title| DocumentRecord {
+                doc_type: "document".to_string(),
+                path: None,
+                title: title.clone(),
+                owner_id: None,
+            }))
+    }
+}
+
+#[async_trait]
+impl LinkGraphRepository for MemoryDocStore {
+    async fn clear_links_for_source(&self, _: Uuid) -> anyhow::Result<()> {
+        unimplemented!()
+    }
+    async fn existing_docs_for_owner(&self, _: Uuid, _: &[Uuid]) -> anyhow::Result<Vec<Uuid>> {
+        unimplemented!()
+    }
+    async fn find_docs_by_owner_and_title_keys(
+        &self,
+        _: Uuid,
+        _: &[String],
+    ) -> anyhow::Result<Vec<TitleCandidate>> {
+        unimplemented!()
+    }
+    async fn upsert_links(&self, _: Uuid, _: &[NewDocumentLink]) -> anyhow::Result<()> {
+        unimplemented!()
+    }
+    async fn replace_block_ids(&self, _: Uuid, _: &[String]) -> anyhow::Result<()> {
+        unimplemented!()
+    }
+    async fn has_block(&self, _: Uuid, _: &str) -> anyhow::Result<bool> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl TaggingRepository for MemoryDocStore {
+    async fn document_tag_names(&self, _: Uuid) -> anyhow::Result<Vec<String>> {
+        unimplemented!()
+    }
+    async fn remove_document_tags(&self, _: Uuid, _: &[String]) -> anyhow::Result<()> {
+        unimplemented!()
+    }
+    async fn upsert_tags_return_ids(&self, _: &[String]) -> anyhow::Result<Vec<i64>> {
+        unimplemented!()
+    }
+    async fn owner_doc_exists(&self, _: Uuid, _: Uuid) -> anyhow::Result<bool> {
+        unimplemented!()
+    }
+    async fn associate_document_tags(&self, _: Uuid, _: &[i64]) -> anyhow::Result<()> {
+        unimplemented!()
+    }
+}
+
+#[async_trait]
+impl DocumentRetentionRepository for MemoryDocStore {
+    async fn get(&self, _: Uuid) -> anyhow::Result<Option<DocumentRetention>> {
+        Ok(None)
+    }
+    async fn get_many(&self, _: &[Uuid]) -> anyhow::Result<HashMap<Uuid, DocumentRetention>> {
+        Ok(HashMap::new())
+    }
+    async fn upsert(&self, _: Uuid, _: DocumentRetention) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Live document contents without connections: reads and writes go straight to `contents`,
+/// keyed by document id, and subscribing fails.
+#[derive(Default)]
+pub struct MemoryRealtime {
+    pub contents: Mutex<HashMap<String, String>>,
+    pub locked: Mutex<HashSet<String>>,
+}
+
+impl MemoryRealtime {
+    pub fn set(&self, doc_id: Uuid, content: &str) {
+        self.contents
+            .lock()
+            .unwrap()
+            .insert(doc_id.to_string(), content.to_string());
+    }
+
+    pub fn content(&self, doc_id: Uuid) -> Option<String> {
+        self.contents
+            .lock()
+            .unwrap()
+            .get(&doc_id.to_string())
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl RealtimeEngine for MemoryRealtime {
+    async fn subscribe(
+        &self,
+        _: &str,
+        _: DynRealtimeSink,
+        _: DynRealtimeStream,
+        _: RealtimeSession,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("in-memory realtime engine has no connections")
+    }
+
+    async fn set_locked(&self, doc_id: &str, locked: bool) -> anyhow::Result<()> {
+        let mut set = self.locked.lock().unwrap();
+        if locked {
+            set.insert(doc_id.to_string());
+        } else {
+            set.remove(doc_id);
+        }
+        Ok(())
+    }
+
+    async fn get_content(&self, doc_id: &str) -> anyhow::Result<Option<String>> {
+        Ok(self.contents.lock().unwrap().get(doc_id).cloned())
+    }
+
+    async fn force_persist(&self, _: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn replace_content(&self, doc_id: &str, content: &str) -> anyhow::Result<()> {
+        self.contents
+            .lock()
+            .unwrap()
+            .insert(doc_id.to_string(), content.to_string());
+        Ok(())
+    }
+}
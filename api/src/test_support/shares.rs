@@ -0,0 +1,380 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::application::ports::share_access_port::ShareAccessPort;
+use crate::application::ports::shares_repository::{
+    FolderShareGrant, NewShare, ShareLimitReached, ShareRow, SharesRepository,
+};
+use crate::test_support::documents::MemoryDocuments;
+
+type Resolution = (Uuid, String, Option<DateTime<Utc>>, Uuid, String);
+type SubtreeNode = (
+    Uuid,
+    String,
+    String,
+    Option<Uuid>,
+    DateTime<Utc>,
+    DateTime<Utc>,
+);
+
+/// `shares` rows kept in memory over a [`MemoryDocuments`] tree, with the SQL
+/// repository's ownership, limit and materialization rules.
+pub struct MemoryShares {
+    pub documents: Arc<MemoryDocuments>,
+    pub rows: Mutex<Vec<ShareRow>>,
+}
+
+impl MemoryShares {
+    pub fn new(documents: Arc<MemoryDocuments>) -> Self {
+        Self {
+            documents,
+            rows: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn find(&self, token: &str) -> Option<ShareRow> {
+        self.rows
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.token == token || r.short_code.as_deref() == Some(token))
+            .cloned()
+    }
+
+    fn owned_by(&self, row: &ShareRow, owner_id: Uuid) -> bool {
+        self.documents
+            .get(row.document_id)
+            .is_some_and(|d| d.owner == owner_id)
+    }
+
+    fn insert(
+        &self,
+        document_id: Uuid,
+        permission: &str,
+        expires_at: Option<DateTime<Utc>>,
+        short_code: Option<&str>,
+        parent_share_id: Option<Uuid>,
+    ) -> ShareRow {
+        let doc = self.documents.get(document_id).expect("document").doc;
+        let row = ShareRow {
+            id: Uuid::new_v4(),
+            token: Uuid::new_v4().to_string(),
+            short_code: short_code.map(str::to_string),
+            permission: permission.to_string(),
+            expires_at,
+            parent_share_id,
+            document_id,
+            document_type: doc.doc_type,
+            document_title: doc.title,
+            created_at: Utc::now(),
+        };
+        self.rows.lock().unwrap().push(row.clone());
+        row
+    }
+}
+
+fn active(row: &ShareRow, now: DateTime<Utc>) -> bool {
+    row.expires_at.is_none_or(|exp| exp > now)
+}
+
+#[async_trait]
+impl SharesRepository for MemoryShares {
+    async fn create_share(
+        &self,
+        owner_id: Uuid,
+        document_id: Uuid,
+        permission: &str,
+        expires_at: Option<DateTime<Utc>>,
+        short_code: Option<&str>,
+    ) -> anyhow::Result<(String, Uuid, String)> {
+        let Some(doc) = self
+            .documents
+            .get(document_id)
+            .filter(|d| d.owner == owner_id)
+        else {
+            anyhow::bail!("forbidden");
+        };
+        let row = self.insert(document_id, permission, expires_at, short_code, None);
+        Ok((row.token, row.id, doc.doc.doc_type))
+    }
+
+    async fn create_shares(
+        &self,
+        owner_id: Uuid,
+        shares: &[NewShare],
+        permission: &str,
+        expires_at: Option<DateTime<Utc>>,
+        max_per_document: usize,
+    ) -> anyhow::Result<Vec<(String, Uuid, String)>> {
+        let before = self.rows.lock().unwrap().clone();
+        let now = Utc::now();
+        let mut out = Vec::with_capacity(shares.len());
+        for share in shares {
+            if self
+                .documents
+                .get(share.document_id)
+                .is_none_or(|d| d.owner != owner_id)
+            {
+                *self.rows.lock().unwrap() = before;
+                anyhow::bail!("forbidden");
+            }
+            let count = self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|r| {
+                    r.document_id == share.document_id
+                        && r.parent_share_id.is_none()
+                        && active(r, now)
+                })
+                .count();
+            if max_per_document > 0 && count >= max_per_document {
+                *self.rows.lock().unwrap() = before;
+                return Err(ShareLimitReached(max_per_document).into());
+            }
+            let row = self.insert(
+                share.document_id,
+                permission,
+                expires_at,
+                share.short_code.as_deref(),
+                None,
+            );
+            if row.document_type == "folder" {
+                for doc in self
+                    .documents
+                    .subtree(share.document_id)
+                    .into_iter()
+                    .skip(1)
+                {
+                    self.insert(doc.id, permission, expires_at, None, Some(row.id));
+                }
+            }
+            out.push((row.token, row.id, row.document_type));
+        }
+        Ok(out)
+    }
+
+    async fn list_document_shares(
+        &self,
+        owner_id: Uuid,
+        document_id: Uuid,
+    ) -> anyhow::Result<Vec<ShareRow>> {
+        let rows = self.rows.lock().unwrap().clone();
+        Ok(rows
+            .into_iter()
+            .rev()
+            .filter(|r| r.document_id == document_id && self.owned_by(r, owner_id))
+            .collect())
+    }
+
+    async fn delete_share(&self, owner_id: Uuid, token: &str) -> anyhow::Result<bool> {
+        let Some(row) = self.find(token).filter(|r| self.owned_by(r, owner_id)) else {
+            return Ok(false);
+        };
+        self.rows
+            .lock()
+            .unwrap()
+            .retain(|r| r.id != row.id && r.parent_share_id != Some(row.id));
+        Ok(true)
+    }
+
+    async fn validate_share_token(
+        &self,
+        token: &str,
+    ) -> anyhow::Result<Option<(Uuid, String, Option<DateTime<Utc>>, String)>> {
+        Ok(self.find(token).map(|r| {
+            let title = self
+                .documents
+                .get(r.document_id)
+                .map(|d| d.doc.title)
+                .unwrap_or_default();
+            (r.document_id, r.permission, r.expires_at, title)
+        }))
+    }
+
+    async fn list_applicable_shares_for_doc(
+        &self,
+        owner_id: Uuid,
+        doc_id: Uuid,
+    ) -> anyhow::Result<Vec<(String, String, Option<DateTime<Utc>>)>> {
+        let rows = self.rows.lock().unwrap().clone();
+        Ok(rows
+            .into_iter()
+            .filter(|r| r.document_id == doc_id && self.owned_by(r, owner_id))
+            .map(|r| (r.token, r.permission, r.expires_at))
+            .collect())
+    }
+
+    async fn list_active_shares(&self, owner_id: Uuid) -> anyhow::Result<Vec<ShareRow>> {
+        let now = Utc::now();
+        let rows = self.rows.lock().unwrap().clone();
+        Ok(rows
+            .into_iter()
+            .rev()
+            .filter(|r| active(r, now) && self.owned_by(r, owner_id))
+            .collect())
+    }
+
+    async fn resolve_share_by_token(&self, token: &str) -> anyhow::Result<Option<Resolution>> {
+        ShareAccessPort::resolve_share_by_token(self, token).await
+    }
+
+    async fn list_subtree_nodes(&self, root_id: Uuid) -> anyhow::Result<Vec<SubtreeNode>> {
+        Ok(self
+            .documents
+            .subtree(root_id)
+            .into_iter()
+            .map(|d| {
+                (
+                    d.id,
+                    d.title,
+                    d.doc_type,
+                    d.parent_id,
+                    d.created_at,
+                    d.updated_at,
+                )
+            })
+            .collect())
+    }
+
+    async fn list_materialized_children(&self, parent_share_id: Uuid) -> anyhow::Result<Vec<Uuid>> {
+        let now = Utc::now();
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.parent_share_id == Some(parent_share_id) && active(r, now))
+            .map(|r| r.document_id)
+            .collect())
+    }
+
+    async fn get_folder_share_for_owner(
+        &self,
+        owner_id: Uuid,
+        token: &str,
+    ) -> anyhow::Result<FolderShareGrant> {
+        let Some(row) = self.find(token) else {
+            anyhow::bail!("not_found");
+        };
+        if !self.owned_by(&row, owner_id) {
+            anyhow::bail!("forbidden");
+        }
+        if row.document_type != "folder" {
+            anyhow::bail!("bad_request");
+        }
+        Ok(FolderShareGrant {
+            share_id: row.id,
+            folder_id: row.document_id,
+            permission: row.permission,
+            expires_at: row.expires_at,
+            created_by: owner_id,
+        })
+    }
+
+    async fn list_ancestor_folder_shares(
+        &self,
+        doc_id: Uuid,
+    ) -> anyhow::Result<Vec<FolderShareGrant>> {
+        let ancestors = self.documents.ancestors(doc_id);
+        let now = Utc::now();
+        let rows = self.rows.lock().unwrap().clone();
+        Ok(rows
+            .into_iter()
+            .filter(|r| {
+                ancestors.contains(&r.document_id)
+                    && r.document_type == "folder"
+                    && r.parent_share_id.is_none()
+                    && active(r, now)
+            })
+            .filter_map(|r| {
+                let owner = self.documents.get(r.document_id)?.owner;
+                Some(FolderShareGrant {
+                    share_id: r.id,
+                    folder_id: r.document_id,
+                    permission: r.permission,
+                    expires_at: r.expires_at,
+                    created_by: owner,
+                })
+            })
+            .collect())
+    }
+
+    async fn insert_materialized_shares(
+        &self,
+        grant: &FolderShareGrant,
+        doc_ids: &[Uuid],
+    ) -> anyhow::Result<i64> {
+        let mut created = 0;
+        for id in doc_ids {
+            let exists = self
+                .rows
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|r| r.parent_share_id == Some(grant.share_id) && r.document_id == *id);
+            if !exists {
+                self.insert(
+                    *id,
+                    &grant.permission,
+                    grant.expires_at,
+                    None,
+                    Some(grant.share_id),
+                );
+                created += 1;
+            }
+        }
+        Ok(created)
+    }
+
+    async fn delete_expired_shares(&self, now: DateTime<Utc>) -> anyhow::Result<u64> {
+        let mut rows = self.rows.lock().unwrap();
+        let expired: Vec<Uuid> = rows
+            .iter()
+            .filter(|r| r.parent_share_id.is_none() && r.expires_at.is_some_and(|exp| exp < now))
+            .map(|r| r.id)
+            .collect();
+        rows.retain(|r| {
+            !expired.contains(&r.id) && r.parent_share_id.is_none_or(|p| !expired.contains(&p))
+        });
+        Ok(expired.len() as u64)
+    }
+}
+
+#[async_trait]
+impl ShareAccessPort for MemoryShares {
+    async fn resolve_share_by_token(&self, token: &str) -> anyhow::Result<Option<Resolution>> {
+        Ok(self.find(token).map(|r| {
+            (
+                r.id,
+                r.permission,
+                r.expires_at,
+                r.document_id,
+                r.document_type,
+            )
+        }))
+    }
+
+    async fn get_materialized_permission(
+        &self,
+        parent_share_id: Uuid,
+        doc_id: Uuid,
+    ) -> anyhow::Result<Option<String>> {
+        let now = Utc::now();
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| {
+                r.parent_share_id == Some(parent_share_id)
+                    && r.document_id == doc_id
+                    && active(r, now)
+            })
+            .map(|r| r.permission.clone()))
+    }
+}
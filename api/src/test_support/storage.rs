@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::application::ports::storage_port::{StoragePort, StoredAttachment, StoredObjectMeta};
+
+/// Uploads kept in memory. A document's markdown lives at `<id>.md` and its attachments
+/// under `<id>/attachments/`; paths are already "absolute", so the relative and absolute
+/// forms are the same.
+#[derive(Default)]
+pub struct MemoryStorage {
+    pub files: Mutex<HashMap<PathBuf, Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    pub fn read(&self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+        self.files.lock().unwrap().get(path.as_ref()).cloned()
+    }
+}
+
+#[async_trait]
+impl StoragePort for MemoryStorage {
+    async fn move_folder_subtree(&self, _: Uuid) -> anyhow::Result<usize> {
+        Ok(0)
+    }
+
+    async fn delete_doc_physical(&self, doc_id: Uuid) -> anyhow::Result<()> {
+        let file = self.build_doc_file_path(doc_id).await?;
+        let dir = self.build_doc_dir(doc_id).await?;
+        self.files
+            .lock()
+            .unwrap()
+            .retain(|path, _| *path != file && !path.starts_with(&dir));
+        Ok(())
+    }
+
+    async fn delete_folder_physical(&self, _: Uuid) -> anyhow::Result<usize> {
+        Ok(0)
+    }
+
+    async fn build_doc_dir(&self, doc_id: Uuid) -> anyhow::Result<PathBuf> {
+        Ok(PathBuf::from(doc_id.to_string()))
+    }
+
+    async fn build_doc_file_path(&self, doc_id: Uuid) -> anyhow::Result<PathBuf> {
+        Ok(PathBuf::from(format!("{}.md", doc_id)))
+    }
+
+    fn relative_from_uploads(&self, abs: &Path) -> String {
+        abs.to_string_lossy().replace('\\', "/")
+    }
+
+    fn user_repo_dir(&self, user_id: Uuid) -> String {
+        user_id.to_string()
+    }
+
+    fn absolute_from_relative(&self, rel: &str) -> PathBuf {
+        PathBuf::from(rel)
+    }
+
+    async fn sync_doc_paths(&self, _: Uuid) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn resolve_upload_path(&self, doc_id: Uuid, rest_path: &str) -> anyhow::Result<PathBuf> {
+        Ok(self.build_doc_dir(doc_id).await?.join(rest_path))
+    }
+
+    async fn read_bytes(&self, abs_path: &Path) -> anyhow::Result<Vec<u8>> {
+        self.read(abs_path)
+            .ok_or_else(|| anyhow::anyhow!("not found: {}", abs_path.display()))
+    }
+
+    async fn stat(&self, abs_path: &Path) -> anyhow::Result<StoredObjectMeta> {
+        let bytes = self.read_bytes(abs_path).await?;
+        Ok(StoredObjectMeta {
+            size: bytes.len() as u64,
+            etag: None,
+            last_modified: None,
+        })
+    }
+
+    async fn read_range(&self, abs_path: &Path, start: u64, end: u64) -> anyhow::Result<Vec<u8>> {
+        let bytes = self.read_bytes(abs_path).await?;
+        let end = (end as usize + 1).min(bytes.len());
+        Ok(bytes
+            .get(start as usize..end)
+            .map(<[u8]>::to_vec)
+            .unwrap_or_default())
+    }
+
+    async fn write_bytes(&self, abs_path: &Path, data: &[u8]) -> anyhow::Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(abs_path.to_path_buf(), data.to_vec());
+        Ok(())
+    }
+
+    async fn store_doc_attachment(
+        &self,
+        doc_id: Uuid,
+        original_filename: Option<&str>,
+        bytes: &[u8],
+    ) -> anyhow::Result<StoredAttachment> {
+        let filename = original_filename.unwrap_or("file.bin").to_string();
+        let path = self
+            .build_doc_dir(doc_id)
+            .await?
+            .join("attachments")
+            .join(&filename);
+        self.write_bytes(&path, bytes).await?;
+        let mut content_hash = String::with_capacity(64);
+        for byte in Sha256::digest(bytes) {
+            let _ = write!(&mut content_hash, "{:02x}", byte);
+        }
+        Ok(StoredAttachment {
+            filename,
+            relative_path: self.relative_from_uploads(&path),
+            size: bytes.len() as i64,
+            content_hash,
+        })
+    }
+
+    async fn self_test(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
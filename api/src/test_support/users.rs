@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::application::ports::user_repository::{UserRepository, UserRow};
+
+/// `users` rows and home documents kept in memory.
+#[derive(Default)]
+pub struct MemoryUsers {
+    pub rows: Mutex<Vec<UserRow>>,
+    pub homes: Mutex<HashMap<Uuid, Uuid>>,
+    /// How many times `find_by_id` was called.
+    pub lookups: AtomicUsize,
+}
+
+impl MemoryUsers {
+    pub fn add(&self, name: &str, email: &str) -> UserRow {
+        let row = UserRow {
+            id: Uuid::new_v4(),
+            email: email.to_string(),
+            name: name.to_string(),
+            password_hash: None,
+        };
+        self.rows.lock().unwrap().push(row.clone());
+        row
+    }
+}
+
+#[async_trait]
+impl UserRepository for MemoryUsers {
+    async fn create_user(
+        &self,
+        email: &str,
+        name: &str,
+        password_hash: &str,
+    ) -> anyhow::Result<UserRow> {
+        anyhow::ensure!(
+            self.find_by_email(email).await?.is_none(),
+            "email already registered"
+        );
+        let row = UserRow {
+            id: Uuid::new_v4(),
+            email: email.to_string(),
+            name: name.to_string(),
+            password_hash: Some(password_hash.to_string()),
+        };
+        self.rows.lock().unwrap().push(row.clone());
+        Ok(row)
+    }
+
+    async fn find_by_email(&self, email: &str) -> anyhow::Result<Option<UserRow>> {
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|u| u.email == email)
+            .cloned())
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> anyhow::Result<Option<UserRow>> {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|u| u.id == id)
+            .cloned())
+    }
+
+    async fn find_by_names_or_emails(&self, keys: &[String]) -> anyhow::Result<Vec<UserRow>> {
+        let keys: Vec<String> = keys.iter().map(|k| k.to_lowercase()).collect();
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|u| {
+                keys.contains(&u.email.to_lowercase()) || keys.contains(&u.name.to_lowercase())
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_user(&self, id: Uuid) -> anyhow::Result<bool> {
+        let mut rows = self.rows.lock().unwrap();
+        let before = rows.len();
+        rows.retain(|u| u.id != id);
+        self.homes.lock().unwrap().remove(&id);
+        Ok(rows.len() < before)
+    }
+
+    async fn home_document(&self, user_id: Uuid) -> anyhow::Result<Option<Uuid>> {
+        Ok(self.homes.lock().unwrap().get(&user_id).copied())
+    }
+
+    async fn set_home_document(&self, user_id: Uuid, doc_id: Option<Uuid>) -> anyhow::Result<()> {
+        let mut homes = self.homes.lock().unwrap();
+        match doc_id {
+            Some(doc_id) => homes.insert(user_id, doc_id),
+            None => homes.remove(&user_id),
+        };
+        Ok(())
+    }
+}
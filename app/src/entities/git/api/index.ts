@@ -18,12 +18,12 @@ export async function fetchStatus(): Promise<GitStatus> {
   return GitService.getStatus()
 }
 
-export async function fetchChanges(): Promise<GitChangesResponse> {
-  return GitService.getChanges()
+export async function fetchChanges(cursor?: string | null): Promise<GitChangesResponse> {
+  return GitService.getChanges({ cursor })
 }
 
-export async function fetchHistory(): Promise<GitHistoryResponse> {
-  return GitService.getHistory()
+export async function fetchHistory(cursor?: string | null): Promise<GitHistoryResponse> {
+  return GitService.getHistory({ cursor })
 }
 
 export async function fetchCommitDiff(from: string, to: string): Promise<GitDiffResult[]> {
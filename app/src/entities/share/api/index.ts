@@ -37,10 +37,14 @@ export async function listDocumentShares(id: string) {
   return SharingService.listDocumentShares({ id })
 }
 
-export async function createShare(input: { document_id: string; permission: string; expires_at?: string | null; scope?: 'document' | 'folder'; parent_share_id?: string | null }) {
+export async function createShare(input: { document_id: string; permission: string; expires_at?: string | null; short_code?: boolean; scope?: 'document' | 'folder'; parent_share_id?: string | null }) {
   return SharingService.createShare({ requestBody: input as any })
 }
 
+export async function createSharesBulk(input: { document_ids: string[]; permission: string; expires_at?: string | null; short_code?: boolean }) {
+  return SharingService.createSharesBulk({ requestBody: input })
+}
+
 export async function deleteShare(token: string) {
   return SharingService.deleteShare({ token })
 }
@@ -1,4 +1,4 @@
-import { useQuery, useQueryClient } from '@tanstack/react-query'
+import { useInfiniteQuery, useQueryClient } from '@tanstack/react-query'
 import { GitCommit as GitCommitIcon, RefreshCw, User, Clock, AlignLeft, Columns2 } from 'lucide-react'
 import React from 'react'
 
@@ -30,20 +30,28 @@ export default function GitHistoryDialog({ open, onOpenChange }: Props) {
   React.useEffect(() => {
     if (open) {
       try { qc.removeQueries({ queryKey: ['git-history'] }) } catch {}
-      qc.prefetchQuery({ queryKey: ['git-history'], queryFn: () => GitSvc.getHistory() })
     }
   }, [open, qc])
 
-  const { data, isLoading, isFetching, error } = useQuery({
+  const { data, isLoading, isFetching, error, hasNextPage, isFetchingNextPage, fetchNextPage } = useInfiniteQuery({
     queryKey: ['git-history'],
-    queryFn: () => GitSvc.getHistory(),
+    queryFn: ({ pageParam }) => GitSvc.getHistory({ cursor: pageParam }),
+    initialPageParam: null as string | null,
+    getNextPageParam: (last) => last.next_cursor ?? null,
     enabled: open,
     refetchOnMount: 'always',
     staleTime: 0,
     retry: false,
   })
 
-  const commits: GitCommitItem[] = data?.commits ?? []
+  const commits: GitCommitItem[] = React.useMemo(() => data?.pages.flatMap((p) => p.commits) ?? [], [data])
+
+  const onCommitsScroll = React.useCallback((e: React.UIEvent<HTMLDivElement>) => {
+    const el = e.currentTarget
+    if (hasNextPage && !isFetchingNextPage && el.scrollHeight - el.scrollTop - el.clientHeight < 200) {
+      fetchNextPage()
+    }
+  }, [hasNextPage, isFetchingNextPage, fetchNextPage])
 
   const fetchCommitDiffs = React.useCallback(async (commit: GitCommitItem) => {
     try {
@@ -95,7 +103,7 @@ export default function GitHistoryDialog({ open, onOpenChange }: Props) {
                       <RefreshCw className={cn('h-4 w-4', isLoading && 'animate-spin')} />
                     </Button>
                   </div>
-                  <div className="flex-1 overflow-y-auto">
+                  <div className="flex-1 overflow-y-auto" onScroll={onCommitsScroll}>
                     <div className="p-4 space-y-3">
                       {(isLoading || (open && isFetching && !data && !error)) && (
                         <div className="flex justify-center items-center py-8"><RefreshCw className="h-6 w-6 animate-spin text-muted-foreground" /></div>
@@ -122,6 +130,9 @@ export default function GitHistoryDialog({ open, onOpenChange }: Props) {
                           </div>
                         </div>
                       ))}
+                      {isFetchingNextPage && (
+                        <div className="flex justify-center py-2"><RefreshCw className="h-4 w-4 animate-spin text-muted-foreground" /></div>
+                      )}
                     </div>
                   </div>
                 </div>
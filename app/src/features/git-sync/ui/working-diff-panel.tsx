@@ -31,6 +31,7 @@ export function WorkingDiffPanel({ documentPath, className }: Props) {
   const [loading, setLoading] = React.useState(true)
   const [error, setError] = React.useState<string | null>(null)
   const [diffs, setDiffs] = React.useState<GitDiffResult[]>([])
+  const [baseCommit, setBaseCommit] = React.useState<string | null>(null)
   const [viewMode, setViewMode] = React.useState<ViewMode>('unified')
   const [expanded, setExpanded] = React.useState<Set<string>>(new Set())
 
@@ -39,14 +40,16 @@ export function WorkingDiffPanel({ documentPath, className }: Props) {
       setLoading(true)
       setError(null)
       const r = await GitSvc.getWorkingDiff()
-      setDiffs(r)
+      setDiffs(r.diffs)
+      setBaseCommit(r.commit_hash ?? null)
       if (documentPath) {
-        const match = r.filter((d) => d.file_path === documentPath).map((d) => d.file_path)
+        const match = r.diffs.filter((d) => d.file_path === documentPath).map((d) => d.file_path)
         setExpanded(new Set(match))
       }
     } catch (e: any) {
       setError(e?.message || 'Failed to load diffs')
       setDiffs([])
+      setBaseCommit(null)
     } finally {
       setLoading(false)
     }
@@ -80,6 +83,11 @@ export function WorkingDiffPanel({ documentPath, className }: Props) {
           <p className="text-sm text-muted-foreground mt-1">
             {documentPath ?? `${relevant.length} file${relevant.length !== 1 ? 's' : ''} with changes`}
           </p>
+          {baseCommit && (
+            <p className="text-xs text-muted-foreground mt-1">
+              Changes since <span className="font-mono">{baseCommit.slice(0, 7)}</span>
+            </p>
+          )}
           {hasChanges && (
             <div className="flex items-center gap-3 text-sm mt-2">
               <span className="text-green-600 dark:text-green-400">+{totalAdd}</span>
@@ -1,5 +1,5 @@
 import { useQueryClient } from '@tanstack/react-query'
-import { Copy, Users, Clock, Eye, Edit, Settings, Trash2, ExternalLink, Globe, Lock } from 'lucide-react'
+import { Copy, Users, Clock, Eye, Edit, MessageSquare, Settings, Trash2, ExternalLink, Globe, Lock } from 'lucide-react'
 import React, { useState, useEffect, useCallback } from 'react'
 import { toast } from 'sonner'
 
@@ -20,6 +20,7 @@ import { createShare, deleteShare, listDocumentShares, shareKeys } from '@/entit
 type ShareLink = {
   id: string
   token: string
+  short_code?: string | null
   permission: string
   expires_at?: string
   url: string
@@ -35,8 +36,8 @@ type Props = {
   targetType?: 'document' | 'folder'
 }
 
-const PERMISSION_ICONS = { view: Eye, edit: Edit, admin: Settings } as const
-const PERMISSION_LABELS = { view: 'View only', edit: 'Can edit', admin: 'Admin' } as const
+const PERMISSION_ICONS = { view: Eye, comment: MessageSquare, edit: Edit, admin: Settings } as const
+const PERMISSION_LABELS = { view: 'View only', comment: 'Can comment', edit: 'Can edit', admin: 'Admin' } as const
 
 export default function ShareDialog({ open, onOpenChange, targetId, targetType = 'document' }: Props) {
   if (!targetId) {
@@ -47,6 +48,7 @@ export default function ShareDialog({ open, onOpenChange, targetId, targetType =
 
   const [permissionLevel, setPermissionLevel] = useState<string>('edit')
   const [shareLinks, setShareLinks] = useState<ShareLink[]>([])
+  const [shareLimit, setShareLimit] = useState<{ active: number; max: number | null }>({ active: 0, max: null })
   const [loading, setLoading] = useState(false)
   const [linkExpiry, setLinkExpiry] = useState<string>('7d')
   const [publishState, setPublishState] = useState({ isPublished: false, url: '', loading: false })
@@ -56,8 +58,9 @@ export default function ShareDialog({ open, onOpenChange, targetId, targetType =
   const loadShareLinks = useCallback(async () => {
     if (!targetId) return
     try {
-      const links = await listDocumentShares(targetId)
-      setShareLinks(links as any)
+      const shares = await listDocumentShares(targetId)
+      setShareLinks(shares.items as any)
+      setShareLimit({ active: shares.active_count, max: shares.max_shares ?? null })
     } catch {}
   }, [targetId])
 
@@ -86,11 +89,11 @@ export default function ShareDialog({ open, onOpenChange, targetId, targetType =
   const createShareLink = async () => {
     setLoading(true)
     try {
-      const req: any = { document_id: targetId, permission: permissionLevel }
+      const req: any = { document_id: targetId, permission: permissionLevel, short_code: true }
       if (linkExpiry !== 'never') { const h = { '1h':1,'24h':24,'7d':168,'30d':720 }[linkExpiry] || 168; const d=new Date(); d.setHours(d.getHours()+h); req.expires_at=d.toISOString() }
       const result = await createShare(req)
       if (result?.token) { await loadShareLinks(); toast.success('Share link created') }
-    } catch { toast.error('Failed to create share link') } finally { setLoading(false) }
+    } catch (e: any) { toast.error(e?.status === 409 ? 'This document already has the maximum number of active share links' : 'Failed to create share link') } finally { setLoading(false) }
   }
   const deleteShareLink = async (link: ShareLink) => {
     try {
@@ -172,6 +175,7 @@ export default function ShareDialog({ open, onOpenChange, targetId, targetType =
                 <SelectTrigger className="w-[160px]"><SelectValue placeholder="Permission" /></SelectTrigger>
                 <SelectContent>
                   <SelectItem value="view">View only</SelectItem>
+                  <SelectItem value="comment">Can comment</SelectItem>
                   <SelectItem value="edit">Can edit</SelectItem>
                   <SelectItem value="admin">Admin</SelectItem>
                 </SelectContent>
@@ -191,13 +195,16 @@ export default function ShareDialog({ open, onOpenChange, targetId, targetType =
           </div>
 
           <div className="space-y-2">
-            <h4 className="font-medium">Active temporary links</h4>
+            <h4 className="font-medium">
+              Active temporary links
+              {shareLimit.max != null && <span className="ml-2 text-sm font-normal text-muted-foreground">{shareLimit.active} / {shareLimit.max}</span>}
+            </h4>
             {shareLinks.length === 0 ? (
               <p className="text-sm text-muted-foreground">No temporary share links created yet.</p>
             ) : (
               shareLinks.map((link) => {
                 const Icon = (PERMISSION_ICONS as any)[link.permission] || Eye
-                const sampleUrl = targetType === 'folder' ? `${baseUrl}/share/${link.token}` : link.url
+                const sampleUrl = targetType === 'folder' ? `${baseUrl}/share/${link.short_code ?? link.token}` : link.url
                 return (
                   <div key={link.id || link.token} className="p-3 border rounded-md space-y-2">
                     <div className="flex items-center justify-between">
@@ -8,33 +8,64 @@ export { OpenAPI } from './core/OpenAPI';
 export type { OpenAPIConfig } from './core/OpenAPI';
 
 export type { ActiveShareItem } from './models/ActiveShareItem';
+export type { ActiveVersionResponse } from './models/ActiveVersionResponse';
 export type { AddPatternsRequest } from './models/AddPatternsRequest';
 export type { ApplicableShareItem } from './models/ApplicableShareItem';
 export type { BacklinkInfo } from './models/BacklinkInfo';
 export type { BacklinksResponse } from './models/BacklinksResponse';
+export type { BulkCreateShareRequest } from './models/BulkCreateShareRequest';
+export type { BulkCreateShareResponse } from './models/BulkCreateShareResponse';
+export type { BulkShareItem } from './models/BulkShareItem';
+export type { CapabilitiesResponse } from './models/CapabilitiesResponse';
 export type { CheckIgnoredRequest } from './models/CheckIgnoredRequest';
+export type { CommentItem } from './models/CommentItem';
+export type { CreateCommentRequest } from './models/CreateCommentRequest';
 export type { CreateDocumentRequest } from './models/CreateDocumentRequest';
 export type { CreateGitConfigRequest } from './models/CreateGitConfigRequest';
 export type { CreateRecordBody } from './models/CreateRecordBody';
 export type { CreateShareRequest } from './models/CreateShareRequest';
 export type { CreateShareResponse } from './models/CreateShareResponse';
+export type { DependencyStatus } from './models/DependencyStatus';
 export type { Document } from './models/Document';
 export type { DocumentArchiveBinary } from './models/DocumentArchiveBinary';
+export { DocumentCapability } from './models/DocumentCapability';
+export type { DocumentCapabilityResponse } from './models/DocumentCapabilityResponse';
+export type { DocumentContentResponse } from './models/DocumentContentResponse';
+export type { DocumentExportBinary } from './models/DocumentExportBinary';
 export type { DocumentListResponse } from './models/DocumentListResponse';
+export type { DocumentPresenceResponse } from './models/DocumentPresenceResponse';
+export type { DocumentRetentionResponse } from './models/DocumentRetentionResponse';
+export type { DocumentSharesResponse } from './models/DocumentSharesResponse';
+export type { DocumentUserAccessItem } from './models/DocumentUserAccessItem';
+export type { DocumentVersionContentResponse } from './models/DocumentVersionContentResponse';
+export type { DocumentVersionItem } from './models/DocumentVersionItem';
 export type { ExecBody } from './models/ExecBody';
 export type { ExecResultResponse } from './models/ExecResultResponse';
+export type { GitBlameLineItem } from './models/GitBlameLineItem';
+export type { GitBlameResponse } from './models/GitBlameResponse';
 export type { GitChangeItem } from './models/GitChangeItem';
 export type { GitChangesResponse } from './models/GitChangesResponse';
 export type { GitCommitItem } from './models/GitCommitItem';
 export type { GitConfigResponse } from './models/GitConfigResponse';
+export type { GitConnectionTestResponse } from './models/GitConnectionTestResponse';
 export type { GitDiffLine } from './models/GitDiffLine';
 export { GitDiffLineType } from './models/GitDiffLineType';
 export type { GitDiffResult } from './models/GitDiffResult';
+export type { GitDiffStats } from './models/GitDiffStats';
+export type { GitGcRequest } from './models/GitGcRequest';
+export type { GitGcResponse } from './models/GitGcResponse';
 export type { GitHistoryResponse } from './models/GitHistoryResponse';
+export type { GitImportResponse } from './models/GitImportResponse';
 export type { GitStatus } from './models/GitStatus';
+export type { GitStorageUsageResponse } from './models/GitStorageUsageResponse';
+export type { GitSyncPreview } from './models/GitSyncPreview';
 export type { GitSyncRequest } from './models/GitSyncRequest';
 export type { GitSyncResponse } from './models/GitSyncResponse';
+export type { GitWorkingDiffResponse } from './models/GitWorkingDiffResponse';
+export type { GlobalPluginEnabledBody } from './models/GlobalPluginEnabledBody';
+export type { GrantDocumentAccessRequest } from './models/GrantDocumentAccessRequest';
 export type { HealthResp } from './models/HealthResp';
+export type { HomeDocumentResponse } from './models/HomeDocumentResponse';
 export type { InstallFromUrlBody } from './models/InstallFromUrlBody';
 export type { InstallResponse } from './models/InstallResponse';
 export type { KvValueBody } from './models/KvValueBody';
@@ -43,40 +74,76 @@ export type { LoginRequest } from './models/LoginRequest';
 export type { LoginResponse } from './models/LoginResponse';
 export type { ManifestItem } from './models/ManifestItem';
 export type { MaterializeResponse } from './models/MaterializeResponse';
+export type { MentionItem } from './models/MentionItem';
+export type { MentionListResponse } from './models/MentionListResponse';
+export type { NotificationItem } from './models/NotificationItem';
+export type { NotificationListResponse } from './models/NotificationListResponse';
 export type { OutgoingLink } from './models/OutgoingLink';
 export type { OutgoingLinksResponse } from './models/OutgoingLinksResponse';
+export type { PinVersionBody } from './models/PinVersionBody';
 export type { PlaceholderItemPayload } from './models/PlaceholderItemPayload';
+export type { PluginDataExportBody } from './models/PluginDataExportBody';
+export type { PluginDataImportBody } from './models/PluginDataImportBody';
+export type { PluginDataImportResponse } from './models/PluginDataImportResponse';
+export type { PluginExecLogItem } from './models/PluginExecLogItem';
+export type { PluginExecLogResponse } from './models/PluginExecLogResponse';
+export type { PluginKvExport } from './models/PluginKvExport';
+export type { PluginRecordExport } from './models/PluginRecordExport';
+export type { PluginScheduleResponse } from './models/PluginScheduleResponse';
+export type { PluginSchedulesResponse } from './models/PluginSchedulesResponse';
+export type { PresenceClient } from './models/PresenceClient';
+export type { PublicDocumentIndex } from './models/PublicDocumentIndex';
 export type { PublicDocumentSummary } from './models/PublicDocumentSummary';
+export type { PublicViewStats } from './models/PublicViewStats';
 export type { PublishResponse } from './models/PublishResponse';
 export type { RecordsResponse } from './models/RecordsResponse';
 export type { RegisterRequest } from './models/RegisterRequest';
+export type { RenderDocumentRequest } from './models/RenderDocumentRequest';
+export type { RendererSpecPayload } from './models/RendererSpecPayload';
+export type { RendererSpecsResponse } from './models/RendererSpecsResponse';
 export type { RenderManyRequest } from './models/RenderManyRequest';
 export type { RenderManyResponse } from './models/RenderManyResponse';
 export type { RenderOptionsPayload } from './models/RenderOptionsPayload';
 export type { RenderRequest } from './models/RenderRequest';
 export type { RenderResponseBody } from './models/RenderResponseBody';
+export type { RenderTreeDocument } from './models/RenderTreeDocument';
+export type { RenderTreeRequest } from './models/RenderTreeRequest';
+export type { RenderTreeResponse } from './models/RenderTreeResponse';
 export type { SearchResult } from './models/SearchResult';
+export type { SecretKeysResponse } from './models/SecretKeysResponse';
+export type { SecretPath } from './models/SecretPath';
+export type { SecretValueBody } from './models/SecretValueBody';
+export type { SetHomeDocumentRequest } from './models/SetHomeDocumentRequest';
 export type { ShareBrowseResponse } from './models/ShareBrowseResponse';
 export type { ShareBrowseTreeItem } from './models/ShareBrowseTreeItem';
 export type { ShareDocumentResponse } from './models/ShareDocumentResponse';
 export type { ShareItem } from './models/ShareItem';
+export { StorageBackendKind } from './models/StorageBackendKind';
 export type { TagItem } from './models/TagItem';
 export type { UninstallBody } from './models/UninstallBody';
+export type { UpdateDocumentAppearanceRequest } from './models/UpdateDocumentAppearanceRequest';
+export type { UpdateDocumentContentRequest } from './models/UpdateDocumentContentRequest';
 export type { UpdateDocumentRequest } from './models/UpdateDocumentRequest';
+export type { UpdateDocumentRetentionRequest } from './models/UpdateDocumentRetentionRequest';
 export type { UpdateGitConfigRequest } from './models/UpdateGitConfigRequest';
+export type { UpdatePluginBody } from './models/UpdatePluginBody';
 export type { UpdateRecordBody } from './models/UpdateRecordBody';
 export type { UploadFileMultipart } from './models/UploadFileMultipart';
 export type { UploadFileResponse } from './models/UploadFileResponse';
+export type { UploadRejectedResponse } from './models/UploadRejectedResponse';
 export type { UserResponse } from './models/UserResponse';
 
 export { AuthService } from './services/AuthService';
+export { CommentsService } from './services/CommentsService';
 export { DocumentsService } from './services/DocumentsService';
 export { FilesService } from './services/FilesService';
 export { GitService } from './services/GitService';
 export { HealthService } from './services/HealthService';
 export { MarkdownService } from './services/MarkdownService';
+export { NotificationsService } from './services/NotificationsService';
 export { PluginsService } from './services/PluginsService';
 export { PublicDocumentsService } from './services/PublicDocumentsService';
 export { RealtimeService } from './services/RealtimeService';
+export { ServerService } from './services/ServerService';
 export { SharingService } from './services/SharingService';
 export { TagsService } from './services/TagsService';
@@ -14,6 +14,10 @@ export type ActiveShareItem = {
     id: string;
     parent_share_id?: string | null;
     permission: string;
+    /**
+     * Alias accepted wherever the token is; `url` uses it when set.
+     */
+    short_code?: string | null;
     token: string;
     url: string;
 };
@@ -0,0 +1,14 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type BulkCreateShareRequest = {
+    /**
+     * Documents to share; duplicates are shared once.
+     */
+    document_ids: Array<string>;
+    expires_at?: string | null;
+    permission?: string | null;
+    short_code?: boolean;
+};
+
@@ -0,0 +1,11 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type BulkShareItem = {
+    document_id: string;
+    short_code?: string | null;
+    token: string;
+    url: string;
+};
+
@@ -0,0 +1,33 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { StorageBackendKind } from './StorageBackendKind';
+export type CapabilitiesResponse = {
+    /**
+     * Edit share links grant edit to anonymous visitors.
+     */
+    anonymous_edit: boolean;
+    /**
+     * Realtime state is shared between nodes through Redis.
+     */
+    cluster_mode: boolean;
+    /**
+     * Changes are committed to linked repositories in the background.
+     */
+    git_auto_sync: boolean;
+    git_sync: boolean;
+    /**
+     * Null when documents may have any number of share links.
+     */
+    max_shares_per_document?: number | null;
+    plugins: boolean;
+    public_analytics: boolean;
+    storage: StorageBackendKind;
+    upload_max_bytes: number;
+    /**
+     * PDF export goes through weasyprint rather than the built-in writer.
+     */
+    weasyprint_pdf: boolean;
+};
+
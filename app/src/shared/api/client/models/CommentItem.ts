@@ -0,0 +1,28 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type CommentItem = {
+    /**
+     * Commented source range as `start_line:start_col-end_line:end_col`, matching the
+     * `data-sourcepos` attributes of rendered markdown.
+     */
+    anchor?: string | null;
+    /**
+     * Absent for comments left through a share link.
+     */
+    author_id?: string | null;
+    author_name?: string | null;
+    body: string;
+    created_at: string;
+    document_id: string;
+    id: string;
+    /**
+     * Set on replies; points at the top-level comment of the thread.
+     */
+    parent_id?: string | null;
+    resolved: boolean;
+    resolved_at?: string | null;
+    resolved_by?: string | null;
+};
+
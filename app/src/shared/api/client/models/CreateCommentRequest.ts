@@ -0,0 +1,20 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type CreateCommentRequest = {
+    /**
+     * Source range in `data-sourcepos` form; ignored on replies.
+     */
+    anchor?: string | null;
+    /**
+     * Display name for share-link commenters; signed-in users are named by their account.
+     */
+    author_name?: string | null;
+    body: string;
+    /**
+     * Top-level comment to reply to.
+     */
+    parent_id?: string | null;
+};
+
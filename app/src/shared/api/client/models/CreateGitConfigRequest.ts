@@ -3,10 +3,18 @@
 /* tslint:disable */
 /* eslint-disable */
 export type CreateGitConfigRequest = {
+    /**
+     * Append an `A`/`M`/`D` list of changed files to the commit body.
+     */
+    append_change_summary?: boolean | null;
     auth_data: any;
     auth_type: string;
     auto_sync?: boolean | null;
     branch_name?: string | null;
+    /**
+     * Default sync message; supports `{count}`, `{date}` and `{files}`.
+     */
+    commit_message_template?: string | null;
     repository_url: string;
 };
 
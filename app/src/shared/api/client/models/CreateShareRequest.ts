@@ -6,5 +6,9 @@ export type CreateShareRequest = {
     document_id: string;
     expires_at?: string | null;
     permission?: string | null;
+    /**
+     * Also issue a short code; the returned URL then uses it instead of the token.
+     */
+    short_code?: boolean;
 };
 
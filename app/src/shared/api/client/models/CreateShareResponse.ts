@@ -3,6 +3,10 @@
 /* tslint:disable */
 /* eslint-disable */
 export type CreateShareResponse = {
+    /**
+     * Alias accepted wherever the token is.
+     */
+    short_code?: string | null;
     token: string;
     url: string;
 };
@@ -3,8 +3,24 @@
 /* tslint:disable */
 /* eslint-disable */
 export type Document = {
+    /**
+     * Links pointing at this document; only set when `include_link_counts=true`.
+     */
+    backlink_count?: number | null;
+    /**
+     * Hex color (`#rrggbb`) used to tint the document in the tree.
+     */
+    color?: string | null;
     created_at: string;
+    /**
+     * Emoji or named icon shown next to the document in the tree.
+     */
+    icon?: string | null;
     id: string;
+    /**
+     * Links from this document; only set when `include_link_counts=true`.
+     */
+    outgoing_count?: number | null;
     parent_id?: string | null;
     path?: string | null;
     title: string;
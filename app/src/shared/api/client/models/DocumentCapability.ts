@@ -0,0 +1,10 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export enum DocumentCapability {
+    NONE = 'none',
+    VIEW = 'view',
+    COMMENT = 'comment',
+    EDIT = 'edit',
+}
@@ -0,0 +1,12 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type DocumentContentResponse = {
+    content: string;
+    /**
+     * Content version; send it back as `If-Match` when writing.
+     */
+    version: string;
+};
+
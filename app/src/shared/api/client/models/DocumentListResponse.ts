@@ -5,5 +5,13 @@
 import type { Document } from './Document';
 export type DocumentListResponse = {
     items: Array<Document>;
+    /**
+     * Offset to request the following page with; absent on the last page.
+     */
+    next_offset?: number | null;
+    /**
+     * Documents matching the request across all pages.
+     */
+    total: number;
 };
 
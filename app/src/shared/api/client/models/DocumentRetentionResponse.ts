@@ -0,0 +1,11 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type DocumentRetentionResponse = {
+    effective_snapshot_keep_versions: number;
+    effective_updates_keep_window: number;
+    snapshot_keep_versions?: number | null;
+    updates_keep_window?: number | null;
+};
+
@@ -0,0 +1,17 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { ShareItem } from './ShareItem';
+export type DocumentSharesResponse = {
+    /**
+     * Unexpired links created on the document itself; these count towards `max_shares`.
+     */
+    active_count: number;
+    items: Array<ShareItem>;
+    /**
+     * Most active links the document may have; absent when unlimited.
+     */
+    max_shares?: number | null;
+};
+
@@ -0,0 +1,12 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type DocumentUserAccessItem = {
+    created_at: string;
+    email: string;
+    name: string;
+    permission: string;
+    user_id: string;
+};
+
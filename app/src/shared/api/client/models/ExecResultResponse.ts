@@ -3,6 +3,7 @@
 /* tslint:disable */
 /* eslint-disable */
 export type ExecResultResponse = {
+    applied?: Array<any>;
     data?: any;
     effects: Array<any>;
     error?: any;
@@ -0,0 +1,14 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type GitBlameLineItem = {
+    author_email: string;
+    author_name: string;
+    content: string;
+    hash: string;
+    line_number: number;
+    message: string;
+    time: string;
+};
+
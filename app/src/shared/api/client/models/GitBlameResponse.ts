@@ -0,0 +1,10 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { GitBlameLineItem } from './GitBlameLineItem';
+export type GitBlameResponse = {
+    lines: Array<GitBlameLineItem>;
+    path: string;
+};
+
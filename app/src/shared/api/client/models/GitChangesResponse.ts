@@ -5,5 +5,13 @@
 import type { GitChangeItem } from './GitChangeItem';
 export type GitChangesResponse = {
     files: Array<GitChangeItem>;
+    /**
+     * Cursor for the next page; absent on the last page.
+     */
+    next_cursor?: string | null;
+    /**
+     * Changed files across all pages.
+     */
+    total: number;
 };
 
@@ -3,9 +3,11 @@
 /* tslint:disable */
 /* eslint-disable */
 export type GitConfigResponse = {
+    append_change_summary: boolean;
     auth_type: string;
     auto_sync: boolean;
     branch_name: string;
+    commit_message_template?: string | null;
     created_at: string;
     id: string;
     repository_url: string;
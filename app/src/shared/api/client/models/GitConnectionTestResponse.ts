@@ -0,0 +1,17 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type GitConnectionTestResponse = {
+    authenticated: boolean;
+    branch: string;
+    branch_exists: boolean;
+    /**
+     * One of not_configured, auth_failed, host_unreachable, branch_missing, other.
+     */
+    error?: string | null;
+    message?: string | null;
+    ok: boolean;
+    reachable: boolean;
+};
+
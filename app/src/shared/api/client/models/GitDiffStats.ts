@@ -0,0 +1,12 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type GitDiffStats = {
+    files_added: number;
+    files_deleted: number;
+    files_modified: number;
+    lines_added: number;
+    lines_deleted: number;
+};
+
@@ -0,0 +1,11 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type GitGcRequest = {
+    /**
+     * Number of most recent commits whose snapshots are kept. Defaults to 20.
+     */
+    keep_commits?: number | null;
+};
+
@@ -0,0 +1,10 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type GitGcResponse = {
+    blobs_deleted: number;
+    bytes_freed: number;
+    commits_pruned: number;
+};
+
@@ -5,5 +5,9 @@
 import type { GitCommitItem } from './GitCommitItem';
 export type GitHistoryResponse = {
     commits: Array<GitCommitItem>;
+    /**
+     * Cursor for the next, older page; absent on the last page.
+     */
+    next_cursor?: string | null;
 };
 
@@ -0,0 +1,17 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type GitImportResponse = {
+    /**
+     * Remote head recorded as the baseline; absent when the branch does not exist yet.
+     */
+    commit_hash?: string | null;
+    documents: number;
+    folders: number;
+    /**
+     * Non-markdown files that were not imported.
+     */
+    skipped: Array<string>;
+};
+
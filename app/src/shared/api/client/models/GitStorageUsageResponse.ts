@@ -0,0 +1,11 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type GitStorageUsageResponse = {
+    commits: number;
+    pack_bytes: number;
+    snapshot_blobs: number;
+    snapshot_bytes: number;
+};
+
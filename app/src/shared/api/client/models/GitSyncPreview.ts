@@ -0,0 +1,13 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { GitChangeItem } from './GitChangeItem';
+import type { GitDiffResult } from './GitDiffResult';
+import type { GitDiffStats } from './GitDiffStats';
+export type GitSyncPreview = {
+    diffs: Array<GitDiffResult>;
+    files: Array<GitChangeItem>;
+    stats: GitDiffStats;
+};
+
@@ -3,6 +3,10 @@
 /* tslint:disable */
 /* eslint-disable */
 export type GitSyncRequest = {
+    /**
+     * Preview the commit without creating or pushing it.
+     */
+    dry_run?: boolean | null;
     force?: boolean | null;
     message?: string | null;
 };
@@ -2,10 +2,12 @@
 /* istanbul ignore file */
 /* tslint:disable */
 /* eslint-disable */
+import type { GitSyncPreview } from './GitSyncPreview';
 export type GitSyncResponse = {
     commit_hash?: string | null;
     files_changed: number;
     message: string;
+    preview?: GitSyncPreview | null;
     success: boolean;
 };
 
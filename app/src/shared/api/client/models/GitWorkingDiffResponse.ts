@@ -0,0 +1,13 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { GitDiffResult } from './GitDiffResult';
+export type GitWorkingDiffResponse = {
+    /**
+     * Commit the diffs are relative to; absent before the first commit.
+     */
+    commit_hash?: string | null;
+    diffs: Array<GitDiffResult>;
+};
+
@@ -0,0 +1,16 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type GrantDocumentAccessRequest = {
+    email?: string | null;
+    /**
+     * `view` or `edit`
+     */
+    permission: string;
+    /**
+     * Target user; either `user_id` or `email` must be given.
+     */
+    user_id?: string | null;
+};
+
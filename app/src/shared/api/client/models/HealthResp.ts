@@ -2,7 +2,9 @@
 /* istanbul ignore file */
 /* tslint:disable */
 /* eslint-disable */
+import type { DependencyStatus } from './DependencyStatus';
 export type HealthResp = {
+    dependencies: Array<DependencyStatus>;
     status: string;
 };
 
@@ -0,0 +1,12 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type MentionItem = {
+    created_at: string;
+    document_id: string;
+    mentioned_by?: string | null;
+    mentioned_by_name?: string | null;
+    title: string;
+};
+
@@ -0,0 +1,20 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type NotificationItem = {
+    actor_id?: string | null;
+    created_at: string;
+    /**
+     * Kind-specific details, e.g. the document title of a mention.
+     */
+    data: Record<string, any>;
+    document_id?: string | null;
+    id: string;
+    /**
+     * `mention`, `share` or `comment`.
+     */
+    kind: string;
+    read_at?: string | null;
+};
+
@@ -0,0 +1,11 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { NotificationItem } from './NotificationItem';
+export type NotificationListResponse = {
+    items: Array<NotificationItem>;
+    next_offset?: number | null;
+    unread_count: number;
+};
+
@@ -0,0 +1,11 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type PinVersionBody = {
+    /**
+     * Version to keep active; omit or null to follow the newest installed version.
+     */
+    version?: string | null;
+};
+
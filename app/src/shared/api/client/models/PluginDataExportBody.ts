@@ -0,0 +1,13 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { PluginKvExport } from './PluginKvExport';
+import type { PluginRecordExport } from './PluginRecordExport';
+export type PluginDataExportBody = {
+    docId: string;
+    kv: Array<PluginKvExport>;
+    plugin: string;
+    records: Array<PluginRecordExport>;
+};
+
@@ -0,0 +1,10 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { PluginDataExportBody } from './PluginDataExportBody';
+export type PluginDataImportBody = {
+    data: PluginDataExportBody;
+    preserveIds?: boolean;
+};
+
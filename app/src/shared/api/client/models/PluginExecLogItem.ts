@@ -0,0 +1,18 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type PluginExecLogItem = {
+    createdAt: string;
+    durationMs: number;
+    error?: string | null;
+    function: string;
+    inputBytes: number;
+    ok: boolean;
+    outputBytes?: number | null;
+    /**
+     * Redacted call payload; only recorded when payload logging is enabled.
+     */
+    payload?: any;
+};
+
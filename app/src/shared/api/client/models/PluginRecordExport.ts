@@ -0,0 +1,12 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type PluginRecordExport = {
+    createdAt?: string | null;
+    data: any;
+    id?: string | null;
+    kind: string;
+    updatedAt?: string | null;
+};
+
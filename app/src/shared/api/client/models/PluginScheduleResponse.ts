@@ -0,0 +1,13 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type PluginScheduleResponse = {
+    action: string;
+    cron: string;
+    /**
+     * Absent when the cron expression is invalid or never matches.
+     */
+    nextRunAt?: string | null;
+};
+
@@ -0,0 +1,21 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type PresenceClient = {
+    anonymous: boolean;
+    client_id: number;
+    /**
+     * Name from the user directory, not the one the client reports.
+     */
+    display_name?: string | null;
+    /**
+     * Awareness state as published by the client.
+     */
+    state: Record<string, any>;
+    /**
+     * Set for signed-in users; share-link and public viewers are anonymous.
+     */
+    user_id?: string | null;
+};
+
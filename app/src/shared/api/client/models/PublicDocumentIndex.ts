@@ -0,0 +1,12 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { PublicDocumentSummary } from './PublicDocumentSummary';
+export type PublicDocumentIndex = {
+    items: Array<PublicDocumentSummary>;
+    page: number;
+    per_page: number;
+    total: number;
+};
+
@@ -0,0 +1,13 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type PublicViewStats = {
+    /**
+     * Days covered by `recent_views`, including today.
+     */
+    recent_days: number;
+    recent_views: number;
+    total_views: number;
+};
+
@@ -4,11 +4,34 @@
 /* eslint-disable */
 export type RenderOptionsPayload = {
     absolute_attachments?: boolean | null;
+    /**
+     * If false, raw HTML is escaped instead of sanitized.
+     */
+    allow_raw_html?: boolean | null;
+    /**
+     * Origin for attachment/image URLs, e.g. a CDN; defaults to `base_origin`.
+     */
+    asset_base?: string | null;
+    /**
+     * Link bare URLs; defaults to the gfm feature.
+     */
+    autolink?: boolean | null;
     base_origin?: string | null;
     doc_id?: string | null;
+    /**
+     * e.g. `gfm`, or single GFM extensions: `table`, `strikethrough`, `tasklist`, `autolink`.
+     */
     features?: Array<string> | null;
     flavor?: string | null;
+    /**
+     * Language of generated labels, e.g. `de`; defaults to English.
+     */
+    locale?: string | null;
     sanitize?: boolean | null;
+    /**
+     * Curly quotes, dashes and ellipses; off by default.
+     */
+    smart_punctuation?: boolean | null;
     theme?: string | null;
     token?: string | null;
 };
@@ -7,5 +7,17 @@ export type RenderResponseBody = {
     hash: string;
     html: string;
     placeholders?: Array<PlaceholderItemPayload>;
+    /**
+     * Checked task list items; only with the `stats` feature.
+     */
+    tasks_completed?: number | null;
+    /**
+     * Task list items; only with the `stats` feature.
+     */
+    tasks_total?: number | null;
+    /**
+     * The document exceeded the render budget; the HTML is incomplete or partly unhighlighted.
+     */
+    truncated: boolean;
 };
 
@@ -0,0 +1,12 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { RenderTreeDocument } from './RenderTreeDocument';
+export type RenderTreeResponse = {
+    documents: Array<RenderTreeDocument>;
+    html: string;
+    skipped: number;
+    truncated: boolean;
+};
+
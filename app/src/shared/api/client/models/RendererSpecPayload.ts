@@ -0,0 +1,20 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type RendererSpecPayload = {
+    hydrate_export?: string | null;
+    hydrate_module_url?: string | null;
+    kind: string;
+    plugin: string;
+    /**
+     * "global" or "user"
+     */
+    scope: string;
+    /**
+     * Whether the server renders this kind; otherwise the placeholder is left for the client.
+     */
+    server_rendered: boolean;
+    version: string;
+};
+
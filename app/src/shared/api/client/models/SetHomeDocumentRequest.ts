@@ -0,0 +1,11 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type SetHomeDocumentRequest = {
+    /**
+     * Document to open on sign-in; null clears the home document.
+     */
+    document_id?: string | null;
+};
+
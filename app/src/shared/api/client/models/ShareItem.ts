@@ -14,6 +14,10 @@ export type ShareItem = {
      * document | folder
      */
     scope: string;
+    /**
+     * Alias accepted wherever the token is; `url` uses it when set.
+     */
+    short_code?: string | null;
     token: string;
     url: string;
 };
@@ -0,0 +1,8 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export enum StorageBackendKind {
+    FILESYSTEM = 'filesystem',
+    S3 = 's3',
+}
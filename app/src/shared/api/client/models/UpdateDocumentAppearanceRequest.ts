@@ -0,0 +1,15 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type UpdateDocumentAppearanceRequest = {
+    /**
+     * Hex color such as `#3b82f6`; `null` clears it.
+     */
+    color?: string | null;
+    /**
+     * An emoji or a named icon (e.g. `folder`, `star`); `null` clears it.
+     */
+    icon?: string | null;
+};
+
@@ -0,0 +1,15 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type UpdateDocumentRetentionRequest = {
+    /**
+     * Snapshots kept for this document; `null` uses the server default.
+     */
+    snapshot_keep_versions?: number | null;
+    /**
+     * Updates kept behind the latest sequence; `null` uses the server default.
+     */
+    updates_keep_window?: number | null;
+};
+
@@ -3,10 +3,12 @@
 /* tslint:disable */
 /* eslint-disable */
 export type UpdateGitConfigRequest = {
+    append_change_summary?: boolean | null;
     auth_data?: any;
     auth_type?: string | null;
     auto_sync?: boolean | null;
     branch_name?: string | null;
+    commit_message_template?: string | null;
     repository_url?: string | null;
 };
 
@@ -0,0 +1,12 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type UpdatePluginBody = {
+    token?: string | null;
+    /**
+     * Package URL; defaults to the URL the plugin was installed from.
+     */
+    url?: string | null;
+};
+
@@ -0,0 +1,12 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export type UploadRejectedResponse = {
+    /**
+     * Machine-readable reason: denied_type, denied_extension, not_allowed, type_mismatch, invalid_svg
+     */
+    code: string;
+    message: string;
+};
+
@@ -0,0 +1,193 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { CommentItem } from '../models/CommentItem';
+import type { CreateCommentRequest } from '../models/CreateCommentRequest';
+import type { CancelablePromise } from '../core/CancelablePromise';
+import { OpenAPI } from '../core/OpenAPI';
+import { request as __request } from '../core/request';
+export class CommentsService {
+    /**
+     * @returns CommentItem
+     * @throws ApiError
+     */
+    public static listDocumentComments({
+        id,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<Array<CommentItem>> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/documents/{id}/comments',
+            path: {
+                'id': id,
+            },
+            query: {
+                'token': token,
+            },
+            errors: {
+                404: `Document not found`,
+            },
+        });
+    }
+    /**
+     * @returns CommentItem
+     * @throws ApiError
+     */
+    public static createDocumentComment({
+        id,
+        requestBody,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        requestBody: CreateCommentRequest,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<CommentItem> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/documents/{id}/comments',
+            path: {
+                'id': id,
+            },
+            query: {
+                'token': token,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                400: `Invalid comment`,
+                403: `Comment permission required`,
+                404: `Document not found`,
+            },
+        });
+    }
+    /**
+     * @returns void
+     * @throws ApiError
+     */
+    public static deleteDocumentComment({
+        id,
+        commentId,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Comment ID
+         */
+        commentId: string,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<void> {
+        return __request(OpenAPI, {
+            method: 'DELETE',
+            url: '/api/documents/{id}/comments/{comment_id}',
+            path: {
+                'id': id,
+                'comment_id': commentId,
+            },
+            query: {
+                'token': token,
+            },
+            errors: {
+                403: `Edit permission or authorship required`,
+                404: `Comment not found`,
+            },
+        });
+    }
+    /**
+     * @returns CommentItem
+     * @throws ApiError
+     */
+    public static reopenDocumentComment({
+        id,
+        commentId,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Comment ID
+         */
+        commentId: string,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<CommentItem> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/documents/{id}/comments/{comment_id}/reopen',
+            path: {
+                'id': id,
+                'comment_id': commentId,
+            },
+            query: {
+                'token': token,
+            },
+            errors: {
+                403: `Edit permission or authorship required`,
+                404: `Comment not found`,
+            },
+        });
+    }
+    /**
+     * @returns CommentItem
+     * @throws ApiError
+     */
+    public static resolveDocumentComment({
+        id,
+        commentId,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Comment ID
+         */
+        commentId: string,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<CommentItem> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/documents/{id}/comments/{comment_id}/resolve',
+            path: {
+                'id': id,
+                'comment_id': commentId,
+            },
+            query: {
+                'token': token,
+            },
+            errors: {
+                403: `Edit permission or authorship required`,
+                404: `Comment not found`,
+            },
+        });
+    }
+}
@@ -6,10 +6,30 @@ import type { BacklinksResponse } from '../models/BacklinksResponse';
 import type { CreateDocumentRequest } from '../models/CreateDocumentRequest';
 import type { Document } from '../models/Document';
 import type { DocumentArchiveBinary } from '../models/DocumentArchiveBinary';
+import type { DocumentCapabilityResponse } from '../models/DocumentCapabilityResponse';
+import type { DocumentContentResponse } from '../models/DocumentContentResponse';
+import type { DocumentExportBinary } from '../models/DocumentExportBinary';
 import type { DocumentListResponse } from '../models/DocumentListResponse';
+import type { DocumentPresenceResponse } from '../models/DocumentPresenceResponse';
+import type { DocumentRetentionResponse } from '../models/DocumentRetentionResponse';
+import type { DocumentUserAccessItem } from '../models/DocumentUserAccessItem';
+import type { DocumentVersionContentResponse } from '../models/DocumentVersionContentResponse';
+import type { DocumentVersionItem } from '../models/DocumentVersionItem';
+import type { GitDiffResult } from '../models/GitDiffResult';
+import type { GrantDocumentAccessRequest } from '../models/GrantDocumentAccessRequest';
+import type { HomeDocumentResponse } from '../models/HomeDocumentResponse';
+import type { MentionListResponse } from '../models/MentionListResponse';
 import type { OutgoingLinksResponse } from '../models/OutgoingLinksResponse';
+import type { RenderDocumentRequest } from '../models/RenderDocumentRequest';
+import type { RenderResponseBody } from '../models/RenderResponseBody';
+import type { RenderTreeRequest } from '../models/RenderTreeRequest';
+import type { RenderTreeResponse } from '../models/RenderTreeResponse';
 import type { SearchResult } from '../models/SearchResult';
+import type { SetHomeDocumentRequest } from '../models/SetHomeDocumentRequest';
+import type { UpdateDocumentAppearanceRequest } from '../models/UpdateDocumentAppearanceRequest';
+import type { UpdateDocumentContentRequest } from '../models/UpdateDocumentContentRequest';
 import type { UpdateDocumentRequest } from '../models/UpdateDocumentRequest';
+import type { UpdateDocumentRetentionRequest } from '../models/UpdateDocumentRetentionRequest';
 import type { CancelablePromise } from '../core/CancelablePromise';
 import { OpenAPI } from '../core/OpenAPI';
 import { request as __request } from '../core/request';
@@ -21,6 +41,11 @@ export class DocumentsService {
     public static listDocuments({
         query,
         tag,
+        type,
+        updatedSince,
+        limit,
+        offset,
+        includeLinkCounts,
     }: {
         /**
          * Search query
@@ -30,6 +55,26 @@ export class DocumentsService {
          * Filter by tag
          */
         tag?: string | null,
+        /**
+         * Filter by type: document or folder
+         */
+        type?: string | null,
+        /**
+         * Only documents updated at or after this time
+         */
+        updatedSince?: string | null,
+        /**
+         * Page size (default 100, max 500)
+         */
+        limit?: number | null,
+        /**
+         * Number of documents to skip
+         */
+        offset?: number | null,
+        /**
+         * Include backlink and outgoing link counts per document
+         */
+        includeLinkCounts?: boolean | null,
     }): CancelablePromise<DocumentListResponse> {
         return __request(OpenAPI, {
             method: 'GET',
@@ -37,6 +82,14 @@ export class DocumentsService {
             query: {
                 'query': query,
                 'tag': tag,
+                'type': type,
+                'updated_since': updatedSince,
+                'limit': limit,
+                'offset': offset,
+                'include_link_counts': includeLinkCounts,
+            },
+            errors: {
+                400: `Invalid type filter`,
             },
         });
     }
@@ -148,6 +201,114 @@ export class DocumentsService {
             mediaType: 'application/json',
         });
     }
+    /**
+     * @returns DocumentUserAccessItem
+     * @throws ApiError
+     */
+    public static listDocumentUserAccess({
+        id,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+    }): CancelablePromise<Array<DocumentUserAccessItem>> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/documents/{id}/access',
+            path: {
+                'id': id,
+            },
+            errors: {
+                404: `Document not found`,
+            },
+        });
+    }
+    /**
+     * @returns DocumentUserAccessItem
+     * @throws ApiError
+     */
+    public static grantDocumentUserAccess({
+        id,
+        requestBody,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        requestBody: GrantDocumentAccessRequest,
+    }): CancelablePromise<Array<DocumentUserAccessItem>> {
+        return __request(OpenAPI, {
+            method: 'PUT',
+            url: '/api/documents/{id}/access',
+            path: {
+                'id': id,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                400: `Invalid grant`,
+                404: `Document or user not found`,
+            },
+        });
+    }
+    /**
+     * @returns void
+     * @throws ApiError
+     */
+    public static revokeDocumentUserAccess({
+        id,
+        userId,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * User whose access is revoked
+         */
+        userId: string,
+    }): CancelablePromise<void> {
+        return __request(OpenAPI, {
+            method: 'DELETE',
+            url: '/api/documents/{id}/access/{user_id}',
+            path: {
+                'id': id,
+                'user_id': userId,
+            },
+            errors: {
+                404: `Document or grant not found`,
+            },
+        });
+    }
+    /**
+     * @returns Document
+     * @throws ApiError
+     */
+    public static updateDocumentAppearance({
+        id,
+        requestBody,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        requestBody: UpdateDocumentAppearanceRequest,
+    }): CancelablePromise<Document> {
+        return __request(OpenAPI, {
+            method: 'PUT',
+            url: '/api/documents/{id}/appearance',
+            path: {
+                'id': id,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                400: `Invalid icon or color`,
+                403: `Edit permission required`,
+            },
+        });
+    }
     /**
      * @returns BacklinksResponse
      * @throws ApiError
@@ -169,7 +330,38 @@ export class DocumentsService {
         });
     }
     /**
-     * @returns any
+     * @returns DocumentCapabilityResponse
+     * @throws ApiError
+     */
+    public static getDocumentCapability({
+        id,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<DocumentCapabilityResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/documents/{id}/capability',
+            path: {
+                'id': id,
+            },
+            query: {
+                'token': token,
+            },
+            errors: {
+                401: `Unauthorized`,
+            },
+        });
+    }
+    /**
+     * @returns DocumentContentResponse
      * @throws ApiError
      */
     public static getDocumentContent({
@@ -179,7 +371,7 @@ export class DocumentsService {
          * Document ID
          */
         id: string,
-    }): CancelablePromise<any> {
+    }): CancelablePromise<DocumentContentResponse> {
         return __request(OpenAPI, {
             method: 'GET',
             url: '/api/documents/{id}/content',
@@ -188,6 +380,52 @@ export class DocumentsService {
             },
         });
     }
+    /**
+     * @returns DocumentContentResponse
+     * @throws ApiError
+     */
+    public static updateDocumentContent({
+        id,
+        ifMatch,
+        requestBody,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Version the edit is based on, or * to overwrite
+         */
+        ifMatch: string,
+        requestBody: UpdateDocumentContentRequest,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<DocumentContentResponse> {
+        return __request(OpenAPI, {
+            method: 'PUT',
+            url: '/api/documents/{id}/content',
+            path: {
+                'id': id,
+            },
+            headers: {
+                'If-Match': ifMatch,
+            },
+            query: {
+                'token': token,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                403: `Edit permission required`,
+                404: `Document not found`,
+                409: `Document changed; body carries the current content`,
+                428: `If-Match header required`,
+            },
+        });
+    }
     /**
      * @returns DocumentArchiveBinary Document archive
      * @throws ApiError
@@ -195,6 +433,8 @@ export class DocumentsService {
     public static downloadDocument({
         id,
         token,
+        format,
+        download,
     }: {
         /**
          * Document ID
@@ -204,6 +444,14 @@ export class DocumentsService {
          * Share token (optional)
          */
         token?: string | null,
+        /**
+         * archive (default, zip with attachments) or markdown
+         */
+        format?: string | null,
+        /**
+         * Set to 0 to serve inline instead of as an attachment
+         */
+        download?: string | null,
     }): CancelablePromise<DocumentArchiveBinary> {
         return __request(OpenAPI, {
             method: 'GET',
@@ -213,10 +461,53 @@ export class DocumentsService {
             },
             query: {
                 'token': token,
+                'format': format,
+                'download': download,
+            },
+            errors: {
+                400: `Unsupported format`,
+                401: `Unauthorized`,
+                404: `Document not found`,
+            },
+        });
+    }
+    /**
+     * @returns DocumentExportBinary Rendered document
+     * @throws ApiError
+     */
+    public static exportDocument({
+        id,
+        format,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Export format: pdf (default) or html
+         */
+        format?: string | null,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<DocumentExportBinary> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/documents/{id}/export',
+            path: {
+                'id': id,
+            },
+            query: {
+                'format': format,
+                'token': token,
             },
             errors: {
+                400: `Unsupported format`,
                 401: `Unauthorized`,
                 404: `Document not found`,
+                422: `Document contains characters the PDF renderer cannot encode`,
             },
         });
     }
@@ -240,4 +531,401 @@ export class DocumentsService {
             },
         });
     }
+    /**
+     * @returns void
+     * @throws ApiError
+     */
+    public static lockDocument({
+        id,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+    }): CancelablePromise<void> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/documents/{id}/lock',
+            path: {
+                'id': id,
+            },
+            errors: {
+                404: `Not found or not the owner`,
+            },
+        });
+    }
+    /**
+     * @returns DocumentPresenceResponse
+     * @throws ApiError
+     */
+    public static getDocumentPresence({
+        id,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+    }): CancelablePromise<DocumentPresenceResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/documents/{id}/presence',
+            path: {
+                'id': id,
+            },
+        });
+    }
+    /**
+     * @returns RenderResponseBody
+     * @throws ApiError
+     */
+    public static renderDocument({
+        id,
+        requestBody,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        requestBody: RenderDocumentRequest,
+    }): CancelablePromise<RenderResponseBody> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/documents/{id}/render',
+            path: {
+                'id': id,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                401: `Unauthorized`,
+                404: `Document not found`,
+            },
+        });
+    }
+    /**
+     * @returns RenderTreeResponse
+     * @throws ApiError
+     */
+    public static renderDocumentTree({
+        id,
+        requestBody,
+    }: {
+        /**
+         * Folder ID
+         */
+        id: string,
+        requestBody: RenderTreeRequest,
+    }): CancelablePromise<RenderTreeResponse> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/documents/{id}/render-tree',
+            path: {
+                'id': id,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                401: `Unauthorized`,
+                404: `Folder not found`,
+            },
+        });
+    }
+    /**
+     * @returns DocumentRetentionResponse
+     * @throws ApiError
+     */
+    public static getDocumentRetention({
+        id,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+    }): CancelablePromise<DocumentRetentionResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/documents/{id}/retention',
+            path: {
+                'id': id,
+            },
+            errors: {
+                404: `Document not found`,
+            },
+        });
+    }
+    /**
+     * @returns DocumentRetentionResponse
+     * @throws ApiError
+     */
+    public static updateDocumentRetention({
+        id,
+        requestBody,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        requestBody: UpdateDocumentRetentionRequest,
+    }): CancelablePromise<DocumentRetentionResponse> {
+        return __request(OpenAPI, {
+            method: 'PUT',
+            url: '/api/documents/{id}/retention',
+            path: {
+                'id': id,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                400: `Invalid retention`,
+                404: `Document not found`,
+            },
+        });
+    }
+    /**
+     * @returns void
+     * @throws ApiError
+     */
+    public static unlockDocument({
+        id,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+    }): CancelablePromise<void> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/documents/{id}/unlock',
+            path: {
+                'id': id,
+            },
+            errors: {
+                404: `Not found or not the owner`,
+            },
+        });
+    }
+    /**
+     * @returns DocumentVersionItem
+     * @throws ApiError
+     */
+    public static listDocumentVersions({
+        id,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<Array<DocumentVersionItem>> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/documents/{id}/versions',
+            path: {
+                'id': id,
+            },
+            query: {
+                'token': token,
+            },
+            errors: {
+                404: `Document not found`,
+            },
+        });
+    }
+    /**
+     * @returns GitDiffResult
+     * @throws ApiError
+     */
+    public static diffDocumentVersions({
+        id,
+        from,
+        to,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Base version
+         */
+        from: number,
+        /**
+         * Compared version
+         */
+        to: number,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<GitDiffResult> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/documents/{id}/versions/diff',
+            path: {
+                'id': id,
+            },
+            query: {
+                'from': from,
+                'to': to,
+                'token': token,
+            },
+            errors: {
+                404: `Version not found`,
+            },
+        });
+    }
+    /**
+     * @returns DocumentVersionContentResponse
+     * @throws ApiError
+     */
+    public static getDocumentVersionContent({
+        id,
+        version,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Snapshot version
+         */
+        version: number,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<DocumentVersionContentResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/documents/{id}/versions/{version}/content',
+            path: {
+                'id': id,
+                'version': version,
+            },
+            query: {
+                'token': token,
+            },
+            errors: {
+                404: `Version not found`,
+            },
+        });
+    }
+    /**
+     * @returns DocumentVersionContentResponse
+     * @throws ApiError
+     */
+    public static restoreDocumentVersion({
+        id,
+        version,
+        token,
+    }: {
+        /**
+         * Document ID
+         */
+        id: string,
+        /**
+         * Snapshot version
+         */
+        version: number,
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<DocumentVersionContentResponse> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/documents/{id}/versions/{version}/restore',
+            path: {
+                'id': id,
+                'version': version,
+            },
+            query: {
+                'token': token,
+            },
+            errors: {
+                404: `Version not found`,
+            },
+        });
+    }
+    /**
+     * @returns HomeDocumentResponse
+     * @throws ApiError
+     */
+    public static getHomeDocument(): CancelablePromise<HomeDocumentResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/me/home',
+        });
+    }
+    /**
+     * @returns HomeDocumentResponse
+     * @throws ApiError
+     */
+    public static setHomeDocument({
+        requestBody,
+    }: {
+        requestBody: SetHomeDocumentRequest,
+    }): CancelablePromise<HomeDocumentResponse> {
+        return __request(OpenAPI, {
+            method: 'PUT',
+            url: '/api/me/home',
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                400: `Folders cannot be the home document`,
+                404: `Document not found`,
+            },
+        });
+    }
+    /**
+     * @returns MentionListResponse
+     * @throws ApiError
+     */
+    public static listMentions({
+        limit,
+    }: {
+        /**
+         * Maximum number of mentions (default 20, max 100)
+         */
+        limit?: number | null,
+    }): CancelablePromise<MentionListResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/me/mentions',
+            query: {
+                'limit': limit,
+            },
+        });
+    }
+    /**
+     * @returns DocumentListResponse
+     * @throws ApiError
+     */
+    public static listRecentDocuments({
+        limit,
+        includeShared,
+    }: {
+        /**
+         * Maximum number of documents (default 20, max 100)
+         */
+        limit?: number | null,
+        /**
+         * Include documents shared with the caller (default true)
+         */
+        includeShared?: boolean | null,
+    }): CancelablePromise<DocumentListResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/me/recent',
+            query: {
+                'limit': limit,
+                'include_shared': includeShared,
+            },
+        });
+    }
 }
@@ -26,16 +26,21 @@ export class FilesService {
             url: '/api/files',
             formData: formData,
             mediaType: 'multipart/form-data',
+            errors: {
+                415: `File type rejected by upload policy`,
+            },
         });
     }
     /**
      * GET /api/files/documents/{filename}?document_id=uuid -> bytes
      * @returns binary OK
+     * @returns any Partial content for a Range request
      * @throws ApiError
      */
     public static getFileByName({
         filename,
         documentId,
+        download,
     }: {
         /**
          * File name
@@ -45,7 +50,11 @@ export class FilesService {
          * Document ID
          */
         documentId: string,
-    }): CancelablePromise<Blob> {
+        /**
+         * Set to 1 to force Content-Disposition: attachment
+         */
+        download?: string | null,
+    }): CancelablePromise<Blob | any> {
         return __request(OpenAPI, {
             method: 'GET',
             url: '/api/files/documents/{filename}',
@@ -54,28 +63,44 @@ export class FilesService {
             },
             query: {
                 'document_id': documentId,
+                'download': download,
+            },
+            errors: {
+                416: `Range not satisfiable`,
             },
         });
     }
     /**
      * GET /api/files/{id} -> bytes (fallback; primary is /uploads/{filename})
      * @returns binary OK
+     * @returns any Partial content for a Range request
      * @throws ApiError
      */
     public static getFile({
         id,
+        download,
     }: {
         /**
          * File ID
          */
         id: string,
-    }): CancelablePromise<Blob> {
+        /**
+         * Set to 1 to force Content-Disposition: attachment
+         */
+        download?: string | null,
+    }): CancelablePromise<Blob | any> {
         return __request(OpenAPI, {
             method: 'GET',
             url: '/api/files/{id}',
             path: {
                 'id': id,
             },
+            query: {
+                'download': download,
+            },
+            errors: {
+                416: `Range not satisfiable`,
+            },
         });
     }
 }
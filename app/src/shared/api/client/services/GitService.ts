@@ -5,25 +5,68 @@
 import type { AddPatternsRequest } from '../models/AddPatternsRequest';
 import type { CheckIgnoredRequest } from '../models/CheckIgnoredRequest';
 import type { CreateGitConfigRequest } from '../models/CreateGitConfigRequest';
+import type { GitBlameResponse } from '../models/GitBlameResponse';
 import type { GitChangesResponse } from '../models/GitChangesResponse';
 import type { GitConfigResponse } from '../models/GitConfigResponse';
+import type { GitConnectionTestResponse } from '../models/GitConnectionTestResponse';
 import type { GitDiffResult } from '../models/GitDiffResult';
+import type { GitGcRequest } from '../models/GitGcRequest';
+import type { GitGcResponse } from '../models/GitGcResponse';
 import type { GitHistoryResponse } from '../models/GitHistoryResponse';
+import type { GitImportResponse } from '../models/GitImportResponse';
 import type { GitStatus } from '../models/GitStatus';
+import type { GitStorageUsageResponse } from '../models/GitStorageUsageResponse';
 import type { GitSyncRequest } from '../models/GitSyncRequest';
 import type { GitSyncResponse } from '../models/GitSyncResponse';
+import type { GitWorkingDiffResponse } from '../models/GitWorkingDiffResponse';
 import type { CancelablePromise } from '../core/CancelablePromise';
 import { OpenAPI } from '../core/OpenAPI';
 import { request as __request } from '../core/request';
 export class GitService {
+    /**
+     * @returns GitBlameResponse
+     * @throws ApiError
+     */
+    public static getBlame({
+        path,
+    }: {
+        /**
+         * Repository path of the file, e.g. notes/plan.md
+         */
+        path: string,
+    }): CancelablePromise<GitBlameResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/git/blame',
+            query: {
+                'path': path,
+            },
+        });
+    }
     /**
      * @returns GitChangesResponse
      * @throws ApiError
      */
-    public static getChanges(): CancelablePromise<GitChangesResponse> {
+    public static getChanges({
+        cursor,
+        limit,
+    }: {
+        /**
+         * `next_cursor` of the previous page
+         */
+        cursor?: string | null,
+        /**
+         * Files per page (default 200, max 1000)
+         */
+        limit?: number | null,
+    }): CancelablePromise<GitChangesResponse> {
         return __request(OpenAPI, {
             method: 'GET',
             url: '/api/git/changes',
+            query: {
+                'cursor': cursor,
+                'limit': limit,
+            },
         });
     }
     /**
@@ -99,15 +142,31 @@ export class GitService {
         });
     }
     /**
-     * @returns GitDiffResult
+     * @returns GitWorkingDiffResponse
      * @throws ApiError
      */
-    public static getWorkingDiff(): CancelablePromise<Array<GitDiffResult>> {
+    public static getWorkingDiff(): CancelablePromise<GitWorkingDiffResponse> {
         return __request(OpenAPI, {
             method: 'GET',
             url: '/api/git/diff/working',
         });
     }
+    /**
+     * @returns GitGcResponse
+     * @throws ApiError
+     */
+    public static collectGarbage({
+        requestBody,
+    }: {
+        requestBody: GitGcRequest,
+    }): CancelablePromise<GitGcResponse> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/git/gc',
+            body: requestBody,
+            mediaType: 'application/json',
+        });
+    }
     /**
      * @returns any OK
      * @throws ApiError
@@ -135,7 +194,7 @@ export class GitService {
         });
     }
     /**
-     * @returns any OK
+     * @returns any Patterns added and skipped
      * @throws ApiError
      */
     public static addGitignorePatterns({
@@ -150,14 +209,49 @@ export class GitService {
             mediaType: 'application/json',
         });
     }
+    /**
+     * @returns any Patterns removed and skipped
+     * @throws ApiError
+     */
+    public static removeGitignorePatterns({
+        requestBody,
+    }: {
+        requestBody: AddPatternsRequest,
+    }): CancelablePromise<any> {
+        return __request(OpenAPI, {
+            method: 'DELETE',
+            url: '/api/git/gitignore/patterns',
+            body: requestBody,
+            mediaType: 'application/json',
+        });
+    }
     /**
      * @returns GitHistoryResponse
      * @throws ApiError
      */
-    public static getHistory(): CancelablePromise<GitHistoryResponse> {
+    public static getHistory({
+        cursor,
+        limit,
+    }: {
+        /**
+         * `next_cursor` of the previous page
+         */
+        cursor?: string | null,
+        /**
+         * Commits per page (default 50, max 200)
+         */
+        limit?: number | null,
+    }): CancelablePromise<GitHistoryResponse> {
         return __request(OpenAPI, {
             method: 'GET',
             url: '/api/git/history',
+            query: {
+                'cursor': cursor,
+                'limit': limit,
+            },
+            errors: {
+                400: `Malformed cursor`,
+            },
         });
     }
     /**
@@ -200,6 +294,22 @@ export class GitService {
             },
         });
     }
+    /**
+     * Imports the configured remote branch's markdown files as documents and continues its
+     * history. Only allowed while the local repository has no commits; a failed import leaves
+     * no documents behind and can be retried.
+     * @returns GitImportResponse
+     * @throws ApiError
+     */
+    public static importRepository(): CancelablePromise<GitImportResponse> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/git/import',
+            errors: {
+                409: `Import failed or the repository already has history`,
+            },
+        });
+    }
     /**
      * @returns any OK
      * @throws ApiError
@@ -220,6 +330,16 @@ export class GitService {
             url: '/api/git/status',
         });
     }
+    /**
+     * @returns GitStorageUsageResponse
+     * @throws ApiError
+     */
+    public static getStorageUsage(): CancelablePromise<GitStorageUsageResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/git/storage',
+        });
+    }
     /**
      * @returns GitSyncResponse
      * @throws ApiError
@@ -239,4 +359,14 @@ export class GitService {
             },
         });
     }
+    /**
+     * @returns GitConnectionTestResponse
+     * @throws ApiError
+     */
+    public static testConnection(): CancelablePromise<GitConnectionTestResponse> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/git/test-connection',
+        });
+    }
 }
@@ -2,6 +2,7 @@
 /* istanbul ignore file */
 /* tslint:disable */
 /* eslint-disable */
+import type { RendererSpecsResponse } from '../models/RendererSpecsResponse';
 import type { RenderManyRequest } from '../models/RenderManyRequest';
 import type { RenderManyResponse } from '../models/RenderManyResponse';
 import type { RenderRequest } from '../models/RenderRequest';
@@ -42,4 +43,27 @@ export class MarkdownService {
             mediaType: 'application/json',
         });
     }
+    /**
+     * Renderers that apply to the caller's render requests: global plugins plus the caller's
+     * enabled user-scoped plugins. Clients use it to skip loading renderers for kinds the server
+     * already renders.
+     * @returns RendererSpecsResponse
+     * @throws ApiError
+     */
+    public static listRenderers({
+        token,
+    }: {
+        /**
+         * Share token (optional)
+         */
+        token?: string | null,
+    }): CancelablePromise<RendererSpecsResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/markdown/renderers',
+            query: {
+                'token': token,
+            },
+        });
+    }
 }
@@ -0,0 +1,65 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { NotificationListResponse } from '../models/NotificationListResponse';
+import type { CancelablePromise } from '../core/CancelablePromise';
+import { OpenAPI } from '../core/OpenAPI';
+import { request as __request } from '../core/request';
+export class NotificationsService {
+    /**
+     * @returns NotificationListResponse
+     * @throws ApiError
+     */
+    public static listNotifications({
+        limit,
+        offset,
+        unread,
+    }: {
+        /**
+         * Maximum number of notifications (default 20, max 100)
+         */
+        limit?: number | null,
+        /**
+         * Number of notifications to skip
+         */
+        offset?: number | null,
+        /**
+         * Only return unread notifications
+         */
+        unread?: boolean | null,
+    }): CancelablePromise<NotificationListResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/me/notifications',
+            query: {
+                'limit': limit,
+                'offset': offset,
+                'unread': unread,
+            },
+        });
+    }
+    /**
+     * @returns void
+     * @throws ApiError
+     */
+    public static markNotificationRead({
+        id,
+    }: {
+        /**
+         * Notification ID
+         */
+        id: string,
+    }): CancelablePromise<void> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/me/notifications/{id}/read',
+            path: {
+                'id': id,
+            },
+            errors: {
+                404: `Notification not found`,
+            },
+        });
+    }
+}
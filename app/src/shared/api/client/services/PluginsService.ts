@@ -2,21 +2,100 @@
 /* istanbul ignore file */
 /* tslint:disable */
 /* eslint-disable */
+import type { ActiveVersionResponse } from '../models/ActiveVersionResponse';
 import type { CreateRecordBody } from '../models/CreateRecordBody';
 import type { ExecBody } from '../models/ExecBody';
 import type { ExecResultResponse } from '../models/ExecResultResponse';
+import type { GlobalPluginEnabledBody } from '../models/GlobalPluginEnabledBody';
 import type { InstallFromUrlBody } from '../models/InstallFromUrlBody';
 import type { InstallResponse } from '../models/InstallResponse';
 import type { KvValueBody } from '../models/KvValueBody';
 import type { KvValueResponse } from '../models/KvValueResponse';
 import type { ManifestItem } from '../models/ManifestItem';
+import type { PinVersionBody } from '../models/PinVersionBody';
+import type { PluginDataExportBody } from '../models/PluginDataExportBody';
+import type { PluginDataImportBody } from '../models/PluginDataImportBody';
+import type { PluginDataImportResponse } from '../models/PluginDataImportResponse';
+import type { PluginExecLogResponse } from '../models/PluginExecLogResponse';
+import type { PluginSchedulesResponse } from '../models/PluginSchedulesResponse';
 import type { RecordsResponse } from '../models/RecordsResponse';
+import type { SecretKeysResponse } from '../models/SecretKeysResponse';
+import type { SecretValueBody } from '../models/SecretValueBody';
 import type { UninstallBody } from '../models/UninstallBody';
+import type { UpdatePluginBody } from '../models/UpdatePluginBody';
 import type { UpdateRecordBody } from '../models/UpdateRecordBody';
 import type { CancelablePromise } from '../core/CancelablePromise';
 import { OpenAPI } from '../core/OpenAPI';
 import { request as __request } from '../core/request';
 export class PluginsService {
+    /**
+     * @returns InstallResponse
+     * @throws ApiError
+     */
+    public static pluginsInstallGlobal({
+        requestBody,
+    }: {
+        requestBody: InstallFromUrlBody,
+    }): CancelablePromise<InstallResponse> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/admin/plugins/install-from-url',
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                403: `Caller is not an administrator`,
+                502: `Package download failed`,
+            },
+        });
+    }
+    /**
+     * @returns void
+     * @throws ApiError
+     */
+    public static pluginsUninstallGlobal({
+        requestBody,
+    }: {
+        requestBody: UninstallBody,
+    }): CancelablePromise<void> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/admin/plugins/uninstall',
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                403: `Caller is not an administrator`,
+                404: `Plugin not installed`,
+            },
+        });
+    }
+    /**
+     * @returns void
+     * @throws ApiError
+     */
+    public static pluginsSetGlobalEnabled({
+        id,
+        requestBody,
+    }: {
+        /**
+         * Plugin ID
+         */
+        id: string,
+        requestBody: GlobalPluginEnabledBody,
+    }): CancelablePromise<void> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/admin/plugins/{id}/enabled',
+            path: {
+                'id': id,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                403: `Caller is not an administrator`,
+                404: `Plugin not installed`,
+            },
+        });
+    }
     /**
      * @returns InstallResponse
      * @throws ApiError
@@ -63,10 +142,198 @@ export class PluginsService {
      * @returns any Plugin event stream
      * @throws ApiError
      */
-    public static sseUpdates(): CancelablePromise<any> {
+    public static sseUpdates({
+        lastEventId,
+    }: {
+        /**
+         * Replay events emitted after this id
+         */
+        lastEventId?: string | null,
+    }): CancelablePromise<any> {
         return __request(OpenAPI, {
             method: 'GET',
             url: '/api/me/plugins/updates',
+            headers: {
+                'Last-Event-ID': lastEventId,
+            },
+        });
+    }
+    /**
+     * @returns PluginExecLogResponse
+     * @throws ApiError
+     */
+    public static pluginsListExecLog({
+        id,
+        limit,
+    }: {
+        /**
+         * Plugin ID
+         */
+        id: string,
+        /**
+         * Limit
+         */
+        limit?: number | null,
+    }): CancelablePromise<PluginExecLogResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/me/plugins/{id}/exec-log',
+            path: {
+                'id': id,
+            },
+            query: {
+                'limit': limit,
+            },
+        });
+    }
+    /**
+     * @returns ActiveVersionResponse
+     * @throws ApiError
+     */
+    public static pluginsPinVersion({
+        id,
+        requestBody,
+    }: {
+        /**
+         * Plugin ID
+         */
+        id: string,
+        requestBody: PinVersionBody,
+    }): CancelablePromise<ActiveVersionResponse> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/me/plugins/{id}/pin',
+            path: {
+                'id': id,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                404: `Plugin or version not installed`,
+            },
+        });
+    }
+    /**
+     * @returns PluginSchedulesResponse
+     * @throws ApiError
+     */
+    public static pluginsListSchedules({
+        id,
+    }: {
+        /**
+         * Plugin ID
+         */
+        id: string,
+    }): CancelablePromise<PluginSchedulesResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/me/plugins/{id}/schedules',
+            path: {
+                'id': id,
+            },
+            errors: {
+                404: `Plugin not installed`,
+            },
+        });
+    }
+    /**
+     * @returns ActiveVersionResponse
+     * @throws ApiError
+     */
+    public static pluginsUpdate({
+        id,
+        requestBody,
+    }: {
+        /**
+         * Plugin ID
+         */
+        id: string,
+        requestBody: UpdatePluginBody,
+    }): CancelablePromise<ActiveVersionResponse> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/me/plugins/{id}/update',
+            path: {
+                'id': id,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                404: `Plugin not installed`,
+                502: `Package download failed`,
+            },
+        });
+    }
+    /**
+     * @returns PluginDataExportBody
+     * @throws ApiError
+     */
+    public static pluginsExportData({
+        plugin,
+        docId,
+        token,
+    }: {
+        /**
+         * Plugin ID
+         */
+        plugin: string,
+        /**
+         * Document ID
+         */
+        docId: string,
+        /**
+         * Share token
+         */
+        token?: string | null,
+    }): CancelablePromise<PluginDataExportBody> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/plugins/{plugin}/docs/{doc_id}/export',
+            path: {
+                'plugin': plugin,
+                'doc_id': docId,
+            },
+            query: {
+                'token': token,
+            },
+        });
+    }
+    /**
+     * @returns PluginDataImportResponse
+     * @throws ApiError
+     */
+    public static pluginsImportData({
+        plugin,
+        docId,
+        requestBody,
+        token,
+    }: {
+        /**
+         * Plugin ID
+         */
+        plugin: string,
+        /**
+         * Document ID
+         */
+        docId: string,
+        requestBody: PluginDataImportBody,
+        /**
+         * Share token
+         */
+        token?: string | null,
+    }): CancelablePromise<PluginDataImportResponse> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/plugins/{plugin}/docs/{doc_id}/import',
+            path: {
+                'plugin': plugin,
+                'doc_id': docId,
+            },
+            query: {
+                'token': token,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
         });
     }
     /**
@@ -277,6 +544,10 @@ export class PluginsService {
             },
             body: requestBody,
             mediaType: 'application/json',
+            errors: {
+                422: `Plugin trapped`,
+                504: `Plugin timed out`,
+            },
         });
     }
     /**
@@ -335,4 +606,91 @@ export class PluginsService {
             mediaType: 'application/json',
         });
     }
+    /**
+     * @returns SecretKeysResponse Names of stored secrets; values are never returned
+     * @throws ApiError
+     */
+    public static pluginsListSecrets({
+        plugin,
+    }: {
+        /**
+         * Plugin ID
+         */
+        plugin: string,
+    }): CancelablePromise<SecretKeysResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/plugins/{plugin}/secrets',
+            path: {
+                'plugin': plugin,
+            },
+            errors: {
+                403: `Plugin lacks the secrets permission`,
+            },
+        });
+    }
+    /**
+     * @returns void
+     * @throws ApiError
+     */
+    public static pluginsPutSecret({
+        plugin,
+        key,
+        requestBody,
+    }: {
+        /**
+         * Plugin ID
+         */
+        plugin: string,
+        /**
+         * Secret name
+         */
+        key: string,
+        requestBody: SecretValueBody,
+    }): CancelablePromise<void> {
+        return __request(OpenAPI, {
+            method: 'PUT',
+            url: '/api/plugins/{plugin}/secrets/{key}',
+            path: {
+                'plugin': plugin,
+                'key': key,
+            },
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                400: `Invalid secret name or value`,
+                403: `Plugin lacks the secrets permission`,
+            },
+        });
+    }
+    /**
+     * @returns void
+     * @throws ApiError
+     */
+    public static pluginsDeleteSecret({
+        plugin,
+        key,
+    }: {
+        /**
+         * Plugin ID
+         */
+        plugin: string,
+        /**
+         * Secret name
+         */
+        key: string,
+    }): CancelablePromise<void> {
+        return __request(OpenAPI, {
+            method: 'DELETE',
+            url: '/api/plugins/{plugin}/secrets/{key}',
+            path: {
+                'plugin': plugin,
+                'key': key,
+            },
+            errors: {
+                403: `Plugin lacks the secrets permission`,
+                404: `Secret not found`,
+            },
+        });
+    }
 }
@@ -3,7 +3,9 @@
 /* tslint:disable */
 /* eslint-disable */
 import type { Document } from '../models/Document';
+import type { PublicDocumentIndex } from '../models/PublicDocumentIndex';
 import type { PublicDocumentSummary } from '../models/PublicDocumentSummary';
+import type { PublicViewStats } from '../models/PublicViewStats';
 import type { PublishResponse } from '../models/PublishResponse';
 import type { CancelablePromise } from '../core/CancelablePromise';
 import { OpenAPI } from '../core/OpenAPI';
@@ -89,6 +91,60 @@ export class PublicDocumentsService {
             },
         });
     }
+    /**
+     * @returns PublicDocumentIndex Page of the owner's published documents
+     * @throws ApiError
+     */
+    public static getUserPublicIndex({
+        name,
+        page,
+        perPage,
+    }: {
+        /**
+         * Owner name
+         */
+        name: string,
+        /**
+         * Page number, starting at 1
+         */
+        page?: number | null,
+        /**
+         * Items per page (default 50, max 500)
+         */
+        perPage?: number | null,
+    }): CancelablePromise<PublicDocumentIndex> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/public/users/{name}/index',
+            path: {
+                'name': name,
+            },
+            query: {
+                'page': page,
+                'per_page': perPage,
+            },
+        });
+    }
+    /**
+     * @returns string Sitemap of the owner's published documents
+     * @throws ApiError
+     */
+    public static getUserPublicSitemap({
+        name,
+    }: {
+        /**
+         * Owner name
+         */
+        name: string,
+    }): CancelablePromise<string> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/public/users/{name}/sitemap.xml',
+            path: {
+                'name': name,
+            },
+        });
+    }
     /**
      * @returns Document Document metadata
      * @throws ApiError
@@ -141,4 +197,33 @@ export class PublicDocumentsService {
             },
         });
     }
+    /**
+     * @returns PublicViewStats View counts
+     * @throws ApiError
+     */
+    public static getPublicViewStats({
+        name,
+        id,
+    }: {
+        /**
+         * Owner name
+         */
+        name: string,
+        /**
+         * Document ID
+         */
+        id: string,
+    }): CancelablePromise<PublicViewStats> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/public/users/{name}/{id}/stats',
+            path: {
+                'name': name,
+                'id': id,
+            },
+            errors: {
+                404: `Not the owner, or analytics disabled`,
+            },
+        });
+    }
 }
@@ -14,6 +14,7 @@ export class RealtimeService {
         id,
         token,
         authorization,
+        secWebSocketProtocol,
     }: {
         /**
          * Document ID (UUID)
@@ -27,6 +28,10 @@ export class RealtimeService {
          * Bearer token (JWT or share token)
          */
         authorization?: string | null,
+        /**
+         * Offered protocol versions, e.g. refmd.yjs.v1
+         */
+        secWebSocketProtocol?: string | null,
     }): CancelablePromise<void> {
         return __request(OpenAPI, {
             method: 'GET',
@@ -36,6 +41,7 @@ export class RealtimeService {
             },
             headers: {
                 'Authorization': authorization,
+                'Sec-WebSocket-Protocol': secWebSocketProtocol,
             },
             query: {
                 'token': token,
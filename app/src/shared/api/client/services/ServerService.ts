@@ -0,0 +1,20 @@
+/* generated using openapi-typescript-codegen -- do not edit */
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { CapabilitiesResponse } from '../models/CapabilitiesResponse';
+import type { CancelablePromise } from '../core/CancelablePromise';
+import { OpenAPI } from '../core/OpenAPI';
+import { request as __request } from '../core/request';
+export class ServerService {
+    /**
+     * @returns CapabilitiesResponse
+     * @throws ApiError
+     */
+    public static getCapabilities(): CancelablePromise<CapabilitiesResponse> {
+        return __request(OpenAPI, {
+            method: 'GET',
+            url: '/api/capabilities',
+        });
+    }
+}
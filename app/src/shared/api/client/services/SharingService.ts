@@ -4,12 +4,14 @@
 /* eslint-disable */
 import type { ActiveShareItem } from '../models/ActiveShareItem';
 import type { ApplicableShareItem } from '../models/ApplicableShareItem';
+import type { BulkCreateShareRequest } from '../models/BulkCreateShareRequest';
+import type { BulkCreateShareResponse } from '../models/BulkCreateShareResponse';
 import type { CreateShareRequest } from '../models/CreateShareRequest';
 import type { CreateShareResponse } from '../models/CreateShareResponse';
+import type { DocumentSharesResponse } from '../models/DocumentSharesResponse';
 import type { MaterializeResponse } from '../models/MaterializeResponse';
 import type { ShareBrowseResponse } from '../models/ShareBrowseResponse';
 import type { ShareDocumentResponse } from '../models/ShareDocumentResponse';
-import type { ShareItem } from '../models/ShareItem';
 import type { CancelablePromise } from '../core/CancelablePromise';
 import { OpenAPI } from '../core/OpenAPI';
 import { request as __request } from '../core/request';
@@ -28,6 +30,9 @@ export class SharingService {
             url: '/api/shares',
             body: requestBody,
             mediaType: 'application/json',
+            errors: {
+                409: `Document already has the maximum number of active share links`,
+            },
         });
     }
     /**
@@ -81,7 +86,28 @@ export class SharingService {
         });
     }
     /**
-     * @returns ShareItem OK
+     * @returns BulkCreateShareResponse One share link per document
+     * @throws ApiError
+     */
+    public static createSharesBulk({
+        requestBody,
+    }: {
+        requestBody: BulkCreateShareRequest,
+    }): CancelablePromise<BulkCreateShareResponse> {
+        return __request(OpenAPI, {
+            method: 'POST',
+            url: '/api/shares/bulk',
+            body: requestBody,
+            mediaType: 'application/json',
+            errors: {
+                400: `No documents, or more than 100`,
+                403: `A document is not owned by the caller; nothing was created`,
+                409: `A document already has the maximum number of active share links`,
+            },
+        });
+    }
+    /**
+     * @returns DocumentSharesResponse OK
      * @throws ApiError
      */
     public static listDocumentShares({
@@ -91,7 +117,7 @@ export class SharingService {
          * Document ID
          */
         id: string,
-    }): CancelablePromise<Array<ShareItem>> {
+    }): CancelablePromise<DocumentSharesResponse> {
         return __request(OpenAPI, {
             method: 'GET',
             url: '/api/shares/documents/{id}',
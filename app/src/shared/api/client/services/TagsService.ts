@@ -13,17 +13,23 @@ export class TagsService {
      */
     public static listTags({
         q,
+        includeHidden,
     }: {
         /**
          * Filter contains
          */
         q?: string | null,
+        /**
+         * Include hidden tags (`_`-prefixed or configured); default false
+         */
+        includeHidden?: boolean | null,
     }): CancelablePromise<Array<TagItem>> {
         return __request(OpenAPI, {
             method: 'GET',
             url: '/api/tags',
             query: {
                 'q': q,
+                'include_hidden': includeHidden,
             },
         });
     }
@@ -24,3 +24,6 @@ export const YJS_SERVER_URL = (() => {
   u.protocol = u.protocol === 'https:' ? 'wss:' : 'ws:'
   return `${u.toString().replace(/\/$/, '')}/api/yjs`
 })()
+
+// Realtime protocol versions offered in Sec-WebSocket-Protocol; the server closes with 4406 if it supports none
+export const YJS_PROTOCOLS = ['refmd.yjs.v2', 'refmd.yjs.v1']
@@ -2,7 +2,7 @@ import type { IndexeddbPersistence } from 'y-indexeddb'
 import type { WebsocketProvider } from 'y-websocket'
 import type * as Y from 'yjs'
 
-import { YJS_SERVER_URL } from '@/shared/lib/config'
+import { YJS_PROTOCOLS, YJS_SERVER_URL } from '@/shared/lib/config'
 
 export type YjsConnectionOptions = {
   token?: string | null
@@ -18,8 +18,65 @@ export type YjsConnection = {
   persistence: IndexeddbPersistence | null
 }
 
+// Protocol v2: a large initial state arrives as numbered slices of one update
+const MSG_SYNC_CHUNK = 100
+
+type Lib0Decoder = { arr: Uint8Array; pos: number }
+
+function readVarUint(decoder: Lib0Decoder): number {
+  let num = 0
+  let mult = 1
+  for (;;) {
+    const byte = decoder.arr[decoder.pos++]
+    num += (byte & 0x7f) * mult
+    if (byte < 0x80) return num
+    mult *= 128
+  }
+}
+
+function installSyncChunkHandler(provider: WebsocketProvider, applyUpdate: (doc: Y.Doc, update: Uint8Array, origin: unknown) => void) {
+  let chunks: Uint8Array[] = []
+  const handlers = (provider as any).messageHandlers as Array<(...args: any[]) => void>
+  handlers[MSG_SYNC_CHUNK] = (_encoder: unknown, decoder: Lib0Decoder, p: WebsocketProvider) => {
+    const index = readVarUint(decoder)
+    const total = readVarUint(decoder)
+    const len = readVarUint(decoder)
+    if (index === 0) chunks = []
+    if (index !== chunks.length) {
+      console.warn('[yjs] Out-of-order sync chunk', index, chunks.length)
+      chunks = []
+      return
+    }
+    chunks.push(decoder.arr.slice(decoder.pos, decoder.pos + len))
+    decoder.pos += len
+    if (chunks.length < total) return
+    const size = chunks.reduce((n, c) => n + c.length, 0)
+    const update = new Uint8Array(size)
+    let offset = 0
+    for (const c of chunks) {
+      update.set(c, offset)
+      offset += c.length
+    }
+    chunks = []
+    applyUpdate(p.doc as unknown as Y.Doc, update, p)
+  }
+}
+
+// Owner toggled the document lock; edits are dropped server-side while it is set
+const MSG_DOC_LOCK = 101
+
+function installDocLockHandler(provider: WebsocketProvider) {
+  const handlers = (provider as any).messageHandlers as Array<(...args: any[]) => void>
+  handlers[MSG_DOC_LOCK] = (_encoder: unknown, decoder: Lib0Decoder, p: WebsocketProvider) => {
+    const len = readVarUint(decoder)
+    const locked = len > 0 && decoder.arr[decoder.pos] === 1
+    decoder.pos += len
+    ;(p as any).emit('document-lock', [locked])
+  }
+}
+
 export async function createYjsConnection(documentId: string, options: YjsConnectionOptions = {}): Promise<YjsConnection> {
-  const { Doc } = await import('yjs')
+  const { Doc, applyUpdate } = await import('yjs')
   const { WebsocketProvider } = await import('y-websocket')
 
   const doc = new Doc() as unknown as Y.Doc
@@ -57,8 +114,17 @@ export async function createYjsConnection(documentId: string, options: YjsConnec
     {
       connect: options.connect ?? true,
       params,
+      protocols: YJS_PROTOCOLS,
     },
   ) as WebsocketProvider
+  installSyncChunkHandler(provider, applyUpdate as any)
+  installDocLockHandler(provider)
+
+  provider.on('connection-close', (event: CloseEvent | null) => {
+    if (event?.code === 4406) {
+      console.error('[yjs] Server does not support this client protocol version', event.reason)
+    }
+  })
 
   if (persistenceReady) {
     try {